@@ -1,14 +1,52 @@
 #![deny(missing_docs)]
 //! Macros for the `aisdk` library.
 
+mod prompt;
+
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::parse::Parser;
 use syn::{
-    Expr, ExprLit, FnArg, ItemFn, Lit, Meta, MetaNameValue, Pat, Token, parse_macro_input,
+    Expr, ExprLit, FnArg, ItemFn, Lit, LitStr, Meta, MetaNameValue, Pat, Token, parse_macro_input,
     punctuated::Punctuated,
 };
 
+#[proc_macro]
+/// Constructs a `PromptTemplate` from a template checked at compile time.
+///
+/// Variables are written `{name}`, with fields as `{user.name}`, and literal
+/// braces as `{{` and `}}`. Conditionals, loops and partials use the tags
+/// `{% if premium %}`, `{% for item in items %}` and `{% include "name" %}`,
+/// closed with `{% endif %}` and `{% endfor %}`. Malformed variables and
+/// unbalanced tags are compile errors.
+///
+/// Requires the `prompt` feature of `aisdk`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use aisdk::macros::prompt;
+///
+/// let template = prompt!("Translate to {language}:{% for line in lines %}\n- {line}{% endfor %}");
+/// let text = template.render(&serde_json::json!({
+///     "language": "French",
+///     "lines": ["Hello", "Goodbye"],
+/// }))?;
+/// ```
+pub fn prompt(input: TokenStream) -> TokenStream {
+    let template = parse_macro_input!(input as LitStr);
+    match prompt::to_tera(&template.value()) {
+        Ok(source) => quote! {
+            ::aisdk::prompt::PromptTemplate::new(#source)
+                .expect("prompt! templates are checked at compile time")
+        }
+        .into(),
+        Err(message) => syn::Error::new(template.span(), message)
+            .to_compile_error()
+            .into(),
+    }
+}
+
 #[proc_macro_attribute]
 /// Constructs a tool from a function defnition. A tool has a name, a description,
 /// an input and a body. all three components are infered from a standard rust
@@ -45,7 +83,8 @@ use syn::{
 /// use `Option` types for arguments that are optional or implement a default for those
 /// that are not and handle those defaults accordingly in the tool body.
 ///
-/// You can override name and description using the macro arguments `name` and `desc`.
+/// You can override name and description using the macro arguments `name` and `desc`,
+/// and turn off the strict schema mode of providers such as OpenAI with `strict = false`.
 ///
 /// # Example with overrides
 /// ```rust,no_run
@@ -54,7 +93,8 @@ use syn::{
 ///
 ///     #[tool(
 ///         name = "the-name-for-this-tool",
-///         desc = "the-description-for-this-tool"
+///         desc = "the-description-for-this-tool",
+///         strict = false
 ///     )]
 ///     fn get_username(id: String) -> Tool {
 ///         // Your code here
@@ -72,9 +112,10 @@ pub fn tool(_attr: TokenStream, item: TokenStream) -> TokenStream {
     let args_parser = Punctuated::<MetaNameValue, Token![,]>::parse_terminated;
     let args = args_parser.parse(_attr);
 
-    let (name_arg, description_arg) = if let Ok(args) = args {
+    let (name_arg, description_arg, strict_arg) = if let Ok(args) = args {
         let mut name: Option<String> = None;
         let mut description: Option<String> = None;
+        let mut strict: Option<bool> = None;
 
         for arg in args {
             if arg.path.is_ident("desc")
@@ -87,12 +128,22 @@ pub fn tool(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 && let Lit::Str(str_lit) = &lit.lit
             {
                 name = Some(str_lit.value());
+            } else if arg.path.is_ident("strict")
+                && let Expr::Lit(lit) = &arg.value
+                && let Lit::Bool(bool_lit) = &lit.lit
+            {
+                strict = Some(bool_lit.value());
             }
         }
 
-        (name, description)
+        (name, description, strict)
     } else {
-        (None, None)
+        (None, None, None)
+    };
+
+    let strict = match strict_arg {
+        Some(strict) => quote! { .strict(#strict) },
+        None => quote! {},
     };
 
     let description = if let Some(desc) = description_arg {
@@ -195,6 +246,7 @@ pub fn tool(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 .name(#name.to_string())
                 .description(#description.to_string())
                 .input_schema(input_schema)
+                #strict
                 .execute(::aisdk::core::tools::ToolExecute::new(Box::new(|inp| -> std::result::Result<String, String> {
                     #(#binding_tokens)*
                     #block
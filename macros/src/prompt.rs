@@ -0,0 +1,140 @@
+//! Conversion of `prompt!` templates to the Tera syntax.
+
+/// Converts a template with `{name}` placeholders to the Tera syntax, checking
+/// its placeholders and tags.
+///
+/// - `{name}` and `{user.name}` become `{{ name }}` and `{{ user.name }}`.
+/// - `{{` and `}}` are literal braces.
+/// - `{% ... %}` tags are kept, and their blocks must be closed.
+pub(crate) fn to_tera(template: &str) -> Result<String, String> {
+    let mut output = String::with_capacity(template.len());
+    let mut blocks: Vec<&'static str> = Vec::new();
+    let mut rest = template;
+
+    while let Some(index) = rest.find(['{', '}']) {
+        output.push_str(&rest[..index]);
+        let tail = &rest[index..];
+
+        if let Some(after) = tail.strip_prefix("{{") {
+            output.push_str("{{ \"{\" }}");
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix("}}") {
+            output.push('}');
+            rest = after;
+        } else if tail.starts_with('}') {
+            return Err("Unmatched `}` in prompt, use `}}` for a literal brace".to_string());
+        } else if let Some(after) = tail.strip_prefix("{%") {
+            let end = after.find("%}").ok_or("Unclosed `{%` tag in prompt")?;
+            check_tag(after[..end].trim(), &mut blocks)?;
+            output.push_str(&tail[..end + 4]);
+            rest = &after[end + 2..];
+        } else {
+            let end = tail.find('}').ok_or("Unclosed `{` in prompt")?;
+            let name = tail[1..end].trim();
+            if !is_variable(name) {
+                return Err(format!(
+                    "Invalid variable `{{{name}}}` in prompt, expected a name such as `{{user.name}}`"
+                ));
+            }
+            output.push_str(&format!("{{{{ {name} }}}}"));
+            rest = &tail[end + 1..];
+        }
+    }
+    output.push_str(rest);
+
+    match blocks.pop() {
+        Some(block) => Err(format!("Unclosed `{{% {block} %}}` block in prompt")),
+        None => Ok(output),
+    }
+}
+
+/// Checks a tag, keeping track of the open blocks.
+fn check_tag(tag: &str, blocks: &mut Vec<&'static str>) -> Result<(), String> {
+    let (keyword, argument) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+    let argument = argument.trim();
+
+    match keyword {
+        "if" | "for" if argument.is_empty() => {
+            Err(format!("Missing expression in `{{% {keyword} %}}`"))
+        }
+        "if" => {
+            blocks.push("if");
+            Ok(())
+        }
+        "for" => {
+            if !argument.contains(" in ") {
+                return Err(format!(
+                    "Expected `{{% for item in items %}}`, got `{{% {tag} %}}`"
+                ));
+            }
+            blocks.push("for");
+            Ok(())
+        }
+        "elif" | "else" => match blocks.last() {
+            Some(&"if") => Ok(()),
+            Some(&"for") if keyword == "else" => Ok(()),
+            _ => Err(format!("`{{% {keyword} %}}` outside of an `if` block")),
+        },
+        "endif" | "endfor" => match blocks.pop() {
+            Some(block) if keyword[3..] == *block => Ok(()),
+            Some(block) => Err(format!(
+                "`{{% {keyword} %}}` closes a `{{% {block} %}}` block"
+            )),
+            None => Err(format!("`{{% {keyword} %}}` without an open block")),
+        },
+        "include" => {
+            let quoted = argument.len() >= 2
+                && (argument.starts_with('"') && argument.ends_with('"')
+                    || argument.starts_with('\'') && argument.ends_with('\''));
+            if quoted {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Expected `{{% include \"name\" %}}`, got `{{% {tag} %}}`"
+                ))
+            }
+        }
+        _ => Err(format!("Unsupported tag `{{% {tag} %}}` in prompt")),
+    }
+}
+
+/// Returns `true` if the name is a variable, optionally with fields.
+fn is_variable(name: &str) -> bool {
+    !name.is_empty()
+        && name.split('.').all(|part| {
+            part.chars()
+                .next()
+                .is_some_and(|c| c.is_alphabetic() || c == '_')
+                && part.chars().all(|c| c.is_alphanumeric() || c == '_')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_tera_converts_variables_and_keeps_tags() {
+        assert_eq!(
+            to_tera("Hi {name}! {% if user.vip %}VIP {user.tier}{% endif %}").unwrap(),
+            "Hi {{ name }}! {% if user.vip %}VIP {{ user.tier }}{% endif %}"
+        );
+        assert_eq!(
+            to_tera("{{\"json\": {value}}}").unwrap(),
+            "{{ \"{\" }}\"json\": {{ value }}}"
+        );
+    }
+
+    #[test]
+    fn test_to_tera_rejects_invalid_templates() {
+        assert!(to_tera("{na me}").is_err());
+        assert!(to_tera("{}").is_err());
+        assert!(to_tera("{name").is_err());
+        assert!(to_tera("name}").is_err());
+        assert!(to_tera("{% for x in xs %}{% endif %}").is_err());
+        assert!(to_tera("{% if x %}").is_err());
+        assert!(to_tera("{% else %}").is_err());
+        assert!(to_tera("{% include partial %}").is_err());
+        assert!(to_tera("{% macro x() %}").is_err());
+    }
+}
@@ -0,0 +1,35 @@
+// tests
+#[cfg(test)]
+mod tests {
+    use aisdk::macros::prompt;
+    use serde_json::json;
+
+    #[test]
+    fn test_prompt_macro_renders_variables_and_blocks() {
+        let template = prompt!(
+            "Hi {user.name}!{% if items %} Your order:{% for item in items %} {item}{% endfor %}{% endif %} {{ok}}"
+        );
+
+        let text = template
+            .render(&json!({ "user": { "name": "Ada" }, "items": ["tea", "scones"] }))
+            .unwrap();
+        assert_eq!(text, "Hi Ada! Your order: tea scones {ok}");
+
+        let text = template
+            .render(&json!({ "user": { "name": "Bob" }, "items": [] }))
+            .unwrap();
+        assert_eq!(text, "Hi Bob! {ok}");
+    }
+
+    #[test]
+    fn test_prompt_macro_with_partials() {
+        let template = prompt!("{% include \"persona\" %} Answer: {question}")
+            .partial("persona", "You are a {{ role }}.")
+            .unwrap();
+
+        let text = template
+            .render(&json!({ "role": "pirate", "question": "Where is the treasure?" }))
+            .unwrap();
+        assert_eq!(text, "You are a pirate. Answer: Where is the treasure?");
+    }
+}
@@ -378,6 +378,8 @@ MANUALLY_MANAGED_MODULES = {
     "amazon_bedrock",
     "togetherai",
     "xai",
+    "ollama",
+    "vertex_ai",
 }
 
 
@@ -521,6 +523,52 @@ def update_providers_mod_rs(root: Path | None = None):
     log(f"Updated {mod_rs_path} with {len(provider_dirs)} codegen providers")
 
 
+def update_catalog_rs(root: Path | None = None):
+    """
+    Regenerate the codegen block in src/core/catalog.rs.
+
+    Lists the models of every codegen-managed provider in the model catalog,
+    between the // [codegen] and // [end-codegen] markers.
+
+    Args:
+        root: Optional project root (defaults to auto-detected root)
+    """
+    if root is None:
+        root = get_project_root()
+
+    catalog_rs_path = root / "src" / "core" / "catalog.rs"
+    content = catalog_rs_path.read_text(encoding="utf-8")
+
+    start_marker = "// [codegen]"
+    end_marker = "// [end-codegen]"
+
+    start_idx = content.find(start_marker)
+    end_idx = content.find(end_marker)
+
+    if start_idx == -1 or end_idx == -1:
+        raise RuntimeError(
+            f"Could not find codegen markers in {catalog_rs_path}. "
+            f"Expected '{start_marker}' and '{end_marker}'"
+        )
+
+    lines = []
+    for dir_name in get_codegen_provider_dirs(root):
+        module_name = provider_id_to_snake_case(dir_name)
+        lines.append(f'    #[cfg(feature = "{dir_name}")]')
+        lines.append("    ProviderModels {")
+        lines.append(f'        id: "{dir_name}",')
+        lines.append(f"        models: crate::providers::{module_name}::capabilities::MODELS,")
+        lines.append("    },")
+
+    codegen_content = "\n".join(lines)
+    new_block = f"{start_marker}\n{codegen_content}\n    {end_marker}"
+
+    new_content = content[:start_idx] + new_block + content[end_idx + len(end_marker) :]
+
+    catalog_rs_path.write_text(new_content, encoding="utf-8")
+    log(f"Updated {catalog_rs_path}")
+
+
 # ============================================================================
 # CAPABILITIES GENERATION
 # ============================================================================
@@ -604,6 +652,48 @@ def parse_model_id(model_id: str) -> tuple[str, str | None]:
     return model_id, None
 
 
+def get_model_pricing(model_data: dict[str, Any]) -> str | None:
+    """
+    Build the `pricing` block of a model from its models.dev cost data.
+
+    Args:
+        model_data: Model configuration from API
+
+    Returns:
+        Rust macro fragment, or None if the model has no input/output cost
+    """
+    cost = model_data.get("cost") or {}
+    input_cost = cost.get("input")
+    output_cost = cost.get("output")
+    if input_cost is None or output_cost is None:
+        return None
+
+    fields = [f"input: {float(input_cost)!r}", f"output: {float(output_cost)!r}"]
+    if cost.get("cache_read") is not None:
+        fields.append(f"cached: {float(cost['cache_read'])!r}")
+    return "{ " + ", ".join(fields) + " }"
+
+
+
+def get_model_limits(model_data: dict[str, Any]) -> list[tuple[str, int]]:
+    """
+    Extract the context window and output token limits of a model.
+
+    Args:
+        model_data: Model configuration from API
+
+    Returns:
+        List of (macro key, value) pairs for the limits that are known
+    """
+    limit = model_data.get("limit") or {}
+    limits = []
+    if limit.get("context"):
+        limits.append(("context_window", int(limit["context"])))
+    if limit.get("output"):
+        limits.append(("max_output_tokens", int(limit["output"])))
+    return limits
+
+
 def generate_capabilities_rs(provider_id: str, models: dict[str, Any]) -> str:
     """
     Generate the complete capabilities.rs content.
@@ -667,17 +757,23 @@ def generate_capabilities_rs(provider_id: str, models: dict[str, Any]) -> str:
         constructor_name = get_model_constructor_name(base_name, folder_prefix)
         display_name = get_model_display_name(model_id, model_data)
         capabilities = get_model_capabilities(model_data)
-
-        lines.extend(
-            [
-                f"        {model_type_name} {{",
-                f'            model_name: "{model_name}",',
-                f"            constructor_name: {constructor_name},",
-                f'            display_name: "{display_name}",',
-                f"            capabilities: [{', '.join(capabilities)}]",
-                "        },",
-            ]
-        )
+        pricing = get_model_pricing(model_data)
+
+        entry = [
+            f"        {model_type_name} {{",
+            f'            model_name: "{model_name}",',
+            f"            constructor_name: {constructor_name},",
+            f'            display_name: "{display_name}",',
+            f"            capabilities: [{', '.join(capabilities)}]",
+        ]
+        if pricing:
+            entry[-1] += ","
+            entry.append(f"            pricing: {pricing}")
+        for key, value in get_model_limits(model_data):
+            entry[-1] += ","
+            entry.append(f"            {key}: {value}")
+        entry.append("        },")
+        lines.extend(entry)
 
     lines.extend(
         [
@@ -990,6 +1086,7 @@ def openai_compatible(
             )
         update_cargo_toml(generated_provider_ids)
         update_providers_mod_rs()
+        update_catalog_rs()
 
         # PHASE 4: Format
         run_cargo_fmt()
@@ -1043,8 +1140,9 @@ def capabilities(
         # PHASE 2: Write all files atomically
         written_files = batch_write_files(pending_writes)
 
-        # PHASE 3: Sync mod.rs codegen block
+        # PHASE 3: Sync mod.rs and catalog.rs codegen blocks
         update_providers_mod_rs()
+        update_catalog_rs()
 
         # PHASE 4: Format
         run_cargo_fmt()
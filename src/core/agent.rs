@@ -0,0 +1,409 @@
+//! Agents keeping their instructions, tools and conversation across turns.
+//!
+//! An [`Agent`] bundles a language model with the system instructions and tools
+//! it runs with, and remembers the conversation, so each turn only needs the new
+//! user input. Tool calls are executed within a turn, as with
+//! [`LanguageModelRequest`].
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::agent::Agent;
+//! use aisdk::providers::OpenAI;
+//!
+//! # fn get_weather() -> aisdk::core::Tool { unimplemented!() }
+//! # async fn run() -> aisdk::Result<()> {
+//! let mut agent = Agent::new(OpenAI::gpt_5())
+//!     .instructions("You are a travel assistant.")
+//!     .with_tool(get_weather());
+//!
+//! let response = agent.run("What is the weather in Paris?").await?;
+//! println!("{:?}", response.text());
+//!
+//! // The agent remembers the previous turn
+//! let response = agent.run("And tomorrow?").await?;
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+use crate::core::capabilities::ToolCallSupport;
+use crate::core::language_model::LanguageModelOptions;
+use crate::core::memory::Memory;
+use crate::core::messages::TaggedMessage;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::core::runtime::block_on;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::core::tools::ToolExecute;
+use crate::core::tools::ToolList;
+use crate::core::{
+    GenerateTextResponse, LanguageModel, LanguageModelRequest, Message, Messages,
+    StreamTextResponse, Tool, UserMessage,
+};
+use crate::error::Result;
+#[cfg(not(target_arch = "wasm32"))]
+use schemars::{JsonSchema, schema_for};
+#[cfg(not(target_arch = "wasm32"))]
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// A language model with persistent instructions, tools and conversation memory.
+#[derive(Debug, Clone)]
+pub struct Agent<M: LanguageModel> {
+    model: M,
+    instructions: Option<String>,
+    tools: Option<ToolList>,
+    memory: Messages,
+    /// The memory the conversation is kept in, instead of the agent itself.
+    store: Option<Arc<dyn Memory>>,
+    /// The conversation of the latest streamed turn, read into the memory once
+    /// the next turn starts.
+    streaming: Option<Arc<Mutex<LanguageModelOptions>>>,
+}
+
+impl<M: LanguageModel> Agent<M> {
+    /// Creates an agent without instructions, tools or memory.
+    pub fn new(model: M) -> Self {
+        Self {
+            model,
+            instructions: None,
+            tools: None,
+            memory: Vec::new(),
+            store: None,
+            streaming: None,
+        }
+    }
+
+    /// Sets the system instructions, sent with every turn.
+    pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
+    /// Adds a tool the agent can call.
+    pub fn with_tool(mut self, tool: Tool) -> Self
+    where
+        M: ToolCallSupport,
+    {
+        self.tools.get_or_insert_default().add_tool(tool);
+        self
+    }
+
+    /// Keeps the conversation in a memory, such as a
+    /// [`SlidingWindowMemory`](crate::core::memory::SlidingWindowMemory), instead of
+    /// the whole conversation in the agent.
+    ///
+    /// Each turn loads the conversation from the memory, and appends its messages
+    /// to it once finished. Copies of the agent share the memory.
+    pub fn with_memory(mut self, memory: impl Memory + 'static) -> Self {
+        self.store = Some(Arc::new(memory));
+        self
+    }
+
+    /// Returns the model of the agent.
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+
+    /// Returns the conversation remembered by the agent, without the instructions.
+    ///
+    /// A streamed turn is included once the next turn starts. With a
+    /// [`Memory`], this is the conversation of the latest turn, as loaded from it.
+    pub fn memory(&self) -> &[Message] {
+        &self.memory
+    }
+
+    /// Forgets the conversation, keeping the instructions and tools.
+    ///
+    /// The conversation kept in a [`Memory`] is left as is.
+    pub fn clear_memory(&mut self) {
+        self.memory.clear();
+        self.streaming = None;
+    }
+
+    /// Runs a turn of the conversation.
+    ///
+    /// # Parameters
+    ///
+    /// * `input` - The message of the user.
+    ///
+    /// # Returns
+    ///
+    /// The response of the turn. Its messages, without the instructions, become
+    /// the memory of the agent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if generation fails, in which case the turn is not remembered.
+    pub async fn run(&mut self, input: impl Into<String>) -> Result<GenerateTextResponse> {
+        let mut request = self.request(input).await;
+        let response = request.generate_text().await?;
+        self.remember(&response.options);
+        Ok(response)
+    }
+
+    /// Runs a turn of the conversation, streaming the response.
+    ///
+    /// # Parameters
+    ///
+    /// * `input` - The message of the user.
+    ///
+    /// # Returns
+    ///
+    /// The streaming response of the turn. Its messages are remembered once the
+    /// next turn starts, after the stream has finished.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if the stream can not be started.
+    pub async fn run_stream(&mut self, input: impl Into<String>) -> Result<StreamTextResponse> {
+        let mut request = self.request(input).await;
+        let response = request.stream_text().await?;
+        self.streaming = Some(response.options.clone());
+        Ok(response)
+    }
+
+    /// Exposes the agent as a tool, so another agent or request can delegate tasks to it.
+    ///
+    /// The model calling the tool writes the task, and optionally the context the
+    /// agent needs from its own conversation. Each call runs a turn on a copy of
+    /// the agent, starting from its current memory, and the text of the response
+    /// becomes the result of the tool.
+    ///
+    /// Not available on `wasm32`, where tools can not wait for a request.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the tool.
+    /// * `description` - What the agent does, so the calling model knows when to use it.
+    ///
+    /// # Returns
+    ///
+    /// A tool running the agent.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    ///# #[cfg(feature = "openai")]
+    ///# {
+    /// use aisdk::core::agent::Agent;
+    /// use aisdk::providers::OpenAI;
+    ///
+    /// let researcher = Agent::new(OpenAI::gpt_5())
+    ///     .instructions("Find facts and cite your sources.");
+    ///
+    /// let mut orchestrator = Agent::new(OpenAI::gpt_5())
+    ///     .instructions("Plan the work and delegate research.")
+    ///     .with_tool(researcher.as_tool("researcher", "Researches a question."));
+    ///# }
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn as_tool(&self, name: impl Into<String>, description: impl Into<String>) -> Tool {
+        let agent = self.clone();
+        Tool {
+            name: name.into(),
+            description: description.into(),
+            input_schema: schema_for!(AgentToolInput),
+            execute: ToolExecute::new(Box::new(move |input| {
+                let input: AgentToolInput =
+                    serde_json::from_value(input).map_err(|e| format!("Invalid input: {e}"))?;
+                let mut agent = agent.clone();
+                block_on(async move {
+                    let response = agent
+                        .run(input.into_prompt())
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    response
+                        .text()
+                        .ok_or_else(|| "The agent did not answer".to_string())
+                })
+            })),
+            strict: None,
+        }
+    }
+
+    /// Builds the request of a turn from the memory and the new input.
+    async fn request(&mut self, input: impl Into<String>) -> LanguageModelRequest<M> {
+        if let Some(options) = self.streaming.take() {
+            let options = options.lock().await;
+            self.remember(&options);
+        }
+
+        // The memory loads the conversation itself when the request starts
+        let mut messages: Vec<TaggedMessage> = match self.store {
+            Some(_) => Vec::new(),
+            None => self
+                .memory
+                .iter()
+                .cloned()
+                .map(TaggedMessage::from)
+                .collect(),
+        };
+        messages.push(Message::User(UserMessage::new(input)).into());
+
+        LanguageModelRequest {
+            model: self.model.clone(),
+            prompt: None,
+            options: LanguageModelOptions {
+                system: self.instructions.clone(),
+                tools: self.tools.clone(),
+                messages,
+                memory: self.store.clone(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Replaces the memory with the conversation of a finished turn.
+    fn remember(&mut self, options: &LanguageModelOptions) {
+        self.memory = options
+            .messages()
+            .into_iter()
+            .filter(|message| !matches!(message, Message::System(_)))
+            .collect();
+    }
+}
+
+/// The input of an agent called as a tool.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Deserialize, JsonSchema)]
+struct AgentToolInput {
+    /// The task for the agent, as a complete request.
+    task: String,
+    /// Information from the conversation the agent needs for the task.
+    #[serde(default)]
+    context: Option<String>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl AgentToolInput {
+    /// Returns the message sent to the agent.
+    fn into_prompt(self) -> String {
+        match self.context {
+            Some(context) if !context.trim().is_empty() => {
+                format!("Context:\n{context}\n\nTask:\n{}", self.task)
+            }
+            _ => self.task,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LanguageModelStreamChunkType;
+    use crate::providers::MockLanguageModel;
+    use futures::StreamExt;
+
+    /// Returns the number of user messages of every call made to a mock model.
+    fn user_turns(model: &MockLanguageModel) -> Vec<usize> {
+        model
+            .calls()
+            .iter()
+            .map(|options| {
+                options
+                    .messages()
+                    .iter()
+                    .filter(|m| matches!(m, Message::User(_)))
+                    .count()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_agent_remembers_turns() {
+        let model = MockLanguageModel::new()
+            .with_text("one")
+            .with_text("two")
+            .with_text("three");
+        let mut agent = Agent::new(model.clone()).instructions("Be brief.");
+
+        let response = agent.run("Hi").await.unwrap();
+        assert_eq!(response.text().unwrap(), "one");
+        let response = agent.run("Again").await.unwrap();
+        assert_eq!(response.text().unwrap(), "two");
+
+        assert_eq!(agent.memory().len(), 4);
+        assert!(matches!(&agent.memory()[2], Message::User(u) if u.content == "Again"));
+
+        agent.clear_memory();
+        agent.run("Hello").await.unwrap();
+        assert_eq!(user_turns(&model), [1, 2, 1]);
+        assert!(
+            model
+                .calls()
+                .iter()
+                .all(|options| options.system.as_deref() == Some("Be brief."))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_agent_as_tool_delegates_task_with_context() {
+        let research = MockLanguageModel::new().with_text("Light scatters.");
+        let researcher = Agent::new(research.clone());
+        let orchestration = MockLanguageModel::new()
+            .with_tool_call(
+                "researcher",
+                serde_json::json!({ "task": "Why is the sky blue?", "context": "For kids" }),
+            )
+            .with_text("done");
+        let mut orchestrator = Agent::new(orchestration.clone())
+            .with_tool(researcher.as_tool("researcher", "Researches a question."));
+
+        let response = orchestrator.run("Explain the sky").await.unwrap();
+        assert_eq!(response.text().unwrap(), "done");
+        assert!(matches!(
+            &research.calls()[0].messages()[..],
+            [Message::User(u)] if u.content == "Context:\nFor kids\n\nTask:\nWhy is the sky blue?"
+        ));
+        assert_eq!(
+            orchestration.calls()[1].tool_results().unwrap()[0].output,
+            Ok(serde_json::Value::String("Light scatters.".to_string()))
+        );
+        // The sub-agent ran on a copy, leaving the original without memory
+        assert!(researcher.memory().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_agent_keeps_conversation_in_memory() {
+        let memory = Arc::new(crate::core::memory::InMemory::new());
+        let model = MockLanguageModel::new().with_text("one").with_text("two");
+        let mut agent = Agent::new(model.clone()).with_memory(memory.clone());
+
+        agent.run("Hi").await.unwrap();
+        let response = agent.run("Again").await.unwrap();
+        assert_eq!(response.text().unwrap(), "two");
+        assert_eq!(memory.load().await.unwrap().len(), 4);
+
+        // Another agent continues the conversation of the memory
+        let other_model = MockLanguageModel::new().with_text("three");
+        let mut other = Agent::new(other_model.clone()).with_memory(memory.clone());
+        other.run("Third").await.unwrap();
+        assert_eq!(user_turns(&model), [1, 2]);
+        assert_eq!(user_turns(&other_model), [3]);
+        assert_eq!(memory.load().await.unwrap().len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_agent_remembers_streamed_turns() {
+        let model = MockLanguageModel::new().with_text("one").with_text("two");
+        let mut agent = Agent::new(model.clone());
+
+        let mut response = agent.run_stream("Hi").await.unwrap();
+        let mut text = String::new();
+        while let Some(chunk) = response.stream.next().await {
+            if let LanguageModelStreamChunkType::Text(delta) = chunk {
+                text.push_str(&delta);
+            }
+        }
+        assert_eq!(text, "one");
+
+        let response = agent.run("Again").await.unwrap();
+        assert_eq!(response.text().unwrap(), "two");
+        assert_eq!(user_turns(&model), [1, 2]);
+        assert_eq!(agent.memory().len(), 4);
+    }
+}
@@ -0,0 +1,332 @@
+//! Batch processing of language model requests.
+//!
+//! Providers with a batch API process large sets of requests asynchronously, at a
+//! lower price, usually within 24 hours. This module defines the
+//! [`BatchLanguageModel`] trait implemented by those providers, and the [`Batch`]
+//! builder, which submits requests, waits for them to be processed and streams
+//! back their results.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::LanguageModelRequest;
+//! use aisdk::core::batch::Batch;
+//! use aisdk::providers::OpenAI;
+//! use futures::StreamExt;
+//!
+//! # async fn run() -> aisdk::Result<()> {
+//! let request = |prompt: &str| {
+//!     LanguageModelRequest::builder()
+//!         .model(OpenAI::gpt_5())
+//!         .prompt(prompt)
+//!         .build()
+//! };
+//!
+//! let mut batch = Batch::new(OpenAI::gpt_5())
+//!     .add("sky", request("Why is the sky blue?"))
+//!     .add("sea", request("Why is the sea salty?"))
+//!     .submit()
+//!     .await?;
+//!
+//! batch.wait().await?;
+//! let mut results = batch.results().await?;
+//! while let Some((custom_id, response)) = results.next().await {
+//!     println!("{custom_id}: {:?}", response?.text());
+//! }
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+use crate::core::language_model::{LanguageModel, LanguageModelOptions, LanguageModelResponse};
+use crate::error::Result;
+use async_trait::async_trait;
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use std::time::Duration;
+
+#[cfg(feature = "language-model-request")]
+use crate::core::{
+    AssistantMessage, Message,
+    language_model::{StopReason, generate_text::GenerateTextResponse},
+    messages::TaggedMessage,
+};
+#[cfg(feature = "language-model-request")]
+use futures::StreamExt;
+#[cfg(feature = "language-model-request")]
+use std::collections::HashMap;
+
+/// The first delay between two polls of a batch.
+const INITIAL_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The longest delay between two polls of a batch.
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A stream of the results of a batch.
+pub type BatchResultStream = Pin<Box<dyn Stream<Item = Result<BatchResult>> + Send>>;
+
+/// A request of a batch.
+#[derive(Debug, Clone)]
+pub struct BatchRequest {
+    /// The ID of the request, unique within the batch, used to match its result.
+    pub custom_id: String,
+    /// The options of the request.
+    pub options: LanguageModelOptions,
+}
+
+/// The status of a batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    /// The input of the batch is being validated.
+    Validating,
+    /// The requests of the batch are being processed.
+    InProgress,
+    /// The results of the batch are being prepared.
+    Finalizing,
+    /// The batch was processed, and its results are available.
+    Completed,
+    /// The batch failed before its requests were processed.
+    Failed,
+    /// The batch was not processed within its completion window.
+    Expired,
+    /// The batch is being cancelled.
+    Cancelling,
+    /// The batch was cancelled. The results of the requests processed before are available.
+    Cancelled,
+}
+
+impl BatchStatus {
+    /// Returns whether the batch is still being processed.
+    pub fn is_pending(&self) -> bool {
+        matches!(
+            self,
+            Self::Validating | Self::InProgress | Self::Finalizing | Self::Cancelling
+        )
+    }
+}
+
+/// The number of requests of a batch, by state.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchRequestCounts {
+    /// The number of requests in the batch.
+    pub total: usize,
+    /// The number of requests that succeeded.
+    pub succeeded: usize,
+    /// The number of requests that failed, were cancelled or expired.
+    pub failed: usize,
+}
+
+/// A snapshot of a batch.
+#[derive(Debug, Clone)]
+pub struct BatchInfo {
+    /// The ID of the batch.
+    pub id: String,
+    /// The status of the batch.
+    pub status: BatchStatus,
+    /// The number of requests of the batch, by state.
+    pub request_counts: BatchRequestCounts,
+}
+
+/// The result of a request of a batch.
+#[derive(Debug, Clone)]
+pub struct BatchResult {
+    /// The ID of the request.
+    pub custom_id: String,
+    /// The response of the model, or the error of the request.
+    pub response: Result<LanguageModelResponse>,
+}
+
+/// A language model whose provider can process requests in batches.
+#[async_trait]
+pub trait BatchLanguageModel: LanguageModel {
+    /// Submits a batch of requests.
+    ///
+    /// Input files, when the provider needs one, are uploaded first.
+    async fn create_batch(&mut self, requests: Vec<BatchRequest>) -> Result<BatchInfo>;
+
+    /// Retrieves the current state of a batch, using its ID.
+    async fn retrieve_batch(&self, id: &str) -> Result<BatchInfo>;
+
+    /// Cancels a batch. The requests processed before keep their results.
+    async fn cancel_batch(&self, id: &str) -> Result<BatchInfo>;
+
+    /// Streams the results of a processed batch, in no particular order.
+    async fn batch_results(&self, id: &str) -> Result<BatchResultStream>;
+}
+
+/// Waits for a batch to be processed, polling it with an increasing interval.
+///
+/// # Returns
+///
+/// The final state of the batch.
+///
+/// # Errors
+///
+/// Returns an `Error` if a request fails.
+pub async fn wait_for_batch<M: BatchLanguageModel>(model: &M, id: &str) -> Result<BatchInfo> {
+    let mut interval = INITIAL_POLL_INTERVAL;
+    loop {
+        let info = model.retrieve_batch(id).await?;
+        if !info.status.is_pending() {
+            return Ok(info);
+        }
+
+        crate::core::runtime::sleep(interval).await;
+        interval = (interval * 2).min(MAX_POLL_INTERVAL);
+    }
+}
+
+/// A builder for a batch of requests.
+#[cfg(feature = "language-model-request")]
+pub struct Batch<M: BatchLanguageModel> {
+    model: M,
+    requests: Vec<BatchRequest>,
+}
+
+#[cfg(feature = "language-model-request")]
+impl<M: BatchLanguageModel> Batch<M> {
+    /// Creates an empty batch for a model.
+    pub fn new(model: M) -> Self {
+        Self {
+            model,
+            requests: Vec::new(),
+        }
+    }
+
+    /// Adds a request to the batch.
+    ///
+    /// # Parameters
+    ///
+    /// * `custom_id` - The ID of the request, unique within the batch.
+    /// * `options` - The options of the request, or a [`LanguageModelRequest`](crate::core::LanguageModelRequest).
+    ///   Tool calls in the responses are returned, but not executed.
+    pub fn add(
+        mut self,
+        custom_id: impl Into<String>,
+        options: impl Into<LanguageModelOptions>,
+    ) -> Self {
+        self.requests.push(BatchRequest {
+            custom_id: custom_id.into(),
+            options: options.into(),
+        });
+        self
+    }
+
+    /// Submits the batch to the provider.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if the batch is empty, two requests share an ID, or a
+    /// request fails.
+    pub async fn submit(mut self) -> Result<SubmittedBatch<M>> {
+        if self.requests.is_empty() {
+            return Err(crate::Error::InvalidInput(
+                "A batch needs at least one request".to_string(),
+            ));
+        }
+
+        let mut requests = HashMap::new();
+        for request in &self.requests {
+            if requests
+                .insert(request.custom_id.clone(), request.options.clone())
+                .is_some()
+            {
+                return Err(crate::Error::InvalidInput(format!(
+                    "Duplicate batch request ID: {}",
+                    request.custom_id
+                )));
+            }
+        }
+
+        let info = self.model.create_batch(self.requests).await?;
+        Ok(SubmittedBatch {
+            model: self.model,
+            info,
+            requests,
+        })
+    }
+}
+
+/// A batch submitted to the provider.
+#[cfg(feature = "language-model-request")]
+pub struct SubmittedBatch<M: BatchLanguageModel> {
+    model: M,
+    info: BatchInfo,
+    requests: HashMap<String, LanguageModelOptions>,
+}
+
+#[cfg(feature = "language-model-request")]
+impl<M: BatchLanguageModel> SubmittedBatch<M> {
+    /// Returns the last known state of the batch.
+    pub fn info(&self) -> &BatchInfo {
+        &self.info
+    }
+
+    /// Retrieves the current state of the batch.
+    pub async fn refresh(&mut self) -> Result<&BatchInfo> {
+        self.info = self.model.retrieve_batch(&self.info.id).await?;
+        Ok(&self.info)
+    }
+
+    /// Cancels the batch.
+    pub async fn cancel(&mut self) -> Result<&BatchInfo> {
+        self.info = self.model.cancel_batch(&self.info.id).await?;
+        Ok(&self.info)
+    }
+
+    /// Waits for the batch to be processed.
+    pub async fn wait(&mut self) -> Result<&BatchInfo> {
+        self.info = wait_for_batch(&self.model, &self.info.id).await?;
+        Ok(&self.info)
+    }
+
+    /// Streams the results of the processed batch, as the IDs of the requests
+    /// and their responses.
+    ///
+    /// The options of each response are those of its request, with the output
+    /// of the model added to the messages.
+    pub async fn results(
+        &self,
+    ) -> Result<impl Stream<Item = (String, Result<GenerateTextResponse>)> + Send + 'static> {
+        let requests = self.requests.clone();
+        let results = self.model.batch_results(&self.info.id).await?;
+
+        Ok(results.filter_map(move |result| {
+            let result = match result {
+                Ok(result) => {
+                    let options = requests.get(&result.custom_id).cloned().unwrap_or_default();
+                    Some((
+                        result.custom_id,
+                        result
+                            .response
+                            .map(|response| text_response(options, response)),
+                    ))
+                }
+                // Lines that can not be read have no request ID
+                Err(e) => Some((String::new(), Err(e))),
+            };
+            futures::future::ready(result)
+        }))
+    }
+}
+
+/// Adds the output of a batch request to its options, as a single step.
+#[cfg(feature = "language-model-request")]
+fn text_response(
+    mut options: LanguageModelOptions,
+    response: LanguageModelResponse,
+) -> GenerateTextResponse {
+    options.current_step_id += 1;
+    for content in response.contents {
+        options.messages.push(TaggedMessage::new(
+            options.current_step_id,
+            Message::Assistant(AssistantMessage::new(content, response.usage.clone())),
+        ));
+    }
+    options.stop_reason = Some(StopReason::Finish);
+    GenerateTextResponse { options }
+}
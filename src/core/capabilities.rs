@@ -4,7 +4,30 @@
 //! These traits are used to determine which models are compatible with a given provider.
 //!
 //! This ensures that selected models are capable of doing the tasks they are intended for.
-//! For example, only models that support tool calls can be used for tool usage.
+//! For example, only models that support tool calls can be used for tool usage: the
+//! request builder methods needing a capability, such as
+//! [`with_tool`](crate::core::language_model::request::LanguageModelRequestBuilder::with_tool),
+//! [`schema`](crate::core::language_model::request::LanguageModelRequestBuilder::schema) or
+//! [`reasoning_effort`](crate::core::language_model::request::LanguageModelRequestBuilder::reasoning_effort),
+//! do not compile for other models.
+//!
+//! ```rust,compile_fail
+//! use aisdk::core::{LanguageModelRequest, Tool};
+//! use aisdk::providers::OpenAI;
+//!
+//! // GPT-3.5 Turbo does not support tool calls
+//! let request = LanguageModelRequest::builder()
+//!     .model(OpenAI::gpt_3_5_turbo())
+//!     .prompt("What is the weather in Paris?")
+//!     .with_tool(Tool::default())
+//!     .build();
+//! ```
+//!
+//! The same capabilities are available at runtime as a [`CapabilitySet`], through
+//! [`LanguageModel::capabilities`](crate::core::LanguageModel::capabilities), for
+//! applications that pick models from configuration.
+
+use serde::{Deserialize, Serialize};
 
 /// A trait that represents a model name.
 /// struct name to actual model name
@@ -12,6 +35,145 @@
 pub trait ModelName: Send + Sync + std::fmt::Debug + Clone + 'static {
     /// The underlying API model name.
     const MODEL_NAME: &'static str;
+
+    /// Per-million-token pricing of the model, if known.
+    const PRICING: Option<crate::core::pricing::ModelPricing> = None;
+
+    /// Maximum number of tokens the model accepts in a single request, if known.
+    const CONTEXT_WINDOW: Option<usize> = None;
+
+    /// Maximum number of tokens the model can generate in a single response, if known.
+    const MAX_OUTPUT_TOKENS: Option<usize> = None;
+
+    /// Capabilities of the model. Models that do not declare their capabilities
+    /// report all of them, leaving the validation to the provider's API.
+    const CAPABILITIES: CapabilitySet = CapabilitySet::all();
+}
+
+/// A capability of a model, the runtime counterpart of the marker traits below.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// See [`ToolCallSupport`].
+    ToolCall,
+    /// See [`ReasoningSupport`].
+    Reasoning,
+    /// See [`StructuredOutputSupport`].
+    StructuredOutput,
+    /// See [`TextInputSupport`].
+    TextInput,
+    /// See [`VideoInputSupport`].
+    VideoInput,
+    /// See [`AudioInputSupport`].
+    AudioInput,
+    /// See [`ImageInputSupport`].
+    ImageInput,
+    /// See [`TextOutputSupport`].
+    TextOutput,
+    /// See [`VideoOutputSupport`].
+    VideoOutput,
+    /// See [`AudioOutputSupport`].
+    AudioOutput,
+    /// See [`ImageOutputSupport`].
+    ImageOutput,
+}
+
+impl Capability {
+    /// All capabilities.
+    pub const ALL: [Capability; 11] = [
+        Capability::ToolCall,
+        Capability::Reasoning,
+        Capability::StructuredOutput,
+        Capability::TextInput,
+        Capability::VideoInput,
+        Capability::AudioInput,
+        Capability::ImageInput,
+        Capability::TextOutput,
+        Capability::VideoOutput,
+        Capability::AudioOutput,
+        Capability::ImageOutput,
+    ];
+
+    const fn bit(self) -> u16 {
+        1 << self as u16
+    }
+}
+
+/// A set of [`Capability`] values.
+///
+/// # Example
+///
+/// ```rust
+/// use aisdk::core::capabilities::{Capability, CapabilitySet};
+///
+/// let set = CapabilitySet::empty()
+///     .with(Capability::TextInput)
+///     .with(Capability::ToolCall);
+///
+/// assert!(set.contains(Capability::ToolCall));
+/// assert!(!set.contains(Capability::Reasoning));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CapabilitySet(u16);
+
+impl CapabilitySet {
+    /// Returns a set without capabilities.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns a set with every capability.
+    pub const fn all() -> Self {
+        Self((1 << Capability::ALL.len()) - 1)
+    }
+
+    /// Returns the set with the capability added.
+    pub const fn with(self, capability: Capability) -> Self {
+        Self(self.0 | capability.bit())
+    }
+
+    /// Returns whether the set contains the capability.
+    pub const fn contains(self, capability: Capability) -> bool {
+        self.0 & capability.bit() != 0
+    }
+
+    /// Returns the capabilities contained in both sets.
+    pub const fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// Returns whether the set has no capabilities.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns an iterator over the capabilities in the set.
+    pub fn iter(self) -> impl Iterator<Item = Capability> {
+        Capability::ALL
+            .into_iter()
+            .filter(move |capability| self.contains(*capability))
+    }
+}
+
+impl FromIterator<Capability> for CapabilitySet {
+    fn from_iter<I: IntoIterator<Item = Capability>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::empty(), |set, capability| set.with(capability))
+    }
+}
+
+impl Serialize for CapabilitySet {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for CapabilitySet {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Vec::<Capability>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
 }
 
 /// Marker trait for models that support tool calls.
@@ -51,6 +213,7 @@ pub trait ImageOutputSupport {}
 ///
 /// Unlike statically-typed models (like `Gpt4o`, `Claude3`, etc.), this model
 /// DynamicModel bypasses compile-time capability checking and allows to set any model name as a string.
+/// It reports every capability at runtime, since the model is only known to the API.
 ///
 /// # Use Cases
 ///
@@ -69,6 +232,15 @@ impl ModelName for DynamicModel {
 ///
 /// This macro generates model struct definitions, trait implementations,
 /// and constructor methods for a provider's supported models.
+///
+/// Each model can optionally declare its per-million-token `pricing`
+/// (`input`, `output` and an optional `cached` rate, in US dollars),
+/// which is exposed through [`ModelName::PRICING`], as well as its
+/// `context_window` and `max_output_tokens` limits, exposed through
+/// [`ModelName::CONTEXT_WINDOW`] and [`ModelName::MAX_OUTPUT_TOKENS`].
+///
+/// The models are also listed in a `MODELS` constant, which feeds the
+/// [`ModelCatalog`](crate::core::catalog::ModelCatalog).
 #[macro_export]
 macro_rules! model_capabilities {
     (
@@ -80,6 +252,15 @@ macro_rules! model_capabilities {
                     constructor_name: $constructor_name:ident,
                     display_name: $display_name:literal,
                     capabilities: [$($capability:ident),* $(,)?]
+                    $(, pricing: {
+                        input: $input_price:literal,
+                        output: $output_price:literal
+                        $(, cached: $cached_price:literal)?
+                        $(,)?
+                    })?
+                    $(, context_window: $context_window:literal)?
+                    $(, max_output_tokens: $max_output_tokens:literal)?
+                    $(,)?
                 }
             ),* $(,)?
         }
@@ -104,6 +285,29 @@ macro_rules! model_capabilities {
             impl ModelName for $model {
                 /// The underlying API model name.
                 const MODEL_NAME: &'static str = $model_name;
+
+                $(
+                    /// Per-million-token pricing of the model.
+                    const PRICING: Option<$crate::core::pricing::ModelPricing> = Some(
+                        $crate::core::pricing::ModelPricing::new($input_price, $output_price)
+                            $(.with_cached($cached_price))?
+                    );
+                )?
+
+                $(
+                    /// Maximum number of tokens the model accepts in a single request.
+                    const CONTEXT_WINDOW: Option<usize> = Some($context_window);
+                )?
+
+                $(
+                    /// Maximum number of tokens the model can generate in a single response.
+                    const MAX_OUTPUT_TOKENS: Option<usize> = Some($max_output_tokens);
+                )?
+
+                /// Capabilities of the model.
+                const CAPABILITIES: $crate::core::capabilities::CapabilitySet =
+                    $crate::core::capabilities::CapabilitySet::empty()
+                        $(.with($crate::__capability!($capability)))*;
             }
 
             $(
@@ -133,6 +337,15 @@ macro_rules! model_capabilities {
             }
         )*
 
+        #[doc = concat!(
+            "Models of the [`",
+            stringify!($provider),
+            "`] provider, as listed in the [`ModelCatalog`]($crate::core::catalog::ModelCatalog)."
+        )]
+        pub const MODELS: &[$crate::core::catalog::ModelInfo] = &[
+            $($crate::core::catalog::ModelInfo::of::<$model>($display_name),)*
+        ];
+
         // Auto-generate capability implementations for Provider<DynamicModel>
         // This allows runtime model selection with API-validated capabilities
 
@@ -149,3 +362,131 @@ macro_rules! model_capabilities {
         impl AudioOutputSupport for $provider<DynamicModel> {}
     };
 }
+
+/// Implements every capability marker trait for a model wrapper, for the
+/// capabilities of the wrapped model `M`.
+///
+/// Wrappers such as [`WrappedLanguageModel`](crate::core::language_model::middleware::WrappedLanguageModel)
+/// can then be used with the request builder methods requiring a capability,
+/// exactly like the model they wrap.
+macro_rules! forward_capabilities {
+    ($wrapper:ident $(, $param:ident)?) => {
+        $crate::core::capabilities::forward_capabilities!(
+            @impl $wrapper [$($param)?]
+            ToolCallSupport
+            StructuredOutputSupport
+            ReasoningSupport
+            TextInputSupport
+            TextOutputSupport
+            ImageInputSupport
+            VideoInputSupport
+            AudioInputSupport
+            ImageOutputSupport
+            VideoOutputSupport
+            AudioOutputSupport
+        );
+    };
+    (@impl $wrapper:ident $params:tt $($capability:ident)*) => {
+        $(
+            $crate::core::capabilities::forward_capabilities!(
+                @one $wrapper $params $capability
+            );
+        )*
+    };
+    (@one $wrapper:ident [] $capability:ident) => {
+        impl<M> $crate::core::capabilities::$capability for $wrapper<M>
+        where
+            M: $crate::core::language_model::LanguageModel + $crate::core::capabilities::$capability,
+        {
+        }
+    };
+    (@one $wrapper:ident [$param:ident] $capability:ident) => {
+        impl<M, $param> $crate::core::capabilities::$capability for $wrapper<M, $param>
+        where
+            M: $crate::core::language_model::LanguageModel + $crate::core::capabilities::$capability,
+        {
+        }
+    };
+}
+
+pub(crate) use forward_capabilities;
+
+/// Maps a capability marker trait to its [`Capability`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __capability {
+    (ToolCallSupport) => {
+        $crate::core::capabilities::Capability::ToolCall
+    };
+    (ReasoningSupport) => {
+        $crate::core::capabilities::Capability::Reasoning
+    };
+    (StructuredOutputSupport) => {
+        $crate::core::capabilities::Capability::StructuredOutput
+    };
+    (TextInputSupport) => {
+        $crate::core::capabilities::Capability::TextInput
+    };
+    (VideoInputSupport) => {
+        $crate::core::capabilities::Capability::VideoInput
+    };
+    (AudioInputSupport) => {
+        $crate::core::capabilities::Capability::AudioInput
+    };
+    (ImageInputSupport) => {
+        $crate::core::capabilities::Capability::ImageInput
+    };
+    (TextOutputSupport) => {
+        $crate::core::capabilities::Capability::TextOutput
+    };
+    (VideoOutputSupport) => {
+        $crate::core::capabilities::Capability::VideoOutput
+    };
+    (AudioOutputSupport) => {
+        $crate::core::capabilities::Capability::AudioOutput
+    };
+    (ImageOutputSupport) => {
+        $crate::core::capabilities::Capability::ImageOutput
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capability_set_operations() {
+        let set: CapabilitySet = [Capability::TextInput, Capability::ToolCall]
+            .into_iter()
+            .collect();
+        assert!(set.contains(Capability::ToolCall));
+        assert!(!set.contains(Capability::Reasoning));
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![Capability::ToolCall, Capability::TextInput]
+        );
+
+        let other = CapabilitySet::empty().with(Capability::TextInput);
+        assert_eq!(set.intersection(other), other);
+        assert!(CapabilitySet::empty().is_empty());
+        assert_eq!(CapabilitySet::all().iter().count(), Capability::ALL.len());
+
+        let json = serde_json::to_value(set).unwrap();
+        assert_eq!(json, serde_json::json!(["tool_call", "text_input"]));
+        assert_eq!(serde_json::from_value::<CapabilitySet>(json).unwrap(), set);
+    }
+
+    #[cfg(feature = "openai")]
+    #[test]
+    fn test_provider_capabilities() {
+        use crate::core::LanguageModel;
+        use crate::providers::OpenAI;
+
+        let model = OpenAI::gpt_3_5_turbo();
+        assert!(model.supports(Capability::TextInput));
+        assert!(!model.supports(Capability::ToolCall));
+
+        let model = OpenAI::model_name("my-fine-tuned-model");
+        assert_eq!(model.capabilities(), CapabilitySet::all());
+    }
+}
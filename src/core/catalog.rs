@@ -0,0 +1,538 @@
+//! Catalog of the models compiled into the SDK.
+//!
+//! The [`ModelCatalog`] lists the models of every enabled provider with their
+//! display names, capabilities, pricing and limits, for building model pickers.
+//! Models are identified by `"{provider}:{model}"` strings such as `"openai:gpt-4o"`,
+//! where the provider is the name of its cargo feature.
+
+use serde::Serialize;
+
+use crate::core::capabilities::{Capability, CapabilitySet, ModelName};
+use crate::core::pricing::ModelPricing;
+
+/// A model of a provider, as declared with [`model_capabilities!`](crate::model_capabilities).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ModelInfo {
+    /// The API model name, e.g. `gpt-4o`.
+    pub name: &'static str,
+    /// The human-readable name, e.g. `GPT-4o`.
+    pub display_name: &'static str,
+    /// The capabilities of the model.
+    pub capabilities: CapabilitySet,
+    /// Per-million-token pricing of the model, if known.
+    pub pricing: Option<ModelPricing>,
+    /// Maximum number of tokens the model accepts in a single request, if known.
+    pub context_window: Option<usize>,
+    /// Maximum number of tokens the model can generate in a single response, if known.
+    pub max_output_tokens: Option<usize>,
+}
+
+impl ModelInfo {
+    /// Returns the catalog entry of a model type.
+    pub const fn of<M: ModelName>(display_name: &'static str) -> Self {
+        Self {
+            name: M::MODEL_NAME,
+            display_name,
+            capabilities: M::CAPABILITIES,
+            pricing: M::PRICING,
+            context_window: M::CONTEXT_WINDOW,
+            max_output_tokens: M::MAX_OUTPUT_TOKENS,
+        }
+    }
+}
+
+/// The models of a provider.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ProviderModels {
+    /// The ID of the provider, which is the name of its cargo feature, e.g. `openai`.
+    pub id: &'static str,
+    /// The models of the provider.
+    pub models: &'static [ModelInfo],
+}
+
+/// A model in the catalog, together with its provider.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CatalogModel {
+    /// The ID of the provider.
+    pub provider: &'static str,
+    /// The model.
+    #[serde(flatten)]
+    pub model: &'static ModelInfo,
+}
+
+impl CatalogModel {
+    /// Returns the ID of the model, e.g. `openai:gpt-4o`.
+    pub fn id(&self) -> String {
+        format!("{}:{}", self.provider, self.model.name)
+    }
+}
+
+/// Catalog of the models of the enabled providers.
+///
+/// # Example
+///
+/// ```rust
+/// use aisdk::core::capabilities::Capability;
+/// use aisdk::core::catalog::ModelCatalog;
+///
+/// // Models for a picker that only offers models with tool calls
+/// let models: Vec<_> = ModelCatalog::models()
+///     .filter(|model| model.model.capabilities.contains(Capability::ToolCall))
+///     .map(|model| (model.id(), model.model.display_name))
+///     .collect();
+///
+/// if let Some(model) = ModelCatalog::get("openai:gpt-4o") {
+///     println!("{} supports {:?}", model.model.display_name, model.model.capabilities);
+/// }
+/// ```
+pub struct ModelCatalog;
+
+impl ModelCatalog {
+    /// Returns the enabled providers and their models.
+    pub fn providers() -> &'static [ProviderModels] {
+        PROVIDERS
+    }
+
+    /// Returns the provider with the given ID, if it is enabled.
+    pub fn provider(id: &str) -> Option<&'static ProviderModels> {
+        PROVIDERS.iter().find(|provider| provider.id == id)
+    }
+
+    /// Returns all models of the enabled providers.
+    pub fn models() -> impl Iterator<Item = CatalogModel> {
+        PROVIDERS.iter().flat_map(|provider| {
+            provider.models.iter().map(|model| CatalogModel {
+                provider: provider.id,
+                model,
+            })
+        })
+    }
+
+    /// Returns the models that have the capability.
+    pub fn with_capability(capability: Capability) -> impl Iterator<Item = CatalogModel> {
+        Self::models().filter(move |model| model.model.capabilities.contains(capability))
+    }
+
+    /// Looks up a model by its `"{provider}:{model}"` ID.
+    ///
+    /// Returns `None` if the ID is malformed, the provider is not enabled, or the
+    /// provider has no such model.
+    pub fn get(id: &str) -> Option<CatalogModel> {
+        let (provider, name) = id.split_once(':')?;
+        let provider = Self::provider(provider)?;
+        provider
+            .models
+            .iter()
+            .find(|model| model.name == name)
+            .map(|model| CatalogModel {
+                provider: provider.id,
+                model,
+            })
+    }
+}
+
+const PROVIDERS: &[ProviderModels] = &[
+    #[cfg(feature = "openai")]
+    ProviderModels {
+        id: "openai",
+        models: crate::providers::openai::capabilities::MODELS,
+    },
+    #[cfg(feature = "anthropic")]
+    ProviderModels {
+        id: "anthropic",
+        models: crate::providers::anthropic::capabilities::MODELS,
+    },
+    #[cfg(feature = "groq")]
+    ProviderModels {
+        id: "groq",
+        models: crate::providers::groq::capabilities::MODELS,
+    },
+    #[cfg(feature = "google")]
+    ProviderModels {
+        id: "google",
+        models: crate::providers::google::capabilities::MODELS,
+    },
+    #[cfg(feature = "vertex-ai")]
+    ProviderModels {
+        id: "vertex-ai",
+        models: crate::providers::vertex_ai::capabilities::MODELS,
+    },
+    #[cfg(feature = "vercel")]
+    ProviderModels {
+        id: "vercel",
+        models: crate::providers::vercel::capabilities::MODELS,
+    },
+    #[cfg(feature = "openrouter")]
+    ProviderModels {
+        id: "openrouter",
+        models: crate::providers::openrouter::capabilities::MODELS,
+    },
+    #[cfg(feature = "mistral")]
+    ProviderModels {
+        id: "mistral",
+        models: crate::providers::mistral::capabilities::MODELS,
+    },
+    #[cfg(feature = "amazon-bedrock")]
+    ProviderModels {
+        id: "amazon-bedrock",
+        models: crate::providers::amazon_bedrock::capabilities::MODELS,
+    },
+    #[cfg(feature = "togetherai")]
+    ProviderModels {
+        id: "togetherai",
+        models: crate::providers::togetherai::capabilities::MODELS,
+    },
+    #[cfg(feature = "xai")]
+    ProviderModels {
+        id: "xai",
+        models: crate::providers::xai::capabilities::MODELS,
+    },
+    #[cfg(feature = "ollama")]
+    ProviderModels {
+        id: "ollama",
+        models: crate::providers::ollama::capabilities::MODELS,
+    },
+    // [codegen]
+    #[cfg(feature = "302ai")]
+    ProviderModels {
+        id: "302ai",
+        models: crate::providers::ai_302::capabilities::MODELS,
+    },
+    #[cfg(feature = "abacus")]
+    ProviderModels {
+        id: "abacus",
+        models: crate::providers::abacus::capabilities::MODELS,
+    },
+    #[cfg(feature = "aihubmix")]
+    ProviderModels {
+        id: "aihubmix",
+        models: crate::providers::aihubmix::capabilities::MODELS,
+    },
+    #[cfg(feature = "alibaba")]
+    ProviderModels {
+        id: "alibaba",
+        models: crate::providers::alibaba::capabilities::MODELS,
+    },
+    #[cfg(feature = "alibaba-cn")]
+    ProviderModels {
+        id: "alibaba-cn",
+        models: crate::providers::alibaba_cn::capabilities::MODELS,
+    },
+    #[cfg(feature = "bailing")]
+    ProviderModels {
+        id: "bailing",
+        models: crate::providers::bailing::capabilities::MODELS,
+    },
+    #[cfg(feature = "baseten")]
+    ProviderModels {
+        id: "baseten",
+        models: crate::providers::baseten::capabilities::MODELS,
+    },
+    #[cfg(feature = "berget")]
+    ProviderModels {
+        id: "berget",
+        models: crate::providers::berget::capabilities::MODELS,
+    },
+    #[cfg(feature = "chutes")]
+    ProviderModels {
+        id: "chutes",
+        models: crate::providers::chutes::capabilities::MODELS,
+    },
+    #[cfg(feature = "cloudflare-ai-gateway")]
+    ProviderModels {
+        id: "cloudflare-ai-gateway",
+        models: crate::providers::cloudflare_ai_gateway::capabilities::MODELS,
+    },
+    #[cfg(feature = "cloudflare-workers-ai")]
+    ProviderModels {
+        id: "cloudflare-workers-ai",
+        models: crate::providers::cloudflare_workers_ai::capabilities::MODELS,
+    },
+    #[cfg(feature = "cortecs")]
+    ProviderModels {
+        id: "cortecs",
+        models: crate::providers::cortecs::capabilities::MODELS,
+    },
+    #[cfg(feature = "deepseek")]
+    ProviderModels {
+        id: "deepseek",
+        models: crate::providers::deepseek::capabilities::MODELS,
+    },
+    #[cfg(feature = "fastrouter")]
+    ProviderModels {
+        id: "fastrouter",
+        models: crate::providers::fastrouter::capabilities::MODELS,
+    },
+    #[cfg(feature = "fireworks-ai")]
+    ProviderModels {
+        id: "fireworks-ai",
+        models: crate::providers::fireworks_ai::capabilities::MODELS,
+    },
+    #[cfg(feature = "firmware")]
+    ProviderModels {
+        id: "firmware",
+        models: crate::providers::firmware::capabilities::MODELS,
+    },
+    #[cfg(feature = "friendli")]
+    ProviderModels {
+        id: "friendli",
+        models: crate::providers::friendli::capabilities::MODELS,
+    },
+    #[cfg(feature = "github-copilot")]
+    ProviderModels {
+        id: "github-copilot",
+        models: crate::providers::github_copilot::capabilities::MODELS,
+    },
+    #[cfg(feature = "github-models")]
+    ProviderModels {
+        id: "github-models",
+        models: crate::providers::github_models::capabilities::MODELS,
+    },
+    #[cfg(feature = "helicone")]
+    ProviderModels {
+        id: "helicone",
+        models: crate::providers::helicone::capabilities::MODELS,
+    },
+    #[cfg(feature = "huggingface")]
+    ProviderModels {
+        id: "huggingface",
+        models: crate::providers::huggingface::capabilities::MODELS,
+    },
+    #[cfg(feature = "iflowcn")]
+    ProviderModels {
+        id: "iflowcn",
+        models: crate::providers::iflowcn::capabilities::MODELS,
+    },
+    #[cfg(feature = "inception")]
+    ProviderModels {
+        id: "inception",
+        models: crate::providers::inception::capabilities::MODELS,
+    },
+    #[cfg(feature = "inference")]
+    ProviderModels {
+        id: "inference",
+        models: crate::providers::inference::capabilities::MODELS,
+    },
+    #[cfg(feature = "io-net")]
+    ProviderModels {
+        id: "io-net",
+        models: crate::providers::io_net::capabilities::MODELS,
+    },
+    #[cfg(feature = "jiekou")]
+    ProviderModels {
+        id: "jiekou",
+        models: crate::providers::jiekou::capabilities::MODELS,
+    },
+    #[cfg(feature = "kuae-cloud-coding-plan")]
+    ProviderModels {
+        id: "kuae-cloud-coding-plan",
+        models: crate::providers::kuae_cloud_coding_plan::capabilities::MODELS,
+    },
+    #[cfg(feature = "llama")]
+    ProviderModels {
+        id: "llama",
+        models: crate::providers::llama::capabilities::MODELS,
+    },
+    #[cfg(feature = "lmstudio")]
+    ProviderModels {
+        id: "lmstudio",
+        models: crate::providers::lmstudio::capabilities::MODELS,
+    },
+    #[cfg(feature = "lucidquery")]
+    ProviderModels {
+        id: "lucidquery",
+        models: crate::providers::lucidquery::capabilities::MODELS,
+    },
+    #[cfg(feature = "moark")]
+    ProviderModels {
+        id: "moark",
+        models: crate::providers::moark::capabilities::MODELS,
+    },
+    #[cfg(feature = "modelscope")]
+    ProviderModels {
+        id: "modelscope",
+        models: crate::providers::modelscope::capabilities::MODELS,
+    },
+    #[cfg(feature = "moonshotai")]
+    ProviderModels {
+        id: "moonshotai",
+        models: crate::providers::moonshotai::capabilities::MODELS,
+    },
+    #[cfg(feature = "moonshotai-cn")]
+    ProviderModels {
+        id: "moonshotai-cn",
+        models: crate::providers::moonshotai_cn::capabilities::MODELS,
+    },
+    #[cfg(feature = "morph")]
+    ProviderModels {
+        id: "morph",
+        models: crate::providers::morph::capabilities::MODELS,
+    },
+    #[cfg(feature = "nano-gpt")]
+    ProviderModels {
+        id: "nano-gpt",
+        models: crate::providers::nano_gpt::capabilities::MODELS,
+    },
+    #[cfg(feature = "nebius")]
+    ProviderModels {
+        id: "nebius",
+        models: crate::providers::nebius::capabilities::MODELS,
+    },
+    #[cfg(feature = "nova")]
+    ProviderModels {
+        id: "nova",
+        models: crate::providers::nova::capabilities::MODELS,
+    },
+    #[cfg(feature = "novita-ai")]
+    ProviderModels {
+        id: "novita-ai",
+        models: crate::providers::novita_ai::capabilities::MODELS,
+    },
+    #[cfg(feature = "nvidia")]
+    ProviderModels {
+        id: "nvidia",
+        models: crate::providers::nvidia::capabilities::MODELS,
+    },
+    #[cfg(feature = "ollama-cloud")]
+    ProviderModels {
+        id: "ollama-cloud",
+        models: crate::providers::ollama_cloud::capabilities::MODELS,
+    },
+    #[cfg(feature = "opencode")]
+    ProviderModels {
+        id: "opencode",
+        models: crate::providers::opencode::capabilities::MODELS,
+    },
+    #[cfg(feature = "ovhcloud")]
+    ProviderModels {
+        id: "ovhcloud",
+        models: crate::providers::ovhcloud::capabilities::MODELS,
+    },
+    #[cfg(feature = "poe")]
+    ProviderModels {
+        id: "poe",
+        models: crate::providers::poe::capabilities::MODELS,
+    },
+    #[cfg(feature = "requesty")]
+    ProviderModels {
+        id: "requesty",
+        models: crate::providers::requesty::capabilities::MODELS,
+    },
+    #[cfg(feature = "scaleway")]
+    ProviderModels {
+        id: "scaleway",
+        models: crate::providers::scaleway::capabilities::MODELS,
+    },
+    #[cfg(feature = "siliconflow")]
+    ProviderModels {
+        id: "siliconflow",
+        models: crate::providers::siliconflow::capabilities::MODELS,
+    },
+    #[cfg(feature = "siliconflow-cn")]
+    ProviderModels {
+        id: "siliconflow-cn",
+        models: crate::providers::siliconflow_cn::capabilities::MODELS,
+    },
+    #[cfg(feature = "stackit")]
+    ProviderModels {
+        id: "stackit",
+        models: crate::providers::stackit::capabilities::MODELS,
+    },
+    #[cfg(feature = "stepfun")]
+    ProviderModels {
+        id: "stepfun",
+        models: crate::providers::stepfun::capabilities::MODELS,
+    },
+    #[cfg(feature = "submodel")]
+    ProviderModels {
+        id: "submodel",
+        models: crate::providers::submodel::capabilities::MODELS,
+    },
+    #[cfg(feature = "synthetic")]
+    ProviderModels {
+        id: "synthetic",
+        models: crate::providers::synthetic::capabilities::MODELS,
+    },
+    #[cfg(feature = "upstage")]
+    ProviderModels {
+        id: "upstage",
+        models: crate::providers::upstage::capabilities::MODELS,
+    },
+    #[cfg(feature = "vultr")]
+    ProviderModels {
+        id: "vultr",
+        models: crate::providers::vultr::capabilities::MODELS,
+    },
+    #[cfg(feature = "wandb")]
+    ProviderModels {
+        id: "wandb",
+        models: crate::providers::wandb::capabilities::MODELS,
+    },
+    #[cfg(feature = "xiaomi")]
+    ProviderModels {
+        id: "xiaomi",
+        models: crate::providers::xiaomi::capabilities::MODELS,
+    },
+    #[cfg(feature = "zai")]
+    ProviderModels {
+        id: "zai",
+        models: crate::providers::zai::capabilities::MODELS,
+    },
+    #[cfg(feature = "zai-coding-plan")]
+    ProviderModels {
+        id: "zai-coding-plan",
+        models: crate::providers::zai_coding_plan::capabilities::MODELS,
+    },
+    #[cfg(feature = "zenmux")]
+    ProviderModels {
+        id: "zenmux",
+        models: crate::providers::zenmux::capabilities::MODELS,
+    },
+    #[cfg(feature = "zhipuai")]
+    ProviderModels {
+        id: "zhipuai",
+        models: crate::providers::zhipuai::capabilities::MODELS,
+    },
+    #[cfg(feature = "zhipuai-coding-plan")]
+    ProviderModels {
+        id: "zhipuai-coding-plan",
+        models: crate::providers::zhipuai_coding_plan::capabilities::MODELS,
+    },
+    // [end-codegen]
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_rejects_malformed_and_unknown_ids() {
+        assert!(ModelCatalog::get("gpt-4o").is_none());
+        assert!(ModelCatalog::get("unknown-provider:gpt-4o").is_none());
+    }
+
+    #[cfg(feature = "openai")]
+    #[test]
+    fn test_catalog_lists_openai_models() {
+        let model = ModelCatalog::get("openai:gpt-4o").unwrap();
+        assert_eq!(model.id(), "openai:gpt-4o");
+        assert_eq!(model.model.display_name, "GPT-4o");
+        assert!(model.model.capabilities.contains(Capability::ToolCall));
+
+        assert!(ModelCatalog::get("openai:not-a-model").is_none());
+        assert!(
+            ModelCatalog::with_capability(Capability::Reasoning)
+                .all(|model| model.model.capabilities.contains(Capability::Reasoning))
+        );
+
+        let json = serde_json::to_value(model).unwrap();
+        assert_eq!(json["provider"], "openai");
+        assert_eq!(json["name"], "gpt-4o");
+        assert!(
+            json["capabilities"]
+                .as_array()
+                .unwrap()
+                .contains(&"tool_call".into())
+        );
+    }
+}
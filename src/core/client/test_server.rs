@@ -0,0 +1,105 @@
+//! A local HTTP server for tests of provider clients.
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{UnboundedReceiver, unbounded_channel};
+
+/// A response served by [`serve`].
+pub(crate) enum TestResponse {
+    /// A JSON body, in which `{address}` is replaced by the server address.
+    Json(String),
+    /// A body with the given content type.
+    Body(String, Vec<u8>),
+    /// A JSON body with an error status code.
+    Error(u16, String),
+}
+
+impl TestResponse {
+    /// A JSON body, in which `{address}` is replaced by the server address.
+    pub(crate) fn json(body: impl Into<String>) -> Self {
+        Self::Json(body.into())
+    }
+
+    fn into_bytes(self, address: &str) -> Vec<u8> {
+        match self {
+            Self::Json(body) => Self::Body(
+                "application/json".to_string(),
+                body.replace("{address}", address).into_bytes(),
+            )
+            .into_bytes(address),
+            Self::Body(content_type, body) => http_response("200 OK", &content_type, body),
+            Self::Error(status, body) => http_response(
+                &format!("{status} Error"),
+                "application/json",
+                body.into_bytes(),
+            ),
+        }
+    }
+}
+
+fn http_response(status: &str, content_type: &str, body: Vec<u8>) -> Vec<u8> {
+    let mut response = format!(
+        "HTTP/1.1 {status}\r\ncontent-type: {content_type}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+        body.len()
+    )
+    .into_bytes();
+    response.extend(body);
+    response
+}
+
+/// Serves the given responses on a local port, one per connection, and returns
+/// its address along with the received requests.
+///
+/// Each request is read in full, including a body announced by its
+/// `content-length` or sent with chunked transfer encoding.
+pub(crate) async fn serve(responses: Vec<TestResponse>) -> (String, UnboundedReceiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = unbounded_channel();
+    let base_url = address.clone();
+    tokio::spawn(async move {
+        for response in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let request = read_request(&mut socket).await;
+            let _ = sender.send(String::from_utf8_lossy(&request).into_owned());
+            socket
+                .write_all(&response.into_bytes(&base_url))
+                .await
+                .unwrap();
+        }
+    });
+    (address, receiver)
+}
+
+/// Reads the headers of a request and the body announced by them.
+async fn read_request(socket: &mut TcpStream) -> Vec<u8> {
+    let mut request = Vec::new();
+    let mut buffer = [0; 8192];
+    loop {
+        let read = socket.read(&mut buffer).await.unwrap();
+        request.extend_from_slice(&buffer[..read]);
+        if read == 0 || is_complete(&request) {
+            return request;
+        }
+    }
+}
+
+fn is_complete(request: &[u8]) -> bool {
+    let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") else {
+        return false;
+    };
+    let head = String::from_utf8_lossy(&request[..end]).to_ascii_lowercase();
+    let body = &request[end + 4..];
+    let header = |name: &str| {
+        head.lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+            .map(str::trim)
+    };
+    if header("transfer-encoding") == Some("chunked") {
+        return body.ends_with(b"0\r\n\r\n");
+    }
+    let length = header("content-length")
+        .and_then(|length| length.parse::<usize>().ok())
+        .unwrap_or(0);
+    body.len() >= length
+}
@@ -0,0 +1,597 @@
+//! Record and replay of provider HTTP traffic.
+//!
+//! A [`Cassette`] records the responses of real provider requests, including
+//! server-sent event streams, to a JSON fixture file and replays them later without
+//! network access. This allows integration tests of provider conversion code to run
+//! in CI without API keys.
+//!
+//! Requests made inside [`Cassette::run`] go through the cassette. Interactions are
+//! replayed in the order they were recorded, and each replayed request must have
+//! the same method and path as the recorded one. Request headers are never
+//! recorded, so API keys do not end up in fixture files.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::LanguageModelRequest;
+//! use aisdk::core::client::vcr::Cassette;
+//! use aisdk::providers::OpenAI;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> aisdk::Result<()> {
+//! // Replays the fixture if it exists, otherwise records it.
+//! let cassette = Cassette::auto("tests/fixtures/openai_hello.json")?;
+//!
+//! let response = cassette
+//!     .run(async {
+//!         LanguageModelRequest::builder()
+//!             .model(OpenAI::gpt_5())
+//!             .prompt("Say hello")
+//!             .build()
+//!             .generate_text()
+//!             .await
+//!     })
+//!     .await?;
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+use crate::error::{Error, Result};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Environment variable that forces [`Cassette::auto`] to record when set to `record`.
+pub const VCR_MODE_ENV: &str = "AISDK_VCR";
+
+tokio::task_local! {
+    static CASSETTE: Cassette;
+}
+
+/// Returns the cassette of the current task, if any.
+pub(crate) fn current() -> Option<Cassette> {
+    CASSETTE.try_with(|cassette| cassette.clone()).ok()
+}
+
+/// Whether a cassette records or replays traffic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VcrMode {
+    /// Sends real requests and records their responses.
+    Record,
+    /// Answers requests with previously recorded responses.
+    Replay,
+}
+
+/// A recorded request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    #[serde(default)]
+    pub body: serde_json::Value,
+}
+
+/// A recorded response of a regular request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RecordedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+/// A recorded event of a server-sent event stream.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum RecordedEvent {
+    /// The stream was opened.
+    Open,
+    /// A message was received.
+    Message { event: String, data: String },
+    /// The stream failed, with the status and headers of the response if it had one.
+    Error {
+        status: Option<u16>,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+        details: String,
+    },
+}
+
+/// A request and its recorded response or stream events.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Interaction {
+    pub request: RecordedRequest,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<RecordedResponse>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub events: Option<Vec<RecordedEvent>>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug)]
+struct CassetteState {
+    /// Recorded interactions, or the interactions left to replay.
+    interactions: VecDeque<Interaction>,
+}
+
+/// A fixture file of recorded provider traffic.
+///
+/// Clones share the same recording.
+#[derive(Debug, Clone)]
+pub struct Cassette {
+    path: Option<PathBuf>,
+    mode: VcrMode,
+    state: Arc<Mutex<CassetteState>>,
+}
+
+impl Cassette {
+    fn new(path: Option<PathBuf>, mode: VcrMode, interactions: Vec<Interaction>) -> Self {
+        Self {
+            path,
+            mode,
+            state: Arc::new(Mutex::new(CassetteState {
+                interactions: interactions.into(),
+            })),
+        }
+    }
+
+    /// Creates a cassette that records traffic to `path`.
+    ///
+    /// The file is written when [`run`](Self::run) completes or when
+    /// [`save`](Self::save) is called.
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self::new(Some(path.into()), VcrMode::Record, Vec::new())
+    }
+
+    /// Creates a cassette that replays the traffic recorded in `path`.
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self> {
+        let json = std::fs::read_to_string(path.as_ref()).map_err(|e| {
+            Error::Other(format!(
+                "Failed to read cassette {}: {e}",
+                path.as_ref().display()
+            ))
+        })?;
+        let mut cassette = Self::from_json(&json)?;
+        cassette.path = Some(path.as_ref().to_path_buf());
+        Ok(cassette)
+    }
+
+    /// Creates a cassette that replays `path` if it exists and records it otherwise.
+    ///
+    /// Setting the `AISDK_VCR` environment variable to `record` always records,
+    /// which refreshes existing fixtures.
+    pub fn auto(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let force_record = std::env::var(VCR_MODE_ENV).is_ok_and(|mode| mode == "record");
+        if path.exists() && !force_record {
+            Self::replay(path)
+        } else {
+            Ok(Self::record(path))
+        }
+    }
+
+    /// Creates a replaying cassette from the JSON contents of a fixture file.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let file: CassetteFile = serde_json::from_str(json)
+            .map_err(|e| Error::InvalidInput(format!("Invalid cassette: {e}")))?;
+        Ok(Self::new(None, VcrMode::Replay, file.interactions))
+    }
+
+    /// Returns the recorded interactions as JSON.
+    pub fn to_json(&self) -> Result<String> {
+        let file = CassetteFile {
+            interactions: self.state.lock().interactions.iter().cloned().collect(),
+        };
+        serde_json::to_string_pretty(&file)
+            .map_err(|e| Error::Other(format!("Failed to serialize cassette: {e}")))
+    }
+
+    /// Returns whether the cassette records or replays traffic.
+    pub fn mode(&self) -> VcrMode {
+        self.mode
+    }
+
+    /// Writes the recorded interactions to the fixture file.
+    ///
+    /// Does nothing when replaying or when the cassette has no file.
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = self.path.as_ref().filter(|_| self.mode == VcrMode::Record) else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| Error::Other(format!("Failed to create {}: {e}", parent.display())))?;
+        }
+        std::fs::write(path, self.to_json()?)
+            .map_err(|e| Error::Other(format!("Failed to write {}: {e}", path.display())))
+    }
+
+    /// Runs `future` with all provider requests going through this cassette.
+    ///
+    /// When recording, the fixture file is written once the future completes.
+    pub async fn run<F: Future>(&self, future: F) -> F::Output {
+        let output = CASSETTE.scope(self.clone(), future).await;
+        if let Err(e) = self.save() {
+            log::error!("{e}");
+        }
+        output
+    }
+
+    pub(crate) fn is_replaying(&self) -> bool {
+        self.mode == VcrMode::Replay
+    }
+
+    /// Takes the next interaction, checking that it was recorded for the same request.
+    fn next(&self, request: &RecordedRequest) -> Result<Interaction> {
+        let interaction = self.state.lock().interactions.pop_front().ok_or_else(|| {
+            Error::Other(format!(
+                "Cassette has no recorded interaction left for {} {}",
+                request.method, request.path
+            ))
+        })?;
+
+        if interaction.request.method != request.method || interaction.request.path != request.path
+        {
+            return Err(Error::Other(format!(
+                "Request {} {} does not match the recorded request {} {}",
+                request.method, request.path, interaction.request.method, interaction.request.path
+            )));
+        }
+        Ok(interaction)
+    }
+
+    /// Replays the response of a regular request.
+    pub(crate) fn next_response(&self, request: &RecordedRequest) -> Result<RecordedResponse> {
+        self.next(request)?.response.ok_or_else(|| {
+            Error::Other(format!(
+                "Recorded interaction for {} {} is a stream",
+                request.method, request.path
+            ))
+        })
+    }
+
+    /// Replays the events of a stream.
+    pub(crate) fn next_events(&self, request: &RecordedRequest) -> Result<Vec<RecordedEvent>> {
+        self.next(request)?.events.ok_or_else(|| {
+            Error::Other(format!(
+                "Recorded interaction for {} {} is not a stream",
+                request.method, request.path
+            ))
+        })
+    }
+
+    /// Records the response of a regular request.
+    pub(crate) fn record_response(&self, request: RecordedRequest, response: RecordedResponse) {
+        self.state.lock().interactions.push_back(Interaction {
+            request,
+            response: Some(response),
+            events: None,
+        });
+    }
+
+    /// Starts recording a stream and returns a handle to record its events with.
+    pub(crate) fn record_stream(&self, request: RecordedRequest) -> StreamRecorder {
+        let mut state = self.state.lock();
+        state.interactions.push_back(Interaction {
+            request,
+            response: None,
+            events: Some(Vec::new()),
+        });
+        StreamRecorder {
+            index: state.interactions.len() - 1,
+            state: self.state.clone(),
+        }
+    }
+}
+
+/// Records the events of a stream as they are received.
+#[derive(Debug, Clone)]
+pub(crate) struct StreamRecorder {
+    index: usize,
+    state: Arc<Mutex<CassetteState>>,
+}
+
+impl StreamRecorder {
+    pub(crate) fn push(&self, event: RecordedEvent) {
+        let mut state = self.state.lock();
+        if let Some(events) = state
+            .interactions
+            .get_mut(self.index)
+            .and_then(|i| i.events.as_mut())
+        {
+            events.push(event);
+        }
+    }
+}
+
+impl RecordedRequest {
+    pub(crate) fn new(method: &reqwest::Method, url: &reqwest::Url, body: &reqwest::Body) -> Self {
+        let body = body
+            .as_bytes()
+            .map(|bytes| {
+                serde_json::from_slice(bytes).unwrap_or_else(|_| {
+                    serde_json::Value::String(String::from_utf8_lossy(bytes).into_owned())
+                })
+            })
+            .unwrap_or_default();
+        Self {
+            method: method.to_string(),
+            path: url.path().to_string(),
+            body,
+        }
+    }
+}
+
+/// Converts response headers for recording. Cookies are left out.
+pub(crate) fn record_headers(headers: &reqwest::header::HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .filter(|(name, _)| *name != reqwest::header::SET_COOKIE)
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+/// Converts recorded headers back into a header map.
+pub(crate) fn replay_headers(headers: &BTreeMap<String, String>) -> reqwest::header::HeaderMap {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.parse().ok()?, value.parse().ok()?)))
+        .collect()
+}
+
+impl From<&std::result::Result<reqwest_eventsource::Event, reqwest_eventsource::Error>>
+    for RecordedEvent
+{
+    fn from(
+        event: &std::result::Result<reqwest_eventsource::Event, reqwest_eventsource::Error>,
+    ) -> Self {
+        match event {
+            Ok(reqwest_eventsource::Event::Open) => RecordedEvent::Open,
+            Ok(reqwest_eventsource::Event::Message(message)) => RecordedEvent::Message {
+                event: message.event.clone(),
+                data: message.data.clone(),
+            },
+            Err(error @ reqwest_eventsource::Error::InvalidStatusCode(status, response)) => {
+                RecordedEvent::Error {
+                    status: Some(status.as_u16()),
+                    headers: record_headers(response.headers()),
+                    details: error.to_string(),
+                }
+            }
+            Err(error) => RecordedEvent::Error {
+                status: None,
+                headers: BTreeMap::new(),
+                details: error.to_string(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{LanguageModelClient, retry_request, shared_client, sse_error};
+    use super::*;
+    use crate::core::client::test_server::{TestResponse, serve};
+    use futures::StreamExt;
+    use reqwest_eventsource::Event;
+
+    /// A client whose stream events are the raw event data.
+    struct TestClient;
+
+    impl LanguageModelClient for TestClient {
+        type Response = serde_json::Value;
+        type StreamEvent = String;
+
+        fn path(&self) -> String {
+            "/v1/test".to_string()
+        }
+
+        fn method(&self) -> reqwest::Method {
+            reqwest::Method::POST
+        }
+
+        fn query_params(&self) -> Vec<(&str, &str)> {
+            Vec::new()
+        }
+
+        fn body(&self) -> reqwest::Body {
+            reqwest::Body::from(r#"{"prompt":"hi"}"#)
+        }
+
+        fn headers(&self) -> reqwest::header::HeaderMap {
+            reqwest::header::HeaderMap::new()
+        }
+
+        fn parse_stream_sse(
+            event: std::result::Result<Event, reqwest_eventsource::Error>,
+        ) -> Result<Self::StreamEvent> {
+            match event {
+                Ok(Event::Open) => Ok("open".to_string()),
+                Ok(Event::Message(message)) => Ok(message.data),
+                Err(e) => Err(sse_error(e)),
+            }
+        }
+
+        fn end_stream(event: &Self::StreamEvent) -> bool {
+            event == "[DONE]"
+        }
+    }
+
+    fn request(path: &str) -> RecordedRequest {
+        RecordedRequest {
+            method: "POST".to_string(),
+            path: path.to_string(),
+            body: serde_json::json!({ "prompt": "hi" }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cassette_records_and_replays_response() {
+        let (base_url, _requests) = serve(vec![TestResponse::json(r#"{"ok":true}"#)]).await;
+
+        let recorder = Cassette::new(None, VcrMode::Record, Vec::new());
+        let recorded: serde_json::Value = recorder
+            .run(TestClient.send(base_url.as_str(), None, None, Some(0)))
+            .await
+            .unwrap();
+        assert_eq!(recorded, serde_json::json!({ "ok": true }));
+
+        // The server is gone, so the response can only come from the cassette
+        let player = Cassette::from_json(&recorder.to_json().unwrap()).unwrap();
+        let replayed: serde_json::Value = player
+            .run(TestClient.send(base_url.as_str(), None, None, Some(0)))
+            .await
+            .unwrap();
+        assert_eq!(replayed, recorded);
+    }
+
+    #[tokio::test]
+    async fn test_cassette_replays_error_response() {
+        let cassette = Cassette::new(
+            None,
+            VcrMode::Replay,
+            vec![Interaction {
+                request: request("/v1/test"),
+                response: Some(RecordedResponse {
+                    status: 429,
+                    headers: BTreeMap::from([("retry-after".to_string(), "3".to_string())]),
+                    body: "slow down".to_string(),
+                }),
+                events: None,
+            }],
+        );
+
+        let result: Result<serde_json::Value> = cassette
+            .run(retry_request(
+                shared_client(),
+                "http://localhost/v1/test".parse().unwrap(),
+                reqwest::Method::POST,
+                reqwest::header::HeaderMap::new(),
+                Vec::new(),
+                || reqwest::Body::from("{}"),
+                Default::default(),
+            ))
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(Error::RateLimited { retry_after: Some(d), .. }) if d.as_secs() == 3
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cassette_records_stream_error_body() {
+        let body = r#"{"error":{"message":"Invalid prompt","type":"invalid_request_error","code":"content_policy_violation"}}"#;
+        let (base_url, _requests) = serve(vec![TestResponse::Error(400, body.to_string())]).await;
+
+        // The error is classified by the code in its body, not only by its status
+        let base_url = &base_url;
+        let first_error = |cassette: Cassette| async move {
+            cassette
+                .run(async {
+                    TestClient
+                        .send_and_stream(base_url.as_str(), None, None)
+                        .await
+                        .unwrap()
+                        .next()
+                        .await
+                })
+                .await
+        };
+        let recorder = Cassette::new(None, VcrMode::Record, Vec::new());
+        let recorded = first_error(recorder.clone()).await;
+        assert!(matches!(recorded, Some(Err(Error::ContentFiltered { .. }))));
+
+        let player = Cassette::from_json(&recorder.to_json().unwrap()).unwrap();
+        assert_eq!(first_error(player).await, recorded);
+    }
+
+    #[tokio::test]
+    async fn test_cassette_replays_stream() {
+        let cassette = Cassette::new(
+            None,
+            VcrMode::Replay,
+            vec![Interaction {
+                request: request("/v1/test"),
+                response: None,
+                events: Some(vec![
+                    RecordedEvent::Open,
+                    RecordedEvent::Message {
+                        event: "message".to_string(),
+                        data: "Hello".to_string(),
+                    },
+                    RecordedEvent::Message {
+                        event: "message".to_string(),
+                        data: "[DONE]".to_string(),
+                    },
+                    RecordedEvent::Message {
+                        event: "message".to_string(),
+                        data: "ignored after the end".to_string(),
+                    },
+                ]),
+            }],
+        );
+
+        let events: Vec<String> = cassette
+            .run(async {
+                TestClient
+                    .send_and_stream("http://localhost", None, None)
+                    .await
+                    .unwrap()
+                    .map(|e| e.unwrap())
+                    .collect()
+                    .await
+            })
+            .await;
+
+        assert_eq!(events, vec!["open", "Hello", "[DONE]"]);
+    }
+
+    #[tokio::test]
+    async fn test_cassette_rejects_mismatched_request() {
+        let cassette = Cassette::new(
+            None,
+            VcrMode::Replay,
+            vec![Interaction {
+                request: request("/v1/other"),
+                response: None,
+                events: Some(Vec::new()),
+            }],
+        );
+
+        let result = cassette
+            .run(TestClient.send_and_stream("http://localhost", None, None))
+            .await;
+
+        assert!(matches!(result, Err(Error::Other(_))));
+    }
+
+    #[test]
+    fn test_cassette_json_round_trip() {
+        let interaction = Interaction {
+            request: request("/v1/test"),
+            response: None,
+            events: Some(vec![RecordedEvent::Error {
+                status: Some(500),
+                headers: BTreeMap::new(),
+                details: "Internal error".to_string(),
+            }]),
+        };
+        let cassette = Cassette::new(None, VcrMode::Record, vec![interaction.clone()]);
+
+        let replay = Cassette::from_json(&cassette.to_json().unwrap()).unwrap();
+
+        assert_eq!(replay.mode(), VcrMode::Replay);
+        assert_eq!(replay.next_events(&interaction.request).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,224 @@
+//! Credentials of the providers, resolved before each request.
+//!
+//! A [`CredentialProvider`] returns the API key sent with the requests of a
+//! provider. Keys can be static, read from an environment variable, or fetched by
+//! an async function, such as a call to a secret manager or a token exchange, so
+//! they can be rotated without rebuilding the provider.
+//!
+//! The built-in providers are:
+//!
+//! - [`StaticCredentials`], always returning the same key.
+//! - [`EnvCredentials`], reading the key from an environment variable at each request.
+//! - [`FnCredentials`], calling an async function, created with [`from_fn`].
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::{DynamicModel, credentials};
+//! use aisdk::providers::OpenAI;
+//!
+//! # async fn read_secret(name: &str) -> aisdk::Result<String> { unimplemented!() }
+//! # fn run() -> aisdk::Result<()> {
+//! let openai = OpenAI::<DynamicModel>::builder()
+//!     .model_name("gpt-4o")
+//!     .credential_provider(credentials::from_fn(|| read_secret("openai")))
+//!     .build()?;
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use std::fmt::Debug;
+use std::future::Future;
+use std::sync::Arc;
+
+/// A source of the API key sent with the requests of a provider.
+///
+/// The key is resolved before each generation or embedding request, so
+/// implementations can refresh or rotate it.
+#[async_trait]
+pub trait CredentialProvider: Send + Sync + Debug {
+    /// Returns the API key of the next request.
+    async fn api_key(&self) -> Result<String>;
+
+    /// Returns the base URL the last returned API key was issued for, if the
+    /// credentials determine it.
+    ///
+    /// OpenAI-compatible providers send their requests to it instead of their own
+    /// base URL. Defaults to `None`.
+    fn base_url(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Credentials returning the same API key for every request.
+#[derive(Clone, PartialEq, Eq)]
+pub struct StaticCredentials(String);
+
+impl StaticCredentials {
+    /// Creates credentials returning the given API key.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self(api_key.into())
+    }
+}
+
+impl Debug for StaticCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("StaticCredentials(..)")
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for StaticCredentials {
+    async fn api_key(&self) -> Result<String> {
+        Ok(self.0.clone())
+    }
+}
+
+/// Credentials reading the API key from an environment variable at each request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvCredentials {
+    variable: String,
+}
+
+impl EnvCredentials {
+    /// Creates credentials reading the API key from the given environment variable.
+    pub fn new(variable: impl Into<String>) -> Self {
+        Self {
+            variable: variable.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for EnvCredentials {
+    /// Returns the value of the variable.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MissingField`] if the variable is not set or empty.
+    async fn api_key(&self) -> Result<String> {
+        std::env::var(&self.variable)
+            .ok()
+            .filter(|key| !key.is_empty())
+            .ok_or_else(|| Error::MissingField(self.variable.clone()))
+    }
+}
+
+/// Credentials calling an async function for the API key of each request.
+///
+/// Created with [`from_fn`].
+#[derive(Clone)]
+pub struct FnCredentials<F> {
+    f: F,
+}
+
+impl<F> Debug for FnCredentials<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("FnCredentials(..)")
+    }
+}
+
+#[async_trait]
+impl<F, Fut> CredentialProvider for FnCredentials<F>
+where
+    F: Fn() -> Fut + Send + Sync,
+    Fut: Future<Output = Result<String>> + Send,
+{
+    async fn api_key(&self) -> Result<String> {
+        (self.f)().await
+    }
+}
+
+/// Creates credentials calling the given async function for the API key of
+/// each request.
+pub fn from_fn<F, Fut>(f: F) -> FnCredentials<F>
+where
+    F: Fn() -> Fut + Send + Sync,
+    Fut: Future<Output = Result<String>> + Send,
+{
+    FnCredentials { f }
+}
+
+/// A credential provider shared by the clones of a provider.
+#[derive(Clone)]
+pub struct Credentials(Arc<dyn CredentialProvider>);
+
+impl Credentials {
+    /// Wraps a credential provider.
+    pub fn new(provider: impl CredentialProvider + 'static) -> Self {
+        Self(Arc::new(provider))
+    }
+
+    /// Returns the API key of the next request.
+    pub async fn api_key(&self) -> Result<String> {
+        self.0.api_key().await
+    }
+
+    /// Returns the base URL the last returned API key was issued for, if the
+    /// credentials determine it.
+    pub fn base_url(&self) -> Option<String> {
+        self.0.base_url()
+    }
+}
+
+impl Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Replaces the API key of a provider with the key of its credentials, if it
+/// has some.
+#[allow(dead_code)]
+pub(crate) async fn resolve_api_key(
+    credentials: &Option<Credentials>,
+    api_key: &mut String,
+) -> Result<()> {
+    if let Some(credentials) = credentials {
+        *api_key = credentials.api_key().await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_credentials_resolve_api_key() {
+        let mut api_key = "initial".to_string();
+        resolve_api_key(&None, &mut api_key).await.unwrap();
+        assert_eq!(api_key, "initial");
+
+        let credentials = Some(Credentials::new(StaticCredentials::new("sk-static")));
+        resolve_api_key(&credentials, &mut api_key).await.unwrap();
+        assert_eq!(api_key, "sk-static");
+        assert_eq!(format!("{credentials:?}"), "Some(StaticCredentials(..))");
+
+        let missing = Credentials::new(EnvCredentials::new("AISDK_TEST_MISSING_KEY"));
+        assert_eq!(
+            missing.api_key().await,
+            Err(Error::MissingField("AISDK_TEST_MISSING_KEY".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fn_credentials_are_called_for_each_key() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let credentials = Credentials::new(from_fn(move || {
+            let call = counter.fetch_add(1, Ordering::SeqCst);
+            async move { Ok(format!("sk-{call}")) }
+        }));
+
+        assert_eq!(credentials.api_key().await.unwrap(), "sk-0");
+        assert_eq!(credentials.clone().api_key().await.unwrap(), "sk-1");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
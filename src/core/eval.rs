@@ -0,0 +1,570 @@
+//! Evaluation of models and prompts against test cases.
+//!
+//! An [`Eval`] is a set of [`EvalCase`]s, each an input and optionally the
+//! expected output, and of [`Grader`]s scoring the output of the model for each
+//! case. Running it against a model generates the output of every case and
+//! produces an [`EvalReport`], which can be printed and checked in CI to catch
+//! regressions when a model or a prompt changes.
+//!
+//! Graders range from exact comparisons, such as [`ExactMatch`] and
+//! [`Contains`], to [`LlmJudge`], which asks a model to score the output
+//! against criteria with [`generate_object`](LanguageModelRequest::generate_object).
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::eval::{Contains, Eval, EvalCase, LlmJudge};
+//! use aisdk::providers::OpenAI;
+//!
+//! # async fn run() -> aisdk::Result<()> {
+//! let eval = Eval::new("capitals")
+//!     .system("Answer in one sentence.")
+//!     .case(EvalCase::new("france", "What is the capital of France?").expected("Paris"))
+//!     .case(EvalCase::new("japan", "What is the capital of Japan?").expected("Tokyo"))
+//!     .grader(Contains)
+//!     .grader(LlmJudge::new(OpenAI::gpt_5(), "The answer is a single, polite sentence."));
+//!
+//! let reports = [
+//!     eval.run(OpenAI::gpt_5_mini()).await,
+//!     eval.run(OpenAI::gpt_5_nano()).await,
+//! ];
+//! for report in reports {
+//!     println!("{report}");
+//!     assert!(report.pass_rate() >= 0.9);
+//! }
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+use crate::core::capabilities::TextInputSupport;
+use crate::core::language_model::LanguageModel;
+use crate::core::language_model::generate_many::generate_many;
+use crate::core::language_model::request::LanguageModelRequest;
+use crate::error::Result;
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use std::sync::Arc;
+
+/// The number of cases generated concurrently when it is not set.
+const DEFAULT_CONCURRENCY: usize = 4;
+
+/// A test case of an [`Eval`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalCase {
+    /// The name of the case, shown in reports.
+    pub name: String,
+    /// The prompt sent to the model.
+    pub input: String,
+    /// The expected output, used by graders comparing outputs.
+    pub expected: Option<String>,
+}
+
+impl EvalCase {
+    /// Creates a case with a name and the prompt sent to the model.
+    pub fn new(name: impl Into<String>, input: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            input: input.into(),
+            expected: None,
+        }
+    }
+
+    /// Sets the expected output of the case.
+    pub fn expected(mut self, expected: impl Into<String>) -> Self {
+        self.expected = Some(expected.into());
+        self
+    }
+}
+
+/// The score a [`Grader`] gave to the output of a case.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Grade {
+    /// The name of the grader.
+    pub grader: String,
+    /// The score, between 0.0 and 1.0.
+    pub score: f32,
+    /// Whether the output passed the grader.
+    pub passed: bool,
+    /// Why the output got this score, if the grader explains it.
+    pub reason: Option<String>,
+}
+
+impl Grade {
+    /// Creates a passing or failing grade, scored 1.0 or 0.0.
+    pub fn pass_fail(grader: impl Into<String>, passed: bool) -> Self {
+        Self {
+            grader: grader.into(),
+            score: if passed { 1.0 } else { 0.0 },
+            passed,
+            reason: None,
+        }
+    }
+
+    /// Sets why the output got this grade.
+    pub fn reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+}
+
+/// Scores the output of a model for a case.
+#[async_trait]
+pub trait Grader: Send + Sync {
+    /// The name of the grader, shown in reports.
+    fn name(&self) -> String;
+
+    /// Grades the output of the model for a case.
+    ///
+    /// An error, such as a failed request of an [`LlmJudge`], fails the case.
+    async fn grade(&self, case: &EvalCase, output: &str) -> Result<Grade>;
+}
+
+/// Passes if the output equals the expected output, ignoring surrounding
+/// whitespace. Cases without an expected output fail.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExactMatch;
+
+#[async_trait]
+impl Grader for ExactMatch {
+    fn name(&self) -> String {
+        "exact_match".to_string()
+    }
+
+    async fn grade(&self, case: &EvalCase, output: &str) -> Result<Grade> {
+        let passed = case
+            .expected
+            .as_deref()
+            .is_some_and(|expected| expected.trim() == output.trim());
+        Ok(Grade::pass_fail(self.name(), passed))
+    }
+}
+
+/// Passes if the output contains the expected output, ignoring case. Cases
+/// without an expected output fail.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Contains;
+
+#[async_trait]
+impl Grader for Contains {
+    fn name(&self) -> String {
+        "contains".to_string()
+    }
+
+    async fn grade(&self, case: &EvalCase, output: &str) -> Result<Grade> {
+        let passed = case
+            .expected
+            .as_deref()
+            .is_some_and(|expected| output.to_lowercase().contains(&expected.to_lowercase()));
+        Ok(Grade::pass_fail(self.name(), passed))
+    }
+}
+
+/// Function returning whether the output of a case passed.
+type GradeFn = Arc<dyn Fn(&EvalCase, &str) -> bool + Send + Sync>;
+
+/// Grades outputs with a function returning whether the output passed.
+pub struct FnGrader {
+    name: String,
+    grade: GradeFn,
+}
+
+impl FnGrader {
+    /// Creates a grader with a name and a function returning whether the output
+    /// of a case passed.
+    pub fn new(
+        name: impl Into<String>,
+        grade: impl Fn(&EvalCase, &str) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            grade: Arc::new(grade),
+        }
+    }
+}
+
+#[async_trait]
+impl Grader for FnGrader {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    async fn grade(&self, case: &EvalCase, output: &str) -> Result<Grade> {
+        Ok(Grade::pass_fail(self.name(), (self.grade)(case, output)))
+    }
+}
+
+/// The verdict of an [`LlmJudge`].
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct Verdict {
+    /// How well the output meets the criteria, between 0.0 and 1.0.
+    pub score: f32,
+    /// A short explanation of the score.
+    pub reason: String,
+}
+
+/// Asks a model to score outputs against criteria, as an LLM-as-judge.
+///
+/// The judge is given the input, the expected output if any, the output and
+/// the criteria, and generates a [`Verdict`]. The output passes if the score is
+/// at least the [`threshold`](Self::threshold).
+#[derive(Debug, Clone)]
+pub struct LlmJudge<M: LanguageModel> {
+    model: M,
+    criteria: String,
+    threshold: f32,
+}
+
+impl<M: LanguageModel + TextInputSupport> LlmJudge<M> {
+    /// Creates a judge scoring outputs with a model against the given criteria.
+    pub fn new(model: M, criteria: impl Into<String>) -> Self {
+        Self {
+            model,
+            criteria: criteria.into(),
+            threshold: 0.7,
+        }
+    }
+
+    /// Sets the lowest passing score. Defaults to 0.7.
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Returns the prompt asking the judge to grade an output.
+    fn prompt(&self, case: &EvalCase, output: &str) -> String {
+        let mut prompt = format!("Input:\n{}\n\n", case.input);
+        if let Some(expected) = &case.expected {
+            prompt.push_str(&format!("Expected output:\n{expected}\n\n"));
+        }
+        prompt.push_str(&format!(
+            "Output:\n{output}\n\nCriteria:\n{}",
+            self.criteria
+        ));
+        prompt
+    }
+}
+
+#[async_trait]
+impl<M: LanguageModel + TextInputSupport> Grader for LlmJudge<M> {
+    fn name(&self) -> String {
+        format!("llm_judge({})", self.model.name())
+    }
+
+    async fn grade(&self, case: &EvalCase, output: &str) -> Result<Grade> {
+        let verdict = LanguageModelRequest::builder()
+            .model(self.model.clone())
+            .system(
+                "You grade the output of an AI assistant. Score how well the output \
+                 meets the criteria between 0.0 (not at all) and 1.0 (fully), and \
+                 explain the score in one sentence.",
+            )
+            .prompt(self.prompt(case, output))
+            .build()
+            .generate_object::<Verdict>()
+            .await?
+            .object;
+
+        let score = verdict.score.clamp(0.0, 1.0);
+        Ok(Grade {
+            grader: self.name(),
+            score,
+            passed: score >= self.threshold,
+            reason: Some(verdict.reason),
+        })
+    }
+}
+
+/// The result of a case of an [`EvalReport`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CaseResult {
+    /// The case.
+    pub case: EvalCase,
+    /// The output of the model, or the error of the generation.
+    pub output: std::result::Result<String, String>,
+    /// The grades of the output, or the errors of the graders that failed.
+    pub grades: Vec<std::result::Result<Grade, String>>,
+}
+
+impl CaseResult {
+    /// Returns whether the output was generated and passed every grader.
+    pub fn passed(&self) -> bool {
+        self.output.is_ok()
+            && self
+                .grades
+                .iter()
+                .all(|grade| grade.as_ref().is_ok_and(|grade| grade.passed))
+    }
+
+    /// Returns the mean score of the graders, 0.0 if the generation or a grader
+    /// failed.
+    pub fn score(&self) -> f32 {
+        if self.output.is_err() || self.grades.is_empty() {
+            return if self.passed() { 1.0 } else { 0.0 };
+        }
+        let total: f32 = self
+            .grades
+            .iter()
+            .map(|grade| grade.as_ref().map_or(0.0, |grade| grade.score))
+            .sum();
+        total / self.grades.len() as f32
+    }
+}
+
+/// The results of an [`Eval`] run against a model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalReport {
+    /// The name of the eval.
+    pub eval: String,
+    /// The name of the model.
+    pub model: String,
+    /// The results of the cases, in the order of the cases.
+    pub results: Vec<CaseResult>,
+}
+
+impl EvalReport {
+    /// Returns the share of the cases that passed, between 0.0 and 1.0.
+    pub fn pass_rate(&self) -> f32 {
+        if self.results.is_empty() {
+            return 0.0;
+        }
+        let passed = self.results.iter().filter(|r| r.passed()).count();
+        passed as f32 / self.results.len() as f32
+    }
+
+    /// Returns the mean score of the cases, between 0.0 and 1.0.
+    pub fn score(&self) -> f32 {
+        if self.results.is_empty() {
+            return 0.0;
+        }
+        let total: f32 = self.results.iter().map(CaseResult::score).sum();
+        total / self.results.len() as f32
+    }
+
+    /// Returns the cases that did not pass.
+    pub fn failures(&self) -> impl Iterator<Item = &CaseResult> {
+        self.results.iter().filter(|r| !r.passed())
+    }
+}
+
+impl Display for EvalReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} on {}: {}/{} passed, score {:.2}",
+            self.eval,
+            self.model,
+            self.results.iter().filter(|r| r.passed()).count(),
+            self.results.len(),
+            self.score()
+        )?;
+        for result in &self.results {
+            let status = if result.passed() { "pass" } else { "FAIL" };
+            writeln!(f, "  {status} {} ({:.2})", result.case.name, result.score())?;
+            if let Err(error) = &result.output {
+                writeln!(f, "    error: {error}")?;
+            }
+            for grade in &result.grades {
+                match grade {
+                    Ok(grade) if !grade.passed => match &grade.reason {
+                        Some(reason) => writeln!(f, "    {}: {reason}", grade.grader)?,
+                        None => writeln!(f, "    {}: failed", grade.grader)?,
+                    },
+                    Ok(_) => {}
+                    Err(error) => writeln!(f, "    grader error: {error}")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A set of cases and graders to run against models.
+#[derive(Clone)]
+pub struct Eval {
+    name: String,
+    system: Option<String>,
+    cases: Vec<EvalCase>,
+    graders: Vec<Arc<dyn Grader>>,
+    concurrency: usize,
+}
+
+impl Eval {
+    /// Creates an eval without cases or graders.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            system: None,
+            cases: Vec::new(),
+            graders: Vec::new(),
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+
+    /// Sets the system prompt of the requests of the cases.
+    pub fn system(mut self, system: impl Into<String>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    /// Adds a case.
+    pub fn case(mut self, case: EvalCase) -> Self {
+        self.cases.push(case);
+        self
+    }
+
+    /// Adds cases.
+    pub fn cases(mut self, cases: impl IntoIterator<Item = EvalCase>) -> Self {
+        self.cases.extend(cases);
+        self
+    }
+
+    /// Adds a grader run on the output of every case.
+    pub fn grader(mut self, grader: impl Grader + 'static) -> Self {
+        self.graders.push(Arc::new(grader));
+        self
+    }
+
+    /// Sets the number of cases generated concurrently. Defaults to 4.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Runs the cases against a model and grades their outputs.
+    ///
+    /// Failed generations and graders are recorded in the report as failed
+    /// cases, so a run always produces a report.
+    pub async fn run<M: LanguageModel + TextInputSupport>(&self, model: M) -> EvalReport {
+        let model_name = model.name();
+        let requests = self.cases.iter().map(|case| {
+            let builder = LanguageModelRequest::builder().model(model.clone());
+            match &self.system {
+                Some(system) => builder.system(system).prompt(&case.input).build(),
+                None => builder.prompt(&case.input).build(),
+            }
+        });
+        let outputs = generate_many(requests)
+            .concurrency(self.concurrency)
+            .run()
+            .await;
+
+        let mut results = Vec::with_capacity(self.cases.len());
+        for (case, output) in self.cases.iter().zip(outputs) {
+            let output = output
+                .map(|response| response.text().unwrap_or_default())
+                .map_err(|error| error.to_string());
+            let mut grades = Vec::with_capacity(self.graders.len());
+            if let Ok(output) = &output {
+                for grader in &self.graders {
+                    grades.push(
+                        grader
+                            .grade(case, output)
+                            .await
+                            .map_err(|error| error.to_string()),
+                    );
+                }
+            }
+            results.push(CaseResult {
+                case: case.clone(),
+                output,
+                grades,
+            });
+        }
+
+        EvalReport {
+            eval: self.name.clone(),
+            model: model_name,
+            results,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Message;
+    use crate::error::Error;
+    use crate::providers::MockLanguageModel;
+
+    #[tokio::test]
+    async fn test_eval_report() {
+        let model = MockLanguageModel::new()
+            .with_name("geography")
+            .with_text("It is Paris, please note.")
+            .with_text("Kyoto")
+            .with_error(Error::Other("unknown country".to_string()));
+        let judge = MockLanguageModel::new()
+            .with_name("geography")
+            .with_text(r#"{ "score": 0.9, "reason": "Polite." }"#)
+            .with_text(r#"{ "score": 0.2, "reason": "Not polite." }"#);
+
+        let report = Eval::new("capitals")
+            .case(EvalCase::new("france", "France?").expected("Paris"))
+            .case(EvalCase::new("japan", "Japan?").expected("Tokyo"))
+            .case(EvalCase::new("atlantis", "Atlantis?"))
+            .grader(Contains)
+            .grader(LlmJudge::new(judge.clone(), "The answer is polite."))
+            .concurrency(1)
+            .run(model)
+            .await;
+
+        assert_eq!(report.model, "geography");
+        let passed: Vec<bool> = report.results.iter().map(CaseResult::passed).collect();
+        assert_eq!(passed, [true, false, false]);
+        assert!((report.pass_rate() - 1.0 / 3.0).abs() < 1e-6);
+
+        let japan = &report.results[1];
+        assert_eq!(japan.output, Ok("Kyoto".to_string()));
+        assert_eq!(
+            japan.grades[1],
+            Ok(Grade {
+                grader: "llm_judge(geography)".to_string(),
+                score: 0.2,
+                passed: false,
+                reason: Some("Not polite.".to_string()),
+            })
+        );
+        assert!((japan.score() - 0.1).abs() < 1e-6);
+        assert!(report.results[2].output.is_err());
+        assert_eq!(report.failures().count(), 2);
+        assert!(matches!(
+            judge.calls()[1].messages().last(),
+            Some(Message::User(u)) if u.content.contains("Criteria:") && u.content.contains("Kyoto")
+        ));
+
+        let printed = report.to_string();
+        assert!(printed.starts_with("capitals on geography: 1/3 passed"));
+        assert!(printed.contains("  FAIL japan (0.10)\n    contains: failed\n"));
+    }
+
+    #[tokio::test]
+    async fn test_graders() {
+        let case = EvalCase::new("case", "input").expected(" Paris ");
+        assert!(ExactMatch.grade(&case, "Paris").await.unwrap().passed);
+        assert!(!ExactMatch.grade(&case, "paris").await.unwrap().passed);
+        assert!(
+            Contains
+                .grade(
+                    &EvalCase::new("case", "input").expected("paris"),
+                    "It is Paris."
+                )
+                .await
+                .unwrap()
+                .passed
+        );
+        let short = FnGrader::new("short", |_, output| output.len() < 10);
+        assert!(short.grade(&case, "Paris").await.unwrap().passed);
+        assert!(
+            !short
+                .grade(&case, "The capital is Paris")
+                .await
+                .unwrap()
+                .passed
+        );
+    }
+}
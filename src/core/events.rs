@@ -0,0 +1,303 @@
+//! Events of the requests made to language models, for observability.
+//!
+//! An [`EventSink`] receives the requests sent to the models, their responses,
+//! the streamed chunks, the executed tool calls and the errors of generations. It
+//! is the single integration point for logging and analytics pipelines, such as
+//! exporters to Langfuse or Helicone.
+//!
+//! Sinks are registered for a single request with
+//! [`event_sink`](crate::core::language_model::request::LanguageModelRequestBuilder::event_sink),
+//! or for all requests with [`add_global_sink`].
+//!
+//! The built-in sinks are:
+//!
+//! - [`JsonLinesSink`], writing each event as a line of JSON.
+//! - [`TracingSink`], emitting each event with `tracing`, behind the `tracing` feature.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use aisdk::core::events::{self, JsonLinesSink};
+//!
+//! events::add_global_sink(JsonLinesSink::create("events.jsonl").unwrap());
+//! ```
+
+use crate::core::Message;
+use crate::core::language_model::{
+    LanguageModelResponseContentType, LanguageModelStreamChunkType, Usage,
+};
+use crate::core::tools::{ToolCallInfo, ToolResultInfo};
+use crate::error::{Error, Result};
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+use std::fmt::Debug;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+/// A request sent to a model, at the start of a step.
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestEvent {
+    /// The name of the model.
+    pub model: String,
+    /// The ID of the step.
+    pub step: usize,
+    /// The messages sent to the model.
+    pub messages: Vec<Message>,
+}
+
+/// The response of a model, at the end of a step.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResponseEvent {
+    /// The name of the model.
+    pub model: String,
+    /// The ID of the step.
+    pub step: usize,
+    /// The contents generated by the model.
+    pub contents: Vec<LanguageModelResponseContentType>,
+    /// The token usage of the step.
+    pub usage: Option<Usage>,
+}
+
+/// Receives the events of the requests made to language models.
+///
+/// All methods have default implementations that ignore the event, so a sink
+/// only needs to implement the events it is interested in. They are called on
+/// the task running the generation and should not block.
+pub trait EventSink: Send + Sync + Debug {
+    /// Called before a request is sent to a model.
+    fn on_request(&self, _request: &RequestEvent) {}
+
+    /// Called once the response of a model has been received.
+    fn on_response(&self, _response: &ResponseEvent) {}
+
+    /// Called for every chunk emitted by a stream.
+    fn on_chunk(&self, _chunk: &LanguageModelStreamChunkType) {}
+
+    /// Called once a tool call has been executed.
+    fn on_tool_call(&self, _call: &ToolCallInfo, _result: &ToolResultInfo) {}
+
+    /// Called when a generation fails.
+    fn on_error(&self, _error: &Error) {}
+}
+
+/// A sink shared by requests.
+pub type SharedEventSink = Arc<dyn EventSink>;
+
+/// The sinks receiving the events of all requests.
+static GLOBAL_SINKS: RwLock<Vec<SharedEventSink>> = RwLock::new(Vec::new());
+
+/// Registers a sink receiving the events of all requests.
+pub fn add_global_sink(sink: impl EventSink + 'static) {
+    GLOBAL_SINKS.write().push(Arc::new(sink));
+}
+
+/// Removes the sinks registered with [`add_global_sink`].
+pub fn clear_global_sinks() {
+    GLOBAL_SINKS.write().clear();
+}
+
+/// Returns whether events of a request with the given sinks are received by
+/// any sink, to skip building them otherwise.
+pub(crate) fn has_sinks(sinks: &[SharedEventSink]) -> bool {
+    !sinks.is_empty() || !GLOBAL_SINKS.read().is_empty()
+}
+
+/// Sends an event to the global sinks and the sinks of a request.
+pub(crate) fn emit(sinks: &[SharedEventSink], event: impl Fn(&dyn EventSink)) {
+    for sink in GLOBAL_SINKS.read().iter().chain(sinks) {
+        event(sink.as_ref());
+    }
+}
+
+/// An event, as written by [`JsonLinesSink`].
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Event<'a> {
+    Request(&'a RequestEvent),
+    Response(&'a ResponseEvent),
+    Chunk {
+        chunk: &'a LanguageModelStreamChunkType,
+    },
+    ToolCall {
+        call: &'a ToolCallInfo,
+        result: &'a ToolResultInfo,
+    },
+    Error {
+        error: String,
+    },
+}
+
+/// A sink writing each event as a line of JSON, with its type in the `event` field.
+pub struct JsonLinesSink {
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl Debug for JsonLinesSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("JsonLinesSink(..)")
+    }
+}
+
+impl JsonLinesSink {
+    /// Creates a sink writing to the given writer.
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Mutex::new(Box::new(writer)),
+        }
+    }
+
+    /// Creates a sink appending to the file at the given path, creating it if
+    /// it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if the file can not be opened.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| Error::InvalidInput(format!("Failed to open {}: {e}", path.display())))?;
+        Ok(Self::new(file))
+    }
+
+    /// Writes an event, dropping it if it can not be written.
+    fn write(&self, event: Event<'_>) {
+        let Ok(mut line) = serde_json::to_vec(&event) else {
+            return;
+        };
+        line.push(b'\n');
+        let mut writer = self.writer.lock();
+        if writer
+            .write_all(&line)
+            .and_then(|_| writer.flush())
+            .is_err()
+        {
+            log::warn!("Failed to write an event to the JSON lines sink");
+        }
+    }
+}
+
+impl EventSink for JsonLinesSink {
+    fn on_request(&self, request: &RequestEvent) {
+        self.write(Event::Request(request));
+    }
+
+    fn on_response(&self, response: &ResponseEvent) {
+        self.write(Event::Response(response));
+    }
+
+    fn on_chunk(&self, chunk: &LanguageModelStreamChunkType) {
+        self.write(Event::Chunk { chunk });
+    }
+
+    fn on_tool_call(&self, call: &ToolCallInfo, result: &ToolResultInfo) {
+        self.write(Event::ToolCall { call, result });
+    }
+
+    fn on_error(&self, error: &Error) {
+        self.write(Event::Error {
+            error: error.to_string(),
+        });
+    }
+}
+
+/// A sink emitting each event with `tracing`, under the `aisdk` target.
+///
+/// Requests, responses and tool calls are emitted at the `INFO` level, chunks
+/// at the `TRACE` level and errors at the `ERROR` level.
+#[cfg(feature = "tracing")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingSink;
+
+#[cfg(feature = "tracing")]
+impl EventSink for TracingSink {
+    fn on_request(&self, request: &RequestEvent) {
+        tracing::info!(
+            target: "aisdk",
+            model = %request.model,
+            step = request.step,
+            messages = request.messages.len(),
+            "request"
+        );
+    }
+
+    fn on_response(&self, response: &ResponseEvent) {
+        let usage = response.usage.clone().unwrap_or_default();
+        tracing::info!(
+            target: "aisdk",
+            model = %response.model,
+            step = response.step,
+            input_tokens = ?usage.input_tokens,
+            output_tokens = ?usage.output_tokens,
+            "response"
+        );
+    }
+
+    fn on_chunk(&self, chunk: &LanguageModelStreamChunkType) {
+        tracing::trace!(target: "aisdk", ?chunk, "chunk");
+    }
+
+    fn on_tool_call(&self, call: &ToolCallInfo, _result: &ToolResultInfo) {
+        tracing::info!(
+            target: "aisdk",
+            tool = %call.tool.name,
+            id = %call.tool.id,
+            "tool call"
+        );
+    }
+
+    fn on_error(&self, error: &Error) {
+        tracing::error!(target: "aisdk", %error, "error");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A writer shared with the test reading what the sink wrote.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_lines_sink_writes_one_line_per_event() {
+        let buffer = SharedBuffer::default();
+        let sink = JsonLinesSink::new(buffer.clone());
+
+        sink.on_request(&RequestEvent {
+            model: "model".to_string(),
+            step: 1,
+            messages: vec![Message::Developer("Hello".to_string())],
+        });
+        sink.on_chunk(&LanguageModelStreamChunkType::Text("Hi".to_string()));
+        sink.on_error(&Error::Other("failed".to_string()));
+
+        let output = String::from_utf8(buffer.0.lock().clone()).unwrap();
+        let events: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0]["event"], "request");
+        assert_eq!(events[0]["model"], "model");
+        assert_eq!(events[0]["step"], 1);
+        assert_eq!(events[1]["event"], "chunk");
+        assert_eq!(events[1]["chunk"]["type"], "text");
+        assert_eq!(events[1]["chunk"]["content"], "Hi");
+        assert_eq!(events[2]["event"], "error");
+    }
+}
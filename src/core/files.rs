@@ -0,0 +1,198 @@
+//! Files stored by providers.
+//!
+//! Providers with a Files API store documents, images or datasets uploaded once,
+//! which can then be referenced by later requests instead of being sent again.
+//! This module defines the [`FileProvider`] trait implemented by those providers,
+//! and the [`FileHandle`] returned for an uploaded file, which can be attached to
+//! a [`UserMessage`](crate::core::UserMessage) with
+//! [`with_file`](crate::core::UserMessage::with_file).
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::files::{FileProvider, FilePurpose};
+//! use aisdk::core::{Message, UserMessage};
+//! use aisdk::providers::OpenAI;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let openai = OpenAI::gpt_5();
+//! let report = std::fs::read("report.pdf")?;
+//! let file = openai.upload_file(report, "report.pdf", FilePurpose::UserData).await?;
+//!
+//! let message = Message::User(UserMessage::new("Summarize the report.").with_file(file));
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+use crate::error::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// The intended use of an uploaded file.
+///
+/// Only used by providers that require one, such as OpenAI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilePurpose {
+    /// A file used by assistants, such as a document searched by a tool.
+    Assistants,
+    /// The input file of a batch.
+    Batch,
+    /// Training data for fine-tuning.
+    FineTune,
+    /// An image used as the input of a model.
+    Vision,
+    /// A file used as the input of a model, such as a PDF.
+    UserData,
+    /// Another purpose, as named by the provider.
+    Other(String),
+}
+
+impl FilePurpose {
+    /// Returns the name of the purpose in the OpenAI API.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Assistants => "assistants",
+            Self::Batch => "batch",
+            Self::FineTune => "fine-tune",
+            Self::Vision => "vision",
+            Self::UserData => "user_data",
+            Self::Other(purpose) => purpose,
+        }
+    }
+}
+
+/// A file stored by a provider.
+///
+/// Handles are only valid for the provider, and the account, that stored the file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileHandle {
+    /// The ID of the file (e.g., "file-abc123" or "files/abc123").
+    pub id: String,
+    /// The name of the file, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    /// The media type of the file (e.g., "application/pdf"), if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// The size of the file in bytes, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// The URI of the file, used instead of the ID by providers such as Google.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+}
+
+impl FileHandle {
+    /// Creates a handle from the ID of a file stored earlier.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            filename: None,
+            mime_type: None,
+            size: None,
+            uri: None,
+        }
+    }
+
+    /// Returns whether the file is an image, based on its media type.
+    pub fn is_image(&self) -> bool {
+        self.mime_type
+            .as_deref()
+            .is_some_and(|mime| mime.starts_with("image/"))
+    }
+}
+
+/// A provider that stores files for later requests.
+#[async_trait]
+pub trait FileProvider {
+    /// Uploads a file.
+    ///
+    /// # Parameters
+    ///
+    /// * `bytes` - The content of the file.
+    /// * `filename` - The name of the file, whose extension gives its media type.
+    /// * `purpose` - The intended use of the file.
+    ///
+    /// # Returns
+    ///
+    /// The handle of the stored file.
+    async fn upload_file(
+        &self,
+        bytes: Vec<u8>,
+        filename: &str,
+        purpose: FilePurpose,
+    ) -> Result<FileHandle>;
+
+    /// Lists the stored files.
+    async fn list_files(&self) -> Result<Vec<FileHandle>>;
+
+    /// Deletes a stored file, using its ID.
+    async fn delete_file(&self, id: &str) -> Result<()>;
+}
+
+/// Returns the media type of a file from the extension of its name.
+#[allow(dead_code)]
+pub(crate) fn mime_type(filename: &str) -> &'static str {
+    let extension = filename
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "pdf" => "application/pdf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "txt" => "text/plain",
+        "md" => "text/markdown",
+        "csv" => "text/csv",
+        "html" => "text/html",
+        "json" => "application/json",
+        "jsonl" => "application/jsonl",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mime_type_from_extension() {
+        assert_eq!(mime_type("report.PDF"), "application/pdf");
+        assert_eq!(mime_type("photo.jpeg"), "image/jpeg");
+        assert_eq!(mime_type("batch.jsonl"), "application/jsonl");
+        assert_eq!(mime_type("README"), "application/octet-stream");
+    }
+
+    #[test]
+    fn test_user_message_serializes_files_only_when_attached() {
+        use crate::core::UserMessage;
+
+        let message = UserMessage::new("Hello");
+        let json = serde_json::to_string(&message).unwrap();
+        assert_eq!(json, r#""Hello""#);
+        let parsed: UserMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.content, message.content);
+        assert_eq!(parsed.files, message.files);
+
+        let mut file = FileHandle::new("file-1");
+        file.mime_type = Some("application/pdf".to_string());
+        let message = UserMessage::new("Summarize").with_file(file);
+        let json = serde_json::to_string(&message).unwrap();
+        assert_eq!(
+            json,
+            r#"{"content":"Summarize","files":[{"id":"file-1","mime_type":"application/pdf"}]}"#
+        );
+        let parsed: UserMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.content, message.content);
+        assert_eq!(parsed.files, message.files);
+    }
+}
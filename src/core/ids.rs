@@ -0,0 +1,148 @@
+//! Generation of the IDs of messages, tool calls and completions.
+//!
+//! IDs the SDK makes up, such as the IDs of the messages streamed to UIs or of
+//! the tool calls of providers that do not send one, come from an
+//! [`IdGenerator`]. It generates random UUIDs by default, and can be replaced
+//! for a future with [`with_id_generator`], for example with a
+//! [`SequentialIdGenerator`] to get the same IDs on every run in tests and
+//! reproducible transcripts. The generator applies to the future and the tasks
+//! the SDK spawns for it, so futures running in parallel each keep their own.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aisdk::core::ids::{self, SequentialIdGenerator};
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let ids = ids::with_id_generator(SequentialIdGenerator::new(), async {
+//!     [ids::generate_id("msg"), ids::generate_id("msg")]
+//! })
+//! .await;
+//! assert_eq!(ids, ["msg_1", "msg_2"]);
+//! # }
+//! ```
+
+use std::fmt::Debug;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Generates the IDs the SDK makes up.
+pub trait IdGenerator: Send + Sync + Debug {
+    /// Generates an ID starting with the given prefix, such as `msg_...` for
+    /// the prefix `msg`. IDs without a prefix are generated for an empty prefix.
+    fn generate(&self, prefix: &str) -> String;
+}
+
+/// Joins a prefix and a unique part into an ID.
+fn join(prefix: &str, unique: impl std::fmt::Display) -> String {
+    if prefix.is_empty() {
+        unique.to_string()
+    } else {
+        format!("{prefix}_{unique}")
+    }
+}
+
+/// Generates IDs from random UUIDs, such as `msg_67e5504410b1426f9247bb680e5fe0c8`.
+///
+/// This is the default generator.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidGenerator;
+
+impl IdGenerator for UuidGenerator {
+    fn generate(&self, prefix: &str) -> String {
+        join(prefix, uuid::Uuid::new_v4().simple())
+    }
+}
+
+/// Generates IDs from a counter, such as `msg_1`, `call_2` and `msg_3`.
+///
+/// The counter is shared by all prefixes, so the IDs only depend on the order in
+/// which they are generated.
+#[derive(Debug, Default)]
+pub struct SequentialIdGenerator {
+    counter: AtomicU64,
+}
+
+impl SequentialIdGenerator {
+    /// Creates a generator counting from 1.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+    fn generate(&self, prefix: &str) -> String {
+        join(prefix, self.counter.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+}
+
+tokio::task_local! {
+    /// The generator replacing the default one in the current task.
+    static GENERATOR: Arc<dyn IdGenerator>;
+}
+
+/// Runs a future generating the IDs made up by the SDK with the given generator.
+pub async fn with_id_generator<F: Future>(
+    generator: impl IdGenerator + 'static,
+    future: F,
+) -> F::Output {
+    GENERATOR.scope(Arc::new(generator), future).await
+}
+
+/// Wraps a future so it keeps the generator of the current task, once spawned
+/// on another one.
+pub(crate) fn inherit_id_generator<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    let generator = GENERATOR.try_with(Arc::clone).ok();
+    async move {
+        match generator {
+            Some(generator) => GENERATOR.scope(generator, future).await,
+            None => future.await,
+        }
+    }
+}
+
+/// Generates an ID starting with the given prefix with the current generator.
+pub fn generate_id(prefix: &str) -> String {
+    GENERATOR
+        .try_with(|generator| generator.generate(prefix))
+        .unwrap_or_else(|_| UuidGenerator.generate(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uuid_generator() {
+        let id = UuidGenerator.generate("msg");
+        assert!(id.starts_with("msg_"));
+        assert_eq!(id.len(), 4 + 32);
+        assert_ne!(id, UuidGenerator.generate("msg"));
+        assert_eq!(UuidGenerator.generate("").len(), 32);
+    }
+
+    #[test]
+    fn test_sequential_generator() {
+        let generator = SequentialIdGenerator::new();
+        assert_eq!(generator.generate("msg"), "msg_1");
+        assert_eq!(generator.generate("call"), "call_2");
+        assert_eq!(generator.generate(""), "3");
+    }
+
+    #[tokio::test]
+    async fn test_with_id_generator_is_scoped_to_the_future() {
+        let sequential = with_id_generator(SequentialIdGenerator::new(), async {
+            let spawned = tokio::spawn(inherit_id_generator(async { generate_id("call") }));
+            [generate_id("msg"), spawned.await.unwrap()]
+        });
+        let (sequential, other) = tokio::join!(sequential, async {
+            tokio::task::yield_now().await;
+            generate_id("msg")
+        });
+
+        assert_eq!(sequential, ["msg_1", "call_2"]);
+        assert_eq!(other.len(), 4 + 32);
+    }
+}
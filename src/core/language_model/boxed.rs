@@ -0,0 +1,274 @@
+//! Type-erased language models.
+//!
+//! [`BoxedLanguageModel`] wraps any [`LanguageModel`] behind a single type, so models
+//! of different providers can be stored together, swapped at runtime, or resolved
+//! from configuration with a
+//! [`ProviderRegistry`](crate::core::registry::ProviderRegistry). It is built on the
+//! object-safe [`DynLanguageModel`] trait.
+//!
+//! Like [`DynamicModel`](crate::core::DynamicModel), a boxed model implements all
+//! capability marker traits and leaves the validation of requests to the provider.
+
+use crate::core::Message;
+use crate::core::capabilities::*;
+use crate::core::language_model::{
+    LanguageModel, LanguageModelOptions, LanguageModelResponse, ProviderStream,
+};
+use crate::core::pricing::ModelPricing;
+use crate::error::Result;
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use std::fmt::Debug;
+
+/// An object-safe view of a [`LanguageModel`].
+///
+/// [`LanguageModel`] is not object-safe, since it requires `Clone` and generic
+/// methods. This trait exposes the same operations through boxed futures and is
+/// implemented for every language model, so models can be stored as
+/// `Box<dyn DynLanguageModel>` or `&mut dyn DynLanguageModel`. Most code should
+/// use [`BoxedLanguageModel`], which implements [`LanguageModel`] on top of it.
+///
+/// The methods are prefixed with `dyn_` so they do not clash with the methods of
+/// [`LanguageModel`] when both traits are in scope.
+pub trait DynLanguageModel: Send + Sync + Debug {
+    /// See [`LanguageModel::name`].
+    fn dyn_name(&self) -> String;
+
+    /// See [`LanguageModel::generate_text`].
+    fn dyn_generate_text(
+        &mut self,
+        options: LanguageModelOptions,
+    ) -> BoxFuture<'_, Result<LanguageModelResponse>>;
+
+    /// See [`LanguageModel::stream_text`].
+    fn dyn_stream_text(
+        &mut self,
+        options: LanguageModelOptions,
+    ) -> BoxFuture<'_, Result<ProviderStream>>;
+
+    /// See [`LanguageModel::pricing`].
+    fn dyn_pricing(&self) -> Option<ModelPricing>;
+
+    /// See [`LanguageModel::context_window`].
+    fn dyn_context_window(&self) -> Option<usize>;
+
+    /// See [`LanguageModel::max_output_tokens`].
+    fn dyn_max_output_tokens(&self) -> Option<usize>;
+
+    /// See [`LanguageModel::capabilities`].
+    fn dyn_capabilities(&self) -> CapabilitySet;
+
+    /// See [`LanguageModel::count_tokens`].
+    fn dyn_count_tokens<'a>(&'a mut self, messages: &'a [Message]) -> BoxFuture<'a, Result<usize>>;
+
+    /// Clones the model into a new box.
+    fn dyn_clone(&self) -> Box<dyn DynLanguageModel>;
+}
+
+impl<M: LanguageModel> DynLanguageModel for M {
+    fn dyn_name(&self) -> String {
+        self.name()
+    }
+
+    fn dyn_generate_text(
+        &mut self,
+        options: LanguageModelOptions,
+    ) -> BoxFuture<'_, Result<LanguageModelResponse>> {
+        self.generate_text(options)
+    }
+
+    fn dyn_stream_text(
+        &mut self,
+        options: LanguageModelOptions,
+    ) -> BoxFuture<'_, Result<ProviderStream>> {
+        self.stream_text(options)
+    }
+
+    fn dyn_pricing(&self) -> Option<ModelPricing> {
+        self.pricing()
+    }
+
+    fn dyn_context_window(&self) -> Option<usize> {
+        self.context_window()
+    }
+
+    fn dyn_max_output_tokens(&self) -> Option<usize> {
+        self.max_output_tokens()
+    }
+
+    fn dyn_capabilities(&self) -> CapabilitySet {
+        self.capabilities()
+    }
+
+    fn dyn_count_tokens<'a>(&'a mut self, messages: &'a [Message]) -> BoxFuture<'a, Result<usize>> {
+        self.count_tokens(messages)
+    }
+
+    fn dyn_clone(&self) -> Box<dyn DynLanguageModel> {
+        Box::new(self.clone())
+    }
+}
+
+/// A language model of any type, boxed behind a single type.
+///
+/// # Examples
+///
+/// ```rust,no_run
+///# #[cfg(all(feature = "openai", feature = "anthropic"))]
+///# {
+/// use aisdk::core::DynamicModel;
+/// use aisdk::core::language_model::boxed::BoxedLanguageModel;
+/// use aisdk::providers::{Anthropic, OpenAI};
+///
+/// let use_openai = std::env::var("USE_OPENAI").is_ok();
+/// let model = if use_openai {
+///     BoxedLanguageModel::new(OpenAI::<DynamicModel>::model_name("gpt-5"))
+/// } else {
+///     BoxedLanguageModel::new(Anthropic::<DynamicModel>::model_name("claude-sonnet-4-5"))
+/// };
+///# }
+/// ```
+pub struct BoxedLanguageModel(Box<dyn DynLanguageModel>);
+
+impl BoxedLanguageModel {
+    /// Boxes a language model.
+    pub fn new<M: LanguageModel>(model: M) -> Self {
+        Self(Box::new(model))
+    }
+
+    /// Returns the boxed model.
+    pub fn into_inner(self) -> Box<dyn DynLanguageModel> {
+        self.0
+    }
+}
+
+impl From<Box<dyn DynLanguageModel>> for BoxedLanguageModel {
+    fn from(model: Box<dyn DynLanguageModel>) -> Self {
+        Self(model)
+    }
+}
+
+impl Clone for BoxedLanguageModel {
+    fn clone(&self) -> Self {
+        Self(self.0.dyn_clone())
+    }
+}
+
+impl Debug for BoxedLanguageModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[async_trait]
+impl LanguageModel for BoxedLanguageModel {
+    fn name(&self) -> String {
+        self.0.dyn_name()
+    }
+
+    async fn generate_text(
+        &mut self,
+        options: LanguageModelOptions,
+    ) -> Result<LanguageModelResponse> {
+        self.0.dyn_generate_text(options).await
+    }
+
+    async fn stream_text(&mut self, options: LanguageModelOptions) -> Result<ProviderStream> {
+        self.0.dyn_stream_text(options).await
+    }
+
+    fn pricing(&self) -> Option<ModelPricing> {
+        self.0.dyn_pricing()
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        self.0.dyn_context_window()
+    }
+
+    fn max_output_tokens(&self) -> Option<usize> {
+        self.0.dyn_max_output_tokens()
+    }
+
+    fn capabilities(&self) -> CapabilitySet {
+        self.0.dyn_capabilities()
+    }
+
+    async fn count_tokens(&mut self, messages: &[Message]) -> Result<usize> {
+        self.0.dyn_count_tokens(messages).await
+    }
+
+    /// Returns the model itself, as it is already boxed.
+    fn boxed(self) -> BoxedLanguageModel {
+        self
+    }
+}
+
+impl ToolCallSupport for BoxedLanguageModel {}
+impl StructuredOutputSupport for BoxedLanguageModel {}
+impl ReasoningSupport for BoxedLanguageModel {}
+impl TextInputSupport for BoxedLanguageModel {}
+impl TextOutputSupport for BoxedLanguageModel {}
+impl ImageInputSupport for BoxedLanguageModel {}
+impl VideoInputSupport for BoxedLanguageModel {}
+impl AudioInputSupport for BoxedLanguageModel {}
+impl ImageOutputSupport for BoxedLanguageModel {}
+impl VideoOutputSupport for BoxedLanguageModel {}
+impl AudioOutputSupport for BoxedLanguageModel {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::language_model::LanguageModelResponseContentType;
+    use crate::core::language_model::fallback::FallbackModel;
+    use crate::core::language_model::middleware::default_settings::with_defaults;
+    use crate::error::Error;
+    use crate::providers::MockLanguageModel;
+
+    #[tokio::test]
+    async fn test_boxed_models_of_different_types() {
+        // A text-only model, and a wrapped model of another type
+        let text_only = MockLanguageModel::new()
+            .with_name("text-only")
+            .with_capabilities(
+                CapabilitySet::empty()
+                    .with(Capability::TextInput)
+                    .with(Capability::TextOutput),
+            )
+            .with_error(Error::ApiError {
+                details: "unavailable".to_string(),
+                status_code: Some(reqwest::StatusCode::SERVICE_UNAVAILABLE),
+            });
+        let reply = MockLanguageModel::new()
+            .with_name("reply")
+            .with_text("reply")
+            .with_text("reply");
+        let reply = with_defaults(reply, LanguageModelOptions::default());
+
+        let models = vec![text_only.boxed(), reply.boxed()];
+        assert_eq!(
+            models.iter().map(|m| m.name()).collect::<Vec<_>>(),
+            ["text-only", "reply"]
+        );
+        assert!(!models[0].supports(Capability::ToolCall));
+        assert!(models[1].supports(Capability::ToolCall));
+
+        let mut model = FallbackModel::new(models.clone());
+        let response = model
+            .generate_text(LanguageModelOptions::default())
+            .await
+            .unwrap();
+        assert!(matches!(
+            &response.contents[0],
+            LanguageModelResponseContentType::Text(text) if text == "reply"
+        ));
+
+        let mut model: Box<dyn DynLanguageModel> = models[1].clone().into_inner();
+        assert_eq!(model.dyn_name(), "reply");
+        assert!(
+            model
+                .dyn_generate_text(LanguageModelOptions::default())
+                .await
+                .is_ok()
+        );
+    }
+}
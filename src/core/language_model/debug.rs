@@ -0,0 +1,201 @@
+//! Logging of the steps of requests built with `debug(true)`.
+//!
+//! Each step logs the options sent to the model, the response with its
+//! provider ID and a summary of its contents, and the executed tool calls, at
+//! the debug level under the `aisdk` target. Lines are emitted with `tracing`
+//! when the `tracing` feature is enabled, and with `log` otherwise.
+
+use crate::core::language_model::{LanguageModelOptions, LanguageModelResponseContentType, Usage};
+use crate::core::tools::{ToolCallInfo, ToolResultInfo};
+use crate::core::{AssistantMessage, Message};
+use std::fmt::{self, Write};
+
+/// The number of characters of a text kept in a summary.
+const MAX_TEXT_LEN: usize = 200;
+
+fn emit(args: fmt::Arguments<'_>) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(target: "aisdk", "{args}");
+    #[cfg(not(feature = "tracing"))]
+    log::debug!(target: "aisdk", "{args}");
+}
+
+/// Shortens a text to its first characters, on a single line.
+fn shorten(text: &str) -> String {
+    let text = text.replace('\n', "\\n");
+    match text.char_indices().nth(MAX_TEXT_LEN) {
+        Some((end, _)) => format!("{}... ({} chars)", &text[..end], text.chars().count()),
+        None => text,
+    }
+}
+
+fn describe_content(content: &LanguageModelResponseContentType) -> String {
+    match content {
+        LanguageModelResponseContentType::Text(text) => format!("text: {}", shorten(text)),
+        LanguageModelResponseContentType::ToolCall(call) => {
+            format!(
+                "tool call {} ({}): {}",
+                call.tool.name, call.tool.id, call.input
+            )
+        }
+        LanguageModelResponseContentType::Reasoning { content, .. } => {
+            format!("reasoning: {}", shorten(content))
+        }
+        LanguageModelResponseContentType::Source(source) => format!("source: {}", source.id()),
+        LanguageModelResponseContentType::Citation(citation) => {
+            format!("citation: {}", shorten(&citation.cited_text))
+        }
+        LanguageModelResponseContentType::NotSupported(kind) => format!("not supported: {kind}"),
+    }
+}
+
+fn describe_message(message: &Message) -> String {
+    match message {
+        Message::System(system) => format!("system: {}", shorten(&system.content)),
+        Message::Developer(content) => format!("developer: {}", shorten(content)),
+        Message::User(user) => format!("user: {}", shorten(&user.content.to_string())),
+        Message::Assistant(AssistantMessage { content, .. }) => {
+            format!("assistant {}", describe_content(content))
+        }
+        Message::Tool(result) => match &result.output {
+            Ok(output) => format!(
+                "tool result {}: {}",
+                result.tool.name,
+                shorten(&output.to_string())
+            ),
+            Err(error) => format!("tool error {}: {error}", result.tool.name),
+        },
+    }
+}
+
+/// Logs the options of a step sent to a model.
+pub(crate) fn log_request(model: &str, options: &LanguageModelOptions) {
+    let mut line = format!("step {} request to {model}", options.current_step_id);
+    let mut field = |name: &str, value: &dyn fmt::Debug| {
+        let _ = write!(line, ", {name}: {value:?}");
+    };
+    if let Some(system) = &options.system {
+        field("system", &shorten(system));
+    }
+    for (name, value) in [
+        ("temperature", options.temperature),
+        ("top_p", options.top_p),
+        ("presence_penalty", options.presence_penalty),
+        ("frequency_penalty", options.frequency_penalty),
+    ] {
+        if let Some(value) = value {
+            field(name, &value);
+        }
+    }
+    for (name, value) in [
+        ("top_k", options.top_k),
+        ("seed", options.seed),
+        ("max_output_tokens", options.max_output_tokens),
+        ("thinking_budget", options.thinking_budget),
+    ] {
+        if let Some(value) = value {
+            field(name, &value);
+        }
+    }
+    if let Some(stop_sequences) = &options.stop_sequences {
+        field("stop_sequences", stop_sequences);
+    }
+    if let Some(effort) = &options.reasoning_effort {
+        field("reasoning_effort", effort);
+    }
+    if let Some(tools) = &options.tools {
+        let names: Vec<String> = tools
+            .tools
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .map(|tool| tool.name.clone())
+            .collect();
+        field("tools", &names);
+    }
+    if let Some(tool_choice) = &options.tool_choice {
+        field("tool_choice", tool_choice);
+    }
+    if options.schema.is_some() {
+        field("schema", &true);
+    }
+    if let Some(id) = &options.previous_response_id {
+        field("previous_response_id", id);
+    }
+    let messages = options.messages();
+    field("messages", &messages.len());
+    emit(format_args!("{line}"));
+    for message in &messages {
+        emit(format_args!("  {}", describe_message(message)));
+    }
+}
+
+/// Logs the response of a model to a step.
+pub(crate) fn log_response(
+    model: &str,
+    step: usize,
+    response_id: Option<&str>,
+    contents: &[LanguageModelResponseContentType],
+    usage: Option<&Usage>,
+) {
+    let usage = usage.cloned().unwrap_or_default();
+    emit(format_args!(
+        "step {step} response from {model}, id: {}, input_tokens: {:?}, output_tokens: {:?}",
+        response_id.unwrap_or("none"),
+        usage.input_tokens,
+        usage.output_tokens,
+    ));
+    for content in contents {
+        emit(format_args!("  {}", describe_content(content)));
+    }
+}
+
+/// Logs an executed tool call.
+pub(crate) fn log_tool_call(step: usize, call: &ToolCallInfo, result: &ToolResultInfo) {
+    emit(format_args!(
+        "step {step} executed {} ({}) with {}",
+        call.tool.name, call.tool.id, call.input
+    ));
+    emit(format_args!(
+        "  {}",
+        describe_message(&Message::Tool(result.clone()))
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shorten() {
+        assert_eq!(shorten("Hello\nworld"), "Hello\\nworld");
+        let long = "é".repeat(MAX_TEXT_LEN + 5);
+        assert_eq!(
+            shorten(&long),
+            format!(
+                "{}... ({} chars)",
+                "é".repeat(MAX_TEXT_LEN),
+                MAX_TEXT_LEN + 5
+            )
+        );
+    }
+
+    #[test]
+    fn test_describe_message() {
+        let mut call = ToolCallInfo::new("get_weather");
+        call.id("call_1");
+        call.input(serde_json::json!({ "city": "Paris" }));
+        let message = Message::Assistant(AssistantMessage::new(
+            LanguageModelResponseContentType::ToolCall(call),
+            None,
+        ));
+        assert_eq!(
+            describe_message(&message),
+            r#"assistant tool call get_weather (call_1): {"city":"Paris"}"#
+        );
+        assert_eq!(
+            describe_message(&Message::User("Hi".to_string().into())),
+            "user: Hi"
+        );
+    }
+}
@@ -0,0 +1,351 @@
+//! Failover across multiple language models.
+//!
+//! [`FallbackModel`] tries a list of models in order and moves on to the next one
+//! when a model fails with a retryable error (rate limits, server errors and
+//! timeouts). Other errors, such as invalid requests, are returned immediately
+//! since another model would most likely fail the same way.
+//!
+//! For streaming calls, failover only happens while opening the stream. Errors that
+//! occur after the stream has started are reported on the stream itself.
+//!
+//! To fail over across providers, box the models with
+//! [`LanguageModel::boxed`] so they share a single type.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::{DynamicModel, LanguageModelRequest};
+//! use aisdk::core::language_model::fallback::FallbackModel;
+//! use aisdk::providers::OpenAI;
+//!
+//! # async fn run() -> aisdk::Result<()> {
+//! let model = FallbackModel::new(vec![
+//!     OpenAI::<DynamicModel>::model_name("gpt-5"),
+//!     OpenAI::<DynamicModel>::model_name("gpt-5-mini"),
+//! ])
+//! .with_retries(1)
+//! .on_failover(|event| eprintln!("{} failed, trying {}", event.from, event.to));
+//!
+//! let result = LanguageModelRequest::builder()
+//!     .model(model)
+//!     .prompt("What is the capital of France?")
+//!     .build()
+//!     .generate_text()
+//!     .await?;
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+use crate::core::Message;
+use crate::core::capabilities::{CapabilitySet, forward_capabilities};
+use crate::core::language_model::{
+    LanguageModel, LanguageModelOptions, LanguageModelResponse, ProviderStream,
+};
+use crate::core::pricing::ModelPricing;
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Information about a failover from one model to the next.
+#[derive(Debug, Clone)]
+pub struct FailoverEvent {
+    /// The name of the model that failed.
+    pub from: String,
+    /// The name of the model that is tried next.
+    pub to: String,
+    /// The last error returned by the failed model.
+    pub error: Error,
+}
+
+/// Hook called whenever a [`FallbackModel`] moves on to the next model.
+pub type OnFailoverHook = Arc<dyn Fn(&FailoverEvent) + Send + Sync>;
+
+#[derive(Debug, Clone)]
+struct FallbackEntry<M> {
+    model: M,
+    retries: u32,
+}
+
+/// A language model that falls back to the next model when one fails with a retryable error.
+#[derive(Clone)]
+pub struct FallbackModel<M: LanguageModel> {
+    entries: Vec<FallbackEntry<M>>,
+    on_failover: Option<OnFailoverHook>,
+}
+
+impl<M: LanguageModel> FallbackModel<M> {
+    /// Creates a new fallback model that tries the given models in order.
+    ///
+    /// Each model is tried once. Use [`with_retries`](Self::with_retries) or
+    /// [`with_model_retries`](Self::with_model_retries) to retry a model before
+    /// falling back to the next one.
+    pub fn new(models: Vec<M>) -> Self {
+        Self {
+            entries: models
+                .into_iter()
+                .map(|model| FallbackEntry { model, retries: 0 })
+                .collect(),
+            on_failover: None,
+        }
+    }
+
+    /// Sets the number of retries of every model before falling back to the next one.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        for entry in self.entries.iter_mut() {
+            entry.retries = retries;
+        }
+        self
+    }
+
+    /// Sets the number of retries of the model at `index` before falling back to the next one.
+    ///
+    /// Indexes out of range are ignored.
+    pub fn with_model_retries(mut self, index: usize, retries: u32) -> Self {
+        if let Some(entry) = self.entries.get_mut(index) {
+            entry.retries = retries;
+        }
+        self
+    }
+
+    /// Sets a hook that is called whenever a model fails and the next one is tried.
+    pub fn on_failover<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&FailoverEvent) + Send + Sync + 'static,
+    {
+        self.on_failover = Some(Arc::new(hook));
+        self
+    }
+
+    /// Returns the models in the order they are tried.
+    pub fn models(&self) -> impl Iterator<Item = &M> {
+        self.entries.iter().map(|e| &e.model)
+    }
+
+    /// Calls each model in order until one succeeds or fails with a non-retryable error.
+    async fn call<T, F>(&mut self, options: LanguageModelOptions, call: F) -> Result<T>
+    where
+        F: for<'a> Fn(&'a mut M, LanguageModelOptions) -> BoxFuture<'a, Result<T>>,
+    {
+        let mut last_error = None;
+
+        for index in 0..self.entries.len() {
+            let entry = &mut self.entries[index];
+            for attempt in 0..=entry.retries {
+                match call(&mut entry.model, options.clone()).await {
+                    Ok(result) => return Ok(result),
+                    Err(e) if e.is_retryable() => {
+                        log::warn!(
+                            "Model {} failed (attempt {}/{}): {e}",
+                            entry.model.name(),
+                            attempt + 1,
+                            entry.retries + 1
+                        );
+                        last_error = Some(e);
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+
+            if let (Some(next), Some(error)) = (self.entries.get(index + 1), &last_error) {
+                let event = FailoverEvent {
+                    from: self.entries[index].model.name(),
+                    to: next.model.name(),
+                    error: error.clone(),
+                };
+                log::warn!("Falling back from {} to {}", event.from, event.to);
+                if let Some(hook) = &self.on_failover {
+                    hook(&event);
+                }
+            }
+        }
+
+        Err(last_error
+            .unwrap_or_else(|| Error::InvalidInput("FallbackModel has no models".to_string())))
+    }
+}
+
+impl<M: LanguageModel> Debug for FallbackModel<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FallbackModel")
+            .field("entries", &self.entries)
+            .field("on_failover", &self.on_failover.is_some())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<M: LanguageModel> LanguageModel for FallbackModel<M> {
+    /// Returns the name of the primary model.
+    fn name(&self) -> String {
+        self.entries
+            .first()
+            .map(|e| e.model.name())
+            .unwrap_or_default()
+    }
+
+    async fn generate_text(
+        &mut self,
+        options: LanguageModelOptions,
+    ) -> Result<LanguageModelResponse> {
+        self.call(options, |model, options| model.generate_text(options))
+            .await
+    }
+
+    async fn stream_text(&mut self, options: LanguageModelOptions) -> Result<ProviderStream> {
+        self.call(options, |model, options| model.stream_text(options))
+            .await
+    }
+
+    /// Returns the pricing of the primary model.
+    fn pricing(&self) -> Option<ModelPricing> {
+        self.entries.first().and_then(|e| e.model.pricing())
+    }
+
+    /// Returns the smallest known context window, so requests fit any of the models.
+    fn context_window(&self) -> Option<usize> {
+        self.entries
+            .iter()
+            .filter_map(|e| e.model.context_window())
+            .min()
+    }
+
+    /// Returns the smallest known output limit, so requests fit any of the models.
+    fn max_output_tokens(&self) -> Option<usize> {
+        self.entries
+            .iter()
+            .filter_map(|e| e.model.max_output_tokens())
+            .min()
+    }
+
+    /// Returns the capabilities all of the models support, so requests work with any of them.
+    fn capabilities(&self) -> CapabilitySet {
+        self.entries.iter().fold(CapabilitySet::all(), |set, e| {
+            set.intersection(e.model.capabilities())
+        })
+    }
+
+    async fn count_tokens(&mut self, messages: &[Message]) -> Result<usize> {
+        match self.entries.first_mut() {
+            Some(entry) => entry.model.count_tokens(messages).await,
+            None => Ok(crate::core::tokens::estimate_messages_tokens(messages)),
+        }
+    }
+}
+
+forward_capabilities!(FallbackModel);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LanguageModelRequest;
+    use crate::core::language_model::LanguageModelResponseContentType;
+    use crate::providers::mock::{MockLanguageModel, MockResponse};
+    use std::sync::Mutex;
+
+    /// Fails with the given status code a number of times before answering with its name.
+    fn flaky(name: &str, status: u16, failures: usize) -> MockLanguageModel {
+        let model = MockLanguageModel::new().with_name(name);
+        for _ in 0..failures {
+            model.push(MockResponse::Error(Error::ApiError {
+                details: "failed".to_string(),
+                status_code: Some(reqwest::StatusCode::from_u16(status).unwrap()),
+            }));
+        }
+        model.with_text(name)
+    }
+
+    fn text(response: &LanguageModelResponse) -> &str {
+        match &response.contents[0] {
+            LanguageModelResponseContentType::Text(text) => text,
+            other => panic!("Expected text, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_model_uses_next_model_on_retryable_error() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        let mut model =
+            FallbackModel::new(vec![flaky("primary", 429, 1), flaky("secondary", 429, 0)])
+                .on_failover(move |event| {
+                    recorded
+                        .lock()
+                        .unwrap()
+                        .push((event.from.clone(), event.to.clone()));
+                });
+
+        let response = model
+            .generate_text(LanguageModelOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(text(&response), "secondary");
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![("primary".to_string(), "secondary".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fallback_model_returns_non_retryable_errors() {
+        let secondary = flaky("secondary", 400, 0);
+        let mut model = FallbackModel::new(vec![flaky("primary", 400, 1), secondary.clone()]);
+
+        let result = model.generate_text(LanguageModelOptions::default()).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::ApiError { status_code: Some(s), .. }) if s == reqwest::StatusCode::BAD_REQUEST
+        ));
+        assert!(secondary.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fallback_model_retries_within_budget() {
+        let primary = flaky("primary", 503, 2);
+        let mut model = FallbackModel::new(vec![primary.clone(), flaky("secondary", 503, 0)])
+            .with_model_retries(0, 2);
+
+        let response = model
+            .generate_text(LanguageModelOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(text(&response), "primary");
+        assert_eq!(primary.calls().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_model_returns_last_error_when_all_fail() {
+        let mut model =
+            FallbackModel::new(vec![flaky("primary", 500, 1), flaky("secondary", 502, 1)]);
+
+        let result = model.generate_text(LanguageModelOptions::default()).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::ApiError { status_code: Some(s), .. }) if s == reqwest::StatusCode::BAD_GATEWAY
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_model_builds_requests() {
+        let result = LanguageModelRequest::builder()
+            .model(FallbackModel::new(vec![
+                flaky("primary", 503, 1),
+                flaky("secondary", 503, 0),
+            ]))
+            .prompt("Hello")
+            .build()
+            .generate_text()
+            .await
+            .unwrap();
+
+        assert_eq!(result.text(), Some("secondary".to_string()));
+    }
+}
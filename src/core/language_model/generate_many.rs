@@ -0,0 +1,343 @@
+//! Concurrent generation of many requests.
+//!
+//! [`generate_many`] runs a set of requests with a bounded number of them in
+//! flight, which is the usual shape of offline evaluations and data labeling
+//! jobs. Requests failing with a transient error are retried, an optional
+//! [`RateLimiter`] keeps the job below the provider's limits, and a progress
+//! callback reports each finished request. The results are returned in the
+//! order of the requests.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::LanguageModelRequest;
+//! use aisdk::core::language_model::generate_many::generate_many;
+//! use aisdk::core::rate_limit::RateLimiter;
+//! use aisdk::providers::OpenAI;
+//!
+//! # async fn run() {
+//! let reviews = ["Great product!", "Broke after a week."];
+//! let requests = reviews.iter().map(|review| {
+//!     LanguageModelRequest::builder()
+//!         .model(OpenAI::gpt_5_mini())
+//!         .system("Label the sentiment of the review as positive or negative.")
+//!         .prompt(*review)
+//!         .build()
+//! });
+//!
+//! let results = generate_many(requests)
+//!     .concurrency(8)
+//!     .rate_limiter(RateLimiter::new().requests_per_minute(500))
+//!     .on_progress(|progress| println!("{}/{}", progress.completed, progress.total))
+//!     .run()
+//!     .await;
+//!
+//! for (review, result) in reviews.iter().zip(results) {
+//!     println!("{review}: {:?}", result.map(|response| response.text()));
+//! }
+//! # }
+//!# }
+//! ```
+
+use crate::core::language_model::generate_text::GenerateTextResponse;
+use crate::core::language_model::request::LanguageModelRequest;
+use crate::core::language_model::{LanguageModel, Usage};
+use crate::core::rate_limit::{RateLimiter, estimate_request_tokens};
+use crate::core::tokens::estimate_tokens;
+use crate::error::{Error, Result};
+use futures::StreamExt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// The number of requests in flight when it is not set.
+const DEFAULT_CONCURRENCY: usize = 4;
+
+/// The number of retries of a request when it is not set.
+const DEFAULT_MAX_RETRIES: u32 = 2;
+
+/// The first delay before retrying a request.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The progress of a [`GenerateMany`] job, reported after each request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// The index of the request that finished.
+    pub index: usize,
+    /// The number of requests that finished, successfully or not.
+    pub completed: usize,
+    /// The number of requests that failed after their retries.
+    pub failed: usize,
+    /// The number of requests of the job.
+    pub total: usize,
+}
+
+/// Hook called with the progress of a job after each request.
+pub type ProgressHook = Arc<dyn Fn(&Progress) + Send + Sync>;
+
+/// Runs many requests concurrently, created with [`generate_many`].
+pub struct GenerateMany<M: LanguageModel> {
+    requests: Vec<LanguageModelRequest<M>>,
+    concurrency: usize,
+    max_retries: u32,
+    rate_limiter: Option<RateLimiter>,
+    on_progress: Option<ProgressHook>,
+}
+
+/// Creates a job generating the text of each request, see the
+/// [module documentation](self).
+pub fn generate_many<M: LanguageModel>(
+    requests: impl IntoIterator<Item = LanguageModelRequest<M>>,
+) -> GenerateMany<M> {
+    GenerateMany {
+        requests: requests.into_iter().collect(),
+        concurrency: DEFAULT_CONCURRENCY,
+        max_retries: DEFAULT_MAX_RETRIES,
+        rate_limiter: None,
+        on_progress: None,
+    }
+}
+
+impl<M: LanguageModel> GenerateMany<M> {
+    /// Sets the maximum number of requests in flight. Defaults to 4.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Sets the number of times a request failing with a transient error, such
+    /// as a rate limit or an overloaded provider, is retried. Defaults to 2.
+    ///
+    /// Retries wait as long as the provider asked, or back off exponentially
+    /// starting at one second.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Shares a rate limiter between the requests.
+    ///
+    /// Each attempt reserves the estimated tokens of its prompt, corrected with
+    /// the usage of the generation once it has finished. Clones of the limiter
+    /// share its budget, so several jobs can be kept below one provider quota.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Sets a hook called with the progress of the job after each request.
+    pub fn on_progress(mut self, hook: impl Fn(&Progress) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Arc::new(hook));
+        self
+    }
+
+    /// Runs the requests and returns their results in the order of the requests.
+    pub async fn run(self) -> Vec<Result<GenerateTextResponse>> {
+        let total = self.requests.len();
+        let completed = AtomicUsize::new(0);
+        let failed = AtomicUsize::new(0);
+        let (completed, failed) = (&completed, &failed);
+        let max_retries = self.max_retries;
+        let rate_limiter = self.rate_limiter.as_ref();
+        let on_progress = self.on_progress.as_ref();
+
+        let mut results = futures::stream::iter(self.requests.into_iter().enumerate())
+            .map(|(index, mut request)| async move {
+                let result = generate_with_retries(&mut request, max_retries, rate_limiter).await;
+                let failed = match result {
+                    Ok(_) => failed.load(Ordering::SeqCst),
+                    Err(_) => failed.fetch_add(1, Ordering::SeqCst) + 1,
+                };
+                let completed = completed.fetch_add(1, Ordering::SeqCst) + 1;
+                if let Some(hook) = on_progress {
+                    hook(&Progress {
+                        index,
+                        completed,
+                        failed,
+                        total,
+                    });
+                }
+                (index, result)
+            })
+            .buffer_unordered(self.concurrency)
+            .collect::<Vec<_>>()
+            .await;
+        // Requests finish in any order, a slow one not holding back the others
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+}
+
+/// Returns the number of tokens of a usage.
+fn total_tokens(usage: &Usage) -> usize {
+    usage.input_tokens.unwrap_or(0) + usage.output_tokens.unwrap_or(0)
+}
+
+/// Generates the text of a request, retrying transient errors.
+async fn generate_with_retries<M: LanguageModel>(
+    request: &mut LanguageModelRequest<M>,
+    max_retries: u32,
+    rate_limiter: Option<&RateLimiter>,
+) -> Result<GenerateTextResponse> {
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 0;
+    loop {
+        let permit = match rate_limiter {
+            Some(limiter) => {
+                let prompt = request.prompt.as_deref().map(estimate_tokens).unwrap_or(0);
+                let tokens = estimate_request_tokens(&request.options) + prompt;
+                Some(limiter.acquire(tokens).await?)
+            }
+            None => None,
+        };
+        let result = request.generate_text().await;
+        if let (Some(permit), Ok(response)) = (permit, &result) {
+            permit.complete(total_tokens(&response.usage()));
+        }
+
+        match result {
+            Err(error) if error.is_retryable() && attempt < max_retries => {
+                let wait = match &error {
+                    Error::RateLimited {
+                        retry_after: Some(retry_after),
+                        ..
+                    } => *retry_after,
+                    _ => backoff,
+                };
+                log::debug!("Retrying request after {wait:?}: {error}");
+                crate::core::runtime::sleep(wait).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::MockLanguageModel;
+    use parking_lot::Mutex;
+
+    /// Echoes the prompt, failing the first attempt of prompts starting with
+    /// `flaky` and every attempt of prompts starting with `broken`. Later
+    /// prompts finish first.
+    fn echo_model(prompt: &str) -> MockLanguageModel {
+        let overloaded = || Error::Overloaded {
+            details: "busy".to_string(),
+            status_code: None,
+        };
+        let model =
+            MockLanguageModel::new().with_delay(Duration::from_millis(100 - prompt.len() as u64));
+        if prompt.starts_with("broken") {
+            model.with_error(overloaded()).with_error(overloaded())
+        } else if prompt.starts_with("flaky") {
+            model.with_error(overloaded()).with_text(prompt)
+        } else {
+            model.with_text(prompt)
+        }
+    }
+
+    fn request(model: &MockLanguageModel, prompt: &str) -> LanguageModelRequest<MockLanguageModel> {
+        LanguageModelRequest::builder()
+            .model(model.clone())
+            .prompt(prompt)
+            .build()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_generate_many_keeps_order_and_retries() {
+        let prompts = ["a", "bb", "flaky ccc", "broken", "ddddd"];
+        let models: Vec<_> = prompts.iter().map(|prompt| echo_model(prompt)).collect();
+        let progress = Arc::new(Mutex::new(Vec::new()));
+
+        let job = generate_many(
+            models
+                .iter()
+                .zip(prompts)
+                .map(|(model, prompt)| request(model, prompt)),
+        )
+        .concurrency(2)
+        .max_retries(1)
+        .on_progress({
+            let progress = progress.clone();
+            move |p| progress.lock().push(*p)
+        })
+        .run();
+        let started = async {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            models
+                .iter()
+                .filter(|model| !model.calls().is_empty())
+                .count()
+        };
+        let (results, started) = tokio::join!(job, started);
+
+        let texts: Vec<Option<String>> = results
+            .iter()
+            .map(|result| result.as_ref().ok().and_then(|response| response.text()))
+            .collect();
+        assert_eq!(
+            texts,
+            [
+                Some("a".to_string()),
+                Some("bb".to_string()),
+                Some("flaky ccc".to_string()),
+                None,
+                Some("ddddd".to_string()),
+            ]
+        );
+        assert!(matches!(results[3], Err(Error::Overloaded { .. })));
+        assert_eq!(models[2].calls().len(), 2);
+        assert_eq!(models[3].calls().len(), 2);
+        assert_eq!(started, 2);
+
+        let progress = progress.lock();
+        assert_eq!(progress.len(), 5);
+        assert_eq!(
+            progress.last(),
+            Some(&Progress {
+                index: progress.last().unwrap().index,
+                completed: 5,
+                failed: 1,
+                total: 5,
+            })
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_generate_many_starts_requests_behind_a_slow_one() {
+        let slow = MockLanguageModel::new()
+            .with_delay(Duration::from_secs(10))
+            .with_text("slow");
+        let models: Vec<_> = std::iter::once(slow)
+            .chain((0..4).map(|_| {
+                MockLanguageModel::new()
+                    .with_delay(Duration::from_millis(10))
+                    .with_text("fast")
+            }))
+            .collect();
+
+        let job = generate_many(models.iter().map(|model| request(model, "hi")))
+            .concurrency(2)
+            .run();
+        let started = async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            models
+                .iter()
+                .filter(|model| !model.calls().is_empty())
+                .count()
+        };
+        let (results, started) = tokio::join!(job, started);
+
+        assert_eq!(started, 5);
+        let texts: Vec<_> = results
+            .iter()
+            .map(|result| result.as_ref().unwrap().text().unwrap())
+            .collect();
+        assert_eq!(texts, ["slow", "fast", "fast", "fast", "fast"]);
+    }
+}
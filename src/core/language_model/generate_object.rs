@@ -0,0 +1,416 @@
+//! Object generation impl for the `LanguageModelRequest` trait.
+
+use crate::core::capabilities::Capability;
+use crate::core::language_model::{
+    LanguageModel, LanguageModelOptions, LanguageModelResponseContentType, ObjectOutput,
+    generate_text::GenerateTextResponse,
+    json_repair::{RepairAttempt, RepairStrategy},
+    request::LanguageModelRequest,
+};
+use crate::core::messages::{AssistantMessage, Message, TaggedMessage};
+use crate::core::tools::{Tool, ToolExecute, ToolList};
+use crate::error::{Error, Result};
+use schemars::{JsonSchema, Schema, json_schema, schema_for};
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+
+/// Name of the tool models without structured output answer with.
+pub const RESPOND_TOOL_NAME: &str = "respond";
+
+impl<M: LanguageModel> LanguageModelRequest<M> {
+    /// Generates an object of type `T`.
+    ///
+    /// Models supporting structured output generate the object with the schema
+    /// of `T`. Other models supporting tool calls are given a
+    /// [`respond`](RESPOND_TOOL_NAME) tool taking the schema as its input, and
+    /// the object is read from the arguments of their call, so structured
+    /// output works the same across providers.
+    ///
+    /// The [`output`](LanguageModelOptions::output) of the request changes what
+    /// is generated: one of a list of values, into a `String` or an enum, or any
+    /// JSON value, into a [`serde_json::Value`].
+    ///
+    /// Output that does not parse or match is repaired with the
+    /// [`json_repair`](LanguageModelOptions::json_repair) strategies of the
+    /// request, the model being asked again with the error for
+    /// [`RepairStrategy::Reask`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidInput`] if the model supports neither
+    /// structured output nor tool calls, and [`Error::Other`] if the generated
+    /// object does not match `T` or is not one of the values of an enum output.
+    pub async fn generate_object<T: JsonSchema + DeserializeOwned>(
+        &mut self,
+    ) -> Result<GenerateObjectResponse<T>> {
+        let output = self
+            .options
+            .output
+            .take()
+            .unwrap_or_else(|| ObjectOutput::Schema(schema_for!(T)));
+        let max_reasks = self
+            .options
+            .json_repair
+            .iter()
+            .find_map(|strategy| match strategy {
+                RepairStrategy::Reask { max_attempts } => Some(*max_attempts),
+                _ => None,
+            })
+            .unwrap_or(0);
+
+        let options = self.options.clone();
+        let mut repairs = Vec::new();
+        let mut reasks = 0;
+        loop {
+            let (object, response) = self.generate_object_once(&output, &mut repairs).await?;
+            let error = match object {
+                Ok(object) => {
+                    return Ok(GenerateObjectResponse {
+                        object,
+                        response,
+                        repairs,
+                    });
+                }
+                Err(error) if reasks == max_reasks => {
+                    return Err(Error::Other(format!(
+                        "Generated object does not match the schema: {error}"
+                    )));
+                }
+                Err(error) => error,
+            };
+
+            // Ask again in the same conversation, with the error
+            reasks += 1;
+            repairs.push(RepairAttempt {
+                strategy: RepairStrategy::Reask {
+                    max_attempts: max_reasks,
+                },
+                error: error.clone(),
+            });
+            let step = response.options.current_step_id;
+            let mut messages = response.options.messages;
+            messages.push(TaggedMessage::new(
+                step,
+                Message::User(
+                    format!(
+                        "The response could not be used: {error}. Respond again, following the schema."
+                    )
+                    .into(),
+                ),
+            ));
+            self.options = LanguageModelOptions {
+                messages,
+                current_step_id: step,
+                ..options.clone()
+            };
+        }
+    }
+
+    /// Generates an object once, returning the error of an object that does
+    /// not parse or match the output along with the response.
+    async fn generate_object_once<T: DeserializeOwned>(
+        &mut self,
+        output: &ObjectOutput,
+        repairs: &mut Vec<RepairAttempt>,
+    ) -> Result<(std::result::Result<T, String>, GenerateTextResponse)> {
+        let (value, response) = match output {
+            ObjectOutput::Schema(schema) => self.generate_value(schema.clone(), repairs).await?,
+            ObjectOutput::Enum(values) => {
+                // Structured outputs need an object, so the value is wrapped
+                let schema = json_schema!({
+                    "type": "object",
+                    "properties": { "result": { "type": "string", "enum": values } },
+                    "required": ["result"],
+                    "additionalProperties": false,
+                });
+                let (value, response) = self.generate_value(schema, repairs).await?;
+                let value = value.and_then(|mut value| {
+                    let value = value
+                        .get_mut("result")
+                        .map(serde_json::Value::take)
+                        .unwrap_or(value);
+                    if value
+                        .as_str()
+                        .is_some_and(|value| values.iter().any(|v| v == value))
+                    {
+                        Ok(value)
+                    } else {
+                        Err(format!("{value} is not one of {values:?}"))
+                    }
+                });
+                (value, response)
+            }
+            ObjectOutput::NoSchema => {
+                let instruction = "Respond with a JSON value only, without any other text.";
+                self.options.system = Some(match self.options.system.take() {
+                    Some(system) => format!("{system}\n\n{instruction}"),
+                    None => instruction.to_string(),
+                });
+                self.options.schema = None;
+                let response = self.generate_text().await?;
+                let value = response.parse_text(repairs).map_err(|e| e.to_string());
+                (value, response)
+            }
+        };
+
+        let object =
+            value.and_then(|value| serde_json::from_value(value).map_err(|e| e.to_string()));
+        Ok((object, response))
+    }
+
+    /// Generates a JSON value matching a schema, with structured output or
+    /// with the respond tool.
+    async fn generate_value(
+        &mut self,
+        schema: Schema,
+        repairs: &mut Vec<RepairAttempt>,
+    ) -> Result<(
+        std::result::Result<serde_json::Value, String>,
+        GenerateTextResponse,
+    )> {
+        if self.model.supports(Capability::StructuredOutput) {
+            self.options.schema = Some(schema);
+            let response = self.generate_text().await?;
+            let value = response.parse_text(repairs).map_err(|e| e.to_string());
+            return Ok((value, response));
+        }
+
+        if !self.model.supports(Capability::ToolCall) {
+            return Err(Error::InvalidInput(format!(
+                "{} supports neither structured output nor tool calls",
+                self.model.name()
+            )));
+        }
+
+        // Keep the tools of the request untouched, the list being shared
+        let mut tools = ToolList::new(
+            self.options
+                .tools
+                .as_ref()
+                .map(|tools| {
+                    tools
+                        .tools
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .clone()
+                })
+                .unwrap_or_default(),
+        );
+        tools.add_tool(Tool {
+            name: RESPOND_TOOL_NAME.to_string(),
+            description: "Responds to the user. Call it with the final answer.".to_string(),
+            input_schema: schema,
+            execute: ToolExecute::new(Box::new(|_| Ok("Responded".to_string()))),
+            strict: None,
+        });
+        self.options.schema = None;
+        self.options.tools = Some(tools);
+
+        let stop_when = self.options.stop_when.clone();
+        self.options.stop_when = Some(Arc::new(move |options| {
+            responded(options).is_some() || stop_when.as_ref().is_some_and(|stop| stop(options))
+        }));
+
+        let response = self.generate_text().await?;
+        let value = match responded(&response.options) {
+            Some(input) => Ok(input),
+            // The model answered without the tool
+            None => response.parse_text(repairs).map_err(|e| e.to_string()),
+        };
+        Ok((value, response))
+    }
+}
+
+/// Returns the input of the call to the respond tool in the latest step.
+fn responded(options: &LanguageModelOptions) -> Option<serde_json::Value> {
+    options
+        .messages
+        .iter()
+        .rev()
+        .take_while(|message| message.step_id == options.current_step_id)
+        .find_map(|message| match &message.message {
+            Message::Assistant(AssistantMessage {
+                content: LanguageModelResponseContentType::ToolCall(info),
+                ..
+            }) if info.tool.name == RESPOND_TOOL_NAME => Some(info.input.clone()),
+            _ => None,
+        })
+}
+
+/// Response from a [`generate_object`](LanguageModelRequest::generate_object) call.
+#[derive(Debug, Clone)]
+pub struct GenerateObjectResponse<T> {
+    /// The generated object.
+    pub object: T,
+    /// The response the object was read from, with the conversation and usage.
+    pub response: GenerateTextResponse,
+    /// The repairs applied to the output of the model, in order.
+    pub repairs: Vec<RepairAttempt>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::capabilities::CapabilitySet;
+    use crate::providers::MockLanguageModel;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, JsonSchema, PartialEq)]
+    struct Weather {
+        city: String,
+        celsius: i32,
+    }
+
+    #[derive(Debug, Deserialize, JsonSchema, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Sentiment {
+        Positive,
+        Negative,
+    }
+
+    /// Answers with a call to the respond tool if the model supports tool calls
+    /// but no structured output, or with JSON text otherwise.
+    fn request(
+        capabilities: CapabilitySet,
+        answer: serde_json::Value,
+    ) -> LanguageModelRequest<MockLanguageModel> {
+        let model = MockLanguageModel::new().with_capabilities(capabilities);
+        let model = if capabilities.contains(Capability::ToolCall)
+            && !capabilities.contains(Capability::StructuredOutput)
+        {
+            model.with_tool_call(RESPOND_TOOL_NAME, answer)
+        } else {
+            model.with_text(answer.to_string())
+        };
+        LanguageModelRequest::builder()
+            .model(model)
+            .prompt("Hello")
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_generate_object_uses_schema_or_respond_tool() {
+        let answer = serde_json::json!({ "city": "Paris", "celsius": 21 });
+        let expected = Weather {
+            city: "Paris".to_string(),
+            celsius: 21,
+        };
+
+        let response = request(CapabilitySet::all(), answer.clone())
+            .generate_object::<Weather>()
+            .await
+            .unwrap();
+        assert_eq!(response.object, expected);
+        assert!(response.response.options.schema.is_some());
+
+        let tools_only = CapabilitySet::empty().with(Capability::ToolCall);
+        let response = request(tools_only, answer.clone())
+            .generate_object::<Weather>()
+            .await
+            .unwrap();
+        assert_eq!(response.object, expected);
+        assert_eq!(response.response.options.current_step_id, 1);
+
+        let result = request(CapabilitySet::empty(), answer)
+            .generate_object::<Weather>()
+            .await;
+        assert!(matches!(result, Err(Error::InvalidInput(_))));
+    }
+
+    #[tokio::test]
+    async fn test_generate_object_enum_output() {
+        let values = vec!["positive".to_string(), "negative".to_string()];
+        let tools_only = CapabilitySet::empty().with(Capability::ToolCall);
+
+        for capabilities in [CapabilitySet::all(), tools_only] {
+            let mut request = request(capabilities, serde_json::json!({ "result": "negative" }));
+            request.output = Some(ObjectOutput::Enum(values.clone()));
+            let response = request.generate_object::<Sentiment>().await.unwrap();
+            assert_eq!(response.object, Sentiment::Negative);
+        }
+
+        let mut request = request(
+            CapabilitySet::all(),
+            serde_json::json!({ "result": "neutral" }),
+        );
+        request.output = Some(ObjectOutput::Enum(values));
+        let result = request.generate_object::<String>().await;
+        assert!(matches!(result, Err(Error::Other(_))));
+    }
+
+    #[tokio::test]
+    async fn test_generate_object_no_schema_output() {
+        let answer = serde_json::json!({ "anything": [1, 2] });
+        let mut request = request(CapabilitySet::empty(), answer.clone());
+        request.output = Some(ObjectOutput::NoSchema);
+
+        let response = request
+            .generate_object::<serde_json::Value>()
+            .await
+            .unwrap();
+        assert_eq!(response.object, answer);
+        assert!(response.response.options.schema.is_none());
+        assert!(
+            response
+                .response
+                .options
+                .system
+                .unwrap()
+                .contains("JSON value only")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_object_repairs_output() {
+        let model = MockLanguageModel::new()
+            .with_text("```json\n{\"city\": \"Paris\", \"celsius\": 21,}\n```")
+            .with_text("{\"city\": \"Paris\"}")
+            .with_text("{\"city\": \"Paris\", \"celsius\": 21");
+        let mut request = LanguageModelRequest::builder()
+            .model(model)
+            .prompt("Weather in Paris?")
+            .json_repair(RepairStrategy::LOCAL)
+            .build();
+
+        // Fences and trailing commas are repaired without asking again
+        let response = request.generate_object::<Weather>().await.unwrap();
+        assert_eq!(response.object.celsius, 21);
+        let strategies: Vec<_> = response.repairs.iter().map(|r| r.strategy).collect();
+        assert_eq!(
+            strategies,
+            [
+                RepairStrategy::StripMarkdownFences,
+                RepairStrategy::FixTrailingCommas
+            ]
+        );
+
+        // A missing field is only fixed by asking again
+        let result = request.generate_object::<Weather>().await;
+        assert!(matches!(result, Err(Error::Other(_))));
+
+        let mut request = LanguageModelRequest::builder()
+            .model(
+                MockLanguageModel::new()
+                    .with_text("{\"city\": \"Paris\"}")
+                    .with_text("{\"city\": \"Paris\", \"celsius\": 21"),
+            )
+            .prompt("Weather in Paris?")
+            .json_repair([
+                RepairStrategy::CompleteTruncated,
+                RepairStrategy::Reask { max_attempts: 1 },
+            ])
+            .build();
+        let response = request.generate_object::<Weather>().await.unwrap();
+        assert_eq!(response.object.celsius, 21);
+        assert_eq!(
+            response.repairs[0].strategy,
+            RepairStrategy::Reask { max_attempts: 1 }
+        );
+        assert!(response.repairs[0].error.contains("celsius"));
+        assert_eq!(
+            response.repairs[1].strategy,
+            RepairStrategy::CompleteTruncated
+        );
+        assert_eq!(response.response.options.current_step_id, 2);
+    }
+}
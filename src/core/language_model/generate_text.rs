@@ -5,16 +5,26 @@ use crate::{
     Error,
     core::{
         AssistantMessage, Message,
+        events::{self, RequestEvent, ResponseEvent},
         language_model::{
-            LanguageModel, LanguageModelOptions, LanguageModelResponse,
-            LanguageModelResponseContentType, StopReason, request::LanguageModelRequest,
+            Candidate, LanguageModel, LanguageModelOptions, LanguageModelResponse,
+            LanguageModelResponseContentType, StopReason, debug,
+            json_repair::{RepairAttempt, parse_repaired},
+            metrics::StepMetrics,
+            request::LanguageModelRequest,
+            stop_sequences::truncate_at_stop,
+            truncation::truncate_to_fit,
         },
         messages::TaggedMessage,
+        pricing::resolve_pricing,
+        runtime::Instant,
+        tools::ToolApproval,
         utils::resolve_message,
     },
 };
 use serde::de::DeserializeOwned;
 use serde::ser::Error as SerdeError;
+use std::collections::HashMap;
 use std::ops::Deref;
 
 impl<M: LanguageModel> LanguageModelRequest<M> {
@@ -68,40 +78,155 @@ impl<M: LanguageModel> LanguageModelRequest<M> {
 
         let mut options = LanguageModelOptions {
             system: (!system_prompt.is_empty()).then_some(system_prompt),
+            system_fn: self.options.system_fn.clone(),
             messages,
             schema: self.options.schema.to_owned(),
+            constrained_output: self.options.constrained_output.clone(),
+            output: self.options.output.clone(),
+            json_repair: self.options.json_repair.clone(),
             stop_sequences: self.options.stop_sequences.to_owned(),
             tools: self.options.tools.to_owned(),
+            tool_choice: self.options.tool_choice.clone(),
             stop_when: self.options.stop_when.clone(),
             on_step_start: self.options.on_step_start.clone(),
+            prepare_step: self.options.prepare_step.clone(),
             on_step_finish: self.options.on_step_finish.clone(),
+            approval_policy: self.options.approval_policy.clone(),
+            tool_approvals: self.options.tool_approvals.clone(),
+            on_chunk: self.options.on_chunk.clone(),
+            on_error: self.options.on_error.clone(),
+            on_finish: self.options.on_finish.clone(),
+            event_sinks: self.options.event_sinks.clone(),
+            input_guards: self.options.input_guards.clone(),
+            output_guards: self.options.output_guards.clone(),
+            memory: self.options.memory.clone(),
             stop_reason: None,
             headers: self.options.headers.clone(),
+            query: self.options.query.clone(),
+            previous_response_id: self.options.previous_response_id.clone(),
+            response_id: None,
+            request_metadata: self.options.request_metadata.clone(),
+            user: self.options.user.clone(),
+            token_logprobs: None,
+            provider_metadata: None,
+            response_sources: Vec::new(),
+            response_citations: Vec::new(),
+            step_metrics: Vec::new(),
+            step_response_metadata: HashMap::new(),
+            best_of: self.options.best_of.clone(),
+            generated_candidates: Vec::new(),
+            pricing: resolve_pricing(&self.model),
             ..self.options
         };
 
+        // Send the remembered conversation before the input
+        options.load_memory().await?;
+
         loop {
+            // Apply decisions on tool calls from a previous request
+            if !options.apply_tool_approvals().await.is_empty() {
+                options.stop_reason = Some(StopReason::ToolApprovalRequired);
+                break;
+            }
+
             // Update the current step
             options.current_step_id += 1;
 
             // Prepare the next step
+            options.regenerate_system();
             if let Some(hook) = options.on_step_start.clone() {
                 hook(&mut options);
             }
+            let mut step_settings = options
+                .prepare_step
+                .as_ref()
+                .map(|hook| hook(&options))
+                .unwrap_or_default();
 
-            let response: LanguageModelResponse = self
-                .model
-                .generate_text(options.clone())
-                .await
-                .inspect_err(|e| {
+            // Validate the prompt
+            if let Err(violation) = options.apply_input_guards() {
+                return Err(options.stop_on_violation(violation));
+            }
+
+            // Fit the conversation into the context window
+            let mut step_model = step_settings.model.take();
+            match step_model.as_mut() {
+                Some(model) => truncate_to_fit(model, &mut options).await,
+                None => truncate_to_fit(&mut self.model, &mut options).await,
+            }
+            let mut step_options = options.chained();
+            step_settings.apply(&mut step_options);
+            let model_name = match &step_model {
+                Some(model) => model.name(),
+                None => self.model.name(),
+            };
+            if events::has_sinks(&options.event_sinks) {
+                let request = RequestEvent {
+                    model: model_name.clone(),
+                    step: options.current_step_id,
+                    messages: step_options.messages(),
+                };
+                events::emit(&options.event_sinks, |sink| sink.on_request(&request));
+            }
+            if options.debug {
+                debug::log_request(&model_name, &step_options);
+            }
+
+            let started = Instant::now();
+            let response = match step_model.as_mut() {
+                Some(model) => generate_step(model, &mut options, step_options).await,
+                None => generate_step(&mut self.model, &mut options, step_options).await,
+            }
+            .inspect_err(|e| {
                 options.stop_reason = Some(StopReason::Error(e.clone()));
+                options.report_error(e);
             })?;
+            let duration = started.elapsed();
+            options.step_metrics.push(StepMetrics {
+                step: options.current_step_id,
+                time_to_first_token: Some(duration),
+                duration,
+                output_tokens: response.usage.as_ref().and_then(|u| u.output_tokens),
+            });
+            if options.debug {
+                debug::log_response(
+                    &model_name,
+                    options.current_step_id,
+                    response.id.as_deref(),
+                    &response.contents,
+                    response.usage.as_ref(),
+                );
+            }
+            if events::has_sinks(&options.event_sinks) {
+                let event = ResponseEvent {
+                    model: model_name,
+                    step: options.current_step_id,
+                    contents: response.contents.clone(),
+                    usage: response.usage.clone(),
+                };
+                events::emit(&options.event_sinks, |sink| sink.on_response(&event));
+            }
+            let mut metadata = response.metadata.clone().unwrap_or_default();
+            metadata.response_id = metadata.response_id.or_else(|| response.id.clone());
+            options.record_response_metadata(options.current_step_id, metadata);
+            options.record_response_id(response.id.clone());
+            options.token_logprobs = response.logprobs.clone();
+            options.provider_metadata = response.provider_metadata.clone();
 
+            let mut awaiting_approval = false;
             for output in response.contents.iter() {
                 match output {
                     LanguageModelResponseContentType::Text(text) => {
+                        let (text, _) = truncate_at_stop(
+                            text,
+                            options.stop_sequences.as_deref().unwrap_or_default(),
+                        );
+                        let text = match options.apply_output_guards(text) {
+                            Ok(text) => text,
+                            Err(violation) => return Err(options.stop_on_violation(violation)),
+                        };
                         let assistant_msg = Message::Assistant(AssistantMessage {
-                            content: text.clone().into(),
+                            content: text.into(),
                             usage: response.usage.clone(),
                         });
                         options
@@ -133,7 +258,17 @@ impl<M: LanguageModel> LanguageModelRequest<M> {
                                 usage,
                             )),
                         ));
-                        options.handle_tool_call(tool_info).await;
+                        if options.requires_approval(tool_info) {
+                            awaiting_approval = true;
+                        } else {
+                            options.handle_tool_call(tool_info).await;
+                        }
+                    }
+                    LanguageModelResponseContentType::Source(source) => {
+                        options.record_source(source);
+                    }
+                    LanguageModelResponseContentType::Citation(citation) => {
+                        options.record_citation(citation);
                     }
                     _ => (),
                 }
@@ -151,6 +286,11 @@ impl<M: LanguageModel> LanguageModelRequest<M> {
                 break;
             }
 
+            if awaiting_approval {
+                options.stop_reason = Some(StopReason::ToolApprovalRequired);
+                break;
+            }
+
             // Stop If
             if let Some(hook) = &options.stop_when.clone()
                 && hook(&options)
@@ -168,10 +308,70 @@ impl<M: LanguageModel> LanguageModelRequest<M> {
             };
         }
 
+        options.save_memory().await?;
+
+        if let Some(ref hook) = options.on_finish {
+            hook(&options);
+        }
+
         Ok(GenerateTextResponse { options })
     }
 }
 
+/// Generates the response of a step.
+///
+/// When several candidates are requested, the candidates the provider did not
+/// generate in the same request are requested separately, and the response of
+/// the candidate selected with `best_of` is returned.
+async fn generate_step<M: LanguageModel>(
+    model: &mut M,
+    options: &mut LanguageModelOptions,
+    step_options: LanguageModelOptions,
+) -> Result<LanguageModelResponse> {
+    let count = step_options.candidate_count.unwrap_or(1) as usize;
+    if count <= 1 {
+        return model.generate_text(step_options).await;
+    }
+
+    let single = LanguageModelOptions {
+        candidate_count: None,
+        ..step_options.clone()
+    };
+    let mut response = model.generate_text(step_options).await?;
+    let mut candidates = vec![Candidate {
+        contents: std::mem::take(&mut response.contents),
+        usage: response.usage.clone(),
+    }];
+    candidates.append(&mut response.candidates);
+
+    let requests = (candidates.len()..count).map(|_| {
+        let mut model = model.clone();
+        let options = single.clone();
+        async move { model.generate_text(options).await }
+    });
+    for extra in futures::future::try_join_all(requests).await? {
+        candidates.push(Candidate {
+            contents: extra.contents,
+            usage: extra.usage,
+        });
+    }
+
+    let index = options
+        .best_of
+        .as_ref()
+        .map_or(0, |best_of| best_of(&candidates))
+        .min(candidates.len() - 1);
+    let selected = &candidates[index];
+    response.contents = selected.contents.clone();
+    response.usage = selected.usage.clone().or(response.usage);
+    if index > 0 {
+        // Log probabilities are only returned for the first candidate
+        response.logprobs = None;
+    }
+    options.generated_candidates = candidates;
+    Ok(response)
+}
+
 // ============================================================================
 // Section: response types
 // ============================================================================
@@ -188,6 +388,8 @@ impl GenerateTextResponse {
     ///
     /// This method attempts to parse the generated text as JSON and deserialize it
     /// into the specified type `T`. It requires that the response contains text content.
+    /// Text that does not parse is repaired with the local
+    /// [`json_repair`](LanguageModelOptions::json_repair) strategies of the request.
     ///
     /// # Type Parameters
     ///
@@ -201,13 +403,75 @@ impl GenerateTextResponse {
     ///
     /// Returns an error if there is no text response or if deserialization fails.
     pub fn into_schema<T: DeserializeOwned>(&self) -> std::result::Result<T, serde_json::Error> {
+        self.parse_text(&mut Vec::new())
+    }
+
+    /// Deserializes the response text into a structured type, recording the
+    /// repairs applied.
+    pub(crate) fn parse_text<T: DeserializeOwned>(
+        &self,
+        repairs: &mut Vec<RepairAttempt>,
+    ) -> std::result::Result<T, serde_json::Error> {
         if let Some(text) = &self.text() {
-            serde_json::from_str(text)
+            parse_repaired(text, &self.options.json_repair, repairs)
         } else {
             Err(serde_json::Error::custom("No text response found"))
         }
     }
 
+    /// Serializes the conversation of the response to JSON.
+    ///
+    /// The output keeps the step of every message and can be used to resume the
+    /// conversation with [`LanguageModelRequest::from_messages_json`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the messages can not be serialized.
+    pub fn to_json(&self) -> std::result::Result<String, serde_json::Error> {
+        serde_json::to_string(&self.options.messages)
+    }
+
+    /// Continues the conversation after deciding on the tool calls waiting for approval.
+    ///
+    /// The decisions are applied when the returned request starts: approved tools
+    /// are executed, and denied calls are answered with a tool result telling the
+    /// model they were denied. Decisions can be given as `bool`, or as
+    /// [`ToolApproval`] to deny with a reason.
+    ///
+    /// # Parameters
+    ///
+    /// * `model` - The language model to continue with.
+    /// * `approvals` - Decisions by the `approval_id` of each
+    ///   [`ToolApprovalRequest`](crate::core::ToolApprovalRequest).
+    ///
+    /// # Returns
+    ///
+    /// A request that continues the conversation with the same options.
+    pub fn respond_to_approvals<M, K, V>(
+        &self,
+        model: M,
+        approvals: impl IntoIterator<Item = (K, V)>,
+    ) -> LanguageModelRequest<M>
+    where
+        M: LanguageModel,
+        K: Into<String>,
+        V: Into<ToolApproval>,
+    {
+        LanguageModelRequest {
+            model,
+            prompt: None,
+            options: LanguageModelOptions {
+                tool_approvals: approvals
+                    .into_iter()
+                    .map(|(id, approval)| (id.into(), approval.into()))
+                    .collect(),
+                stop_reason: None,
+                pricing: None,
+                ..self.options.clone()
+            },
+        }
+    }
+
     #[cfg(any(test, feature = "test-access"))]
     /// Returns the step ids of the messages in the response.
     pub fn step_ids(&self) -> Vec<usize> {
@@ -228,10 +492,655 @@ mod tests {
     use super::*;
     use crate::core::{
         AssistantMessage,
-        language_model::{LanguageModelResponseContentType, Usage},
+        language_model::{LanguageModelResponseContentType, ResponseMetadata, Usage},
         messages::TaggedMessage,
-        tools::{ToolCallInfo, ToolResultInfo},
+        tools::{ToolApproval, ToolCallInfo, ToolResultInfo},
     };
+    use crate::providers::mock::{MockLanguageModel, MockResponse};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_generate_text_calls_on_error_instead_of_on_finish() {
+        let errors = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = errors.clone();
+        let finished = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let finished_clone = finished.clone();
+
+        let result = LanguageModelRequest::builder()
+            .model(MockLanguageModel::new().with_error(Error::Other("boom".to_string())))
+            .prompt("Hello")
+            .on_error(move |e| recorded.lock().unwrap().push(e.clone()))
+            .on_finish(move |_| finished_clone.store(true, std::sync::atomic::Ordering::SeqCst))
+            .build()
+            .generate_text()
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(
+            *errors.lock().unwrap(),
+            vec![Error::Other("boom".to_string())]
+        );
+        assert!(!finished.load(std::sync::atomic::Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_selects_best_candidate() {
+        let model = MockLanguageModel::new()
+            .with_text("answer 0")
+            .with_text("answer 1")
+            .with_text("answer 2");
+        let response = LanguageModelRequest::builder()
+            .model(model.clone())
+            .prompt("Pick a number")
+            .candidates(3)
+            .best_of(|candidates| {
+                candidates
+                    .iter()
+                    .position(|c| c.text().as_deref() == Some("answer 1"))
+                    .unwrap_or(0)
+            })
+            .build()
+            .generate_text()
+            .await
+            .unwrap();
+
+        assert_eq!(model.calls().len(), 3);
+        assert_eq!(response.candidates().len(), 3);
+        assert_eq!(response.text().unwrap(), "answer 1");
+    }
+
+    #[cfg(feature = "prompt")]
+    #[tokio::test]
+    async fn test_generate_text_with_prompt_template() {
+        use crate::prompt::PromptTemplate;
+
+        #[derive(serde::Serialize)]
+        struct Vars {
+            city: &'static str,
+            days: u8,
+        }
+
+        let template = PromptTemplate::new("Plan {{ days }} days in {{ city }}.").unwrap();
+        let model = MockLanguageModel::new().with_text("Lisbon it is.");
+        LanguageModelRequest::builder()
+            .model(model.clone())
+            .prompt_template(
+                &template,
+                &Vars {
+                    city: "Lisbon",
+                    days: 3,
+                },
+            )
+            .unwrap()
+            .build()
+            .generate_text()
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            &model.calls()[0].messages()[0],
+            Message::User(u) if u.content == "Plan 3 days in Lisbon."
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_truncates_at_stop_sequence() {
+        let response = LanguageModelRequest::builder()
+            .model(MockLanguageModel::new().with_text("The answer is 4.</answer> Anything else?"))
+            .prompt("What is 2 + 2?")
+            .stop_sequences(vec!["</answer>".to_string()])
+            .build()
+            .generate_text()
+            .await
+            .unwrap();
+
+        assert_eq!(response.text().unwrap(), "The answer is 4.");
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_guards_rewrite_prompt_and_response() {
+        use crate::core::language_model::guardrails::GuardDecision;
+
+        let model = MockLanguageModel::new().with_text("your password is safe");
+        let response = LanguageModelRequest::builder()
+            .model(model.clone())
+            .prompt("my password is hunter2")
+            .input_guard(|text| GuardDecision::Rewrite(text.replace("hunter2", "[redacted]")))
+            .output_guard(|text| GuardDecision::Rewrite(text.to_uppercase()))
+            .build()
+            .generate_text()
+            .await
+            .unwrap();
+
+        assert_eq!(response.text().unwrap(), "YOUR PASSWORD IS SAFE");
+        assert!(matches!(
+            &model.calls()[0].messages()[0],
+            Message::User(u) if u.content == "my password is [redacted]"
+        ));
+        assert!(matches!(
+            &response.messages()[0],
+            Message::User(u) if u.content == "my password is [redacted]"
+        ));
+        assert_eq!(response.stop_reason(), Some(StopReason::Finish));
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_guard_rejects_response() {
+        use crate::core::language_model::guardrails::GuardDecision;
+        use crate::error::{GuardrailStage, GuardrailViolation};
+
+        let errors = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = errors.clone();
+        let result = LanguageModelRequest::builder()
+            .model(MockLanguageModel::new().with_text("Our competitors are..."))
+            .prompt("Tell me about competitors")
+            .output_guard(|text| {
+                if text.contains("competitors") {
+                    GuardDecision::Reject("Off topic".to_string())
+                } else {
+                    GuardDecision::Allow
+                }
+            })
+            .on_error(move |e| recorded.lock().unwrap().push(e.clone()))
+            .build()
+            .generate_text()
+            .await;
+
+        let violation = GuardrailViolation {
+            stage: GuardrailStage::Output,
+            reason: "Off topic".to_string(),
+        };
+        assert_eq!(
+            result.unwrap_err(),
+            Error::GuardrailViolation(violation.clone())
+        );
+        assert_eq!(
+            *errors.lock().unwrap(),
+            vec![Error::GuardrailViolation(violation)]
+        );
+    }
+
+    /// The responses of a model calling the `delete_file` tool once, then answering with text.
+    fn delete_file_responses() -> Vec<LanguageModelResponse> {
+        let mut call = ToolCallInfo::new("delete_file");
+        call.id("call_0");
+        call.input(serde_json::json!({ "path": "notes.txt" }));
+        let mut response = LanguageModelResponse::new("");
+        response.contents = vec![LanguageModelResponseContentType::ToolCall(call)];
+        vec![response, LanguageModelResponse::new("done")]
+    }
+
+    /// Calls the `delete_file` tool once, then answers with text.
+    fn tool_calling_model() -> MockLanguageModel {
+        let model = MockLanguageModel::new();
+        for response in delete_file_responses() {
+            model.push(MockResponse::Response(response));
+        }
+        model
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_stops_for_tool_approval() {
+        let executed = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let executed_clone = executed.clone();
+        let tool = crate::core::tools::Tool {
+            name: "delete_file".to_string(),
+            execute: crate::core::tools::ToolExecute::new(Box::new(move |_| {
+                executed_clone.store(true, std::sync::atomic::Ordering::SeqCst);
+                Ok("deleted".to_string())
+            })),
+            ..Default::default()
+        };
+
+        let mut response = LanguageModelRequest::builder()
+            .model(tool_calling_model())
+            .prompt("Delete notes.txt")
+            .with_tool(tool)
+            .needs_approval(["delete_file"])
+            .build()
+            .generate_text()
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            response.options.stop_reason,
+            Some(StopReason::ToolApprovalRequired)
+        ));
+        assert!(!executed.load(std::sync::atomic::Ordering::SeqCst));
+
+        let pending = response.options.pending_approvals();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].approval_id, "call_0");
+        assert_eq!(pending[0].tool_call.tool.name, "delete_file");
+
+        // A denial answers the call without executing the tool
+        response
+            .options
+            .resolve_approval(&pending[0].tool_call, &ToolApproval::denied("not allowed"))
+            .await;
+        assert!(response.options.pending_approvals().is_empty());
+        assert!(!executed.load(std::sync::atomic::Ordering::SeqCst));
+        assert_eq!(
+            response.options.tool_results().unwrap()[0].output,
+            Ok(serde_json::Value::String(
+                "Tool call denied: not allowed".to_string()
+            ))
+        );
+    }
+
+    /// Answers like `tool_calling_model`, numbering the responses `resp_1`, `resp_2`, ...
+    fn chaining_model() -> MockLanguageModel {
+        let model = MockLanguageModel::new();
+        for (step, mut response) in delete_file_responses().into_iter().enumerate() {
+            response.id = Some(format!("resp_{}", step + 1));
+            model.push(MockResponse::Response(response));
+        }
+        model
+    }
+
+    /// Returns the roles of the messages of a request.
+    fn roles(options: &LanguageModelOptions) -> Vec<&'static str> {
+        options
+            .messages()
+            .iter()
+            .map(|m| match m {
+                Message::System(_) => "system",
+                Message::User(_) => "user",
+                Message::Assistant(_) => "assistant",
+                Message::Tool(_) => "tool",
+                Message::Developer(_) => "developer",
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_chains_previous_response_ids() {
+        let tool = crate::core::tools::Tool {
+            name: "delete_file".to_string(),
+            execute: crate::core::tools::ToolExecute::new(Box::new(|_| Ok("deleted".to_string()))),
+            ..Default::default()
+        };
+        let model = chaining_model();
+
+        let response = LanguageModelRequest::builder()
+            .model(model.clone())
+            .system("Be brief")
+            .prompt("Delete notes.txt")
+            .with_tool(tool)
+            .previous_response_id("resp_0")
+            .build()
+            .generate_text()
+            .await
+            .unwrap();
+
+        // The tool call is stored with the first response, so only its result is sent
+        let requests: Vec<_> = model
+            .calls()
+            .iter()
+            .map(|options| (options.previous_response_id.clone(), roles(options)))
+            .collect();
+        assert_eq!(
+            requests,
+            vec![
+                (Some("resp_0".to_string()), vec!["system", "user"]),
+                (Some("resp_1".to_string()), vec!["system", "tool"]),
+            ]
+        );
+        assert_eq!(response.response_id.as_deref(), Some("resp_2"));
+        assert_eq!(response.text().as_deref(), Some("done"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_records_response_metadata_per_step() {
+        let tool = crate::core::tools::Tool {
+            name: "delete_file".to_string(),
+            execute: crate::core::tools::ToolExecute::new(Box::new(|_| Ok("deleted".to_string()))),
+            ..Default::default()
+        };
+
+        let model = MockLanguageModel::new();
+        for (step, mut response) in delete_file_responses().into_iter().enumerate() {
+            let request = step + 1;
+            response.id = Some(format!("resp_{request}"));
+            response.metadata = Some(ResponseMetadata {
+                request_id: Some(format!("req_{request}")),
+                response_id: None,
+                model: Some("metadata-2024-08-06".to_string()),
+            });
+            model.push(MockResponse::Response(response));
+        }
+
+        let response = LanguageModelRequest::builder()
+            .model(model)
+            .prompt("Delete notes.txt")
+            .with_tool(tool)
+            .build()
+            .generate_text()
+            .await
+            .unwrap();
+
+        let metadata = |request: usize| ResponseMetadata {
+            request_id: Some(format!("req_{request}")),
+            response_id: Some(format!("resp_{request}")),
+            model: Some("metadata-2024-08-06".to_string()),
+        };
+        assert_eq!(response.step(1).and_then(|s| s.metadata), Some(metadata(1)));
+        assert_eq!(response.step(2).and_then(|s| s.metadata), Some(metadata(2)));
+        assert_eq!(response.response_metadata(), Some(&metadata(2)));
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_regenerates_system_each_step() {
+        let tool = crate::core::tools::Tool {
+            name: "delete_file".to_string(),
+            execute: crate::core::tools::ToolExecute::new(Box::new(|_| Ok("deleted".to_string()))),
+            ..Default::default()
+        };
+        let prompts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = prompts.clone();
+
+        LanguageModelRequest::builder()
+            .model(chaining_model())
+            .system_fn(|options| {
+                let results = options
+                    .messages()
+                    .iter()
+                    .filter(|m| matches!(m, Message::Tool(_)))
+                    .count();
+                format!("Step {}, {results} tool results", options.current_step_id)
+            })
+            .prompt("Delete notes.txt")
+            .with_tool(tool)
+            .on_step_start(move |options| {
+                let system = options.messages().into_iter().find_map(|m| match m {
+                    Message::System(s) => Some(s.content),
+                    _ => None,
+                });
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push((options.system.clone().unwrap(), system.unwrap()));
+            })
+            .build()
+            .generate_text()
+            .await
+            .unwrap();
+
+        // The system message of the conversation follows the regenerated prompt
+        assert_eq!(
+            *prompts.lock().unwrap(),
+            vec![
+                (
+                    "Step 1, 0 tool results".to_string(),
+                    "Step 1, 0 tool results".to_string()
+                ),
+                (
+                    "Step 2, 1 tool results".to_string(),
+                    "Step 2, 1 tool results".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_records_metrics_of_each_step() {
+        let response = LanguageModelRequest::builder()
+            .model(chaining_model())
+            .prompt("Delete notes.txt")
+            .with_tool(crate::core::tools::Tool {
+                name: "delete_file".to_string(),
+                ..Default::default()
+            })
+            .build()
+            .generate_text()
+            .await
+            .unwrap();
+
+        let metrics = response.metrics();
+        let steps: Vec<usize> = metrics.steps.iter().map(|s| s.step).collect();
+        assert_eq!(steps, vec![1, 2]);
+        for step in &metrics.steps {
+            assert_eq!(step.time_to_first_token, Some(step.duration));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_prepare_step_overrides_model_and_tools() {
+        use crate::core::language_model::StepSettings;
+        use crate::core::tools::{Tool, ToolChoice, ToolExecute};
+
+        let reporting = MockLanguageModel::new().with_text("listed");
+        let step_model = reporting.clone();
+        let response = LanguageModelRequest::builder()
+            .model(tool_calling_model())
+            .prompt("Delete notes.txt")
+            .with_tool(Tool {
+                name: "delete_file".to_string(),
+                execute: ToolExecute::new(Box::new(|_| Ok("deleted".to_string()))),
+                ..Default::default()
+            })
+            .with_tool(Tool {
+                name: "list_files".to_string(),
+                ..Default::default()
+            })
+            .prepare_step(move |options| match options.current_step() {
+                1 => StepSettings::default(),
+                _ => StepSettings::default()
+                    .model(step_model.clone())
+                    .active_tools(["list_files"])
+                    .tool_choice(ToolChoice::Required),
+            })
+            .build()
+            .generate_text()
+            .await
+            .unwrap();
+
+        let calls = reporting.calls();
+        let tools: Vec<String> = calls[0]
+            .tools
+            .as_ref()
+            .map(|tools| {
+                let tools = tools.tools.lock().unwrap();
+                tools.iter().map(|t| t.name.clone()).collect()
+            })
+            .unwrap_or_default();
+        assert_eq!(response.text(), Some("listed".to_string()));
+        assert_eq!(calls[0].tool_choice, Some(ToolChoice::Required));
+        assert_eq!(tools, vec!["list_files".to_string()]);
+        assert_eq!(
+            response.tools.as_ref().unwrap().tools.lock().unwrap().len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_sends_events_to_sinks() {
+        use crate::core::events::{RequestEvent, ResponseEvent};
+
+        #[derive(Debug, Default)]
+        struct RecordingSink(std::sync::Mutex<Vec<String>>);
+
+        impl crate::core::events::EventSink for Arc<RecordingSink> {
+            fn on_request(&self, request: &RequestEvent) {
+                let event = format!("request:{}:{}", request.step, request.messages.len());
+                self.0.lock().unwrap().push(event);
+            }
+
+            fn on_response(&self, response: &ResponseEvent) {
+                let event = format!("response:{}:{}", response.step, response.contents.len());
+                self.0.lock().unwrap().push(event);
+            }
+
+            fn on_tool_call(
+                &self,
+                call: &crate::core::tools::ToolCallInfo,
+                result: &crate::core::tools::ToolResultInfo,
+            ) {
+                let event = format!(
+                    "tool_call:{}:{}",
+                    call.tool.name,
+                    result.output.as_ref().unwrap()
+                );
+                self.0.lock().unwrap().push(event);
+            }
+        }
+
+        let tool = crate::core::tools::Tool {
+            name: "delete_file".to_string(),
+            execute: crate::core::tools::ToolExecute::new(Box::new(|_| Ok("deleted".to_string()))),
+            ..Default::default()
+        };
+        let sink = Arc::new(RecordingSink::default());
+
+        LanguageModelRequest::builder()
+            .model(chaining_model())
+            .prompt("Delete notes.txt")
+            .with_tool(tool)
+            .event_sink(sink.clone())
+            .build()
+            .generate_text()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *sink.0.lock().unwrap(),
+            vec![
+                "request:1:1",
+                "response:1:1",
+                r#"tool_call:delete_file:"deleted""#,
+                "request:2:3",
+                "response:2:1",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_respond_to_approvals_continues_conversation() {
+        let tool = crate::core::tools::Tool {
+            name: "delete_file".to_string(),
+            execute: crate::core::tools::ToolExecute::new(Box::new(|_| Ok("deleted".to_string()))),
+            ..Default::default()
+        };
+
+        let response = LanguageModelRequest::builder()
+            .model(tool_calling_model())
+            .prompt("Delete notes.txt")
+            .with_tool(tool)
+            .needs_approval(["delete_file"])
+            .build()
+            .generate_text()
+            .await
+            .unwrap();
+
+        // Unknown ids are ignored, so the call is still waiting for a decision
+        let paused = response
+            .respond_to_approvals(MockLanguageModel::new(), [("call_9", true)])
+            .generate_text()
+            .await
+            .unwrap();
+        assert!(matches!(
+            paused.stop_reason(),
+            Some(StopReason::ToolApprovalRequired)
+        ));
+        assert_eq!(
+            paused.options.messages.len(),
+            response.options.messages.len()
+        );
+
+        let approved = response
+            .respond_to_approvals(
+                MockLanguageModel::new().with_text("done"),
+                [("call_0", true)],
+            )
+            .generate_text()
+            .await
+            .unwrap();
+        assert_eq!(
+            approved.tool_results().unwrap()[0].output,
+            Ok(serde_json::Value::String("deleted".to_string()))
+        );
+        assert_eq!(approved.text(), Some("done".to_string()));
+        assert!(matches!(approved.stop_reason(), Some(StopReason::Finish)));
+
+        let denied = response
+            .respond_to_approvals(
+                MockLanguageModel::new().with_text("done"),
+                [("call_0", ToolApproval::denied("not today"))],
+            )
+            .generate_text()
+            .await
+            .unwrap();
+        assert_eq!(
+            denied.tool_results().unwrap()[0].output,
+            Ok(serde_json::Value::String(
+                "Tool call denied: not today".to_string()
+            ))
+        );
+        assert_eq!(denied.text(), Some("done".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_conversation_restored_from_json_keeps_pending_approvals() {
+        let tool = || crate::core::tools::Tool {
+            name: "delete_file".to_string(),
+            execute: crate::core::tools::ToolExecute::new(Box::new(|_| Ok("deleted".to_string()))),
+            ..Default::default()
+        };
+
+        let response = LanguageModelRequest::builder()
+            .model(tool_calling_model())
+            .system("You manage files.")
+            .prompt("Delete notes.txt")
+            .with_tool(tool())
+            .needs_approval(["delete_file"])
+            .build()
+            .generate_text()
+            .await
+            .unwrap();
+        let json = response.to_json().unwrap();
+
+        let mut restored =
+            LanguageModelRequest::from_messages_json(MockLanguageModel::new(), &json)
+                .unwrap()
+                .with_tool(tool())
+                .needs_approval(["delete_file"])
+                .build();
+        assert_eq!(
+            serde_json::to_string(&restored.options.messages).unwrap(),
+            json
+        );
+        assert_eq!(
+            restored.options.current_step(),
+            response.options.current_step()
+        );
+        let pending = restored.options.pending_approvals();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].approval_id, "call_0");
+        assert_eq!(
+            pending[0].tool_call.input,
+            serde_json::json!({ "path": "notes.txt" })
+        );
+
+        let paused = restored.generate_text().await.unwrap();
+        assert!(matches!(
+            paused.stop_reason(),
+            Some(StopReason::ToolApprovalRequired)
+        ));
+
+        let approved = paused
+            .respond_to_approvals(
+                MockLanguageModel::new().with_text("done"),
+                [("call_0", true)],
+            )
+            .generate_text()
+            .await
+            .unwrap();
+        assert_eq!(
+            approved.tool_results().unwrap()[0].output,
+            Ok(serde_json::Value::String("deleted".to_string()))
+        );
+        assert_eq!(approved.text(), Some("done".to_string()));
+    }
 
     #[test]
     fn test_generate_text_response_step() {
@@ -334,6 +1243,7 @@ mod tests {
                             output_tokens: Some(5),
                             reasoning_tokens: Some(2),
                             cached_tokens: Some(1),
+                            cache_write_tokens: None,
                         }),
                     }),
                 ),
@@ -346,6 +1256,7 @@ mod tests {
                             output_tokens: Some(3),
                             reasoning_tokens: Some(1),
                             cached_tokens: Some(0),
+                            cache_write_tokens: None,
                         }),
                     }),
                 ),
@@ -361,6 +1272,113 @@ mod tests {
         assert_eq!(total_usage.cached_tokens, Some(1));
     }
 
+    #[test]
+    fn test_generate_text_response_usage_by_step() {
+        let options = LanguageModelOptions {
+            messages: vec![
+                TaggedMessage::new(0, Message::User("User".to_string().into())),
+                TaggedMessage::new(
+                    1,
+                    Message::Assistant(AssistantMessage {
+                        content: LanguageModelResponseContentType::Text("Assistant1".to_string()),
+                        usage: Some(Usage {
+                            input_tokens: Some(10),
+                            output_tokens: Some(5),
+                            reasoning_tokens: None,
+                            cached_tokens: None,
+                            cache_write_tokens: None,
+                        }),
+                    }),
+                ),
+                TaggedMessage::new(
+                    2,
+                    Message::Assistant(AssistantMessage {
+                        content: LanguageModelResponseContentType::Text("Assistant2".to_string()),
+                        usage: Some(Usage {
+                            input_tokens: Some(20),
+                            output_tokens: Some(7),
+                            reasoning_tokens: None,
+                            cached_tokens: None,
+                            cache_write_tokens: None,
+                        }),
+                    }),
+                ),
+            ],
+            ..Default::default()
+        };
+        let response = GenerateTextResponse { options };
+
+        let by_step = response.usage_by_step();
+        assert_eq!(by_step.len(), 3);
+        assert_eq!(by_step[0], (0, Usage::default()));
+        assert_eq!(by_step[1].0, 1);
+        assert_eq!(by_step[1].1.input_tokens, Some(10));
+        assert_eq!(by_step[2].0, 2);
+        assert_eq!(by_step[2].1.output_tokens, Some(7));
+    }
+
+    #[test]
+    fn test_generate_text_response_to_json_round_trip() {
+        let mut call = ToolCallInfo::new("get_weather");
+        call.id("call_1");
+        call.input(serde_json::json!({"city": "Paris"}));
+        let mut failed = ToolResultInfo::new("get_weather");
+        failed.id("call_1");
+        failed.output = Err(Error::ToolCallError("timeout".to_string()));
+
+        let options = LanguageModelOptions {
+            messages: vec![
+                TaggedMessage::new(0, Message::System("System".into())),
+                TaggedMessage::new(0, Message::User("User".into())),
+                TaggedMessage::new(
+                    1,
+                    Message::Assistant(AssistantMessage::new(
+                        LanguageModelResponseContentType::ToolCall(call.clone()),
+                        Some(Usage {
+                            input_tokens: Some(10),
+                            ..Default::default()
+                        }),
+                    )),
+                ),
+                TaggedMessage::new(1, Message::Tool(failed)),
+                create_text_assistant_message(2, "Done"),
+            ],
+            ..Default::default()
+        };
+        let json = GenerateTextResponse { options }.to_json().unwrap();
+
+        let messages: Vec<TaggedMessage> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            messages.iter().map(|m| m.step_id).collect::<Vec<_>>(),
+            vec![0, 0, 1, 1, 2]
+        );
+        assert!(matches!(&messages[0].message, Message::System(s) if s.content == "System"));
+        match &messages[2].message {
+            Message::Assistant(AssistantMessage {
+                content: LanguageModelResponseContentType::ToolCall(info),
+                usage,
+            }) => {
+                assert_eq!(info, &call);
+                assert_eq!(usage.as_ref().unwrap().input_tokens, Some(10));
+            }
+            other => panic!("Expected tool call, got {other:?}"),
+        }
+        match &messages[3].message {
+            Message::Tool(result) => {
+                assert_eq!(result.tool.id, "call_1");
+                assert!(matches!(&result.output, Err(Error::ToolCallError(e)) if e == "timeout"));
+            }
+            other => panic!("Expected tool result, got {other:?}"),
+        }
+        assert!(matches!(
+            &messages[4].message,
+            Message::Assistant(AssistantMessage {
+                content: LanguageModelResponseContentType::Text(t),
+                ..
+            }) if t == "Done"
+        ));
+    }
+
     fn create_tool_call_message(step_id: usize, tool_name: &str) -> TaggedMessage {
         TaggedMessage::new(
             step_id,
@@ -0,0 +1,121 @@
+//! Guards validating the prompt and the responses of a request.
+//!
+//! Input guards run on the latest user message before it is sent to the model,
+//! and output guards run on each text response of the model. A guard can allow
+//! the text, rewrite it (e.g., to redact personal data), or reject it, which stops
+//! generation with [`StopReason::Guardrail`](crate::core::language_model::StopReason::Guardrail)
+//! and an [`Error::GuardrailViolation`].
+//!
+//! Guards are set with
+//! [`input_guard`](crate::core::language_model::request::LanguageModelRequestBuilder::input_guard)
+//! and [`output_guard`](crate::core::language_model::request::LanguageModelRequestBuilder::output_guard),
+//! and run in the order they were added, each on the text left by the previous one.
+//! When streaming with output guards, the text of each step is sent once it has
+//! been validated, instead of as it is generated.
+
+use crate::core::Message;
+use crate::core::language_model::{LanguageModelOptions, StopReason};
+use crate::error::{Error, GuardrailStage, GuardrailViolation};
+use std::sync::Arc;
+
+/// The decision of a guard on a text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuardDecision {
+    /// The text is kept as is.
+    Allow,
+    /// The text is replaced.
+    Rewrite(String),
+    /// The text is rejected, with a reason.
+    Reject(String),
+}
+
+/// Type alias for a guard on the prompt or the responses of a request.
+pub type GuardHook = Arc<dyn Fn(&str) -> GuardDecision + Send + Sync>;
+
+/// Runs guards on a text, in order.
+fn run_guards(
+    guards: &[GuardHook],
+    stage: GuardrailStage,
+    mut text: String,
+) -> Result<String, GuardrailViolation> {
+    for guard in guards {
+        match guard(&text) {
+            GuardDecision::Allow => {}
+            GuardDecision::Rewrite(rewritten) => text = rewritten,
+            GuardDecision::Reject(reason) => return Err(GuardrailViolation { stage, reason }),
+        }
+    }
+    Ok(text)
+}
+
+impl LanguageModelOptions {
+    /// Runs the input guards on the latest message, if it is a user message.
+    ///
+    /// Steps that continue after tool calls end with other messages, so the
+    /// prompt is only guarded once.
+    pub(crate) fn apply_input_guards(&mut self) -> Result<(), GuardrailViolation> {
+        if self.input_guards.is_empty() {
+            return Ok(());
+        }
+        let Some(Message::User(prompt)) = self.messages.last_mut().map(|m| &mut m.message) else {
+            return Ok(());
+        };
+
+        let content = std::mem::take(&mut prompt.content);
+        prompt.content = run_guards(&self.input_guards, GuardrailStage::Input, content)?;
+        Ok(())
+    }
+
+    /// Runs the output guards on a text response.
+    pub(crate) fn apply_output_guards(&self, text: String) -> Result<String, GuardrailViolation> {
+        run_guards(&self.output_guards, GuardrailStage::Output, text)
+    }
+
+    /// Stops generation after a guard rejected a text, and returns the error.
+    pub(crate) fn stop_on_violation(&mut self, violation: GuardrailViolation) -> Error {
+        self.stop_reason = Some(StopReason::Guardrail(violation.clone()));
+        let error = Error::GuardrailViolation(violation);
+        self.report_error(&error);
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guards_run_in_order() {
+        let guards: Vec<GuardHook> = vec![
+            Arc::new(|text: &str| GuardDecision::Rewrite(text.replace("secret", "[redacted]"))),
+            Arc::new(|text: &str| {
+                if text.contains("secret") {
+                    GuardDecision::Reject("Leaked secret".to_string())
+                } else {
+                    GuardDecision::Allow
+                }
+            }),
+        ];
+
+        assert_eq!(
+            run_guards(&guards, GuardrailStage::Output, "the secret".to_string()),
+            Ok("the [redacted]".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rejection_stops_the_guards() {
+        let guards: Vec<GuardHook> = vec![
+            Arc::new(|_: &str| GuardDecision::Reject("Off topic".to_string())),
+            Arc::new(|_: &str| panic!("Guard after a rejection")),
+        ];
+
+        assert_eq!(
+            run_guards(&guards, GuardrailStage::Input, "Hi".to_string()),
+            Err(GuardrailViolation {
+                stage: GuardrailStage::Input,
+                reason: "Off topic".to_string(),
+            })
+        );
+    }
+}
@@ -0,0 +1,211 @@
+//! Repairs of malformed JSON generated by language models.
+//!
+//! Models asked for structured output sometimes wrap the JSON in markdown
+//! fences, leave trailing commas, or stop before the end of the output. The
+//! [`RepairStrategy`]s set on a request are applied in order until the text
+//! parses.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// A way of repairing malformed JSON output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairStrategy {
+    /// Keeps only the content of a markdown code block, such as ```` ```json ````.
+    StripMarkdownFences,
+    /// Removes commas before a closing bracket or brace.
+    FixTrailingCommas,
+    /// Closes the strings, arrays and objects left open by truncated output.
+    CompleteTruncated,
+    /// Asks the model again with the parse error, up to `max_attempts` times.
+    ///
+    /// Only applied by
+    /// [`generate_object`](crate::core::LanguageModelRequest::generate_object).
+    Reask {
+        /// The maximum number of times the model is asked again.
+        max_attempts: u32,
+    },
+}
+
+impl RepairStrategy {
+    /// The strategies repairing the text without calling the model again.
+    pub const LOCAL: [RepairStrategy; 3] = [
+        RepairStrategy::StripMarkdownFences,
+        RepairStrategy::FixTrailingCommas,
+        RepairStrategy::CompleteTruncated,
+    ];
+
+    /// Applies the strategy to the text, returning `None` if it does not
+    /// change it or calls the model.
+    fn apply(&self, text: &str) -> Option<String> {
+        let repaired = match self {
+            RepairStrategy::StripMarkdownFences => strip_markdown_fences(text),
+            RepairStrategy::FixTrailingCommas => fix_trailing_commas(text),
+            RepairStrategy::CompleteTruncated => complete_truncated(text),
+            RepairStrategy::Reask { .. } => return None,
+        };
+        (repaired != text).then_some(repaired)
+    }
+}
+
+/// A repair applied to the output of a model.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepairAttempt {
+    /// The strategy applied.
+    pub strategy: RepairStrategy,
+    /// The parse error of the output before the repair.
+    pub error: String,
+}
+
+/// Parses the text, applying the strategies in order until it parses and
+/// recording the repairs applied.
+pub(crate) fn parse_repaired<T: DeserializeOwned>(
+    text: &str,
+    strategies: &[RepairStrategy],
+    attempts: &mut Vec<RepairAttempt>,
+) -> Result<T, serde_json::Error> {
+    let mut text = text.to_string();
+    let mut result = serde_json::from_str(&text);
+    for strategy in strategies {
+        let Err(error) = &result else { break };
+        if let Some(repaired) = strategy.apply(&text) {
+            attempts.push(RepairAttempt {
+                strategy: *strategy,
+                error: error.to_string(),
+            });
+            text = repaired;
+            result = serde_json::from_str(&text);
+        }
+    }
+    result
+}
+
+fn strip_markdown_fences(text: &str) -> String {
+    let Some(start) = text.find("```") else {
+        return text.to_string();
+    };
+    // Skip the language of the block
+    let content = &text[start + 3..];
+    let content = content.find('\n').map_or("", |line| &content[line + 1..]);
+    let end = content.find("```").unwrap_or(content.len());
+    content[..end].trim().to_string()
+}
+
+fn fix_trailing_commas(text: &str) -> String {
+    let mut fixed = String::with_capacity(text.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if in_string {
+            in_string = escaped || c != '"';
+            escaped = !escaped && c == '\\';
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' && matches!(text[i + 1..].trim_start().chars().next(), Some('}' | ']')) {
+            continue;
+        }
+        fixed.push(c);
+    }
+    fixed
+}
+
+fn complete_truncated(text: &str) -> String {
+    let mut closers = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in text.chars() {
+        if in_string {
+            in_string = escaped || c != '"';
+            escaped = !escaped && c == '\\';
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => closers.push('}'),
+            '[' => closers.push(']'),
+            '}' | ']' => {
+                closers.pop();
+            }
+            _ => (),
+        }
+    }
+
+    let mut completed = text.trim_end().to_string();
+    if in_string {
+        completed.push('"');
+    }
+    if completed.ends_with(',') {
+        completed.pop();
+    } else if completed.ends_with(':') {
+        completed.push_str("null");
+    }
+    completed.extend(closers.iter().rev());
+    completed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn repair(text: &str) -> (Result<Value, serde_json::Error>, Vec<RepairAttempt>) {
+        let mut attempts = Vec::new();
+        let result = parse_repaired(text, &RepairStrategy::LOCAL, &mut attempts);
+        (result, attempts)
+    }
+
+    #[test]
+    fn test_valid_json_is_not_repaired() {
+        let (value, attempts) = repair(r#"{"a": [1, 2]}"#);
+        assert_eq!(value.unwrap(), json!({ "a": [1, 2] }));
+        assert!(attempts.is_empty());
+    }
+
+    #[test]
+    fn test_markdown_fences_are_stripped() {
+        let (value, attempts) = repair("Here it is:\n```json\n{\"a\": [1]}\n```\nDone.");
+        assert_eq!(value.unwrap(), json!({ "a": [1] }));
+        assert_eq!(attempts.len(), 1);
+        assert_eq!(attempts[0].strategy, RepairStrategy::StripMarkdownFences);
+
+        let (value, _) = repair("```json\n{\"a\": 1}\n```");
+        assert_eq!(value.unwrap(), json!({ "a": 1 }));
+    }
+
+    #[test]
+    fn test_trailing_commas_are_removed_outside_strings() {
+        let (value, attempts) = repair(r#"{"a": [1, 2,], "b": ", }",}"#);
+        assert_eq!(value.unwrap(), json!({ "a": [1, 2], "b": ", }" }));
+        assert_eq!(attempts.len(), 1);
+        assert_eq!(attempts[0].strategy, RepairStrategy::FixTrailingCommas);
+    }
+
+    #[test]
+    fn test_truncated_json_is_completed() {
+        let (value, _) = repair(r#"{"a": [1, 2], "b": {"c": "unfinish"#);
+        assert_eq!(
+            value.unwrap(),
+            json!({ "a": [1, 2], "b": { "c": "unfinish" } })
+        );
+
+        let (value, _) = repair(r#"{"a": 1, "b":"#);
+        assert_eq!(value.unwrap(), json!({ "a": 1, "b": null }));
+
+        let (value, attempts) = repair(r#"[{"a": 1},"#);
+        assert_eq!(value.unwrap(), json!([{ "a": 1 }]));
+        assert_eq!(attempts[0].strategy, RepairStrategy::CompleteTruncated);
+    }
+
+    #[test]
+    fn test_only_given_strategies_are_applied() {
+        let mut attempts = Vec::new();
+        let result = parse_repaired::<Value>(
+            "```\n{\"a\": 1,}\n```",
+            &[RepairStrategy::StripMarkdownFences],
+            &mut attempts,
+        );
+        assert!(result.is_err());
+        assert_eq!(attempts.len(), 1);
+    }
+}
@@ -0,0 +1,91 @@
+//! Log probabilities of generated tokens.
+//!
+//! When a request sets [`logprobs`](crate::core::language_model::LanguageModelOptions::logprobs),
+//! providers that support it return the log probability of every generated token,
+//! along with the most likely alternatives. They can be used to score the
+//! confidence of an answer, or to read the probabilities of the labels of a
+//! classifier prompt.
+
+use serde::{Deserialize, Serialize};
+
+/// The log probability of a generated token, with the most likely alternatives.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenLogprob {
+    /// The generated token.
+    pub token: String,
+    /// The natural logarithm of the probability of the token.
+    pub logprob: f64,
+    /// The most likely tokens at this position, most likely first.
+    #[serde(default)]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+impl TokenLogprob {
+    /// Returns the probability of the token, between 0 and 1.
+    pub fn probability(&self) -> f64 {
+        self.logprob.exp()
+    }
+}
+
+/// A likely token at the position of a generated token.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopLogprob {
+    /// The token.
+    pub token: String,
+    /// The natural logarithm of the probability of the token.
+    pub logprob: f64,
+}
+
+impl TopLogprob {
+    /// Returns the probability of the token, between 0 and 1.
+    pub fn probability(&self) -> f64 {
+        self.logprob.exp()
+    }
+}
+
+/// Returns the geometric mean of the probabilities of the tokens, between 0 and 1.
+///
+/// This is the probability of the text normalized by its length, a common
+/// confidence score. Returns `None` if there are no tokens.
+///
+/// # Examples
+///
+/// ```rust
+/// use aisdk::core::language_model::logprobs::{TokenLogprob, confidence};
+///
+/// let token = |logprob: f64| TokenLogprob {
+///     token: "a".to_string(),
+///     logprob,
+///     top_logprobs: vec![],
+/// };
+/// let score = confidence(&[token(0.5f64.ln()), token(0.5f64.ln())]).unwrap();
+/// assert!((score - 0.5).abs() < 1e-9);
+/// ```
+pub fn confidence(logprobs: &[TokenLogprob]) -> Option<f64> {
+    if logprobs.is_empty() {
+        return None;
+    }
+    let mean = logprobs.iter().map(|t| t.logprob).sum::<f64>() / logprobs.len() as f64;
+    Some(mean.exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probabilities_and_confidence() {
+        let token = TokenLogprob {
+            token: "yes".to_string(),
+            logprob: 0.0,
+            top_logprobs: vec![TopLogprob {
+                token: "no".to_string(),
+                logprob: f64::NEG_INFINITY,
+            }],
+        };
+        assert_eq!(token.probability(), 1.0);
+        assert_eq!(token.top_logprobs[0].probability(), 0.0);
+        assert_eq!(confidence(&[token]), Some(1.0));
+        assert_eq!(confidence(&[]), None);
+    }
+}
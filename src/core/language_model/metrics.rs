@@ -0,0 +1,115 @@
+//! Latency and throughput metrics of generations.
+//!
+//! Every step records how long the model took to send its first token and its
+//! whole response, and how many tokens it generated. They are collected in
+//! [`metrics`](crate::core::language_model::LanguageModelOptions::metrics) on the
+//! response, and streamed as a final
+//! [`Metrics`](crate::core::LanguageModelStreamChunkType::Metrics) chunk, so the
+//! performance of providers can be compared and monitored.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The metrics of a step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepMetrics {
+    /// The ID of the step.
+    pub step: usize,
+    /// The time between sending the request and receiving the first token.
+    ///
+    /// Responses that are not streamed arrive at once, so it is their duration.
+    pub time_to_first_token: Option<Duration>,
+    /// The time between sending the request and receiving the whole response.
+    pub duration: Duration,
+    /// The number of tokens generated, if the provider reported it.
+    pub output_tokens: Option<usize>,
+}
+
+impl StepMetrics {
+    /// Returns the number of tokens generated per second, if the provider
+    /// reported the number of tokens.
+    ///
+    /// For streamed responses, the time before the first token is not counted,
+    /// so it measures the generation speed rather than the latency.
+    pub fn tokens_per_second(&self) -> Option<f64> {
+        let tokens = self.output_tokens?;
+        let elapsed = match self.time_to_first_token {
+            Some(first_token) if first_token < self.duration => self.duration - first_token,
+            _ => self.duration,
+        };
+        (!elapsed.is_zero()).then(|| tokens as f64 / elapsed.as_secs_f64())
+    }
+}
+
+/// The metrics of a generation, by step.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Metrics {
+    /// The metrics of each step, in order.
+    pub steps: Vec<StepMetrics>,
+}
+
+impl Metrics {
+    /// Returns the time before the first token of the first step.
+    pub fn time_to_first_token(&self) -> Option<Duration> {
+        self.steps.first()?.time_to_first_token
+    }
+
+    /// Returns the time spent waiting for the model across all steps.
+    ///
+    /// Tool executions between the steps are not counted.
+    pub fn duration(&self) -> Duration {
+        self.steps.iter().map(|step| step.duration).sum()
+    }
+
+    /// Returns the number of tokens generated across all steps, if the provider
+    /// reported it for any of them.
+    pub fn output_tokens(&self) -> Option<usize> {
+        self.steps
+            .iter()
+            .filter_map(|step| step.output_tokens)
+            .reduce(|a, b| a + b)
+    }
+
+    /// Returns the number of tokens generated per second across all steps,
+    /// including the time before their first tokens.
+    pub fn tokens_per_second(&self) -> Option<f64> {
+        let tokens = self.output_tokens()?;
+        let duration = self.duration();
+        (!duration.is_zero()).then(|| tokens as f64 / duration.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_per_second() {
+        let step = StepMetrics {
+            step: 1,
+            time_to_first_token: Some(Duration::from_millis(500)),
+            duration: Duration::from_millis(2500),
+            output_tokens: Some(100),
+        };
+        assert_eq!(step.tokens_per_second(), Some(50.0));
+
+        let metrics = Metrics {
+            steps: vec![
+                step,
+                StepMetrics {
+                    step: 2,
+                    time_to_first_token: None,
+                    duration: Duration::from_millis(1500),
+                    output_tokens: None,
+                },
+            ],
+        };
+        assert_eq!(
+            metrics.time_to_first_token(),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(metrics.duration(), Duration::from_secs(4));
+        assert_eq!(metrics.output_tokens(), Some(100));
+        assert_eq!(metrics.tokens_per_second(), Some(25.0));
+    }
+}
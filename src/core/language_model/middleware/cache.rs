@@ -0,0 +1,552 @@
+//! Response caching middleware.
+//!
+//! [`CacheMiddleware`] serves repeated requests from a [`CacheStore`] instead of
+//! calling the model again. Requests are keyed on their content (model, prompt,
+//! messages, tools and sampling settings), so any change to the request results in
+//! a cache miss.
+//!
+//! Cached responses are also served to streaming calls by replaying them as a
+//! simulated stream, and streamed responses are cached once they have finished.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::LanguageModelRequest;
+//! use aisdk::core::language_model::middleware::{cache::CacheMiddleware, wrap_model};
+//! use aisdk::providers::OpenAI;
+//!
+//! # async fn run() -> aisdk::Result<()> {
+//! let model = wrap_model(OpenAI::gpt_5(), CacheMiddleware::in_memory(100));
+//!
+//! let result = LanguageModelRequest::builder()
+//!     .model(model)
+//!     .prompt("What is the capital of France?")
+//!     .build()
+//!     .generate_text()
+//!     .await?;
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+use crate::core::language_model::logprobs::TokenLogprob;
+use crate::core::language_model::middleware::simulate_streaming::{
+    StreamGranularity, chunk_response,
+};
+use crate::core::language_model::middleware::{LanguageModelMiddleware, Next};
+use crate::core::language_model::{
+    LanguageModelOptions, LanguageModelResponse, LanguageModelResponseContentType,
+    LanguageModelStreamChunk, LanguageModelStreamChunkType, ProviderStream, Usage,
+};
+use crate::error::Result;
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use futures::{Stream, ready};
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Storage backend for cached language model responses.
+#[async_trait]
+pub trait CacheStore: Send + Sync + std::fmt::Debug {
+    /// Returns the cached response for the given key, if any.
+    async fn get(&self, key: &str) -> Option<LanguageModelResponse>;
+
+    /// Stores a response under the given key.
+    async fn set(&self, key: &str, response: LanguageModelResponse);
+}
+
+/// An in-memory [`CacheStore`] that evicts the least recently used entry when full.
+#[derive(Debug)]
+pub struct InMemoryCacheStore {
+    capacity: usize,
+    inner: Mutex<LruState>,
+}
+
+#[derive(Debug, Default)]
+struct LruState {
+    entries: HashMap<String, LanguageModelResponse>,
+    /// Keys ordered from least to most recently used.
+    order: VecDeque<String>,
+}
+
+impl LruState {
+    fn touch(&mut self, key: &str) {
+        if let Some(index) = self.order.iter().position(|k| k == key) {
+            let key = self.order.remove(index).unwrap_or_default();
+            self.order.push_back(key);
+        }
+    }
+}
+
+impl InMemoryCacheStore {
+    /// Creates a new store holding at most `capacity` responses.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(LruState::default()),
+        }
+    }
+
+    /// Returns the number of cached responses.
+    pub fn len(&self) -> usize {
+        self.inner.lock().entries.len()
+    }
+
+    /// Returns `true` if no responses are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait]
+impl CacheStore for InMemoryCacheStore {
+    async fn get(&self, key: &str) -> Option<LanguageModelResponse> {
+        let mut state = self.inner.lock();
+        let response = state.entries.get(key).cloned()?;
+        state.touch(key);
+        Some(response)
+    }
+
+    async fn set(&self, key: &str, response: LanguageModelResponse) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut state = self.inner.lock();
+        if state.entries.insert(key.to_string(), response).is_some() {
+            state.touch(key);
+            return;
+        }
+
+        state.order.push_back(key.to_string());
+        while state.order.len() > self.capacity {
+            if let Some(evicted) = state.order.pop_front() {
+                state.entries.remove(&evicted);
+            }
+        }
+    }
+}
+
+/// Middleware that serves repeated requests from a [`CacheStore`].
+#[derive(Debug, Clone)]
+pub struct CacheMiddleware {
+    store: Arc<dyn CacheStore>,
+}
+
+impl CacheMiddleware {
+    /// Creates a new caching middleware backed by the given store.
+    ///
+    /// The store is shared, so the same cache can be used by several models.
+    pub fn new(store: Arc<dyn CacheStore>) -> Self {
+        Self { store }
+    }
+
+    /// Creates a new caching middleware backed by an [`InMemoryCacheStore`].
+    pub fn in_memory(capacity: usize) -> Self {
+        Self::new(Arc::new(InMemoryCacheStore::new(capacity)))
+    }
+}
+
+#[async_trait]
+impl LanguageModelMiddleware for CacheMiddleware {
+    async fn wrap_generate(
+        &self,
+        options: LanguageModelOptions,
+        next: Next<'_>,
+    ) -> Result<LanguageModelResponse> {
+        let key = cache_key(&next.model_name(), &options);
+        if let Some(response) = self.store.get(&key).await {
+            return Ok(response);
+        }
+
+        let response = next.generate_text(options).await?;
+        if !response.contents.is_empty() {
+            self.store.set(&key, response.clone()).await;
+        }
+        Ok(response)
+    }
+
+    async fn wrap_stream(
+        &self,
+        options: LanguageModelOptions,
+        next: Next<'_>,
+    ) -> Result<ProviderStream> {
+        let key = cache_key(&next.model_name(), &options);
+        if let Some(response) = self.store.get(&key).await {
+            return Ok(replay(response));
+        }
+
+        let stream = next.stream_text(options).await?;
+        Ok(Box::pin(RecordingStream {
+            inner: stream,
+            store: self.store.clone(),
+            key,
+            contents: Vec::new(),
+            usage: None,
+            logprobs: None,
+            provider_metadata: None,
+            failed: false,
+            storing: None,
+        }))
+    }
+}
+
+/// Computes the cache key of a request.
+///
+/// The key covers everything that affects the generated output. Hooks, headers
+/// and retry settings are not part of the key.
+pub fn cache_key(model_name: &str, options: &LanguageModelOptions) -> String {
+    let tools = options.tools.as_ref().map(|list| {
+        let tools = list.tools.lock().unwrap_or_else(|p| p.into_inner());
+        tools
+            .iter()
+            .map(|tool| {
+                serde_json::json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "input_schema": tool.input_schema.as_value(),
+                    "strict": tool.strict,
+                })
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let request = serde_json::json!({
+        "model": model_name,
+        "system": options.system,
+        "messages": options.messages,
+        "schema": options.schema.as_ref().map(|s| s.as_value()),
+        "constrained_output": options.constrained_output,
+        "seed": options.seed,
+        "temperature": options.temperature,
+        "top_p": options.top_p,
+        "top_k": options.top_k,
+        "max_output_tokens": options.max_output_tokens,
+        "stop_sequences": options.stop_sequences,
+        "presence_penalty": options.presence_penalty,
+        "frequency_penalty": options.frequency_penalty,
+        "reasoning_effort": options.reasoning_effort.map(|e| format!("{e:?}")),
+        "thinking_budget": options.thinking_budget,
+        "logprobs": options.logprobs,
+        "candidate_count": options.candidate_count,
+        "previous_response_id": options.previous_response_id,
+        "tools": tools,
+        "tool_choice": options.tool_choice,
+    });
+
+    format!("{:016x}", fnv1a(request.to_string().as_bytes()))
+}
+
+/// 64-bit FNV-1a hash, used because it is stable across builds and platforms.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// Replays a cached response as a stream of word-sized deltas followed by the final messages.
+pub(crate) fn replay(response: LanguageModelResponse) -> ProviderStream {
+    Box::pin(futures::stream::iter(chunk_response(
+        response,
+        StreamGranularity::Word,
+    )))
+}
+
+/// Passes a provider stream through and caches the final messages once it ends.
+///
+/// Streams that fail or are dropped before their end are not cached, as their
+/// response may be incomplete.
+struct RecordingStream {
+    inner: ProviderStream,
+    store: Arc<dyn CacheStore>,
+    key: String,
+    contents: Vec<LanguageModelResponseContentType>,
+    usage: Option<Usage>,
+    logprobs: Option<Vec<TokenLogprob>>,
+    provider_metadata: Option<serde_json::Value>,
+    failed: bool,
+    /// Stores the response once the inner stream has ended.
+    storing: Option<BoxFuture<'static, ()>>,
+}
+
+impl RecordingStream {
+    /// Returns a future storing the recorded response, if it is complete.
+    fn store(&mut self) -> Option<BoxFuture<'static, ()>> {
+        if self.failed || self.contents.is_empty() {
+            return None;
+        }
+
+        let store = self.store.clone();
+        let key = std::mem::take(&mut self.key);
+        let response = LanguageModelResponse {
+            contents: std::mem::take(&mut self.contents),
+            usage: self.usage.take(),
+            id: None,
+            logprobs: self.logprobs.take(),
+            candidates: Vec::new(),
+            provider_metadata: self.provider_metadata.take(),
+            metadata: None,
+        };
+        Some(Box::pin(async move { store.set(&key, response).await }))
+    }
+}
+
+impl Stream for RecordingStream {
+    type Item = Result<Vec<LanguageModelStreamChunk>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(storing) = self.storing.as_mut() {
+            ready!(storing.as_mut().poll(cx));
+            self.storing = None;
+            return Poll::Ready(None);
+        }
+
+        let poll = self.inner.as_mut().poll_next(cx);
+        if let Poll::Ready(None) = poll {
+            // Stores the response before ending the stream
+            if let Some(mut storing) = self.store()
+                && storing.as_mut().poll(cx).is_pending()
+            {
+                self.storing = Some(storing);
+                return Poll::Pending;
+            }
+            return Poll::Ready(None);
+        }
+        if let Poll::Ready(Some(ref item)) = poll {
+            match item {
+                Ok(chunks) => {
+                    for chunk in chunks {
+                        match chunk {
+                            LanguageModelStreamChunk::Done(message) => {
+                                self.contents.push(message.content.clone());
+                                if message.usage.is_some() {
+                                    self.usage = message.usage.clone();
+                                }
+                            }
+                            LanguageModelStreamChunk::Delta(
+                                LanguageModelStreamChunkType::Logprobs(logprobs),
+                            ) => self
+                                .logprobs
+                                .get_or_insert_default()
+                                .extend(logprobs.iter().cloned()),
+                            LanguageModelStreamChunk::Delta(
+                                LanguageModelStreamChunkType::Source(source),
+                            ) => self
+                                .contents
+                                .push(LanguageModelResponseContentType::Source(source.clone())),
+                            LanguageModelStreamChunk::Delta(
+                                LanguageModelStreamChunkType::Citation(citation),
+                            ) => self
+                                .contents
+                                .push(LanguageModelResponseContentType::Citation(citation.clone())),
+                            LanguageModelStreamChunk::Delta(
+                                LanguageModelStreamChunkType::ProviderMetadata(metadata),
+                            ) => self.provider_metadata = Some(metadata.clone()),
+                            LanguageModelStreamChunk::Delta(
+                                LanguageModelStreamChunkType::Failed(_)
+                                | LanguageModelStreamChunkType::Incomplete(_),
+                            ) => self.failed = true,
+                            LanguageModelStreamChunk::Delta(_) => {}
+                        }
+                    }
+                }
+                Err(_) => self.failed = true,
+            }
+        }
+        poll
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LanguageModelRequest;
+    use crate::core::language_model::LanguageModel;
+    use crate::core::language_model::middleware::wrap_model;
+    use crate::core::messages::TaggedMessage;
+    use crate::core::tools::{Tool, ToolChoice, ToolList};
+    use crate::core::{AssistantMessage, Message};
+    use crate::providers::MockLanguageModel;
+    use futures::StreamExt;
+
+    /// Answers with the number of each call.
+    fn counting_model() -> MockLanguageModel {
+        MockLanguageModel::new()
+            .with_text("call number 1")
+            .with_text("call number 2")
+    }
+
+    fn options(prompt: &str) -> LanguageModelOptions {
+        LanguageModelOptions {
+            messages: vec![TaggedMessage::initial_step_msg(Message::User(
+                prompt.into(),
+            ))],
+            ..Default::default()
+        }
+    }
+
+    async fn collect_text(stream: ProviderStream) -> (String, Vec<AssistantMessage>) {
+        let mut text = String::new();
+        let mut done = Vec::new();
+        let batches: Vec<_> = stream.collect().await;
+        for chunk in batches.into_iter().flat_map(|b| b.unwrap()) {
+            match chunk {
+                LanguageModelStreamChunk::Delta(LanguageModelStreamChunkType::Text(t)) => {
+                    text.push_str(&t)
+                }
+                LanguageModelStreamChunk::Done(message) => done.push(message),
+                _ => {}
+            }
+        }
+        (text, done)
+    }
+
+    #[test]
+    fn test_cache_key_depends_on_content() {
+        let a = cache_key("model", &options("hello"));
+        assert_eq!(a, cache_key("model", &options("hello")));
+        assert_ne!(a, cache_key("model", &options("goodbye")));
+        assert_ne!(a, cache_key("other-model", &options("hello")));
+
+        let mut hot = options("hello");
+        hot.temperature = Some(0.9);
+        assert_ne!(a, cache_key("model", &hot));
+
+        let mut forced = options("hello");
+        forced.tool_choice = Some(ToolChoice::Required);
+        assert_ne!(a, cache_key("model", &forced));
+    }
+
+    #[test]
+    fn test_cache_key_depends_on_tool_strictness() {
+        let tool = |strict| Tool {
+            name: "search".to_string(),
+            strict,
+            ..Default::default()
+        };
+        let with_tool = |strict| {
+            let mut options = options("hello");
+            options.tools = Some(ToolList::new(vec![tool(strict)]));
+            options
+        };
+        assert_eq!(
+            cache_key("model", &with_tool(None)),
+            cache_key("model", &with_tool(None))
+        );
+        assert_ne!(
+            cache_key("model", &with_tool(None)),
+            cache_key("model", &with_tool(Some(true)))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_store_evicts_least_recently_used() {
+        let store = InMemoryCacheStore::new(2);
+        store.set("a", LanguageModelResponse::new("a")).await;
+        store.set("b", LanguageModelResponse::new("b")).await;
+        assert!(store.get("a").await.is_some());
+
+        store.set("c", LanguageModelResponse::new("c")).await;
+        assert_eq!(store.len(), 2);
+        assert!(store.get("a").await.is_some());
+        assert!(store.get("b").await.is_none());
+        assert!(store.get("c").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_cache_middleware_serves_repeated_requests() {
+        let calls = counting_model();
+        let mut model = wrap_model(calls.clone(), CacheMiddleware::in_memory(10));
+
+        let first = model.generate_text(options("hello")).await.unwrap();
+        let second = model.generate_text(options("hello")).await.unwrap();
+        let other = model.generate_text(options("goodbye")).await.unwrap();
+
+        assert_eq!(calls.calls().len(), 2);
+        assert!(
+            matches!(&second.contents[0], LanguageModelResponseContentType::Text(t) if t == "call number 1")
+        );
+        assert!(
+            matches!(&first.contents[0], LanguageModelResponseContentType::Text(t) if t == "call number 1")
+        );
+        assert!(
+            matches!(&other.contents[0], LanguageModelResponseContentType::Text(t) if t == "call number 2")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cache_middleware_replays_cached_response_as_stream() {
+        let calls = counting_model();
+        let mut model = wrap_model(calls.clone(), CacheMiddleware::in_memory(10));
+
+        model.generate_text(options("hello")).await.unwrap();
+        let stream = model.stream_text(options("hello")).await.unwrap();
+        let (text, done) = collect_text(stream).await;
+
+        assert_eq!(calls.calls().len(), 1);
+        assert_eq!(text, "call number 1");
+        assert_eq!(done.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_middleware_caches_streamed_response() {
+        let calls = counting_model();
+        let store = Arc::new(InMemoryCacheStore::new(10));
+        let mut model = wrap_model(calls.clone(), CacheMiddleware::new(store.clone()));
+
+        let stream = model.stream_text(options("hello")).await.unwrap();
+        collect_text(stream).await;
+        assert_eq!(store.len(), 1);
+
+        let response = model.generate_text(options("hello")).await.unwrap();
+        assert_eq!(calls.calls().len(), 1);
+        assert!(
+            matches!(&response.contents[0], LanguageModelResponseContentType::Text(t) if t == "call number 1")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cache_middleware_skips_unfinished_streams() {
+        let calls = counting_model();
+        let store = Arc::new(InMemoryCacheStore::new(10));
+        let mut model = wrap_model(calls.clone(), CacheMiddleware::new(store.clone()));
+
+        let mut stream = model.stream_text(options("hello")).await.unwrap();
+        stream.next().await.unwrap().unwrap();
+        drop(stream);
+        assert_eq!(store.len(), 0);
+
+        let response = model.generate_text(options("hello")).await.unwrap();
+        assert_eq!(calls.calls().len(), 2);
+        assert!(
+            matches!(&response.contents[0], LanguageModelResponseContentType::Text(t) if t == "call number 2")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cache_middleware_caches_requests_streamed_to_completion() {
+        let calls = counting_model();
+        let model = wrap_model(calls.clone(), CacheMiddleware::in_memory(10));
+
+        for _ in 0..2 {
+            let mut response = LanguageModelRequest::builder()
+                .model(model.clone())
+                .prompt("hello")
+                .build()
+                .stream_text()
+                .await
+                .unwrap();
+            while response.stream.next().await.is_some() {}
+            assert_eq!(response.text().await.unwrap(), "call number 1");
+        }
+        assert_eq!(calls.calls().len(), 1);
+    }
+}
@@ -0,0 +1,156 @@
+//! Default settings middleware.
+//!
+//! [`DefaultSettings`] fills in the settings a request leaves unset, such as the
+//! temperature, the system prompt or the maximum number of output tokens, so
+//! defaults can be enforced for every request made with a model. Settings set by
+//! the request take precedence. It is usually applied with [`with_defaults`].
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::language_model::LanguageModelOptions;
+//! use aisdk::core::language_model::middleware::default_settings::with_defaults;
+//! use aisdk::providers::OpenAI;
+//!
+//! let mut defaults = LanguageModelOptions::default();
+//! defaults.system = Some("Answer in British English.".to_string());
+//! defaults.max_output_tokens = Some(1024);
+//!
+//! let model = with_defaults(OpenAI::gpt_5(), defaults);
+//!# }
+//! ```
+
+use crate::core::language_model::middleware::{
+    LanguageModelMiddleware, WrappedLanguageModel, wrap_model,
+};
+use crate::core::language_model::{LanguageModel, LanguageModelOptions};
+use crate::error::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+/// A middleware filling in the settings a request leaves unset.
+///
+/// The defaults are the sampling settings, the system prompt, the output and
+/// retry limits, the reasoning settings, and the headers and query parameters
+/// sent to the provider. Headers and query parameters are merged, with the ones
+/// of the request replacing defaults of the same name. Other options are ignored.
+#[derive(Debug, Clone)]
+pub struct DefaultSettings {
+    defaults: LanguageModelOptions,
+}
+
+impl DefaultSettings {
+    /// Creates a middleware applying the given defaults.
+    pub fn new(defaults: LanguageModelOptions) -> Self {
+        Self { defaults }
+    }
+}
+
+/// Merges default entries into a map, keeping the entries already set.
+fn merge(
+    map: Option<HashMap<String, String>>,
+    defaults: &Option<HashMap<String, String>>,
+) -> Option<HashMap<String, String>> {
+    match (map, defaults) {
+        (Some(map), Some(defaults)) => {
+            let mut merged = defaults.clone();
+            merged.extend(map);
+            Some(merged)
+        }
+        (map, defaults) => map.or_else(|| defaults.clone()),
+    }
+}
+
+#[async_trait]
+impl LanguageModelMiddleware for DefaultSettings {
+    async fn transform_options(
+        &self,
+        mut options: LanguageModelOptions,
+    ) -> Result<LanguageModelOptions> {
+        let defaults = &self.defaults;
+        options.system = options.system.or_else(|| defaults.system.clone());
+        options.seed = options.seed.or(defaults.seed);
+        options.temperature = options.temperature.or(defaults.temperature);
+        options.top_p = options.top_p.or(defaults.top_p);
+        options.top_k = options.top_k.or(defaults.top_k);
+        options.max_retries = options.max_retries.or(defaults.max_retries);
+        options.max_output_tokens = options.max_output_tokens.or(defaults.max_output_tokens);
+        options.stop_sequences = options
+            .stop_sequences
+            .or_else(|| defaults.stop_sequences.clone());
+        options.presence_penalty = options.presence_penalty.or(defaults.presence_penalty);
+        options.frequency_penalty = options.frequency_penalty.or(defaults.frequency_penalty);
+        options.reasoning_effort = options.reasoning_effort.or(defaults.reasoning_effort);
+        options.thinking_budget = options.thinking_budget.or(defaults.thinking_budget);
+        options.headers = merge(options.headers, &defaults.headers);
+        options.query = merge(options.query, &defaults.query);
+        Ok(options)
+    }
+}
+
+/// Applies default settings to a language model.
+///
+/// # Parameters
+///
+/// * `model` - The language model to wrap.
+/// * `defaults` - The settings used when a request leaves them unset.
+///
+/// # Returns
+///
+/// A [`WrappedLanguageModel`] that can be used like any other model.
+pub fn with_defaults<M: LanguageModel>(
+    model: M,
+    defaults: LanguageModelOptions,
+) -> WrappedLanguageModel<M> {
+    wrap_model(model, DefaultSettings::new(defaults))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::MockLanguageModel;
+
+    #[tokio::test]
+    async fn test_with_defaults_fills_unset_settings() {
+        let settings = MockLanguageModel::new().with_text("ok");
+        let mut model = with_defaults(
+            settings.clone(),
+            LanguageModelOptions {
+                system: Some("Be brief.".to_string()),
+                temperature: Some(0.2),
+                max_output_tokens: Some(100),
+                headers: Some(HashMap::from([
+                    ("x-team".to_string(), "platform".to_string()),
+                    ("x-tier".to_string(), "default".to_string()),
+                ])),
+                ..Default::default()
+            },
+        );
+
+        model
+            .generate_text(LanguageModelOptions {
+                temperature: Some(0.9),
+                headers: Some(HashMap::from([(
+                    "x-tier".to_string(),
+                    "priority".to_string(),
+                )])),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let options = &settings.calls()[0];
+        assert_eq!(options.system.as_deref(), Some("Be brief."));
+        assert_eq!(options.temperature, Some(0.9));
+        assert_eq!(options.max_output_tokens, Some(100));
+        assert_eq!(
+            options.headers,
+            Some(HashMap::from([
+                ("x-team".to_string(), "platform".to_string()),
+                ("x-tier".to_string(), "priority".to_string()),
+            ]))
+        );
+    }
+}
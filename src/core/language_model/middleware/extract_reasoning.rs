@@ -0,0 +1,356 @@
+//! Reasoning extraction middleware.
+//!
+//! Some models, such as DeepSeek-R1 and the models distilled from it, return
+//! their reasoning inside the generated text, wrapped in `<think>...</think>`
+//! tags. [`ExtractReasoning`] moves it out of the text into
+//! [`Reasoning`](LanguageModelResponseContentType::Reasoning) content, and
+//! streams it as [`Reasoning`](LanguageModelStreamChunkType::Reasoning) chunks.
+//! Tags split across streamed chunks are recognized.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "groq")]
+//!# {
+//! use aisdk::core::language_model::middleware::{
+//!     extract_reasoning::ExtractReasoning, wrap_model,
+//! };
+//! use aisdk::providers::Groq;
+//!
+//! let model = wrap_model(
+//!     Groq::model_name("deepseek-r1-distill-llama-70b"),
+//!     ExtractReasoning::new(),
+//! );
+//!# }
+//! ```
+
+use crate::core::AssistantMessage;
+use crate::core::language_model::middleware::{LanguageModelMiddleware, Next};
+use crate::core::language_model::{
+    LanguageModelOptions, LanguageModelResponse, LanguageModelResponseContentType,
+    LanguageModelStreamChunk, LanguageModelStreamChunkType, ProviderStream,
+};
+use crate::error::Result;
+use async_trait::async_trait;
+use futures::StreamExt;
+
+/// A middleware moving the reasoning wrapped in tags out of the generated text.
+#[derive(Debug, Clone)]
+pub struct ExtractReasoning {
+    open_tag: String,
+    close_tag: String,
+}
+
+impl Default for ExtractReasoning {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExtractReasoning {
+    /// Creates a middleware extracting the reasoning wrapped in `<think>` tags.
+    pub fn new() -> Self {
+        Self::tag_name("think")
+    }
+
+    /// Creates a middleware extracting the reasoning wrapped in tags of the
+    /// given name, such as `"reasoning"` for `<reasoning>...</reasoning>`.
+    pub fn tag_name(name: impl AsRef<str>) -> Self {
+        let name = name.as_ref();
+        Self::tags(format!("<{name}>"), format!("</{name}>"))
+    }
+
+    /// Creates a middleware extracting the reasoning between the given opening
+    /// and closing tags.
+    pub fn tags(open_tag: impl Into<String>, close_tag: impl Into<String>) -> Self {
+        Self {
+            open_tag: open_tag.into(),
+            close_tag: close_tag.into(),
+        }
+    }
+
+    fn splitter(&self) -> TagSplitter {
+        TagSplitter {
+            open_tag: self.open_tag.clone(),
+            close_tag: self.close_tag.clone(),
+            buffer: String::new(),
+            in_reasoning: false,
+            trim_text: false,
+        }
+    }
+
+    /// Splits a text into its reasoning and its remaining text.
+    fn split(&self, text: &str) -> (String, String) {
+        let mut splitter = self.splitter();
+        let mut reasoning = String::new();
+        let mut rest = String::new();
+        let mut segments = splitter.push(text);
+        segments.extend(splitter.finish());
+        for segment in segments {
+            match segment {
+                Segment::Reasoning(part) => reasoning.push_str(&part),
+                Segment::Text(part) => rest.push_str(&part),
+            }
+        }
+        (reasoning, rest)
+    }
+
+    /// Splits text content into reasoning content followed by text content.
+    fn split_content(
+        &self,
+        content: LanguageModelResponseContentType,
+    ) -> Vec<LanguageModelResponseContentType> {
+        let LanguageModelResponseContentType::Text(text) = content else {
+            return vec![content];
+        };
+        let (reasoning, text) = self.split(&text);
+        if reasoning.is_empty() {
+            return vec![LanguageModelResponseContentType::Text(text)];
+        }
+
+        let reasoning = LanguageModelResponseContentType::Reasoning {
+            content: reasoning,
+            extensions: Default::default(),
+        };
+        if text.is_empty() {
+            vec![reasoning]
+        } else {
+            vec![reasoning, LanguageModelResponseContentType::Text(text)]
+        }
+    }
+}
+
+#[async_trait]
+impl LanguageModelMiddleware for ExtractReasoning {
+    async fn wrap_generate(
+        &self,
+        options: LanguageModelOptions,
+        next: Next<'_>,
+    ) -> Result<LanguageModelResponse> {
+        let mut response = next.generate_text(options).await?;
+        response.contents = std::mem::take(&mut response.contents)
+            .into_iter()
+            .flat_map(|content| self.split_content(content))
+            .collect();
+        Ok(response)
+    }
+
+    async fn wrap_stream(
+        &self,
+        options: LanguageModelOptions,
+        next: Next<'_>,
+    ) -> Result<ProviderStream> {
+        let stream = next.stream_text(options).await?;
+        let middleware = self.clone();
+        let mut splitter = self.splitter();
+        Ok(Box::pin(stream.map(move |batch| {
+            let mut chunks = Vec::new();
+            for chunk in batch? {
+                match chunk {
+                    LanguageModelStreamChunk::Delta(LanguageModelStreamChunkType::Text(delta)) => {
+                        chunks.extend(splitter.push(&delta).into_iter().map(Segment::into_chunk));
+                    }
+                    LanguageModelStreamChunk::Done(message) => {
+                        chunks.extend(splitter.finish().into_iter().map(Segment::into_chunk));
+                        chunks.extend(middleware.split_content(message.content).into_iter().map(
+                            |content| {
+                                LanguageModelStreamChunk::Done(AssistantMessage {
+                                    content,
+                                    usage: message.usage.clone(),
+                                })
+                            },
+                        ));
+                    }
+                    other => chunks.push(other),
+                }
+            }
+            Ok(chunks)
+        })))
+    }
+}
+
+/// A part of a text, inside or outside of the reasoning tags.
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Reasoning(String),
+    Text(String),
+}
+
+impl Segment {
+    fn into_chunk(self) -> LanguageModelStreamChunk {
+        LanguageModelStreamChunk::Delta(match self {
+            Segment::Reasoning(text) => LanguageModelStreamChunkType::Reasoning(text),
+            Segment::Text(text) => LanguageModelStreamChunkType::Text(text),
+        })
+    }
+}
+
+/// Splits streamed text on reasoning tags, holding back the text that may be
+/// the start of a tag until the next chunk tells whether it is.
+#[derive(Debug)]
+struct TagSplitter {
+    open_tag: String,
+    close_tag: String,
+    buffer: String,
+    in_reasoning: bool,
+    /// Whether the whitespace separating the reasoning from the text is still
+    /// to be removed.
+    trim_text: bool,
+}
+
+impl TagSplitter {
+    /// Adds a chunk of text and returns the segments that are complete.
+    fn push(&mut self, delta: &str) -> Vec<Segment> {
+        self.buffer.push_str(delta);
+        let mut segments = Vec::new();
+        loop {
+            let tag = if self.in_reasoning {
+                &self.close_tag
+            } else {
+                &self.open_tag
+            };
+            if let Some(index) = self.buffer.find(tag.as_str()) {
+                let rest = self.buffer.split_off(index + tag.len());
+                self.buffer.truncate(index);
+                let part = std::mem::replace(&mut self.buffer, rest);
+                self.emit(part, &mut segments);
+                self.in_reasoning = !self.in_reasoning;
+                self.trim_text = !self.in_reasoning;
+                continue;
+            }
+
+            // Hold back the longest suffix that starts the tag
+            let held = (1..tag.len().min(self.buffer.len() + 1))
+                .rev()
+                .find(|&len| {
+                    self.buffer.is_char_boundary(self.buffer.len() - len)
+                        && tag.starts_with(&self.buffer[self.buffer.len() - len..])
+                })
+                .unwrap_or(0);
+            let rest = self.buffer.split_off(self.buffer.len() - held);
+            let part = std::mem::replace(&mut self.buffer, rest);
+            self.emit(part, &mut segments);
+            return segments;
+        }
+    }
+
+    /// Returns the text held back, once the text has ended.
+    fn finish(&mut self) -> Vec<Segment> {
+        let mut segments = Vec::new();
+        let part = std::mem::take(&mut self.buffer);
+        self.emit(part, &mut segments);
+        segments
+    }
+
+    fn emit(&mut self, part: String, segments: &mut Vec<Segment>) {
+        if self.in_reasoning {
+            if !part.is_empty() {
+                segments.push(Segment::Reasoning(part));
+            }
+            return;
+        }
+
+        let part = if self.trim_text {
+            part.trim_start().to_string()
+        } else {
+            part
+        };
+        if !part.is_empty() {
+            self.trim_text = false;
+            segments.push(Segment::Text(part));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::language_model::LanguageModel;
+    use crate::core::language_model::middleware::wrap_model;
+    use crate::providers::MockLanguageModel;
+
+    /// An answer with reasoning inside its text.
+    const ANSWER: &str = "<think>The user greets me.</think>\n\nHello!";
+
+    #[test]
+    fn test_tag_splitter_handles_tags_split_across_chunks() {
+        let mut splitter = ExtractReasoning::new().splitter();
+        let mut segments = Vec::new();
+        for delta in [
+            "<th",
+            "ink>Let me",
+            " see.</thi",
+            "nk>",
+            "\n",
+            "Yes <",
+            "b>",
+        ] {
+            segments.extend(splitter.push(delta));
+        }
+        segments.extend(splitter.finish());
+
+        assert_eq!(
+            segments,
+            vec![
+                Segment::Reasoning("Let me".to_string()),
+                Segment::Reasoning(" see.".to_string()),
+                Segment::Text("Yes ".to_string()),
+                Segment::Text("<b>".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_reasoning_from_generated_text() {
+        let mut model = wrap_model(
+            MockLanguageModel::new().with_text(ANSWER),
+            ExtractReasoning::new(),
+        );
+        let response = model
+            .generate_text(LanguageModelOptions::default())
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            response.contents.as_slice(),
+            [
+                LanguageModelResponseContentType::Reasoning { content, .. },
+                LanguageModelResponseContentType::Text(text),
+            ] if content == "The user greets me." && text == "Hello!"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_extract_reasoning_from_streamed_text() {
+        let mut model = wrap_model(
+            MockLanguageModel::new().with_text(ANSWER),
+            ExtractReasoning::new(),
+        );
+        let chunks: Vec<LanguageModelStreamChunk> = model
+            .stream_text(LanguageModelOptions::default())
+            .await
+            .unwrap()
+            .flat_map(|batch| futures::stream::iter(batch.unwrap()))
+            .collect()
+            .await;
+
+        let mut reasoning = String::new();
+        let mut text = String::new();
+        let mut done = Vec::new();
+        for chunk in chunks {
+            match chunk {
+                LanguageModelStreamChunk::Delta(LanguageModelStreamChunkType::Reasoning(r)) => {
+                    reasoning.push_str(&r)
+                }
+                LanguageModelStreamChunk::Delta(LanguageModelStreamChunkType::Text(t)) => {
+                    text.push_str(&t)
+                }
+                LanguageModelStreamChunk::Done(message) => done.push(message.content),
+                _ => {}
+            }
+        }
+        assert_eq!(reasoning, "The user greets me.");
+        assert_eq!(text, "Hello!");
+        assert_eq!(done.len(), 2);
+    }
+}
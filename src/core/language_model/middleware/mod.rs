@@ -0,0 +1,319 @@
+//! Middleware for language models.
+//!
+//! Middleware intercepts the calls made to a [`LanguageModel`], which allows
+//! cross-cutting concerns such as logging, caching, guardrails or PII redaction
+//! to be applied to any provider uniformly.
+//!
+//! A middleware implements [`LanguageModelMiddleware`] and is applied with
+//! [`wrap_model`]. The wrapped model is a [`LanguageModel`] itself, so it can be
+//! used anywhere a model is expected, including being wrapped again. When
+//! middlewares are nested, the outermost one runs first.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aisdk::core::language_model::LanguageModelOptions;
+//! use aisdk::core::language_model::middleware::LanguageModelMiddleware;
+//! use aisdk::error::Result;
+//!
+//! #[derive(Debug)]
+//! struct DefaultSystemPrompt;
+//!
+//! #[async_trait::async_trait]
+//! impl LanguageModelMiddleware for DefaultSystemPrompt {
+//!     async fn transform_options(
+//!         &self,
+//!         mut options: LanguageModelOptions,
+//!     ) -> Result<LanguageModelOptions> {
+//!         options.system.get_or_insert_with(|| "You are a helpful assistant.".to_string());
+//!         Ok(options)
+//!     }
+//! }
+//! ```
+
+pub mod cache;
+pub mod default_settings;
+pub mod extract_reasoning;
+pub mod moderation;
+pub mod simulate_streaming;
+
+use crate::core::Message;
+use crate::core::capabilities::{CapabilitySet, forward_capabilities};
+use crate::core::language_model::boxed::DynLanguageModel;
+use crate::core::language_model::{
+    LanguageModel, LanguageModelOptions, LanguageModelResponse, ProviderStream,
+};
+use crate::core::pricing::ModelPricing;
+use crate::error::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Hooks that intercept the calls made to a language model.
+///
+/// All hooks have default implementations that pass the call through unchanged,
+/// so a middleware only needs to implement the hooks it is interested in.
+#[async_trait]
+pub trait LanguageModelMiddleware: Send + Sync + std::fmt::Debug {
+    /// Transforms the options before they are passed to the model.
+    ///
+    /// Called for both generating and streaming calls, before
+    /// [`wrap_generate`](Self::wrap_generate) and [`wrap_stream`](Self::wrap_stream).
+    async fn transform_options(
+        &self,
+        options: LanguageModelOptions,
+    ) -> Result<LanguageModelOptions> {
+        Ok(options)
+    }
+
+    /// Wraps a non-streaming call to the model.
+    ///
+    /// Call [`Next::generate_text`] to invoke the wrapped model, or return a
+    /// response without calling it to short-circuit the request.
+    async fn wrap_generate(
+        &self,
+        options: LanguageModelOptions,
+        next: Next<'_>,
+    ) -> Result<LanguageModelResponse> {
+        next.generate_text(options).await
+    }
+
+    /// Wraps a streaming call to the model.
+    ///
+    /// Call [`Next::stream_text`] to invoke the wrapped model, or return a
+    /// stream without calling it to short-circuit the request.
+    async fn wrap_stream(
+        &self,
+        options: LanguageModelOptions,
+        next: Next<'_>,
+    ) -> Result<ProviderStream> {
+        next.stream_text(options).await
+    }
+}
+
+/// The wrapped model, passed to [`LanguageModelMiddleware`] hooks.
+pub struct Next<'a> {
+    model: &'a mut dyn DynLanguageModel,
+}
+
+impl Next<'_> {
+    /// Returns the name of the wrapped model.
+    pub fn model_name(&self) -> String {
+        self.model.dyn_name()
+    }
+
+    /// Performs a non-streaming call to the wrapped model.
+    pub async fn generate_text(
+        self,
+        options: LanguageModelOptions,
+    ) -> Result<LanguageModelResponse> {
+        self.model.dyn_generate_text(options).await
+    }
+
+    /// Performs a streaming call to the wrapped model.
+    pub async fn stream_text(self, options: LanguageModelOptions) -> Result<ProviderStream> {
+        self.model.dyn_stream_text(options).await
+    }
+}
+
+/// A language model with a middleware applied, created with [`wrap_model`].
+#[derive(Debug, Clone)]
+pub struct WrappedLanguageModel<M: LanguageModel> {
+    /// The wrapped model.
+    pub model: M,
+    middleware: Arc<dyn LanguageModelMiddleware>,
+}
+
+/// Applies a middleware to a language model.
+///
+/// # Parameters
+///
+/// * `model` - The language model to wrap.
+/// * `middleware` - The middleware intercepting the calls to the model.
+///
+/// # Returns
+///
+/// A [`WrappedLanguageModel`] that can be used like any other model.
+pub fn wrap_model<M, W>(model: M, middleware: W) -> WrappedLanguageModel<M>
+where
+    M: LanguageModel,
+    W: LanguageModelMiddleware + 'static,
+{
+    WrappedLanguageModel {
+        model,
+        middleware: Arc::new(middleware),
+    }
+}
+
+#[async_trait]
+impl<M: LanguageModel> LanguageModel for WrappedLanguageModel<M> {
+    fn name(&self) -> String {
+        self.model.name()
+    }
+
+    async fn generate_text(
+        &mut self,
+        options: LanguageModelOptions,
+    ) -> Result<LanguageModelResponse> {
+        let options = self.middleware.transform_options(options).await?;
+        let next = Next {
+            model: &mut self.model,
+        };
+        self.middleware.wrap_generate(options, next).await
+    }
+
+    async fn stream_text(&mut self, options: LanguageModelOptions) -> Result<ProviderStream> {
+        let options = self.middleware.transform_options(options).await?;
+        let next = Next {
+            model: &mut self.model,
+        };
+        self.middleware.wrap_stream(options, next).await
+    }
+
+    fn pricing(&self) -> Option<ModelPricing> {
+        self.model.pricing()
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        self.model.context_window()
+    }
+
+    fn max_output_tokens(&self) -> Option<usize> {
+        self.model.max_output_tokens()
+    }
+
+    fn capabilities(&self) -> CapabilitySet {
+        self.model.capabilities()
+    }
+
+    async fn count_tokens(&mut self, messages: &[Message]) -> Result<usize> {
+        self.model.count_tokens(messages).await
+    }
+}
+
+forward_capabilities!(WrappedLanguageModel);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LanguageModelRequest;
+    use crate::core::language_model::LanguageModelStreamChunkType;
+    use crate::core::language_model::{LanguageModelResponseContentType, LanguageModelStreamChunk};
+    use crate::providers::MockLanguageModel;
+    use futures::StreamExt;
+
+    #[derive(Debug)]
+    struct AppendSystem(&'static str);
+
+    #[async_trait]
+    impl LanguageModelMiddleware for AppendSystem {
+        async fn transform_options(
+            &self,
+            mut options: LanguageModelOptions,
+        ) -> Result<LanguageModelOptions> {
+            options.system.get_or_insert_default().push_str(self.0);
+            Ok(options)
+        }
+    }
+
+    #[derive(Debug)]
+    struct Uppercase;
+
+    #[async_trait]
+    impl LanguageModelMiddleware for Uppercase {
+        async fn wrap_generate(
+            &self,
+            options: LanguageModelOptions,
+            next: Next<'_>,
+        ) -> Result<LanguageModelResponse> {
+            let mut response = next.generate_text(options).await?;
+            for content in response.contents.iter_mut() {
+                if let LanguageModelResponseContentType::Text(text) = content {
+                    *text = text.to_uppercase();
+                }
+            }
+            Ok(response)
+        }
+    }
+
+    fn text(response: &LanguageModelResponse) -> &str {
+        match &response.contents[0] {
+            LanguageModelResponseContentType::Text(text) => text,
+            other => panic!("Expected text, got {other:?}"),
+        }
+    }
+
+    /// Returns the system prompt of every call made to a mock model.
+    fn system_prompts(model: &MockLanguageModel) -> Vec<String> {
+        model
+            .calls()
+            .into_iter()
+            .map(|options| options.system.unwrap_or_default())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_wrap_model_transform_options() {
+        let inner = MockLanguageModel::new().with_name("echo").with_text("hi");
+        let mut model = wrap_model(inner.clone(), AppendSystem("a"));
+        let response = model
+            .generate_text(LanguageModelOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(text(&response), "hi");
+        assert_eq!(system_prompts(&inner), ["a"]);
+        assert_eq!(model.name(), "echo");
+    }
+
+    #[tokio::test]
+    async fn test_wrap_model_wrap_generate() {
+        let inner = MockLanguageModel::new().with_text("hi");
+        let mut model = wrap_model(wrap_model(inner, AppendSystem("a")), Uppercase);
+        let response = model
+            .generate_text(LanguageModelOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(text(&response), "HI");
+    }
+
+    #[tokio::test]
+    async fn test_wrap_model_nested_order() {
+        let echo = MockLanguageModel::new().with_text("hi");
+        let inner = wrap_model(echo.clone(), AppendSystem("inner"));
+        let mut model = wrap_model(inner, AppendSystem("outer-"));
+        model
+            .generate_text(LanguageModelOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(system_prompts(&echo), ["outer-inner"]);
+    }
+
+    #[tokio::test]
+    async fn test_wrap_model_stream_passthrough() {
+        let echo = MockLanguageModel::new().with_text("streamed");
+        let mut model = wrap_model(echo.clone(), AppendSystem("system"));
+        let stream = model
+            .stream_text(LanguageModelOptions::default())
+            .await
+            .unwrap();
+        let chunks: Vec<_> = stream.map(|batch| batch.unwrap()).concat().await;
+        assert!(chunks.iter().any(|chunk| matches!(
+            chunk,
+            LanguageModelStreamChunk::Delta(LanguageModelStreamChunkType::Text(t)) if t == "streamed"
+        )));
+        assert_eq!(system_prompts(&echo), ["system"]);
+    }
+
+    #[tokio::test]
+    async fn test_wrapped_model_builds_requests() {
+        let echo = MockLanguageModel::new().with_text("hi");
+        let result = LanguageModelRequest::builder()
+            .model(wrap_model(echo.clone(), Uppercase))
+            .prompt("Hello")
+            .build()
+            .generate_text()
+            .await
+            .unwrap();
+        assert_eq!(result.text(), Some("HI".to_string()));
+        assert_eq!(echo.calls().len(), 1);
+    }
+}
@@ -0,0 +1,169 @@
+//! Prompt moderation middleware.
+//!
+//! [`ModerationGuard`] classifies the latest user message with a
+//! [`ModerationModel`] before the request reaches the language model, and rejects
+//! flagged prompts with [`Error::ContentFiltered`]. Steps that continue after tool
+//! calls are not moderated again, as their prompt was already checked.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::LanguageModelRequest;
+//! use aisdk::core::language_model::middleware::{moderation::ModerationGuard, wrap_model};
+//! use aisdk::providers::OpenAI;
+//!
+//! # async fn run() -> aisdk::Result<()> {
+//! let moderation = OpenAI::model_name("omni-moderation-latest");
+//! let model = wrap_model(OpenAI::gpt_5(), ModerationGuard::new(moderation));
+//!
+//! let result = LanguageModelRequest::builder()
+//!     .model(model)
+//!     .prompt("Tell me a story.")
+//!     .build()
+//!     .generate_text()
+//!     .await?;
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+use crate::core::Message;
+use crate::core::language_model::LanguageModelOptions;
+use crate::core::language_model::middleware::LanguageModelMiddleware;
+use crate::core::moderation::{ModerationInput, ModerationModel};
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+
+/// A middleware rejecting prompts flagged by a moderation model.
+#[derive(Debug, Clone)]
+pub struct ModerationGuard<M: ModerationModel> {
+    moderation: M,
+}
+
+impl<M: ModerationModel> ModerationGuard<M> {
+    /// Creates a guard classifying prompts with the given moderation model.
+    pub fn new(moderation: M) -> Self {
+        Self { moderation }
+    }
+}
+
+#[async_trait]
+impl<M: ModerationModel> LanguageModelMiddleware for ModerationGuard<M> {
+    async fn transform_options(
+        &self,
+        options: LanguageModelOptions,
+    ) -> Result<LanguageModelOptions> {
+        let Some(Message::User(prompt)) = options.messages.last().map(|m| &m.message) else {
+            return Ok(options);
+        };
+
+        let result = self
+            .moderation
+            .moderate(vec![ModerationInput::text(prompt.content.clone())])
+            .await?;
+        if result.flagged {
+            let categories: Vec<&str> = result
+                .flagged_categories()
+                .into_iter()
+                .map(|category| category.name())
+                .collect();
+            return Err(Error::ContentFiltered {
+                details: format!("Prompt flagged by moderation: {}", categories.join(", ")),
+                status_code: None,
+            });
+        }
+
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::language_model::LanguageModel;
+    use crate::core::language_model::middleware::wrap_model;
+    use crate::core::messages::TaggedMessage;
+    use crate::core::moderation::{ModerationCategory, ModerationCategoryScore, ModerationResult};
+    use crate::core::{AssistantMessage, UserMessage};
+    use crate::providers::MockLanguageModel;
+
+    /// Flags texts containing a word.
+    #[derive(Debug)]
+    struct WordModeration(&'static str);
+
+    #[async_trait]
+    impl ModerationModel for WordModeration {
+        async fn moderate(&self, input: Vec<ModerationInput>) -> Result<ModerationResult> {
+            let flagged = input
+                .iter()
+                .any(|part| matches!(part, ModerationInput::Text(t) if t.contains(self.0)));
+            Ok(ModerationResult {
+                flagged,
+                categories: vec![ModerationCategoryScore {
+                    category: ModerationCategory::Violence,
+                    flagged,
+                    score: if flagged { 0.9 } else { 0.1 },
+                }],
+            })
+        }
+    }
+
+    fn options(messages: Vec<Message>) -> LanguageModelOptions {
+        LanguageModelOptions {
+            messages: messages
+                .into_iter()
+                .map(TaggedMessage::initial_step_msg)
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_moderation_guard_blocks_flagged_prompt() {
+        let ok = MockLanguageModel::new();
+        let mut model = wrap_model(ok.clone(), ModerationGuard::new(WordModeration("attack")));
+
+        let result = model
+            .generate_text(options(vec![Message::User(UserMessage::new(
+                "Plan an attack",
+            ))]))
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::ContentFiltered { details, .. }) if details == "Prompt flagged by moderation: violence"
+        ));
+
+        assert!(
+            model
+                .stream_text(options(vec![Message::User(UserMessage::new(
+                    "Plan an attack",
+                ))]))
+                .await
+                .is_err()
+        );
+        assert!(ok.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_moderation_guard_passes_safe_prompt_and_tool_steps() {
+        let ok = MockLanguageModel::new().with_text("ok").with_text("ok");
+        let mut model = wrap_model(ok, ModerationGuard::new(WordModeration("attack")));
+
+        let response = model
+            .generate_text(options(vec![Message::User(UserMessage::new("Hello"))]))
+            .await
+            .unwrap();
+        assert_eq!(response.contents.len(), 1);
+
+        // Only the latest message is moderated, when it comes from the user
+        let response = model
+            .generate_text(options(vec![
+                Message::User(UserMessage::new("Plan an attack")),
+                Message::Assistant(AssistantMessage::new("Sure".to_string().into(), None)),
+            ]))
+            .await;
+        assert!(response.is_ok());
+    }
+}
@@ -0,0 +1,276 @@
+//! Simulated streaming middleware.
+//!
+//! [`SimulateStreaming`] serves streaming calls with a non-streaming call to the
+//! model, and streams its response in chunks of text once it has been generated.
+//! It lets code built on [`stream_text`](crate::core::LanguageModelRequest::stream_text)
+//! use models and providers that do not support streaming.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::language_model::middleware::{
+//!     simulate_streaming::{SimulateStreaming, StreamGranularity},
+//!     wrap_model,
+//! };
+//! use aisdk::providers::OpenAI;
+//! use std::time::Duration;
+//!
+//! let model = wrap_model(
+//!     OpenAI::gpt_5(),
+//!     SimulateStreaming::new()
+//!         .granularity(StreamGranularity::Line)
+//!         .delay(Duration::from_millis(20)),
+//! );
+//!# }
+//! ```
+
+use crate::core::AssistantMessage;
+use crate::core::language_model::middleware::{LanguageModelMiddleware, Next};
+use crate::core::language_model::{
+    LanguageModelOptions, LanguageModelResponse, LanguageModelResponseContentType,
+    LanguageModelStreamChunk, LanguageModelStreamChunkType, ProviderStream, ToolCallDelta,
+};
+use crate::error::Result;
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::time::Duration;
+
+/// The size of the text chunks streamed by [`SimulateStreaming`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StreamGranularity {
+    /// One character at a time.
+    Character,
+    /// One word at a time, with the whitespace following it.
+    #[default]
+    Word,
+    /// One line at a time, with its line break.
+    Line,
+}
+
+impl StreamGranularity {
+    /// Splits text into chunks of this granularity.
+    fn split(self, text: &str) -> Vec<String> {
+        match self {
+            Self::Character => text.chars().map(String::from).collect(),
+            Self::Word => text.split_inclusive(' ').map(String::from).collect(),
+            Self::Line => text.split_inclusive('\n').map(String::from).collect(),
+        }
+    }
+}
+
+/// A middleware streaming the responses of non-streaming calls to the model.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimulateStreaming {
+    granularity: StreamGranularity,
+    delay: Duration,
+}
+
+impl SimulateStreaming {
+    /// Creates a middleware streaming responses word by word, without delay.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the size of the streamed text chunks.
+    pub fn granularity(mut self, granularity: StreamGranularity) -> Self {
+        self.granularity = granularity;
+        self
+    }
+
+    /// Sets the delay before each text chunk, to stream at a steady rate.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+#[async_trait]
+impl LanguageModelMiddleware for SimulateStreaming {
+    async fn wrap_stream(
+        &self,
+        options: LanguageModelOptions,
+        next: Next<'_>,
+    ) -> Result<ProviderStream> {
+        let response = next.generate_text(options).await?;
+        let batches = futures::stream::iter(chunk_response(response, self.granularity));
+        if self.delay.is_zero() {
+            return Ok(Box::pin(batches));
+        }
+
+        let delay = self.delay;
+        Ok(Box::pin(batches.then(move |batch| async move {
+            if let Ok([LanguageModelStreamChunk::Delta(delta)]) = batch.as_deref()
+                && matches!(
+                    delta,
+                    LanguageModelStreamChunkType::Text(_)
+                        | LanguageModelStreamChunkType::Reasoning(_)
+                )
+            {
+                crate::core::runtime::sleep(delay).await;
+            }
+            batch
+        })))
+    }
+}
+
+/// Splits a response into the batches of a stream: text and reasoning deltas of
+/// the given granularity, the identifiers of the response, and the final messages.
+pub(crate) fn chunk_response(
+    response: LanguageModelResponse,
+    granularity: StreamGranularity,
+) -> Vec<Result<Vec<LanguageModelStreamChunk>>> {
+    let mut batches = vec![Ok(vec![LanguageModelStreamChunk::Delta(
+        LanguageModelStreamChunkType::Start,
+    )])];
+
+    for content in &response.contents {
+        let deltas: Vec<LanguageModelStreamChunkType> = match content {
+            LanguageModelResponseContentType::Text(text) => granularity
+                .split(text)
+                .into_iter()
+                .map(LanguageModelStreamChunkType::Text)
+                .collect(),
+            LanguageModelResponseContentType::Reasoning { content, .. } => granularity
+                .split(content)
+                .into_iter()
+                .map(LanguageModelStreamChunkType::Reasoning)
+                .collect(),
+            LanguageModelResponseContentType::ToolCall(info) => {
+                vec![LanguageModelStreamChunkType::ToolCall(ToolCallDelta {
+                    id: info.tool.id.clone(),
+                    name: info.tool.name.clone(),
+                    delta: info.input.to_string(),
+                })]
+            }
+            LanguageModelResponseContentType::Source(source) => {
+                vec![LanguageModelStreamChunkType::Source(source.clone())]
+            }
+            LanguageModelResponseContentType::Citation(citation) => {
+                vec![LanguageModelStreamChunkType::Citation(citation.clone())]
+            }
+            LanguageModelResponseContentType::NotSupported(_) => vec![],
+        };
+        batches.extend(
+            deltas
+                .into_iter()
+                .map(|d| Ok(vec![LanguageModelStreamChunk::Delta(d)])),
+        );
+    }
+
+    if let Some(logprobs) = response.logprobs {
+        batches.push(Ok(vec![LanguageModelStreamChunk::Delta(
+            LanguageModelStreamChunkType::Logprobs(logprobs),
+        )]));
+    }
+
+    if response.id.is_some() || response.metadata.is_some() {
+        let mut metadata = response.metadata.unwrap_or_default();
+        metadata.response_id = metadata.response_id.or(response.id);
+        batches.push(Ok(vec![LanguageModelStreamChunk::Delta(
+            LanguageModelStreamChunkType::ResponseMetadata(metadata),
+        )]));
+    }
+
+    if let Some(metadata) = response.provider_metadata {
+        batches.push(Ok(vec![LanguageModelStreamChunk::Delta(
+            LanguageModelStreamChunkType::ProviderMetadata(metadata),
+        )]));
+    }
+
+    let usage = response.usage;
+    // Sources and citations are only streamed
+    batches.push(Ok(response
+        .contents
+        .into_iter()
+        .filter(|content| {
+            !matches!(
+                content,
+                LanguageModelResponseContentType::Source(_)
+                    | LanguageModelResponseContentType::Citation(_)
+            )
+        })
+        .map(|content| {
+            LanguageModelStreamChunk::Done(AssistantMessage {
+                content,
+                usage: usage.clone(),
+            })
+        })
+        .collect()));
+
+    batches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::language_model::LanguageModel;
+    use crate::core::language_model::middleware::wrap_model;
+    use crate::providers::MockLanguageModel;
+
+    /// Answers with a fixed text.
+    fn non_streaming_model() -> MockLanguageModel {
+        MockLanguageModel::new().with_text("first line\nsecond line")
+    }
+
+    /// Returns the text deltas of a stream.
+    async fn text_deltas(stream: ProviderStream) -> Vec<String> {
+        stream
+            .flat_map(|batch| futures::stream::iter(batch.unwrap()))
+            .filter_map(|chunk| async move {
+                match chunk {
+                    LanguageModelStreamChunk::Delta(LanguageModelStreamChunkType::Text(text)) => {
+                        Some(text)
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_simulate_streaming_streams_generated_text() {
+        let mut model = wrap_model(non_streaming_model(), SimulateStreaming::new());
+        let stream = model
+            .stream_text(LanguageModelOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            text_deltas(stream).await,
+            ["first ", "line\nsecond ", "line"]
+        );
+
+        let mut model = wrap_model(
+            non_streaming_model(),
+            SimulateStreaming::new()
+                .granularity(StreamGranularity::Line)
+                .delay(Duration::from_millis(1)),
+        );
+        let stream = model
+            .stream_text(LanguageModelOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(text_deltas(stream).await, ["first line\n", "second line"]);
+    }
+
+    #[test]
+    fn test_chunk_response_ends_with_final_message() {
+        let batches = chunk_response(
+            LanguageModelResponse::new("héllo"),
+            StreamGranularity::Character,
+        );
+        assert_eq!(batches.len(), 7);
+        assert!(matches!(
+            batches.last(),
+            Some(Ok(chunks)) if matches!(
+                chunks.as_slice(),
+                [LanguageModelStreamChunk::Done(AssistantMessage {
+                    content: LanguageModelResponseContentType::Text(text),
+                    ..
+                })] if text == "héllo"
+            )
+        ));
+    }
+}
@@ -5,32 +5,64 @@
 //! underlying implementation details of different AI providers, offering a
 //! unified interface for various operations like text generation or streaming.
 
+pub mod boxed;
+pub(crate) mod debug;
+pub mod fallback;
+#[cfg(feature = "language-model-request")]
+pub mod generate_many;
+#[cfg(feature = "language-model-request")]
+pub mod generate_object;
 #[cfg(feature = "language-model-request")]
 pub mod generate_text;
+pub mod guardrails;
+pub mod json_repair;
+pub mod logprobs;
+pub mod metrics;
+pub mod middleware;
 #[cfg(feature = "language-model-request")]
 pub mod request;
+pub mod routed;
+pub mod smooth_stream;
+pub mod sources;
+pub(crate) mod stop_sequences;
+pub(crate) mod stream_resume;
 #[cfg(feature = "language-model-request")]
 pub mod stream_text;
-
+pub mod truncation;
+
+use crate::core::capabilities::{Capability, CapabilitySet};
+use crate::core::events::{self, SharedEventSink};
+use crate::core::language_model::guardrails::GuardHook;
+use crate::core::language_model::json_repair::RepairStrategy;
+use crate::core::language_model::logprobs::TokenLogprob;
+use crate::core::language_model::metrics::{Metrics, StepMetrics};
+use crate::core::language_model::sources::{Citation, Source};
+use crate::core::language_model::truncation::TruncationStrategy;
+use crate::core::memory::Memory;
 use crate::core::messages::{AssistantMessage, TaggedMessage, TaggedMessageHelpers};
+use crate::core::pricing::ModelPricing;
 use crate::core::tools::ToolList;
 use crate::core::{
     Message,
-    tools::{ToolCallInfo, ToolResultInfo},
+    tools::{
+        ApprovalPolicy, ToolApproval, ToolApprovalRequest, ToolCallInfo, ToolChoice, ToolResultInfo,
+    },
 };
 use crate::core::{Messages, utils};
-use crate::error::{Error, Result};
+use crate::error::{Error, GuardrailViolation, Result};
 use async_trait::async_trait;
 use derive_builder::Builder;
 use futures::Stream;
 use schemars::Schema;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
-use std::ops::Add;
+use std::ops::{Add, RangeInclusive};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
-use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use std::time::Duration;
+use tokio::sync::mpsc::{self, Receiver, Sender, UnboundedReceiver, UnboundedSender};
 
 // ============================================================================
 // Section: constants
@@ -81,12 +113,69 @@ pub trait LanguageModel: Send + Sync + std::fmt::Debug + Clone + 'static {
     ///
     /// Returns an `Error` if the API call fails or the request is invalid.
     async fn stream_text(&mut self, options: LanguageModelOptions) -> Result<ProviderStream>;
+
+    /// Returns the built-in pricing metadata of the model, if known.
+    ///
+    /// Prices registered with [`register_pricing`](crate::core::pricing::register_pricing)
+    /// take precedence over the value returned here when estimating costs.
+    fn pricing(&self) -> Option<ModelPricing> {
+        None
+    }
+
+    /// Returns the maximum number of tokens the model accepts in a single request, if known.
+    fn context_window(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns the maximum number of tokens the model can generate in a single response, if known.
+    fn max_output_tokens(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns the capabilities of the model.
+    ///
+    /// This is the runtime counterpart of the capability marker traits, for checking
+    /// what a model supports before building a request when the model is picked at
+    /// runtime. Models that do not declare their capabilities, including
+    /// [`DynamicModel`](crate::core::DynamicModel), report all of them.
+    fn capabilities(&self) -> CapabilitySet {
+        CapabilitySet::all()
+    }
+
+    /// Returns whether the model supports the capability.
+    fn supports(&self, capability: Capability) -> bool {
+        self.capabilities().contains(capability)
+    }
+
+    /// Boxes the model into a [`BoxedLanguageModel`](boxed::BoxedLanguageModel), so it
+    /// can be stored together with models of other types.
+    fn boxed(self) -> boxed::BoxedLanguageModel
+    where
+        Self: Sized,
+    {
+        boxed::BoxedLanguageModel::new(self)
+    }
+
+    /// Counts the number of input tokens the given messages occupy for this model.
+    ///
+    /// Providers with a token counting API (e.g. Anthropic) use it to get an exact count.
+    /// Other providers fall back to the estimator in [`crate::core::tokens`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if the provider's token counting API call fails.
+    async fn count_tokens(&mut self, messages: &[Message]) -> Result<usize> {
+        Ok(crate::core::tokens::estimate_messages_tokens(messages))
+    }
 }
 
 // ============================================================================
 // Section: hook types
 // ============================================================================
 
+/// Type alias for a hook function generating the system prompt of a step.
+pub type SystemHook = Arc<dyn Fn(&LanguageModelOptions) -> String + Send + Sync>;
+
 /// Type alias for a hook function that determines when to stop generation.
 ///
 /// Returns `true` if generation should stop.
@@ -95,9 +184,28 @@ pub type StopWhenHook = Arc<dyn Fn(&LanguageModelOptions) -> bool + Send + Sync>
 /// Type alias for a hook function called before each generation step.
 pub type OnStepStartHook = Arc<dyn Fn(&mut LanguageModelOptions) + Send + Sync>;
 
+/// Type alias for a hook function choosing the model and tools of each step.
+///
+/// Called before each step, after the [`OnStepStartHook`].
+pub type PrepareStepHook = Arc<dyn Fn(&LanguageModelOptions) -> StepSettings + Send + Sync>;
+
 /// Type alias for a hook function called after each generation step.
 pub type OnStepFinishHook = Arc<dyn Fn(&LanguageModelOptions) + Send + Sync>;
 
+/// Type alias for a hook function called for every chunk of a stream.
+pub type OnChunkHook = Arc<dyn Fn(&LanguageModelStreamChunkType) + Send + Sync>;
+
+/// Type alias for a hook function called when generation fails.
+pub type OnErrorHook = Arc<dyn Fn(&Error) + Send + Sync>;
+
+/// Type alias for a hook function called once generation has finished.
+pub type OnFinishHook = Arc<dyn Fn(&LanguageModelOptions) + Send + Sync>;
+
+/// Type alias for a hook function selecting one of the candidates of a step.
+///
+/// Returns the index of the selected candidate.
+pub type BestOfHook = Arc<dyn Fn(&[Candidate]) -> usize + Send + Sync>;
+
 // ============================================================================
 // Section: structs and impls
 // ============================================================================
@@ -111,12 +219,19 @@ pub struct Step {
     pub step_id: usize,
     /// The messages that occurred during this step.
     pub messages: Messages,
+    /// The request ID and model version the provider returned with the
+    /// response of this step, if known.
+    pub metadata: Option<ResponseMetadata>,
 }
 
 impl Step {
     /// Creates a new `Step` with the given ID and messages.
     pub fn new(step_id: usize, messages: Messages) -> Self {
-        Self { step_id, messages }
+        Self {
+            step_id,
+            messages,
+            metadata: None,
+        }
     }
 
     /// Returns a reference to the messages in this step.
@@ -170,6 +285,49 @@ impl Step {
     }
 }
 
+/// Settings of a step, returned by the [`PrepareStepHook`].
+///
+/// Settings that are not set keep the ones of the request.
+#[derive(Debug, Clone, Default)]
+pub struct StepSettings {
+    /// The model generating the step, in place of the model of the request.
+    pub model: Option<boxed::BoxedLanguageModel>,
+    /// The names of the tools offered to the model in the step.
+    pub active_tools: Option<Vec<String>>,
+    /// Whether and which tools the model must call.
+    pub tool_choice: Option<ToolChoice>,
+}
+
+impl StepSettings {
+    /// Sets the model generating the step.
+    pub fn model(mut self, model: impl LanguageModel) -> Self {
+        self.model = Some(model.boxed());
+        self
+    }
+
+    /// Sets the names of the tools offered to the model.
+    pub fn active_tools<T: Into<String>>(mut self, names: impl IntoIterator<Item = T>) -> Self {
+        self.active_tools = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets whether and which tools the model must call.
+    pub fn tool_choice(mut self, choice: ToolChoice) -> Self {
+        self.tool_choice = Some(choice);
+        self
+    }
+
+    /// Applies the tool settings to the options of the step.
+    pub(crate) fn apply(&self, options: &mut LanguageModelOptions) {
+        if let (Some(names), Some(tools)) = (&self.active_tools, &options.tools) {
+            options.tools = Some(tools.only(names));
+        }
+        if let Some(choice) = &self.tool_choice {
+            options.tool_choice = Some(choice.clone());
+        }
+    }
+}
+
 // ============================================================================
 // Section: options
 // ============================================================================
@@ -184,18 +342,32 @@ pub struct LanguageModelOptions {
     /// System prompt to be used for the request.
     pub system: Option<String>,
 
+    /// Hook regenerating the system prompt at the start of each step.
+    pub system_fn: Option<SystemHook>,
+
     /// Output format schema.
     pub schema: Option<Schema>,
 
+    /// Constraint on the decoding of the output, for providers supporting it.
+    pub constrained_output: Option<ConstrainedOutput>,
+
+    /// Kind of output generated by `generate_object`. Defaults to the schema
+    /// of the generated type.
+    pub output: Option<ObjectOutput>,
+
+    /// Repairs applied, in order, to structured output that does not parse.
+    pub json_repair: Vec<RepairStrategy>,
+
     /// The seed (integer) to use for random sampling. If set and supported
     /// by the model, calls will generate deterministic results.
     pub seed: Option<u32>,
 
-    /// Controls randomness in generation (0-100, scaled to 0.0-1.0).
-    pub temperature: Option<u32>,
+    /// Controls randomness in generation, usually between 0.0 and 1.0 or 2.0
+    /// depending on the provider.
+    pub temperature: Option<f32>,
 
-    /// Nucleus sampling parameter (0-100, scaled to 0.0-1.0).
-    pub top_p: Option<u32>,
+    /// Nucleus sampling parameter, between 0.0 and 1.0.
+    pub top_p: Option<f32>,
 
     /// Top-k sampling parameter.
     pub top_k: Option<u32>,
@@ -203,6 +375,9 @@ pub struct LanguageModelOptions {
     /// Maximum number of retries for failed requests.
     pub max_retries: Option<u32>,
 
+    /// Maximum number of times a stream dropped by a transient error is resumed.
+    pub max_stream_resumes: Option<u32>,
+
     /// Maximum number of output tokens to generate.
     pub max_output_tokens: Option<u32>,
 
@@ -218,21 +393,141 @@ pub struct LanguageModelOptions {
     /// to repeatedly use the same words or phrases.
     pub frequency_penalty: Option<f32>,
 
+    /// Number of most likely alternatives to return with each generated token.
+    /// If set, providers that support it return the log probabilities of the
+    /// generated tokens.
+    pub logprobs: Option<u32>,
+
+    /// Number of candidate responses to generate for each step. One of them is
+    /// selected with `best_of` and continues the conversation.
+    pub candidate_count: Option<u32>,
+
+    /// Hook selecting the candidate that continues the conversation. The first
+    /// candidate is selected if it is not set.
+    pub best_of: Option<BestOfHook>,
+
+    /// Number of chunks a stream buffers for its consumer. Streams are unbounded
+    /// if it is not set.
+    pub stream_capacity: Option<usize>,
+
+    /// What a bounded stream does when its buffer is full.
+    pub backpressure: Backpressure,
+
+    /// Interval of the pings a stream sends while tools execute. Nothing is sent
+    /// during tool executions if it is not set.
+    pub ping_interval: Option<Duration>,
+
     /// Hook to conditionally stop generation.
     pub stop_when: Option<StopWhenHook>,
 
     /// Hook called before each generation step.
     pub on_step_start: Option<OnStepStartHook>,
 
+    /// Hook choosing the model and tools of each step.
+    pub prepare_step: Option<PrepareStepHook>,
+
     /// Hook called after each generation step.
     pub on_step_finish: Option<OnStepFinishHook>,
 
+    /// Decides which tool calls need to be approved before they are executed.
+    pub approval_policy: Option<ApprovalPolicy>,
+
+    /// Hook called for every chunk emitted by `stream_text`.
+    pub on_chunk: Option<OnChunkHook>,
+
+    /// Hook called when generation fails.
+    pub on_error: Option<OnErrorHook>,
+
+    /// Hook called once after all generation steps have finished.
+    pub on_finish: Option<OnFinishHook>,
+
+    /// Sinks receiving the events of the requests, in addition to the global
+    /// sinks.
+    pub event_sinks: Vec<SharedEventSink>,
+
+    /// Whether the options, responses and tool calls of each step are logged
+    /// at the debug level.
+    pub debug: bool,
+
+    /// Guards run on the prompt before each step, in order.
+    pub input_guards: Vec<GuardHook>,
+
+    /// Guards run on each text response, in order.
+    pub output_guards: Vec<GuardHook>,
+
     /// Level of reasoning effort for the model.
     pub reasoning_effort: Option<ReasoningEffort>,
 
+    /// Number of tokens the model may spend thinking, for providers with
+    /// budget-based reasoning. Takes precedence over `reasoning_effort`.
+    pub thinking_budget: Option<u32>,
+
+    /// ID of a response stored by the provider, whose conversation the request
+    /// continues without sending it again.
+    ///
+    /// Only the new messages need to be given. During generation it is updated
+    /// to the latest response, so the following steps only send the tool results.
+    pub previous_response_id: Option<String>,
+
+    /// ID of the latest response, set by the provider if it stores responses.
+    pub response_id: Option<String>,
+
+    /// Whether the provider stores the request and its response, such as the
+    /// `store` parameter of OpenAI. `false` opts out of server-side retention.
+    pub store: Option<bool>,
+
+    /// Key-value pairs tagging the request, stored with it by the provider.
+    pub request_metadata: Option<HashMap<String, String>>,
+
+    /// ID of the end user the request is made for, which providers use to
+    /// detect abuse.
+    pub user: Option<String>,
+
+    /// Log probabilities of the tokens of the latest response, set by providers
+    /// that return them.
+    pub token_logprobs: Option<Vec<TokenLogprob>>,
+
+    /// Provider-specific metadata of the latest response, keyed by provider, such as
+    /// the cost reported by a gateway. Set by providers that return it.
+    pub provider_metadata: Option<serde_json::Value>,
+
+    /// Candidates of the latest step, when more than one was requested.
+    pub(crate) generated_candidates: Vec<Candidate>,
+
+    /// Sources returned by the provider during the generation.
+    pub(crate) response_sources: Vec<Source>,
+
+    /// Citations returned by the provider during the generation.
+    pub(crate) response_citations: Vec<Citation>,
+
+    /// Latency and throughput of the steps of the generation.
+    pub(crate) step_metrics: Vec<StepMetrics>,
+
+    /// Identifiers returned by the provider with the response of each step.
+    pub(crate) step_response_metadata: HashMap<usize, ResponseMetadata>,
+
+    /// Number of messages already stored by the provider when chaining responses
+    /// with `previous_response_id`.
+    pub(crate) stored_messages: usize,
+
+    /// Strategy used to fit the conversation into the context window before each step.
+    pub truncation: Option<TruncationStrategy>,
+
+    /// Context window used for truncation, overriding the model's own metadata.
+    pub context_window: Option<usize>,
+
+    /// Memory the conversation is loaded from before the request, and saved to after it.
+    pub memory: Option<Arc<dyn Memory>>,
+
+    /// Number of messages loaded from the memory, once they have been.
+    pub(crate) memory_messages: Option<usize>,
+
     /// List of tools to use.
     pub(crate) tools: Option<ToolList>,
 
+    /// Whether and which tools the model must call.
+    pub tool_choice: Option<ToolChoice>,
+
     /// Current step ID for tracking multi-step interactions.
     pub(crate) current_step_id: usize,
 
@@ -242,31 +537,67 @@ pub struct LanguageModelOptions {
     /// The reason why generation stopped.
     pub(crate) stop_reason: Option<StopReason>,
 
+    /// Decisions on pending tool calls, applied when the next request starts.
+    pub(crate) tool_approvals: HashMap<String, ToolApproval>,
+
     /// Custom HTTP headers to include in the request.
     pub headers: Option<HashMap<String, String>>,
+
+    /// Custom query parameters to include in the URL of the request.
+    pub query: Option<HashMap<String, String>>,
+
+    /// Pricing of the model used for the request, resolved when the request starts.
+    pub(crate) pricing: Option<ModelPricing>,
 }
 
 impl Debug for LanguageModelOptions {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("LanguageModelOptions")
             .field("system", &self.system)
+            .field("system_fn", &self.system_fn.is_some())
             .field("messages", &self.messages)
             .field("schema", &self.schema)
             .field("seed", &self.seed)
+            .field("logprobs", &self.logprobs)
+            .field("candidate_count", &self.candidate_count)
+            .field("best_of", &self.best_of.is_some())
+            .field("stream_capacity", &self.stream_capacity)
+            .field("backpressure", &self.backpressure)
+            .field("ping_interval", &self.ping_interval)
             .field("temperature", &self.temperature)
             .field("top_p", &self.top_p)
             .field("top_k", &self.top_k)
             .field("max_retries", &self.max_retries)
+            .field("max_stream_resumes", &self.max_stream_resumes)
             .field("max_output_tokens", &self.max_output_tokens)
             .field("stop_sequences", &self.stop_sequences)
             .field("presence_penalty", &self.presence_penalty)
             .field("frequency_penalty", &self.frequency_penalty)
             .field("tools", &self.tools)
+            .field("tool_choice", &self.tool_choice)
             .field("current_step_id", &self.current_step_id)
+            .field("tool_approvals", &self.tool_approvals)
             .field("stop_when", &self.stop_when.is_some())
             .field("on_step_start", &self.on_step_start.is_some())
+            .field("prepare_step", &self.prepare_step.is_some())
             .field("on_step_finish", &self.on_step_finish.is_some())
+            .field("approval_policy", &self.approval_policy)
+            .field("on_chunk", &self.on_chunk.is_some())
+            .field("on_error", &self.on_error.is_some())
+            .field("on_finish", &self.on_finish.is_some())
+            .field("event_sinks", &self.event_sinks)
+            .field("debug", &self.debug)
+            .field("input_guards", &self.input_guards.len())
+            .field("output_guards", &self.output_guards.len())
+            .field("truncation", &self.truncation)
+            .field("context_window", &self.context_window)
+            .field("memory", &self.memory)
+            .field("store", &self.store)
+            .field("request_metadata", &self.request_metadata)
+            .field("user", &self.user)
             .field("headers", &self.headers)
+            .field("query", &self.query)
+            .field("pricing", &self.pricing)
             .finish()
     }
 }
@@ -282,6 +613,156 @@ impl LanguageModelOptions {
         self.messages.iter().map(|m| m.message.clone()).collect()
     }
 
+    /// Returns the options of the next request. When responses are chained with
+    /// `previous_response_id`, the messages stored by the provider are left out,
+    /// except for system messages, whose instructions are not carried over.
+    pub(crate) fn chained(&self) -> LanguageModelOptions {
+        let mut options = self.clone();
+        if options.previous_response_id.is_some() && options.stored_messages > 0 {
+            let new_messages = options.messages.split_off(options.stored_messages);
+            options
+                .messages
+                .retain(|m| matches!(m.message, Message::System(_)));
+            options.messages.extend(
+                new_messages
+                    .into_iter()
+                    .filter(|m| !matches!(m.message, Message::Assistant(_))),
+            );
+        }
+        options
+    }
+
+    /// Regenerates the system prompt with the system hook, updating the system
+    /// message of the conversation holding the previous prompt.
+    pub(crate) fn regenerate_system(&mut self) {
+        let Some(hook) = self.system_fn.clone() else {
+            return;
+        };
+        let system = hook(self);
+        let previous = self.system.replace(system.clone()).unwrap_or_default();
+        let mut found = false;
+        for tagged in &mut self.messages {
+            if let Message::System(ref mut message) = tagged.message
+                && message.content == previous
+            {
+                message.content = system.clone();
+                found = true;
+            }
+        }
+        if !found {
+            self.messages.insert(
+                0,
+                TaggedMessage::initial_step_msg(Message::System(system.into())),
+            );
+        }
+    }
+
+    /// Records the ID of a response, continuing the chain of responses from it
+    /// if the request chains them.
+    pub(crate) fn record_response_id(&mut self, id: Option<String>) {
+        if self.previous_response_id.is_some()
+            && let Some(id) = &id
+        {
+            self.previous_response_id = Some(id.clone());
+            self.stored_messages = self.messages.len();
+        }
+        self.response_id = id;
+    }
+
+    /// Records the identifiers returned with the response of a step, keeping
+    /// those already known.
+    pub(crate) fn record_response_metadata(&mut self, step: usize, metadata: ResponseMetadata) {
+        self.step_response_metadata
+            .entry(step)
+            .or_default()
+            .merge(metadata);
+    }
+
+    /// Returns the request ID and model version the provider returned with the
+    /// response of the latest step, if known.
+    pub fn response_metadata(&self) -> Option<&ResponseMetadata> {
+        let step = self.step_response_metadata.keys().max()?;
+        self.step_response_metadata.get(step)
+    }
+
+    /// Checks that the presence and frequency penalties are within the range
+    /// accepted by a provider.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidInput`] if a penalty is out of range.
+    #[allow(dead_code)]
+    pub(crate) fn validate_penalties(&self, range: RangeInclusive<f32>) -> Result<()> {
+        let penalties = [
+            ("presence_penalty", self.presence_penalty),
+            ("frequency_penalty", self.frequency_penalty),
+        ];
+        for (name, value) in penalties {
+            if let Some(value) = value
+                && !range.contains(&value)
+            {
+                return Err(Error::InvalidInput(format!(
+                    "{name} must be between {} and {}, got {value}",
+                    range.start(),
+                    range.end()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the temperature is within the range accepted by a provider,
+    /// and that top-p is between 0.0 and 1.0.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidInput`] if a value is out of range.
+    #[allow(dead_code)]
+    pub(crate) fn validate_sampling(&self, temperature: RangeInclusive<f32>) -> Result<()> {
+        let values = [
+            ("temperature", self.temperature, temperature),
+            ("top_p", self.top_p, 0.0..=1.0),
+        ];
+        for (name, value, range) in values {
+            if let Some(value) = value
+                && !range.contains(&value)
+            {
+                return Err(Error::InvalidInput(format!(
+                    "{name} must be between {} and {}, got {value}",
+                    range.start(),
+                    range.end()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the constrained output is supported by a provider, moving
+    /// a JSON schema constraint to the output [`schema`](Self::schema).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidInput`] if the provider does not support the
+    /// constraint.
+    #[allow(dead_code)]
+    pub(crate) fn resolve_constrained_output(
+        &mut self,
+        provider: &str,
+        supported: impl Fn(&ConstrainedOutput) -> bool,
+    ) -> Result<()> {
+        match self.constrained_output.take() {
+            Some(ConstrainedOutput::JsonSchema(schema)) => self.schema = Some(schema),
+            Some(output) if !supported(&output) => {
+                return Err(Error::InvalidInput(format!(
+                    "{provider} does not support {} constrained output",
+                    output.kind()
+                )));
+            }
+            output => self.constrained_output = output,
+        }
+        Ok(())
+    }
+
     /// Executes a tool call and adds the result to the message history.
     pub(crate) async fn handle_tool_call(&mut self, input: &ToolCallInfo) -> &mut Self {
         if let Some(tools) = &self.tools {
@@ -301,6 +782,12 @@ impl LanguageModelOptions {
             tool_output_info.output(output);
             tool_output_info.id(&input.tool.id);
             tool_output_infos.push(tool_output_info.clone());
+            events::emit(&self.event_sinks, |sink| {
+                sink.on_tool_call(input, &tool_output_info)
+            });
+            if self.debug {
+                debug::log_tool_call(self.current_step_id, input, &tool_output_info);
+            }
 
             // update messages
             self.messages.push(TaggedMessage::new(
@@ -314,6 +801,94 @@ impl LanguageModelOptions {
         }
     }
 
+    /// Reports an error of the generation to the `on_error` hook and the event
+    /// sinks.
+    pub(crate) fn report_error(&self, error: &Error) {
+        if let Some(ref hook) = self.on_error {
+            hook(error);
+        }
+        events::emit(&self.event_sinks, |sink| sink.on_error(error));
+    }
+
+    /// Returns `true` if the tool call has to be approved before it is executed.
+    pub(crate) fn requires_approval(&self, call: &ToolCallInfo) -> bool {
+        self.approval_policy
+            .as_ref()
+            .is_some_and(|policy| policy.requires_approval(call, self))
+    }
+
+    /// Executes an approved tool call, or records that it was denied.
+    pub(crate) async fn resolve_approval(&mut self, call: &ToolCallInfo, approval: &ToolApproval) {
+        match approval {
+            ToolApproval::Approved => {
+                self.handle_tool_call(call).await;
+            }
+            ToolApproval::Denied { reason } => {
+                let mut result = ToolResultInfo::new(&call.tool.name);
+                result.id(&call.tool.id);
+                result.output(serde_json::Value::String(match reason {
+                    Some(reason) => format!("Tool call denied: {reason}"),
+                    None => "Tool call denied".to_string(),
+                }));
+                self.messages.push(TaggedMessage::new(
+                    self.current_step_id,
+                    Message::Tool(result),
+                ));
+            }
+        }
+    }
+
+    /// Applies the stored decisions to the tool calls waiting for approval.
+    ///
+    /// Returns the tool calls that are still waiting for a decision.
+    pub(crate) async fn apply_tool_approvals(&mut self) -> Vec<ToolApprovalRequest> {
+        if self.tool_approvals.is_empty() {
+            return self.pending_approvals();
+        }
+
+        let mut approvals = std::mem::take(&mut self.tool_approvals);
+        let mut pending = Vec::new();
+        for request in self.pending_approvals() {
+            match approvals.remove(&request.approval_id) {
+                Some(approval) => self.resolve_approval(&request.tool_call, &approval).await,
+                None => pending.push(request),
+            }
+        }
+        pending
+    }
+
+    /// Returns the tool calls that are waiting for approval.
+    ///
+    /// These are calls of tools that need approval which do not have a result yet.
+    pub fn pending_approvals(&self) -> Vec<ToolApprovalRequest> {
+        let answered: HashSet<&str> = self
+            .messages
+            .iter()
+            .filter_map(|t| match &t.message {
+                Message::Tool(result) => Some(result.tool.id.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        self.messages
+            .iter()
+            .filter_map(|t| match &t.message {
+                Message::Assistant(AssistantMessage {
+                    content: LanguageModelResponseContentType::ToolCall(call),
+                    ..
+                }) if self.requires_approval(call) && !answered.contains(call.tool.id.as_str()) => {
+                    Some(ToolApprovalRequest::new(call.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the ID of the current step, starting at 1.
+    pub fn current_step(&self) -> usize {
+        self.current_step_id
+    }
+
     /// Returns the step with the given index, if it exists.
     pub fn step(&self, index: usize) -> Option<Step> {
         let messages: Messages = self
@@ -325,7 +900,9 @@ impl LanguageModelOptions {
         if messages.is_empty() {
             None
         } else {
-            Some(Step::new(index, messages))
+            let mut step = Step::new(index, messages);
+            step.metadata = self.step_response_metadata.get(&index).cloned();
+            Some(step)
         }
     }
 
@@ -346,7 +923,11 @@ impl LanguageModelOptions {
         }
         let mut steps: Vec<Step> = step_map
             .into_iter()
-            .map(|(id, msgs)| Step::new(id, msgs))
+            .map(|(id, msgs)| {
+                let mut step = Step::new(id, msgs);
+                step.metadata = self.step_response_metadata.get(&id).cloned();
+                step
+            })
             .collect();
         steps.sort_by_key(|s| s.step_id);
         steps
@@ -360,6 +941,21 @@ impl LanguageModelOptions {
             .fold(Usage::default(), |acc, u| &acc + &u)
     }
 
+    /// Returns the token usage of each step, paired with its step ID, in chronological order.
+    pub fn usage_by_step(&self) -> Vec<(usize, Usage)> {
+        self.steps()
+            .iter()
+            .map(|s| (s.step_id, s.usage()))
+            .collect()
+    }
+
+    /// Estimates the dollar cost of all steps using the pricing of the model.
+    ///
+    /// Returns `None` if no pricing is known for the model.
+    pub fn estimated_cost(&self) -> Option<f64> {
+        self.pricing.as_ref().map(|p| self.usage().cost(p))
+    }
+
     /// Returns the content of the last assistant message, excluding reasoning.
     pub fn content(&self) -> Option<&LanguageModelResponseContentType> {
         if let Some(msg) = self.messages.last() {
@@ -409,6 +1005,48 @@ impl LanguageModelOptions {
     pub fn stop_reason(&self) -> Option<StopReason> {
         self.stop_reason.clone()
     }
+
+    /// Returns the log probabilities of the tokens of the latest response.
+    ///
+    /// Only set when the request asks for `logprobs` and the provider returns them.
+    pub fn logprobs(&self) -> Option<&[TokenLogprob]> {
+        self.token_logprobs.as_deref()
+    }
+
+    /// Returns the candidates of the latest step, when more than one was requested.
+    pub fn candidates(&self) -> &[Candidate] {
+        &self.generated_candidates
+    }
+
+    /// Returns the sources the model used in all steps, such as web search results.
+    pub fn sources(&self) -> &[Source] {
+        &self.response_sources
+    }
+
+    /// Returns the passages of sources cited by the generated text in all steps.
+    pub fn citations(&self) -> &[Citation] {
+        &self.response_citations
+    }
+
+    /// Returns the latency and throughput of the steps of the generation.
+    pub fn metrics(&self) -> Metrics {
+        Metrics {
+            steps: self.step_metrics.clone(),
+        }
+    }
+
+    /// Records a source, unless a source with the same ID was already recorded.
+    pub(crate) fn record_source(&mut self, source: &Source) {
+        if !self.response_sources.iter().any(|s| s.id() == source.id()) {
+            self.response_sources.push(source.clone());
+        }
+    }
+
+    /// Records a citation and its source.
+    pub(crate) fn record_citation(&mut self, citation: &Citation) {
+        self.record_source(&citation.source);
+        self.response_citations.push(citation.clone());
+    }
 }
 
 // ============================================================================
@@ -416,7 +1054,8 @@ impl LanguageModelOptions {
 // ============================================================================
 
 /// The different types of content that can be generated by a language model.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
 pub enum LanguageModelResponseContentType {
     /// Plain text response.
     Text(String),
@@ -427,8 +1066,13 @@ pub enum LanguageModelResponseContentType {
         /// The reasoning/thinking content
         content: String,
         /// Provider-specific extensions
+        #[serde(default)]
         extensions: crate::extensions::Extensions,
     },
+    /// A source the model used, such as a web search result.
+    Source(Source),
+    /// A passage of a source cited by the text.
+    Citation(Citation),
     /// Feature not supported by the provider.
     NotSupported(String),
 }
@@ -453,16 +1097,41 @@ impl LanguageModelResponseContentType {
 }
 
 /// Token usage statistics for a language model operation.
-#[derive(Default, Debug, Clone, PartialEq)]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Usage {
-    /// Number of input tokens processed.
+    /// Number of input tokens processed, including the tokens read from and
+    /// written to the prompt cache.
     pub input_tokens: Option<usize>,
     /// Number of output tokens generated.
     pub output_tokens: Option<usize>,
     /// Number of tokens used for reasoning.
     pub reasoning_tokens: Option<usize>,
-    /// Number of cached tokens reused.
+    /// Number of input tokens read from the prompt cache.
     pub cached_tokens: Option<usize>,
+    /// Number of input tokens written to the prompt cache.
+    pub cache_write_tokens: Option<usize>,
+}
+
+impl Usage {
+    /// Computes the dollar cost of this usage with the given pricing.
+    ///
+    /// Cache reads and cache writes are part of the input tokens. They are billed
+    /// at the cached and cache write rates, falling back to the input rate when
+    /// no such rate is set. Missing token counts are treated as zero.
+    pub fn cost(&self, pricing: &ModelPricing) -> f64 {
+        let input = self.input_tokens.unwrap_or(0);
+        let cached = self.cached_tokens.unwrap_or(0).min(input);
+        let cache_write = self.cache_write_tokens.unwrap_or(0).min(input - cached);
+        let output = self.output_tokens.unwrap_or(0);
+
+        let cached_rate = pricing.cached.unwrap_or(pricing.input);
+        let cache_write_rate = pricing.cache_write.unwrap_or(pricing.input);
+        ((input - cached - cache_write) as f64 * pricing.input
+            + cached as f64 * cached_rate
+            + cache_write as f64 * cache_write_rate
+            + output as f64 * pricing.output)
+            / 1_000_000.0
+    }
 }
 
 impl Add for &Usage {
@@ -474,18 +1143,41 @@ impl Add for &Usage {
             output_tokens: utils::sum_options(self.output_tokens, rhs.output_tokens),
             reasoning_tokens: utils::sum_options(self.reasoning_tokens, rhs.reasoning_tokens),
             cached_tokens: utils::sum_options(self.cached_tokens, rhs.cached_tokens),
+            cache_write_tokens: utils::sum_options(self.cache_write_tokens, rhs.cache_write_tokens),
         }
     }
 }
 
 /// Response from a language model.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LanguageModelResponse {
     /// The generated contents (supports multiple outputs).
     pub contents: Vec<LanguageModelResponseContentType>,
 
     /// Usage information
     pub usage: Option<Usage>,
+
+    /// The ID the provider assigned to the response, for providers that store
+    /// responses (e.g. the OpenAI Responses API).
+    #[serde(default)]
+    pub id: Option<String>,
+
+    /// Log probabilities of the generated tokens, if requested and supported.
+    #[serde(default)]
+    pub logprobs: Option<Vec<TokenLogprob>>,
+
+    /// The other candidates generated in the same request, when more than one
+    /// was requested. The first candidate is `contents`.
+    #[serde(default)]
+    pub candidates: Vec<Candidate>,
+
+    /// Provider-specific metadata of the response, keyed by provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_metadata: Option<serde_json::Value>,
+
+    /// The request ID and model version the provider returned with the response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ResponseMetadata>,
 }
 
 impl LanguageModelResponse {
@@ -494,12 +1186,71 @@ impl LanguageModelResponse {
         Self {
             contents: vec![LanguageModelResponseContentType::new(text.into())],
             usage: None,
+            id: None,
+            logprobs: None,
+            candidates: Vec::new(),
+            provider_metadata: None,
+            metadata: None,
         }
     }
 }
 
+/// Identifiers a provider returned with a response, to reference it in support
+/// tickets and traces.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponseMetadata {
+    /// The ID of the HTTP request, from the `x-request-id` or `request-id` header.
+    ///
+    /// It is only known for responses that are not streamed.
+    #[serde(default)]
+    pub request_id: Option<String>,
+    /// The ID the provider assigned to the response.
+    #[serde(default)]
+    pub response_id: Option<String>,
+    /// The version of the model that generated the response, such as
+    /// `gpt-4o-2024-08-06`.
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl ResponseMetadata {
+    /// Fills the fields that are not set with those of `other`.
+    pub(crate) fn merge(&mut self, other: ResponseMetadata) {
+        self.request_id = self.request_id.take().or(other.request_id);
+        self.response_id = self.response_id.take().or(other.response_id);
+        self.model = self.model.take().or(other.model);
+    }
+}
+
+/// One of several responses generated for the same step.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Candidate {
+    /// The generated contents.
+    pub contents: Vec<LanguageModelResponseContentType>,
+
+    /// Usage of the request that generated the candidate. Providers generating
+    /// all candidates in one request only report the usage of the whole request.
+    pub usage: Option<Usage>,
+}
+
+impl Candidate {
+    /// Returns the text of the candidate, if it has any.
+    pub fn text(&self) -> Option<String> {
+        let text: String = self
+            .contents
+            .iter()
+            .filter_map(|c| match c {
+                LanguageModelResponseContentType::Text(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        (!text.is_empty()).then_some(text)
+    }
+}
+
 /// Types of chunks that can be emitted during streaming text generation.
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "content", rename_all = "snake_case")]
 pub enum LanguageModelStreamChunkType {
     /// Indicates the start of generation.
     #[default]
@@ -509,17 +1260,51 @@ pub enum LanguageModelStreamChunkType {
     /// Reasoning summary text chunk (content delta only)
     Reasoning(String),
     /// Tool call argument chunk
-    ToolCall(String),
+    ToolCall(ToolCallDelta),
+    /// A tool call whose input is complete, emitted before the tool is executed.
+    ToolCallAvailable(ToolCallInfo),
+    /// The result of an executed tool call.
+    ToolResult(ToolResultInfo),
     /// Successful completion of generation.
     End(AssistantMessage),
+    /// Token usage of a step, emitted once the step has finished.
+    Usage(Usage),
+    /// Log probabilities of the tokens of the latest text chunks.
+    Logprobs(Vec<TokenLogprob>),
+    /// A source the model used, such as a web search result.
+    Source(Source),
+    /// A passage of a source cited by the latest text chunks.
+    Citation(Citation),
+    /// Provider-specific metadata of the response, keyed by provider.
+    ProviderMetadata(serde_json::Value),
+    /// The identifiers the provider returned with the response of the step.
+    ResponseMetadata(ResponseMetadata),
+    /// Latency and throughput of the generation, emitted once it has finished.
+    Metrics(Metrics),
     /// Generation failed with an error message.
     Failed(String),
     /// Generation ended with an incomplete response.
     Incomplete(String),
+    /// Keep-alive sent periodically while tools execute, see
+    /// [`ping_interval`](request::LanguageModelRequestBuilder::ping_interval).
+    Ping,
+    /// A tool call is waiting for approval. The stream pauses until it is approved or denied.
+    ToolApprovalRequired(ToolApprovalRequest),
     /// Feature not supported by the provider.
     NotSupported(String),
 }
 
+/// A chunk of the input of a tool call, streamed while the model generates it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ToolCallDelta {
+    /// The ID of the tool call.
+    pub id: String,
+    /// The name of the called tool.
+    pub name: String,
+    /// The next part of the JSON input.
+    pub delta: String,
+}
+
 /// A chunk of data from a streaming language model response.
 #[derive(Debug, Clone)]
 pub enum LanguageModelStreamChunk {
@@ -530,12 +1315,17 @@ pub enum LanguageModelStreamChunk {
 }
 
 /// A common interface for stream responses generated by providers (e.g. OpenAI)
-pub(crate) type ProviderStream =
-    Pin<Box<dyn Stream<Item = Result<Vec<LanguageModelStreamChunk>>> + Send>>;
+pub type ProviderStream = Pin<Box<dyn Stream<Item = Result<Vec<LanguageModelStreamChunk>>> + Send>>;
 
 /// A stream wrapper that provides a channel-based interface for language model streaming.
 pub struct LanguageModelStream {
-    receiver: UnboundedReceiver<LanguageModelStreamChunkType>,
+    receiver: ChunkReceiver,
+}
+
+/// The receiving half of the channel of a [`LanguageModelStream`].
+enum ChunkReceiver {
+    Unbounded(UnboundedReceiver<LanguageModelStreamChunkType>),
+    Bounded(Receiver<LanguageModelStreamChunkType>),
 }
 
 impl LanguageModelStream {
@@ -552,14 +1342,32 @@ impl LanguageModelStream {
         LanguageModelStream,
     ) {
         let (tx, rx) = mpsc::unbounded_channel();
-        (tx, LanguageModelStream { receiver: rx })
+        let receiver = ChunkReceiver::Unbounded(rx);
+        (tx, LanguageModelStream { receiver })
+    }
+
+    /// Creates a new stream buffering at most `capacity` chunks.
+    ///
+    /// Sending a chunk waits while the buffer is full, so a slow consumer slows
+    /// down the producer instead of growing the buffer.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of `(Sender<LanguageModelStreamChunkType>, LanguageModelStream)`.
+    pub fn bounded(capacity: usize) -> (Sender<LanguageModelStreamChunkType>, LanguageModelStream) {
+        let (tx, rx) = mpsc::channel(capacity.max(1));
+        let receiver = ChunkReceiver::Bounded(rx);
+        (tx, LanguageModelStream { receiver })
     }
 }
 
 impl Stream for LanguageModelStream {
     type Item = LanguageModelStreamChunkType;
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.receiver.poll_recv(cx)
+        match &mut self.receiver {
+            ChunkReceiver::Unbounded(receiver) => receiver.poll_recv(cx),
+            ChunkReceiver::Bounded(receiver) => receiver.poll_recv(cx),
+        }
     }
 }
 
@@ -575,10 +1383,25 @@ pub enum StopReason {
     Hook,
     /// Stopped due to an error.
     Error(Error),
+    /// Stopped because tool calls are waiting for approval.
+    ToolApprovalRequired,
+    /// Stopped because a guard rejected the prompt or a response.
+    Guardrail(GuardrailViolation),
     /// Other unspecified reason.
     Other(String),
 }
 
+/// What a bounded stream does when its consumer falls behind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Waits for the consumer to read a chunk before sending the next one.
+    #[default]
+    Block,
+    /// Merges the text deltas that do not fit in the buffer into a single chunk,
+    /// so text never waits for the consumer. Other chunks still wait.
+    CoalesceText,
+}
+
 /// Levels of reasoning effort for language models that support it.
 #[derive(Debug, Clone, Copy, Default)]
 pub enum ReasoningEffort {
@@ -591,6 +1414,47 @@ pub enum ReasoningEffort {
     High,
 }
 
+/// Constraints on the decoding of the output of a language model.
+///
+/// Support differs per provider: requests with a constraint the provider does
+/// not support fail with [`Error::InvalidInput`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConstrainedOutput {
+    /// Any valid JSON object.
+    JsonObject,
+    /// JSON conforming to a schema, the same as setting the output schema.
+    JsonSchema(Schema),
+    /// Text matching a regular expression (Together AI).
+    Regex(String),
+    /// Text matching a GBNF grammar (Fireworks AI).
+    Grammar(String),
+}
+
+impl ConstrainedOutput {
+    /// A readable name of the kind of constraint, for error messages.
+    fn kind(&self) -> &'static str {
+        match self {
+            ConstrainedOutput::JsonObject => "JSON object",
+            ConstrainedOutput::JsonSchema(_) => "JSON schema",
+            ConstrainedOutput::Regex(_) => "regex",
+            ConstrainedOutput::Grammar(_) => "grammar",
+        }
+    }
+}
+
+/// Kinds of output generated by `generate_object`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectOutput {
+    /// An object matching a schema.
+    Schema(Schema),
+    /// One of the given values, for classification.
+    Enum(Vec<String>),
+    /// Any JSON value, without a schema.
+    NoSchema,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -602,12 +1466,14 @@ mod tests {
             output_tokens: Some(20),
             reasoning_tokens: Some(5),
             cached_tokens: Some(2),
+            cache_write_tokens: None,
         };
         let u2 = Usage {
             input_tokens: Some(15),
             output_tokens: Some(25),
             reasoning_tokens: Some(10),
             cached_tokens: Some(3),
+            cache_write_tokens: None,
         };
         let result = &u1 + &u2;
         assert_eq!(result.input_tokens, Some(25));
@@ -623,12 +1489,14 @@ mod tests {
             output_tokens: Some(20),
             reasoning_tokens: Some(5),
             cached_tokens: Some(2),
+            cache_write_tokens: None,
         };
         let u2 = Usage {
             input_tokens: None,
             output_tokens: None,
             reasoning_tokens: None,
             cached_tokens: None,
+            cache_write_tokens: None,
         };
         let result = &u1 + &u2;
         assert_eq!(result.input_tokens, Some(10));
@@ -644,12 +1512,14 @@ mod tests {
             output_tokens: None,
             reasoning_tokens: None,
             cached_tokens: None,
+            cache_write_tokens: None,
         };
         let u2 = Usage {
             input_tokens: Some(15),
             output_tokens: Some(25),
             reasoning_tokens: Some(10),
             cached_tokens: Some(3),
+            cache_write_tokens: None,
         };
         let result = &u1 + &u2;
         assert_eq!(result.input_tokens, Some(15));
@@ -676,12 +1546,14 @@ mod tests {
             output_tokens: None,
             reasoning_tokens: None,
             cached_tokens: Some(2),
+            cache_write_tokens: None,
         };
         let u2 = Usage {
             input_tokens: None,
             output_tokens: Some(25),
             reasoning_tokens: Some(10),
             cached_tokens: None,
+            cache_write_tokens: None,
         };
         let result = &u1 + &u2;
         assert_eq!(result.input_tokens, Some(10));
@@ -697,12 +1569,14 @@ mod tests {
             output_tokens: Some(0),
             reasoning_tokens: Some(0),
             cached_tokens: Some(0),
+            cache_write_tokens: None,
         };
         let u2 = Usage {
             input_tokens: Some(0),
             output_tokens: Some(0),
             reasoning_tokens: Some(0),
             cached_tokens: Some(0),
+            cache_write_tokens: None,
         };
         let result = &u1 + &u2;
         assert_eq!(result.input_tokens, Some(0));
@@ -711,6 +1585,98 @@ mod tests {
         assert_eq!(result.cached_tokens, Some(0));
     }
 
+    #[test]
+    fn test_usage_cost() {
+        let usage = Usage {
+            input_tokens: Some(1_000_000),
+            output_tokens: Some(500_000),
+            reasoning_tokens: Some(100_000),
+            cached_tokens: Some(200_000),
+            cache_write_tokens: None,
+        };
+        let pricing = ModelPricing::new(2.0, 8.0).with_cached(0.5);
+        // 800k input * 2.0 + 200k cached * 0.5 + 500k output * 8.0
+        assert!((usage.cost(&pricing) - 5.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_usage_cost_with_cache_writes() {
+        let usage = Usage {
+            input_tokens: Some(1_000_000),
+            output_tokens: Some(100_000),
+            reasoning_tokens: None,
+            cached_tokens: Some(600_000),
+            cache_write_tokens: Some(300_000),
+        };
+        let pricing = ModelPricing::new(3.0, 15.0)
+            .with_cached(0.3)
+            .with_cache_write(3.75);
+        // 100k input * 3.0 + 600k cached * 0.3 + 300k written * 3.75 + 100k output * 15.0
+        assert!((usage.cost(&pricing) - 3.105).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_usage_cost_without_cached_rate() {
+        let usage = Usage {
+            input_tokens: Some(1_000_000),
+            output_tokens: None,
+            reasoning_tokens: None,
+            cached_tokens: Some(400_000),
+            cache_write_tokens: None,
+        };
+        let pricing = ModelPricing::new(3.0, 15.0);
+        assert!((usage.cost(&pricing) - 3.0).abs() < 1e-9);
+        assert_eq!(Usage::default().cost(&pricing), 0.0);
+    }
+
+    #[test]
+    fn test_approval_policy() {
+        let mut call = ToolCallInfo::new("delete_file");
+        call.id("call_0");
+        let mut options = LanguageModelOptions {
+            messages: vec![TaggedMessage::new(
+                1,
+                Message::Assistant(AssistantMessage {
+                    content: LanguageModelResponseContentType::Text("Hello".to_string()),
+                    usage: Some(Usage {
+                        input_tokens: Some(1_000_000),
+                        ..Default::default()
+                    }),
+                }),
+            )],
+            pricing: Some(ModelPricing::new(2.0, 8.0)),
+            ..Default::default()
+        };
+
+        assert!(!options.requires_approval(&call));
+
+        options.approval_policy = Some(ApprovalPolicy::Never);
+        assert!(!options.requires_approval(&call));
+
+        options.approval_policy = Some(ApprovalPolicy::AlwaysForTools(vec![
+            "delete_file".to_string(),
+        ]));
+        assert!(options.requires_approval(&call));
+        options.approval_policy = Some(ApprovalPolicy::AlwaysForTools(vec![
+            "read_file".to_string(),
+        ]));
+        assert!(!options.requires_approval(&call));
+
+        options.approval_policy = Some(ApprovalPolicy::dynamic(|call, history| {
+            call.tool.name.starts_with("delete") && !history.is_empty()
+        }));
+        assert!(options.requires_approval(&call));
+
+        // The conversation so far cost $2
+        options.approval_policy = Some(ApprovalPolicy::CostThreshold(1.5));
+        assert!(options.requires_approval(&call));
+        options.approval_policy = Some(ApprovalPolicy::CostThreshold(2.5));
+        assert!(!options.requires_approval(&call));
+        // Without pricing the cost is unknown
+        options.pricing = None;
+        assert!(options.requires_approval(&call));
+    }
+
     #[test]
     fn test_step_usage() {
         let messages = vec![
@@ -721,6 +1687,7 @@ mod tests {
                     output_tokens: Some(5),
                     reasoning_tokens: Some(2),
                     cached_tokens: Some(1),
+                    cache_write_tokens: None,
                 }),
             }),
             Message::User("Hi".to_string().into()),
@@ -731,6 +1698,7 @@ mod tests {
                     output_tokens: Some(3),
                     reasoning_tokens: Some(1),
                     cached_tokens: Some(0),
+                    cache_write_tokens: None,
                 }),
             }),
         ];
@@ -986,4 +1954,52 @@ mod tests {
             assert_eq!(result.tool.name, format!("tool{i}"));
         }
     }
+
+    #[test]
+    fn test_validate_sampling() {
+        let mut options = LanguageModelOptions {
+            temperature: Some(1.5),
+            top_p: Some(0.9),
+            ..Default::default()
+        };
+        assert!(options.validate_sampling(0.0..=2.0).is_ok());
+        assert!(
+            matches!(options.validate_sampling(0.0..=1.0), Err(Error::InvalidInput(message)) if message == "temperature must be between 0 and 1, got 1.5")
+        );
+
+        options.top_p = Some(90.0);
+        assert!(matches!(
+            options.validate_sampling(0.0..=2.0),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_resolve_constrained_output() {
+        let schema = schemars::json_schema!({ "type": "object" });
+        let mut options = LanguageModelOptions {
+            constrained_output: Some(ConstrainedOutput::JsonSchema(schema.clone())),
+            ..Default::default()
+        };
+        options
+            .resolve_constrained_output("Test", |_| false)
+            .unwrap();
+        assert_eq!(options.schema, Some(schema));
+        assert_eq!(options.constrained_output, None);
+
+        options.constrained_output = Some(ConstrainedOutput::JsonObject);
+        options
+            .resolve_constrained_output("Test", |output| *output == ConstrainedOutput::JsonObject)
+            .unwrap();
+        assert_eq!(
+            options.constrained_output,
+            Some(ConstrainedOutput::JsonObject)
+        );
+
+        options.constrained_output = Some(ConstrainedOutput::Regex("[a-z]+".to_string()));
+        let result = options.resolve_constrained_output("Test", |_| false);
+        assert!(
+            matches!(result, Err(Error::InvalidInput(message)) if message == "Test does not support regex constrained output")
+        );
+    }
 }
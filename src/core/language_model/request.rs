@@ -4,15 +4,31 @@
 //! and options for generating text or streaming responses. It includes a type-state builder
 //! pattern to ensure requests are constructed correctly and safely.
 
-use crate::core::Messages;
 use crate::core::capabilities::*;
-use crate::core::language_model::{LanguageModel, LanguageModelOptions};
-use crate::core::tools::Tool;
+use crate::core::events::EventSink;
+use crate::core::language_model::guardrails::GuardDecision;
+use crate::core::language_model::json_repair::RepairStrategy;
+use crate::core::language_model::truncation::TruncationStrategy;
+use crate::core::language_model::{
+    Backpressure, Candidate, LanguageModel, LanguageModelOptions, LanguageModelStreamChunkType,
+    StepSettings, SystemHook,
+};
+use crate::core::memory::Memory;
+use crate::core::messages::TaggedMessage;
+use crate::core::tools::{ApprovalPolicy, Tool, ToolChoice};
+use crate::core::utils::resolve_message;
+use crate::core::{Message, Messages};
+use crate::error::{Error, Result, ValidationIssue};
+#[cfg(feature = "prompt")]
+use crate::prompt::PromptTemplate;
 use schemars::{JsonSchema, schema_for};
+#[cfg(feature = "prompt")]
+use serde::Serialize;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Options for text generation requests such as `generate_text` and `stream_text`.
 #[derive(Debug)]
@@ -37,6 +53,58 @@ impl<M: LanguageModel> LanguageModelRequest<M> {
     pub fn builder() -> LanguageModelRequestBuilder<M> {
         LanguageModelRequestBuilder::default()
     }
+
+    /// Creates a builder that resumes a conversation serialized with
+    /// [`GenerateTextResponse::to_json`](crate::core::GenerateTextResponse::to_json).
+    ///
+    /// The restored messages keep their step ids, and new steps continue after the
+    /// last restored step.
+    ///
+    /// # Parameters
+    ///
+    /// * `model` - The language model instance to use.
+    /// * `json` - The serialized conversation.
+    ///
+    /// # Returns
+    ///
+    /// The builder in the [`OptionsStage`] state.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidInput`] if the JSON is not a valid conversation.
+    pub fn from_messages_json(
+        model: M,
+        json: &str,
+    ) -> Result<LanguageModelRequestBuilder<M, OptionsStage>> {
+        let messages: Vec<TaggedMessage> = serde_json::from_str(json)
+            .map_err(|e| Error::InvalidInput(format!("Invalid conversation JSON: {e}")))?;
+
+        Ok(LanguageModelRequestBuilder {
+            model: Some(model),
+            prompt: None,
+            options: LanguageModelOptions {
+                current_step_id: messages.iter().map(|m| m.step_id).max().unwrap_or(0),
+                messages,
+                ..Default::default()
+            },
+            state: std::marker::PhantomData,
+        })
+    }
+}
+
+impl<M: LanguageModel> From<LanguageModelRequest<M>> for LanguageModelOptions {
+    /// Returns the options of a request, with the prompt resolved into messages.
+    ///
+    /// Used to send requests without the generation loop, such as in a
+    /// [`Batch`](crate::core::batch::Batch).
+    fn from(request: LanguageModelRequest<M>) -> Self {
+        let (system, messages) = resolve_message(&request.options, &request.prompt);
+        LanguageModelOptions {
+            system: (!system.is_empty()).then_some(system),
+            messages,
+            ..request.options
+        }
+    }
 }
 
 impl<M: LanguageModel> Deref for LanguageModelRequest<M> {
@@ -177,6 +245,36 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, SystemStage> {
         }
     }
 
+    /// Sets a system prompt regenerated at the start of each step.
+    ///
+    /// The function receives the options of the request, so the prompt can
+    /// reference the current step, the remaining steps or the results of the
+    /// tools called so far.
+    ///
+    /// # Parameters
+    ///
+    /// * `system` - A function returning the system prompt of a step.
+    ///
+    /// # Returns
+    ///
+    /// The builder in the [`ConversationStage`] state.
+    pub fn system_fn<F>(self, system: F) -> LanguageModelRequestBuilder<M, ConversationStage>
+    where
+        F: Fn(&LanguageModelOptions) -> String + Send + Sync + 'static,
+    {
+        let system_fn: SystemHook = Arc::new(system);
+        LanguageModelRequestBuilder {
+            model: self.model,
+            prompt: self.prompt,
+            options: LanguageModelOptions {
+                system: Some(system_fn(&self.options)),
+                system_fn: Some(system_fn),
+                ..self.options
+            },
+            state: std::marker::PhantomData,
+        }
+    }
+
     /// Sets a simple text prompt for the request.
     ///
     /// This skips the system prompt and goes directly to options.
@@ -188,7 +286,10 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, SystemStage> {
     /// # Returns
     ///
     /// The builder in the [`OptionsStage`] state.
-    pub fn prompt(self, prompt: impl Into<String>) -> LanguageModelRequestBuilder<M, OptionsStage> {
+    pub fn prompt(self, prompt: impl Into<String>) -> LanguageModelRequestBuilder<M, OptionsStage>
+    where
+        M: TextInputSupport,
+    {
         LanguageModelRequestBuilder {
             model: self.model,
             prompt: Some(prompt.into()),
@@ -197,6 +298,32 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, SystemStage> {
         }
     }
 
+    /// Sets the prompt of the request from a template.
+    ///
+    /// # Parameters
+    ///
+    /// * `template` - The prompt template.
+    /// * `variables` - The variables of the template, such as a struct or a `HashMap`.
+    ///
+    /// # Returns
+    ///
+    /// The builder in the [`OptionsStage`] state.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PromptError`] if the template can not be rendered.
+    #[cfg(feature = "prompt")]
+    pub fn prompt_template(
+        self,
+        template: &PromptTemplate,
+        variables: &impl Serialize,
+    ) -> Result<LanguageModelRequestBuilder<M, OptionsStage>>
+    where
+        M: TextInputSupport,
+    {
+        Ok(self.prompt(template.render(variables)?))
+    }
+
     /// Sets conversation messages for the request.
     ///
     /// This allows for multi-turn conversations with the model.
@@ -208,7 +335,10 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, SystemStage> {
     /// # Returns
     ///
     /// The builder in the [`OptionsStage`] state.
-    pub fn messages(self, messages: Messages) -> LanguageModelRequestBuilder<M, OptionsStage> {
+    pub fn messages(self, messages: Messages) -> LanguageModelRequestBuilder<M, OptionsStage>
+    where
+        M: TextInputSupport,
+    {
         LanguageModelRequestBuilder {
             model: self.model,
             prompt: self.prompt,
@@ -219,6 +349,50 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, SystemStage> {
             state: std::marker::PhantomData,
         }
     }
+
+    /// Continues the conversation of a previous request.
+    ///
+    /// Imports the options, messages and step counter of the previous request, so
+    /// follow-up turns do not require rebuilding the message list. Messages added
+    /// afterwards with [`prompt`](LanguageModelRequestBuilder::prompt) or
+    /// [`messages`](LanguageModelRequestBuilder::messages) are appended in a new step.
+    ///
+    /// # Parameters
+    ///
+    /// * `previous` - The previous response, e.g. a [`GenerateTextResponse`](crate::core::GenerateTextResponse).
+    ///
+    /// # Returns
+    ///
+    /// The builder in the [`ConversationStage`] state.
+    pub fn continue_from(
+        self,
+        previous: &LanguageModelOptions,
+    ) -> LanguageModelRequestBuilder<M, ConversationStage> {
+        LanguageModelRequestBuilder {
+            model: self.model,
+            prompt: None,
+            options: LanguageModelOptions {
+                stop_reason: None,
+                pricing: None,
+                ..previous.clone()
+            },
+            state: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<M: LanguageModel, State> LanguageModelRequestBuilder<M, State> {
+    /// Appends input messages to a continued conversation in a new step.
+    fn append_input(mut options: LanguageModelOptions, messages: Messages) -> LanguageModelOptions {
+        options.current_step_id += 1;
+        let step_id = options.current_step_id;
+        options.messages.extend(
+            messages
+                .into_iter()
+                .map(|msg| TaggedMessage::new(step_id, msg)),
+        );
+        options
+    }
 }
 
 /// Methods available in the [`ConversationStage`] state.
@@ -239,6 +413,11 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, ConversationStage> {
     where
         M: TextInputSupport,
     {
+        // Continued conversations already have messages, so the prompt is appended
+        if !self.options.messages.is_empty() {
+            return self.messages(vec![Message::User(prompt.into().into())]);
+        }
+
         LanguageModelRequestBuilder {
             model: self.model,
             prompt: Some(prompt.into()),
@@ -247,10 +426,39 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, ConversationStage> {
         }
     }
 
+    /// Sets the prompt of the request from a template.
+    ///
+    /// When continuing a previous conversation, the prompt is appended to it.
+    ///
+    /// # Parameters
+    ///
+    /// * `template` - The prompt template.
+    /// * `variables` - The variables of the template, such as a struct or a `HashMap`.
+    ///
+    /// # Returns
+    ///
+    /// The builder in the [`OptionsStage`] state.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PromptError`] if the template can not be rendered.
+    #[cfg(feature = "prompt")]
+    pub fn prompt_template(
+        self,
+        template: &PromptTemplate,
+        variables: &impl Serialize,
+    ) -> Result<LanguageModelRequestBuilder<M, OptionsStage>>
+    where
+        M: TextInputSupport,
+    {
+        Ok(self.prompt(template.render(variables)?))
+    }
+
     /// Sets conversation messages for the request.
     ///
     /// This method allows providing a full conversation history as a vector of messages,
     /// enabling multi-turn conversations with the language model.
+    /// When continuing a previous conversation, the messages are appended to it instead.
     ///
     /// # Parameters
     ///
@@ -263,13 +471,19 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, ConversationStage> {
     where
         M: TextInputSupport,
     {
+        let options = if self.options.messages.is_empty() {
+            LanguageModelOptions {
+                messages: messages.into_iter().map(|msg| msg.into()).collect(),
+                ..self.options
+            }
+        } else {
+            Self::append_input(self.options, messages)
+        };
+
         LanguageModelRequestBuilder {
             model: self.model,
             prompt: self.prompt,
-            options: LanguageModelOptions {
-                messages: messages.into_iter().map(|msg| msg.into()).collect(),
-                ..self.options
-            },
+            options,
             state: std::marker::PhantomData,
         }
     }
@@ -297,6 +511,60 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, OptionsStage> {
         self
     }
 
+    /// Sets the kind of output generated by
+    /// [`generate_object`](LanguageModelRequest::generate_object), such as one
+    /// of a list of values.
+    ///
+    /// # Parameters
+    ///
+    /// * `output` - The kind of output.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the output set.
+    pub fn output(mut self, output: crate::core::language_model::ObjectOutput) -> Self {
+        self.output = Some(output);
+        self
+    }
+
+    /// Sets the repairs applied, in order, to structured output that does not
+    /// parse, such as [`RepairStrategy::LOCAL`].
+    ///
+    /// # Parameters
+    ///
+    /// * `strategies` - The repair strategies.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the repair strategies set.
+    pub fn json_repair(mut self, strategies: impl IntoIterator<Item = RepairStrategy>) -> Self {
+        self.json_repair = strategies.into_iter().collect();
+        self
+    }
+
+    /// Constrains the decoding of the output, such as to any JSON object or to
+    /// a regular expression.
+    ///
+    /// # Parameters
+    ///
+    /// * `constrained_output` - The constraint on the output.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the constraint set. Generation fails with
+    /// [`Error::InvalidInput`](crate::error::Error::InvalidInput) if the
+    /// provider does not support it.
+    pub fn constrained_output(
+        mut self,
+        constrained_output: crate::core::language_model::ConstrainedOutput,
+    ) -> Self
+    where
+        M: StructuredOutputSupport,
+    {
+        self.constrained_output = Some(constrained_output);
+        self
+    }
+
     /// Sets a seed for deterministic generation.
     ///
     /// # Parameters
@@ -311,36 +579,53 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, OptionsStage> {
         self
     }
 
-    /// Sets the temperature for generation randomness (0-100, scaled to 0.0-1.0).
+    /// Sets the temperature for generation randomness.
     ///
     /// Higher values increase creativity, lower values increase determinism.
+    /// Providers accept values between 0.0 and 1.0 or 2.0, and fail the request
+    /// with [`Error::InvalidInput`](crate::error::Error::InvalidInput) otherwise.
     ///
     /// # Parameters
     ///
-    /// * `temperature` - The temperature value (0-100).
+    /// * `temperature` - The temperature value, such as `0.7`.
     ///
     /// # Returns
     ///
     /// The builder with the temperature set.
-    pub fn temperature(mut self, temperature: impl Into<u32>) -> Self {
-        self.temperature = Some(temperature.into());
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
         self
     }
 
-    /// Sets the top-p (nucleus) sampling parameter (0-100, scaled to 0.0-1.0).
+    /// Sets the temperature on the former 0-100 scale, scaled to 0.0-1.0.
+    #[deprecated(note = "use `temperature` with a value such as 0.7 instead")]
+    pub fn temperature_percent(self, temperature: u32) -> Self {
+        self.temperature(temperature as f32 / 100.0)
+    }
+
+    /// Sets the top-p (nucleus) sampling parameter.
+    ///
+    /// Requests with a value outside of 0.0 to 1.0 fail with
+    /// [`Error::InvalidInput`](crate::error::Error::InvalidInput).
     ///
     /// # Parameters
     ///
-    /// * `top_p` - The top-p value (0-100).
+    /// * `top_p` - The top-p value, such as `0.9`.
     ///
     /// # Returns
     ///
     /// The builder with top-p set.
-    pub fn top_p(mut self, top_p: impl Into<u32>) -> Self {
-        self.top_p = Some(top_p.into());
+    pub fn top_p(mut self, top_p: f32) -> Self {
+        self.top_p = Some(top_p);
         self
     }
 
+    /// Sets top-p on the former 0-100 scale, scaled to 0.0-1.0.
+    #[deprecated(note = "use `top_p` with a value such as 0.9 instead")]
+    pub fn top_p_percent(self, top_p: u32) -> Self {
+        self.top_p(top_p as f32 / 100.0)
+    }
+
     /// Sets the top-k sampling parameter.
     ///
     /// # Parameters
@@ -371,6 +656,11 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, OptionsStage> {
 
     /// Sets the maximum number of retries for failed requests.
     ///
+    /// Rate limited (429) and transient server errors are retried, waiting as long
+    /// as the provider's `Retry-After` header asks. Set to 0 to disable retries and
+    /// handle [`Error::RateLimited`](crate::error::Error::RateLimited) yourself.
+    /// Defaults to 5.
+    ///
     /// # Parameters
     ///
     /// * `max_retries` - The maximum retry count.
@@ -383,8 +673,37 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, OptionsStage> {
         self
     }
 
+    /// Sets the maximum number of times a stream is resumed after a transient
+    /// failure, such as a dropped connection, in the middle of a step.
+    ///
+    /// The step is requested again and the text and reasoning that were already
+    /// streamed are skipped, so readers of the stream do not see them twice. The
+    /// stream fails with the original error if the new response diverges from
+    /// what was streamed, or if tool calls were already streamed. The estimated
+    /// usage of the abandoned attempts is added to the usage of the step.
+    ///
+    /// Defaults to 2 for requests with a temperature of 0 or a seed, and to 0,
+    /// which disables resuming, for the others: their resumed responses rarely
+    /// repeat what was streamed.
+    ///
+    /// # Parameters
+    ///
+    /// * `max_stream_resumes` - The maximum resume count per step.
+    ///
+    /// # Returns
+    ///
+    /// The builder with max stream resumes set.
+    pub fn max_stream_resumes(mut self, max_stream_resumes: impl Into<u32>) -> Self {
+        self.max_stream_resumes = Some(max_stream_resumes.into());
+        self
+    }
+
     /// Sets the frequency penalty to reduce repetition.
     ///
+    /// Positive values penalize tokens by how often they already appeared. Most
+    /// providers accept values between -2.0 and 2.0, and reject the request with
+    /// [`Error::InvalidInput`](crate::error::Error::InvalidInput) otherwise.
+    ///
     /// # Parameters
     ///
     /// * `frequency_penalty` - The penalty value.
@@ -397,6 +716,136 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, OptionsStage> {
         self
     }
 
+    /// Requests the log probabilities of the generated tokens.
+    ///
+    /// The log probabilities are available with
+    /// [`logprobs`](crate::core::language_model::LanguageModelOptions::logprobs) on
+    /// the response, and as [`Logprobs`](crate::core::LanguageModelStreamChunkType::Logprobs)
+    /// chunks when streaming. Providers that do not support them ignore the option.
+    ///
+    /// # Parameters
+    ///
+    /// * `top_n` - The number of most likely alternatives to return with each token.
+    ///
+    /// # Returns
+    ///
+    /// The builder with log probabilities requested.
+    pub fn logprobs(mut self, top_n: u32) -> Self {
+        self.logprobs = Some(top_n);
+        self
+    }
+
+    /// Generates several candidate responses for each step.
+    ///
+    /// Providers that support it generate the candidates in a single request, and
+    /// the missing candidates are requested separately otherwise. The candidate
+    /// selected with [`best_of`](Self::best_of), or the first one, continues the
+    /// conversation, and all of them are available with
+    /// [`candidates`](crate::core::language_model::LanguageModelOptions::candidates).
+    /// Only used by `generate_text`, streams generate a single candidate.
+    ///
+    /// # Parameters
+    ///
+    /// * `n` - The number of candidates to generate.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the number of candidates set.
+    pub fn candidates(mut self, n: u32) -> Self {
+        self.candidate_count = Some(n);
+        self
+    }
+
+    /// Bounds the number of chunks a stream buffers for its consumer.
+    ///
+    /// Streams are unbounded by default, so a slow consumer makes the buffer grow.
+    /// With a capacity, the [`Backpressure`](crate::core::language_model::Backpressure)
+    /// policy decides what happens when the buffer is full. The conversation is
+    /// locked while a step streams, so drain the stream before awaiting methods
+    /// such as `text` on the response.
+    ///
+    /// # Parameters
+    ///
+    /// * `capacity` - The number of chunks to buffer, at least 1.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the stream capacity set.
+    pub fn stream_capacity(mut self, capacity: usize) -> Self {
+        self.stream_capacity = Some(capacity);
+        self
+    }
+
+    /// Sets what a bounded stream does when its consumer falls behind.
+    ///
+    /// Only used with a [`stream_capacity`](Self::stream_capacity).
+    ///
+    /// # Parameters
+    ///
+    /// * `backpressure` - The backpressure policy.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the backpressure policy set.
+    pub fn backpressure(mut self, backpressure: Backpressure) -> Self {
+        self.backpressure = backpressure;
+        self
+    }
+
+    /// Sends a [`Ping`](LanguageModelStreamChunkType::Ping) chunk at the given
+    /// interval while tools execute.
+    ///
+    /// A stream is silent while a slow tool runs, and proxies or clients may close
+    /// idle connections. The pings keep them open, and integrations send them as
+    /// keep-alive comments. Nothing is sent during tool executions by default.
+    ///
+    /// # Parameters
+    ///
+    /// * `interval` - The time between two pings.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the ping interval set.
+    pub fn ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = Some(interval);
+        self
+    }
+
+    /// Sets the hook selecting the candidate that continues the conversation.
+    ///
+    /// # Parameters
+    ///
+    /// * `hook` - A function returning the index of the selected candidate.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the selection hook set.
+    pub fn best_of<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&[Candidate]) -> usize + Send + Sync + 'static,
+    {
+        self.best_of = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets the presence penalty to encourage new topics.
+    ///
+    /// Positive values penalize tokens that already appeared at all. Most
+    /// providers accept values between -2.0 and 2.0, and reject the request with
+    /// [`Error::InvalidInput`](crate::error::Error::InvalidInput) otherwise.
+    ///
+    /// # Parameters
+    ///
+    /// * `presence_penalty` - The penalty value.
+    ///
+    /// # Returns
+    ///
+    /// The builder with presence penalty set.
+    pub fn presence_penalty(mut self, presence_penalty: impl Into<f32>) -> Self {
+        self.presence_penalty = Some(presence_penalty.into());
+        self
+    }
+
     /// Adds a tool to the request.
     ///
     /// # Arguments
@@ -414,8 +863,74 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, OptionsStage> {
         self
     }
 
+    /// Sets whether and which tools the model must call.
+    ///
+    /// # Parameters
+    ///
+    /// * `choice` - The tool choice, which defaults to [`ToolChoice::Auto`].
+    ///
+    /// # Returns
+    ///
+    /// The builder with the tool choice set.
+    pub fn tool_choice(mut self, choice: ToolChoice) -> Self
+    where
+        M: ToolCallSupport,
+    {
+        self.tool_choice = Some(choice);
+        self
+    }
+
+    /// Sets the policy that decides which tool calls need approval before they are executed.
+    ///
+    /// `generate_text` stops with [`StopReason::ToolApprovalRequired`](crate::core::language_model::StopReason::ToolApprovalRequired) when such a tool
+    /// is called, and `stream_text` pauses until the call is approved or denied on the
+    /// [`StreamTextResponse`](crate::core::StreamTextResponse).
+    ///
+    /// # Parameters
+    ///
+    /// * `policy` - The approval policy.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the approval policy set.
+    pub fn approval_policy(mut self, policy: ApprovalPolicy) -> Self
+    where
+        M: ToolCallSupport,
+    {
+        self.approval_policy = Some(policy);
+        self
+    }
+
+    /// Requires approval before calls of the given tools are executed.
+    ///
+    /// This is a shorthand for [`ApprovalPolicy::AlwaysForTools`], and adds to the
+    /// list if that policy is already set.
+    ///
+    /// # Parameters
+    ///
+    /// * `tools` - The names of the tools that need approval.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the tools marked as needing approval.
+    pub fn needs_approval(mut self, tools: impl IntoIterator<Item = impl Into<String>>) -> Self
+    where
+        M: ToolCallSupport,
+    {
+        let tools = tools.into_iter().map(Into::into);
+        match self.approval_policy {
+            Some(ApprovalPolicy::AlwaysForTools(ref mut list)) => list.extend(tools),
+            _ => self.approval_policy = Some(ApprovalPolicy::AlwaysForTools(tools.collect())),
+        }
+        self
+    }
+
     /// Sets a condition to stop the generation loop.
     ///
+    /// Ready-made conditions, such as
+    /// [`stop_after_steps`](crate::core::utils::stop_after_steps), and combinators
+    /// are available in [`utils`](crate::core::utils).
+    ///
     /// # Parameters
     ///
     /// * `hook` - A function that returns `true` when generation should stop.
@@ -448,6 +963,54 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, OptionsStage> {
         self
     }
 
+    /// Sets a hook choosing the model and tools of each generation step.
+    ///
+    /// The hook is called before each step, after the
+    /// [`on_step_start`](Self::on_step_start) hook, and can switch to another
+    /// model, restrict the tools offered to the model, or force a tool call, for
+    /// that step only.
+    ///
+    /// # Parameters
+    ///
+    /// * `hook` - A function returning the settings of the step.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the hook set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    ///# #[cfg(feature = "openai")]
+    ///# {
+    /// use aisdk::core::LanguageModelRequest;
+    /// use aisdk::core::language_model::StepSettings;
+    /// use aisdk::core::tools::ToolChoice;
+    /// use aisdk::providers::OpenAI;
+    ///
+    /// # fn search() -> aisdk::core::Tool { unimplemented!() }
+    /// # fn get_weather() -> aisdk::core::Tool { unimplemented!() }
+    /// let request = LanguageModelRequest::builder()
+    ///     .model(OpenAI::gpt_5())
+    ///     .prompt("Find the weather in Paris")
+    ///     .with_tool(search())
+    ///     .with_tool(get_weather())
+    ///     // Search first, then let the model choose
+    ///     .prepare_step(|options| match options.current_step() {
+    ///         1 => StepSettings::default().tool_choice(ToolChoice::Tool("search".into())),
+    ///         _ => StepSettings::default(),
+    ///     })
+    ///     .build();
+    ///# }
+    /// ```
+    pub fn prepare_step<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&LanguageModelOptions) -> StepSettings + Send + Sync + 'static,
+    {
+        self.prepare_step = Some(Arc::new(hook));
+        self
+    }
+
     /// Sets a hook to run at the end of each generation step.
     ///
     /// # Parameters
@@ -465,6 +1028,135 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, OptionsStage> {
         self
     }
 
+    /// Sets a hook to run for every chunk emitted by [`stream_text`](LanguageModelRequest::stream_text).
+    ///
+    /// # Parameters
+    ///
+    /// * `hook` - A function called with each chunk before it is sent to the stream.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the hook set.
+    pub fn on_chunk<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&LanguageModelStreamChunkType) + Send + Sync + 'static,
+    {
+        self.on_chunk = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets a hook to run when generation fails.
+    ///
+    /// # Parameters
+    ///
+    /// * `hook` - A function called with the error of the model call.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the hook set.
+    pub fn on_error<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Error) + Send + Sync + 'static,
+    {
+        self.on_error = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets a hook to run once all generation steps have finished.
+    ///
+    /// The hook is not called when generation fails; [`on_error`](Self::on_error)
+    /// is called instead.
+    ///
+    /// # Parameters
+    ///
+    /// * `hook` - A function called with the final state of the conversation.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the hook set.
+    pub fn on_finish<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&LanguageModelOptions) + Send + Sync + 'static,
+    {
+        self.on_finish = Some(Arc::new(hook));
+        self
+    }
+
+    /// Adds a sink receiving the events of the request, in addition to the
+    /// global sinks.
+    ///
+    /// # Parameters
+    ///
+    /// * `sink` - The sink receiving the requests, responses, chunks, tool calls and errors.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the sink added.
+    pub fn event_sink(mut self, sink: impl EventSink + 'static) -> Self {
+        self.event_sinks.push(Arc::new(sink));
+        self
+    }
+
+    /// Logs the options sent to the model at each step, the responses with
+    /// their provider IDs, and the executed tool calls.
+    ///
+    /// The lines are logged at the debug level under the `aisdk` target, with
+    /// `tracing` when the `tracing` feature is enabled and with `log` otherwise.
+    ///
+    /// # Parameters
+    ///
+    /// * `enabled` - Whether to log the steps.
+    ///
+    /// # Returns
+    ///
+    /// The builder with debug logging set.
+    pub fn debug(mut self, enabled: bool) -> Self {
+        self.debug = enabled;
+        self
+    }
+
+    /// Adds a guard on the prompt, run before it is sent to the model.
+    ///
+    /// The guard can allow, rewrite or reject the latest user message. A rejected
+    /// prompt stops generation with [`Error::GuardrailViolation`]. See
+    /// [`guardrails`](crate::core::language_model::guardrails).
+    ///
+    /// # Parameters
+    ///
+    /// * `guard` - A function deciding on the text of the prompt.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the guard added.
+    pub fn input_guard<F>(mut self, guard: F) -> Self
+    where
+        F: Fn(&str) -> GuardDecision + Send + Sync + 'static,
+    {
+        self.input_guards.push(Arc::new(guard));
+        self
+    }
+
+    /// Adds a guard on the text responses of the model.
+    ///
+    /// The guard can allow, rewrite or reject each text response. A rejected
+    /// response is dropped, and stops generation with [`Error::GuardrailViolation`].
+    /// See [`guardrails`](crate::core::language_model::guardrails).
+    ///
+    /// # Parameters
+    ///
+    /// * `guard` - A function deciding on the text of a response.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the guard added.
+    pub fn output_guard<F>(mut self, guard: F) -> Self
+    where
+        F: Fn(&str) -> GuardDecision + Send + Sync + 'static,
+    {
+        self.output_guards.push(Arc::new(guard));
+        self
+    }
+
     /// Sets the reasoning effort level.
     ///
     /// # Parameters
@@ -485,6 +1177,159 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, OptionsStage> {
         self
     }
 
+    /// Sets the number of tokens the model may spend thinking.
+    ///
+    /// Used by providers with budget-based reasoning, such as Anthropic's extended
+    /// thinking, in place of the budget derived from the reasoning effort. The
+    /// budget counts towards the maximum number of output tokens.
+    ///
+    /// # Parameters
+    ///
+    /// * `tokens` - The thinking budget in tokens.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the thinking budget set.
+    pub fn thinking_budget(mut self, tokens: u32) -> Self
+    where
+        M: ReasoningSupport,
+    {
+        self.thinking_budget = Some(tokens);
+        self
+    }
+
+    /// Continues the conversation of a response stored by the provider.
+    ///
+    /// Only the new messages are sent, which saves resending the whole history
+    /// to providers that store responses, such as the OpenAI Responses API. The
+    /// ID of a response is available as
+    /// [`response_id`](crate::core::language_model::LanguageModelOptions::response_id)
+    /// on the result of [`generate_text`](LanguageModelRequest::generate_text), and
+    /// in the [`response_metadata`](crate::core::StreamTextResponse::response_metadata)
+    /// of a streamed response. The chain advances with each step of a request.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - The ID of the response to continue from.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the previous response ID set.
+    pub fn previous_response_id(mut self, id: impl Into<String>) -> Self {
+        self.previous_response_id = Some(id.into());
+        self
+    }
+
+    /// Sets whether the provider stores the request and its response.
+    ///
+    /// Privacy-sensitive deployments set it to `false` to opt out of
+    /// server-side retention, for providers supporting it such as OpenAI.
+    /// Responses that are not stored can not be continued with
+    /// [`previous_response_id`](Self::previous_response_id).
+    ///
+    /// # Parameters
+    ///
+    /// * `store` - Whether the provider stores the request.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the storage preference set.
+    pub fn store(mut self, store: bool) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Tags the request with a key-value pair stored with it by the provider,
+    /// sent as the `metadata` of OpenAI and the request metadata of Amazon
+    /// Bedrock.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key of the tag.
+    /// * `value` - The value of the tag.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the tag added.
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.request_metadata
+            .get_or_insert_default()
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets the ID of the end user the request is made for, sent as the `user`
+    /// of OpenAI and the `metadata.user_id` of Anthropic. Use an opaque ID,
+    /// such as a hash of the username, rather than personal information.
+    ///
+    /// # Parameters
+    ///
+    /// * `user` - The ID of the end user.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the user set.
+    pub fn user(mut self, user: impl Into<String>) -> Self {
+        self.user = Some(user.into());
+        self
+    }
+
+    /// Fits the conversation into the model's context window before each step.
+    ///
+    /// The size of the conversation is estimated with [`crate::core::tokens`] and
+    /// compared against the model's context window, minus the output tokens reserved
+    /// for the response. When it does not fit, the given strategy is applied to the
+    /// conversation, which later steps and the response keep. Truncation is skipped if
+    /// the context window of the model is unknown; use
+    /// [`context_window`](Self::context_window) to provide one.
+    ///
+    /// # Parameters
+    ///
+    /// * `strategy` - How to shrink the conversation when it does not fit.
+    ///
+    /// # Returns
+    ///
+    /// The builder with truncation enabled.
+    pub fn truncate_to_fit(mut self, strategy: TruncationStrategy) -> Self {
+        self.truncation = Some(strategy);
+        self
+    }
+
+    /// Sets the context window used for truncation.
+    ///
+    /// This overrides the context window reported by the model, and is required
+    /// for truncation with models that do not declare one (e.g. `DynamicModel`).
+    ///
+    /// # Parameters
+    ///
+    /// * `tokens` - The maximum number of tokens the model accepts.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the context window set.
+    pub fn context_window(mut self, tokens: usize) -> Self {
+        self.options.context_window = Some(tokens);
+        self
+    }
+
+    /// Keeps the conversation in a memory across requests.
+    ///
+    /// The remembered messages are sent before the prompt, and the messages of
+    /// the request are appended to the memory once it finishes. Requests that
+    /// fail or wait for tool approvals are not remembered.
+    ///
+    /// # Parameters
+    ///
+    /// * `memory` - The memory, shared with the other requests of the conversation.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the memory set.
+    pub fn memory(mut self, memory: impl Memory + 'static) -> Self {
+        self.options.memory = Some(Arc::new(memory));
+        self
+    }
+
     /// Sets custom HTTP headers for the request.
     ///
     /// These headers will be merged with the provider's default headers.
@@ -499,9 +1344,149 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, OptionsStage> {
         self
     }
 
+    /// Adds custom HTTP headers to the request, such as the tags of a gateway or
+    /// the attribution headers of a router.
+    ///
+    /// The headers are merged with the ones already set, and take precedence over
+    /// the provider's default headers.
+    ///
+    /// # Parameters
+    ///
+    /// * `headers` - Pairs of header names and values.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the headers added.
+    pub fn extra_headers<K, V>(mut self, headers: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.options
+            .headers
+            .get_or_insert_default()
+            .extend(headers.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
+    /// Adds query parameters to the URL of the request, such as a tenant id.
+    ///
+    /// The parameters are merged with the ones already set, and sent along with
+    /// the provider's own parameters.
+    ///
+    /// # Parameters
+    ///
+    /// * `query` - Pairs of parameter names and values.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the query parameters added.
+    pub fn extra_query<K, V>(mut self, query: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.options
+            .query
+            .get_or_insert_default()
+            .extend(query.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
+    /// Returns the problems of the configuration that would make the request fail.
+    ///
+    /// The capabilities are checked against
+    /// [`LanguageModel::capabilities`], so models picked at runtime, such as
+    /// [`DynamicModel`] providers and boxed models, are checked too.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        let has_prompt = self.prompt.as_ref().is_some_and(|p| !p.is_empty());
+        if !has_prompt && self.options.messages.is_empty() {
+            issues.push(ValidationIssue::EmptyConversation);
+        }
+
+        let capabilities = self
+            .model
+            .as_ref()
+            .map_or_else(CapabilitySet::all, |model| model.capabilities());
+        let has_tools = self
+            .options
+            .tools
+            .as_ref()
+            .is_some_and(|tools| !tools.tools.lock().map_or(true, |t| t.is_empty()));
+        let has_images = self.options.messages.iter().any(|t| match &t.message {
+            Message::User(user) => user.files.iter().any(|file| file.is_image()),
+            _ => false,
+        });
+        let required = [
+            (
+                "schema",
+                self.options.schema.is_some(),
+                Capability::StructuredOutput,
+            ),
+            ("tools", has_tools, Capability::ToolCall),
+            (
+                "reasoning_effort",
+                self.options.reasoning_effort.is_some(),
+                Capability::Reasoning,
+            ),
+            (
+                "thinking_budget",
+                self.options.thinking_budget.is_some(),
+                Capability::Reasoning,
+            ),
+            ("files", has_images, Capability::ImageInput),
+        ];
+        for (option, set, capability) in required {
+            if set && !capabilities.contains(capability) {
+                issues.push(ValidationIssue::UnsupportedCapability { option, capability });
+            }
+        }
+
+        let ranges = [
+            ("temperature", self.options.temperature, 0.0, 2.0),
+            ("top_p", self.options.top_p, 0.0, 1.0),
+        ];
+        for (option, value, min, max) in ranges {
+            if let Some(value) = value
+                && !(min..=max).contains(&value)
+            {
+                issues.push(ValidationIssue::OutOfRange {
+                    option,
+                    value,
+                    min,
+                    max,
+                });
+            }
+        }
+
+        issues
+    }
+
+    /// Builds the `LanguageModelRequest`, checking its configuration first.
+    ///
+    /// # Returns
+    ///
+    /// The constructed `LanguageModelRequest`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfiguration`] listing every problem found by
+    /// [`validate`](Self::validate).
+    pub fn try_build(self) -> Result<LanguageModelRequest<M>> {
+        let issues = self.validate();
+        if !issues.is_empty() {
+            return Err(Error::InvalidConfiguration(issues));
+        }
+        Ok(self.build())
+    }
+
     /// Builds the `LanguageModelRequest`.
     ///
-    /// This method consumes the builder and returns the configured request.
+    /// This method consumes the builder and returns the configured request,
+    /// without checking it. Use [`try_build`](Self::try_build) to catch
+    /// configurations that can only fail before the request is sent.
     ///
     /// # Returns
     ///
@@ -518,3 +1503,100 @@ impl<M: LanguageModel> LanguageModelRequestBuilder<M, OptionsStage> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::MockLanguageModel;
+
+    /// Only reads and writes text.
+    fn text_only_model() -> MockLanguageModel {
+        MockLanguageModel::new().with_capabilities(
+            CapabilitySet::empty()
+                .with(Capability::TextInput)
+                .with(Capability::TextOutput),
+        )
+    }
+
+    /// Builds a request with `prompt` and `messages`, which require [`TextInputSupport`].
+    fn build_text_requests<M: LanguageModel + TextInputSupport>(model: M) {
+        LanguageModelRequest::builder()
+            .model(model.clone())
+            .prompt("Hello")
+            .build();
+        LanguageModelRequest::builder()
+            .model(model)
+            .messages(vec![Message::User("Hello".into())])
+            .build();
+    }
+
+    #[test]
+    fn test_model_wrappers_accept_text_input() {
+        use crate::core::language_model::fallback::FallbackModel;
+        use crate::core::language_model::middleware::{
+            default_settings::with_defaults, wrap_model,
+        };
+        use crate::core::language_model::routed::RoutedModel;
+
+        let model = MockLanguageModel::new();
+        build_text_requests(model.clone().boxed());
+        build_text_requests(with_defaults(
+            model.clone(),
+            LanguageModelOptions::default(),
+        ));
+        build_text_requests(wrap_model(
+            model.clone(),
+            crate::core::rate_limit::RateLimiter::new(),
+        ));
+        build_text_requests(FallbackModel::new(vec![model.clone()]));
+        build_text_requests(RoutedModel::round_robin(vec![model.clone()]));
+        #[cfg(feature = "tower")]
+        build_text_requests(crate::integrations::tower::with_layer(
+            model,
+            tower::layer::util::Identity::new(),
+        ));
+    }
+
+    #[test]
+    fn test_try_build_lists_every_problem() {
+        let result = LanguageModelRequest::builder()
+            .model(text_only_model().boxed())
+            .messages(vec![])
+            .with_tool(Tool {
+                name: "search".to_string(),
+                ..Default::default()
+            })
+            .temperature(3.0)
+            .try_build();
+
+        let Err(Error::InvalidConfiguration(issues)) = result else {
+            panic!("expected an invalid configuration");
+        };
+        assert_eq!(
+            issues,
+            vec![
+                ValidationIssue::EmptyConversation,
+                ValidationIssue::UnsupportedCapability {
+                    option: "tools",
+                    capability: Capability::ToolCall,
+                },
+                ValidationIssue::OutOfRange {
+                    option: "temperature",
+                    value: 3.0,
+                    min: 0.0,
+                    max: 2.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_try_build_accepts_valid_configuration() {
+        let request = LanguageModelRequest::builder()
+            .model(text_only_model().boxed())
+            .prompt("Hi")
+            .temperature(0.7)
+            .try_build();
+        assert!(request.is_ok());
+    }
+}
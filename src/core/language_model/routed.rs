@@ -0,0 +1,378 @@
+//! Load balancing across multiple language models.
+//!
+//! [`RoutedModel`] distributes requests over a set of models, e.g. the same
+//! underlying model served by several providers, using one of the following
+//! strategies:
+//!
+//! - **Round-robin**: models are used in turn.
+//! - **Weighted**: models are used in proportion to their weights.
+//! - **Least-latency**: the model with the lowest observed latency is used.
+//!
+//! Routing state is shared between clones of a `RoutedModel`, so requests built
+//! from the same model are balanced together.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::{DynamicModel, LanguageModelRequest};
+//! use aisdk::core::language_model::routed::RoutedModel;
+//! use aisdk::providers::OpenAI;
+//!
+//! # async fn run() -> aisdk::Result<()> {
+//! let model = RoutedModel::weighted(vec![
+//!     (OpenAI::<DynamicModel>::model_name("gpt-5"), 3),
+//!     (OpenAI::<DynamicModel>::model_name("gpt-5-mini"), 1),
+//! ]);
+//!
+//! let result = LanguageModelRequest::builder()
+//!     .model(model)
+//!     .prompt("What is the capital of France?")
+//!     .build()
+//!     .generate_text()
+//!     .await?;
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+use crate::core::Message;
+use crate::core::capabilities::{CapabilitySet, forward_capabilities};
+use crate::core::language_model::{
+    LanguageModel, LanguageModelOptions, LanguageModelResponse, ProviderStream,
+};
+use crate::core::pricing::ModelPricing;
+use crate::core::runtime::Instant;
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Weight of the latest measurement in the moving average used by least-latency routing.
+const LATENCY_SMOOTHING: f64 = 0.3;
+
+/// Latency added to a failed request, so least-latency routing moves away from failing models.
+const FAILURE_PENALTY: Duration = Duration::from_secs(30);
+
+/// Strategies for distributing requests over the models of a [`RoutedModel`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoutingStrategy {
+    /// Uses the models in turn.
+    RoundRobin,
+    /// Uses the models in proportion to their weights. Models with a weight of 0 are not used.
+    Weighted(Vec<u32>),
+    /// Uses the model with the lowest observed latency. Failed requests count as
+    /// slow requests, so failing models are avoided.
+    LeastLatency,
+}
+
+/// Routing state shared between clones of a [`RoutedModel`].
+#[derive(Debug, Default)]
+struct RoutingState {
+    /// Next model for round-robin routing.
+    next: AtomicUsize,
+    /// Current weights for smooth weighted round-robin routing.
+    current_weights: Mutex<Vec<i64>>,
+    /// Latency measurements of each model.
+    latencies: Mutex<Vec<LatencyStats>>,
+}
+
+/// Latency measurements of a model, used by least-latency routing.
+#[derive(Debug, Default, Clone)]
+struct LatencyStats {
+    /// Moving average of the latency, including failure penalties.
+    average: Option<Duration>,
+    /// Whether a request probing the model's latency is in flight.
+    probing: bool,
+}
+
+/// A language model that distributes requests over a set of models.
+#[derive(Debug, Clone)]
+pub struct RoutedModel<M: LanguageModel> {
+    models: Vec<M>,
+    strategy: RoutingStrategy,
+    state: Arc<RoutingState>,
+}
+
+impl<M: LanguageModel> RoutedModel<M> {
+    /// Creates a new routed model with the given strategy.
+    ///
+    /// For [`RoutingStrategy::Weighted`], models without a weight get a weight of 1.
+    pub fn new(models: Vec<M>, strategy: RoutingStrategy) -> Self {
+        let state = RoutingState {
+            current_weights: Mutex::new(vec![0; models.len()]),
+            latencies: Mutex::new(vec![LatencyStats::default(); models.len()]),
+            ..Default::default()
+        };
+        Self {
+            models,
+            strategy,
+            state: Arc::new(state),
+        }
+    }
+
+    /// Creates a new routed model that uses the models in turn.
+    pub fn round_robin(models: Vec<M>) -> Self {
+        Self::new(models, RoutingStrategy::RoundRobin)
+    }
+
+    /// Creates a new routed model that uses the models in proportion to their weights.
+    pub fn weighted(models: Vec<(M, u32)>) -> Self {
+        let (models, weights) = models.into_iter().unzip();
+        Self::new(models, RoutingStrategy::Weighted(weights))
+    }
+
+    /// Creates a new routed model that uses the model with the lowest observed latency.
+    ///
+    /// Models without a measurement yet are probed first, one request each.
+    /// Failed requests are measured with a penalty, so failing models are avoided.
+    pub fn least_latency(models: Vec<M>) -> Self {
+        Self::new(models, RoutingStrategy::LeastLatency)
+    }
+
+    /// Returns the routing strategy.
+    pub fn strategy(&self) -> &RoutingStrategy {
+        &self.strategy
+    }
+
+    /// Returns the models requests are distributed over.
+    pub fn models(&self) -> &[M] {
+        &self.models
+    }
+
+    /// Selects the model for the next request.
+    fn select(&self) -> usize {
+        let len = self.models.len();
+        match &self.strategy {
+            RoutingStrategy::RoundRobin => self.state.next.fetch_add(1, Ordering::Relaxed) % len,
+            RoutingStrategy::Weighted(weights) => {
+                // Smooth weighted round-robin, which spreads out picks of heavier models
+                let weight = |i: usize| i64::from(weights.get(i).copied().unwrap_or(1));
+                let total: i64 = (0..len).map(weight).sum();
+                let mut current = self.state.current_weights.lock();
+                for (i, w) in current.iter_mut().enumerate() {
+                    *w += weight(i);
+                }
+                let selected = (0..len)
+                    .filter(|&i| weight(i) > 0 || total == 0)
+                    .max_by_key(|&i| (current[i], -(i as i64)))
+                    .unwrap_or(0);
+                current[selected] -= total;
+                selected
+            }
+            RoutingStrategy::LeastLatency => {
+                let mut latencies = self.state.latencies.lock();
+                let unprobed = (0..len).find(|&i| {
+                    let stats = &latencies[i];
+                    stats.average.is_none() && !stats.probing
+                });
+                if let Some(index) = unprobed {
+                    latencies[index].probing = true;
+                    return index;
+                }
+                (0..len)
+                    .min_by_key(|&i| latencies[i].average.unwrap_or(Duration::MAX))
+                    .unwrap_or(0)
+            }
+        }
+    }
+
+    /// Records the latency of a request to the model at `index`.
+    ///
+    /// Failed requests are recorded with [`FAILURE_PENALTY`] added to their latency.
+    fn record_latency(&self, index: usize, elapsed: Duration, failed: bool) {
+        let elapsed = if failed {
+            elapsed + FAILURE_PENALTY
+        } else {
+            elapsed
+        };
+        let mut latencies = self.state.latencies.lock();
+        let stats = &mut latencies[index];
+        stats.probing = false;
+        stats.average = Some(match stats.average {
+            Some(average) => {
+                average.mul_f64(1.0 - LATENCY_SMOOTHING) + elapsed.mul_f64(LATENCY_SMOOTHING)
+            }
+            None => elapsed,
+        });
+    }
+
+    fn no_models() -> Error {
+        Error::InvalidInput("RoutedModel has no models".to_string())
+    }
+}
+
+#[async_trait]
+impl<M: LanguageModel> LanguageModel for RoutedModel<M> {
+    /// Returns the name of the first model.
+    fn name(&self) -> String {
+        self.models.first().map(|m| m.name()).unwrap_or_default()
+    }
+
+    async fn generate_text(
+        &mut self,
+        options: LanguageModelOptions,
+    ) -> Result<LanguageModelResponse> {
+        if self.models.is_empty() {
+            return Err(Self::no_models());
+        }
+
+        let index = self.select();
+        let start = Instant::now();
+        let response = self.models[index].generate_text(options).await;
+        self.record_latency(index, start.elapsed(), response.is_err());
+        response
+    }
+
+    /// Streams text from the selected model. Latency is measured until the stream is opened.
+    async fn stream_text(&mut self, options: LanguageModelOptions) -> Result<ProviderStream> {
+        if self.models.is_empty() {
+            return Err(Self::no_models());
+        }
+
+        let index = self.select();
+        let start = Instant::now();
+        let stream = self.models[index].stream_text(options).await;
+        self.record_latency(index, start.elapsed(), stream.is_err());
+        stream
+    }
+
+    /// Returns the pricing of the first model.
+    fn pricing(&self) -> Option<ModelPricing> {
+        self.models.first().and_then(|m| m.pricing())
+    }
+
+    /// Returns the smallest known context window, so requests fit any of the models.
+    fn context_window(&self) -> Option<usize> {
+        self.models.iter().filter_map(|m| m.context_window()).min()
+    }
+
+    /// Returns the smallest known output limit, so requests fit any of the models.
+    fn max_output_tokens(&self) -> Option<usize> {
+        self.models
+            .iter()
+            .filter_map(|m| m.max_output_tokens())
+            .min()
+    }
+
+    /// Returns the capabilities all of the models support, so requests work with any of them.
+    fn capabilities(&self) -> CapabilitySet {
+        self.models.iter().fold(CapabilitySet::all(), |set, m| {
+            set.intersection(m.capabilities())
+        })
+    }
+
+    async fn count_tokens(&mut self, messages: &[Message]) -> Result<usize> {
+        match self.models.first_mut() {
+            Some(model) => model.count_tokens(messages).await,
+            None => Ok(crate::core::tokens::estimate_messages_tokens(messages)),
+        }
+    }
+}
+
+forward_capabilities!(RoutedModel);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LanguageModelRequest;
+    use crate::core::language_model::LanguageModelResponseContentType;
+    use crate::providers::MockLanguageModel;
+
+    /// Answers with its name, up to 8 times.
+    fn named(name: &str) -> MockLanguageModel {
+        (0..8).fold(MockLanguageModel::new().with_name(name), |model, _| {
+            model.with_text(name)
+        })
+    }
+
+    async fn route(model: &mut RoutedModel<MockLanguageModel>, requests: usize) -> Vec<String> {
+        let mut names = Vec::new();
+        for _ in 0..requests {
+            let response = model
+                .generate_text(LanguageModelOptions::default())
+                .await
+                .unwrap();
+            match &response.contents[0] {
+                LanguageModelResponseContentType::Text(text) => names.push(text.clone()),
+                other => panic!("Expected text, got {other:?}"),
+            }
+        }
+        names
+    }
+
+    #[tokio::test]
+    async fn test_routed_model_round_robin() {
+        let mut model = RoutedModel::round_robin(vec![named("a"), named("b"), named("c")]);
+        assert_eq!(route(&mut model, 4).await, vec!["a", "b", "c", "a"]);
+    }
+
+    #[tokio::test]
+    async fn test_routed_model_round_robin_shared_between_clones() {
+        let mut model = RoutedModel::round_robin(vec![named("a"), named("b")]);
+        let mut clone = model.clone();
+        assert_eq!(route(&mut model, 1).await, vec!["a"]);
+        assert_eq!(route(&mut clone, 1).await, vec!["b"]);
+    }
+
+    #[tokio::test]
+    async fn test_routed_model_weighted() {
+        let mut model = RoutedModel::weighted(vec![(named("a"), 3), (named("b"), 1)]);
+        let names = route(&mut model, 8).await;
+        assert_eq!(names.iter().filter(|n| *n == "a").count(), 6);
+        assert_eq!(names.iter().filter(|n| *n == "b").count(), 2);
+        // Picks of the heavier model are spread out
+        assert_eq!(names[..4], ["a", "a", "b", "a"]);
+    }
+
+    #[tokio::test]
+    async fn test_routed_model_least_latency() {
+        let slow = named("slow").with_delay(Duration::from_millis(20));
+        let mut model = RoutedModel::least_latency(vec![slow, named("fast")]);
+        // Both models are measured first, then the fastest one is used
+        assert_eq!(
+            route(&mut model, 4).await,
+            vec!["slow", "fast", "fast", "fast"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_routed_model_least_latency_avoids_failing_model() {
+        let failing = MockLanguageModel::new()
+            .with_name("failing")
+            .with_error(Error::Other("down".to_string()));
+        let slow = named("slow").with_delay(Duration::from_millis(20));
+        let mut model = RoutedModel::least_latency(vec![failing.clone(), slow]);
+
+        // The failing model is probed once, then the slower but working model is used
+        let result = model.generate_text(LanguageModelOptions::default()).await;
+        assert!(result.is_err());
+        assert_eq!(route(&mut model, 3).await, vec!["slow", "slow", "slow"]);
+        assert_eq!(failing.calls().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_routed_model_builds_requests() {
+        let model = RoutedModel::round_robin(vec![named("a"), named("b")]);
+        for expected in ["a", "b"] {
+            let result = LanguageModelRequest::builder()
+                .model(model.clone())
+                .prompt("Hello")
+                .build()
+                .generate_text()
+                .await
+                .unwrap();
+            assert_eq!(result.text(), Some(expected.to_string()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_routed_model_without_models() {
+        let mut model = RoutedModel::<MockLanguageModel>::round_robin(vec![]);
+        let result = model.generate_text(LanguageModelOptions::default()).await;
+        assert!(matches!(result, Err(Error::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,200 @@
+//! Smoothing of streamed text for display.
+//!
+//! Providers send text in deltas of arbitrary size, from single characters to
+//! whole sentences, which makes text rendered as it streams look jittery.
+//! [`smooth_stream`] re-chunks the text deltas of a stream into words or lines,
+//! optionally released at a steady pace. Other chunks are passed through in order,
+//! after the text received before them.
+
+use crate::core::LanguageModelStreamChunkType;
+use futures::{Stream, StreamExt};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// How [`smooth_stream`] re-chunks text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmoothPolicy {
+    /// Emits text one word at a time, with the whitespace following it.
+    Word,
+    /// Emits text one line at a time, with its line break.
+    Line,
+    /// Emits text one word at a time, waiting a fixed delay before each word.
+    Delay(Duration),
+}
+
+/// Re-chunks the text deltas of a stream according to a policy.
+///
+/// Text is buffered until a whole word or line is received. Buffered text is
+/// sent before any other chunk, and once the stream ends.
+///
+/// # Examples
+///
+/// ```rust,no_run
+///# #[cfg(feature = "openai")]
+///# {
+/// use aisdk::core::LanguageModelRequest;
+/// use aisdk::core::language_model::smooth_stream::{SmoothPolicy, smooth_stream};
+/// use aisdk::providers::OpenAI;
+/// use futures::StreamExt;
+///
+/// # async fn run() -> aisdk::Result<()> {
+/// let response = LanguageModelRequest::builder()
+///     .model(OpenAI::gpt_5())
+///     .prompt("Tell me a story.")
+///     .build()
+///     .stream_text()
+///     .await?;
+/// let mut stream = Box::pin(smooth_stream(response.stream, SmoothPolicy::Word));
+/// while let Some(chunk) = stream.next().await {
+///     // Render the chunk
+/// }
+/// # Ok(())
+/// # }
+///# }
+/// ```
+pub fn smooth_stream<S>(
+    stream: S,
+    policy: SmoothPolicy,
+) -> impl Stream<Item = LanguageModelStreamChunkType> + Send
+where
+    S: Stream<Item = LanguageModelStreamChunkType> + Send + Unpin,
+{
+    let state = SmoothState {
+        stream,
+        policy,
+        buffer: String::new(),
+        ready: VecDeque::new(),
+        done: false,
+    };
+    futures::stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(chunk) = state.ready.pop_front() {
+                if let (SmoothPolicy::Delay(delay), LanguageModelStreamChunkType::Text(_)) =
+                    (state.policy, &chunk)
+                {
+                    crate::core::runtime::sleep(delay).await;
+                }
+                return Some((chunk, state));
+            }
+            if state.done {
+                return None;
+            }
+
+            match state.stream.next().await {
+                Some(LanguageModelStreamChunkType::Text(delta)) => {
+                    state.buffer.push_str(&delta);
+                    state.split();
+                }
+                Some(chunk) => {
+                    state.flush();
+                    state.ready.push_back(chunk);
+                }
+                None => {
+                    state.flush();
+                    state.done = true;
+                }
+            }
+        }
+    })
+}
+
+struct SmoothState<S> {
+    stream: S,
+    policy: SmoothPolicy,
+    /// Text received but not complete yet.
+    buffer: String,
+    /// Chunks ready to be sent.
+    ready: VecDeque<LanguageModelStreamChunkType>,
+    done: bool,
+}
+
+impl<S> SmoothState<S> {
+    /// Moves the complete words or lines of the buffer to the ready chunks.
+    fn split(&mut self) {
+        while let Some(end) = match self.policy {
+            SmoothPolicy::Line => self.buffer.find('\n').map(|i| i + 1),
+            SmoothPolicy::Word | SmoothPolicy::Delay(_) => word_end(&self.buffer),
+        } {
+            let rest = self.buffer.split_off(end);
+            let piece = std::mem::replace(&mut self.buffer, rest);
+            self.ready
+                .push_back(LanguageModelStreamChunkType::Text(piece));
+        }
+    }
+
+    /// Moves all buffered text to the ready chunks.
+    fn flush(&mut self) {
+        self.split();
+        if !self.buffer.is_empty() {
+            let text = std::mem::take(&mut self.buffer);
+            self.ready
+                .push_back(LanguageModelStreamChunkType::Text(text));
+        }
+    }
+}
+
+/// Returns the end of the first word of the text and the whitespace following
+/// it, if the whitespace has been received.
+fn word_end(text: &str) -> Option<usize> {
+    let start = text.find(|c: char| !c.is_whitespace())?;
+    let word_len = text[start..].find(char::is_whitespace)?;
+    let spaces = start + word_len;
+    let end = text[spaces..]
+        .find(|c: char| !c.is_whitespace())
+        .map_or(text.len(), |i| spaces + i);
+    Some(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::language_model::Usage;
+
+    fn text(text: &str) -> LanguageModelStreamChunkType {
+        LanguageModelStreamChunkType::Text(text.to_string())
+    }
+
+    async fn collect(
+        chunks: Vec<LanguageModelStreamChunkType>,
+        policy: SmoothPolicy,
+    ) -> Vec<String> {
+        smooth_stream(futures::stream::iter(chunks), policy)
+            .map(|chunk| match chunk {
+                LanguageModelStreamChunkType::Text(text) => text,
+                other => format!("<{other:?}>"),
+            })
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_smooth_stream_by_word() {
+        let chunks = vec![
+            text("Hel"),
+            text("lo wor"),
+            text("ld!\nBye"),
+            LanguageModelStreamChunkType::Usage(Usage::default()),
+            text(" now"),
+        ];
+        let texts = collect(chunks, SmoothPolicy::Word).await;
+        assert_eq!(texts[..3], ["Hello ", "world!\n", "Bye"]);
+        assert!(texts[3].starts_with("<Usage"));
+        assert_eq!(texts[4..], [" now"]);
+    }
+
+    #[tokio::test]
+    async fn test_smooth_stream_by_line() {
+        let chunks = vec![text("one\ntw"), text("o\n\nthree")];
+        let texts = collect(chunks, SmoothPolicy::Line).await;
+        assert_eq!(texts, ["one\n", "two\n", "\n", "three"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_smooth_stream_with_delay() {
+        let start = tokio::time::Instant::now();
+        let chunks = vec![text("a b c")];
+        let texts = collect(chunks, SmoothPolicy::Delay(Duration::from_millis(10))).await;
+        assert_eq!(texts, ["a ", "b ", "c"]);
+        assert_eq!(start.elapsed(), Duration::from_millis(30));
+    }
+}
@@ -0,0 +1,56 @@
+//! Sources and citations of generated text.
+//!
+//! Providers that search the web or read documents return the sources they used,
+//! and the passages of them that the generated text cites. They are collected in
+//! [`sources`](crate::core::language_model::LanguageModelOptions::sources) and
+//! [`citations`](crate::core::language_model::LanguageModelOptions::citations) on
+//! the response, and streamed as [`Source`](crate::core::LanguageModelStreamChunkType::Source)
+//! and [`Citation`](crate::core::LanguageModelStreamChunkType::Citation) chunks.
+
+use serde::{Deserialize, Serialize};
+
+/// A source the model used, such as a web page or a document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "source_type", rename_all = "snake_case")]
+pub enum Source {
+    /// A web page.
+    Url {
+        /// The ID of the source.
+        id: String,
+        /// The URL of the page.
+        url: String,
+        /// The title of the page.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+    },
+    /// A document sent with the prompt.
+    Document {
+        /// The ID of the source.
+        id: String,
+        /// The title of the document.
+        title: String,
+        /// The media type of the document, such as `application/pdf`.
+        media_type: String,
+        /// The file name of the document.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        filename: Option<String>,
+    },
+}
+
+impl Source {
+    /// Returns the ID of the source.
+    pub fn id(&self) -> &str {
+        match self {
+            Source::Url { id, .. } | Source::Document { id, .. } => id,
+        }
+    }
+}
+
+/// A passage of a source cited by the generated text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Citation {
+    /// The cited passage.
+    pub cited_text: String,
+    /// The source of the passage.
+    pub source: Source,
+}
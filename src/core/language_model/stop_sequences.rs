@@ -0,0 +1,144 @@
+//! Client-side enforcement of stop sequences.
+//!
+//! Providers apply stop sequences with different limits, and some APIs (such as
+//! the OpenAI Responses API) do not support them at all. Generated text is
+//! therefore also cut at the first stop sequence on the client. When streaming,
+//! text that could be the start of a stop sequence is withheld until the
+//! following text shows whether it is one, so a partial stop sequence is never
+//! sent.
+
+/// Returns the text before the first stop sequence, and whether one was found.
+pub(crate) fn truncate_at_stop(text: &str, stop_sequences: &[String]) -> (String, bool) {
+    match first_stop(text, stop_sequences) {
+        Some(index) => (text[..index].to_string(), true),
+        None => (text.to_string(), false),
+    }
+}
+
+/// Returns the index of the first stop sequence in the text.
+fn first_stop(text: &str, stop_sequences: &[String]) -> Option<usize> {
+    stop_sequences
+        .iter()
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| text.find(s.as_str()))
+        .min()
+}
+
+/// Filters streamed text deltas, withholding text that may start a stop sequence.
+#[derive(Debug, Default)]
+pub(crate) struct StopSequenceFilter {
+    stop_sequences: Vec<String>,
+    /// Text received but not sent yet, as it may start a stop sequence.
+    pending: String,
+    /// Whether a stop sequence was found, after which no text is sent.
+    stopped: bool,
+}
+
+impl StopSequenceFilter {
+    /// Creates a filter for the given stop sequences.
+    pub(crate) fn new(stop_sequences: Vec<String>) -> Self {
+        Self {
+            stop_sequences: stop_sequences
+                .into_iter()
+                .filter(|s| !s.is_empty())
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns `true` if the filter has stop sequences.
+    pub(crate) fn is_active(&self) -> bool {
+        !self.stop_sequences.is_empty()
+    }
+
+    /// Adds a text delta, and returns the text that can be sent.
+    pub(crate) fn push(&mut self, delta: &str) -> Option<String> {
+        if self.stopped {
+            return None;
+        }
+        self.pending.push_str(delta);
+
+        if let Some(index) = first_stop(&self.pending, &self.stop_sequences) {
+            self.stopped = true;
+            let text = self.pending[..index].to_string();
+            self.pending.clear();
+            return (!text.is_empty()).then_some(text);
+        }
+
+        // Keep the longest suffix that may start a stop sequence
+        let held = self.partial_match_len();
+        let text: String = self.pending.drain(..self.pending.len() - held).collect();
+        (!text.is_empty()).then_some(text)
+    }
+
+    /// Returns the withheld text once the stream has ended without a stop sequence.
+    pub(crate) fn finish(&mut self) -> Option<String> {
+        let text = std::mem::take(&mut self.pending);
+        (!self.stopped && !text.is_empty()).then_some(text)
+    }
+
+    /// Returns the length of the longest suffix of the pending text that is a
+    /// prefix of a stop sequence.
+    fn partial_match_len(&self) -> usize {
+        self.stop_sequences
+            .iter()
+            .flat_map(|stop| {
+                stop.char_indices()
+                    .skip(1)
+                    .map(|(i, _)| &stop[..i])
+                    .filter(|prefix| self.pending.ends_with(prefix))
+                    .map(str::len)
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stops(sequences: &[&str]) -> Vec<String> {
+        sequences.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_truncate_at_first_stop() {
+        assert_eq!(
+            truncate_at_stop("a END b STOP", &stops(&["STOP", "END"])),
+            ("a ".to_string(), true)
+        );
+        assert_eq!(
+            truncate_at_stop("no stop", &stops(&["STOP", ""])),
+            ("no stop".to_string(), false)
+        );
+    }
+
+    #[test]
+    fn test_filter_withholds_partial_stop_sequences() {
+        let mut filter = StopSequenceFilter::new(stops(&["</answer>"]));
+        assert_eq!(
+            filter.push("The answer is 4</"),
+            Some("The answer is 4".to_string())
+        );
+        assert_eq!(filter.push("ans"), None);
+        assert_eq!(filter.push("wer> trailing"), None);
+        assert_eq!(filter.push("more"), None);
+        assert_eq!(filter.finish(), None);
+    }
+
+    #[test]
+    fn test_filter_releases_text_that_is_not_a_stop_sequence() {
+        let mut filter = StopSequenceFilter::new(stops(&["STOP"]));
+        assert_eq!(filter.push("ST"), None);
+        assert_eq!(filter.push("ART"), Some("START".to_string()));
+        assert_eq!(filter.push(" S"), Some(" ".to_string()));
+        assert_eq!(filter.finish(), Some("S".to_string()));
+    }
+
+    #[test]
+    fn test_filter_handles_multibyte_text() {
+        let mut filter = StopSequenceFilter::new(stops(&["éé"]));
+        assert_eq!(filter.push("caféé"), Some("caf".to_string()));
+    }
+}
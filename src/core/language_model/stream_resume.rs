@@ -0,0 +1,333 @@
+//! Recovery of streams failing in the middle of a step.
+//!
+//! When the connection of a stream drops with a transient error, such as a
+//! timeout or a reset connection, the step is requested again and the new
+//! stream continues where the failed one stopped: the text and reasoning that
+//! were already streamed are skipped. If the new response does not start with
+//! what was streamed, as it may happen when sampling is not deterministic, or if
+//! tool calls or final messages were already streamed, the original error is
+//! returned instead.
+//!
+//! Resuming is only on by default for requests that sample deterministically,
+//! with a temperature of 0 or a seed. The estimated usage of the abandoned
+//! attempts is added to the usage of the step.
+
+use crate::core::language_model::{
+    LanguageModel, LanguageModelOptions, LanguageModelStreamChunk, LanguageModelStreamChunkType,
+    ProviderStream, Usage,
+};
+use crate::core::rate_limit::estimate_request_tokens;
+use crate::core::tokens::estimate_tokens;
+use crate::error::{Error, Result};
+use futures::StreamExt;
+
+/// The number of times a step of a deterministic request is resumed when the
+/// request does not set it.
+const DEFAULT_MAX_RESUMES: u32 = 2;
+
+/// Returns the number of times a step is resumed when the request does not set
+/// it: [`DEFAULT_MAX_RESUMES`] for requests with a temperature of 0 or a seed,
+/// whose resumed responses are likely to repeat what was streamed, and 0 for
+/// the others.
+pub(crate) fn default_max_resumes(options: &LanguageModelOptions) -> u32 {
+    if options.temperature == Some(0.0) || options.seed.is_some() {
+        DEFAULT_MAX_RESUMES
+    } else {
+        0
+    }
+}
+
+/// The text of a channel, such as the text or the reasoning, streamed so far and
+/// received from the current stream.
+#[derive(Debug, Default)]
+struct Channel {
+    sent: String,
+    received: String,
+}
+
+impl Channel {
+    /// Adds a received delta, returning the part of it that was not streamed
+    /// yet, or `None` if it diverges from what was streamed.
+    fn push(&mut self, delta: &str) -> Option<String> {
+        self.received.push_str(delta);
+        if self.received.len() <= self.sent.len() {
+            return self.sent.starts_with(&self.received).then(String::new);
+        }
+        if !self.received.starts_with(&self.sent) {
+            return None;
+        }
+        let rest = self.received[self.sent.len()..].to_string();
+        self.sent.clone_from(&self.received);
+        Some(rest)
+    }
+
+    /// Returns whether the current stream is still repeating streamed text.
+    fn is_replaying(&self) -> bool {
+        self.received.len() < self.sent.len()
+    }
+}
+
+struct ResumableStream<M> {
+    model: M,
+    options: LanguageModelOptions,
+    stream: ProviderStream,
+    resumes_left: u32,
+    text: Channel,
+    reasoning: Channel,
+    /// Whether chunks that can not be skipped when resuming were streamed.
+    committed: bool,
+    /// The error that made the stream resume.
+    error: Option<Error>,
+    /// The estimated usage of the attempts that failed.
+    abandoned: Option<Usage>,
+    ended: bool,
+}
+
+impl<M: LanguageModel> ResumableStream<M> {
+    /// Removes the parts of a batch that were already streamed, failing if the
+    /// batch diverges from them.
+    fn filter(
+        &mut self,
+        batch: Vec<LanguageModelStreamChunk>,
+    ) -> Option<Vec<LanguageModelStreamChunk>> {
+        let mut chunks = Vec::with_capacity(batch.len());
+        for chunk in batch {
+            match chunk {
+                LanguageModelStreamChunk::Delta(LanguageModelStreamChunkType::Text(delta)) => {
+                    let rest = self.text.push(&delta)?;
+                    if !rest.is_empty() {
+                        chunks.push(LanguageModelStreamChunk::Delta(
+                            LanguageModelStreamChunkType::Text(rest),
+                        ));
+                    }
+                }
+                LanguageModelStreamChunk::Delta(LanguageModelStreamChunkType::Reasoning(delta)) => {
+                    let rest = self.reasoning.push(&delta)?;
+                    if !rest.is_empty() {
+                        chunks.push(LanguageModelStreamChunk::Delta(
+                            LanguageModelStreamChunkType::Reasoning(rest),
+                        ));
+                    }
+                }
+                LanguageModelStreamChunk::Delta(LanguageModelStreamChunkType::Logprobs(_))
+                    if self.text.is_replaying() => {}
+                LanguageModelStreamChunk::Delta(LanguageModelStreamChunkType::ToolCall(_)) => {
+                    self.committed = true;
+                    chunks.push(chunk);
+                }
+                LanguageModelStreamChunk::Done(mut message) => {
+                    self.committed = true;
+                    if let Some(abandoned) = self.abandoned.take() {
+                        message.usage = Some(match message.usage {
+                            Some(usage) => &usage + &abandoned,
+                            None => abandoned,
+                        });
+                    }
+                    chunks.push(LanguageModelStreamChunk::Done(message));
+                }
+                chunk => chunks.push(chunk),
+            }
+        }
+        Some(chunks)
+    }
+
+    /// Adds the estimated usage of the failed attempt to the abandoned usage.
+    fn abandon(&mut self) {
+        let output =
+            estimate_tokens(&self.text.received) + estimate_tokens(&self.reasoning.received);
+        let attempt = Usage {
+            input_tokens: Some(estimate_request_tokens(&self.options)),
+            output_tokens: Some(output),
+            ..Default::default()
+        };
+        self.abandoned = Some(match self.abandoned.take() {
+            Some(abandoned) => &abandoned + &attempt,
+            None => attempt,
+        });
+    }
+
+    /// Returns the next batch, requesting the step again after a transient failure.
+    async fn next(&mut self) -> Option<Result<Vec<LanguageModelStreamChunk>>> {
+        if self.ended {
+            return None;
+        }
+        loop {
+            match self.stream.next().await? {
+                Ok(batch) => match self.filter(batch) {
+                    Some(chunks) if chunks.is_empty() => continue,
+                    Some(chunks) => return Some(Ok(chunks)),
+                    None => {
+                        self.ended = true;
+                        let error = self.error.take().unwrap_or_else(|| {
+                            Error::Other("The resumed stream diverged".to_string())
+                        });
+                        return Some(Err(error));
+                    }
+                },
+                Err(error) if self.committed || self.resumes_left == 0 || !error.is_retryable() => {
+                    return Some(Err(error));
+                }
+                Err(error) => {
+                    self.resumes_left -= 1;
+                    self.abandon();
+                    log::warn!("Resuming a stream of {} after: {error}", self.model.name());
+                    match self.model.stream_text(self.options.clone()).await {
+                        Ok(stream) => {
+                            self.stream = stream;
+                            self.text.received.clear();
+                            self.reasoning.received.clear();
+                            self.error = Some(error);
+                        }
+                        Err(_) => return Some(Err(error)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Wraps the stream of a step so that a transient failure resumes it, at most
+/// `max_resumes` times.
+pub(crate) fn resumable<M: LanguageModel>(
+    model: M,
+    options: LanguageModelOptions,
+    stream: ProviderStream,
+    max_resumes: u32,
+) -> ProviderStream {
+    if max_resumes == 0 {
+        return stream;
+    }
+    let state = ResumableStream {
+        model,
+        options,
+        stream,
+        resumes_left: max_resumes,
+        text: Channel::default(),
+        reasoning: Channel::default(),
+        committed: false,
+        error: None,
+        abandoned: None,
+        ended: false,
+    };
+    Box::pin(futures::stream::unfold(state, |mut state| async move {
+        let batch = state.next().await?;
+        Some((batch, state))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::AssistantMessage;
+    use crate::providers::mock::{MockLanguageModel, MockResponse};
+
+    /// Streams the given texts, the first attempts dropping their connection
+    /// after two chunks.
+    fn flaky_model(texts: &[&str]) -> MockLanguageModel {
+        let model = MockLanguageModel::new();
+        for (attempt, text) in texts.iter().enumerate() {
+            let mut chunks: Vec<LanguageModelStreamChunk> = text
+                .split_inclusive(' ')
+                .map(|word| {
+                    LanguageModelStreamChunk::Delta(LanguageModelStreamChunkType::Text(
+                        word.to_string(),
+                    ))
+                })
+                .collect();
+            if attempt + 1 < texts.len() {
+                chunks.truncate(2);
+                model.push(MockResponse::InterruptedStream(
+                    chunks,
+                    Error::TransportError("connection reset".to_string()),
+                ));
+            } else {
+                chunks.push(LanguageModelStreamChunk::Done(AssistantMessage::new(
+                    text.to_string().into(),
+                    None,
+                )));
+                model.push(MockResponse::Stream(chunks));
+            }
+        }
+        model
+    }
+
+    async fn stream(texts: Vec<&'static str>) -> (Vec<String>, Option<Error>) {
+        let mut model = flaky_model(&texts);
+        let options = LanguageModelOptions::default();
+        let stream = model.stream_text(options.clone()).await.unwrap();
+        let mut stream = resumable(model, options, stream, DEFAULT_MAX_RESUMES);
+
+        let mut deltas = Vec::new();
+        while let Some(batch) = stream.next().await {
+            match batch {
+                Ok(chunks) => {
+                    for chunk in chunks {
+                        if let LanguageModelStreamChunk::Delta(
+                            LanguageModelStreamChunkType::Text(text),
+                        ) = chunk
+                        {
+                            deltas.push(text);
+                        }
+                    }
+                }
+                Err(error) => return (deltas, Some(error)),
+            }
+        }
+        (deltas, None)
+    }
+
+    #[tokio::test]
+    async fn test_resumes_without_repeating_streamed_text() {
+        let (deltas, error) = stream(vec![
+            "The quick brown fox",
+            "The quick brown fox",
+            "The quick brown fox jumps",
+        ])
+        .await;
+        assert_eq!(deltas, ["The ", "quick ", "brown ", "fox ", "jumps"]);
+        assert_eq!(error, None);
+    }
+
+    #[tokio::test]
+    async fn test_fails_when_resumed_text_diverges() {
+        let (deltas, error) = stream(vec!["The quick brown fox", "A slow fox"]).await;
+        assert_eq!(deltas, ["The ", "quick "]);
+        assert_eq!(
+            error,
+            Some(Error::TransportError("connection reset".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_adds_usage_of_abandoned_attempts() {
+        let mut model = flaky_model(&["The quick brown fox", "The quick brown fox"]);
+        let options = LanguageModelOptions {
+            system: Some("Be brief".to_string()),
+            ..Default::default()
+        };
+        let stream = model.stream_text(options.clone()).await.unwrap();
+        let batches: Vec<_> = resumable(model, options.clone(), stream, 1).collect().await;
+
+        let usage = batches
+            .into_iter()
+            .flat_map(|batch| batch.unwrap())
+            .find_map(|chunk| match chunk {
+                LanguageModelStreamChunk::Done(message) => message.usage,
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(usage.input_tokens, Some(estimate_request_tokens(&options)));
+        assert_eq!(usage.output_tokens, Some(estimate_tokens("The quick ")));
+    }
+
+    #[test]
+    fn test_resumes_deterministic_requests_by_default() {
+        let mut options = LanguageModelOptions::default();
+        assert_eq!(default_max_resumes(&options), 0);
+        options.temperature = Some(0.0);
+        assert_eq!(default_max_resumes(&options), DEFAULT_MAX_RESUMES);
+        options.temperature = Some(0.7);
+        options.seed = Some(42);
+        assert_eq!(default_max_resumes(&options), DEFAULT_MAX_RESUMES);
+    }
+}
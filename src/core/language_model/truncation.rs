@@ -0,0 +1,305 @@
+//! Automatic prompt truncation to keep requests within a model's context window.
+//!
+//! When a [`TruncationStrategy`] is set on a request, the conversation sent to the
+//! model is checked against the model's context window before every step. If the
+//! estimated size exceeds the available budget, the oldest messages are removed
+//! (and optionally summarized) so the request does not get rejected by the provider.
+//!
+//! The truncated conversation replaces the conversation of the request, so later
+//! steps start from it: a summary is generated once and then sent with the
+//! following steps, until it leaves the window itself and is summarized again
+//! with the messages following it. The removed messages are not on the response.
+
+use crate::core::Message;
+use crate::core::language_model::{
+    LanguageModel, LanguageModelOptions, LanguageModelResponseContentType,
+};
+use crate::core::messages::TaggedMessage;
+use crate::core::tokens::{estimate_message_tokens, estimate_tokens, message_text};
+
+/// The part of the message budget reserved for the summary, as a divisor.
+const SUMMARY_SHARE: usize = 4;
+
+/// System prompt used to summarize messages that no longer fit in the context window.
+const SUMMARY_SYSTEM_PROMPT: &str = "Summarize the following conversation concisely. \
+Preserve facts, decisions, tool results and open questions that later messages may rely on.";
+
+/// Strategies for fitting a conversation into the context window of a model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TruncationStrategy {
+    /// Drops the oldest non-system messages until the conversation fits.
+    #[default]
+    DropOldest,
+    /// Replaces the oldest non-system messages with a summary generated by the same model.
+    ///
+    /// Falls back to [`TruncationStrategy::DropOldest`] if the summary can not be generated.
+    Summarize,
+}
+
+/// Returns the number of tokens available for the conversation messages,
+/// or `None` if the context window of the model is unknown.
+fn message_budget<M: LanguageModel>(model: &M, options: &LanguageModelOptions) -> Option<usize> {
+    let context_window = options.context_window.or_else(|| model.context_window())?;
+    let reserved_output = options
+        .max_output_tokens
+        .map(|t| t as usize)
+        .or_else(|| model.max_output_tokens())
+        .unwrap_or_default();
+    let system = options.system.as_deref().map(estimate_tokens).unwrap_or(0);
+    let tools = options
+        .tools
+        .as_ref()
+        .map(|t| {
+            let tools = t.tools.lock().unwrap_or_else(|p| p.into_inner());
+            tools
+                .iter()
+                .map(|tool| {
+                    estimate_tokens(&tool.name)
+                        + estimate_tokens(&tool.description)
+                        + estimate_tokens(&tool.input_schema.as_value().to_string())
+                })
+                .sum()
+        })
+        .unwrap_or(0);
+
+    Some(context_window.saturating_sub(reserved_output + system + tools))
+}
+
+/// Removes the oldest non-system messages until the estimated size fits in `budget`.
+///
+/// The most recent message is always kept, and tool results whose tool call was
+/// removed are removed as well.
+///
+/// # Returns
+///
+/// The removed messages, in their original order.
+pub(crate) fn drop_oldest(messages: &mut Vec<TaggedMessage>, budget: usize) -> Vec<TaggedMessage> {
+    let mut total: usize = messages
+        .iter()
+        .map(|m| estimate_message_tokens(&m.message))
+        .sum();
+    let mut dropped = Vec::new();
+
+    while total > budget {
+        let Some(index) = messages
+            .iter()
+            .position(|m| !matches!(m.message, Message::System(_)))
+        else {
+            break;
+        };
+
+        // Always keep the latest message
+        if index + 1 >= messages.len() {
+            break;
+        }
+
+        let removed = messages.remove(index);
+        total -= estimate_message_tokens(&removed.message);
+        dropped.push(removed);
+
+        // Tool results can not be sent without their tool call
+        while index + 1 < messages.len() && matches!(messages[index].message, Message::Tool(_)) {
+            let removed = messages.remove(index);
+            total -= estimate_message_tokens(&removed.message);
+            dropped.push(removed);
+        }
+    }
+
+    dropped
+}
+
+/// Generates a summary of the given messages using the model, of at most
+/// `max_tokens` tokens if set.
+pub(crate) async fn summarize<M: LanguageModel>(
+    model: &mut M,
+    options: &LanguageModelOptions,
+    messages: &[TaggedMessage],
+    max_tokens: Option<u32>,
+) -> Option<String> {
+    let transcript = messages
+        .iter()
+        .map(|m| format!("{}: {}", role_label(&m.message), message_text(&m.message)))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let summary_options = LanguageModelOptions {
+        system: Some(SUMMARY_SYSTEM_PROMPT.to_string()),
+        messages: vec![TaggedMessage::initial_step_msg(Message::User(
+            transcript.into(),
+        ))],
+        headers: options.headers.clone(),
+        query: options.query.clone(),
+        max_output_tokens: max_tokens,
+        ..Default::default()
+    };
+
+    match model.generate_text(summary_options).await {
+        Ok(response) => response.contents.into_iter().find_map(|c| match c {
+            LanguageModelResponseContentType::Text(t) => Some(t),
+            _ => None,
+        }),
+        Err(e) => {
+            log::warn!("Failed to summarize truncated messages: {e}");
+            None
+        }
+    }
+}
+
+/// Returns the message standing in for the summarized part of a conversation.
+pub(crate) fn summary_message(summary: &str) -> Message {
+    Message::User(format!("Summary of the earlier conversation:\n{summary}").into())
+}
+
+fn role_label(message: &Message) -> &'static str {
+    match message {
+        Message::System(_) => "system",
+        Message::User(_) => "user",
+        Message::Assistant(_) => "assistant",
+        Message::Tool(_) => "tool",
+        Message::Developer(_) => "developer",
+    }
+}
+
+/// Applies the truncation strategy of `options` to its messages.
+///
+/// Leaves the options unchanged if no strategy is set, the context window of
+/// the model is unknown, or the conversation already fits.
+pub(crate) async fn truncate_to_fit<M: LanguageModel>(
+    model: &mut M,
+    options: &mut LanguageModelOptions,
+) {
+    let Some(strategy) = options.truncation else {
+        return;
+    };
+    let Some(budget) = message_budget(model, options) else {
+        return;
+    };
+
+    if strategy == TruncationStrategy::DropOldest {
+        drop_oldest(&mut options.messages, budget);
+        return;
+    }
+
+    let total: usize = options
+        .messages
+        .iter()
+        .map(|m| estimate_message_tokens(&m.message))
+        .sum();
+    if total <= budget {
+        return;
+    }
+
+    // Leave room for the summary, whose length is capped to it
+    let reserved = budget / SUMMARY_SHARE;
+    let dropped = drop_oldest(&mut options.messages, budget - reserved);
+    if dropped.is_empty() {
+        return;
+    }
+    let max_tokens = u32::try_from(reserved).unwrap_or(u32::MAX);
+    if let Some(summary) = summarize(model, options, &dropped, Some(max_tokens)).await {
+        let index = options
+            .messages
+            .iter()
+            .position(|m| !matches!(m.message, Message::System(_)))
+            .unwrap_or(options.messages.len());
+        options.messages.insert(
+            index,
+            TaggedMessage::new(dropped[0].step_id, summary_message(&summary)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::AssistantMessage;
+    use crate::core::tools::{ToolCallInfo, ToolResultInfo};
+    use crate::providers::MockLanguageModel;
+
+    fn text_of(messages: &[TaggedMessage]) -> Vec<String> {
+        messages.iter().map(|m| message_text(&m.message)).collect()
+    }
+
+    #[test]
+    fn test_drop_oldest_noop_when_within_budget() {
+        let mut messages = vec![
+            TaggedMessage::initial_step_msg(Message::User("first".into())),
+            TaggedMessage::initial_step_msg(Message::User("second".into())),
+        ];
+        let dropped = drop_oldest(&mut messages, 1_000);
+        assert!(dropped.is_empty());
+        assert_eq!(messages.len(), 2);
+    }
+
+    #[test]
+    fn test_drop_oldest_keeps_system_and_latest_message() {
+        let mut messages = vec![
+            TaggedMessage::initial_step_msg(Message::System("system".into())),
+            TaggedMessage::initial_step_msg(Message::User("a".repeat(400).as_str().into())),
+            TaggedMessage::initial_step_msg(Message::User("b".repeat(400).as_str().into())),
+            TaggedMessage::initial_step_msg(Message::User("latest".into())),
+        ];
+        let dropped = drop_oldest(&mut messages, 0);
+        assert_eq!(dropped.len(), 2);
+        assert_eq!(text_of(&messages), vec!["system", "latest"]);
+    }
+
+    #[test]
+    fn test_drop_oldest_removes_orphaned_tool_results() {
+        let mut messages = vec![
+            TaggedMessage::new(
+                1,
+                Message::Assistant(AssistantMessage::new(
+                    LanguageModelResponseContentType::ToolCall(ToolCallInfo::new("search")),
+                    None,
+                )),
+            ),
+            TaggedMessage::new(1, Message::Tool(ToolResultInfo::new("search"))),
+            TaggedMessage::new(2, Message::User("c".repeat(40).as_str().into())),
+            TaggedMessage::new(2, Message::User("latest".into())),
+        ];
+        let budget = estimate_message_tokens(&messages[2].message)
+            + estimate_message_tokens(&messages[3].message);
+        let dropped = drop_oldest(&mut messages, budget);
+        assert_eq!(dropped.len(), 2);
+        assert!(matches!(dropped[1].message, Message::Tool(_)));
+        assert_eq!(messages.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_summarize_keeps_the_summary_in_the_options() {
+        let mut model = MockLanguageModel::new()
+            .with_context_window(100)
+            .with_text("the user asked about a, b and c");
+        let mut options = LanguageModelOptions {
+            messages: ["a", "b", "c"]
+                .iter()
+                .map(|c| {
+                    TaggedMessage::initial_step_msg(Message::User(c.repeat(400).as_str().into()))
+                })
+                .chain([TaggedMessage::initial_step_msg(Message::User(
+                    "latest".into(),
+                ))])
+                .collect(),
+            truncation: Some(TruncationStrategy::Summarize),
+            ..Default::default()
+        };
+
+        truncate_to_fit(&mut model, &mut options).await;
+        assert_eq!(
+            text_of(&options.messages),
+            vec![
+                message_text(&summary_message("the user asked about a, b and c")),
+                "latest".to_string(),
+            ]
+        );
+        let calls = model.calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].max_output_tokens, Some(25));
+
+        // The summary is sent again with the next step instead of being regenerated
+        truncate_to_fit(&mut model, &mut options).await;
+        assert_eq!(options.messages.len(), 2);
+        assert_eq!(model.calls().len(), 1);
+    }
+}
@@ -0,0 +1,479 @@
+//! Conversation memory, keeping the messages of a conversation across requests.
+//!
+//! A [`Memory`] stores the conversation of a request or an [`Agent`](crate::core::agent::Agent)
+//! between turns. Before each request the remembered messages are loaded in front of
+//! the new input, and once the request finishes the messages of the turn are appended
+//! and the memory is truncated to its limits.
+//!
+//! The built-in memories are:
+//!
+//! - [`InMemory`], keeping the whole conversation in the process.
+//! - [`SlidingWindowMemory`], keeping the latest messages within a token budget.
+//! - [`SummarizingMemory`], replacing the messages that no longer fit with a summary
+//!   generated by a model.
+//! - [`PersistentMemory`], storing the conversation of a session in a [`MemoryStore`],
+//!   such as a database.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::LanguageModelRequest;
+//! use aisdk::core::memory::SlidingWindowMemory;
+//! use aisdk::providers::OpenAI;
+//! use std::sync::Arc;
+//!
+//! # async fn run() -> aisdk::Result<()> {
+//! let memory = Arc::new(SlidingWindowMemory::new(4_000));
+//!
+//! let response = LanguageModelRequest::builder()
+//!     .model(OpenAI::gpt_5())
+//!     .prompt("What did I ask before?")
+//!     .memory(memory.clone())
+//!     .build()
+//!     .generate_text()
+//!     .await?;
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+use crate::core::language_model::truncation::{drop_oldest, summarize, summary_message};
+use crate::core::language_model::{LanguageModel, LanguageModelOptions, StopReason};
+use crate::core::messages::TaggedMessage;
+use crate::core::tokens::estimate_tokens;
+use crate::core::{Message, Messages};
+use crate::error::Result;
+use async_trait::async_trait;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+/// A store of the conversation, loaded before each request and updated after it.
+///
+/// Memories are shared between requests, so they are used through `&self`.
+#[async_trait]
+pub trait Memory: Send + Sync + Debug {
+    /// Appends the messages of a finished turn to the conversation.
+    async fn append(&self, messages: Messages) -> Result<()>;
+
+    /// Returns the conversation to send with the next request.
+    async fn load(&self) -> Result<Messages>;
+
+    /// Shrinks the conversation to the limits of the memory.
+    ///
+    /// Called after each turn is appended. Does nothing by default.
+    async fn truncate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T: Memory + ?Sized> Memory for Arc<T> {
+    async fn append(&self, messages: Messages) -> Result<()> {
+        (**self).append(messages).await
+    }
+
+    async fn load(&self) -> Result<Messages> {
+        (**self).load().await
+    }
+
+    async fn truncate(&self) -> Result<()> {
+        (**self).truncate().await
+    }
+}
+
+/// A memory keeping the whole conversation in the process.
+#[derive(Debug, Default)]
+pub struct InMemory {
+    messages: Mutex<Messages>,
+}
+
+impl InMemory {
+    /// Creates an empty memory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl Memory for InMemory {
+    async fn append(&self, messages: Messages) -> Result<()> {
+        lock(&self.messages).extend(messages);
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Messages> {
+        Ok(lock(&self.messages).clone())
+    }
+}
+
+/// A memory keeping the latest messages that fit in a number of tokens.
+///
+/// Tokens are estimated with [`crate::core::tokens`]. The latest message is always
+/// kept, and tool results are dropped along with their tool call.
+#[derive(Debug)]
+pub struct SlidingWindowMemory {
+    max_tokens: usize,
+    messages: Mutex<Messages>,
+}
+
+impl SlidingWindowMemory {
+    /// Creates an empty memory keeping at most `max_tokens` tokens of conversation.
+    pub fn new(max_tokens: usize) -> Self {
+        Self {
+            max_tokens,
+            messages: Mutex::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl Memory for SlidingWindowMemory {
+    async fn append(&self, messages: Messages) -> Result<()> {
+        lock(&self.messages).extend(messages);
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Messages> {
+        Ok(lock(&self.messages).clone())
+    }
+
+    async fn truncate(&self) -> Result<()> {
+        let mut messages = lock(&self.messages);
+        *messages = window(std::mem::take(&mut *messages), self.max_tokens).0;
+        Ok(())
+    }
+}
+
+/// A memory replacing the oldest messages with a summary once the conversation
+/// no longer fits in a number of tokens.
+///
+/// The summary is generated by the given model, and updated with the messages
+/// leaving the window at every truncation. If it can not be generated, these
+/// messages are dropped.
+#[derive(Debug)]
+pub struct SummarizingMemory<M: LanguageModel> {
+    model: M,
+    max_tokens: usize,
+    state: tokio::sync::Mutex<SummarizedConversation>,
+}
+
+/// The conversation of a [`SummarizingMemory`].
+#[derive(Debug, Default)]
+struct SummarizedConversation {
+    summary: Option<String>,
+    messages: Messages,
+}
+
+impl<M: LanguageModel> SummarizingMemory<M> {
+    /// Creates an empty memory keeping at most `max_tokens` tokens of conversation,
+    /// summarizing older messages with `model`.
+    pub fn new(model: M, max_tokens: usize) -> Self {
+        Self {
+            model,
+            max_tokens,
+            state: tokio::sync::Mutex::default(),
+        }
+    }
+
+    /// Returns the summary of the messages that left the window, if any.
+    pub async fn summary(&self) -> Option<String> {
+        self.state.lock().await.summary.clone()
+    }
+}
+
+#[async_trait]
+impl<M: LanguageModel> Memory for SummarizingMemory<M> {
+    async fn append(&self, messages: Messages) -> Result<()> {
+        self.state.lock().await.messages.extend(messages);
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Messages> {
+        let state = self.state.lock().await;
+        Ok(state
+            .summary
+            .as_deref()
+            .map(summary_message)
+            .into_iter()
+            .chain(state.messages.iter().cloned())
+            .collect())
+    }
+
+    async fn truncate(&self) -> Result<()> {
+        let mut state = self.state.lock().await;
+        let summary_tokens = state.summary.as_deref().map(estimate_tokens).unwrap_or(0);
+        let budget = self.max_tokens.saturating_sub(summary_tokens);
+
+        let (messages, dropped) = window(std::mem::take(&mut state.messages), budget);
+        state.messages = messages;
+        if dropped.is_empty() {
+            return Ok(());
+        }
+
+        // The previous summary is summarized again with the dropped messages
+        let mut summarized: Vec<TaggedMessage> = state
+            .summary
+            .as_deref()
+            .map(summary_message)
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        summarized.extend(dropped.into_iter().map(TaggedMessage::from));
+
+        let mut model = self.model.clone();
+        if let Some(summary) = summarize(
+            &mut model,
+            &LanguageModelOptions::default(),
+            &summarized,
+            None,
+        )
+        .await
+        {
+            state.summary = Some(summary);
+        }
+        Ok(())
+    }
+}
+
+/// A backend persisting the conversations of sessions, such as a database.
+#[async_trait]
+pub trait MemoryStore: Send + Sync + Debug {
+    /// Returns the conversation of a session, empty if it does not exist.
+    async fn load(&self, session_id: &str) -> Result<Messages>;
+
+    /// Replaces the conversation of a session.
+    async fn save(&self, session_id: &str, messages: &[Message]) -> Result<()>;
+}
+
+/// A memory keeping the conversation of a session in a [`MemoryStore`].
+///
+/// Appending loads and saves the whole conversation, so a session should only
+/// be used by one conversation at a time.
+#[derive(Debug)]
+pub struct PersistentMemory<S: MemoryStore> {
+    store: S,
+    session_id: String,
+    max_tokens: Option<usize>,
+}
+
+impl<S: MemoryStore> PersistentMemory<S> {
+    /// Creates a memory for the conversation of `session_id` in `store`.
+    pub fn new(store: S, session_id: impl Into<String>) -> Self {
+        Self {
+            store,
+            session_id: session_id.into(),
+            max_tokens: None,
+        }
+    }
+
+    /// Keeps at most `max_tokens` tokens of conversation in the store, as with
+    /// [`SlidingWindowMemory`].
+    pub fn max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+}
+
+#[async_trait]
+impl<S: MemoryStore> Memory for PersistentMemory<S> {
+    async fn append(&self, messages: Messages) -> Result<()> {
+        let mut conversation = self.store.load(&self.session_id).await?;
+        conversation.extend(messages);
+        self.store.save(&self.session_id, &conversation).await
+    }
+
+    async fn load(&self) -> Result<Messages> {
+        self.store.load(&self.session_id).await
+    }
+
+    async fn truncate(&self) -> Result<()> {
+        let Some(max_tokens) = self.max_tokens else {
+            return Ok(());
+        };
+        let (conversation, dropped) = window(self.load().await?, max_tokens);
+        if dropped.is_empty() {
+            return Ok(());
+        }
+        self.store.save(&self.session_id, &conversation).await
+    }
+}
+
+/// Locks a mutex, recovering the data if a thread panicked while holding it.
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|p| p.into_inner())
+}
+
+/// Splits a conversation into the latest messages fitting in `max_tokens`, and
+/// the dropped ones.
+fn window(messages: Messages, max_tokens: usize) -> (Messages, Messages) {
+    let mut tagged: Vec<TaggedMessage> = messages.into_iter().map(Into::into).collect();
+    let dropped = drop_oldest(&mut tagged, max_tokens);
+    (
+        tagged.into_iter().map(|m| m.message).collect(),
+        dropped.into_iter().map(|m| m.message).collect(),
+    )
+}
+
+impl LanguageModelOptions {
+    /// Inserts the conversation of the memory before the new input.
+    ///
+    /// Continued conversations already contain it, so it is only loaded once.
+    pub(crate) async fn load_memory(&mut self) -> Result<()> {
+        let Some(memory) = self.memory.clone() else {
+            return Ok(());
+        };
+        if self.memory_messages.is_some() {
+            return Ok(());
+        }
+
+        let history = memory.load().await?;
+        let index = self
+            .messages
+            .iter()
+            .position(|m| !matches!(m.message, Message::System(_)))
+            .unwrap_or(self.messages.len());
+        self.memory_messages = Some(history.len());
+        self.messages.splice(
+            index..index,
+            history.into_iter().map(TaggedMessage::initial_step_msg),
+        );
+        Ok(())
+    }
+
+    /// Appends the messages of the finished turn to the memory, and truncates it.
+    ///
+    /// Turns that failed or wait for tool approvals are not remembered.
+    pub(crate) async fn save_memory(&mut self) -> Result<()> {
+        let (Some(memory), Some(remembered)) = (self.memory.clone(), self.memory_messages) else {
+            return Ok(());
+        };
+        if matches!(
+            self.stop_reason,
+            Some(
+                StopReason::Error(_) | StopReason::Guardrail(_) | StopReason::ToolApprovalRequired
+            )
+        ) {
+            return Ok(());
+        }
+
+        let conversation: Messages = self
+            .messages()
+            .into_iter()
+            .filter(|m| !matches!(m, Message::System(_)))
+            .collect();
+        self.memory_messages = Some(conversation.len());
+        memory
+            .append(conversation.into_iter().skip(remembered).collect())
+            .await?;
+        memory.truncate().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::AssistantMessage;
+    use crate::core::tokens::estimate_message_tokens;
+    use crate::providers::MockLanguageModel;
+    use std::collections::HashMap;
+
+    fn user(text: &str) -> Message {
+        Message::User(text.into())
+    }
+
+    fn assistant(text: &str) -> Message {
+        Message::Assistant(AssistantMessage::new(text.to_string().into(), None))
+    }
+
+    fn texts(messages: &[Message]) -> Vec<String> {
+        messages
+            .iter()
+            .map(crate::core::tokens::message_text)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_sliding_window_keeps_latest_messages() {
+        let memory = SlidingWindowMemory::new(estimate_message_tokens(&user("third")) * 2);
+        memory
+            .append(vec![user("first"), assistant("second"), user("third")])
+            .await
+            .unwrap();
+        assert_eq!(memory.load().await.unwrap().len(), 3);
+
+        memory.truncate().await.unwrap();
+        assert_eq!(
+            texts(&memory.load().await.unwrap()),
+            vec!["second", "third"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_summarizing_memory_summarizes_dropped_messages() {
+        let summarizer = MockLanguageModel::new()
+            .with_text("2 lines")
+            .with_text("4 lines");
+        let memory = SummarizingMemory::new(summarizer.clone(), 10);
+        memory
+            .append(vec![
+                user(&"a".repeat(80)),
+                assistant(&"b".repeat(80)),
+                user("c"),
+            ])
+            .await
+            .unwrap();
+        memory.truncate().await.unwrap();
+        assert_eq!(memory.summary().await.as_deref(), Some("2 lines"));
+        assert_eq!(
+            texts(&memory.load().await.unwrap()),
+            vec!["Summary of the earlier conversation:\n2 lines", "c"]
+        );
+
+        // The previous summary is carried into the next one
+        memory
+            .append(vec![assistant(&"d".repeat(80)), user("e")])
+            .await
+            .unwrap();
+        memory.truncate().await.unwrap();
+        assert_eq!(memory.summary().await.as_deref(), Some("4 lines"));
+
+        let transcripts: Vec<String> = summarizer
+            .calls()
+            .iter()
+            .map(|options| crate::core::tokens::message_text(&options.messages()[0]))
+            .collect();
+        assert_eq!(transcripts[0].lines().count(), 2);
+        assert_eq!(transcripts[1].lines().count(), 4);
+        assert!(transcripts[1].contains("2 lines"));
+    }
+
+    #[derive(Debug, Default)]
+    struct MapStore(Mutex<HashMap<String, Messages>>);
+
+    #[async_trait]
+    impl MemoryStore for MapStore {
+        async fn load(&self, session_id: &str) -> Result<Messages> {
+            Ok(lock(&self.0).get(session_id).cloned().unwrap_or_default())
+        }
+
+        async fn save(&self, session_id: &str, messages: &[Message]) -> Result<()> {
+            lock(&self.0).insert(session_id.to_string(), messages.to_vec());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_persistent_memory_uses_session() {
+        let memory = PersistentMemory::new(MapStore::default(), "alice").max_tokens(10);
+        memory
+            .append(vec![user(&"a".repeat(80)), user("hi")])
+            .await
+            .unwrap();
+        memory.truncate().await.unwrap();
+
+        assert_eq!(texts(&memory.load().await.unwrap()), vec!["hi"]);
+        assert!(memory.store.load("bob").await.unwrap().is_empty());
+    }
+}
@@ -1,12 +1,15 @@
 //! Message types for the `aisdk` library.
 
 use crate::core::{
+    files::FileHandle,
     language_model::{LanguageModelResponseContentType, Usage},
     tools::{ToolCallInfo, ToolResultInfo},
 };
+use serde::{Deserialize, Serialize};
 
 /// The role of a participant in a conversation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Role {
     /// System-level instructions or context.
     System,
@@ -17,7 +20,8 @@ pub enum Role {
 }
 
 /// A message in a conversation with a language model.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "role", content = "content", rename_all = "snake_case")]
 pub enum Message {
     /// A system message providing context or instructions.
     System(SystemMessage),
@@ -27,7 +31,12 @@ pub enum Message {
     Assistant(AssistantMessage),
     /// A tool result message from executing a tool call.
     Tool(ToolResultInfo),
-    /// A developer-specific message for advanced use cases.
+    /// Instructions from the developer of the application, taking precedence
+    /// over user messages.
+    ///
+    /// Sent with the developer role to OpenAI, and added to the system prompt
+    /// of providers without it, such as Anthropic, Google and OpenAI compatible
+    /// providers.
     Developer(String),
 }
 
@@ -77,7 +86,8 @@ impl Message {
 }
 
 /// A system message that provides context or instructions to the model.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct SystemMessage {
     /// The text content of the system message.
     pub content: String,
@@ -105,10 +115,16 @@ impl From<&str> for SystemMessage {
 }
 
 /// A user message containing input from the human participant.
-#[derive(Debug, Clone)]
+///
+/// Serialized as its text content, or as an object when files are attached.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "UserMessageRepr", into = "UserMessageRepr")]
 pub struct UserMessage {
     /// The text content of the user message.
     pub content: String,
+
+    /// Files stored by the provider, such as PDFs or images, sent along with the text.
+    pub files: Vec<FileHandle>,
 }
 
 impl UserMessage {
@@ -116,6 +132,49 @@ impl UserMessage {
     pub fn new(content: impl Into<String>) -> Self {
         Self {
             content: content.into(),
+            files: Vec::new(),
+        }
+    }
+
+    /// Attaches a file stored by the provider to the message.
+    ///
+    /// Providers without file references ignore attached files.
+    pub fn with_file(mut self, file: FileHandle) -> Self {
+        self.files.push(file);
+        self
+    }
+}
+
+/// The serialized form of a [`UserMessage`], which stays a plain string without files.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum UserMessageRepr {
+    Text(String),
+    WithFiles {
+        content: String,
+        #[serde(default)]
+        files: Vec<FileHandle>,
+    },
+}
+
+impl From<UserMessageRepr> for UserMessage {
+    fn from(repr: UserMessageRepr) -> Self {
+        match repr {
+            UserMessageRepr::Text(content) => Self::new(content),
+            UserMessageRepr::WithFiles { content, files } => Self { content, files },
+        }
+    }
+}
+
+impl From<UserMessage> for UserMessageRepr {
+    fn from(message: UserMessage) -> Self {
+        if message.files.is_empty() {
+            Self::Text(message.content)
+        } else {
+            Self::WithFiles {
+                content: message.content,
+                files: message.files,
+            }
         }
     }
 }
@@ -133,7 +192,7 @@ impl From<&str> for UserMessage {
 }
 
 /// A message generated by the language model assistant.
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct AssistantMessage {
     /// The content of the assistant's response.
     pub content: LanguageModelResponseContentType,
@@ -278,11 +337,92 @@ impl MessageBuilder<Conversation> {
             state: std::marker::PhantomData,
         }
     }
+
+    /// Adds an assistant call to a tool, such as one of a stored conversation.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the tool.
+    /// * `id` - The id of the call, matching the id of its result.
+    /// * `args` - The input of the tool.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the message added.
+    ///
+    /// # Example
+    /// ```
+    /// use aisdk::core::Message;
+    /// use serde_json::json;
+    ///
+    /// let msgs = Message::builder()
+    ///     .user("What's the weather in Paris?")
+    ///     .tool_call("get_weather", "call_1", json!({ "city": "Paris" }))
+    ///     .tool_result("call_1", json!({ "celsius": 21 }))
+    ///     .assistant("It is 21°C in Paris.")
+    ///     .build();
+    ///
+    /// let Message::Tool(result) = &msgs[2] else { unreachable!() };
+    /// assert_eq!(result.tool.name, "get_weather");
+    /// ```
+    pub fn tool_call(
+        mut self,
+        name: impl Into<String>,
+        id: impl Into<String>,
+        args: serde_json::Value,
+    ) -> MessageBuilder<Conversation> {
+        let mut call = ToolCallInfo::new(name);
+        call.id(id);
+        call.input(args);
+        self.messages.push(Message::Assistant(AssistantMessage::new(
+            LanguageModelResponseContentType::ToolCall(call),
+            None,
+        )));
+        self
+    }
+
+    /// Adds the result of a tool call.
+    ///
+    /// The name of the tool, needed by some providers, is taken from the call
+    /// with the same id added before.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - The id of the call.
+    /// * `output` - The output of the tool.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the message added.
+    pub fn tool_result(
+        mut self,
+        id: impl Into<String>,
+        output: serde_json::Value,
+    ) -> MessageBuilder<Conversation> {
+        let id = id.into();
+        let name = self
+            .messages
+            .iter()
+            .rev()
+            .find_map(|message| match message {
+                Message::Assistant(AssistantMessage {
+                    content: LanguageModelResponseContentType::ToolCall(call),
+                    ..
+                }) if call.tool.id == id => Some(call.tool.name.clone()),
+                _ => None,
+            })
+            .unwrap_or_default();
+        let mut result = ToolResultInfo::new(name);
+        result.id(id);
+        result.output(output);
+        self.messages.push(Message::Tool(result));
+        self
+    }
 }
 
 /// A message tagged with its step id in a list of messages
 /// used for tracking steps in a conversation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct TaggedMessage {
     pub step_id: usize,
     pub message: Message,
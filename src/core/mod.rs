@@ -7,12 +7,33 @@
 //! Key types like `GenerateTextCallOptions` and `GenerateTextResponse` are also
 //! re-exported for convenient access.
 
+#[cfg(feature = "language-model-request")]
+pub mod agent;
+pub mod batch;
 pub mod capabilities;
+pub mod catalog;
 pub mod client;
+pub mod credentials;
 pub mod embedding_model;
+#[cfg(feature = "language-model-request")]
+pub mod eval;
+pub mod events;
+pub mod files;
+pub mod ids;
 pub mod language_model;
+pub mod memory;
 pub mod messages;
+pub mod moderation;
+pub mod pricing;
 pub mod provider;
+pub mod rag;
+pub mod rate_limit;
+pub mod registry;
+pub(crate) mod runtime;
+#[allow(dead_code)] // Only used by the providers enabled with features
+pub(crate) mod schema;
+pub mod testing;
+pub mod tokens;
 pub mod tools;
 pub mod utils;
 
@@ -21,8 +42,8 @@ pub use capabilities::DynamicModel;
 pub use language_model::{LanguageModel, LanguageModelStreamChunkType};
 #[cfg(feature = "language-model-request")]
 pub use language_model::{
-    generate_text::GenerateTextResponse, request::LanguageModelRequest,
-    stream_text::StreamTextResponse,
+    generate_object::GenerateObjectResponse, generate_text::GenerateTextResponse,
+    request::LanguageModelRequest, stream_text::StreamTextResponse,
 };
 
 pub use embedding_model::EmbeddingModel;
@@ -30,5 +51,8 @@ pub use embedding_model::EmbeddingModel;
 pub use embedding_model::EmbeddingModelRequest;
 
 pub use messages::{AssistantMessage, Message, Messages, Role, SystemMessage, UserMessage};
+pub use pricing::ModelPricing;
 pub use provider::Provider;
-pub use tools::{Tool, ToolCallInfo, ToolResultInfo};
+pub use tools::{
+    ApprovalPolicy, Tool, ToolApproval, ToolApprovalRequest, ToolCallInfo, ToolResultInfo,
+};
@@ -0,0 +1,379 @@
+//! Moderation of text and images.
+//!
+//! A [`ModerationModel`] classifies content against safety categories, such as
+//! hate or violence, and reports whether it should be flagged. It is implemented
+//! by providers with a moderation endpoint, such as OpenAI, and by [`LlamaGuard`],
+//! which runs a Llama Guard model through any chat provider.
+//!
+//! To block flagged prompts before they reach a language model, wrap the model
+//! with the [`ModerationGuard`](crate::core::language_model::middleware::moderation::ModerationGuard)
+//! middleware.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::moderation::{ModerationCategory, ModerationInput, ModerationModel};
+//! use aisdk::providers::OpenAI;
+//!
+//! # async fn run() -> aisdk::Result<()> {
+//! let moderation = OpenAI::model_name("omni-moderation-latest");
+//! let result = moderation
+//!     .moderate(vec![ModerationInput::text("I want to hurt someone")])
+//!     .await?;
+//!
+//! if result.flagged {
+//!     println!("Flagged for {:?}", result.flagged_categories());
+//! }
+//! println!("Violence: {}", result.score(&ModerationCategory::Violence));
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+use crate::core::language_model::{
+    LanguageModel, LanguageModelOptions, LanguageModelResponseContentType,
+};
+use crate::core::messages::TaggedMessage;
+use crate::core::{Message, UserMessage};
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// A piece of content to classify.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationInput {
+    /// A text.
+    Text(String),
+    /// An image, as a URL or a base64 data URL.
+    ImageUrl(String),
+}
+
+impl ModerationInput {
+    /// Creates a text input.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text(text.into())
+    }
+
+    /// Creates an image input from a URL or a base64 data URL.
+    pub fn image_url(url: impl Into<String>) -> Self {
+        Self::ImageUrl(url.into())
+    }
+}
+
+impl From<&str> for ModerationInput {
+    fn from(text: &str) -> Self {
+        Self::text(text)
+    }
+}
+
+impl From<String> for ModerationInput {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+/// A safety category of moderated content.
+///
+/// Categories without an equivalent in this list are kept as
+/// [`Other`](Self::Other), with the name given by the backend.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationCategory {
+    /// Content meant to arouse sexual excitement.
+    Sexual,
+    /// Sexual content involving minors.
+    SexualMinors,
+    /// Content that harasses a target.
+    Harassment,
+    /// Harassment that includes violence or serious harm.
+    HarassmentThreatening,
+    /// Content that expresses hate based on a protected attribute.
+    Hate,
+    /// Hateful content that includes violence or serious harm.
+    HateThreatening,
+    /// Content that gives advice or instructions on committing illicit acts.
+    Illicit,
+    /// Illicit content that also involves violence or weapons.
+    IllicitViolent,
+    /// Content that promotes or depicts self-harm.
+    SelfHarm,
+    /// Content where the speaker expresses an intent to harm themselves.
+    SelfHarmIntent,
+    /// Content that encourages or instructs on self-harm.
+    SelfHarmInstructions,
+    /// Content that depicts death, violence or physical injury.
+    Violence,
+    /// Violent content depicted in graphic detail.
+    ViolenceGraphic,
+    /// Another category, as named by the backend.
+    Other(String),
+}
+
+impl ModerationCategory {
+    /// Returns the name of the category (e.g., "self_harm_intent").
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Sexual => "sexual",
+            Self::SexualMinors => "sexual_minors",
+            Self::Harassment => "harassment",
+            Self::HarassmentThreatening => "harassment_threatening",
+            Self::Hate => "hate",
+            Self::HateThreatening => "hate_threatening",
+            Self::Illicit => "illicit",
+            Self::IllicitViolent => "illicit_violent",
+            Self::SelfHarm => "self_harm",
+            Self::SelfHarmIntent => "self_harm_intent",
+            Self::SelfHarmInstructions => "self_harm_instructions",
+            Self::Violence => "violence",
+            Self::ViolenceGraphic => "violence_graphic",
+            Self::Other(name) => name,
+        }
+    }
+
+    /// Returns the category with the given OpenAI name (e.g., "self-harm/intent").
+    pub fn from_openai_name(name: &str) -> Self {
+        match name {
+            "sexual" => Self::Sexual,
+            "sexual/minors" => Self::SexualMinors,
+            "harassment" => Self::Harassment,
+            "harassment/threatening" => Self::HarassmentThreatening,
+            "hate" => Self::Hate,
+            "hate/threatening" => Self::HateThreatening,
+            "illicit" => Self::Illicit,
+            "illicit/violent" => Self::IllicitViolent,
+            "self-harm" => Self::SelfHarm,
+            "self-harm/intent" => Self::SelfHarmIntent,
+            "self-harm/instructions" => Self::SelfHarmInstructions,
+            "violence" => Self::Violence,
+            "violence/graphic" => Self::ViolenceGraphic,
+            other => Self::Other(other.to_string()),
+        }
+    }
+
+    /// Returns the category of a Llama Guard hazard code (e.g., "S10").
+    ///
+    /// Hazards without an equivalent category are kept with a descriptive name.
+    pub fn from_llama_guard_code(code: &str) -> Self {
+        match code {
+            "S1" => Self::Violence,
+            "S2" => Self::Illicit,
+            "S3" => Self::Other("sex_crimes".to_string()),
+            "S4" => Self::SexualMinors,
+            "S5" => Self::Other("defamation".to_string()),
+            "S6" => Self::Other("specialized_advice".to_string()),
+            "S7" => Self::Other("privacy".to_string()),
+            "S8" => Self::Other("intellectual_property".to_string()),
+            "S9" => Self::IllicitViolent,
+            "S10" => Self::Hate,
+            "S11" => Self::SelfHarm,
+            "S12" => Self::Sexual,
+            "S13" => Self::Other("elections".to_string()),
+            "S14" => Self::Other("code_interpreter_abuse".to_string()),
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+/// The classification of content for a category.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModerationCategoryScore {
+    /// The category.
+    pub category: ModerationCategory,
+    /// Whether the content is flagged for the category.
+    pub flagged: bool,
+    /// The confidence that the content belongs to the category, between 0 and 1.
+    ///
+    /// Backends without scores, such as Llama Guard, report 1 for flagged
+    /// categories and 0 otherwise.
+    pub score: f32,
+}
+
+/// The result of a moderation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModerationResult {
+    /// Whether the content is flagged for any category.
+    pub flagged: bool,
+    /// The classification of the content, for each category reported by the backend.
+    pub categories: Vec<ModerationCategoryScore>,
+}
+
+impl ModerationResult {
+    /// Returns the categories the content is flagged for.
+    pub fn flagged_categories(&self) -> Vec<&ModerationCategory> {
+        self.categories
+            .iter()
+            .filter(|score| score.flagged)
+            .map(|score| &score.category)
+            .collect()
+    }
+
+    /// Returns the score of a category, or 0 if the backend did not report it.
+    pub fn score(&self, category: &ModerationCategory) -> f32 {
+        self.categories
+            .iter()
+            .find(|score| &score.category == category)
+            .map_or(0.0, |score| score.score)
+    }
+}
+
+/// A model that classifies content against safety categories.
+#[async_trait]
+pub trait ModerationModel: Send + Sync + std::fmt::Debug {
+    /// Classifies content.
+    ///
+    /// # Parameters
+    ///
+    /// * `input` - The parts of the content, such as a text and the images it refers to.
+    ///   They are classified together, as a single piece of content.
+    ///
+    /// # Returns
+    ///
+    /// The classification of the content.
+    async fn moderate(&self, input: Vec<ModerationInput>) -> Result<ModerationResult>;
+}
+
+/// A moderation backend running a Llama Guard model through a chat provider.
+///
+/// Llama Guard answers "safe", or "unsafe" followed by the codes of the violated
+/// hazards, which are mapped to [`ModerationCategory`]. Only text inputs are
+/// supported.
+///
+/// # Examples
+///
+/// ```rust,no_run
+///# #[cfg(feature = "groq")]
+///# {
+/// use aisdk::core::moderation::{LlamaGuard, ModerationModel};
+/// use aisdk::providers::Groq;
+///
+/// # async fn run() -> aisdk::Result<()> {
+/// let guard = LlamaGuard::new(Groq::model_name("meta-llama/llama-guard-4-12b"));
+/// let result = guard.moderate(vec!["How do I make a bomb?".into()]).await?;
+/// # Ok(())
+/// # }
+///# }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LlamaGuard<M: LanguageModel> {
+    model: M,
+}
+
+impl<M: LanguageModel> LlamaGuard<M> {
+    /// Creates a backend running a Llama Guard model.
+    pub fn new(model: M) -> Self {
+        Self { model }
+    }
+}
+
+#[async_trait]
+impl<M: LanguageModel> ModerationModel for LlamaGuard<M> {
+    async fn moderate(&self, input: Vec<ModerationInput>) -> Result<ModerationResult> {
+        let mut texts = Vec::with_capacity(input.len());
+        for part in input {
+            match part {
+                ModerationInput::Text(text) => texts.push(text),
+                ModerationInput::ImageUrl(_) => {
+                    return Err(Error::InvalidInput(
+                        "Llama Guard only moderates text".to_string(),
+                    ));
+                }
+            }
+        }
+
+        // The chat template of the model wraps the message in its classification prompt
+        let options = LanguageModelOptions {
+            messages: vec![TaggedMessage::initial_step_msg(Message::User(
+                UserMessage::new(texts.join("\n")),
+            ))],
+            ..Default::default()
+        };
+        let response = self.model.clone().generate_text(options).await?;
+        let verdict: String = response
+            .contents
+            .iter()
+            .filter_map(|content| match content {
+                LanguageModelResponseContentType::Text(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        parse_llama_guard_verdict(&verdict)
+    }
+}
+
+/// Parses the answer of a Llama Guard model.
+fn parse_llama_guard_verdict(verdict: &str) -> Result<ModerationResult> {
+    let mut lines = verdict.lines().map(str::trim).filter(|l| !l.is_empty());
+    match lines.next() {
+        Some("safe") => Ok(ModerationResult::default()),
+        Some("unsafe") => Ok(ModerationResult {
+            flagged: true,
+            categories: lines
+                .flat_map(|line| line.split(','))
+                .map(str::trim)
+                .filter(|code| !code.is_empty())
+                .map(|code| ModerationCategoryScore {
+                    category: ModerationCategory::from_llama_guard_code(code),
+                    flagged: true,
+                    score: 1.0,
+                })
+                .collect(),
+        }),
+        _ => Err(Error::Other(format!(
+            "Unexpected Llama Guard verdict: {verdict}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::MockLanguageModel;
+
+    /// Answers with a verdict of the guard model.
+    fn llama_guard(verdict: &str) -> MockLanguageModel {
+        MockLanguageModel::new()
+            .with_name("llama-guard")
+            .with_text(verdict)
+    }
+
+    #[tokio::test]
+    async fn test_llama_guard_safe() {
+        let guard = LlamaGuard::new(llama_guard("\n\nsafe"));
+        let result = guard.moderate(vec!["Hello".into()]).await.unwrap();
+        assert!(!result.flagged);
+        assert!(result.flagged_categories().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_llama_guard_unsafe_categories() {
+        let guard = LlamaGuard::new(llama_guard("unsafe\nS1,S10"));
+        let result = guard.moderate(vec!["...".into()]).await.unwrap();
+        assert!(result.flagged);
+        assert_eq!(
+            result.flagged_categories(),
+            [&ModerationCategory::Violence, &ModerationCategory::Hate]
+        );
+        assert_eq!(result.score(&ModerationCategory::Hate), 1.0);
+        assert_eq!(result.score(&ModerationCategory::Sexual), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_llama_guard_rejects_images() {
+        let guard = LlamaGuard::new(llama_guard("safe"));
+        let result = guard
+            .moderate(vec![ModerationInput::image_url(
+                "https://example.com/a.png",
+            )])
+            .await;
+        assert!(matches!(result, Err(Error::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_unexpected_llama_guard_verdict() {
+        assert!(parse_llama_guard_verdict("I can't help with that").is_err());
+    }
+}
@@ -0,0 +1,129 @@
+//! Pricing metadata and cost estimation for language models.
+//!
+//! Models declared through [`model_capabilities!`](crate::model_capabilities) can carry
+//! per-million-token prices, which are used to estimate the dollar cost of a request
+//! from its [`Usage`](crate::core::language_model::Usage).
+//!
+//! Prices change over time and custom or fine-tuned models have no built-in pricing,
+//! so a process-wide override registry is provided. Overrides always take precedence
+//! over the built-in prices.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aisdk::core::pricing::{ModelPricing, register_pricing, pricing_override};
+//!
+//! register_pricing("my-fine-tuned-model", ModelPricing::new(3.0, 12.0).with_cached(1.5));
+//!
+//! assert_eq!(
+//!     pricing_override("my-fine-tuned-model"),
+//!     Some(ModelPricing::new(3.0, 12.0).with_cached(1.5))
+//! );
+//! ```
+
+use crate::core::language_model::LanguageModel;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Per-million-token prices of a model, in US dollars.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct ModelPricing {
+    /// Price per million input tokens.
+    pub input: f64,
+    /// Price per million output tokens.
+    pub output: f64,
+    /// Price per million cached input tokens. Falls back to `input` when not set.
+    pub cached: Option<f64>,
+    /// Price per million input tokens written to the prompt cache. Falls back to
+    /// `input` when not set.
+    #[serde(default)]
+    pub cache_write: Option<f64>,
+}
+
+impl ModelPricing {
+    /// Creates a new pricing entry from input and output per-million-token rates.
+    pub const fn new(input: f64, output: f64) -> Self {
+        Self {
+            input,
+            output,
+            cached: None,
+            cache_write: None,
+        }
+    }
+
+    /// Sets the per-million-token rate for cached input tokens.
+    pub const fn with_cached(mut self, cached: f64) -> Self {
+        self.cached = Some(cached);
+        self
+    }
+
+    /// Sets the per-million-token rate for input tokens written to the prompt cache.
+    pub const fn with_cache_write(mut self, cache_write: f64) -> Self {
+        self.cache_write = Some(cache_write);
+        self
+    }
+}
+
+static PRICING_OVERRIDES: LazyLock<RwLock<HashMap<String, ModelPricing>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Registers custom pricing for a model, overriding any built-in pricing.
+///
+/// # Parameters
+///
+/// * `model_name` - The API model identifier, as returned by [`LanguageModel::name`].
+/// * `pricing` - The prices to use for the model.
+pub fn register_pricing(model_name: impl Into<String>, pricing: ModelPricing) {
+    PRICING_OVERRIDES.write().insert(model_name.into(), pricing);
+}
+
+/// Removes a previously registered pricing override.
+///
+/// # Returns
+///
+/// The removed pricing, if one was registered.
+pub fn unregister_pricing(model_name: &str) -> Option<ModelPricing> {
+    PRICING_OVERRIDES.write().remove(model_name)
+}
+
+/// Returns the registered pricing override for a model, if any.
+pub fn pricing_override(model_name: &str) -> Option<ModelPricing> {
+    PRICING_OVERRIDES.read().get(model_name).copied()
+}
+
+/// Resolves the pricing of a model, preferring registered overrides over built-in pricing.
+pub(crate) fn resolve_pricing<M: LanguageModel>(model: &M) -> Option<ModelPricing> {
+    pricing_override(&model.name()).or_else(|| model.pricing())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_pricing_with_cached() {
+        let pricing = ModelPricing::new(2.5, 10.0).with_cached(1.25);
+        assert_eq!(pricing.input, 2.5);
+        assert_eq!(pricing.output, 10.0);
+        assert_eq!(pricing.cached, Some(1.25));
+        assert_eq!(pricing.cache_write, None);
+        assert_eq!(pricing.with_cache_write(3.125).cache_write, Some(3.125));
+    }
+
+    #[test]
+    fn test_register_and_unregister_pricing() {
+        let name = "test-register-and-unregister-pricing";
+        assert_eq!(pricing_override(name), None);
+
+        register_pricing(name, ModelPricing::new(1.0, 2.0));
+        assert_eq!(pricing_override(name), Some(ModelPricing::new(1.0, 2.0)));
+
+        register_pricing(name, ModelPricing::new(3.0, 4.0));
+        assert_eq!(pricing_override(name), Some(ModelPricing::new(3.0, 4.0)));
+
+        assert_eq!(unregister_pricing(name), Some(ModelPricing::new(3.0, 4.0)));
+        assert_eq!(pricing_override(name), None);
+    }
+}
@@ -0,0 +1,150 @@
+//! Splitting of texts into chunks small enough to be embedded and retrieved.
+//!
+//! Chunk sizes are estimated with [`crate::core::tokens`], and the whitespace
+//! between words is normalized to single spaces.
+
+use crate::core::tokens::estimate_tokens;
+
+/// Splits a text into chunks of at most `max_tokens` tokens.
+///
+/// Consecutive chunks share up to `overlap_tokens` tokens, so a passage cut
+/// between two chunks can still be retrieved whole. Words longer than
+/// `max_tokens` become a chunk of their own.
+///
+/// # Examples
+///
+/// ```rust
+/// use aisdk::core::rag::chunking::chunk_by_tokens;
+///
+/// let chunks = chunk_by_tokens("one two six ten", 2, 1);
+/// assert_eq!(chunks, ["one two", "two six", "six ten"]);
+/// ```
+pub fn chunk_by_tokens(text: &str, max_tokens: usize, overlap_tokens: usize) -> Vec<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < words.len() {
+        // Extend the chunk while it fits, with at least one word
+        let mut end = start;
+        let mut tokens = 0;
+        while end < words.len() {
+            let word_tokens = estimate_tokens(words[end]);
+            if end > start && tokens + word_tokens > max_tokens {
+                break;
+            }
+            tokens += word_tokens;
+            end += 1;
+        }
+        chunks.push(words[start..end].join(" "));
+        if end == words.len() {
+            break;
+        }
+
+        // Start the next chunk on the overlap, always moving forward
+        let mut next = end;
+        let mut overlap = 0;
+        while next > start + 1 {
+            let word_tokens = estimate_tokens(words[next - 1]);
+            if overlap + word_tokens > overlap_tokens {
+                break;
+            }
+            overlap += word_tokens;
+            next -= 1;
+        }
+        start = next;
+    }
+
+    chunks
+}
+
+/// Splits a text into chunks of whole sentences, of at most `max_tokens` tokens.
+///
+/// Sentences longer than `max_tokens` are split with [`chunk_by_tokens`].
+///
+/// # Examples
+///
+/// ```rust
+/// use aisdk::core::rag::chunking::chunk_by_sentences;
+///
+/// let chunks = chunk_by_sentences("The sky is blue. Grass is green. Snow is white.", 8);
+/// assert_eq!(chunks, ["The sky is blue. Grass is green.", "Snow is white."]);
+/// ```
+pub fn chunk_by_sentences(text: &str, max_tokens: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut tokens = 0;
+
+    for sentence in sentences(text) {
+        let sentence_tokens = estimate_tokens(sentence);
+        if !current.is_empty() && tokens + sentence_tokens > max_tokens {
+            chunks.push(current.join(" "));
+            current.clear();
+            tokens = 0;
+        }
+        if sentence_tokens > max_tokens {
+            chunks.extend(chunk_by_tokens(sentence, max_tokens, 0));
+            continue;
+        }
+        current.push(sentence);
+        tokens += sentence_tokens;
+    }
+    if !current.is_empty() {
+        chunks.push(current.join(" "));
+    }
+
+    chunks
+}
+
+/// Splits a text into sentences, ending at `.`, `!` or `?` followed by whitespace.
+fn sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        let at_boundary = matches!(c, '.' | '!' | '?')
+            && chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+        if at_boundary {
+            sentences.push(&text[start..index + c.len_utf8()]);
+            start = index + c.len_utf8();
+        }
+    }
+    sentences.push(&text[start..]);
+
+    sentences
+        .into_iter()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_by_tokens_without_overlap() {
+        assert_eq!(
+            chunk_by_tokens("a b  c\nd e", 2, 0),
+            vec!["a b", "c d", "e"]
+        );
+        assert!(chunk_by_tokens("   ", 2, 0).is_empty());
+    }
+
+    #[test]
+    fn test_chunk_by_tokens_keeps_long_words() {
+        assert_eq!(
+            chunk_by_tokens("tiny incomprehensibilities", 2, 1),
+            vec!["tiny", "incomprehensibilities"]
+        );
+    }
+
+    #[test]
+    fn test_chunk_by_sentences_splits_long_sentences() {
+        assert_eq!(
+            chunk_by_sentences("Hi! Is it 3.5 or 4? a b c d e f", 3),
+            vec!["Hi!", "Is it 3.5", "or 4?", "a b c", "d e f"]
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! In-memory vector index, searched by cosine similarity.
+
+use crate::core::embedding_model::{EmbeddingModel, EmbeddingModelOptions};
+use crate::core::rag::store::{MetadataFilter, VectorStore};
+use crate::core::rag::{Document, ScoredDocument};
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+
+/// Returns the cosine similarity of two vectors, between -1 and 1.
+///
+/// Vectors of different lengths, or with a zero norm, have a similarity of 0.
+///
+/// # Examples
+///
+/// ```rust
+/// use aisdk::core::rag::index::cosine_similarity;
+///
+/// assert_eq!(cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]), 1.0);
+/// assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+/// ```
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+/// Embeds the texts of documents with an embedding model, in a single request.
+///
+/// # Errors
+///
+/// Returns an `Error` if the request fails, or if the model does not return
+/// one embedding per document.
+pub async fn embed_documents<E: EmbeddingModel>(
+    model: &E,
+    documents: &[Document],
+) -> Result<Vec<Vec<f32>>> {
+    let embeddings = model
+        .embed(EmbeddingModelOptions {
+            input: documents.iter().map(|d| d.text.clone()).collect(),
+            dimensions: None,
+        })
+        .await?;
+    if embeddings.len() != documents.len() {
+        return Err(Error::Other(format!(
+            "Expected {} embeddings, got {}",
+            documents.len(),
+            embeddings.len()
+        )));
+    }
+    Ok(embeddings)
+}
+
+/// A document stored in a [`VectorIndex`], with its embedding.
+#[derive(Debug, Clone)]
+struct IndexEntry {
+    document: Document,
+    embedding: Vec<f32>,
+}
+
+/// An in-memory index of documents, searched by the cosine similarity of their embeddings.
+///
+/// The index can be shared between tasks, and is the in-memory [`VectorStore`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use aisdk::core::embedding_model::EmbeddingModel;
+/// use aisdk::core::rag::{Document, chunking::chunk_by_sentences, index::VectorIndex};
+///
+/// # async fn run(embedding_model: impl EmbeddingModel, manual: String) -> aisdk::Result<()> {
+/// let index = VectorIndex::new();
+/// let chunks = chunk_by_sentences(&manual, 200);
+/// index
+///     .add_documents(&embedding_model, chunks.into_iter().map(Document::new).collect())
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct VectorIndex {
+    entries: RwLock<Vec<IndexEntry>>,
+}
+
+impl Clone for VectorIndex {
+    fn clone(&self) -> Self {
+        Self {
+            entries: RwLock::new(self.entries.read().clone()),
+        }
+    }
+}
+
+impl VectorIndex {
+    /// Creates an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a document with its embedding, replacing the document with the same ID.
+    pub fn insert(&self, document: Document, embedding: Vec<f32>) {
+        let entry = IndexEntry {
+            document,
+            embedding,
+        };
+        let mut entries = self.entries.write();
+        match entries
+            .iter_mut()
+            .find(|e| e.document.id == entry.document.id)
+        {
+            Some(existing) => *existing = entry,
+            None => entries.push(entry),
+        }
+    }
+
+    /// Embeds documents with an embedding model and adds them to the index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if the documents can not be embedded, in which case
+    /// none of them is added.
+    pub async fn add_documents<E: EmbeddingModel>(
+        &self,
+        model: &E,
+        documents: Vec<Document>,
+    ) -> Result<()> {
+        let embeddings = embed_documents(model, &documents).await?;
+        for (document, embedding) in documents.into_iter().zip(embeddings) {
+            self.insert(document, embedding);
+        }
+        Ok(())
+    }
+
+    /// Removes the document with the given ID, returning it if it was indexed.
+    pub fn remove(&self, id: &str) -> Option<Document> {
+        let mut entries = self.entries.write();
+        let index = entries.iter().position(|e| e.document.id == id)?;
+        Some(entries.remove(index).document)
+    }
+
+    /// Returns the `top_k` documents most similar to an embedding, most similar first.
+    pub fn search(&self, embedding: &[f32], top_k: usize) -> Vec<ScoredDocument> {
+        self.search_matching(embedding, top_k, &MetadataFilter::default())
+    }
+
+    /// Returns the `top_k` documents matching a filter that are most similar to
+    /// an embedding, most similar first.
+    pub fn search_matching(
+        &self,
+        embedding: &[f32],
+        top_k: usize,
+        filter: &MetadataFilter,
+    ) -> Vec<ScoredDocument> {
+        let mut results: Vec<ScoredDocument> = self
+            .entries
+            .read()
+            .iter()
+            .filter(|entry| filter.matches(&entry.document.metadata))
+            .map(|entry| ScoredDocument {
+                document: entry.document.clone(),
+                score: cosine_similarity(embedding, &entry.embedding),
+            })
+            .collect();
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        results.truncate(top_k);
+        results
+    }
+
+    /// Returns the number of indexed documents.
+    pub fn len(&self) -> usize {
+        self.entries.read().len()
+    }
+
+    /// Returns `true` if the index has no documents.
+    pub fn is_empty(&self) -> bool {
+        self.entries.read().is_empty()
+    }
+}
+
+#[async_trait]
+impl VectorStore for VectorIndex {
+    async fn upsert(&self, documents: Vec<(Document, Vec<f32>)>) -> Result<()> {
+        for (document, embedding) in documents {
+            self.insert(document, embedding);
+        }
+        Ok(())
+    }
+
+    async fn query(
+        &self,
+        embedding: &[f32],
+        top_k: usize,
+        filter: &MetadataFilter,
+    ) -> Result<Vec<ScoredDocument>> {
+        Ok(self.search_matching(embedding, top_k, filter))
+    }
+
+    async fn delete(&self, ids: &[String]) -> Result<()> {
+        self.entries
+            .write()
+            .retain(|entry| !ids.contains(&entry.document.id));
+        Ok(())
+    }
+
+    async fn delete_matching(&self, filter: &MetadataFilter) -> Result<()> {
+        self.entries
+            .write()
+            .retain(|entry| !filter.matches(&entry.document.metadata));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine_similarity_edge_cases() {
+        assert_eq!(cosine_similarity(&[2.0, 0.0], &[-3.0, 0.0]), -1.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn test_search_ranks_by_similarity() {
+        let index = VectorIndex::new();
+        index.insert(Document::new("north").id("n"), vec![0.0, 1.0]);
+        index.insert(Document::new("east").id("e"), vec![1.0, 0.0]);
+        index.insert(Document::new("north-east").id("ne"), vec![1.0, 1.0]);
+        index.insert(Document::new("due east").id("e"), vec![1.0, 0.1]);
+        assert_eq!(index.len(), 3);
+
+        let results = index.search(&[1.0, 0.0], 2);
+        let texts: Vec<&str> = results.iter().map(|r| r.document.text.as_str()).collect();
+        assert_eq!(texts, vec!["due east", "north-east"]);
+
+        assert_eq!(index.remove("n").unwrap().text, "north");
+        assert!(index.remove("n").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_vector_store_filters_by_metadata() {
+        let index = VectorIndex::new();
+        index
+            .upsert(vec![
+                (Document::new("a").with_metadata("lang", "en"), vec![1.0]),
+                (Document::new("b").with_metadata("lang", "fr"), vec![1.0]),
+                (Document::new("c").with_metadata("lang", "en"), vec![1.0]),
+            ])
+            .await
+            .unwrap();
+
+        let english = MetadataFilter::new().eq("lang", "en");
+        let results = index.query(&[1.0], 10, &english).await.unwrap();
+        assert_eq!(results.len(), 2);
+
+        index.delete_matching(&english).await.unwrap();
+        let results = index
+            .query(&[1.0], 10, &MetadataFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].document.text, "b");
+
+        index
+            .delete(&[results[0].document.id.clone()])
+            .await
+            .unwrap();
+        assert!(index.is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+//! Retrieval-augmented generation.
+//!
+//! Texts are split into chunks with [`chunking`], embedded with an
+//! [`EmbeddingModel`] and stored in a [`VectorStore`], such as the in-memory
+//! [`VectorIndex`](index::VectorIndex) or, with the `pgvector` feature, a Postgres table. Before a
+//! request, [`retrieve_and_augment`] finds the chunks most similar to the prompt
+//! and adds them to it as context.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::LanguageModelRequest;
+//! use aisdk::core::embedding_model::EmbeddingModel;
+//! use aisdk::core::rag::{Document, chunking::chunk_by_sentences, index::VectorIndex, retrieve_and_augment};
+//! use aisdk::providers::OpenAI;
+//!
+//! # async fn run(embedder: impl EmbeddingModel, handbook: String) -> aisdk::Result<()> {
+//! let index = VectorIndex::new();
+//! let chunks = chunk_by_sentences(&handbook, 200);
+//! index
+//!     .add_documents(&embedder, chunks.into_iter().map(Document::new).collect())
+//!     .await?;
+//!
+//! let prompt = retrieve_and_augment(&embedder, &index, "How many days off do I get?", 3).await?;
+//! let response = LanguageModelRequest::builder()
+//!     .model(OpenAI::gpt_5())
+//!     .prompt(prompt)
+//!     .build()
+//!     .generate_text()
+//!     .await?;
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+pub mod chunking;
+pub mod index;
+#[cfg(feature = "pgvector")]
+pub mod pgvector;
+pub mod store;
+
+use crate::core::embedding_model::{EmbeddingModel, EmbeddingModelOptions};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use store::{MetadataFilter, VectorStore};
+
+/// A text to retrieve, such as a chunk of a larger document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Document {
+    /// The ID of the document, unique within an index.
+    pub id: String,
+    /// The text of the document.
+    pub text: String,
+    /// Additional information on the document, such as its source.
+    #[serde(default)]
+    pub metadata: HashMap<String, Value>,
+}
+
+impl Document {
+    /// Creates a document with a generated ID and no metadata.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            id: crate::core::ids::generate_id(""),
+            text: text.into(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Sets the ID of the document.
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = id.into();
+        self
+    }
+
+    /// Adds a metadata entry to the document.
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// A retrieved document, with its similarity to the query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoredDocument {
+    /// The retrieved document.
+    pub document: Document,
+    /// The similarity of the document to the query, higher is more similar.
+    pub score: f32,
+}
+
+/// Retrieves the `top_k` documents most similar to a prompt, and adds them to
+/// the prompt as context.
+///
+/// # Parameters
+///
+/// * `model` - The embedding model the store was built with.
+/// * `store` - The store to retrieve documents from.
+/// * `prompt` - The prompt of the user.
+/// * `top_k` - The maximum number of documents to add.
+///
+/// # Returns
+///
+/// The prompt with the retrieved documents, or the prompt as is if none was found.
+///
+/// # Errors
+///
+/// Returns an `Error` if the prompt can not be embedded or the store can not be queried.
+pub async fn retrieve_and_augment<E: EmbeddingModel, S: VectorStore + ?Sized>(
+    model: &E,
+    store: &S,
+    prompt: &str,
+    top_k: usize,
+) -> Result<String> {
+    if top_k == 0 {
+        return Ok(prompt.to_string());
+    }
+
+    let embedding = model
+        .embed(EmbeddingModelOptions {
+            input: vec![prompt.to_string()],
+            dimensions: None,
+        })
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::Other("No embedding returned for the prompt".to_string()))?;
+
+    let documents = store
+        .query(&embedding, top_k, &MetadataFilter::default())
+        .await?;
+    Ok(augment(prompt, &documents))
+}
+
+/// Adds retrieved documents to a prompt as numbered context.
+fn augment(prompt: &str, documents: &[ScoredDocument]) -> String {
+    if documents.is_empty() {
+        return prompt.to_string();
+    }
+
+    let context = documents
+        .iter()
+        .enumerate()
+        .map(|(i, d)| format!("[{}] {}", i + 1, d.document.text))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    format!(
+        "Answer using the following context when it is relevant.\n\nContext:\n{context}\n\nQuestion:\n{prompt}"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use index::VectorIndex;
+
+    /// Embeds texts by whether they mention cats and dogs.
+    #[derive(Debug, Clone)]
+    struct PetEmbedder;
+
+    #[async_trait]
+    impl EmbeddingModel for PetEmbedder {
+        async fn embed(&self, input: EmbeddingModelOptions) -> Result<Vec<Vec<f32>>> {
+            Ok(input
+                .input
+                .iter()
+                .map(|text| {
+                    let text = text.to_lowercase();
+                    vec![
+                        text.contains("cat") as u8 as f32,
+                        text.contains("dog") as u8 as f32,
+                    ]
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_and_augment_adds_top_documents() {
+        let index = VectorIndex::new();
+        assert_eq!(
+            retrieve_and_augment(&PetEmbedder, &index, "Do cats purr?", 1)
+                .await
+                .unwrap(),
+            "Do cats purr?"
+        );
+
+        index
+            .add_documents(
+                &PetEmbedder,
+                vec![
+                    Document::new("Dogs bark."),
+                    Document::new("Cats purr when content.").with_metadata("source", "cats.md"),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let prompt = retrieve_and_augment(&PetEmbedder, &index, "Do cats purr?", 1)
+            .await
+            .unwrap();
+        assert_eq!(
+            prompt,
+            "Answer using the following context when it is relevant.\n\nContext:\n[1] Cats purr when content.\n\nQuestion:\nDo cats purr?"
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! [`VectorStore`] backed by a Postgres table with the pgvector extension.
+//!
+//! Documents are stored in a table with the columns `id` (text primary key),
+//! `text`, `metadata` (jsonb) and `embedding` (vector), and are searched by
+//! cosine distance. Metadata filters use JSON containment (`@>`).
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "pgvector")]
+//!# {
+//! use aisdk::core::rag::pgvector::PgVectorStore;
+//!
+//! # async fn run(database_url: String) -> Result<(), Box<dyn std::error::Error>> {
+//! let (client, connection) = tokio_postgres::connect(&database_url, tokio_postgres::NoTls).await?;
+//! tokio::spawn(connection);
+//!
+//! let store = PgVectorStore::new(client, "documents")?;
+//! store.create_table(1536).await?;
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+use crate::core::rag::store::{MetadataFilter, VectorStore};
+use crate::core::rag::{Document, ScoredDocument};
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use pgvector::Vector;
+use serde_json::Value;
+use std::sync::Arc;
+use tokio_postgres::Client;
+
+/// A [`VectorStore`] keeping documents in a Postgres table, searched with pgvector.
+#[derive(Debug, Clone)]
+pub struct PgVectorStore {
+    client: Arc<Client>,
+    table: String,
+}
+
+impl PgVectorStore {
+    /// Creates a store for the documents of `table`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidInput`] if the table name is not a plain SQL
+    /// identifier, optionally qualified with a schema.
+    pub fn new(client: impl Into<Arc<Client>>, table: impl Into<String>) -> Result<Self> {
+        let table = table.into();
+        if !is_identifier(&table) {
+            return Err(Error::InvalidInput(format!("Invalid table name: {table}")));
+        }
+        Ok(Self {
+            client: client.into(),
+            table,
+        })
+    }
+
+    /// Creates the pgvector extension and the table of the store, if they do not exist.
+    ///
+    /// # Parameters
+    ///
+    /// * `dimensions` - The number of dimensions of the embeddings.
+    pub async fn create_table(&self, dimensions: usize) -> Result<()> {
+        let statement = format!(
+            "CREATE EXTENSION IF NOT EXISTS vector;
+             CREATE TABLE IF NOT EXISTS {} (
+                 id TEXT PRIMARY KEY,
+                 text TEXT NOT NULL,
+                 metadata JSONB NOT NULL DEFAULT '{{}}',
+                 embedding VECTOR({dimensions}) NOT NULL
+             )",
+            self.table
+        );
+        self.client
+            .batch_execute(&statement)
+            .await
+            .map_err(postgres_error)
+    }
+}
+
+#[async_trait]
+impl VectorStore for PgVectorStore {
+    async fn upsert(&self, documents: Vec<(Document, Vec<f32>)>) -> Result<()> {
+        let statement = format!(
+            "INSERT INTO {} (id, text, metadata, embedding) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (id) DO UPDATE
+             SET text = EXCLUDED.text, metadata = EXCLUDED.metadata, embedding = EXCLUDED.embedding",
+            self.table
+        );
+        let statement = self
+            .client
+            .prepare(&statement)
+            .await
+            .map_err(postgres_error)?;
+
+        for (document, embedding) in documents {
+            let metadata = serde_json::to_value(&document.metadata)
+                .map_err(|e| Error::InvalidInput(format!("Invalid metadata: {e}")))?;
+            self.client
+                .execute(
+                    &statement,
+                    &[
+                        &document.id,
+                        &document.text,
+                        &metadata,
+                        &Vector::from(embedding),
+                    ],
+                )
+                .await
+                .map_err(postgres_error)?;
+        }
+        Ok(())
+    }
+
+    async fn query(
+        &self,
+        embedding: &[f32],
+        top_k: usize,
+        filter: &MetadataFilter,
+    ) -> Result<Vec<ScoredDocument>> {
+        let statement = format!(
+            "SELECT id, text, metadata, (1 - (embedding <=> $1))::REAL AS score FROM {}
+             WHERE metadata @> $2 ORDER BY embedding <=> $1 LIMIT $3",
+            self.table
+        );
+        let rows = self
+            .client
+            .query(
+                &statement,
+                &[
+                    &Vector::from(embedding.to_vec()),
+                    &filter.to_json(),
+                    &(top_k as i64),
+                ],
+            )
+            .await
+            .map_err(postgres_error)?;
+
+        rows.into_iter()
+            .map(|row| {
+                let metadata: Value = row.try_get("metadata").map_err(postgres_error)?;
+                Ok(ScoredDocument {
+                    document: Document {
+                        id: row.try_get("id").map_err(postgres_error)?,
+                        text: row.try_get("text").map_err(postgres_error)?,
+                        metadata: serde_json::from_value(metadata).unwrap_or_default(),
+                    },
+                    score: row.try_get("score").map_err(postgres_error)?,
+                })
+            })
+            .collect()
+    }
+
+    async fn delete(&self, ids: &[String]) -> Result<()> {
+        let statement = format!("DELETE FROM {} WHERE id = ANY($1)", self.table);
+        self.client
+            .execute(&statement, &[&ids])
+            .await
+            .map_err(postgres_error)?;
+        Ok(())
+    }
+
+    async fn delete_matching(&self, filter: &MetadataFilter) -> Result<()> {
+        let statement = format!("DELETE FROM {} WHERE metadata @> $1", self.table);
+        self.client
+            .execute(&statement, &[&filter.to_json()])
+            .await
+            .map_err(postgres_error)?;
+        Ok(())
+    }
+}
+
+/// Returns `true` if the name is a SQL identifier, optionally qualified with a schema.
+///
+/// Table names are interpolated into statements, so anything else is rejected.
+fn is_identifier(name: &str) -> bool {
+    let parts: Vec<&str> = name.split('.').collect();
+    parts.len() <= 2
+        && parts.iter().all(|part| {
+            part.chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+}
+
+fn postgres_error(error: tokio_postgres::Error) -> Error {
+    Error::Other(format!("Postgres error: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_identifier() {
+        assert!(is_identifier("documents"));
+        assert!(is_identifier("rag.documents_v2"));
+        assert!(!is_identifier(""));
+        assert!(!is_identifier("2documents"));
+        assert!(!is_identifier("a.b.c"));
+        assert!(!is_identifier("documents; DROP TABLE users"));
+    }
+}
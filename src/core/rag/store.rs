@@ -0,0 +1,105 @@
+//! Storage of embedded documents, in memory or in an external vector database.
+
+use crate::core::rag::{Document, ScoredDocument};
+use crate::error::Result;
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// A filter on the metadata of documents.
+///
+/// A document matches if its metadata has every entry of the filter. The
+/// default filter matches all documents.
+///
+/// # Examples
+///
+/// ```rust
+/// use aisdk::core::rag::store::MetadataFilter;
+///
+/// let filter = MetadataFilter::new().eq("source", "handbook.md").eq("version", 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetadataFilter {
+    equals: Map<String, Value>,
+}
+
+impl MetadataFilter {
+    /// Creates a filter matching all documents.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only matches documents whose metadata has `key` set to `value`.
+    pub fn eq(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.equals.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns `true` if the filter matches all documents.
+    pub fn is_empty(&self) -> bool {
+        self.equals.is_empty()
+    }
+
+    /// Returns `true` if the metadata has every entry of the filter.
+    pub fn matches(&self, metadata: &HashMap<String, Value>) -> bool {
+        self.equals
+            .iter()
+            .all(|(key, value)| metadata.get(key) == Some(value))
+    }
+
+    /// Returns the entries of the filter as a JSON object.
+    pub fn to_json(&self) -> Value {
+        Value::Object(self.equals.clone())
+    }
+}
+
+/// A store of documents and their embeddings, searched by similarity.
+///
+/// Implemented by the in-memory [`VectorIndex`](crate::core::rag::index::VectorIndex),
+/// and by `PgVectorStore` with the `pgvector` feature.
+#[async_trait]
+pub trait VectorStore: Send + Sync + Debug {
+    /// Adds documents with their embeddings, replacing the documents with the same IDs.
+    async fn upsert(&self, documents: Vec<(Document, Vec<f32>)>) -> Result<()>;
+
+    /// Returns the `top_k` documents matching a filter that are most similar to
+    /// an embedding, most similar first.
+    async fn query(
+        &self,
+        embedding: &[f32],
+        top_k: usize,
+        filter: &MetadataFilter,
+    ) -> Result<Vec<ScoredDocument>>;
+
+    /// Removes the documents with the given IDs.
+    async fn delete(&self, ids: &[String]) -> Result<()>;
+
+    /// Removes the documents matching a filter.
+    async fn delete_matching(&self, filter: &MetadataFilter) -> Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_metadata_filter_matches_every_entry() {
+        let metadata: HashMap<String, Value> =
+            serde_json::from_value(json!({ "source": "a.md", "page": 3 })).unwrap();
+
+        assert!(MetadataFilter::new().matches(&metadata));
+        assert!(MetadataFilter::new().eq("page", 3).matches(&metadata));
+        assert!(
+            !MetadataFilter::new()
+                .eq("page", 3)
+                .eq("source", "b.md")
+                .matches(&metadata)
+        );
+        assert_eq!(
+            MetadataFilter::new().eq("page", 3).to_json(),
+            json!({ "page": 3 })
+        );
+    }
+}
@@ -0,0 +1,334 @@
+//! Client-side rate limiting.
+//!
+//! A [`RateLimiter`] enforces requests-per-minute (RPM) and tokens-per-minute (TPM)
+//! budgets over a sliding one minute window. When a budget is exhausted, requests
+//! wait until enough capacity is available instead of failing, which keeps batch
+//! jobs below the provider's limits.
+//!
+//! A rate limiter is a [`LanguageModelMiddleware`], so it is applied with
+//! [`wrap_model`](crate::core::language_model::middleware::wrap_model). Clones
+//! share the same budget, so one limiter can be applied to several models that
+//! share a provider quota.
+//!
+//! Token usage is estimated from the request before it is sent and corrected
+//! with the usage reported by the provider once the response is received.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::LanguageModelRequest;
+//! use aisdk::core::language_model::middleware::wrap_model;
+//! use aisdk::core::rate_limit::RateLimiter;
+//! use aisdk::providers::OpenAI;
+//!
+//! # async fn run() -> aisdk::Result<()> {
+//! let limiter = RateLimiter::new()
+//!     .requests_per_minute(500)
+//!     .tokens_per_minute(200_000);
+//!
+//! let gpt_5 = wrap_model(OpenAI::gpt_5(), limiter.clone());
+//! let gpt_5_mini = wrap_model(OpenAI::gpt_5_mini(), limiter);
+//!
+//! let result = LanguageModelRequest::builder()
+//!     .model(gpt_5)
+//!     .prompt("Summarize the report.")
+//!     .build()
+//!     .generate_text()
+//!     .await?;
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+use crate::core::language_model::middleware::{LanguageModelMiddleware, Next};
+use crate::core::language_model::{
+    LanguageModelOptions, LanguageModelResponse, LanguageModelStreamChunk, ProviderStream, Usage,
+};
+use crate::core::runtime::Instant;
+use crate::core::tokens::{estimate_message_tokens, estimate_tokens};
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use futures::StreamExt;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Length of the sliding window budgets are enforced over.
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// A request recorded in the current window.
+#[derive(Debug)]
+struct Entry {
+    id: u64,
+    at: Instant,
+    tokens: usize,
+}
+
+#[derive(Debug, Default)]
+struct Window {
+    next_id: u64,
+    entries: VecDeque<Entry>,
+}
+
+impl Window {
+    fn prune(&mut self, now: Instant) {
+        while self
+            .entries
+            .front()
+            .is_some_and(|e| now.duration_since(e.at) >= WINDOW)
+        {
+            self.entries.pop_front();
+        }
+    }
+
+    fn tokens(&self) -> usize {
+        self.entries.iter().map(|e| e.tokens).sum()
+    }
+}
+
+/// Enforces requests-per-minute and tokens-per-minute budgets.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    requests_per_minute: Option<usize>,
+    tokens_per_minute: Option<usize>,
+    window: Arc<Mutex<Window>>,
+}
+
+/// A reservation made by [`RateLimiter::acquire`].
+///
+/// The reserved tokens count towards the budget until they leave the window.
+/// Call [`complete`](Self::complete) to replace the estimate with the actual usage.
+#[derive(Debug)]
+pub struct RateLimitPermit {
+    id: u64,
+    window: Arc<Mutex<Window>>,
+}
+
+impl RateLimitPermit {
+    /// Replaces the reserved tokens with the number of tokens actually used.
+    pub fn complete(self, tokens: usize) {
+        let mut window = self.window.lock();
+        if let Some(entry) = window.entries.iter_mut().find(|e| e.id == self.id) {
+            entry.tokens = tokens;
+        }
+    }
+}
+
+impl RateLimiter {
+    /// Creates a new rate limiter without any budgets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of requests per minute.
+    pub fn requests_per_minute(mut self, requests: usize) -> Self {
+        self.requests_per_minute = Some(requests);
+        self
+    }
+
+    /// Sets the maximum number of tokens per minute.
+    pub fn tokens_per_minute(mut self, tokens: usize) -> Self {
+        self.tokens_per_minute = Some(tokens);
+        self
+    }
+
+    /// Waits until a request using `tokens` tokens fits in the budgets and reserves it.
+    ///
+    /// A single request larger than the token budget is let through once the window
+    /// is empty, so it does not wait forever.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if a budget is zero, as no request would ever
+    /// fit in it.
+    pub async fn acquire(&self, tokens: usize) -> Result<RateLimitPermit> {
+        for (budget, limit) in [
+            ("requests_per_minute", self.requests_per_minute),
+            ("tokens_per_minute", self.tokens_per_minute),
+        ] {
+            if limit == Some(0) {
+                return Err(Error::InvalidInput(format!(
+                    "{budget} must be greater than zero"
+                )));
+            }
+        }
+
+        loop {
+            let wait = {
+                let now = Instant::now();
+                let mut window = self.window.lock();
+                window.prune(now);
+
+                let requests_ok = self
+                    .requests_per_minute
+                    .is_none_or(|limit| window.entries.len() < limit);
+                let tokens_ok = self.tokens_per_minute.is_none_or(|limit| {
+                    window.entries.is_empty() || window.tokens() + tokens <= limit
+                });
+
+                if requests_ok && tokens_ok {
+                    let id = window.next_id;
+                    window.next_id += 1;
+                    window.entries.push_back(Entry {
+                        id,
+                        at: now,
+                        tokens,
+                    });
+                    return Ok(RateLimitPermit {
+                        id,
+                        window: self.window.clone(),
+                    });
+                }
+
+                // Wait until the oldest request leaves the window and check again
+                window
+                    .entries
+                    .front()
+                    .map(|e| (e.at + WINDOW).saturating_duration_since(now))
+                    .unwrap_or_default()
+            };
+
+            log::debug!("Rate limit reached, waiting {wait:?}");
+            crate::core::runtime::sleep(wait).await;
+        }
+    }
+}
+
+/// Estimates the number of input tokens of a request.
+pub(crate) fn estimate_request_tokens(options: &LanguageModelOptions) -> usize {
+    let system = options.system.as_deref().map(estimate_tokens).unwrap_or(0);
+    let messages: usize = options
+        .messages
+        .iter()
+        .map(|m| estimate_message_tokens(&m.message))
+        .sum();
+    system + messages
+}
+
+fn total_tokens(usage: &Usage) -> Option<usize> {
+    match (usage.input_tokens, usage.output_tokens) {
+        (None, None) => None,
+        (input, output) => Some(input.unwrap_or(0) + output.unwrap_or(0)),
+    }
+}
+
+#[async_trait]
+impl LanguageModelMiddleware for RateLimiter {
+    async fn wrap_generate(
+        &self,
+        options: LanguageModelOptions,
+        next: Next<'_>,
+    ) -> Result<LanguageModelResponse> {
+        let permit = self.acquire(estimate_request_tokens(&options)).await?;
+        let response = next.generate_text(options).await?;
+        if let Some(tokens) = response.usage.as_ref().and_then(total_tokens) {
+            permit.complete(tokens);
+        }
+        Ok(response)
+    }
+
+    async fn wrap_stream(
+        &self,
+        options: LanguageModelOptions,
+        next: Next<'_>,
+    ) -> Result<ProviderStream> {
+        let permit = Mutex::new(Some(self.acquire(estimate_request_tokens(&options)).await?));
+        let stream = next.stream_text(options).await?;
+
+        Ok(Box::pin(stream.inspect(move |item| {
+            let Ok(chunks) = item else {
+                return;
+            };
+            let usage = chunks.iter().find_map(|chunk| match chunk {
+                LanguageModelStreamChunk::Done(message) => {
+                    message.usage.as_ref().and_then(total_tokens)
+                }
+                _ => None,
+            });
+            if let Some(tokens) = usage
+                && let Some(permit) = permit.lock().take()
+            {
+                permit.complete(tokens);
+            }
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter_requests_per_minute() {
+        let limiter = RateLimiter::new().requests_per_minute(2);
+        let start = Instant::now();
+
+        limiter.acquire(0).await.unwrap();
+        limiter.acquire(0).await.unwrap();
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        limiter.acquire(0).await.unwrap();
+        assert_eq!(start.elapsed(), WINDOW);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter_tokens_per_minute() {
+        let limiter = RateLimiter::new().tokens_per_minute(100);
+        let start = Instant::now();
+
+        limiter.acquire(60).await.unwrap();
+        tokio::time::advance(Duration::from_secs(10)).await;
+        limiter.acquire(40).await.unwrap();
+        assert_eq!(start.elapsed(), Duration::from_secs(10));
+
+        // Waits for the first request to leave the window
+        limiter.acquire(50).await.unwrap();
+        assert_eq!(start.elapsed(), WINDOW);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter_permit_complete_corrects_estimate() {
+        let limiter = RateLimiter::new().tokens_per_minute(100);
+        let start = Instant::now();
+
+        limiter.acquire(90).await.unwrap().complete(10);
+        limiter.acquire(90).await.unwrap();
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter_lets_oversized_request_through() {
+        let limiter = RateLimiter::new().tokens_per_minute(100);
+        let start = Instant::now();
+
+        limiter.acquire(500).await.unwrap();
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter_shared_between_clones() {
+        let limiter = RateLimiter::new().requests_per_minute(1);
+        let clone = limiter.clone();
+        let start = Instant::now();
+
+        limiter.acquire(0).await.unwrap();
+        clone.acquire(0).await.unwrap();
+        assert_eq!(start.elapsed(), WINDOW);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_rejects_zero_budgets() {
+        for limiter in [
+            RateLimiter::new().requests_per_minute(0),
+            RateLimiter::new().tokens_per_minute(0),
+        ] {
+            assert!(matches!(
+                limiter.acquire(0).await,
+                Err(Error::InvalidInput(_))
+            ));
+        }
+    }
+}
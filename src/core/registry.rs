@@ -0,0 +1,147 @@
+//! Resolution of models from string IDs.
+//!
+//! A [`ProviderRegistry`] holds the configured providers of an application and
+//! resolves `"{provider}:{model}"` IDs, such as `"anthropic:claude-sonnet-4-5"`,
+//! into a [`BoxedLanguageModel`]. This lets applications select models from
+//! configuration files instead of compile-time generics.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(all(feature = "openai", feature = "anthropic"))]
+//!# {
+//! use aisdk::core::DynamicModel;
+//! use aisdk::core::registry::ProviderRegistry;
+//! use aisdk::providers::{Anthropic, OpenAI};
+//!
+//! let registry = ProviderRegistry::new()
+//!     .with_provider("openai", |model| Ok(OpenAI::<DynamicModel>::model_name(model)))
+//!     .with_provider("anthropic", |model| {
+//!         Anthropic::<DynamicModel>::builder()
+//!             .model_name(model)
+//!             .api_key("my-api-key")
+//!             .build()
+//!     });
+//!
+//! let model = registry.language_model("anthropic:claude-sonnet-4-5").unwrap();
+//!# }
+//! ```
+
+use crate::core::language_model::LanguageModel;
+use crate::core::language_model::boxed::BoxedLanguageModel;
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Creates a model of a provider from its model name.
+type ModelFactory = dyn Fn(&str) -> Result<BoxedLanguageModel> + Send + Sync;
+
+/// A registry of providers that resolves models from `"{provider}:{model}"` IDs.
+#[derive(Clone, Default)]
+pub struct ProviderRegistry {
+    providers: HashMap<String, Arc<ModelFactory>>,
+}
+
+impl ProviderRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a provider under an ID.
+    ///
+    /// The factory creates a model of the provider from the model name of the
+    /// resolved ID. Registering an ID again replaces the previous provider.
+    pub fn with_provider<M, F>(mut self, id: impl Into<String>, factory: F) -> Self
+    where
+        M: LanguageModel,
+        F: Fn(&str) -> Result<M> + Send + Sync + 'static,
+    {
+        self.providers.insert(
+            id.into(),
+            Arc::new(move |model| factory(model).map(BoxedLanguageModel::new)),
+        );
+        self
+    }
+
+    /// Returns the IDs of the registered providers.
+    pub fn providers(&self) -> impl Iterator<Item = &str> {
+        self.providers.keys().map(String::as_str)
+    }
+
+    /// Resolves a `"{provider}:{model}"` ID into a language model.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidInput`] if the ID is malformed or the provider is not
+    /// registered, and the error of the provider's factory if it fails.
+    pub fn language_model(&self, id: &str) -> Result<BoxedLanguageModel> {
+        let (provider, model) = id.split_once(':').ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "model ID `{id}` must be in the form `provider:model`"
+            ))
+        })?;
+        let factory = self.providers.get(provider).ok_or_else(|| {
+            Error::InvalidInput(format!("provider `{provider}` is not registered"))
+        })?;
+        factory(model)
+    }
+}
+
+impl Debug for ProviderRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProviderRegistry")
+            .field("providers", &self.providers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::language_model::LanguageModelOptions;
+    use crate::providers::MockLanguageModel;
+
+    /// Answers once with its name.
+    fn named(name: String) -> MockLanguageModel {
+        MockLanguageModel::new()
+            .with_name(name.clone())
+            .with_text(name)
+    }
+
+    #[tokio::test]
+    async fn test_registry_resolves_models_by_id() {
+        let registry = ProviderRegistry::new()
+            .with_provider("first", |model| Ok(named(format!("first/{model}"))))
+            .with_provider("second", |model| match model {
+                "missing" => Err(Error::MissingField("api_key".to_string())),
+                model => Ok(named(format!("second/{model}"))),
+            });
+
+        let mut model = registry.language_model("second:model-a").unwrap();
+        assert_eq!(model.name(), "second/model-a");
+        let response = model
+            .generate_text(LanguageModelOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(response.contents.len(), 1);
+
+        let mut providers: Vec<_> = registry.providers().collect();
+        providers.sort();
+        assert_eq!(providers, ["first", "second"]);
+
+        assert!(matches!(
+            registry.language_model("model-a"),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            registry.language_model("third:model-a"),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            registry.language_model("second:missing"),
+            Err(Error::MissingField(_))
+        ));
+    }
+}
@@ -0,0 +1,124 @@
+//! The async runtime the crate runs on.
+//!
+//! Tasks, timers and clocks of the crate go through this module. Natively they
+//! run on tokio. On `wasm32` with the `wasm` feature, tasks run on the event loop
+//! of the browser, timers use `setTimeout`, and the futures of the HTTP client,
+//! which are not `Send` there, are wrapped with [`sendable`].
+
+use std::future::Future;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::{SystemTime, UNIX_EPOCH};
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use tokio::time::{Instant, interval_at, sleep};
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) use wasmtimer::{
+    std::Instant,
+    tokio::{interval_at, sleep},
+};
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::{SystemTime, UNIX_EPOCH};
+
+/// A handle to the output of a task started with [`spawn_with_handle`].
+///
+/// Awaiting it fails if the task panicked.
+#[cfg(not(target_arch = "wasm32"))]
+pub type JoinHandle<T> = tokio::task::JoinHandle<T>;
+
+/// A handle to the output of a task started with [`spawn_with_handle`].
+///
+/// Awaiting it fails if the task panicked.
+#[cfg(target_arch = "wasm32")]
+pub type JoinHandle<T> = futures::channel::oneshot::Receiver<T>;
+
+/// Runs a future in the background on the async runtime, discarding its output.
+///
+/// All fire-and-forget tasks of the crate go through this function. The task
+/// keeps the ID generator of the caller.
+pub(crate) fn spawn<F>(future: F)
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    #[cfg(not(target_arch = "wasm32"))]
+    tokio::spawn(crate::core::ids::inherit_id_generator(future));
+
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_futures::spawn_local(async move {
+        crate::core::ids::inherit_id_generator(future).await;
+    });
+}
+
+/// Runs a future in the background on the async runtime and returns a handle to
+/// its output. The task keeps the ID generator of the caller.
+pub(crate) fn spawn_with_handle<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    #[cfg(not(target_arch = "wasm32"))]
+    return tokio::spawn(crate::core::ids::inherit_id_generator(future));
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        spawn(async move {
+            let _ = sender.send(future.await);
+        });
+        receiver
+    }
+}
+
+/// Makes a future or stream `Send`.
+///
+/// The futures and streams of the HTTP client are not `Send` on `wasm32`, where
+/// everything runs on a single thread, so they are wrapped to be usable in the
+/// `Send` futures of the crate. Natively the value is returned unchanged.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn sendable<T: Send>(value: T) -> T {
+    value
+}
+
+/// Makes a future or stream `Send`.
+///
+/// The futures and streams of the HTTP client are not `Send` on `wasm32`, where
+/// everything runs on a single thread, so they are wrapped to be usable in the
+/// `Send` futures of the crate. Natively the value is returned unchanged.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn sendable<T>(value: T) -> send_wrapper::SendWrapper<T> {
+    send_wrapper::SendWrapper::new(value)
+}
+
+/// Runs a future to completion from synchronous code, such as the function of a tool.
+///
+/// On a multi-threaded runtime the current worker is handed over while the future
+/// runs. Otherwise, or outside of a runtime, the future runs on a separate thread.
+/// Not available on `wasm32`, where the single thread can not block.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn block_on<F>(future: F) -> F::Output
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    use tokio::runtime::{Handle, RuntimeFlavor};
+
+    if let Ok(handle) = Handle::try_current()
+        && handle.runtime_flavor() == RuntimeFlavor::MultiThread
+    {
+        return tokio::task::block_in_place(|| handle.block_on(future));
+    }
+
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .expect("Failed to build runtime")
+                    .block_on(future)
+            })
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
@@ -0,0 +1,496 @@
+//! Transformations of the JSON schemas sent to providers.
+
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// Keywords holding a map of subschemas.
+const SCHEMA_MAPS: [&str; 3] = ["properties", "$defs", "definitions"];
+
+/// Keywords holding a list of subschemas.
+const SCHEMA_LISTS: [&str; 4] = ["anyOf", "oneOf", "allOf", "prefixItems"];
+
+/// Keywords holding a single subschema.
+const SCHEMA_VALUES: [&str; 6] = ["items", "additionalProperties", "not", "if", "then", "else"];
+
+/// Keywords OpenAI strict mode rejects.
+const OPENAI_STRICT_UNSUPPORTED: [&str; 14] = [
+    "default",
+    "not",
+    "if",
+    "then",
+    "else",
+    "patternProperties",
+    "unevaluatedProperties",
+    "unevaluatedItems",
+    "propertyNames",
+    "dependentRequired",
+    "dependentSchemas",
+    "contains",
+    "minContains",
+    "maxContains",
+];
+
+/// Keywords Gemini accepts.
+const GEMINI_SUPPORTED: [&str; 21] = [
+    "type",
+    "title",
+    "description",
+    "nullable",
+    "enum",
+    "items",
+    "properties",
+    "required",
+    "propertyOrdering",
+    "anyOf",
+    "minItems",
+    "maxItems",
+    "minProperties",
+    "maxProperties",
+    "minLength",
+    "maxLength",
+    "pattern",
+    "minimum",
+    "maximum",
+    "default",
+    "example",
+];
+
+/// Keywords about the schema document rather than the data, dropped silently.
+const DOCUMENT_KEYWORDS: [&str; 5] = ["$schema", "$id", "$comment", "$defs", "definitions"];
+
+/// The dialect of JSON schema accepted by a provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SchemaDialect {
+    /// OpenAI strict mode, used by tools and structured outputs.
+    OpenAIStrict,
+    /// Gemini, which takes a subset of OpenAPI schemas without references.
+    Gemini,
+}
+
+impl SchemaDialect {
+    fn name(self) -> &'static str {
+        match self {
+            Self::OpenAIStrict => "OpenAI strict mode",
+            Self::Gemini => "Gemini",
+        }
+    }
+}
+
+/// Calls `f` on each direct subschema of a schema.
+fn for_each_subschema(object: &mut Map<String, Value>, f: &mut impl FnMut(&mut Value)) {
+    for (keyword, value) in object.iter_mut() {
+        let keyword = keyword.as_str();
+        match value {
+            Value::Object(schemas) if SCHEMA_MAPS.contains(&keyword) => {
+                schemas.values_mut().for_each(&mut *f)
+            }
+            Value::Array(schemas) if SCHEMA_LISTS.contains(&keyword) || keyword == "items" => {
+                schemas.iter_mut().for_each(&mut *f)
+            }
+            Value::Object(_) if SCHEMA_VALUES.contains(&keyword) => f(value),
+            _ => {}
+        }
+    }
+}
+
+/// Rewrites a schema into the dialect of a provider, at all levels.
+///
+/// Keywords the provider rejects are dropped, with a warning naming them since
+/// the model no longer sees the constraints they described.
+pub(crate) fn sanitize(schema: &mut Value, dialect: SchemaDialect) {
+    let mut dropped = BTreeSet::new();
+    match dialect {
+        SchemaDialect::OpenAIStrict => {
+            remove_keywords(schema, &OPENAI_STRICT_UNSUPPORTED, &mut dropped)
+        }
+        SchemaDialect::Gemini => {
+            let definitions = take_definitions(schema);
+            inline_references(schema, &definitions, &mut Vec::new(), &mut dropped);
+            to_gemini(schema, &mut dropped);
+        }
+    }
+    if !dropped.is_empty() {
+        let dropped = dropped.into_iter().collect::<Vec<_>>().join(", ");
+        log::warn!(
+            "Dropped the schema keywords {dropped}, which {} does not support",
+            dialect.name()
+        );
+    }
+}
+
+/// Removes keywords from a schema and its subschemas.
+fn remove_keywords(schema: &mut Value, keywords: &[&str], dropped: &mut BTreeSet<String>) {
+    let Value::Object(object) = schema else {
+        return;
+    };
+    object.retain(|keyword, _| {
+        let keep = !keywords.contains(&keyword.as_str());
+        if !keep {
+            dropped.insert(keyword.clone());
+        }
+        keep
+    });
+    for_each_subschema(object, &mut |schema| {
+        remove_keywords(schema, keywords, dropped)
+    });
+}
+
+/// Takes the definitions out of the root of a schema.
+fn take_definitions(schema: &mut Value) -> Map<String, Value> {
+    let mut definitions = Map::new();
+    if let Value::Object(object) = schema {
+        for keyword in ["definitions", "$defs"] {
+            if let Some(Value::Object(defined)) = object.remove(keyword) {
+                definitions.extend(defined);
+            }
+        }
+    }
+    definitions
+}
+
+/// Replaces references with the definitions they point to.
+///
+/// Recursive references cannot be inlined, so they become untyped objects.
+fn inline_references(
+    schema: &mut Value,
+    definitions: &Map<String, Value>,
+    stack: &mut Vec<String>,
+    dropped: &mut BTreeSet<String>,
+) {
+    let Value::Object(object) = schema else {
+        return;
+    };
+
+    if let Some(reference) = object.remove("$ref") {
+        let name = reference.as_str().and_then(|reference| {
+            reference
+                .strip_prefix("#/$defs/")
+                .or_else(|| reference.strip_prefix("#/definitions/"))
+        });
+        match name.and_then(|name| Some((name, definitions.get(name)?))) {
+            Some((name, definition)) if !stack.iter().any(|seen| seen == name) => {
+                let name = name.to_string();
+                let mut inlined = definition.clone();
+                // Keywords next to the reference, like a description, win
+                if let Value::Object(inlined) = &mut inlined {
+                    inlined.extend(std::mem::take(object));
+                }
+                *schema = inlined;
+                stack.push(name);
+                inline_references(schema, definitions, stack, dropped);
+                stack.pop();
+                return;
+            }
+            _ => {
+                dropped.insert("$ref".to_string());
+                object
+                    .entry("type")
+                    .or_insert_with(|| Value::String("object".to_string()));
+            }
+        }
+    }
+
+    for_each_subschema(object, &mut |schema| {
+        inline_references(schema, definitions, stack, dropped)
+    });
+}
+
+/// Rewrites an inlined schema into the OpenAPI subset Gemini accepts.
+fn to_gemini(schema: &mut Value, dropped: &mut BTreeSet<String>) {
+    let Value::Object(object) = schema else {
+        return;
+    };
+
+    let mut nullable = false;
+    if let Some(Value::Array(variants)) = object.get_mut("anyOf") {
+        let count = variants.len();
+        variants.retain(|variant| variant.get("type") != Some(&Value::from("null")));
+        nullable |= variants.len() < count;
+        if variants.len() == 1 {
+            // An optional value is a single variant that can also be null
+            if let Some(Value::Object(variant)) = variants.pop() {
+                object.remove("anyOf");
+                for (keyword, value) in variant {
+                    object.entry(keyword).or_insert(value);
+                }
+            }
+        }
+    }
+    if let Some(Value::Array(kinds)) = object.get_mut("type") {
+        let count = kinds.len();
+        kinds.retain(|kind| kind != "null");
+        nullable |= kinds.len() < count;
+        if kinds.len() == 1 {
+            let kind = kinds.remove(0);
+            object.insert("type".to_string(), kind);
+        } else if let Some(Value::Array(kinds)) = object.remove("type") {
+            let variants = kinds
+                .into_iter()
+                .map(|kind| serde_json::json!({ "type": kind }))
+                .collect();
+            object.insert("anyOf".to_string(), Value::Array(variants));
+        }
+    }
+    if nullable {
+        object.insert("nullable".to_string(), Value::Bool(true));
+    }
+    if let Some(value) = object.remove("const") {
+        object.insert("enum".to_string(), Value::Array(vec![value]));
+    }
+
+    object.retain(|keyword, _| {
+        let keep = GEMINI_SUPPORTED.contains(&keyword.as_str());
+        if !keep && !DOCUMENT_KEYWORDS.contains(&keyword.as_str()) {
+            dropped.insert(keyword.clone());
+        }
+        keep
+    });
+    for_each_subschema(object, &mut |schema| to_gemini(schema, dropped));
+}
+
+/// Returns whether a schema describes an object.
+fn is_object(schema: &Map<String, Value>) -> bool {
+    let has_object_type = match schema.get("type") {
+        Some(Value::String(kind)) => kind == "object",
+        Some(Value::Array(kinds)) => kinds.iter().any(|kind| kind == "object"),
+        _ => false,
+    };
+    has_object_type || schema.contains_key("properties")
+}
+
+/// Lets a schema also accept `null`.
+fn make_nullable(schema: &mut Value) {
+    let Value::Object(object) = schema else {
+        return;
+    };
+    if !object.contains_key("type") {
+        if let Some(Value::Array(variants)) = object.get_mut("anyOf") {
+            if !variants.iter().any(|variant| variant["type"] == "null") {
+                variants.push(serde_json::json!({ "type": "null" }));
+            }
+        } else {
+            *schema = serde_json::json!({ "anyOf": [schema.take(), { "type": "null" }] });
+        }
+        return;
+    }
+    match object.get_mut("type") {
+        Some(Value::String(kind)) if kind == "null" => return,
+        Some(kind @ Value::String(_)) => {
+            let single = kind.take();
+            *kind = Value::Array(vec![single, "null".into()]);
+        }
+        Some(Value::Array(kinds)) if !kinds.iter().any(|kind| kind == "null") => {
+            kinds.push("null".into())
+        }
+        _ => {}
+    }
+    if let Some(Value::Array(values)) = object.get_mut("enum")
+        && !values.contains(&Value::Null)
+    {
+        values.push(Value::Null);
+    }
+}
+
+/// Makes a schema follow the rules of OpenAI's strict mode, at all levels.
+///
+/// Objects list all their properties as required and forbid additional
+/// properties. Properties that were optional become nullable, so the model
+/// leaves them out by sending `null`.
+pub(crate) fn make_strict(schema: &mut Value) {
+    let Value::Object(object) = schema else {
+        return;
+    };
+
+    if is_object(object) {
+        let required = match object.remove("required") {
+            Some(Value::Array(required)) => required,
+            _ => Vec::new(),
+        };
+        let properties = object
+            .entry("properties")
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Value::Object(properties) = properties {
+            for (name, property) in properties.iter_mut() {
+                if !required.iter().any(|required| required == name.as_str()) {
+                    make_nullable(property);
+                }
+            }
+            let names = properties.keys().cloned().map(Value::String).collect();
+            object.insert("required".to_string(), Value::Array(names));
+        }
+        object.insert("additionalProperties".to_string(), Value::Bool(false));
+    }
+
+    for_each_subschema(object, &mut make_strict);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_make_strict_requires_all_properties() {
+        let mut schema = json!({
+            "type": "object",
+            "properties": {
+                "city": { "type": "string" },
+                "unit": { "type": "string", "enum": ["celsius", "fahrenheit"] },
+                "days": { "type": ["integer", "null"] },
+                "place": { "$ref": "#/$defs/Place" },
+            },
+            "required": ["city"],
+            "$defs": {
+                "Place": {
+                    "type": "object",
+                    "properties": { "name": { "type": "string" } },
+                },
+            },
+        });
+
+        make_strict(&mut schema);
+
+        assert_eq!(
+            schema,
+            json!({
+                "type": "object",
+                "properties": {
+                    "city": { "type": "string" },
+                    "unit": {
+                        "type": ["string", "null"],
+                        "enum": ["celsius", "fahrenheit", null],
+                    },
+                    "days": { "type": ["integer", "null"] },
+                    "place": { "anyOf": [{ "$ref": "#/$defs/Place" }, { "type": "null" }] },
+                },
+                "required": ["city", "days", "place", "unit"],
+                "additionalProperties": false,
+                "$defs": {
+                    "Place": {
+                        "type": "object",
+                        "properties": { "name": { "type": ["string", "null"] } },
+                        "required": ["name"],
+                        "additionalProperties": false,
+                    },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_make_strict_recurses_into_items_and_variants() {
+        let mut schema = json!({
+            "type": "object",
+            "properties": {
+                "stops": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "name": { "type": "string" } },
+                        "required": ["name"],
+                    },
+                },
+                "filter": {
+                    "anyOf": [
+                        { "type": "object", "properties": {} },
+                        { "type": "string" },
+                    ],
+                },
+            },
+            "required": ["stops", "filter"],
+        });
+
+        make_strict(&mut schema);
+
+        assert_eq!(
+            schema["properties"]["stops"]["items"]["additionalProperties"],
+            false
+        );
+        assert_eq!(
+            schema["properties"]["filter"]["anyOf"][0],
+            json!({ "type": "object", "properties": {}, "required": [], "additionalProperties": false })
+        );
+        assert_eq!(
+            schema["properties"]["filter"]["anyOf"][1],
+            json!({ "type": "string" })
+        );
+    }
+
+    #[test]
+    fn test_sanitize_for_openai_strict_drops_defaults() {
+        let mut schema = json!({
+            "type": "object",
+            "properties": {
+                "default": { "type": "string", "default": "none" },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "contains": { "const": "rust" },
+                },
+            },
+        });
+
+        sanitize(&mut schema, SchemaDialect::OpenAIStrict);
+
+        assert_eq!(
+            schema,
+            json!({
+                "type": "object",
+                "properties": {
+                    "default": { "type": "string" },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_sanitize_for_gemini_inlines_references() {
+        let mut schema = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "Trip",
+            "type": "object",
+            "properties": {
+                "start": { "type": "string", "format": "date" },
+                "nights": { "type": ["integer", "null"], "format": "uint32" },
+                "home": { "anyOf": [{ "$ref": "#/$defs/Place" }, { "type": "null" }] },
+                "mode": { "const": "train" },
+            },
+            "required": ["start"],
+            "additionalProperties": false,
+            "$defs": {
+                "Place": {
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string" },
+                        "near": { "$ref": "#/$defs/Place", "description": "A nearby place" },
+                    },
+                },
+            },
+        });
+
+        sanitize(&mut schema, SchemaDialect::Gemini);
+
+        assert_eq!(
+            schema,
+            json!({
+                "title": "Trip",
+                "type": "object",
+                "properties": {
+                    "start": { "type": "string" },
+                    "nights": { "type": "integer", "nullable": true },
+                    "home": {
+                        "type": "object",
+                        "nullable": true,
+                        "properties": {
+                            "name": { "type": "string" },
+                            "near": { "type": "object", "description": "A nearby place" },
+                        },
+                    },
+                    "mode": { "enum": ["train"] },
+                },
+                "required": ["start"],
+            })
+        );
+    }
+}
@@ -0,0 +1,486 @@
+//! Assertion helpers for testing agent loops.
+//!
+//! The helpers check the steps of a generation, such as the tools the model
+//! called, and print a [`StepTrace`] of the whole conversation when they fail, so
+//! multi-step runs can be debugged from the test output. They take the options
+//! of a response, which a [`GenerateTextResponse`](crate::core::GenerateTextResponse)
+//! dereferences to.
+//!
+//! Responses can also be compared with a stored snapshot: [`snapshot`] turns the
+//! conversation, usage and stop reason into canonical JSON, with volatile fields
+//! such as tool call IDs redacted, and [`assert_snapshot`] compares it with the
+//! file of the snapshot. A missing file fails the assertion, so snapshots that
+//! were never committed do not pass in CI. Set the `AISDK_UPDATE_SNAPSHOTS`
+//! environment variable to write missing files and update changed ones.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "openai")]
+//!# {
+//! use aisdk::core::LanguageModelRequest;
+//! use aisdk::core::testing::{StepTrace, assert_steps, assert_tool_called};
+//! use aisdk::providers::OpenAI;
+//!
+//! # async fn run(get_weather: aisdk::core::Tool) {
+//! let response = LanguageModelRequest::builder()
+//!     .model(OpenAI::gpt_5())
+//!     .prompt("What is the weather in Paris?")
+//!     .with_tool(get_weather)
+//!     .build()
+//!     .generate_text()
+//!     .await
+//!     .unwrap();
+//!
+//! println!("{}", StepTrace::new(&response));
+//! assert_tool_called(&response, "get_weather");
+//! assert_steps(&response, 2);
+//! # }
+//!# }
+//! ```
+
+use crate::core::language_model::{LanguageModelOptions, LanguageModelResponseContentType};
+use crate::core::tools::ToolCallInfo;
+use crate::core::{AssistantMessage, Message};
+use serde_json::{Value, json};
+use std::fmt::{self, Display};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A printable trace of the steps of a generation, one line per message.
+///
+/// ```text
+/// step 0
+///   user: What is the weather in Paris?
+/// step 1
+///   tool call: get_weather {"city":"Paris"}
+///   tool result: get_weather -> "sunny"
+/// step 2
+///   assistant: It is sunny in Paris.
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct StepTrace<'a> {
+    options: &'a LanguageModelOptions,
+}
+
+impl<'a> StepTrace<'a> {
+    /// Creates the trace of the steps of a response.
+    pub fn new(options: &'a LanguageModelOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl Display for StepTrace<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in self.options.steps() {
+            writeln!(f, "step {}", step.step_id)?;
+            for message in step.messages() {
+                write!(f, "  ")?;
+                match message {
+                    Message::System(system) => writeln!(f, "system: {}", system.content)?,
+                    Message::Developer(content) => writeln!(f, "developer: {content}")?,
+                    Message::User(user) => writeln!(f, "user: {}", user.content)?,
+                    Message::Assistant(AssistantMessage { content, .. }) => match content {
+                        LanguageModelResponseContentType::Text(text) => {
+                            writeln!(f, "assistant: {text}")?
+                        }
+                        LanguageModelResponseContentType::ToolCall(call) => {
+                            writeln!(f, "tool call: {} {}", call.tool.name, call.input)?
+                        }
+                        LanguageModelResponseContentType::Reasoning { content, .. } => {
+                            writeln!(f, "reasoning: {content}")?
+                        }
+                        LanguageModelResponseContentType::Source(source) => {
+                            writeln!(f, "source: {}", source.id())?
+                        }
+                        LanguageModelResponseContentType::Citation(citation) => {
+                            writeln!(f, "citation: {}", citation.cited_text)?
+                        }
+                        LanguageModelResponseContentType::NotSupported(kind) => {
+                            writeln!(f, "not supported: {kind}")?
+                        }
+                    },
+                    Message::Tool(result) => match &result.output {
+                        Ok(output) => writeln!(f, "tool result: {} -> {output}", result.tool.name)?,
+                        Err(error) => writeln!(f, "tool error: {} -> {error}", result.tool.name)?,
+                    },
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the calls of the tool with the given name, in order.
+fn calls_of(options: &LanguageModelOptions, name: &str) -> Vec<ToolCallInfo> {
+    options
+        .tool_calls()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|call| call.tool.name == name)
+        .collect()
+}
+
+/// Asserts that the model called the tool with the given name.
+///
+/// # Panics
+///
+/// Panics with the trace of the steps if the tool was not called.
+#[track_caller]
+pub fn assert_tool_called(options: &LanguageModelOptions, name: &str) {
+    assert!(
+        !calls_of(options, name).is_empty(),
+        "expected a call of `{name}`\n{}",
+        StepTrace::new(options)
+    );
+}
+
+/// Asserts that the model called the tool with the given name and input.
+///
+/// # Panics
+///
+/// Panics with the trace of the steps if no call of the tool had the input.
+#[track_caller]
+pub fn assert_tool_called_with(
+    options: &LanguageModelOptions,
+    name: &str,
+    input: &serde_json::Value,
+) {
+    assert!(
+        calls_of(options, name)
+            .iter()
+            .any(|call| &call.input == input),
+        "expected a call of `{name}` with {input}\n{}",
+        StepTrace::new(options)
+    );
+}
+
+/// Asserts that the model did not call the tool with the given name.
+///
+/// # Panics
+///
+/// Panics with the trace of the steps if the tool was called.
+#[track_caller]
+pub fn assert_tool_not_called(options: &LanguageModelOptions, name: &str) {
+    assert!(
+        calls_of(options, name).is_empty(),
+        "expected no call of `{name}`\n{}",
+        StepTrace::new(options)
+    );
+}
+
+/// Asserts that the model answered in the given number of steps.
+///
+/// Only the steps with a response of the model are counted, not the step of
+/// the initial prompt.
+///
+/// # Panics
+///
+/// Panics with the trace of the steps if the number of steps differs.
+#[track_caller]
+pub fn assert_steps(options: &LanguageModelOptions, steps: usize) {
+    let count = options
+        .steps()
+        .iter()
+        .filter(|step| {
+            step.messages()
+                .iter()
+                .any(|message| matches!(message, Message::Assistant(_)))
+        })
+        .count();
+    assert_eq!(
+        count,
+        steps,
+        "expected {steps} steps, got {count}\n{}",
+        StepTrace::new(options)
+    );
+}
+
+/// Hook redacting parts of a snapshot.
+pub type RedactionHook = Arc<dyn Fn(&mut Value) + Send + Sync>;
+
+/// Redacts the volatile fields of a snapshot, such as IDs and timestamps.
+///
+/// The default redactions replace the value of every `id` field with `"[id]"`.
+#[derive(Clone)]
+pub struct Redactions {
+    keys: Vec<String>,
+    hooks: Vec<RedactionHook>,
+}
+
+impl Default for Redactions {
+    fn default() -> Self {
+        Self::none().key("id")
+    }
+}
+
+impl Redactions {
+    /// Creates redactions that keep the snapshot unchanged.
+    pub fn none() -> Self {
+        Self {
+            keys: Vec::new(),
+            hooks: Vec::new(),
+        }
+    }
+
+    /// Replaces the value of every field with the given name, at any depth, with
+    /// `"[name]"`.
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.keys.push(key.into());
+        self
+    }
+
+    /// Adds a hook changing the snapshot, applied after the redacted keys.
+    pub fn with(mut self, hook: impl Fn(&mut Value) + Send + Sync + 'static) -> Self {
+        self.hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Applies the redactions to a snapshot.
+    pub fn apply(&self, value: &mut Value) {
+        self.redact_keys(value);
+        for hook in &self.hooks {
+            hook(value);
+        }
+    }
+
+    fn redact_keys(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if self.keys.contains(key) {
+                        *value = Value::String(format!("[{key}]"));
+                    } else {
+                        self.redact_keys(value);
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(|v| self.redact_keys(v)),
+            _ => {}
+        }
+    }
+}
+
+/// Returns the canonical JSON of the steps, usage and stop reason of a
+/// generation, with the [default redactions](Redactions::default).
+pub fn snapshot(options: &LanguageModelOptions) -> Value {
+    snapshot_with(options, &Redactions::default())
+}
+
+/// Returns the canonical JSON of the steps, usage and stop reason of a
+/// generation, with the given redactions.
+pub fn snapshot_with(options: &LanguageModelOptions, redactions: &Redactions) -> Value {
+    let steps: Vec<Value> = options
+        .steps()
+        .iter()
+        .map(|step| {
+            json!({
+                "step": step.step_id,
+                "messages": step.messages(),
+            })
+        })
+        .collect();
+    let mut snapshot = json!({
+        "steps": steps,
+        "usage": options.usage(),
+        "stop_reason": options.stop_reason().map(|reason| format!("{reason:?}")),
+    });
+    redactions.apply(&mut snapshot);
+    snapshot
+}
+
+/// Asserts that a snapshot matches the file `tests/snapshots/{name}.json` of
+/// the crate under test.
+///
+/// # Panics
+///
+/// Panics with the differing lines if the snapshot does not match the file.
+#[track_caller]
+pub fn assert_snapshot(name: &str, snapshot: &Value) {
+    let dir = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default()
+        .join("tests")
+        .join("snapshots");
+    assert_snapshot_in(&dir, name, snapshot);
+}
+
+/// Asserts that a snapshot matches the file `{name}.json` of a directory.
+///
+/// The file is written instead if the `AISDK_UPDATE_SNAPSHOTS` environment
+/// variable is set.
+///
+/// # Panics
+///
+/// Panics if the file does not exist, or with the differing lines if the
+/// snapshot does not match it.
+#[track_caller]
+pub fn assert_snapshot_in(dir: &Path, name: &str, snapshot: &Value) {
+    let update = std::env::var_os("AISDK_UPDATE_SNAPSHOTS").is_some();
+    check_snapshot(dir, name, snapshot, update);
+}
+
+/// Compares a snapshot with its file, or writes the file when updating.
+#[track_caller]
+fn check_snapshot(dir: &Path, name: &str, snapshot: &Value, update: bool) {
+    let path = dir.join(format!("{name}.json"));
+    let actual = format!("{}\n", serde_json::to_string_pretty(snapshot).unwrap());
+    let expected = std::fs::read_to_string(&path).ok();
+
+    match expected {
+        None if !update => panic!(
+            "snapshot `{name}` is missing at {}\nSet AISDK_UPDATE_SNAPSHOTS=1 to write it",
+            path.display()
+        ),
+        Some(expected) if !update => {
+            if expected == actual {
+                return;
+            }
+            let mut diff = String::new();
+            let (expected, actual): (Vec<_>, Vec<_>) =
+                (expected.lines().collect(), actual.lines().collect());
+            for line in 0..expected.len().max(actual.len()) {
+                let (old, new) = (expected.get(line), actual.get(line));
+                if old != new {
+                    if let Some(old) = old {
+                        diff.push_str(&format!("{:>4} - {old}\n", line + 1));
+                    }
+                    if let Some(new) = new {
+                        diff.push_str(&format!("{:>4} + {new}\n", line + 1));
+                    }
+                }
+            }
+            panic!(
+                "snapshot `{name}` does not match {}\n{diff}Set AISDK_UPDATE_SNAPSHOTS=1 to update it",
+                path.display()
+            );
+        }
+        _ => {
+            std::fs::create_dir_all(dir).unwrap();
+            std::fs::write(&path, actual).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::messages::TaggedMessage;
+    use crate::core::tools::ToolResultInfo;
+
+    fn agent_run() -> LanguageModelOptions {
+        let mut call = ToolCallInfo::new("get_weather");
+        call.input(serde_json::json!({ "city": "Paris" }));
+        let mut result = ToolResultInfo::new("get_weather");
+        result.output = Ok(serde_json::json!("sunny"));
+        let assistant = |content| {
+            Message::Assistant(AssistantMessage {
+                content,
+                usage: None,
+            })
+        };
+
+        LanguageModelOptions {
+            messages: vec![
+                TaggedMessage::initial_step_msg(Message::User(
+                    "What is the weather in Paris?".to_string().into(),
+                )),
+                TaggedMessage::new(
+                    1,
+                    assistant(LanguageModelResponseContentType::ToolCall(call)),
+                ),
+                TaggedMessage::new(1, Message::Tool(result)),
+                TaggedMessage::new(
+                    2,
+                    assistant(LanguageModelResponseContentType::Text(
+                        "It is sunny in Paris.".to_string(),
+                    )),
+                ),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_step_trace() {
+        assert_eq!(
+            StepTrace::new(&agent_run()).to_string(),
+            "step 0\n  user: What is the weather in Paris?\nstep 1\n  tool call: get_weather {\"city\":\"Paris\"}\n  tool result: get_weather -> \"sunny\"\nstep 2\n  assistant: It is sunny in Paris.\n"
+        );
+    }
+
+    #[test]
+    fn test_assertions() {
+        let options = agent_run();
+        assert_tool_called(&options, "get_weather");
+        assert_tool_called_with(
+            &options,
+            "get_weather",
+            &serde_json::json!({ "city": "Paris" }),
+        );
+        assert_tool_not_called(&options, "send_email");
+        assert_steps(&options, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a call of `send_email`\nstep 0")]
+    fn test_assert_tool_called_prints_trace() {
+        assert_tool_called(&agent_run(), "send_email");
+    }
+
+    #[test]
+    fn test_snapshot_redacts_volatile_fields() {
+        let mut options = agent_run();
+        options.messages[1] = TaggedMessage::new(1, {
+            let mut call = ToolCallInfo::new("get_weather");
+            call.id("call_8f2a");
+            call.input(serde_json::json!({ "city": "Paris" }));
+            Message::Assistant(AssistantMessage::new(
+                LanguageModelResponseContentType::ToolCall(call),
+                None,
+            ))
+        });
+
+        let snapshot = snapshot(&options);
+        assert_eq!(snapshot["steps"][1]["step"], 1);
+        assert_eq!(
+            snapshot["steps"][1]["messages"][0].to_string(),
+            snapshot_with(&options, &Redactions::none())["steps"][1]["messages"][0]
+                .to_string()
+                .replace("call_8f2a", "[id]")
+        );
+
+        let redactions = Redactions::none()
+            .key("city")
+            .with(|value| value["usage"] = Value::Null);
+        let snapshot = snapshot_with(&options, &redactions);
+        assert!(snapshot.to_string().contains(r#""city":"[city]""#));
+        assert_eq!(snapshot["usage"], Value::Null);
+    }
+
+    #[test]
+    fn test_assert_snapshot_in() {
+        let dir = tempfile::tempdir().unwrap();
+        let value = snapshot(&agent_run());
+        let missing = std::panic::catch_unwind(|| {
+            check_snapshot(dir.path(), "agent_run", &value, false);
+        });
+        let message = missing.unwrap_err();
+        let message = message.downcast_ref::<String>().unwrap();
+        assert!(message.contains("snapshot `agent_run` is missing"));
+        assert!(!dir.path().join("agent_run.json").exists());
+
+        // An update writes the snapshot, the next runs compare with it
+        check_snapshot(dir.path(), "agent_run", &value, true);
+        assert!(dir.path().join("agent_run.json").exists());
+        assert_snapshot_in(dir.path(), "agent_run", &value);
+
+        let changed = std::panic::catch_unwind(|| {
+            assert_snapshot_in(dir.path(), "agent_run", &serde_json::json!({ "steps": [] }));
+        });
+        let message = changed.unwrap_err();
+        let message = message.downcast_ref::<String>().unwrap();
+        assert!(message.contains("snapshot `agent_run` does not match"));
+        assert!(message.contains(r#"   2 +   "steps": []"#));
+    }
+}
@@ -0,0 +1,136 @@
+//! Token estimation utilities.
+//!
+//! Providers tokenize text differently and most do not expose their tokenizers,
+//! so this module offers a fast, provider-agnostic approximation. It is intended
+//! for budgeting (e.g. fitting a conversation into a context window), not billing.
+//!
+//! The estimate follows the common rule of thumb of roughly four characters per
+//! token for English text, never counting fewer tokens than there are words, and
+//! adds a small fixed overhead per message for role and formatting tokens.
+
+use crate::core::language_model::LanguageModelResponseContentType;
+use crate::core::messages::Message;
+
+/// Average number of characters per token used by the estimator.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Fixed number of tokens added per message for role and formatting markup.
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Estimates the number of tokens in a piece of text.
+///
+/// # Examples
+///
+/// ```rust
+/// use aisdk::core::tokens::estimate_tokens;
+///
+/// assert_eq!(estimate_tokens(""), 0);
+/// assert_eq!(estimate_tokens("Hello, world!"), 4);
+/// ```
+pub fn estimate_tokens(text: &str) -> usize {
+    let chars = text.chars().count();
+    let words = text.split_whitespace().count();
+    chars.div_ceil(CHARS_PER_TOKEN).max(words)
+}
+
+/// Estimates the number of tokens a message occupies in a request, including overhead.
+pub fn estimate_message_tokens(message: &Message) -> usize {
+    MESSAGE_OVERHEAD_TOKENS + estimate_tokens(&message_text(message))
+}
+
+/// Estimates the total number of tokens of a list of messages.
+pub fn estimate_messages_tokens(messages: &[Message]) -> usize {
+    messages.iter().map(estimate_message_tokens).sum()
+}
+
+/// Renders the content of a message as plain text.
+pub(crate) fn message_text(message: &Message) -> String {
+    match message {
+        Message::System(s) => s.content.clone(),
+        Message::User(u) => u.content.clone(),
+        Message::Developer(d) => d.clone(),
+        Message::Assistant(a) => match &a.content {
+            LanguageModelResponseContentType::Text(text) => text.clone(),
+            LanguageModelResponseContentType::Reasoning { content, .. } => content.clone(),
+            LanguageModelResponseContentType::ToolCall(info) => {
+                format!("{} {}", info.tool.name, info.input)
+            }
+            LanguageModelResponseContentType::Source(_)
+            | LanguageModelResponseContentType::Citation(_) => String::new(),
+            LanguageModelResponseContentType::NotSupported(text) => text.clone(),
+        },
+        Message::Tool(result) => match &result.output {
+            Ok(value) => format!("{} {}", result.tool.name, value),
+            Err(err) => format!("{} {}", result.tool.name, err),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::AssistantMessage;
+    use crate::core::tools::{ToolCallInfo, ToolResultInfo};
+
+    #[test]
+    fn test_estimate_tokens_empty() {
+        assert_eq!(estimate_tokens(""), 0);
+    }
+
+    #[test]
+    fn test_estimate_tokens_uses_character_count() {
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+        assert_eq!(estimate_tokens("abcdefghi"), 3);
+    }
+
+    #[test]
+    fn test_estimate_tokens_never_below_word_count() {
+        assert_eq!(estimate_tokens("a b c d e f"), 6);
+    }
+
+    #[test]
+    fn test_estimate_message_tokens_includes_overhead() {
+        let message = Message::User("abcdefgh".into());
+        assert_eq!(
+            estimate_message_tokens(&message),
+            MESSAGE_OVERHEAD_TOKENS + 2
+        );
+    }
+
+    #[test]
+    fn test_estimate_messages_tokens_sums_messages() {
+        let messages = vec![
+            Message::System("abcd".into()),
+            Message::User("abcd".into()),
+            Message::Assistant(AssistantMessage::new(
+                LanguageModelResponseContentType::Text("abcd".to_string()),
+                None,
+            )),
+        ];
+        assert_eq!(
+            estimate_messages_tokens(&messages),
+            3 * (MESSAGE_OVERHEAD_TOKENS + 1)
+        );
+    }
+
+    #[test]
+    fn test_message_text_tool_messages() {
+        let mut call = ToolCallInfo::new("get_weather");
+        call.input(serde_json::json!({"city": "Paris"}));
+        let call_message = Message::Assistant(AssistantMessage::new(
+            LanguageModelResponseContentType::ToolCall(call),
+            None,
+        ));
+        assert_eq!(
+            message_text(&call_message),
+            r#"get_weather {"city":"Paris"}"#
+        );
+
+        let mut result = ToolResultInfo::new("get_weather");
+        result.output(serde_json::json!("sunny"));
+        assert_eq!(
+            message_text(&Message::Tool(result)),
+            r#"get_weather "sunny""#
+        );
+    }
+}
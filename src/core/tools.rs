@@ -55,6 +55,7 @@
 //!             let b = params["b"].as_u64().unwrap();
 //!             Ok(format!("{}", a + b))
 //!         })),
+//!     strict: None,
 //! };
 //!
 //! assert_eq!(tool.name, "sum");
@@ -62,6 +63,9 @@
 //! ```
 //!
 
+use crate::core::Message;
+use crate::core::language_model::LanguageModelOptions;
+use crate::core::runtime::{JoinHandle, spawn_with_handle};
 use crate::error::{Error, Result};
 use crate::extensions::Extensions;
 use derive_builder::Builder;
@@ -70,7 +74,6 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fmt::Debug;
 use std::sync::{Arc, Mutex};
-use tokio::task::JoinHandle;
 
 /// A function that will be called when the tool is executed.
 pub type ToolFn = Box<dyn Fn(Value) -> std::result::Result<String, String> + Send + Sync>;
@@ -158,6 +161,7 @@ impl<'de> Deserialize<'de> for ToolExecute {
 ///             let b = params["b"].as_u64().unwrap();
 ///             Ok(format!("{}", a + b))
 ///         })),
+///     strict: None,
 /// };
 ///
 /// assert_eq!(tool.name, "sum");
@@ -174,6 +178,13 @@ pub struct Tool {
     pub input_schema: Schema,
     /// The output schema of the tool. AI will use this to generate outputs.
     pub execute: ToolExecute,
+    /// Whether providers supporting it, such as OpenAI, constrain the input of
+    /// the tool to its schema. Defaults to strict.
+    ///
+    /// Strict mode supports a subset of JSON schema, so tools whose schema uses
+    /// keywords it rejects should disable it.
+    #[builder(default)]
+    pub strict: Option<bool>,
 }
 
 impl Debug for Tool {
@@ -181,6 +192,7 @@ impl Debug for Tool {
         f.debug_struct("Tool")
             .field("name", &self.name)
             .field("description", &self.description)
+            .field("strict", &self.strict)
             .finish()
     }
 }
@@ -215,10 +227,25 @@ impl ToolList {
             .push(tool);
     }
 
+    /// Returns a list of the tools with the given names.
+    pub fn only(&self, names: &[String]) -> ToolList {
+        let tools = self
+            .tools
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        ToolList::new(
+            tools
+                .iter()
+                .filter(|tool| names.contains(&tool.name))
+                .cloned()
+                .collect(),
+        )
+    }
+
     /// Executes a tool.
     pub async fn execute(&self, tool_info: ToolCallInfo) -> JoinHandle<Result<String>> {
         let tools = self.tools.clone();
-        tokio::spawn(async move {
+        spawn_with_handle(async move {
             let tools = tools
                 .lock()
                 .unwrap_or_else(|poisoned| poisoned.into_inner());
@@ -234,7 +261,22 @@ impl ToolList {
     }
 }
 
-#[derive(Default, Debug, Clone, PartialEq)]
+/// Whether and which tools the model must call.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolChoice {
+    /// The model decides whether to call tools.
+    #[default]
+    Auto,
+    /// The model does not call tools.
+    None,
+    /// The model calls at least one tool.
+    Required,
+    /// The model calls the tool with the given name.
+    Tool(String),
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 /// Describes a tool
 pub struct ToolDetails {
     /// The name of the tool, usually a function name.
@@ -244,13 +286,14 @@ pub struct ToolDetails {
 }
 
 /// Contains information necessary to call a tool
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct ToolCallInfo {
     /// The details of the tool to be called.
     pub tool: ToolDetails,
     /// The input parameters for the tool.
     pub input: serde_json::Value,
     /// Provider-specific extensions.
+    #[serde(default)]
     pub extensions: Extensions,
 }
 
@@ -290,15 +333,54 @@ impl ToolCallInfo {
 }
 
 /// Contains information from a tool
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolResultInfo {
     /// The details of the tool.
     pub tool: ToolDetails,
 
     /// The output of the tool.
+    #[serde(with = "tool_output")]
     pub output: Result<serde_json::Value>,
 }
 
+/// Serializes tool outputs as `{"ok": value}` or `{"error": message}`.
+///
+/// Errors are restored as [`Error::ToolCallError`] since the original error type
+/// can not be reconstructed.
+mod tool_output {
+    use crate::error::{Error, Result};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde_json::Value;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum ToolOutput {
+        Ok(Value),
+        Error(String),
+    }
+
+    pub fn serialize<S: Serializer>(
+        output: &Result<Value>,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        match output {
+            Ok(value) => ToolOutput::Ok(value.clone()),
+            Err(Error::ToolCallError(message)) => ToolOutput::Error(message.clone()),
+            Err(e) => ToolOutput::Error(e.to_string()),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Result<Value>, D::Error> {
+        Ok(match ToolOutput::deserialize(deserializer)? {
+            ToolOutput::Ok(value) => Ok(value),
+            ToolOutput::Error(message) => Err(Error::ToolCallError(message)),
+        })
+    }
+}
+
 impl Default for ToolResultInfo {
     fn default() -> Self {
         Self {
@@ -335,3 +417,118 @@ impl ToolResultInfo {
         self.output = Ok(inp);
     }
 }
+
+/// A tool call that waits for approval before it is executed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolApprovalRequest {
+    /// Identifies the request. This is the id of the tool call.
+    pub approval_id: String,
+    /// The tool call waiting for approval.
+    pub tool_call: ToolCallInfo,
+}
+
+impl ToolApprovalRequest {
+    /// Creates an approval request for the given tool call.
+    pub fn new(tool_call: ToolCallInfo) -> Self {
+        Self {
+            approval_id: tool_call.tool.id.clone(),
+            tool_call,
+        }
+    }
+}
+
+/// Decides whether a tool call needs approval, given the call and the conversation so far.
+pub type ApprovalFn = Arc<dyn Fn(&ToolCallInfo, &[Message]) -> bool + Send + Sync>;
+
+/// Decides which tool calls need approval before they are executed.
+///
+/// The policy is set on the request and checked for every tool call in both
+/// `generate_text` and `stream_text`.
+#[derive(Clone, Default)]
+pub enum ApprovalPolicy {
+    /// No tool call needs approval.
+    #[default]
+    Never,
+    /// Calls of the listed tools need approval.
+    AlwaysForTools(Vec<String>),
+    /// Calls for which the function returns `true` need approval.
+    Dynamic(ApprovalFn),
+    /// Every tool call needs approval once the estimated cost of the conversation
+    /// in US dollars reaches the threshold.
+    ///
+    /// Without pricing for the model, such as with a `DynamicModel`, the cost
+    /// cannot be estimated and every tool call needs approval.
+    CostThreshold(f64),
+}
+
+impl ApprovalPolicy {
+    /// Creates a [`Dynamic`](Self::Dynamic) policy from a function.
+    pub fn dynamic<F>(f: F) -> Self
+    where
+        F: Fn(&ToolCallInfo, &[Message]) -> bool + Send + Sync + 'static,
+    {
+        Self::Dynamic(Arc::new(f))
+    }
+
+    /// Returns `true` if the tool call needs approval in the given conversation.
+    pub(crate) fn requires_approval(
+        &self,
+        call: &ToolCallInfo,
+        options: &LanguageModelOptions,
+    ) -> bool {
+        match self {
+            Self::Never => false,
+            Self::AlwaysForTools(tools) => tools.contains(&call.tool.name),
+            Self::Dynamic(f) => f(call, &options.messages()),
+            Self::CostThreshold(threshold) => options
+                .estimated_cost()
+                .is_none_or(|cost| cost >= *threshold),
+        }
+    }
+}
+
+impl Debug for ApprovalPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Never => write!(f, "Never"),
+            Self::AlwaysForTools(tools) => f.debug_tuple("AlwaysForTools").field(tools).finish(),
+            Self::Dynamic(_) => write!(f, "Dynamic"),
+            Self::CostThreshold(threshold) => {
+                f.debug_tuple("CostThreshold").field(threshold).finish()
+            }
+        }
+    }
+}
+
+/// A decision on a [`ToolApprovalRequest`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum ToolApproval {
+    /// The tool call is executed.
+    Approved,
+    /// The tool call is not executed, and the model is told it was denied.
+    Denied {
+        /// Why the tool call was denied, passed on to the model.
+        reason: Option<String>,
+    },
+}
+
+impl ToolApproval {
+    /// Creates a denial with the given reason.
+    pub fn denied(reason: impl Into<String>) -> Self {
+        Self::Denied {
+            reason: Some(reason.into()),
+        }
+    }
+}
+
+impl From<bool> for ToolApproval {
+    /// `true` approves the tool call and `false` denies it without a reason.
+    fn from(approved: bool) -> Self {
+        if approved {
+            Self::Approved
+        } else {
+            Self::Denied { reason: None }
+        }
+    }
+}
@@ -47,6 +47,60 @@ pub fn step_count_is(step: usize) -> impl Fn(&LanguageModelOptions) -> bool {
     move |options| options.steps().len() > step
 }
 
+/// A boxed stop condition, as returned by the stop condition helpers.
+///
+/// It can be passed to `stop_when` directly, or combined with [`any`] and [`all`].
+pub type StopCondition = Box<dyn Fn(&LanguageModelOptions) -> bool + Send + Sync>;
+
+/// Creates a stop condition that is met once the given number of steps has run.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use aisdk::core::utils::{any, stop_after_steps, stop_when_tool_called};
+///
+/// // Stop once the agent has finalized its answer, or after 10 steps
+/// let stop = any([stop_after_steps(10), stop_when_tool_called("finalize")]);
+/// ```
+pub fn stop_after_steps(steps: usize) -> StopCondition {
+    Box::new(move |options| options.current_step() >= steps)
+}
+
+/// Creates a stop condition that is met once the input and output tokens used
+/// across all steps reach the given budget.
+///
+/// Only the tokens reported by the provider are counted.
+pub fn stop_after_total_tokens(tokens: usize) -> StopCondition {
+    Box::new(move |options| {
+        let usage = options.usage();
+        usage.input_tokens.unwrap_or_default() + usage.output_tokens.unwrap_or_default() >= tokens
+    })
+}
+
+/// Creates a stop condition that is met once the model has called the tool
+/// with the given name in the current step.
+pub fn stop_when_tool_called(name: impl Into<String>) -> StopCondition {
+    let name = name.into();
+    Box::new(move |options| {
+        options
+            .step(options.current_step())
+            .and_then(|step| step.tool_calls())
+            .is_some_and(|calls| calls.iter().any(|call| call.tool.name == name))
+    })
+}
+
+/// Combines stop conditions into one that is met once any of them is met.
+pub fn any(conditions: impl IntoIterator<Item = StopCondition>) -> StopCondition {
+    let conditions: Vec<StopCondition> = conditions.into_iter().collect();
+    Box::new(move |options| conditions.iter().any(|condition| condition(options)))
+}
+
+/// Combines stop conditions into one that is met once all of them are met.
+pub fn all(conditions: impl IntoIterator<Item = StopCondition>) -> StopCondition {
+    let conditions: Vec<StopCondition> = conditions.into_iter().collect();
+    Box::new(move |options| conditions.iter().all(|condition| condition(options)))
+}
+
 /// Resolves the message to be used for text generation.
 ///
 /// This function takes a prompt and a list of messages and returns a vector of
@@ -99,6 +153,30 @@ pub(crate) fn sum_options(a: Option<usize>, b: Option<usize>) -> Option<usize> {
     }
 }
 
+#[allow(dead_code)]
+/// Returns the value of an environment variable, if it is set and not empty.
+pub(crate) fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+#[allow(dead_code)]
+/// Converts the name of a provider to the prefix of its environment variables,
+/// such as `TogetherAI` to `TOGETHER_AI`.
+pub(crate) fn env_prefix(provider: &str) -> String {
+    let mut prefix = String::with_capacity(provider.len() + 4);
+    let mut previous: Option<char> = None;
+    for c in provider.chars() {
+        if c.is_ascii_uppercase()
+            && previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit())
+        {
+            prefix.push('_');
+        }
+        prefix.push(c.to_ascii_uppercase());
+        previous = Some(c);
+    }
+    prefix
+}
+
 #[allow(dead_code)]
 /// Validates the base URL.
 pub(crate) fn validate_base_url(s: &str) -> crate::error::Result<String> {
@@ -153,10 +231,168 @@ pub(crate) fn join_url(base_url: impl IntoUrl, path: &str) -> Result<Url> {
         .map_err(|_| Error::InvalidInput("Failed to join base URL and path".into()))
 }
 
+/// Parses an incomplete JSON document, such as a structured output that is still streaming.
+///
+/// Open strings, arrays and objects are closed, and a trailing value that cannot be
+/// completed (e.g. an object key without a value, or a partial `true`) is dropped.
+///
+/// # Parameters
+///
+/// * `text` - The JSON text received so far.
+///
+/// # Returns
+///
+/// The value of the completed JSON, or `None` if nothing can be parsed yet.
+///
+/// # Examples
+///
+/// ```rust
+/// use aisdk::core::utils::parse_partial_json;
+///
+/// let value = parse_partial_json(r#"{"name": "Ada", "langs": ["en", "fr"#).unwrap();
+/// assert_eq!(value, serde_json::json!({ "name": "Ada", "langs": ["en", "fr"] }));
+/// ```
+pub fn parse_partial_json(text: &str) -> Option<serde_json::Value> {
+    // Positions where the text can be cut, with the closers needed at that point
+    let mut cuts: Vec<(usize, String)> = Vec::new();
+    let mut stack: Vec<char> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    let closers = |stack: &[char]| stack.iter().rev().collect::<String>();
+
+    for (i, c) in text.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+                cuts.push((i + 1, closers(&stack)));
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => {
+                stack.push(if c == '{' { '}' } else { ']' });
+                cuts.push((i + 1, closers(&stack)));
+            }
+            '}' | ']' => {
+                stack.pop();
+                cuts.push((i + 1, closers(&stack)));
+            }
+            ',' => cuts.push((i, closers(&stack))),
+            _ => {}
+        }
+    }
+
+    let mut end = closers(&stack);
+    if in_string && !escaped {
+        end.insert(0, '"');
+    }
+    cuts.push((text.len(), end));
+
+    cuts.iter().rev().find_map(|(cut, closers)| {
+        serde_json::from_str(&format!("{}{closers}", &text[..*cut])).ok()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_env_prefix() {
+        assert_eq!(env_prefix("Mistral"), "MISTRAL");
+        assert_eq!(env_prefix("TogetherAI"), "TOGETHER_AI");
+        assert_eq!(env_prefix("ZhipuaiCodingPlan"), "ZHIPUAI_CODING_PLAN");
+        assert_eq!(env_prefix("XAI"), "XAI");
+    }
+
+    #[test]
+    fn test_parse_partial_json() {
+        use serde_json::json;
+
+        assert_eq!(parse_partial_json(""), None);
+        assert_eq!(parse_partial_json("{"), Some(json!({})));
+        assert_eq!(parse_partial_json(r#"{"na"#), Some(json!({})));
+        assert_eq!(
+            parse_partial_json(r#"{"name": "Ad"#),
+            Some(json!({ "name": "Ad" }))
+        );
+        assert_eq!(
+            parse_partial_json(r#"{"n": 12, "ok": tr"#),
+            Some(json!({ "n": 12 }))
+        );
+        assert_eq!(
+            parse_partial_json(r#"{"items": [{"a": 1}, {"b": "x\"#),
+            Some(json!({ "items": [{ "a": 1 }, {}] }))
+        );
+        assert_eq!(
+            parse_partial_json(r#"{"a": [1, 2], "b": null}"#),
+            Some(json!({ "a": [1, 2], "b": null }))
+        );
+    }
+
+    #[test]
+    fn test_stop_conditions() {
+        use crate::core::AssistantMessage;
+        use crate::core::language_model::{LanguageModelResponseContentType, Usage};
+        use crate::core::tools::ToolCallInfo;
+
+        let usage = Usage {
+            input_tokens: Some(300),
+            output_tokens: Some(200),
+            ..Default::default()
+        };
+        let options = LanguageModelOptions {
+            current_step_id: 2,
+            messages: vec![
+                TaggedMessage::initial_step_msg(Message::User("Hi".to_string().into())),
+                TaggedMessage::new(
+                    1,
+                    Message::Assistant(AssistantMessage {
+                        content: LanguageModelResponseContentType::ToolCall(ToolCallInfo::new(
+                            "search",
+                        )),
+                        usage: Some(usage.clone()),
+                    }),
+                ),
+                TaggedMessage::new(
+                    2,
+                    Message::Assistant(AssistantMessage {
+                        content: LanguageModelResponseContentType::ToolCall(ToolCallInfo::new(
+                            "finalize",
+                        )),
+                        usage: Some(usage),
+                    }),
+                ),
+            ],
+            ..Default::default()
+        };
+
+        assert!(stop_after_steps(2)(&options));
+        assert!(!stop_after_steps(3)(&options));
+        assert!(stop_after_total_tokens(1000)(&options));
+        assert!(!stop_after_total_tokens(1001)(&options));
+        assert!(stop_when_tool_called("finalize")(&options));
+        assert!(!stop_when_tool_called("search")(&options));
+        assert!(any([
+            stop_after_steps(3),
+            stop_when_tool_called("finalize")
+        ])(&options));
+        assert!(!all([
+            stop_after_steps(3),
+            stop_when_tool_called("finalize")
+        ])(&options));
+        assert!(all([
+            stop_after_steps(1),
+            stop_when_tool_called("finalize")
+        ])(&options));
+    }
+
     #[test]
     fn test_sum_options_both_some() {
         assert_eq!(sum_options(Some(1), Some(2)), Some(3));
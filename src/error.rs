@@ -14,7 +14,9 @@
 //! }
 //! ```
 
+use crate::core::capabilities::Capability;
 use std::sync::Arc;
+use std::time::Duration;
 
 use derive_builder::UninitializedFieldError;
 
@@ -46,6 +48,77 @@ pub enum Error {
         status_code: Option<reqwest::StatusCode>,
     },
 
+    /// The provider rejected the request because a rate limit was exceeded (HTTP 429).
+    #[error("Rate limited (retry after {retry_after:?}): {details}")]
+    RateLimited {
+        /// How long the provider asked to wait before retrying, if it said so.
+        retry_after: Option<Duration>,
+        /// The rate limits reported by the provider in the response headers.
+        limit_info: Box<RateLimitInfo>,
+        /// The error details/message.
+        details: String,
+    },
+
+    /// The API key is missing, invalid or not allowed to use the requested resource (HTTP 401/403).
+    #[error("Authentication error: {status_code:?} - {details}")]
+    AuthenticationError {
+        /// The error message returned by the provider.
+        details: String,
+        /// The HTTP status code, if available.
+        status_code: Option<reqwest::StatusCode>,
+    },
+
+    /// The provider rejected the request as malformed or unsupported (HTTP 400/413/422).
+    #[error("Invalid request: {status_code:?} - {details}")]
+    InvalidRequest {
+        /// The error message returned by the provider.
+        details: String,
+        /// The HTTP status code, if available.
+        status_code: Option<reqwest::StatusCode>,
+    },
+
+    /// The requested model does not exist or is not available to this account (HTTP 404).
+    #[error("Model not found: {status_code:?} - {details}")]
+    ModelNotFound {
+        /// The error message returned by the provider.
+        details: String,
+        /// The HTTP status code, if available.
+        status_code: Option<reqwest::StatusCode>,
+    },
+
+    /// The request or response was blocked by the provider's content policy.
+    #[error("Content filtered: {status_code:?} - {details}")]
+    ContentFiltered {
+        /// The error message returned by the provider.
+        details: String,
+        /// The HTTP status code, if available.
+        status_code: Option<reqwest::StatusCode>,
+    },
+
+    /// The provider is temporarily overloaded (e.g. HTTP 503/529). Retrying later may succeed.
+    #[error("Provider overloaded: {status_code:?} - {details}")]
+    Overloaded {
+        /// The error message returned by the provider.
+        details: String,
+        /// The HTTP status code, if available.
+        status_code: Option<reqwest::StatusCode>,
+    },
+
+    /// The account has run out of credits or exceeded its usage quota. Unlike
+    /// [`Error::RateLimited`], waiting does not help.
+    #[error("Quota exceeded: {status_code:?} - {details}")]
+    QuotaExceeded {
+        /// The error message returned by the provider.
+        details: String,
+        /// The HTTP status code, if available.
+        status_code: Option<reqwest::StatusCode>,
+    },
+
+    /// The request failed without a complete response, such as on a timeout or a
+    /// dropped connection. Retrying may succeed.
+    #[error("Transport error: {0}")]
+    TransportError(String),
+
     /// An error for invalid input.
     #[error("Invalid input: {0}")]
     InvalidInput(String),
@@ -66,6 +139,132 @@ pub enum Error {
     /// Provider-specific error.
     #[error("Provider error: {0}")]
     ProviderError(Arc<dyn ProviderError>),
+
+    /// A prompt or a response was rejected by a guard of the request.
+    #[error("Guardrail violation: {0}")]
+    GuardrailViolation(GuardrailViolation),
+
+    /// A request was configured in a way that can only fail, with every problem found.
+    #[error("Invalid configuration: {}", join_issues(.0))]
+    InvalidConfiguration(Vec<ValidationIssue>),
+}
+
+/// A problem found when validating the configuration of a request.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ValidationIssue {
+    /// The request has neither a prompt nor messages.
+    #[error("the request has no prompt and no messages")]
+    EmptyConversation,
+    /// An option requires a capability the model does not have.
+    #[error("`{option}` requires {capability:?} support, which the model does not have")]
+    UnsupportedCapability {
+        /// The option that was set.
+        option: &'static str,
+        /// The capability it requires.
+        capability: Capability,
+    },
+    /// An option is outside of the range accepted by all providers.
+    #[error("`{option}` must be between {min} and {max}, got {value}")]
+    OutOfRange {
+        /// The option that was set.
+        option: &'static str,
+        /// The value it was set to.
+        value: f32,
+        /// The smallest accepted value.
+        min: f32,
+        /// The largest accepted value.
+        max: f32,
+    },
+}
+
+fn join_issues(issues: &[ValidationIssue]) -> String {
+    issues
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// The step of a request at which a guard runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardrailStage {
+    /// The prompt, before it is sent to the model.
+    Input,
+    /// A text response of the model.
+    Output,
+}
+
+/// The rejection of a prompt or a response by a guard.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{stage:?} rejected: {reason}")]
+pub struct GuardrailViolation {
+    /// Whether the prompt or a response was rejected.
+    pub stage: GuardrailStage,
+    /// The reason given by the guard.
+    pub reason: String,
+}
+
+/// Rate limit information reported by a provider in its response headers.
+///
+/// Both the OpenAI style `x-ratelimit-*` headers and the Anthropic style
+/// `anthropic-ratelimit-*` headers are recognized. Fields are `None` when the
+/// provider did not send the corresponding header.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    /// The maximum number of requests allowed in the current window.
+    pub limit_requests: Option<u64>,
+    /// The number of requests remaining in the current window.
+    pub remaining_requests: Option<u64>,
+    /// When the request limit resets, as reported by the provider
+    /// (e.g. `1s` for OpenAI or an RFC 3339 timestamp for Anthropic).
+    pub reset_requests: Option<String>,
+    /// The maximum number of tokens allowed in the current window.
+    pub limit_tokens: Option<u64>,
+    /// The number of tokens remaining in the current window.
+    pub remaining_tokens: Option<u64>,
+    /// When the token limit resets, as reported by the provider.
+    pub reset_tokens: Option<String>,
+}
+
+impl RateLimitInfo {
+    /// Reads the rate limit headers of a response.
+    pub(crate) fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {
+        let get = |names: [&str; 2]| {
+            names
+                .iter()
+                .find_map(|name| headers.get(*name))
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let number = |names: [&str; 2]| get(names).and_then(|v| v.parse().ok());
+
+        Self {
+            limit_requests: number([
+                "x-ratelimit-limit-requests",
+                "anthropic-ratelimit-requests-limit",
+            ]),
+            remaining_requests: number([
+                "x-ratelimit-remaining-requests",
+                "anthropic-ratelimit-requests-remaining",
+            ]),
+            reset_requests: get([
+                "x-ratelimit-reset-requests",
+                "anthropic-ratelimit-requests-reset",
+            ]),
+            limit_tokens: number([
+                "x-ratelimit-limit-tokens",
+                "anthropic-ratelimit-tokens-limit",
+            ]),
+            remaining_tokens: number([
+                "x-ratelimit-remaining-tokens",
+                "anthropic-ratelimit-tokens-remaining",
+            ]),
+            reset_tokens: get([
+                "x-ratelimit-reset-tokens",
+                "anthropic-ratelimit-tokens-reset",
+            ]),
+        }
+    }
 }
 
 /// Implements `From` for `UninitializedFieldError` to convert it to `Error`.
@@ -86,11 +285,63 @@ impl From<Error> for String {
             } => {
                 format!("API error: {status_code:?} - {details}")
             }
+            Error::RateLimited {
+                retry_after,
+                details,
+                ..
+            } => format!("Rate limited (retry after {retry_after:?}): {details}"),
+            Error::AuthenticationError {
+                details,
+                status_code,
+            } => format!("Authentication error: {status_code:?} - {details}"),
+            Error::InvalidRequest {
+                details,
+                status_code,
+            } => format!("Invalid request: {status_code:?} - {details}"),
+            Error::ModelNotFound {
+                details,
+                status_code,
+            } => format!("Model not found: {status_code:?} - {details}"),
+            Error::ContentFiltered {
+                details,
+                status_code,
+            } => format!("Content filtered: {status_code:?} - {details}"),
+            Error::Overloaded {
+                details,
+                status_code,
+            } => format!("Provider overloaded: {status_code:?} - {details}"),
+            Error::QuotaExceeded {
+                details,
+                status_code,
+            } => format!("Quota exceeded: {status_code:?} - {details}"),
+            Error::TransportError(error) => format!("Transport error: {error}"),
             Error::InvalidInput(error) => format!("Invalid input: {error}"),
             Error::ToolCallError(error) => format!("Tool error: {error}"),
             Error::Other(error) => format!("Other error: {error}"),
             Error::ProviderError(error) => format!("Provider error: {error}"),
             Error::PromptError(error) => format!("Prompt error: {error}"),
+            Error::GuardrailViolation(violation) => format!("Guardrail violation: {violation}"),
+            Error::InvalidConfiguration(issues) => {
+                format!("Invalid configuration: {}", join_issues(&issues))
+            }
+        }
+    }
+}
+
+impl Error {
+    /// Returns `true` if the error is transient and the request may succeed when retried.
+    ///
+    /// This covers rate limits (429), overloaded providers, server errors (5xx) and
+    /// [transport errors](Error::TransportError), such as timeouts and connection
+    /// errors. Responses that could not be parsed are not retried.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::RateLimited { .. } | Error::Overloaded { .. } | Error::TransportError(_) => true,
+            Error::ApiError {
+                status_code: Some(status),
+                ..
+            } => *status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error(),
+            _ => false,
         }
     }
 }
@@ -4,6 +4,9 @@
 use parking_lot::{
     MappedRwLockReadGuard, MappedRwLockWriteGuard, RwLock, RwLockReadGuard, RwLockWriteGuard,
 };
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -48,6 +51,77 @@ impl Extensions {
     }
 }
 
+/// Metadata that is kept when [`Extensions`] are serialized.
+///
+/// Only types listed in [`PERSISTED`] are written out; everything else is
+/// considered transient and dropped.
+#[allow(dead_code)]
+pub(crate) trait PersistedExtension:
+    Serialize + DeserializeOwned + Default + Send + Sync + 'static
+{
+    /// A stable key identifying the metadata in serialized form.
+    const KEY: &'static str;
+}
+
+/// Serialization functions for a single persisted metadata type.
+struct Codec {
+    key: &'static str,
+    save: fn(&Extensions) -> Option<Value>,
+    load: fn(&Extensions, Value) -> serde_json::Result<()>,
+}
+
+#[allow(dead_code)]
+const fn codec<T: PersistedExtension>() -> Codec {
+    Codec {
+        key: T::KEY,
+        save: |ext| {
+            let map = ext.map.read();
+            let value = map.get(&TypeId::of::<T>())?.downcast_ref::<T>()?;
+            serde_json::to_value(value).ok()
+        },
+        load: |ext, value| {
+            ext.insert(serde_json::from_value::<T>(value)?);
+            Ok(())
+        },
+    }
+}
+
+/// Metadata types that survive serialization.
+const PERSISTED: &[Codec] = &[
+    #[cfg(feature = "anthropic")]
+    codec::<crate::providers::anthropic::extensions::AnthropicThinkingMetadata>(),
+    #[cfg(feature = "openai")]
+    codec::<crate::providers::openai::extensions::OpenAIItemMetadata>(),
+    #[cfg(feature = "google")]
+    codec::<crate::providers::google::extensions::GoogleToolMetadata>(),
+    #[cfg(feature = "amazon-bedrock")]
+    codec::<crate::providers::amazon_bedrock::extensions::BedrockReasoningMetadata>(),
+];
+
+impl Serialize for Extensions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PERSISTED
+            .iter()
+            .filter_map(|codec| Some((codec.key.to_string(), (codec.save)(self)?)))
+            .collect::<Map<_, _>>()
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Extensions {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut values = Map::deserialize(deserializer)?;
+        let extensions = Extensions::default();
+        // Unknown keys belong to providers that are not enabled and are ignored
+        for codec in PERSISTED {
+            if let Some(value) = values.remove(codec.key) {
+                (codec.load)(&extensions, value).map_err(serde::de::Error::custom)?;
+            }
+        }
+        Ok(extensions)
+    }
+}
+
 impl std::fmt::Debug for Extensions {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Extensions").finish()
@@ -1,8 +1,41 @@
 //! Integration with Axum.
+//!
+//! # Examples
+//!
+//! A chat endpoint for Vercel's `useChat` hook:
+//!
+//! ```rust,no_run
+//!# #[cfg(all(feature = "axum", feature = "openai"))]
+//!# {
+//! use aisdk::core::LanguageModelRequest;
+//! use aisdk::integrations::axum::ui_stream_response;
+//! use aisdk::integrations::vercel_aisdk_ui::VercelUIRequest;
+//! use aisdk::providers::OpenAI;
+//! use axum::response::IntoResponse;
+//!
+//! async fn chat(request: VercelUIRequest) -> impl IntoResponse {
+//!     let response = LanguageModelRequest::builder()
+//!         .model(OpenAI::gpt_5())
+//!         .messages(request.into())
+//!         .build()
+//!         .stream_text()
+//!         .await
+//!         .unwrap();
+//!
+//!     ui_stream_response(response)
+//! }
+//!# }
+//! ```
 
-use crate::integrations::vercel_aisdk_ui::VercelUIStreamBuilder;
-use axum::response::Sse;
+pub use crate::integrations::vercel_aisdk_ui::UI_MESSAGE_STREAM_HEADER;
+use crate::integrations::vercel_aisdk_ui::{
+    VercelUIRequest, VercelUIStream, VercelUIStreamBuilder,
+};
+use axum::Json;
+use axum::extract::{FromRequest, Request, rejection::JsonRejection};
+use axum::http::HeaderName;
 use axum::response::sse::{Event, KeepAliveStream};
+use axum::response::{IntoResponse, Sse};
 use futures::StreamExt;
 
 /// Type alias for the Axum SSE response with boxed stream for trait implementations.
@@ -18,12 +51,70 @@ impl From<crate::core::StreamTextResponse> for AxumSseResponse {
         response
             .to_axum_vercel_ui_stream()
             .send_reasoning()
+            .send_sources()
             .send_start()
             .send_finish()
             .build()
     }
 }
 
+/// Creates a Vercel AI SDK UI compatible SSE response from a `StreamTextResponse`.
+///
+/// The response sends start, reasoning and finish chunks, and sets the
+/// [`UI_MESSAGE_STREAM_HEADER`] expected by the `useChat` hook. Use
+/// [`to_axum_vercel_ui_stream`](crate::core::StreamTextResponse::to_axum_vercel_ui_stream)
+/// to configure the stream instead.
+///
+/// # Parameters
+/// - `response`: The streaming response to send to the client.
+///
+/// # Returns
+/// A response that can be returned from an Axum handler.
+pub fn ui_stream_response(response: crate::core::StreamTextResponse) -> impl IntoResponse {
+    (
+        [(HeaderName::from_static(UI_MESSAGE_STREAM_HEADER), "v1")],
+        AxumSseResponse::from(response),
+    )
+}
+
+/// Creates an SSE response that streams a structured output as it is generated.
+///
+/// Each event holds the partial object as JSON, see
+/// [`into_partial_object_stream`](crate::core::StreamTextResponse::into_partial_object_stream).
+/// A failed generation ends the stream with an `error` event.
+///
+/// # Parameters
+/// - `response`: The streaming response of a request with a schema.
+///
+/// # Returns
+/// A response that can be returned from an Axum handler.
+pub fn object_stream_response(response: crate::core::StreamTextResponse) -> AxumSseResponse {
+    let stream = response
+        .into_partial_object_stream()
+        .map(|result| match result {
+            Ok(value) => Ok(Event::default().data(value.to_string())),
+            Err(e) => Ok(Event::default().event("error").data(e.to_string())),
+        });
+
+    Sse::new(Box::pin(stream) as _).keep_alive(axum::response::sse::KeepAlive::new())
+}
+
+/// Extracts a [`VercelUIRequest`] from a JSON request body.
+///
+/// Convert the request into core messages with `.into()` or
+/// [`Message::from_vercel_ui_message`](crate::core::Message::from_vercel_ui_message).
+impl<S> FromRequest<S> for VercelUIRequest
+where
+    S: Send + Sync,
+{
+    type Rejection = JsonRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(request) = Json::<VercelUIRequest>::from_request(req, state).await?;
+        Ok(request)
+    }
+}
+
 impl crate::core::StreamTextResponse {
     /// Creates a builder for configuring a Vercel AI SDK UI compatible stream response from this `StreamTextResponse`.
     ///
@@ -50,6 +141,7 @@ impl crate::core::StreamTextResponse {
             let ui_stream = context.into_vercel_ui_stream(options);
 
             let mapped_stream = ui_stream.map(|result| match result {
+                Ok(VercelUIStream::Ping) => Ok(Event::default().comment("ping")),
                 Ok(chunk) => {
                     let json = serde_json::to_string(&chunk).map_err(|e| {
                         crate::error::Error::Other(format!("JSON serialization error: {e}"))
@@ -68,3 +160,32 @@ impl crate::core::StreamTextResponse {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+
+    #[tokio::test]
+    async fn test_vercel_ui_request_extractor() {
+        let body = serde_json::json!({
+            "id": "chat_1",
+            "trigger": "submit-message",
+            "messages": [{ "id": "1", "role": "user", "parts": [{ "type": "text", "text": "Hi" }] }]
+        });
+        let req = Request::builder()
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        let request = VercelUIRequest::from_request(req, &()).await.unwrap();
+        assert_eq!(request.id, "chat_1");
+        assert_eq!(request.messages[0].parts[0].text, "Hi");
+
+        let req = Request::builder()
+            .header("content-type", "application/json")
+            .body(Body::from("{}"))
+            .unwrap();
+        assert!(VercelUIRequest::from_request(req, &()).await.is_err());
+    }
+}
@@ -2,4 +2,9 @@
 
 #[cfg(feature = "axum")]
 pub mod axum;
+#[cfg(feature = "language-model-request")]
+pub mod openai_server;
+pub mod resumable_stream;
+#[cfg(feature = "tower")]
+pub mod tower;
 pub mod vercel_aisdk_ui;
@@ -0,0 +1,602 @@
+//! OpenAI compatible server.
+//!
+//! [`OpenAIServer`] answers OpenAI Chat Completions requests with any
+//! [`LanguageModel`], so existing OpenAI clients and SDKs can point at an aisdk
+//! backend. Tools added to the server are executed on the server, and the client
+//! receives the final answer. Streaming requests are answered with server-sent
+//! events in the Chat Completions chunk format.
+//!
+//! The server is independent of the HTTP framework: deserialize the request body
+//! into a [`ChatCompletionRequest`], and send the [`ChatCompletionReply`] as JSON
+//! or as an event stream.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(all(feature = "language-model-request", feature = "openai"))]
+//!# {
+//! use aisdk::integrations::openai_server::{ChatCompletionReply, ChatCompletionRequest, OpenAIServer};
+//! use aisdk::providers::OpenAI;
+//!
+//! # fn get_weather() -> aisdk::core::Tool { unimplemented!() }
+//! # async fn run(request: ChatCompletionRequest) -> aisdk::Result<()> {
+//! let server = OpenAIServer::new(OpenAI::gpt_5()).with_tool(get_weather());
+//!
+//! // POST /v1/chat/completions
+//! match server.chat_completions(request).await? {
+//!     ChatCompletionReply::Completion(completion) => { /* respond with JSON */ }
+//!     ChatCompletionReply::Stream(body) => { /* respond with text/event-stream */ }
+//! }
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+use crate::core::capabilities::{TextInputSupport, ToolCallSupport};
+use crate::core::ids::generate_id;
+use crate::core::language_model::{
+    LanguageModel, LanguageModelResponseContentType, StopReason, Usage,
+    request::LanguageModelRequest,
+};
+use crate::core::messages::{AssistantMessage, Message, Messages, SystemMessage, UserMessage};
+use crate::core::tools::Tool;
+use crate::core::{LanguageModelStreamChunkType, StreamTextResponse};
+use crate::error::{Error, Result};
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Type alias for a response body of server-sent events.
+pub type SseStream = Pin<Box<dyn Stream<Item = Result<Bytes>> + Send + 'static>>;
+
+/// A Chat Completions request, as sent by OpenAI clients.
+///
+/// Parameters that are not listed are ignored.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChatCompletionRequest {
+    /// The model requested by the client, echoed in the response.
+    #[serde(default)]
+    pub model: String,
+    /// The conversation.
+    pub messages: Vec<ChatMessage>,
+    /// Whether to stream the response.
+    #[serde(default)]
+    pub stream: bool,
+    /// Sampling temperature, between 0 and 2.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Nucleus sampling probability, between 0 and 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Maximum number of tokens to generate.
+    #[serde(
+        alias = "max_completion_tokens",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_tokens: Option<u32>,
+    /// Sequences that stop the generation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<StopSequences>,
+    /// Seed for deterministic sampling.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u32>,
+    /// Penalty for tokens that already appeared, between -2 and 2.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    /// Penalty for frequent tokens, between -2 and 2.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+}
+
+/// One or several stop sequences.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StopSequences {
+    /// A single stop sequence.
+    One(String),
+    /// Several stop sequences.
+    Many(Vec<String>),
+}
+
+impl From<StopSequences> for Vec<String> {
+    fn from(value: StopSequences) -> Self {
+        match value {
+            StopSequences::One(stop) => vec![stop],
+            StopSequences::Many(stops) => stops,
+        }
+    }
+}
+
+/// A message of a Chat Completions request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// The role: `system`, `developer`, `user` or `assistant`.
+    pub role: String,
+    /// The content of the message.
+    #[serde(default)]
+    pub content: Option<ChatContent>,
+}
+
+/// The content of a message, as text or as parts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ChatContent {
+    /// Text content.
+    Text(String),
+    /// Content parts, of which the text parts are used.
+    Parts(Vec<ChatContentPart>),
+}
+
+/// A part of the content of a message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatContentPart {
+    /// The type of the part, such as `text`.
+    #[serde(rename = "type")]
+    pub part_type: String,
+    /// The text of a text part.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl ChatContent {
+    /// Returns the text of the content.
+    pub fn text(&self) -> String {
+        match self {
+            ChatContent::Text(text) => text.clone(),
+            ChatContent::Parts(parts) => parts
+                .iter()
+                .filter(|part| part.part_type == "text")
+                .filter_map(|part| part.text.as_deref())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+/// A Chat Completions response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatCompletion {
+    /// The ID of the completion.
+    pub id: String,
+    /// Always `chat.completion`.
+    pub object: String,
+    /// The Unix timestamp of the completion, in seconds.
+    pub created: u64,
+    /// The model requested by the client.
+    pub model: String,
+    /// The generated answers.
+    pub choices: Vec<ChatCompletionChoice>,
+    /// The token usage of the request.
+    pub usage: ChatCompletionUsage,
+}
+
+/// A generated answer of a [`ChatCompletion`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatCompletionChoice {
+    /// The index of the answer.
+    pub index: u32,
+    /// The answer.
+    pub message: ChatCompletionMessage,
+    /// Why the generation stopped: `stop` or `content_filter`.
+    pub finish_reason: String,
+}
+
+/// The message of a [`ChatCompletionChoice`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatCompletionMessage {
+    /// Always `assistant`.
+    pub role: String,
+    /// The text of the answer.
+    pub content: Option<String>,
+}
+
+/// The token usage of a Chat Completions request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChatCompletionUsage {
+    /// Number of input tokens.
+    pub prompt_tokens: usize,
+    /// Number of generated tokens.
+    pub completion_tokens: usize,
+    /// Total number of tokens.
+    pub total_tokens: usize,
+}
+
+impl From<Usage> for ChatCompletionUsage {
+    fn from(usage: Usage) -> Self {
+        let prompt_tokens = usage.input_tokens.unwrap_or(0);
+        let completion_tokens = usage.output_tokens.unwrap_or(0);
+        Self {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+        }
+    }
+}
+
+/// A chunk of a streamed Chat Completions response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatCompletionChunk {
+    /// The ID of the completion, the same for every chunk.
+    pub id: String,
+    /// Always `chat.completion.chunk`.
+    pub object: String,
+    /// The Unix timestamp of the completion, in seconds.
+    pub created: u64,
+    /// The model requested by the client.
+    pub model: String,
+    /// The deltas of the answer.
+    pub choices: Vec<ChatCompletionChunkChoice>,
+    /// The token usage of the request, sent with the last chunk.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<ChatCompletionUsage>,
+}
+
+/// A delta of the answer in a [`ChatCompletionChunk`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatCompletionChunkChoice {
+    /// The index of the answer.
+    pub index: u32,
+    /// The new part of the answer.
+    pub delta: ChatCompletionDelta,
+    /// Why the generation stopped, set on the last chunk.
+    pub finish_reason: Option<String>,
+}
+
+/// The new part of an answer.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChatCompletionDelta {
+    /// `assistant` on the first chunk.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// New text of the answer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+/// The reply to a Chat Completions request.
+pub enum ChatCompletionReply {
+    /// The completion of a request without streaming, sent as JSON.
+    Completion(ChatCompletion),
+    /// The body of a streaming response, sent with the `text/event-stream` content type.
+    Stream(SseStream),
+}
+
+/// Answers OpenAI Chat Completions requests with a language model.
+#[derive(Clone)]
+pub struct OpenAIServer<M: LanguageModel> {
+    model: M,
+    tools: Vec<Tool>,
+}
+
+impl<M: LanguageModel + TextInputSupport> OpenAIServer<M> {
+    /// Creates a server answering with the given model.
+    pub fn new(model: M) -> Self {
+        Self {
+            model,
+            tools: Vec::new(),
+        }
+    }
+
+    /// Adds a tool the model can call. Tools are executed on the server.
+    pub fn with_tool(mut self, tool: Tool) -> Self
+    where
+        M: ToolCallSupport,
+    {
+        self.tools.push(tool);
+        self
+    }
+
+    /// Answers a Chat Completions request, streaming if the request asks for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if the request is invalid, or if the generation fails
+    /// before it starts streaming.
+    pub async fn chat_completions(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionReply> {
+        if request.stream {
+            self.stream(request).await.map(ChatCompletionReply::Stream)
+        } else {
+            self.complete(request)
+                .await
+                .map(ChatCompletionReply::Completion)
+        }
+    }
+
+    /// Answers a Chat Completions request without streaming.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if the request is invalid or the generation fails.
+    pub async fn complete(&self, request: ChatCompletionRequest) -> Result<ChatCompletion> {
+        let model = request.model.clone();
+        let response = self.request(request)?.generate_text().await?;
+
+        Ok(ChatCompletion {
+            id: completion_id(),
+            object: "chat.completion".to_string(),
+            created: now(),
+            model,
+            choices: vec![ChatCompletionChoice {
+                index: 0,
+                message: ChatCompletionMessage {
+                    role: "assistant".to_string(),
+                    content: response.text(),
+                },
+                finish_reason: finish_reason(response.stop_reason()).to_string(),
+            }],
+            usage: response.usage().into(),
+        })
+    }
+
+    /// Answers a Chat Completions request with a stream of server-sent events.
+    ///
+    /// The stream ends with `data: [DONE]`. A generation that fails while
+    /// streaming sends an `error` object before it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if the request is invalid or the generation can not start.
+    pub async fn stream(&self, request: ChatCompletionRequest) -> Result<SseStream> {
+        let model = request.model.clone();
+        let response = self.request(request)?.stream_text().await?;
+        Ok(Box::pin(completion_chunks(response, model)))
+    }
+
+    /// Creates the language model request of a Chat Completions request.
+    fn request(&self, request: ChatCompletionRequest) -> Result<LanguageModelRequest<M>> {
+        let messages = request
+            .messages
+            .into_iter()
+            .map(to_message)
+            .collect::<Result<Messages>>()?;
+
+        let mut builder = LanguageModelRequest::builder()
+            .model(self.model.clone())
+            .messages(messages);
+        builder.temperature = request.temperature;
+        builder.top_p = request.top_p;
+        builder.max_output_tokens = request.max_tokens;
+        builder.stop_sequences = request.stop.map(Vec::from);
+        builder.seed = request.seed;
+        builder.presence_penalty = request.presence_penalty;
+        builder.frequency_penalty = request.frequency_penalty;
+        for tool in &self.tools {
+            builder.tools.get_or_insert_default().add_tool(tool.clone());
+        }
+        builder.validate_penalties(-2.0..=2.0)?;
+
+        Ok(builder.build())
+    }
+}
+
+/// Converts a message of a Chat Completions request.
+fn to_message(message: ChatMessage) -> Result<Message> {
+    let text = message.content.map(|c| c.text()).unwrap_or_default();
+    match message.role.as_str() {
+        "system" | "developer" => Ok(Message::System(SystemMessage::new(text))),
+        "user" => Ok(Message::User(UserMessage::new(text))),
+        "assistant" => Ok(Message::Assistant(AssistantMessage::new(
+            LanguageModelResponseContentType::Text(text),
+            None,
+        ))),
+        role => Err(Error::InvalidInput(format!(
+            "Unsupported message role `{role}`, tools are executed on the server"
+        ))),
+    }
+}
+
+/// Converts a response stream to Chat Completions chunks framed as server-sent events.
+fn completion_chunks(
+    response: StreamTextResponse,
+    model: String,
+) -> impl Stream<Item = Result<Bytes>> + Send + 'static {
+    let chunk = {
+        let id = completion_id();
+        let created = now();
+        move |delta: ChatCompletionDelta, finish_reason: Option<&str>, usage| {
+            let chunk = ChatCompletionChunk {
+                id: id.clone(),
+                object: "chat.completion.chunk".to_string(),
+                created,
+                model: model.clone(),
+                choices: vec![ChatCompletionChunkChoice {
+                    index: 0,
+                    delta,
+                    finish_reason: finish_reason.map(str::to_string),
+                }],
+                usage,
+            };
+            event(&chunk)
+        }
+    };
+
+    let first = chunk(
+        ChatCompletionDelta {
+            role: Some("assistant".to_string()),
+            content: None,
+        },
+        None,
+        None,
+    );
+    let options = response.options.clone();
+    let last_chunk = chunk.clone();
+    let body = response.stream.filter_map(move |c| {
+        let event = match c {
+            LanguageModelStreamChunkType::Text(text) => Some(chunk(
+                ChatCompletionDelta {
+                    content: Some(text),
+                    ..Default::default()
+                },
+                None,
+                None,
+            )),
+            LanguageModelStreamChunkType::Failed(error) => Some(event(&serde_json::json!({
+                "error": { "message": error, "type": "server_error" }
+            }))),
+            LanguageModelStreamChunkType::Ping => Some(Ok(Bytes::from_static(b": ping\n\n"))),
+            _ => None,
+        };
+        futures::future::ready(event)
+    });
+    let last = futures::stream::once(async move {
+        let options = options.lock().await;
+        let usage = options.usage();
+        match options.stop_reason() {
+            Some(StopReason::Error(_)) => None,
+            reason => Some(last_chunk(
+                ChatCompletionDelta::default(),
+                Some(finish_reason(reason)),
+                Some(usage.into()),
+            )),
+        }
+    })
+    .filter_map(futures::future::ready);
+    let done = futures::stream::once(futures::future::ready(Ok(Bytes::from_static(
+        b"data: [DONE]\n\n",
+    ))));
+
+    futures::stream::once(futures::future::ready(first))
+        .chain(body)
+        .chain(last)
+        .chain(done)
+}
+
+/// Frames a value as a server-sent event.
+fn event<T: Serialize>(value: &T) -> Result<Bytes> {
+    let json = serde_json::to_string(value)
+        .map_err(|e| Error::Other(format!("JSON serialization error: {e}")))?;
+    Ok(Bytes::from(format!("data: {json}\n\n")))
+}
+
+/// Returns the Chat Completions finish reason of a stop reason.
+fn finish_reason(reason: Option<StopReason>) -> &'static str {
+    match reason {
+        Some(StopReason::Guardrail(_)) => "content_filter",
+        _ => "stop",
+    }
+}
+
+/// Returns a new completion ID.
+fn completion_id() -> String {
+    format!("chatcmpl-{}", generate_id(""))
+}
+
+/// Returns the current Unix timestamp, in seconds.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::language_model::LanguageModelResponse;
+    use crate::providers::MockLanguageModel;
+
+    /// Answers with the prompt of [`request`], counting its words as output tokens.
+    fn echo_model() -> MockLanguageModel {
+        let mut response = LanguageModelResponse::new("hello world END");
+        response.usage = Some(Usage {
+            input_tokens: Some(1),
+            output_tokens: Some(3),
+            ..Default::default()
+        });
+        MockLanguageModel::new().with_response(response)
+    }
+
+    fn request(stream: bool) -> ChatCompletionRequest {
+        serde_json::from_value(serde_json::json!({
+            "model": "echo-1",
+            "stream": stream,
+            "temperature": 0.7,
+            "stop": "END",
+            "messages": [
+                { "role": "system", "content": "Repeat after me." },
+                { "role": "user", "content": [{ "type": "text", "text": "hello world END" }] }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_without_streaming() {
+        let model = echo_model();
+        let server = OpenAIServer::new(model.clone());
+        let ChatCompletionReply::Completion(completion) =
+            server.chat_completions(request(false)).await.unwrap()
+        else {
+            panic!("Expected a completion");
+        };
+
+        assert_eq!(completion.model, "echo-1");
+        assert_eq!(completion.object, "chat.completion");
+        assert_eq!(
+            completion.choices[0].message.content.as_deref(),
+            Some("hello world ")
+        );
+        assert_eq!(completion.choices[0].finish_reason, "stop");
+        assert_eq!(completion.usage.total_tokens, 4);
+
+        let options = &model.calls()[0];
+        assert_eq!(options.temperature, Some(0.7));
+        assert!(matches!(
+            options.messages().as_slice(),
+            [Message::System(_), Message::User(u)] if u.content == "hello world END"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_with_streaming() {
+        let server = OpenAIServer::new(echo_model());
+        let body: Vec<u8> = server
+            .stream(request(true))
+            .await
+            .unwrap()
+            .map(|bytes| bytes.unwrap().to_vec())
+            .concat()
+            .await;
+        let body = String::from_utf8(body).unwrap();
+
+        let events: Vec<&str> = body
+            .split("\n\n")
+            .filter(|e| !e.is_empty())
+            .map(|e| e.strip_prefix("data: ").unwrap())
+            .collect();
+        assert_eq!(events.last(), Some(&"[DONE]"));
+
+        let chunks: Vec<ChatCompletionChunk> = events[..events.len() - 1]
+            .iter()
+            .map(|e| serde_json::from_str(e).unwrap())
+            .collect();
+        assert_eq!(
+            chunks[0].choices[0].delta.role.as_deref(),
+            Some("assistant")
+        );
+        let text: String = chunks
+            .iter()
+            .filter_map(|c| c.choices[0].delta.content.clone())
+            .collect();
+        assert_eq!(text, "hello world ");
+        let last = chunks.last().unwrap();
+        assert_eq!(last.choices[0].finish_reason.as_deref(), Some("stop"));
+        assert_eq!(last.usage.unwrap().completion_tokens, 3);
+        assert!(chunks.iter().all(|c| c.id == chunks[0].id));
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_rejects_tool_messages() {
+        let mut request = request(false);
+        request.messages.push(ChatMessage {
+            role: "tool".to_string(),
+            content: None,
+        });
+        let result = OpenAIServer::new(echo_model()).complete(request).await;
+        assert!(matches!(result, Err(Error::InvalidInput(_))));
+    }
+}
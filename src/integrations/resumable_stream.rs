@@ -0,0 +1,324 @@
+//! Resumable UI message streams.
+//!
+//! A client that loses its connection in the middle of a generation normally loses
+//! the rest of the response. With a [`UIStreamStore`], the chunks of a UI message
+//! stream are buffered by message ID while the generation keeps running in the
+//! background, so the client can reconnect and [`resume`] from the last chunk it
+//! received.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "language-model-request")]
+//!# {
+//! use aisdk::integrations::resumable_stream::{InMemoryUIStreamStore, resume};
+//! use aisdk::integrations::vercel_aisdk_ui::VercelUIStreamOptions;
+//! use std::sync::Arc;
+//!
+//! # async fn run(response: aisdk::core::StreamTextResponse) {
+//! let store = Arc::new(InMemoryUIStreamStore::new());
+//!
+//! // Start the generation. It keeps running if the client disconnects.
+//! let (message_id, stream) = response.into_resumable_vercel_ui_stream(
+//!     VercelUIStreamOptions::default(),
+//!     store.clone(),
+//! );
+//!
+//! // After reconnecting, continue after the 10 chunks the client already received
+//! let stream = resume(store, message_id, 10).await;
+//! # }
+//!# }
+//! ```
+
+use crate::core::ids::generate_id;
+use crate::integrations::vercel_aisdk_ui::VercelUIStream;
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Chunks of a stored UI message stream.
+#[derive(Debug, Clone, Default)]
+pub struct StoredUIStream {
+    /// The chunks, starting at the requested index.
+    pub chunks: Vec<VercelUIStream>,
+    /// Whether the stream has finished, so no more chunks will be appended.
+    pub finished: bool,
+}
+
+/// Storage backend that buffers UI message stream chunks by message ID.
+#[async_trait]
+pub trait UIStreamStore: Send + Sync + std::fmt::Debug {
+    /// Appends a chunk to the stream of the message.
+    async fn append(&self, message_id: &str, chunk: VercelUIStream);
+
+    /// Marks the stream of the message as finished.
+    async fn finish(&self, message_id: &str);
+
+    /// Returns the chunks of the message starting at index `from`, or `None` if
+    /// the message is unknown.
+    async fn read(&self, message_id: &str, from: usize) -> Option<StoredUIStream>;
+
+    /// Waits until the stream of the message has more than `len` chunks or has finished.
+    ///
+    /// The default implementation polls [`read`](Self::read), so stores that can be
+    /// notified of new chunks should override it.
+    async fn wait(&self, message_id: &str, len: usize) {
+        loop {
+            match self.read(message_id, len).await {
+                Some(stored) if stored.chunks.is_empty() && !stored.finished => {
+                    crate::core::runtime::sleep(Duration::from_millis(100)).await;
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Removes the stream of the message.
+    async fn remove(&self, message_id: &str);
+}
+
+/// An in-memory [`UIStreamStore`].
+///
+/// Streams are kept until they are removed with [`UIStreamStore::remove`].
+#[derive(Debug, Default)]
+pub struct InMemoryUIStreamStore {
+    streams: Mutex<HashMap<String, StoredUIStream>>,
+    notify: Notify,
+}
+
+impl InMemoryUIStreamStore {
+    /// Creates a new, empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of stored streams.
+    pub fn len(&self) -> usize {
+        self.streams.lock().len()
+    }
+
+    /// Returns `true` if no streams are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait]
+impl UIStreamStore for InMemoryUIStreamStore {
+    async fn append(&self, message_id: &str, chunk: VercelUIStream) {
+        self.streams
+            .lock()
+            .entry(message_id.to_string())
+            .or_default()
+            .chunks
+            .push(chunk);
+        self.notify.notify_waiters();
+    }
+
+    async fn finish(&self, message_id: &str) {
+        self.streams
+            .lock()
+            .entry(message_id.to_string())
+            .or_default()
+            .finished = true;
+        self.notify.notify_waiters();
+    }
+
+    async fn read(&self, message_id: &str, from: usize) -> Option<StoredUIStream> {
+        let streams = self.streams.lock();
+        let stored = streams.get(message_id)?;
+        Some(StoredUIStream {
+            chunks: stored.chunks.iter().skip(from).cloned().collect(),
+            finished: stored.finished,
+        })
+    }
+
+    async fn wait(&self, message_id: &str, len: usize) {
+        loop {
+            // Register before checking, so a chunk appended in between is not missed
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            match self.streams.lock().get(message_id) {
+                Some(stored) if stored.chunks.len() <= len && !stored.finished => {}
+                _ => return,
+            }
+
+            notified.await;
+        }
+    }
+
+    async fn remove(&self, message_id: &str) {
+        self.streams.lock().remove(message_id);
+        self.notify.notify_waiters();
+    }
+}
+
+/// Drives a UI message stream in the background and stores its chunks.
+///
+/// The stream keeps running even if no client is connected. Errors of the stream
+/// are stored as [`VercelUIStream::Error`] chunks, and the stored stream is marked
+/// as finished once the stream ends.
+///
+/// # Parameters
+/// - `stream`: The UI message stream to store.
+/// - `message_id`: The message ID to store the chunks under.
+/// - `store`: The store to append the chunks to.
+pub fn persist<S>(stream: S, message_id: impl Into<String>, store: Arc<dyn UIStreamStore>)
+where
+    S: Stream<Item = crate::Result<VercelUIStream>> + Send + 'static,
+{
+    let message_id = message_id.into();
+    crate::core::runtime::spawn(async move {
+        let mut stream = Box::pin(stream);
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.unwrap_or_else(|e| VercelUIStream::Error {
+                error_text: e.to_string(),
+            });
+            // Keep-alives are only meaningful to the connected client
+            if let VercelUIStream::Ping = chunk {
+                continue;
+            }
+            store.append(&message_id, chunk).await;
+        }
+        store.finish(&message_id).await;
+    });
+}
+
+/// Resumes a stored UI message stream.
+///
+/// The returned stream yields the stored chunks starting at index `from`, then
+/// follows new chunks until the stream has finished.
+///
+/// # Parameters
+/// - `store`: The store holding the stream.
+/// - `message_id`: The message ID of the stream.
+/// - `from`: The number of chunks the client has already received.
+///
+/// # Returns
+/// The resumed stream, or `None` if the store has no stream for the message.
+pub async fn resume(
+    store: Arc<dyn UIStreamStore>,
+    message_id: impl Into<String>,
+    from: usize,
+) -> Option<impl Stream<Item = crate::Result<VercelUIStream>> + Send + 'static> {
+    let message_id = message_id.into();
+    store.read(&message_id, from).await?;
+
+    let stream = futures::stream::unfold(
+        (store, message_id, from, false),
+        |(store, message_id, next, done)| async move {
+            if done {
+                return None;
+            }
+            store.wait(&message_id, next).await;
+            let stored = store.read(&message_id, next).await?;
+            let len = stored.chunks.len();
+            let done = stored.finished;
+            Some((stored.chunks, (store, message_id, next + len, done)))
+        },
+    )
+    .flat_map(|chunks| futures::stream::iter(chunks.into_iter().map(Ok)));
+
+    Some(stream)
+}
+
+#[cfg(feature = "language-model-request")]
+impl crate::core::StreamTextResponse {
+    /// Converts this `StreamTextResponse` into a UI message stream that can be resumed.
+    ///
+    /// The generation runs in the background and its chunks are buffered in the store,
+    /// so a client that disconnects can continue with [`resume`].
+    ///
+    /// # Parameters
+    /// - `options`: Configuration options controlling streaming behavior.
+    /// - `store`: The store to buffer the chunks in.
+    ///
+    /// # Returns
+    /// The message ID the chunks are stored under, and the stream for the current client.
+    pub fn into_resumable_vercel_ui_stream(
+        self,
+        mut options: crate::integrations::vercel_aisdk_ui::VercelUIStreamOptions,
+        store: Arc<dyn UIStreamStore>,
+    ) -> (
+        String,
+        impl Stream<Item = crate::Result<VercelUIStream>> + Send + 'static,
+    ) {
+        let message_id = options
+            .generate_message_id
+            .as_ref()
+            .map(|f| f())
+            .unwrap_or_else(|| generate_id("msg"));
+        let id = message_id.clone();
+        options.generate_message_id = Some(Box::new(move || id.clone()));
+
+        persist(
+            self.into_vercel_ui_stream(options),
+            &message_id,
+            store.clone(),
+        );
+
+        let id = message_id.clone();
+        let stream = futures::stream::once(resume(store, id, 0))
+            .filter_map(futures::future::ready)
+            .flatten();
+
+        (message_id, stream)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delta(text: &str) -> VercelUIStream {
+        VercelUIStream::TextDelta {
+            id: "text".to_string(),
+            delta: text.to_string(),
+            provider_metadata: None,
+        }
+    }
+
+    fn text(chunk: crate::Result<VercelUIStream>) -> String {
+        match chunk.unwrap() {
+            VercelUIStream::TextDelta { delta, .. } => delta,
+            other => panic!("Expected text delta, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume_replays_and_follows_stream() {
+        let store: Arc<dyn UIStreamStore> = Arc::new(InMemoryUIStreamStore::new());
+        assert!(resume(store.clone(), "msg", 0).await.is_none());
+
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        persist(rx, "msg", store.clone());
+        tx.unbounded_send(Ok(delta("a"))).unwrap();
+        tx.unbounded_send(Ok(delta("b"))).unwrap();
+        while store.read("msg", 0).await.map_or(0, |s| s.chunks.len()) < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        // The client received the first chunk before disconnecting
+        let mut resumed = Box::pin(resume(store.clone(), "msg", 1).await.unwrap());
+        assert_eq!(text(resumed.next().await.unwrap()), "b");
+
+        tx.unbounded_send(Ok(delta("c"))).unwrap();
+        assert_eq!(text(resumed.next().await.unwrap()), "c");
+
+        drop(tx);
+        assert!(resumed.next().await.is_none());
+
+        let stored = store.read("msg", 0).await.unwrap();
+        assert_eq!(stored.chunks.len(), 3);
+        assert!(stored.finished);
+
+        store.remove("msg").await;
+        assert!(store.read("msg", 0).await.is_none());
+    }
+}
@@ -0,0 +1,266 @@
+//! Integration with Tower.
+//!
+//! [`LanguageModelService`] exposes a language model as a
+//! [`tower::Service`], and [`with_layer`] applies a [`tower::Layer`] to it and turns
+//! the result back into a [`LanguageModel`]. This lets standard tower middleware
+//! such as timeouts, concurrency limits or retries compose with any provider.
+//!
+//! Only non-streaming calls go through the service. Streaming calls are passed to
+//! the model directly.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(all(feature = "tower", feature = "openai"))]
+//!# {
+//! use aisdk::core::LanguageModelRequest;
+//! use aisdk::integrations::tower::with_layer;
+//! use aisdk::providers::OpenAI;
+//! use std::time::Duration;
+//! use tower::timeout::TimeoutLayer;
+//!
+//! # async fn run() -> aisdk::Result<()> {
+//! let model = with_layer(OpenAI::gpt_5(), TimeoutLayer::new(Duration::from_secs(30)));
+//!
+//! let response = LanguageModelRequest::builder()
+//!     .model(model)
+//!     .prompt("Hello")
+//!     .build()
+//!     .generate_text()
+//!     .await?;
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+use crate::core::Message;
+use crate::core::capabilities::{CapabilitySet, forward_capabilities};
+use crate::core::language_model::{
+    LanguageModel, LanguageModelOptions, LanguageModelResponse, ProviderStream,
+};
+use crate::core::pricing::ModelPricing;
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// A [`tower::Service`] that performs non-streaming calls to a language model.
+#[derive(Debug, Clone)]
+pub struct LanguageModelService<M: LanguageModel> {
+    /// The language model.
+    pub model: M,
+}
+
+impl<M: LanguageModel> LanguageModelService<M> {
+    /// Creates a new service for the model.
+    pub fn new(model: M) -> Self {
+        Self { model }
+    }
+}
+
+impl<M: LanguageModel> Service<LanguageModelOptions> for LanguageModelService<M> {
+    type Response = LanguageModelResponse;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<LanguageModelResponse>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, options: LanguageModelOptions) -> Self::Future {
+        let mut model = self.model.clone();
+        Box::pin(async move { model.generate_text(options).await })
+    }
+}
+
+/// A language model whose non-streaming calls go through a tower service, created
+/// with [`with_layer`].
+#[derive(Debug, Clone)]
+pub struct TowerLanguageModel<M: LanguageModel, S> {
+    /// The wrapped model, used for streaming calls.
+    pub model: M,
+    service: S,
+}
+
+/// Applies a tower layer to a language model.
+///
+/// # Parameters
+///
+/// * `model` - The language model to wrap.
+/// * `layer` - The layer applied to the [`LanguageModelService`] of the model.
+///
+/// # Returns
+///
+/// A [`TowerLanguageModel`] that can be used like any other model.
+pub fn with_layer<M, L>(model: M, layer: L) -> TowerLanguageModel<M, L::Service>
+where
+    M: LanguageModel,
+    L: Layer<LanguageModelService<M>>,
+{
+    TowerLanguageModel {
+        service: layer.layer(LanguageModelService::new(model.clone())),
+        model,
+    }
+}
+
+/// Converts an error of a tower service back into an `Error`.
+fn into_error(error: tower::BoxError) -> Error {
+    match error.downcast::<Error>() {
+        Ok(error) => *error,
+        Err(error) => Error::Other(error.to_string()),
+    }
+}
+
+#[async_trait]
+impl<M, S> LanguageModel for TowerLanguageModel<M, S>
+where
+    M: LanguageModel,
+    S: Service<LanguageModelOptions, Response = LanguageModelResponse>
+        + Clone
+        + Send
+        + Sync
+        + std::fmt::Debug
+        + 'static,
+    S::Error: Into<tower::BoxError>,
+    S::Future: Send,
+{
+    fn name(&self) -> String {
+        self.model.name()
+    }
+
+    async fn generate_text(
+        &mut self,
+        options: LanguageModelOptions,
+    ) -> Result<LanguageModelResponse> {
+        futures::future::poll_fn(|cx| self.service.poll_ready(cx))
+            .await
+            .map_err(|e| into_error(e.into()))?;
+        self.service
+            .call(options)
+            .await
+            .map_err(|e| into_error(e.into()))
+    }
+
+    async fn stream_text(&mut self, options: LanguageModelOptions) -> Result<ProviderStream> {
+        self.model.stream_text(options).await
+    }
+
+    fn pricing(&self) -> Option<ModelPricing> {
+        self.model.pricing()
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        self.model.context_window()
+    }
+
+    fn max_output_tokens(&self) -> Option<usize> {
+        self.model.max_output_tokens()
+    }
+
+    fn capabilities(&self) -> CapabilitySet {
+        self.model.capabilities()
+    }
+
+    async fn count_tokens(&mut self, messages: &[Message]) -> Result<usize> {
+        self.model.count_tokens(messages).await
+    }
+}
+
+forward_capabilities!(TowerLanguageModel, S);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LanguageModelRequest;
+    use crate::core::language_model::LanguageModelResponseContentType;
+    use crate::providers::MockLanguageModel;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Counts the calls and rejects requests without a system prompt.
+    #[derive(Debug, Clone)]
+    struct CountingLayer(Arc<AtomicUsize>);
+
+    impl<S> Layer<S> for CountingLayer {
+        type Service = CountingService<S>;
+
+        fn layer(&self, inner: S) -> Self::Service {
+            CountingService(self.0.clone(), inner)
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct CountingService<S>(Arc<AtomicUsize>, S);
+
+    impl<S> Service<LanguageModelOptions> for CountingService<S>
+    where
+        S: Service<LanguageModelOptions, Error = Error>,
+        S::Future: Send + 'static,
+    {
+        type Response = S::Response;
+        type Error = tower::BoxError;
+        type Future = BoxFuture<'static, std::result::Result<S::Response, tower::BoxError>>;
+
+        fn poll_ready(
+            &mut self,
+            cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            self.1.poll_ready(cx).map_err(Into::into)
+        }
+
+        fn call(&mut self, options: LanguageModelOptions) -> Self::Future {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            if options.system.is_none() {
+                return Box::pin(async { Err("missing system prompt".into()) });
+            }
+            let future = self.1.call(options);
+            Box::pin(async move { future.await.map_err(Into::into) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_with_layer_routes_calls_through_service() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let echo = MockLanguageModel::new()
+            .with_name("echo")
+            .with_text("brief");
+        let mut model = with_layer(echo.clone(), CountingLayer(calls.clone()));
+        assert_eq!(model.name(), "echo");
+
+        let options = LanguageModelOptions {
+            system: Some("be brief".to_string()),
+            ..Default::default()
+        };
+        let response = model.generate_text(options).await.unwrap();
+        assert!(matches!(
+            &response.contents[0],
+            LanguageModelResponseContentType::Text(text) if text == "brief"
+        ));
+        assert_eq!(echo.calls()[0].system.as_deref(), Some("be brief"));
+
+        let error = model
+            .generate_text(LanguageModelOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::Other(message) if message == "missing system prompt"));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(echo.calls().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tower_model_builds_requests() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let echo = MockLanguageModel::new().with_text("brief");
+        let result = LanguageModelRequest::builder()
+            .model(with_layer(echo, CountingLayer(calls.clone())))
+            .system("be brief")
+            .prompt("Hello")
+            .build()
+            .generate_text()
+            .await
+            .unwrap();
+        assert_eq!(result.text(), Some("brief".to_string()));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,22 +1,53 @@
 //! Integration with Vercel's AI SDK UI.
 
+#[cfg(feature = "language-model-request")]
+use bytes::Bytes;
 #[cfg(feature = "language-model-request")]
 use futures::Stream;
 #[cfg(feature = "language-model-request")]
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-#[cfg(feature = "language-model-request")]
-use uuid;
 
 #[cfg(feature = "language-model-request")]
 use crate::core::LanguageModelStreamChunkType;
+#[cfg(feature = "language-model-request")]
+use crate::core::ids::generate_id;
+#[cfg(feature = "language-model-request")]
+use crate::core::language_model::sources::Source;
 
 /// Vercel's ai-sdk UI message chunk types.
 /// These represent the JSON chunks sent over SSE to the frontend.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "kebab-case")]
+#[serde(
+    tag = "type",
+    rename_all = "kebab-case",
+    rename_all_fields = "camelCase"
+)]
 pub enum VercelUIStream {
+    /// Start of the assistant message
+    #[serde(rename = "start")]
+    Start {
+        /// Message ID
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message_id: Option<String>,
+        /// Optional message metadata
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message_metadata: Option<Value>,
+    },
+    /// End of the assistant message
+    #[serde(rename = "finish")]
+    Finish {
+        /// Optional message metadata, including the token usage of the message
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message_metadata: Option<Value>,
+    },
+    /// Metadata of the assistant message, sent at any point of the stream
+    #[serde(rename = "message-metadata")]
+    MessageMetadata {
+        /// Message metadata
+        message_metadata: Value,
+    },
     /// Start of text message
     #[serde(rename = "text-start")]
     TextStart {
@@ -75,45 +106,59 @@ pub enum VercelUIStream {
         #[serde(skip_serializing_if = "Option::is_none")]
         provider_metadata: Option<Value>,
     },
-    /// Start of tool call
-    #[serde(rename = "tool-call-start")]
-    ToolCallStart {
-        /// Message ID
-        id: String,
+    /// Start of the input of a tool call
+    #[serde(rename = "tool-input-start")]
+    ToolInputStart {
         /// Tool call ID
         tool_call_id: String,
         /// Tool name
         tool_name: String,
-        /// Optional provider metadata
-        #[serde(skip_serializing_if = "Option::is_none")]
-        provider_metadata: Option<Value>,
     },
-    /// Delta of tool call
-    #[serde(rename = "tool-call-delta")]
-    ToolCallDelta {
-        /// Message ID
-        id: String,
+    /// Delta of the input of a tool call
+    #[serde(rename = "tool-input-delta")]
+    ToolInputDelta {
         /// Tool call ID
         tool_call_id: String,
-        /// Delta
-        delta: String,
-        /// Optional provider metadata
-        #[serde(skip_serializing_if = "Option::is_none")]
-        provider_metadata: Option<Value>,
+        /// Delta of the JSON input
+        input_text_delta: String,
     },
-    /// End of tool call
-    #[serde(rename = "tool-call-end")]
-    ToolCallEnd {
-        /// Message ID
-        id: String,
+    /// Complete input of a tool call
+    #[serde(rename = "tool-input-available")]
+    ToolInputAvailable {
         /// Tool call ID
         tool_call_id: String,
-        /// Result
-        result: Value,
+        /// Tool name
+        tool_name: String,
+        /// Input of the tool call
+        input: Value,
         /// Optional provider metadata
         #[serde(skip_serializing_if = "Option::is_none")]
         provider_metadata: Option<Value>,
     },
+    /// Output of an executed tool call
+    #[serde(rename = "tool-output-available")]
+    ToolOutputAvailable {
+        /// Tool call ID
+        tool_call_id: String,
+        /// Output of the tool
+        output: Value,
+    },
+    /// Error of an executed tool call
+    #[serde(rename = "tool-output-error")]
+    ToolOutputError {
+        /// Tool call ID
+        tool_call_id: String,
+        /// Error text
+        error_text: String,
+    },
+    /// A tool call waiting for approval
+    #[serde(rename = "tool-approval-request")]
+    ToolApprovalRequest {
+        /// Approval ID
+        approval_id: String,
+        /// Tool call ID
+        tool_call_id: String,
+    },
     /// Error chunk
     #[serde(rename = "error")]
     Error {
@@ -126,23 +171,176 @@ pub enum VercelUIStream {
         /// Error text
         error_text: String,
     },
-    // TODO: init - Add additional vercel UI chunks for data parts, sources, etc.
-    // as needed for full compatibility
+    /// Keep-alive sent while tools execute. It is not a chunk of the protocol,
+    /// and is sent as an SSE comment instead.
+    #[serde(skip)]
+    Ping,
+    /// Source referenced by a URL
+    #[serde(rename = "source-url")]
+    SourceUrl {
+        /// Source ID
+        source_id: String,
+        /// URL of the source
+        url: String,
+        /// Optional title of the source
+        #[serde(skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        /// Optional provider metadata
+        #[serde(skip_serializing_if = "Option::is_none")]
+        provider_metadata: Option<Value>,
+    },
+    /// Source referenced by a document
+    #[serde(rename = "source-document")]
+    SourceDocument {
+        /// Source ID
+        source_id: String,
+        /// IANA media type of the document
+        media_type: String,
+        /// Title of the document
+        title: String,
+        /// Optional filename of the document
+        #[serde(skip_serializing_if = "Option::is_none")]
+        filename: Option<String>,
+        /// Optional provider metadata
+        #[serde(skip_serializing_if = "Option::is_none")]
+        provider_metadata: Option<Value>,
+    },
+    /// Custom data part, sent with the type `data-{name}`
+    #[serde(untagged)]
+    Data(VercelUIDataPart),
+}
+
+/// A custom data part of the UI message stream.
+///
+/// Data parts are sent with the type `data-{name}` and rendered by the frontend
+/// from the `parts` of the message, or only passed to the `onData` callback of
+/// `useChat` if they are transient.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VercelUIDataPart {
+    /// Name of the data part, sent as the type `data-{name}`
+    pub name: String,
+    /// Optional ID. Parts with the same ID replace each other on the frontend.
+    pub id: Option<String>,
+    /// The data of the part
+    pub data: Value,
+    /// Whether the part is only sent to the frontend and not added to the message
+    pub transient: bool,
+}
+
+impl VercelUIDataPart {
+    /// Creates a data part with the given name and data.
+    pub fn new(name: impl Into<String>, data: Value) -> Self {
+        Self {
+            name: name.into(),
+            id: None,
+            data,
+            transient: false,
+        }
+    }
+
+    /// Sets the ID of the data part.
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Marks the data part as transient.
+    pub fn transient(mut self) -> Self {
+        self.transient = true;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawDataPart {
+    #[serde(rename = "type")]
+    part_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    data: Value,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    transient: bool,
+}
+
+impl Serialize for VercelUIDataPart {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RawDataPart {
+            part_type: format!("data-{}", self.name),
+            id: self.id.clone(),
+            data: self.data.clone(),
+            transient: self.transient,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for VercelUIDataPart {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawDataPart::deserialize(deserializer)?;
+        let name = raw
+            .part_type
+            .strip_prefix("data-")
+            .ok_or_else(|| serde::de::Error::custom("data part type must start with `data-`"))?;
+        Ok(Self {
+            name: name.to_string(),
+            id: raw.id,
+            data: raw.data,
+            transient: raw.transient,
+        })
+    }
 }
 
+#[cfg(feature = "language-model-request")]
+impl From<Source> for VercelUIStream {
+    fn from(source: Source) -> Self {
+        match source {
+            Source::Url { id, url, title } => VercelUIStream::SourceUrl {
+                source_id: id,
+                url,
+                title,
+                provider_metadata: None,
+            },
+            Source::Document {
+                id,
+                title,
+                media_type,
+                filename,
+            } => VercelUIStream::SourceDocument {
+                source_id: id,
+                media_type,
+                title,
+                filename,
+                provider_metadata: None,
+            },
+        }
+    }
+}
+
+/// Header that marks a response as a Vercel AI SDK UI message stream.
+pub const UI_MESSAGE_STREAM_HEADER: &str = "x-vercel-ai-ui-message-stream";
+
+/// Type alias for a Vercel UI message stream framed as server-sent events.
+#[cfg(feature = "language-model-request")]
+pub type VercelUISseStream =
+    std::pin::Pin<Box<dyn Stream<Item = crate::Result<Bytes>> + Send + 'static>>;
+
 #[derive(Default)]
 /// Configuration for vercel UI message stream.
 pub struct VercelUIStreamOptions {
     /// Whether to send reasoning chunks
     pub send_reasoning: bool,
-    /// Whether to send sources (TODO: uncomment when sources are supported)
-    //pub send_sources: bool,
+    /// Whether to send sources
+    pub send_sources: bool,
     /// Whether to send start chunks
     pub send_start: bool,
     /// Whether to send finish chunks
     pub send_finish: bool,
     /// Custom message ID generator
     pub generate_message_id: Option<Box<VercelUIStreamIdGenerator>>,
+    /// Metadata sent with the start and finish chunks
+    pub message_metadata: Option<Value>,
+    /// Data parts sent after the start of the message
+    pub data_parts: Vec<VercelUIDataPart>,
 }
 
 /// Type alias for custom message ID generator functions.
@@ -190,6 +388,13 @@ impl<C, T> VercelUIStreamBuilder<C, T> {
         self
     }
 
+    /// Enable sending `source-url` and `source-document` chunks for the sources the
+    /// model used and cited.
+    pub fn send_sources(mut self) -> Self {
+        self.options.send_sources = true;
+        self
+    }
+
     /// Enable sending start chunks.
     pub fn send_start(mut self) -> Self {
         self.options.send_start = true;
@@ -211,6 +416,21 @@ impl<C, T> VercelUIStreamBuilder<C, T> {
         self
     }
 
+    /// Set metadata sent with the start and finish chunks.
+    ///
+    /// If the metadata is a JSON object, the finish chunk adds the token usage of
+    /// the message to it under `usage`.
+    pub fn with_message_metadata(mut self, metadata: Value) -> Self {
+        self.options.message_metadata = Some(metadata);
+        self
+    }
+
+    /// Add a data part sent after the start of the message.
+    pub fn with_data(mut self, part: VercelUIDataPart) -> Self {
+        self.options.data_parts.push(part);
+        self
+    }
+
     /// Build the final response using the configured options.
     pub fn build(self) -> T {
         (self.build_fn)(self.context, self.options)
@@ -223,6 +443,8 @@ impl crate::core::StreamTextResponse {
     ///
     /// Transforms the underlying language model stream into Vercel-compatible UI chunks (e.g., text deltas,
     /// reasoning deltas), enabling streaming of the language model output to a frontend using Vercel's ai-sdk-ui.
+    /// Text and reasoning deltas are wrapped in start and end chunks, and the finish chunk
+    /// carries the token usage of the message in its metadata.
     ///
     /// # Parameters
     /// - `options`: Configuration options controlling streaming behavior (e.g., enabling reasoning chunks).
@@ -233,82 +455,446 @@ impl crate::core::StreamTextResponse {
         self,
         options: VercelUIStreamOptions,
     ) -> impl Stream<Item = crate::Result<VercelUIStream>> {
+        let state = UIStreamState::new(options);
+
+        // A trailing `None` marks the end of the stream
+        self.stream
+            .map(Some)
+            .chain(futures::stream::once(futures::future::ready(None)))
+            .scan(state, |state, chunk| {
+                futures::future::ready(Some(state.convert(chunk)))
+            })
+            .flat_map(|chunks| futures::stream::iter(chunks.into_iter().map(Ok)))
+    }
+
+    /// Creates a builder for a Vercel AI SDK UI message stream framed as server-sent events.
+    ///
+    /// The stream yields the bytes of the response body, ending with `data: [DONE]`,
+    /// so any HTTP framework can send it. Respond with the `text/event-stream`
+    /// content type and the [`UI_MESSAGE_STREAM_HEADER`] header set to `v1`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    ///# #[cfg(feature = "language-model-request")]
+    ///# {
+    /// # fn run(response: aisdk::core::StreamTextResponse) {
+    /// let body = response
+    ///     .into_ui_stream()
+    ///     .send_start()
+    ///     .send_finish()
+    ///     .build();
+    /// # }
+    ///# }
+    /// ```
+    ///
+    /// # Returns
+    /// A `VercelUIStreamBuilder` for configuring and building the byte stream.
+    pub fn into_ui_stream(self) -> VercelUIStreamBuilder<Self, VercelUISseStream> {
+        VercelUIStreamBuilder::new(self, |context, options| {
+            let events = context.into_vercel_ui_stream(options).map(|result| {
+                let chunk = result?;
+                if let VercelUIStream::Ping = chunk {
+                    return Ok(Bytes::from_static(b": ping\n\n"));
+                }
+                let json = serde_json::to_string(&chunk).map_err(|e| {
+                    crate::error::Error::Other(format!("JSON serialization error: {e}"))
+                })?;
+                Ok(Bytes::from(format!("data: {json}\n\n")))
+            });
+            let done = futures::stream::once(futures::future::ready(Ok(Bytes::from_static(
+                b"data: [DONE]\n\n",
+            ))));
+            Box::pin(events.chain(done)) as VercelUISseStream
+        })
+    }
+
+    /// Converts this `StreamTextResponse` into the bytes of a Vercel AI SDK UI
+    /// message stream, with start, reasoning, source and finish chunks.
+    ///
+    /// Use [`into_ui_stream`](Self::into_ui_stream) to configure the stream instead.
+    ///
+    /// # Examples
+    ///
+    /// An axum response:
+    ///
+    /// ```rust,no_run
+    ///# #[cfg(feature = "axum")]
+    ///# {
+    /// use aisdk::core::StreamTextResponse;
+    /// use aisdk::integrations::vercel_aisdk_ui::UI_MESSAGE_STREAM_HEADER;
+    /// use axum::body::Body;
+    /// use axum::http::header::CONTENT_TYPE;
+    /// use axum::response::Response;
+    ///
+    /// fn respond(response: StreamTextResponse) -> Response {
+    ///     Response::builder()
+    ///         .header(CONTENT_TYPE, "text/event-stream")
+    ///         .header(UI_MESSAGE_STREAM_HEADER, "v1")
+    ///         .body(Body::from_stream(response.into_sse_bytes()))
+    ///         .unwrap()
+    /// }
+    ///# }
+    /// ```
+    pub fn into_sse_bytes(self) -> VercelUISseStream {
+        self.into_ui_stream()
+            .send_reasoning()
+            .send_sources()
+            .send_start()
+            .send_finish()
+            .build()
+    }
+}
+
+/// Tracks the open parts of a UI message stream.
+#[cfg(feature = "language-model-request")]
+struct UIStreamState {
+    options: VercelUIStreamOptions,
+    message_id: String,
+    parts: usize,
+    text_id: Option<String>,
+    reasoning_id: Option<String>,
+    tool_calls: std::collections::HashSet<String>,
+    sources: std::collections::HashSet<String>,
+    usage: Option<crate::core::language_model::Usage>,
+}
+
+#[cfg(feature = "language-model-request")]
+impl UIStreamState {
+    fn new(options: VercelUIStreamOptions) -> Self {
         let message_id = options
             .generate_message_id
             .as_ref()
             .map(|f| f())
-            .unwrap_or_else(|| format!("msg_{}", uuid::Uuid::new_v4().simple()));
-
-        self.stream.filter_map(move |chunk| {
-            let ui_chunk = match chunk {
-                LanguageModelStreamChunkType::Start if options.send_start => {
-                    Some(VercelUIStream::TextStart {
-                        id: message_id.clone(),
-                        provider_metadata: None,
-                    })
+            .unwrap_or_else(|| generate_id("msg"));
+
+        Self {
+            options,
+            message_id,
+            parts: 0,
+            text_id: None,
+            reasoning_id: None,
+            tool_calls: Default::default(),
+            sources: Default::default(),
+            usage: None,
+        }
+    }
+
+    /// Returns a new ID for a text or reasoning part.
+    fn next_part_id(&mut self) -> String {
+        self.parts += 1;
+        format!("{}_{}", self.message_id, self.parts)
+    }
+
+    fn close_text(&mut self, out: &mut Vec<VercelUIStream>) {
+        if let Some(id) = self.text_id.take() {
+            out.push(VercelUIStream::TextEnd {
+                id,
+                provider_metadata: None,
+            });
+        }
+    }
+
+    fn close_reasoning(&mut self, out: &mut Vec<VercelUIStream>) {
+        if let Some(id) = self.reasoning_id.take() {
+            out.push(VercelUIStream::ReasoningEnd {
+                id,
+                provider_metadata: None,
+            });
+        }
+    }
+
+    /// Starts the input of a tool call, unless it has already been started.
+    fn start_tool_input(
+        &mut self,
+        tool_call_id: &str,
+        tool_name: &str,
+        out: &mut Vec<VercelUIStream>,
+    ) {
+        if self.tool_calls.insert(tool_call_id.to_string()) {
+            self.close_reasoning(out);
+            self.close_text(out);
+            out.push(VercelUIStream::ToolInputStart {
+                tool_call_id: tool_call_id.to_string(),
+                tool_name: tool_name.to_string(),
+            });
+        }
+    }
+
+    /// Returns the message metadata with the token usage of the message.
+    fn finish_metadata(&self) -> Option<Value> {
+        let usage = self
+            .usage
+            .as_ref()
+            .and_then(|usage| serde_json::to_value(usage).ok());
+
+        match (self.options.message_metadata.clone(), usage) {
+            (Some(Value::Object(mut metadata)), Some(usage)) => {
+                metadata.insert("usage".to_string(), usage);
+                Some(Value::Object(metadata))
+            }
+            (None, Some(usage)) => Some(serde_json::json!({ "usage": usage })),
+            (metadata, _) => metadata,
+        }
+    }
+
+    /// Sends a source the first time it is seen, as citations repeat their sources.
+    fn send_source(&mut self, source: Source, out: &mut Vec<VercelUIStream>) {
+        if self.sources.insert(source.id().to_string()) {
+            out.push(source.into());
+        }
+    }
+
+    /// Converts a chunk of the language model stream, or the end of the stream if `None`.
+    fn convert(&mut self, chunk: Option<LanguageModelStreamChunkType>) -> Vec<VercelUIStream> {
+        let mut out = Vec::new();
+        match chunk {
+            Some(LanguageModelStreamChunkType::Start) => {
+                if self.options.send_start {
+                    out.push(VercelUIStream::Start {
+                        message_id: Some(self.message_id.clone()),
+                        message_metadata: self.options.message_metadata.clone(),
+                    });
                 }
+                out.extend(self.options.data_parts.drain(..).map(VercelUIStream::Data));
+            }
 
-                LanguageModelStreamChunkType::Text(delta) => Some(VercelUIStream::TextDelta {
-                    id: message_id.clone(),
+            Some(LanguageModelStreamChunkType::Text(delta)) => {
+                self.close_reasoning(&mut out);
+                let id = match &self.text_id {
+                    Some(id) => id.clone(),
+                    None => {
+                        let id = self.next_part_id();
+                        out.push(VercelUIStream::TextStart {
+                            id: id.clone(),
+                            provider_metadata: None,
+                        });
+                        self.text_id = Some(id.clone());
+                        id
+                    }
+                };
+                out.push(VercelUIStream::TextDelta {
+                    id,
                     delta,
                     provider_metadata: None,
-                }),
-
-                LanguageModelStreamChunkType::Reasoning(delta) if options.send_reasoning => {
-                    Some(VercelUIStream::ReasoningDelta {
-                        id: message_id.clone(),
-                        delta,
-                        provider_metadata: None,
-                    })
-                }
+                });
+            }
 
-                LanguageModelStreamChunkType::ToolCall(_json_str) => {
-                    //TODO: handle tool call streams when they are supported
-                    Some(VercelUIStream::ToolCallStart {
-                        id: message_id.clone(),
-                        tool_call_id: "unknown".to_string(),
-                        tool_name: "unknown".to_string(),
-                        provider_metadata: None,
-                    })
-                }
+            Some(LanguageModelStreamChunkType::Reasoning(delta)) if self.options.send_reasoning => {
+                self.close_text(&mut out);
+                let id = match &self.reasoning_id {
+                    Some(id) => id.clone(),
+                    None => {
+                        let id = self.next_part_id();
+                        out.push(VercelUIStream::ReasoningStart {
+                            id: id.clone(),
+                            provider_metadata: None,
+                        });
+                        self.reasoning_id = Some(id.clone());
+                        id
+                    }
+                };
+                out.push(VercelUIStream::ReasoningDelta {
+                    id,
+                    delta,
+                    provider_metadata: None,
+                });
+            }
 
-                LanguageModelStreamChunkType::End(_) if options.send_finish => {
-                    Some(VercelUIStream::TextEnd {
-                        id: message_id.clone(),
-                        provider_metadata: None,
-                    })
-                }
+            Some(LanguageModelStreamChunkType::ToolCall(delta)) => {
+                self.start_tool_input(&delta.id, &delta.name, &mut out);
+                out.push(VercelUIStream::ToolInputDelta {
+                    tool_call_id: delta.id,
+                    input_text_delta: delta.delta,
+                });
+            }
+
+            Some(LanguageModelStreamChunkType::ToolCallAvailable(call)) => {
+                self.start_tool_input(&call.tool.id, &call.tool.name, &mut out);
+                out.push(VercelUIStream::ToolInputAvailable {
+                    tool_call_id: call.tool.id,
+                    tool_name: call.tool.name,
+                    input: call.input,
+                    provider_metadata: None,
+                });
+            }
+
+            Some(LanguageModelStreamChunkType::ToolResult(result)) => {
+                out.push(match result.output {
+                    Ok(output) => VercelUIStream::ToolOutputAvailable {
+                        tool_call_id: result.tool.id,
+                        output,
+                    },
+                    Err(e) => VercelUIStream::ToolOutputError {
+                        tool_call_id: result.tool.id,
+                        error_text: e.to_string(),
+                    },
+                });
+            }
+
+            Some(LanguageModelStreamChunkType::ToolApprovalRequired(request)) => {
+                out.push(VercelUIStream::ToolApprovalRequest {
+                    approval_id: request.approval_id,
+                    tool_call_id: request.tool_call.tool.id,
+                });
+            }
+
+            Some(LanguageModelStreamChunkType::Source(source)) if self.options.send_sources => {
+                self.send_source(source, &mut out);
+            }
+
+            Some(LanguageModelStreamChunkType::Citation(citation)) if self.options.send_sources => {
+                self.send_source(citation.source, &mut out);
+            }
 
+            Some(LanguageModelStreamChunkType::Usage(usage)) => {
+                // A step has finished, so its parts are complete
+                self.close_reasoning(&mut out);
+                self.close_text(&mut out);
+                self.usage = Some(match self.usage.take() {
+                    Some(total) => &total + &usage,
+                    None => usage,
+                });
+            }
+
+            Some(LanguageModelStreamChunkType::End(_)) => {
+                self.close_reasoning(&mut out);
+                self.close_text(&mut out);
+            }
+
+            Some(
                 LanguageModelStreamChunkType::Failed(error)
-                | LanguageModelStreamChunkType::Incomplete(error) => {
-                    Some(VercelUIStream::Error { error_text: error })
-                }
+                | LanguageModelStreamChunkType::Incomplete(error),
+            ) => out.push(VercelUIStream::Error { error_text: error }),
 
-                // Skip and continue
-                LanguageModelStreamChunkType::NotSupported(_) => None,
+            Some(LanguageModelStreamChunkType::Ping) => out.push(VercelUIStream::Ping),
 
-                //TODO: handle other vercel chunk types
-                // Skip and continue
-                _ => None,
-            };
+            None => {
+                self.close_reasoning(&mut out);
+                self.close_text(&mut out);
+                if self.options.send_finish {
+                    out.push(VercelUIStream::Finish {
+                        message_metadata: self.finish_metadata(),
+                    });
+                }
+            }
 
-            futures::future::ready(ui_chunk.map(Ok))
-        })
+            //TODO: handle other vercel chunk types
+            // Skip and continue
+            Some(_) => {}
+        }
+        out
     }
 }
 
 /// Represents a part of a UI message from Vercel's useChat hook.
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct VercelUIMessagePart {
     /// The text content of the part.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub text: String,
-    /// The type of the part (e.g., "text").
+    /// The type of the part (e.g., "text", "reasoning", "tool-{name}" or "dynamic-tool").
     #[serde(rename = "type")]
     pub part_type: String,
+    /// The tool call ID of a tool part.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    /// The tool name of a "dynamic-tool" part.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    /// The state of the part. Text and reasoning parts are "streaming" or "done", and
+    /// tool parts go from "input-streaming" and "input-available" to "output-available"
+    /// or "output-error".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    /// The input of a tool part.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<Value>,
+    /// The output of a tool part.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<Value>,
+    /// The error of a tool part in the "output-error" state.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_text: Option<String>,
+    /// The source ID of a "source-url" or "source-document" part.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_id: Option<String>,
+    /// The URL of a "source-url" or "file" part.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// The title of a source part.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The media type of a "source-document" or "file" part.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    /// The filename of a "source-document" or "file" part.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+}
+
+impl VercelUIMessagePart {
+    /// Creates a finished part of the type with the text, such as a "text" part.
+    fn done(part_type: &str, text: impl Into<String>) -> Self {
+        Self {
+            part_type: part_type.to_string(),
+            text: text.into(),
+            state: Some("done".to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the file of a "file" part.
+    fn file(&self) -> Option<crate::core::files::FileHandle> {
+        if self.part_type != "file" {
+            return None;
+        }
+        // The URL of the file, such as a provider file URI, is its reference
+        let url = self.url.clone()?;
+        let mut file = crate::core::files::FileHandle::new(&url);
+        file.uri = Some(url);
+        file.filename = self.filename.clone();
+        file.mime_type = self.media_type.clone();
+        Some(file)
+    }
+
+    /// Returns the tool call and its result if this is a tool part with an output.
+    fn tool_invocation(
+        &self,
+    ) -> Option<(
+        crate::core::tools::ToolCallInfo,
+        crate::core::tools::ToolResultInfo,
+    )> {
+        let name = match self.part_type.as_str() {
+            "dynamic-tool" => self.tool_name.clone()?,
+            part_type => part_type.strip_prefix("tool-")?.to_string(),
+        };
+        let id = self.tool_call_id.clone()?;
+
+        let mut call = crate::core::tools::ToolCallInfo::new(&name);
+        call.id(&id);
+        call.input(self.input.clone().unwrap_or(Value::Null));
+
+        let mut result = crate::core::tools::ToolResultInfo::new(&name);
+        result.id(&id);
+        match self.state.as_deref() {
+            Some("output-available") => result.output(self.output.clone().unwrap_or(Value::Null)),
+            Some("output-error") => {
+                result.output = Err(crate::error::Error::ToolCallError(
+                    self.error_text.clone().unwrap_or_default(),
+                ))
+            }
+            // Calls without an output can not be sent back to the model
+            _ => return None,
+        }
+
+        Some((call, result))
+    }
 }
 
 /// Represents a UI message from Vercel's useChat hook.
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 pub struct VercelUIMessage {
     /// Unique identifier for the message.
     pub id: String,
@@ -318,6 +904,258 @@ pub struct VercelUIMessage {
     pub parts: Vec<VercelUIMessagePart>,
 }
 
+impl VercelUIMessage {
+    /// Creates an empty assistant message, to be filled with [`apply`](Self::apply).
+    pub fn assistant() -> Self {
+        Self {
+            role: "assistant".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Converts messages to UI messages, such as to send a stored conversation to
+    /// `useChat`.
+    ///
+    /// This is the reverse of [`Message::from_vercel_ui_message`](crate::core::Message::from_vercel_ui_message):
+    /// consecutive assistant messages and tool results make a single assistant
+    /// message with text, reasoning and tool parts, and files of user messages
+    /// become file parts. Developer messages become system messages.
+    ///
+    /// # Parameters
+    /// - `messages`: The messages to convert.
+    ///
+    /// # Returns
+    /// A vector of `VercelUIMessage`, each with a new ID.
+    pub fn from_messages(messages: &[crate::core::Message]) -> Vec<Self> {
+        use crate::core::Message;
+        use crate::core::language_model::LanguageModelResponseContentType;
+
+        let mut ui_messages: Vec<Self> = Vec::new();
+        for message in messages {
+            match message {
+                Message::System(system) => {
+                    ui_messages.push(Self::with_text("system", &system.content))
+                }
+                Message::Developer(developer) => {
+                    ui_messages.push(Self::with_text("system", developer))
+                }
+                Message::User(user) => {
+                    let mut ui_message = Self::with_text("user", &user.content);
+                    ui_message
+                        .parts
+                        .extend(user.files.iter().map(|file| VercelUIMessagePart {
+                            part_type: "file".to_string(),
+                            url: Some(file.uri.clone().unwrap_or_else(|| file.id.clone())),
+                            media_type: file.mime_type.clone(),
+                            filename: file.filename.clone(),
+                            ..Default::default()
+                        }));
+                    ui_messages.push(ui_message);
+                }
+                Message::Assistant(assistant) => {
+                    let part = match &assistant.content {
+                        LanguageModelResponseContentType::Text(text) => {
+                            VercelUIMessagePart::done("text", text)
+                        }
+                        LanguageModelResponseContentType::Reasoning { content, .. } => {
+                            VercelUIMessagePart::done("reasoning", content)
+                        }
+                        LanguageModelResponseContentType::ToolCall(call) => VercelUIMessagePart {
+                            part_type: format!("tool-{}", call.tool.name),
+                            tool_call_id: Some(call.tool.id.clone()),
+                            state: Some("input-available".to_string()),
+                            input: Some(call.input.clone()),
+                            ..Default::default()
+                        },
+                        _ => continue,
+                    };
+                    let ui_message = match ui_messages.last_mut() {
+                        Some(last) if last.role == "assistant" => last,
+                        _ => {
+                            ui_messages.push(Self::with_text("assistant", ""));
+                            ui_messages.last_mut().expect("message was just pushed")
+                        }
+                    };
+                    ui_message.parts.push(part);
+                }
+                Message::Tool(result) => {
+                    let part = ui_messages
+                        .last_mut()
+                        .and_then(|last| last.tool_part(&result.tool.id));
+                    if let Some(part) = part {
+                        match &result.output {
+                            Ok(output) => {
+                                part.output = Some(output.clone());
+                                part.state = Some("output-available".to_string());
+                            }
+                            Err(error) => {
+                                part.error_text = Some(error.to_string());
+                                part.state = Some("output-error".to_string());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        ui_messages
+    }
+
+    /// Creates a message of the role with a new ID and a text part, if the text
+    /// is not empty.
+    fn with_text(role: &str, text: &str) -> Self {
+        Self {
+            id: generate_id("msg"),
+            role: role.to_string(),
+            parts: (!text.is_empty())
+                .then(|| VercelUIMessagePart::done("text", text))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Applies a chunk of a UI message stream to the message.
+    ///
+    /// This is the client side of the stream: text and reasoning deltas are appended
+    /// to their parts, and tool parts move through their states as the tool call is
+    /// streamed and executed, so a UI can render the message while it is generated.
+    /// Chunks that do not change the parts are ignored.
+    ///
+    /// # Parameters
+    /// - `chunk`: The next chunk of the stream.
+    pub fn apply(&mut self, chunk: VercelUIStream) {
+        match chunk {
+            VercelUIStream::Start {
+                message_id: Some(id),
+                ..
+            } => self.id = id,
+            VercelUIStream::TextStart { .. } => self.start_part("text"),
+            VercelUIStream::TextDelta { delta, .. } => self.append("text", &delta),
+            VercelUIStream::TextEnd { .. } => self.end_part("text"),
+            VercelUIStream::ReasoningStart { .. } => self.start_part("reasoning"),
+            VercelUIStream::ReasoningDelta { delta, .. } => self.append("reasoning", &delta),
+            VercelUIStream::ReasoningEnd { .. } => self.end_part("reasoning"),
+            VercelUIStream::ToolInputStart {
+                tool_call_id,
+                tool_name,
+            } => self.parts.push(VercelUIMessagePart {
+                part_type: format!("tool-{tool_name}"),
+                tool_call_id: Some(tool_call_id),
+                state: Some("input-streaming".to_string()),
+                ..Default::default()
+            }),
+            VercelUIStream::ToolInputAvailable {
+                tool_call_id,
+                tool_name,
+                input,
+                ..
+            } => {
+                if self.tool_part(&tool_call_id).is_none() {
+                    self.parts.push(VercelUIMessagePart {
+                        part_type: format!("tool-{tool_name}"),
+                        tool_call_id: Some(tool_call_id.clone()),
+                        ..Default::default()
+                    });
+                }
+                if let Some(part) = self.tool_part(&tool_call_id) {
+                    part.input = Some(input);
+                    part.state = Some("input-available".to_string());
+                }
+            }
+            VercelUIStream::ToolOutputAvailable {
+                tool_call_id,
+                output,
+            } => {
+                if let Some(part) = self.tool_part(&tool_call_id) {
+                    part.output = Some(output);
+                    part.state = Some("output-available".to_string());
+                }
+            }
+            VercelUIStream::ToolOutputError {
+                tool_call_id,
+                error_text,
+            } => {
+                if let Some(part) = self.tool_part(&tool_call_id) {
+                    part.error_text = Some(error_text);
+                    part.state = Some("output-error".to_string());
+                }
+            }
+            VercelUIStream::SourceUrl {
+                source_id,
+                url,
+                title,
+                ..
+            } => self.parts.push(VercelUIMessagePart {
+                part_type: "source-url".to_string(),
+                source_id: Some(source_id),
+                url: Some(url),
+                title,
+                ..Default::default()
+            }),
+            VercelUIStream::SourceDocument {
+                source_id,
+                media_type,
+                title,
+                filename,
+                ..
+            } => self.parts.push(VercelUIMessagePart {
+                part_type: "source-document".to_string(),
+                source_id: Some(source_id),
+                title: Some(title),
+                media_type: Some(media_type),
+                filename,
+                ..Default::default()
+            }),
+            _ => {}
+        }
+    }
+
+    fn start_part(&mut self, part_type: &str) {
+        self.parts.push(VercelUIMessagePart {
+            part_type: part_type.to_string(),
+            state: Some("streaming".to_string()),
+            ..Default::default()
+        });
+    }
+
+    /// Appends a delta to the streaming part of the type, starting one if needed.
+    fn append(&mut self, part_type: &str, delta: &str) {
+        let streaming = self.parts.last().is_some_and(|part| {
+            part.part_type == part_type && part.state.as_deref() == Some("streaming")
+        });
+        if !streaming {
+            self.start_part(part_type);
+        }
+        if let Some(part) = self.parts.last_mut() {
+            part.text.push_str(delta);
+        }
+    }
+
+    fn end_part(&mut self, part_type: &str) {
+        if let Some(part) =
+            self.parts.iter_mut().rev().find(|part| {
+                part.part_type == part_type && part.state.as_deref() == Some("streaming")
+            })
+        {
+            part.state = Some("done".to_string());
+        }
+    }
+
+    fn tool_part(&mut self, tool_call_id: &str) -> Option<&mut VercelUIMessagePart> {
+        self.parts
+            .iter_mut()
+            .find(|part| part.tool_call_id.as_deref() == Some(tool_call_id))
+    }
+
+    /// Returns the joined text of all "text" parts.
+    fn text(&self) -> String {
+        self.parts
+            .iter()
+            .filter(|part| part.part_type == "text")
+            .map(|part| part.text.as_str())
+            .collect()
+    }
+}
+
 /// Represents a request body from Vercel's useChat hook.
 #[derive(Deserialize, Debug)]
 pub struct VercelUIRequest {
@@ -333,8 +1171,10 @@ impl crate::core::Message {
     /// Converts a slice of Vercel UI messages to the `aisdk::core::Message` format.
     ///
     /// This function extracts text content from UI message parts and maps roles to the
-    /// corresponding `Message` variants. Currently only "text" parts are supported; other part types
-    /// (e.g., files, tools) are ignored.
+    /// corresponding `Message` variants. File parts of user messages become files
+    /// referenced by their URL, reasoning parts of assistant messages become reasoning
+    /// messages, and tool parts with an output become a tool call followed by its tool
+    /// result. Other part types (e.g., sources) are ignored.
     ///
     /// # Parameters
     /// - `ui_messages`: A slice of `VercelUIMessage` to convert.
@@ -343,31 +1183,60 @@ impl crate::core::Message {
     /// A vector of `Message` instances.
     ///
     /// # Notes
-    /// - Joins multiple text parts into a single string.
-    /// - TODO: Add support for file parts (e.g., map to URLs in content).
-    /// - TODO: Add support for tool parts (e.g., map to `Tool` messages).
+    /// - Joins consecutive text parts into a single string.
+    /// - File URLs are sent as is, so they must be references the provider accepts,
+    ///   such as the URI of a file uploaded to it.
     pub fn from_vercel_ui_message(
         ui_messages: &[VercelUIMessage],
     ) -> crate::core::messages::Messages {
-        ui_messages
-            .iter()
-            .filter_map(|msg| {
-                let content = msg
-                    .parts
-                    .iter()
-                    .filter(|part| part.part_type == "text")
-                    .map(|part| part.text.clone())
-                    .collect::<Vec<_>>()
-                    .join("");
-
-                match msg.role.as_str() {
-                    "system" => Some(crate::core::messages::Message::System(content.into())),
-                    "user" => Some(crate::core::messages::Message::User(content.into())),
-                    "assistant" => Some(crate::core::messages::Message::Assistant(content.into())),
-                    _ => None,
+        use crate::core::language_model::LanguageModelResponseContentType;
+        use crate::core::messages::{AssistantMessage, Message, UserMessage};
+
+        let mut messages = Vec::new();
+        for msg in ui_messages {
+            match msg.role.as_str() {
+                "system" => messages.push(Message::System(msg.text().into())),
+                "user" => {
+                    let mut user = UserMessage::new(msg.text());
+                    user.files = msg.parts.iter().filter_map(|part| part.file()).collect();
+                    messages.push(Message::User(user));
                 }
-            })
-            .collect()
+                "assistant" => {
+                    let start = messages.len();
+                    let mut text = String::new();
+                    for part in &msg.parts {
+                        if part.part_type == "text" {
+                            text.push_str(&part.text);
+                        } else if part.part_type == "reasoning" {
+                            if !text.is_empty() {
+                                messages.push(Message::Assistant(std::mem::take(&mut text).into()));
+                            }
+                            messages.push(Message::Assistant(AssistantMessage::new(
+                                LanguageModelResponseContentType::Reasoning {
+                                    content: part.text.clone(),
+                                    extensions: Default::default(),
+                                },
+                                None,
+                            )));
+                        } else if let Some((call, result)) = part.tool_invocation() {
+                            if !text.is_empty() {
+                                messages.push(Message::Assistant(std::mem::take(&mut text).into()));
+                            }
+                            messages.push(Message::Assistant(AssistantMessage::new(
+                                LanguageModelResponseContentType::ToolCall(call),
+                                None,
+                            )));
+                            messages.push(Message::Tool(result));
+                        }
+                    }
+                    if !text.is_empty() || messages.len() == start {
+                        messages.push(Message::Assistant(text.into()));
+                    }
+                }
+                _ => {}
+            }
+        }
+        messages
     }
 }
 
@@ -377,3 +1246,440 @@ impl From<VercelUIRequest> for Vec<crate::core::messages::Message> {
         crate::core::messages::Message::from_vercel_ui_message(&request.messages)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::language_model::LanguageModelResponseContentType;
+    use crate::core::messages::Message;
+
+    #[test]
+    fn test_vercel_ui_stream_serialization() {
+        let chunk = VercelUIStream::Error {
+            error_text: "boom".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&chunk).unwrap(),
+            serde_json::json!({ "type": "error", "errorText": "boom" })
+        );
+
+        let part = VercelUIDataPart::new("weather", serde_json::json!({ "city": "Paris" }))
+            .with_id("w1")
+            .transient();
+        let json = serde_json::to_value(VercelUIStream::Data(part.clone())).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "data-weather",
+                "id": "w1",
+                "data": { "city": "Paris" },
+                "transient": true
+            })
+        );
+        match serde_json::from_value::<VercelUIStream>(json).unwrap() {
+            VercelUIStream::Data(parsed) => assert_eq!(parsed, part),
+            other => panic!("Expected data part, got {other:?}"),
+        }
+    }
+
+    #[cfg(feature = "language-model-request")]
+    #[test]
+    fn test_ui_stream_state_wraps_parts_and_reports_usage() {
+        use crate::core::LanguageModelStreamChunkType;
+        use crate::core::language_model::Usage;
+
+        let mut state = UIStreamState::new(VercelUIStreamOptions {
+            send_reasoning: true,
+            send_sources: false,
+            send_start: true,
+            send_finish: true,
+            generate_message_id: Some(Box::new(|| "msg".to_string())),
+            message_metadata: Some(serde_json::json!({ "model": "mock" })),
+            data_parts: vec![VercelUIDataPart::new(
+                "status",
+                serde_json::json!("started"),
+            )],
+        });
+
+        let chunks: Vec<VercelUIStream> = [
+            Some(LanguageModelStreamChunkType::Start),
+            Some(LanguageModelStreamChunkType::Reasoning("Hmm".to_string())),
+            Some(LanguageModelStreamChunkType::Text("Hel".to_string())),
+            Some(LanguageModelStreamChunkType::Text("lo".to_string())),
+            Some(LanguageModelStreamChunkType::Usage(Usage {
+                input_tokens: Some(3),
+                output_tokens: Some(2),
+                ..Default::default()
+            })),
+            None,
+        ]
+        .into_iter()
+        .flat_map(|chunk| state.convert(chunk))
+        .collect();
+
+        let types: Vec<String> = chunks
+            .iter()
+            .map(|c| {
+                serde_json::to_value(c).unwrap()["type"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(
+            types,
+            [
+                "start",
+                "data-status",
+                "reasoning-start",
+                "reasoning-delta",
+                "reasoning-end",
+                "text-start",
+                "text-delta",
+                "text-delta",
+                "text-end",
+                "finish"
+            ]
+        );
+
+        let finish = serde_json::to_value(chunks.last().unwrap()).unwrap();
+        assert_eq!(finish["messageMetadata"]["model"], "mock");
+        assert_eq!(finish["messageMetadata"]["usage"]["input_tokens"], 3);
+        assert_eq!(finish["messageMetadata"]["usage"]["output_tokens"], 2);
+    }
+
+    #[cfg(feature = "language-model-request")]
+    #[test]
+    fn test_ui_stream_state_sends_sources_once() {
+        use crate::core::LanguageModelStreamChunkType;
+        use crate::core::language_model::sources::{Citation, Source};
+
+        let page = Source::Url {
+            id: "src_1".to_string(),
+            url: "https://example.com".to_string(),
+            title: Some("Example".to_string()),
+        };
+        let document = Source::Document {
+            id: "document-0".to_string(),
+            title: "Report".to_string(),
+            media_type: "application/pdf".to_string(),
+            filename: None,
+        };
+        let chunks = [
+            LanguageModelStreamChunkType::Source(page.clone()),
+            LanguageModelStreamChunkType::Citation(Citation {
+                cited_text: "Hello".to_string(),
+                source: page,
+            }),
+            LanguageModelStreamChunkType::Citation(Citation {
+                cited_text: "World".to_string(),
+                source: document,
+            }),
+        ];
+
+        let mut state = UIStreamState::new(VercelUIStreamOptions::default());
+        assert!(
+            chunks
+                .iter()
+                .flat_map(|chunk| state.convert(Some(chunk.clone())))
+                .next()
+                .is_none()
+        );
+
+        let mut state = UIStreamState::new(VercelUIStreamOptions {
+            send_sources: true,
+            ..Default::default()
+        });
+        let ui_chunks: Vec<VercelUIStream> = chunks
+            .into_iter()
+            .flat_map(|chunk| state.convert(Some(chunk)))
+            .collect();
+        let json: Vec<Value> = ui_chunks
+            .iter()
+            .map(|chunk| serde_json::to_value(chunk).unwrap())
+            .collect();
+        assert_eq!(json.len(), 2);
+        assert_eq!(json[0]["type"], "source-url");
+        assert_eq!(json[0]["sourceId"], "src_1");
+        assert_eq!(json[0]["url"], "https://example.com");
+        assert_eq!(json[1]["type"], "source-document");
+        assert_eq!(json[1]["mediaType"], "application/pdf");
+
+        let mut message = VercelUIMessage::assistant();
+        ui_chunks.into_iter().for_each(|chunk| message.apply(chunk));
+        assert_eq!(message.parts[0].part_type, "source-url");
+        assert_eq!(message.parts[0].url.as_deref(), Some("https://example.com"));
+        assert_eq!(message.parts[1].part_type, "source-document");
+        assert_eq!(message.parts[1].title.as_deref(), Some("Report"));
+    }
+
+    #[cfg(feature = "language-model-request")]
+    #[tokio::test]
+    async fn test_into_sse_bytes_frames_ui_chunks() {
+        use crate::core::language_model::request::LanguageModelRequest;
+        use crate::providers::MockLanguageModel;
+
+        let response = LanguageModelRequest::builder()
+            .model(MockLanguageModel::new().with_text("Hello"))
+            .prompt("Hi")
+            .build()
+            .stream_text()
+            .await
+            .unwrap();
+        let body: Vec<u8> = response
+            .into_sse_bytes()
+            .map(|bytes| bytes.unwrap().to_vec())
+            .concat()
+            .await;
+        let body = String::from_utf8(body).unwrap();
+
+        let events: Vec<&str> = body.split("\n\n").filter(|e| !e.is_empty()).collect();
+        assert!(events.iter().all(|e| e.starts_with("data: ")));
+        assert!(events[0].contains("\"type\":\"start\""));
+        assert!(body.contains("{\"type\":\"text-delta\",\"id\":"));
+        assert!(body.contains("\"delta\":\"Hello\""));
+        assert_eq!(events.last(), Some(&"data: [DONE]"));
+    }
+
+    #[cfg(feature = "language-model-request")]
+    #[test]
+    fn test_ui_stream_state_maps_tool_calls() {
+        use crate::core::LanguageModelStreamChunkType;
+        use crate::core::language_model::ToolCallDelta;
+        use crate::core::tools::{ToolCallInfo, ToolResultInfo};
+
+        let mut state = UIStreamState::new(VercelUIStreamOptions::default());
+
+        let mut call = ToolCallInfo::new("get_weather");
+        call.id("call_1");
+        call.input(serde_json::json!({ "city": "Paris" }));
+        let mut result = ToolResultInfo::new("get_weather");
+        result.id("call_1");
+        result.output(serde_json::json!("Sunny"));
+
+        let chunks: Vec<serde_json::Value> = [
+            LanguageModelStreamChunkType::Text("Checking".to_string()),
+            LanguageModelStreamChunkType::ToolCall(ToolCallDelta {
+                id: "call_1".to_string(),
+                name: "get_weather".to_string(),
+                delta: "{\"city\":".to_string(),
+            }),
+            LanguageModelStreamChunkType::ToolCall(ToolCallDelta {
+                id: "call_1".to_string(),
+                name: "get_weather".to_string(),
+                delta: "\"Paris\"}".to_string(),
+            }),
+            LanguageModelStreamChunkType::ToolCallAvailable(call),
+            LanguageModelStreamChunkType::ToolResult(result),
+        ]
+        .into_iter()
+        .flat_map(|chunk| state.convert(Some(chunk)))
+        .map(|chunk| serde_json::to_value(chunk).unwrap())
+        .collect();
+
+        let types: Vec<&str> = chunks.iter().map(|c| c["type"].as_str().unwrap()).collect();
+        assert_eq!(
+            types,
+            [
+                "text-start",
+                "text-delta",
+                "text-end",
+                "tool-input-start",
+                "tool-input-delta",
+                "tool-input-delta",
+                "tool-input-available",
+                "tool-output-available"
+            ]
+        );
+        assert_eq!(chunks[3]["toolCallId"], "call_1");
+        assert_eq!(chunks[3]["toolName"], "get_weather");
+        assert_eq!(chunks[6]["input"]["city"], "Paris");
+        assert_eq!(chunks[7]["output"], "Sunny");
+    }
+
+    #[test]
+    fn test_vercel_ui_message_apply_builds_parts() {
+        let chunks = serde_json::json!([
+            { "type": "start", "messageId": "msg_1" },
+            { "type": "reasoning-start", "id": "r" },
+            { "type": "reasoning-delta", "id": "r", "delta": "Need " },
+            { "type": "reasoning-delta", "id": "r", "delta": "weather" },
+            { "type": "reasoning-end", "id": "r" },
+            { "type": "tool-input-start", "toolCallId": "call_1", "toolName": "get_weather" },
+            { "type": "tool-input-delta", "toolCallId": "call_1", "inputTextDelta": "{}" },
+            { "type": "tool-input-available", "toolCallId": "call_1", "toolName": "get_weather", "input": { "city": "Paris" } },
+            { "type": "tool-output-available", "toolCallId": "call_1", "output": "Sunny" },
+            { "type": "text-start", "id": "t" },
+            { "type": "text-delta", "id": "t", "delta": "It is sunny." },
+            { "type": "finish" }
+        ]);
+
+        let mut message = VercelUIMessage::assistant();
+        let mut states = Vec::new();
+        for chunk in serde_json::from_value::<Vec<VercelUIStream>>(chunks).unwrap() {
+            message.apply(chunk);
+            if let Some(part) = message
+                .parts
+                .iter()
+                .find(|p| p.part_type == "tool-get_weather")
+            {
+                states.push(part.state.clone().unwrap());
+            }
+        }
+        states.dedup();
+
+        assert_eq!(message.id, "msg_1");
+        assert_eq!(
+            states,
+            ["input-streaming", "input-available", "output-available"]
+        );
+        assert_eq!(message.parts.len(), 3);
+        assert_eq!(message.parts[0].part_type, "reasoning");
+        assert_eq!(message.parts[0].text, "Need weather");
+        assert_eq!(message.parts[0].state.as_deref(), Some("done"));
+        assert_eq!(
+            message.parts[1].input,
+            Some(serde_json::json!({ "city": "Paris" }))
+        );
+        assert_eq!(message.parts[1].output, Some(serde_json::json!("Sunny")));
+        assert_eq!(message.parts[2].text, "It is sunny.");
+        assert_eq!(message.parts[2].state.as_deref(), Some("streaming"));
+
+        // The accumulated message converts back into core messages
+        let messages = crate::core::Message::from_vercel_ui_message(&[message]);
+        assert_eq!(messages.len(), 4);
+        assert!(matches!(
+            &messages[0],
+            crate::core::Message::Assistant(m)
+                if matches!(m.content, LanguageModelResponseContentType::Reasoning { .. })
+        ));
+    }
+
+    #[test]
+    fn test_from_vercel_ui_message_with_tool_parts() {
+        let request: VercelUIRequest = serde_json::from_value(serde_json::json!({
+            "id": "chat_1",
+            "trigger": "submit-message",
+            "messages": [
+                { "id": "1", "role": "user", "parts": [{ "type": "text", "text": "Weather in Paris?" }] },
+                { "id": "2", "role": "assistant", "parts": [
+                    { "type": "step-start" },
+                    { "type": "text", "text": "Let me check." },
+                    {
+                        "type": "tool-get_weather",
+                        "toolCallId": "call_1",
+                        "state": "output-available",
+                        "input": { "city": "Paris" },
+                        "output": "Sunny"
+                    },
+                    {
+                        "type": "dynamic-tool",
+                        "toolName": "get_time",
+                        "toolCallId": "call_2",
+                        "state": "output-error",
+                        "input": {},
+                        "errorText": "Timeout"
+                    },
+                    { "type": "tool-get_weather", "toolCallId": "call_3", "state": "input-available" },
+                    { "type": "text", "text": "It is sunny." }
+                ]}
+            ]
+        }))
+        .unwrap();
+
+        let messages: Vec<Message> = request.into();
+        assert_eq!(messages.len(), 7);
+        assert!(matches!(&messages[0], Message::User(_)));
+        assert!(matches!(
+            &messages[1],
+            Message::Assistant(m) if matches!(&m.content, LanguageModelResponseContentType::Text(t) if t == "Let me check.")
+        ));
+        match &messages[2] {
+            Message::Assistant(m) => match &m.content {
+                LanguageModelResponseContentType::ToolCall(call) => {
+                    assert_eq!(call.tool.name, "get_weather");
+                    assert_eq!(call.tool.id, "call_1");
+                    assert_eq!(call.input, serde_json::json!({ "city": "Paris" }));
+                }
+                other => panic!("Expected tool call, got {other:?}"),
+            },
+            other => panic!("Expected assistant message, got {other:?}"),
+        }
+        match &messages[3] {
+            Message::Tool(result) => {
+                assert_eq!(result.tool.id, "call_1");
+                assert_eq!(result.output, Ok(serde_json::json!("Sunny")));
+            }
+            other => panic!("Expected tool result, got {other:?}"),
+        }
+        match &messages[5] {
+            Message::Tool(result) => {
+                assert_eq!(result.tool.name, "get_time");
+                assert!(result.output.is_err());
+            }
+            other => panic!("Expected tool result, got {other:?}"),
+        }
+        // The call without an output is dropped
+        assert!(matches!(
+            &messages[6],
+            Message::Assistant(m) if matches!(&m.content, LanguageModelResponseContentType::Text(t) if t == "It is sunny.")
+        ));
+    }
+
+    #[test]
+    fn test_ui_messages_round_trip_through_messages() {
+        let ui_messages: Vec<VercelUIMessage> = serde_json::from_value(serde_json::json!([
+            { "id": "1", "role": "user", "parts": [
+                { "type": "text", "text": "Describe this.", "state": "done" },
+                {
+                    "type": "file",
+                    "mediaType": "application/pdf",
+                    "filename": "report.pdf",
+                    "url": "https://example.com/report.pdf"
+                }
+            ]},
+            { "id": "2", "role": "assistant", "parts": [
+                { "type": "reasoning", "text": "Reading the report.", "state": "done" },
+                {
+                    "type": "tool-summarize",
+                    "toolCallId": "call_1",
+                    "state": "output-available",
+                    "input": { "pages": 3 },
+                    "output": "Sales grew."
+                },
+                { "type": "text", "text": "Sales grew.", "state": "done" }
+            ]}
+        ]))
+        .unwrap();
+
+        let messages = Message::from_vercel_ui_message(&ui_messages);
+        assert_eq!(messages.len(), 5);
+        match &messages[0] {
+            Message::User(user) => {
+                assert_eq!(user.content, "Describe this.");
+                assert_eq!(
+                    user.files[0].uri.as_deref(),
+                    Some("https://example.com/report.pdf")
+                );
+                assert_eq!(user.files[0].mime_type.as_deref(), Some("application/pdf"));
+            }
+            other => panic!("Expected user message, got {other:?}"),
+        }
+        assert!(matches!(
+            &messages[1],
+            Message::Assistant(m) if matches!(&m.content, LanguageModelResponseContentType::Reasoning { content, .. } if content == "Reading the report.")
+        ));
+
+        let round_trip = VercelUIMessage::from_messages(&messages);
+        assert_eq!(round_trip.len(), 2);
+        assert!(
+            round_trip
+                .iter()
+                .all(|message| message.id.starts_with("msg_"))
+        );
+        for (ui_message, expected) in round_trip.iter().zip(&ui_messages) {
+            assert_eq!(ui_message.role, expected.role);
+            assert_eq!(ui_message.parts, expected.parts);
+        }
+    }
+}
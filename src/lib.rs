@@ -3,6 +3,9 @@
 //! `aisdk` is An open-source Rust library for building AI-powered applications, inspired by the Vercel AI SDK.
 //! It provides a type-safe interface for interacting with Large Language Models (LLMs).
 
+#[cfg(all(target_arch = "wasm32", not(feature = "wasm")))]
+compile_error!("building for wasm32 requires the `wasm` feature");
+
 pub mod core;
 pub mod error;
 pub mod extensions;
@@ -14,8 +17,14 @@ pub mod providers;
 // re-exports
 pub use error::{Error, Result};
 
-/// Re-exports the `aisdk_macros::tool` macro for convenient tool definition.
+/// The HTTP client library of the providers, to build the clients passed to their
+/// `http_client` builder methods.
+pub use reqwest;
+
+/// Re-exports the `aisdk_macros` macros for convenient tool and prompt definition.
 pub mod macros {
+    #[cfg(feature = "prompt")]
+    pub use aisdk_macros::prompt;
     pub use aisdk_macros::tool;
 }
 
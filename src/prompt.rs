@@ -2,12 +2,20 @@
 //!
 //! This module provides utilities for managing and rendering prompt templates
 //! using the Tera templating engine.
+//!
+//! [`PromptEnv`] loads templates from files, while [`PromptTemplate`] is built
+//! from a string, either at runtime or with the [`prompt!`](crate::macros::prompt) macro,
+//! which checks the template at compile time.
 
 use crate::error::{Error, Result};
 use derive_builder::Builder;
+use serde::Serialize;
 use std::{collections::HashMap, path::PathBuf};
 use tera::{Context, Tera};
 
+/// Name of the main template of a [`PromptTemplate`].
+const MAIN_TEMPLATE: &str = "__prompt__";
+
 /// A lightweight wrapper around the Tera templating engine.
 pub struct PromptEnv {
     template: Tera,
@@ -50,6 +58,99 @@ impl PromptEnv {
     }
 }
 
+/// A prompt template with named variables, conditionals, loops and partials.
+///
+/// Templates use the Tera syntax: `{{ name }}` for variables, `{% if premium %}`
+/// and `{% for item in items %}` for conditionals and loops, and
+/// `{% include "partial" %}` for partials registered with
+/// [`partial`](Self::partial). Variables are given as any serializable value,
+/// such as a struct, so they can be typed.
+///
+/// # Examples
+///
+/// ```rust
+/// use aisdk::prompt::PromptTemplate;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Order {
+///     customer: String,
+///     items: Vec<String>,
+/// }
+///
+/// let template = PromptTemplate::new(
+///     "{% include \"greeting\" %} Summarize the order:{% for item in items %} {{ item }}{% endfor %}",
+/// )
+/// .unwrap()
+/// .partial("greeting", "You are helping {{ customer }}.")
+/// .unwrap();
+///
+/// let order = Order {
+///     customer: "Ada".to_string(),
+///     items: vec!["tea".to_string(), "scones".to_string()],
+/// };
+/// assert_eq!(
+///     template.render(&order).unwrap(),
+///     "You are helping Ada. Summarize the order: tea scones"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct PromptTemplate {
+    source: String,
+    tera: Tera,
+}
+
+impl PromptTemplate {
+    /// Creates a template from its source.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PromptError`] if the template is not valid.
+    pub fn new(source: impl Into<String>) -> Result<Self> {
+        let source = source.into();
+        let mut tera = Tera::default();
+        tera.add_raw_template(MAIN_TEMPLATE, &source)
+            .map_err(|e| Error::PromptError(format!("error parsing template: {e:?}")))?;
+        Ok(Self { source, tera })
+    }
+
+    /// Registers a partial, included in the template with `{% include "name" %}`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PromptError`] if the partial is not valid.
+    pub fn partial(mut self, name: impl Into<String>, source: impl AsRef<str>) -> Result<Self> {
+        self.tera
+            .add_raw_template(&name.into(), source.as_ref())
+            .map_err(|e| Error::PromptError(format!("error parsing partial: {e:?}")))?;
+        Ok(self)
+    }
+
+    /// Returns the source of the template.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Renders the template with the given variables.
+    ///
+    /// # Parameters
+    ///
+    /// * `variables` - A value serializing to a map of variables, such as a struct or a `HashMap`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PromptError`] if the variables are not a map, or if a
+    /// variable or partial used by the template is missing.
+    pub fn render(&self, variables: &impl Serialize) -> Result<String> {
+        let ctx = Context::from_serialize(variables)
+            .map_err(|e| Error::PromptError(format!("error creating variable context: {e:?}")))?;
+
+        self.tera
+            .render(MAIN_TEMPLATE, &ctx)
+            .map_err(|e| Error::PromptError(format!("error rendering prompt: {e:?}")))
+    }
+}
+
 /// A prompt template with variables for rendering.
 #[derive(Builder)]
 #[builder(pattern = "owned", setter(into), build_fn(error = "Error"))]
@@ -82,6 +183,49 @@ impl PromptBuilder {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_prompt_template_conditionals() {
+        let template = PromptTemplate::new(
+            "Hi {{ name }}.{% if premium %} Thanks for subscribing!{% endif %}",
+        )
+        .unwrap();
+
+        let vars = HashMap::from([("name", serde_json::json!("Ada")), ("premium", true.into())]);
+        assert_eq!(
+            template.render(&vars).unwrap(),
+            "Hi Ada. Thanks for subscribing!"
+        );
+
+        let vars = HashMap::from([
+            ("name", serde_json::json!("Bob")),
+            ("premium", false.into()),
+        ]);
+        assert_eq!(template.render(&vars).unwrap(), "Hi Bob.");
+    }
+
+    #[test]
+    fn test_prompt_template_errors() {
+        assert!(matches!(
+            PromptTemplate::new("{% if %}"),
+            Err(Error::PromptError(_))
+        ));
+
+        let template = PromptTemplate::new("{{ missing }} <b>").unwrap();
+        assert!(matches!(
+            template.render(&HashMap::<String, String>::new()),
+            Err(Error::PromptError(_))
+        ));
+
+        // Templates are not HTML escaped
+        let template = PromptTemplate::new("{{ text }}").unwrap();
+        assert_eq!(
+            template
+                .render(&HashMap::from([("text", "<b>&</b>")]))
+                .unwrap(),
+            "<b>&</b>"
+        );
+    }
+
     #[test]
     fn test_prompt_env_creation_with_examples_path() {
         // Test creating a PromptEnv with the examples/prompts path
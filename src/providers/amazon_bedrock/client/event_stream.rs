@@ -0,0 +1,157 @@
+//! Decoding of the binary `application/vnd.amazon.eventstream` framing used by
+//! the `ConverseStream` API.
+//!
+//! Each message is a prelude with the total and header lengths, the headers, a
+//! payload and a trailing checksum. Checksums are not verified since the
+//! transport is already protected by TLS.
+
+use crate::error::{Error, Result};
+
+/// A decoded event stream message.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Frame {
+    /// The `:event-type` header, or the `:exception-type` header of an error.
+    pub event_type: String,
+    /// The JSON payload of the message.
+    pub payload: String,
+}
+
+const PRELUDE_LENGTH: usize = 12;
+const CHECKSUM_LENGTH: usize = 4;
+
+fn read_u16(bytes: &[u8], at: usize) -> Option<usize> {
+    Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?) as usize)
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Option<usize> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?) as usize)
+}
+
+fn invalid(details: &str) -> Error {
+    Error::ApiError {
+        status_code: None,
+        details: format!("Invalid event stream message: {details}"),
+    }
+}
+
+/// Removes the first complete message from the buffer and decodes it.
+///
+/// Returns `Ok(None)` when the buffer does not hold a complete message yet.
+pub(crate) fn decode(buffer: &mut Vec<u8>) -> Result<Option<Frame>> {
+    let Some(total_length) = read_u32(buffer, 0) else {
+        return Ok(None);
+    };
+    if buffer.len() < total_length {
+        return Ok(None);
+    }
+    let headers_length = read_u32(buffer, 4).ok_or_else(|| invalid("truncated prelude"))?;
+    if PRELUDE_LENGTH + headers_length + CHECKSUM_LENGTH > total_length {
+        return Err(invalid("headers exceed the message length"));
+    }
+
+    let message: Vec<u8> = buffer.drain(..total_length).collect();
+    let headers = &message[PRELUDE_LENGTH..PRELUDE_LENGTH + headers_length];
+    let payload = &message[PRELUDE_LENGTH + headers_length..total_length - CHECKSUM_LENGTH];
+
+    let mut frame = Frame {
+        event_type: String::new(),
+        payload: String::from_utf8_lossy(payload).into_owned(),
+    };
+
+    let mut at = 0;
+    while at < headers.len() {
+        let name_length = headers[at] as usize;
+        let name = headers
+            .get(at + 1..at + 1 + name_length)
+            .ok_or_else(|| invalid("truncated header name"))?;
+        at += 1 + name_length;
+        let value_type = *headers.get(at).ok_or_else(|| invalid("truncated header"))?;
+        at += 1;
+
+        let value_length = match value_type {
+            0 | 1 => 0,
+            2 => 1,
+            3 => 2,
+            4 => 4,
+            5 | 8 => 8,
+            9 => 16,
+            6 | 7 => {
+                let length = read_u16(headers, at).ok_or_else(|| invalid("truncated header"))?;
+                at += 2;
+                length
+            }
+            other => return Err(invalid(&format!("unknown header type {other}"))),
+        };
+        let value = headers
+            .get(at..at + value_length)
+            .ok_or_else(|| invalid("truncated header value"))?;
+        at += value_length;
+
+        if value_type == 7 && matches!(name, b":event-type" | b":exception-type") {
+            frame.event_type = String::from_utf8_lossy(value).into_owned();
+        }
+    }
+
+    Ok(Some(frame))
+}
+
+/// Encodes a message with string headers. Checksums are left zeroed.
+#[cfg(test)]
+pub(crate) fn encode(message_type: &str, event_type: &str, payload: &str) -> Vec<u8> {
+    let type_header = if message_type == "exception" {
+        ":exception-type"
+    } else {
+        ":event-type"
+    };
+
+    let mut headers = Vec::new();
+    for (name, value) in [(":message-type", message_type), (type_header, event_type)] {
+        headers.push(name.len() as u8);
+        headers.extend_from_slice(name.as_bytes());
+        headers.push(7);
+        headers.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        headers.extend_from_slice(value.as_bytes());
+    }
+
+    let total_length = PRELUDE_LENGTH + headers.len() + payload.len() + CHECKSUM_LENGTH;
+    let mut message = Vec::with_capacity(total_length);
+    message.extend_from_slice(&(total_length as u32).to_be_bytes());
+    message.extend_from_slice(&(headers.len() as u32).to_be_bytes());
+    message.extend_from_slice(&[0; 4]);
+    message.extend_from_slice(&headers);
+    message.extend_from_slice(payload.as_bytes());
+    message.extend_from_slice(&[0; 4]);
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_split_messages() {
+        let mut bytes = encode("event", "messageStart", r#"{"role":"assistant"}"#);
+        bytes.extend(encode(
+            "exception",
+            "throttlingException",
+            r#"{"message":"Too many requests"}"#,
+        ));
+
+        // Feed the bytes in two parts that split the first message
+        let mut buffer = bytes[..10].to_vec();
+        assert_eq!(decode(&mut buffer).unwrap(), None);
+        buffer.extend_from_slice(&bytes[10..]);
+
+        assert_eq!(
+            decode(&mut buffer).unwrap(),
+            Some(Frame {
+                event_type: "messageStart".to_string(),
+                payload: r#"{"role":"assistant"}"#.to_string(),
+            })
+        );
+        let exception = decode(&mut buffer).unwrap().unwrap();
+        assert_eq!(exception.event_type, "throttlingException");
+        assert!(buffer.is_empty());
+        assert_eq!(decode(&mut buffer).unwrap(), None);
+    }
+}
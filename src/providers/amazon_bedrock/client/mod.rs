@@ -0,0 +1,345 @@
+//! Client implementation for the Amazon Bedrock provider.
+
+pub(crate) mod event_stream;
+pub(crate) mod signing;
+/// Type definitions for the Bedrock Converse API.
+pub mod types;
+
+pub(crate) use types::*;
+
+use crate::core::capabilities::ModelName;
+use crate::core::client::{
+    LanguageModelClient, request_error, shared_client, sse_error, status_error, vcr,
+    with_additional_query,
+};
+use crate::core::utils::join_url;
+use crate::error::{Error, Result};
+use crate::providers::amazon_bedrock::AmazonBedrock;
+use derive_builder::Builder;
+use futures::{Stream, StreamExt};
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+use reqwest_eventsource::Event;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::pin::Pin;
+
+/// A request to the `Converse` or `ConverseStream` API.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Builder)]
+#[builder(setter(into), build_fn(error = "Error"))]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BedrockOptions {
+    /// The model ID, which is part of the path instead of the body.
+    #[serde(skip)]
+    pub(crate) model: String,
+    #[builder(default)]
+    pub(crate) messages: Vec<BedrockMessage>,
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) system: Vec<BedrockSystemContent>,
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) inference_config: Option<BedrockInferenceConfig>,
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) tool_config: Option<BedrockToolConfig>,
+    /// Model specific parameters, such as the thinking budget of Claude models.
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) additional_model_request_fields: Option<serde_json::Value>,
+    /// Key-value pairs tagging the request in the invocation logs.
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) request_metadata: Option<HashMap<String, String>>,
+    /// Whether to use the `ConverseStream` API.
+    #[builder(default)]
+    #[serde(skip)]
+    pub(crate) stream: bool,
+    /// Whether the output is requested through the structured output tool.
+    #[builder(default)]
+    #[serde(skip)]
+    pub(crate) structured_output: bool,
+}
+
+impl BedrockOptions {
+    pub(crate) fn builder() -> BedrockOptionsBuilder {
+        BedrockOptionsBuilder::default()
+    }
+}
+
+/// The event type and JSON payload of a message of a streamed response.
+type EventMessage = (String, String);
+
+/// Parses a message of a streamed response from its event type and JSON payload.
+pub(crate) fn parse_event(event_type: &str, payload: &str) -> Result<BedrockStreamEvent> {
+    let invalid = |e: serde_json::Error| Error::ApiError {
+        status_code: None,
+        details: format!("Invalid JSON in stream: {e}"),
+    };
+    let payload: serde_json::Value = serde_json::from_str(payload).map_err(invalid)?;
+
+    if event_type.ends_with("Exception") {
+        return Ok(BedrockStreamEvent::Exception {
+            kind: event_type.to_string(),
+            message: payload
+                .get("message")
+                .and_then(|m| m.as_str())
+                .unwrap_or_default()
+                .to_string(),
+        });
+    }
+
+    serde_json::from_value(serde_json::json!({ event_type: payload })).map_err(invalid)
+}
+
+impl<M: ModelName> LanguageModelClient for AmazonBedrock<M> {
+    type Response = BedrockConverseResponse;
+    type StreamEvent = BedrockStreamEvent;
+
+    fn path(&self) -> String {
+        // Model IDs contain colons, which are encoded like the AWS SDKs do
+        let path = self.settings.path.clone().unwrap_or_else(|| {
+            format!("/model/{}/converse", self.options.model.replace(':', "%3A"))
+        });
+        if self.options.stream {
+            format!("{path}-stream")
+        } else {
+            path
+        }
+    }
+
+    fn method(&self) -> reqwest::Method {
+        reqwest::Method::POST
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        self.settings
+            .http_client
+            .clone()
+            .unwrap_or_else(shared_client)
+    }
+
+    fn headers(&self) -> reqwest::header::HeaderMap {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+        if !self.settings.api_key.is_empty() {
+            headers.insert(
+                AUTHORIZATION,
+                format!("Bearer {}", self.settings.api_key).parse().unwrap(),
+            );
+            return headers;
+        }
+
+        // Sign the request with the access keys
+        let Ok(url) = join_url(self.settings.base_url.as_str(), &self.path()) else {
+            return headers;
+        };
+        let host = match url.port() {
+            Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        let body = serde_json::to_vec(&self.options).unwrap_or_default();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let credentials = signing::Credentials {
+            access_key_id: &self.settings.access_key_id,
+            secret_access_key: &self.settings.secret_access_key,
+            session_token: self.settings.session_token.as_deref(),
+        };
+
+        let signed = signing::sign(
+            self.method().as_str(),
+            &url,
+            &[("content-type", "application/json"), ("host", &host)],
+            &body,
+            &credentials,
+            &self.settings.region,
+            "bedrock",
+            &signing::amz_date(now),
+        );
+
+        headers.insert("x-amz-date", signed.amz_date.parse().unwrap());
+        if let Some(token) = credentials.session_token
+            && let Ok(token) = token.parse()
+        {
+            headers.insert("x-amz-security-token", token);
+        }
+        if let Ok(authorization) = signed.authorization.parse() {
+            headers.insert(AUTHORIZATION, authorization);
+        }
+        headers
+    }
+
+    fn query_params(&self) -> Vec<(&str, &str)> {
+        Vec::new()
+    }
+
+    fn body(&self) -> reqwest::Body {
+        let body = serde_json::to_string(&self.options).unwrap();
+        reqwest::Body::from(body)
+    }
+
+    // ConverseStream uses the binary AWS event stream framing instead of
+    // server-sent events, see `send_and_stream_events`.
+    fn parse_stream_sse(
+        event: std::result::Result<Event, reqwest_eventsource::Error>,
+    ) -> Result<Self::StreamEvent> {
+        match event {
+            Ok(Event::Open) => Err(Error::Other("Unexpected stream open event".to_string())),
+            Ok(Event::Message(msg)) => parse_event(&msg.event, &msg.data),
+            Err(e) => Err(sse_error(e)),
+        }
+    }
+
+    fn end_stream(event: &Self::StreamEvent) -> bool {
+        matches!(
+            event,
+            BedrockStreamEvent::Metadata { .. } | BedrockStreamEvent::Exception { .. }
+        )
+    }
+}
+
+impl<M: ModelName> AmazonBedrock<M> {
+    /// Sends the `ConverseStream` request and decodes the event stream response.
+    ///
+    /// Like `send_and_stream`, streams are recorded to and replayed from the
+    /// current cassette, with the event type and JSON payload of each message.
+    pub(crate) async fn send_and_stream_events(
+        &self,
+        additional_headers: Option<HashMap<String, String>>,
+        additional_query: Option<HashMap<String, String>>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<BedrockStreamEvent>> + Send>>> {
+        let url = join_url(self.settings.base_url.as_str(), &self.path())?;
+        let mut headers = LanguageModelClient::headers(self);
+        if let Some(ref extra) = additional_headers
+            && let Ok(extra_map) = reqwest::header::HeaderMap::try_from(extra)
+        {
+            headers.extend(extra_map);
+        }
+
+        let method = self.method();
+        let body = self.body();
+        let cassette = vcr::current();
+        let request = cassette
+            .as_ref()
+            .map(|_| vcr::RecordedRequest::new(&method, &url, &body));
+
+        let messages: Pin<Box<dyn Stream<Item = Result<EventMessage>> + Send>> =
+            match (cassette, request) {
+                (Some(cassette), Some(request)) if cassette.is_replaying() => {
+                    let events = cassette.next_events(&request)?;
+                    Box::pin(futures::stream::iter(events).filter_map(|event| {
+                        futures::future::ready(match event {
+                            vcr::RecordedEvent::Open => None,
+                            vcr::RecordedEvent::Message { event, data } => Some(Ok((event, data))),
+                            vcr::RecordedEvent::Error {
+                                status,
+                                headers,
+                                details,
+                            } => Some(Err(
+                                match status.and_then(|s| reqwest::StatusCode::from_u16(s).ok()) {
+                                    Some(status) => status_error(
+                                        status,
+                                        &vcr::replay_headers(&headers),
+                                        details,
+                                    ),
+                                    None => Error::ApiError {
+                                        status_code: None,
+                                        details,
+                                    },
+                                },
+                            )),
+                        })
+                    }))
+                }
+                (cassette, request) => {
+                    let recorder = cassette.zip(request).map(|(c, r)| c.record_stream(r));
+
+                    let response = self
+                        .http_client()
+                        .request(method, url)
+                        .headers(headers)
+                        .query(&with_additional_query(
+                            self.query_params(),
+                            &additional_query,
+                        ))
+                        .body(body)
+                        .send()
+                        .await
+                        .map_err(request_error)?;
+
+                    let status = response.status();
+                    if !status.is_success() {
+                        let response_headers = response.headers().clone();
+                        let details = response.text().await.unwrap_or_default();
+                        if let Some(recorder) = &recorder {
+                            recorder.push(vcr::RecordedEvent::Error {
+                                status: Some(status.as_u16()),
+                                headers: vcr::record_headers(&response_headers),
+                                details: details.clone(),
+                            });
+                        }
+                        return Err(status_error(status, &response_headers, details));
+                    }
+
+                    Box::pin(event_stream_messages(response).inspect(move |message| {
+                        if let (Some(recorder), Ok((event, data))) = (&recorder, message) {
+                            recorder.push(vcr::RecordedEvent::Message {
+                                event: event.clone(),
+                                data: data.clone(),
+                            });
+                        }
+                    }))
+                }
+            };
+
+        // Stop after the final metadata event or the first error
+        let stream = messages
+            .map(|message| message.and_then(|(event, data)| parse_event(&event, &data)))
+            .scan(false, |ended, event| {
+                if *ended {
+                    return futures::future::ready(None);
+                }
+                *ended = event.as_ref().map_or(true, Self::end_stream);
+                futures::future::ready(Some(event))
+            });
+
+        Ok(Box::pin(stream))
+    }
+}
+
+/// Decodes the body of a response into the event types and payloads of its messages.
+fn event_stream_messages(
+    response: reqwest::Response,
+) -> impl Stream<Item = Result<EventMessage>> + Send {
+    futures::stream::unfold(
+        (Box::pin(response.bytes_stream()), Vec::new()),
+        |(mut bytes, mut buffer)| async move {
+            loop {
+                match event_stream::decode(&mut buffer) {
+                    Ok(Some(frame)) => {
+                        return Some((Ok((frame.event_type, frame.payload)), (bytes, buffer)));
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        buffer.clear();
+                        return Some((Err(e), (bytes, buffer)));
+                    }
+                }
+                match bytes.next().await {
+                    Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                    Some(Err(e)) => {
+                        let error = Error::ApiError {
+                            status_code: e.status(),
+                            details: format!("Failed to read stream: {e}"),
+                        };
+                        return Some((Err(error), (bytes, buffer)));
+                    }
+                    None => return None,
+                }
+            }
+        },
+    )
+}
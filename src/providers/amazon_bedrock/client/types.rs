@@ -0,0 +1,251 @@
+//! Type definitions for the Bedrock Converse API.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The role of a conversation message.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum BedrockRole {
+    #[default]
+    User,
+    Assistant,
+}
+
+/// A message of the conversation.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct BedrockMessage {
+    pub role: BedrockRole,
+    #[serde(default)]
+    pub content: Vec<BedrockContentBlock>,
+}
+
+/// A block of message content. Exactly one of the fields is set.
+///
+/// Blocks of kinds the provider does not handle, such as images or citations,
+/// deserialize with every field unset.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BedrockContentBlock {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_use: Option<BedrockToolUse>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_result: Option<BedrockToolResult>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_content: Option<BedrockReasoningContent>,
+}
+
+/// A tool call made by the model.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BedrockToolUse {
+    pub tool_use_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub input: Value,
+}
+
+/// The result of a tool call.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BedrockToolResult {
+    pub tool_use_id: String,
+    pub content: Vec<BedrockToolResultContent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+/// A block of tool result content.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct BedrockToolResultContent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json: Option<Value>,
+}
+
+/// Reasoning content of the model.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BedrockReasoningContent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_text: Option<BedrockReasoningText>,
+}
+
+/// The reasoning text and its signature.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct BedrockReasoningText {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+/// A block of the system prompt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct BedrockSystemContent {
+    pub text: String,
+}
+
+/// Inference parameters of a request.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BedrockInferenceConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_sequences: Option<Vec<String>>,
+}
+
+/// The tools available to the model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BedrockToolConfig {
+    pub tools: Vec<BedrockTool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<Value>,
+}
+
+/// A tool available to the model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BedrockTool {
+    pub tool_spec: BedrockToolSpec,
+}
+
+/// The definition of a tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BedrockToolSpec {
+    pub name: String,
+    pub description: String,
+    pub input_schema: BedrockInputSchema,
+}
+
+/// The JSON schema of the input of a tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct BedrockInputSchema {
+    pub json: Value,
+}
+
+/// Token usage of a request.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BedrockUsage {
+    #[serde(default)]
+    pub input_tokens: Option<usize>,
+    #[serde(default)]
+    pub output_tokens: Option<usize>,
+    #[serde(default)]
+    pub cache_read_input_tokens: Option<usize>,
+    #[serde(default)]
+    pub cache_write_input_tokens: Option<usize>,
+}
+
+/// The output of a `Converse` request.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct BedrockOutput {
+    #[serde(default)]
+    pub message: Option<BedrockMessage>,
+}
+
+/// A response of the `Converse` API.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BedrockConverseResponse {
+    #[serde(default)]
+    pub output: BedrockOutput,
+    #[serde(default)]
+    pub stop_reason: Option<String>,
+    #[serde(default)]
+    pub usage: Option<BedrockUsage>,
+}
+
+/// The start of a content block of a streamed response.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BedrockContentBlockStart {
+    #[serde(default)]
+    pub tool_use: Option<BedrockToolUseStart>,
+}
+
+/// The ID and name of a streamed tool call.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BedrockToolUseStart {
+    pub tool_use_id: String,
+    pub name: String,
+}
+
+/// A chunk of a content block of a streamed response.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BedrockContentBlockDelta {
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub tool_use: Option<BedrockToolUseDelta>,
+    #[serde(default)]
+    pub reasoning_content: Option<BedrockReasoningDelta>,
+}
+
+/// A chunk of the JSON input of a streamed tool call.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct BedrockToolUseDelta {
+    #[serde(default)]
+    pub input: String,
+}
+
+/// A chunk of streamed reasoning content.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct BedrockReasoningDelta {
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+/// An event of a `ConverseStream` response, keyed by its event type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum BedrockStreamEvent {
+    MessageStart {},
+    #[serde(rename_all = "camelCase")]
+    ContentBlockStart {
+        #[serde(default)]
+        content_block_index: usize,
+        #[serde(default)]
+        start: BedrockContentBlockStart,
+    },
+    #[serde(rename_all = "camelCase")]
+    ContentBlockDelta {
+        #[serde(default)]
+        content_block_index: usize,
+        #[serde(default)]
+        delta: BedrockContentBlockDelta,
+    },
+    #[serde(rename_all = "camelCase")]
+    ContentBlockStop {
+        #[serde(default)]
+        content_block_index: usize,
+    },
+    #[serde(rename_all = "camelCase")]
+    MessageStop {
+        #[serde(default)]
+        stop_reason: Option<String>,
+    },
+    Metadata {
+        #[serde(default)]
+        usage: Option<BedrockUsage>,
+    },
+    /// The service reported an error in the middle of the stream.
+    Exception {
+        kind: String,
+        message: String,
+    },
+}
@@ -0,0 +1,337 @@
+//! Conversions between types used by the Amazon Bedrock provider and the types used by the core library.
+
+use crate::core::Message;
+use crate::core::language_model::{
+    LanguageModelOptions, LanguageModelResponseContentType, ReasoningEffort, Usage,
+};
+use crate::core::tools::ToolChoice;
+use crate::providers::amazon_bedrock::client::{
+    BedrockContentBlock, BedrockInferenceConfig, BedrockInputSchema, BedrockMessage,
+    BedrockOptions, BedrockReasoningContent, BedrockReasoningText, BedrockRole,
+    BedrockSystemContent, BedrockTool, BedrockToolConfig, BedrockToolResult,
+    BedrockToolResultContent, BedrockToolSpec, BedrockToolUse, BedrockUsage,
+};
+use crate::providers::amazon_bedrock::extensions;
+
+/// The name of the tool used to request structured output, since the Converse
+/// API has no response format parameter.
+pub(crate) const JSON_TOOL_NAME: &str = "json";
+
+/// Removes the `$schema` keyword, which Bedrock does not accept.
+fn input_schema(mut schema: serde_json::Value) -> BedrockInputSchema {
+    if let Some(obj) = schema.as_object_mut() {
+        obj.remove("$schema");
+    }
+    BedrockInputSchema { json: schema }
+}
+
+impl From<LanguageModelOptions> for BedrockOptions {
+    fn from(options: LanguageModelOptions) -> Self {
+        let mut system = Vec::new();
+        let mut messages: Vec<BedrockMessage> = Vec::new();
+
+        if let Some(text) = options.system
+            && !text.is_empty()
+        {
+            system.push(BedrockSystemContent { text });
+        }
+
+        for msg in options.messages {
+            let (role, block) = match msg.message {
+                Message::System(s) => {
+                    if !s.content.is_empty() {
+                        system.push(BedrockSystemContent { text: s.content });
+                    }
+                    continue;
+                }
+                Message::Developer(text) => {
+                    system.push(BedrockSystemContent { text });
+                    continue;
+                }
+                Message::User(u) => (
+                    BedrockRole::User,
+                    BedrockContentBlock {
+                        text: Some(u.content),
+                        ..Default::default()
+                    },
+                ),
+                Message::Assistant(a) => match a.content {
+                    LanguageModelResponseContentType::Text(text) => (
+                        BedrockRole::Assistant,
+                        BedrockContentBlock {
+                            text: Some(text),
+                            ..Default::default()
+                        },
+                    ),
+                    LanguageModelResponseContentType::ToolCall(call) => (
+                        BedrockRole::Assistant,
+                        BedrockContentBlock {
+                            tool_use: Some(BedrockToolUse {
+                                tool_use_id: call.tool.id,
+                                name: call.tool.name,
+                                input: call.input,
+                            }),
+                            ..Default::default()
+                        },
+                    ),
+                    LanguageModelResponseContentType::Reasoning {
+                        content,
+                        extensions,
+                    } => {
+                        let signature = extensions
+                            .get::<extensions::BedrockReasoningMetadata>()
+                            .signature
+                            .clone();
+                        (
+                            BedrockRole::Assistant,
+                            BedrockContentBlock {
+                                reasoning_content: Some(BedrockReasoningContent {
+                                    reasoning_text: Some(BedrockReasoningText {
+                                        text: content,
+                                        signature,
+                                    }),
+                                }),
+                                ..Default::default()
+                            },
+                        )
+                    }
+                    LanguageModelResponseContentType::Source(_)
+                    | LanguageModelResponseContentType::Citation(_)
+                    | LanguageModelResponseContentType::NotSupported(_) => continue,
+                },
+                Message::Tool(result) => {
+                    let (content, status) = match result.output {
+                        Ok(serde_json::Value::String(text)) => (
+                            BedrockToolResultContent {
+                                text: Some(text),
+                                json: None,
+                            },
+                            None,
+                        ),
+                        Ok(json @ serde_json::Value::Object(_)) => (
+                            BedrockToolResultContent {
+                                text: None,
+                                json: Some(json),
+                            },
+                            None,
+                        ),
+                        Ok(other) => (
+                            BedrockToolResultContent {
+                                text: Some(other.to_string()),
+                                json: None,
+                            },
+                            None,
+                        ),
+                        Err(e) => (
+                            BedrockToolResultContent {
+                                text: Some(e.to_string()),
+                                json: None,
+                            },
+                            Some("error".to_string()),
+                        ),
+                    };
+                    (
+                        BedrockRole::User,
+                        BedrockContentBlock {
+                            tool_result: Some(BedrockToolResult {
+                                tool_use_id: result.tool.id,
+                                content: vec![content],
+                                status,
+                            }),
+                            ..Default::default()
+                        },
+                    )
+                }
+            };
+
+            // Roles must alternate, so consecutive blocks of a role share a message
+            match messages.last_mut() {
+                Some(last) if last.role == role => last.content.push(block),
+                _ => messages.push(BedrockMessage {
+                    role,
+                    content: vec![block],
+                }),
+            }
+        }
+
+        let mut tools: Vec<BedrockTool> = options
+            .tools
+            .map(|tools| {
+                tools
+                    .tools
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .iter()
+                    .map(|tool| BedrockTool {
+                        tool_spec: BedrockToolSpec {
+                            name: tool.name.clone(),
+                            description: tool.description.clone(),
+                            input_schema: input_schema(tool.input_schema.clone().to_value()),
+                        },
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut tool_choice = match options.tool_choice {
+            _ if tools.is_empty() => None,
+            Some(ToolChoice::Required) => Some(serde_json::json!({ "any": {} })),
+            Some(ToolChoice::Tool(name)) => Some(serde_json::json!({ "tool": { "name": name } })),
+            // The Converse API can not prevent tool calls, so the tools are not sent
+            Some(ToolChoice::None) => {
+                tools.clear();
+                None
+            }
+            Some(ToolChoice::Auto) | None => None,
+        };
+
+        // Structured output is requested through a tool whose input is the output
+        let structured_output = options.schema.is_some();
+        if let Some(schema) = options.schema {
+            if tools.is_empty() {
+                tool_choice = Some(serde_json::json!({ "tool": { "name": JSON_TOOL_NAME } }));
+            }
+            tools.push(BedrockTool {
+                tool_spec: BedrockToolSpec {
+                    name: JSON_TOOL_NAME.to_string(),
+                    description: "Respond with a JSON object.".to_string(),
+                    input_schema: input_schema(serde_json::to_value(schema).unwrap_or_default()),
+                },
+            });
+        }
+
+        // Extended thinking of Claude models, with the budgets of the Anthropic provider
+        let mut max_tokens = options.max_output_tokens;
+        let budget_tokens = match (options.thinking_budget, options.reasoning_effort) {
+            (Some(budget), _) => {
+                max_tokens.get_or_insert(budget + 4096);
+                Some(budget)
+            }
+            (None, Some(effort)) => {
+                let max = *max_tokens.get_or_insert(10_000);
+                Some(match effort {
+                    ReasoningEffort::Low => max / 4,
+                    ReasoningEffort::Medium => max / 2,
+                    ReasoningEffort::High => max - max / 4,
+                })
+            }
+            (None, None) => None,
+        };
+        let additional_model_request_fields = budget_tokens.map(|budget_tokens| {
+            serde_json::json!({
+                "thinking": { "type": "enabled", "budget_tokens": budget_tokens }
+            })
+        });
+
+        BedrockOptions {
+            model: String::new(),
+            messages,
+            system,
+            inference_config: Some(BedrockInferenceConfig {
+                max_tokens,
+                temperature: options.temperature,
+                top_p: options.top_p,
+                stop_sequences: options.stop_sequences,
+            }),
+            tool_config: (!tools.is_empty()).then_some(BedrockToolConfig { tools, tool_choice }),
+            additional_model_request_fields,
+            request_metadata: options.request_metadata,
+            stream: false,
+            structured_output,
+        }
+    }
+}
+
+/// Bedrock reports cache reads and writes separately from the input tokens,
+/// which are added back so the input tokens cover the whole prompt.
+impl From<&BedrockUsage> for Usage {
+    fn from(usage: &BedrockUsage) -> Self {
+        Self {
+            input_tokens: usage.input_tokens.map(|input| {
+                input
+                    + usage.cache_read_input_tokens.unwrap_or(0)
+                    + usage.cache_write_input_tokens.unwrap_or(0)
+            }),
+            output_tokens: usage.output_tokens,
+            reasoning_tokens: None,
+            cached_tokens: usage.cache_read_input_tokens,
+            cache_write_tokens: usage.cache_write_input_tokens,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::messages::TaggedMessage;
+    use crate::core::tools::{ToolCallInfo, ToolDetails, ToolResultInfo};
+    use crate::core::{AssistantMessage, UserMessage};
+
+    #[test]
+    fn test_options_to_converse_request() {
+        let mut first = ToolCallInfo::new("get_weather");
+        first.id("tooluse_0");
+        first.input(serde_json::json!({ "city": "Paris" }));
+        let mut second = ToolCallInfo::new("get_weather");
+        second.id("tooluse_1");
+        second.input(serde_json::json!({ "city": "Rome" }));
+
+        let result = |id: &str, output: &str| {
+            TaggedMessage::from(Message::Tool(ToolResultInfo {
+                tool: ToolDetails {
+                    id: id.to_string(),
+                    name: "get_weather".to_string(),
+                },
+                output: Ok(serde_json::Value::String(output.to_string())),
+            }))
+        };
+
+        let options = LanguageModelOptions {
+            system: Some("Be brief".to_string()),
+            temperature: Some(0.5),
+            max_output_tokens: Some(100),
+            messages: vec![
+                TaggedMessage::from(Message::User(UserMessage::new(
+                    "Weather in Paris and Rome?",
+                ))),
+                TaggedMessage::from(Message::Assistant(AssistantMessage {
+                    content: LanguageModelResponseContentType::ToolCall(first),
+                    usage: None,
+                })),
+                TaggedMessage::from(Message::Assistant(AssistantMessage {
+                    content: LanguageModelResponseContentType::ToolCall(second),
+                    usage: None,
+                })),
+                result("tooluse_0", "Sunny"),
+                result("tooluse_1", "Rainy"),
+            ],
+            ..Default::default()
+        };
+
+        let request = serde_json::to_value(BedrockOptions::from(options)).unwrap();
+        assert_eq!(
+            request,
+            serde_json::json!({
+                "messages": [
+                    { "role": "user", "content": [{ "text": "Weather in Paris and Rome?" }] },
+                    {
+                        "role": "assistant",
+                        "content": [
+                            { "toolUse": { "toolUseId": "tooluse_0", "name": "get_weather", "input": { "city": "Paris" } } },
+                            { "toolUse": { "toolUseId": "tooluse_1", "name": "get_weather", "input": { "city": "Rome" } } }
+                        ]
+                    },
+                    {
+                        "role": "user",
+                        "content": [
+                            { "toolResult": { "toolUseId": "tooluse_0", "content": [{ "text": "Sunny" }] } },
+                            { "toolResult": { "toolUseId": "tooluse_1", "content": [{ "text": "Rainy" }] } }
+                        ]
+                    }
+                ],
+                "system": [{ "text": "Be brief" }],
+                "inferenceConfig": { "maxTokens": 100, "temperature": 0.5 }
+            })
+        );
+    }
+}
@@ -0,0 +1,18 @@
+//! Amazon Bedrock-specific metadata for extensions.
+
+use crate::extensions::PersistedExtension;
+use serde::{Deserialize, Serialize};
+
+/// Metadata specific to reasoning content returned by the Converse API.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct BedrockReasoningMetadata {
+    /// The signature of the reasoning text.
+    ///
+    /// Models such as Claude require it to be sent back in subsequent turns
+    /// together with the reasoning text.
+    pub signature: Option<String>,
+}
+
+impl PersistedExtension for BedrockReasoningMetadata {
+    const KEY: &'static str = "bedrock.reasoning";
+}
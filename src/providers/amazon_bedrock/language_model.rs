@@ -0,0 +1,480 @@
+//! Language model implementation for the Amazon Bedrock provider.
+
+use crate::core::capabilities::CapabilitySet;
+use crate::core::capabilities::ModelName;
+use crate::core::client::LanguageModelClient;
+use crate::core::credentials::resolve_api_key;
+use crate::core::language_model::{
+    LanguageModel, LanguageModelOptions, LanguageModelResponse, LanguageModelResponseContentType,
+    LanguageModelStreamChunk, LanguageModelStreamChunkType, ProviderStream, ToolCallDelta, Usage,
+};
+use crate::core::messages::AssistantMessage;
+use crate::core::pricing::ModelPricing;
+use crate::core::tools::ToolCallInfo;
+use crate::error::Result;
+use crate::extensions::Extensions;
+use crate::providers::amazon_bedrock::AmazonBedrock;
+use crate::providers::amazon_bedrock::client::{
+    BedrockContentBlock, BedrockOptions, BedrockReasoningContent, BedrockReasoningText,
+    BedrockStreamEvent, BedrockToolUse,
+};
+use crate::providers::amazon_bedrock::conversions::JSON_TOOL_NAME;
+use crate::providers::amazon_bedrock::extensions;
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::collections::BTreeMap;
+
+fn reasoning(content: String, signature: Option<String>) -> LanguageModelResponseContentType {
+    let extensions = Extensions::default();
+    extensions
+        .get_mut::<extensions::BedrockReasoningMetadata>()
+        .signature = signature;
+    LanguageModelResponseContentType::Reasoning {
+        content,
+        extensions,
+    }
+}
+
+fn tool_call(id: String, name: String, input: serde_json::Value) -> ToolCallInfo {
+    let mut info = ToolCallInfo::new(name);
+    info.id(id);
+    info.input(input);
+    info
+}
+
+/// Converts the content blocks of a response. The input of the structured output
+/// tool is returned as text.
+fn contents(
+    blocks: Vec<BedrockContentBlock>,
+    structured_output: bool,
+) -> Vec<LanguageModelResponseContentType> {
+    let mut contents = Vec::new();
+    for block in blocks {
+        if let Some(text) = block.text {
+            contents.push(LanguageModelResponseContentType::Text(text));
+        } else if let Some(text) = block.reasoning_content.and_then(|r| r.reasoning_text) {
+            contents.push(reasoning(text.text, text.signature));
+        } else if let Some(tool_use) = block.tool_use {
+            if structured_output && tool_use.name == JSON_TOOL_NAME {
+                contents.push(LanguageModelResponseContentType::Text(
+                    tool_use.input.to_string(),
+                ));
+            } else {
+                contents.push(LanguageModelResponseContentType::ToolCall(tool_call(
+                    tool_use.tool_use_id,
+                    tool_use.name,
+                    tool_use.input,
+                )));
+            }
+        }
+    }
+    contents
+}
+
+/// A content block accumulated from a streamed response.
+#[derive(Debug, Clone)]
+enum StreamedBlock {
+    Text(String),
+    Reasoning {
+        text: String,
+        signature: Option<String>,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: String,
+    },
+}
+
+impl<M: ModelName> AmazonBedrock<M> {
+    /// Converts the core options and applies the model and provider settings.
+    fn request_options(&self, options: LanguageModelOptions, stream: bool) -> BedrockOptions {
+        let mut options: BedrockOptions = options.into();
+        options.model = self.options.model.clone();
+        options.stream = stream;
+
+        // Only Claude models accept the thinking parameters
+        if !options.model.contains("anthropic.") {
+            options.additional_model_request_fields = None;
+        }
+        options
+    }
+}
+
+#[async_trait]
+impl<M: ModelName> LanguageModel for AmazonBedrock<M> {
+    /// Returns the name of the model.
+    fn name(&self) -> String {
+        self.options.model.clone()
+    }
+
+    fn pricing(&self) -> Option<ModelPricing> {
+        M::PRICING
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        M::CONTEXT_WINDOW
+    }
+
+    fn max_output_tokens(&self) -> Option<usize> {
+        M::MAX_OUTPUT_TOKENS
+    }
+
+    fn capabilities(&self) -> CapabilitySet {
+        M::CAPABILITIES
+    }
+
+    /// Generates text using the `Converse` API.
+    async fn generate_text(
+        &mut self,
+        mut options: LanguageModelOptions,
+    ) -> Result<LanguageModelResponse> {
+        resolve_api_key(
+            &self.settings.credential_provider,
+            &mut self.settings.api_key,
+        )
+        .await?;
+        options.resolve_constrained_output("Amazon Bedrock", |_| false)?;
+        options.validate_sampling(0.0..=1.0)?;
+        let additional_headers = options.headers.clone();
+        let additional_query = options.query.clone();
+        let max_retries = options.max_retries;
+        self.options = self.request_options(options, false);
+
+        let (response, metadata) = self
+            .send_with_metadata(
+                self.settings.base_url.clone(),
+                additional_headers,
+                additional_query,
+                max_retries,
+            )
+            .await?;
+
+        let blocks = response
+            .output
+            .message
+            .map(|message| message.content)
+            .unwrap_or_default();
+
+        Ok(LanguageModelResponse {
+            contents: contents(blocks, self.options.structured_output),
+            usage: response.usage.as_ref().map(Usage::from),
+            id: None,
+            logprobs: None,
+            candidates: Vec::new(),
+            provider_metadata: None,
+            metadata: Some(metadata),
+        })
+    }
+
+    /// Streams text using the `ConverseStream` API.
+    async fn stream_text(&mut self, mut options: LanguageModelOptions) -> Result<ProviderStream> {
+        resolve_api_key(
+            &self.settings.credential_provider,
+            &mut self.settings.api_key,
+        )
+        .await?;
+        options.resolve_constrained_output("Amazon Bedrock", |_| false)?;
+        options.validate_sampling(0.0..=1.0)?;
+        let additional_headers = options.headers.clone();
+        let additional_query = options.query.clone();
+        self.options = self.request_options(options, true);
+        let structured_output = self.options.structured_output;
+
+        let response = self
+            .send_and_stream_events(additional_headers, additional_query)
+            .await?;
+
+        // The accumulated content blocks, by their index
+        let stream = response.scan(BTreeMap::new(), move |blocks, event| {
+            futures::future::ready(Some(match event {
+                Ok(BedrockStreamEvent::ContentBlockStart {
+                    content_block_index,
+                    start,
+                }) => {
+                    if let Some(tool_use) = start.tool_use {
+                        blocks.insert(
+                            content_block_index,
+                            StreamedBlock::ToolUse {
+                                id: tool_use.tool_use_id,
+                                name: tool_use.name,
+                                input: String::new(),
+                            },
+                        );
+                    }
+                    Ok(Vec::new())
+                }
+                Ok(BedrockStreamEvent::ContentBlockDelta {
+                    content_block_index,
+                    delta,
+                }) => {
+                    let mut chunks = Vec::new();
+
+                    if let Some(text) = delta.text {
+                        if let StreamedBlock::Text(content) = blocks
+                            .entry(content_block_index)
+                            .or_insert_with(|| StreamedBlock::Text(String::new()))
+                        {
+                            content.push_str(&text);
+                        }
+                        chunks.push(LanguageModelStreamChunk::Delta(
+                            LanguageModelStreamChunkType::Text(text),
+                        ));
+                    }
+
+                    if let Some(delta) = delta.reasoning_content {
+                        let block = blocks.entry(content_block_index).or_insert_with(|| {
+                            StreamedBlock::Reasoning {
+                                text: String::new(),
+                                signature: None,
+                            }
+                        });
+                        if let StreamedBlock::Reasoning { text, signature } = block {
+                            if let Some(delta) = delta.text {
+                                text.push_str(&delta);
+                                chunks.push(LanguageModelStreamChunk::Delta(
+                                    LanguageModelStreamChunkType::Reasoning(delta),
+                                ));
+                            }
+                            if delta.signature.is_some() {
+                                *signature = delta.signature;
+                            }
+                        }
+                    }
+
+                    if let Some(delta) = delta.tool_use
+                        && let Some(StreamedBlock::ToolUse { id, name, input }) =
+                            blocks.get_mut(&content_block_index)
+                    {
+                        input.push_str(&delta.input);
+                        // The structured output streams as text
+                        let chunk = if structured_output && name == JSON_TOOL_NAME {
+                            LanguageModelStreamChunkType::Text(delta.input)
+                        } else {
+                            LanguageModelStreamChunkType::ToolCall(ToolCallDelta {
+                                id: id.clone(),
+                                name: name.clone(),
+                                delta: delta.input,
+                            })
+                        };
+                        chunks.push(LanguageModelStreamChunk::Delta(chunk));
+                    }
+
+                    Ok(chunks)
+                }
+                Ok(BedrockStreamEvent::Metadata { usage }) => {
+                    let usage = usage.as_ref().map(Usage::from);
+                    let blocks = std::mem::take(blocks)
+                        .into_values()
+                        .map(|block| match block {
+                            StreamedBlock::Text(text) => BedrockContentBlock {
+                                text: Some(text),
+                                ..Default::default()
+                            },
+                            StreamedBlock::Reasoning { text, signature } => BedrockContentBlock {
+                                reasoning_content: Some(BedrockReasoningContent {
+                                    reasoning_text: Some(BedrockReasoningText { text, signature }),
+                                }),
+                                ..Default::default()
+                            },
+                            StreamedBlock::ToolUse { id, name, input } => BedrockContentBlock {
+                                tool_use: Some(BedrockToolUse {
+                                    tool_use_id: id,
+                                    name,
+                                    input: serde_json::from_str(&input)
+                                        .unwrap_or_else(|_| serde_json::json!({})),
+                                }),
+                                ..Default::default()
+                            },
+                        })
+                        .collect();
+
+                    Ok(contents(blocks, structured_output)
+                        .into_iter()
+                        .map(|content| {
+                            LanguageModelStreamChunk::Done(AssistantMessage {
+                                content,
+                                usage: usage.clone(),
+                            })
+                        })
+                        .collect())
+                }
+                Ok(BedrockStreamEvent::Exception { kind, message }) => {
+                    Ok(vec![LanguageModelStreamChunk::Delta(
+                        LanguageModelStreamChunkType::Failed(format!("{kind}: {message}")),
+                    )])
+                }
+                Ok(_) => Ok(Vec::new()),
+                Err(e) => Err(e),
+            }))
+        });
+
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DynamicModel;
+    use crate::core::client::test_server::{TestResponse, serve};
+    use crate::core::client::vcr::Cassette;
+    use crate::providers::amazon_bedrock::client::event_stream::encode;
+
+    /// Serves a single HTTP response on a local port and returns its address,
+    fn model(base_url: String) -> AmazonBedrock<DynamicModel> {
+        AmazonBedrock::<DynamicModel>::builder()
+            .model_name("anthropic.claude-3-5-haiku-20241022-v1:0")
+            .region("eu-west-1")
+            .base_url(base_url)
+            .api_key("")
+            .credentials("AKIDEXAMPLE", "secret")
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_signs_converse_request() {
+        let body = serde_json::json!({
+            "output": { "message": { "role": "assistant", "content": [
+                { "reasoningContent": { "reasoningText": { "text": "Hmm", "signature": "sig" } } },
+                { "text": "Hello" },
+                { "toolUse": { "toolUseId": "tooluse_0", "name": "get_weather", "input": { "city": "Paris" } } }
+            ] } },
+            "stopReason": "tool_use",
+            "usage": { "inputTokens": 5, "outputTokens": 3, "totalTokens": 8 }
+        });
+        let (base_url, mut request) = serve(vec![TestResponse::Body(
+            "application/json".to_string(),
+            body.to_string().into_bytes(),
+        )])
+        .await;
+        let mut model = model(base_url);
+
+        let response = model
+            .generate_text(LanguageModelOptions::default())
+            .await
+            .unwrap();
+
+        let request = request.recv().await.unwrap().to_lowercase();
+        assert!(request.starts_with(
+            "post /model/anthropic.claude-3-5-haiku-20241022-v1%3a0/converse http/1.1"
+        ));
+        assert!(request.contains("authorization: aws4-hmac-sha256 credential=akidexample/"));
+        assert!(request.contains("/eu-west-1/bedrock/aws4_request, signedheaders=content-type;host;x-amz-date, signature="));
+
+        assert_eq!(response.usage.unwrap().output_tokens, Some(3));
+        assert!(matches!(
+            &response.contents[0],
+            LanguageModelResponseContentType::Reasoning { content, extensions }
+                if content == "Hmm"
+                    && extensions.get::<extensions::BedrockReasoningMetadata>().signature.as_deref() == Some("sig")
+        ));
+        assert!(
+            matches!(&response.contents[1], LanguageModelResponseContentType::Text(t) if t == "Hello")
+        );
+        assert!(matches!(
+            &response.contents[2],
+            LanguageModelResponseContentType::ToolCall(call)
+                if call.tool.id == "tooluse_0" && call.input == serde_json::json!({ "city": "Paris" })
+        ));
+    }
+
+    async fn collect(stream: ProviderStream) -> Vec<String> {
+        stream
+            .map(|chunks| chunks.unwrap())
+            .concat()
+            .await
+            .into_iter()
+            .map(|chunk| match chunk {
+                LanguageModelStreamChunk::Delta(LanguageModelStreamChunkType::Text(t)) => {
+                    format!("text:{t}")
+                }
+                LanguageModelStreamChunk::Delta(LanguageModelStreamChunkType::ToolCall(call)) => {
+                    format!("tool:{}:{}", call.name, call.delta)
+                }
+                LanguageModelStreamChunk::Done(message) => format!(
+                    "done:{:?}:{:?}",
+                    message.content,
+                    message.usage.unwrap().output_tokens
+                ),
+                other => format!("{other:?}"),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_stream_text_decodes_event_stream_and_replays_it() {
+        let mut body = Vec::new();
+        for (event, payload) in [
+            ("messageStart", r#"{"role":"assistant"}"#),
+            (
+                "contentBlockDelta",
+                r#"{"contentBlockIndex":0,"delta":{"text":"Hel"}}"#,
+            ),
+            (
+                "contentBlockDelta",
+                r#"{"contentBlockIndex":0,"delta":{"text":"lo"}}"#,
+            ),
+            ("contentBlockStop", r#"{"contentBlockIndex":0}"#),
+            (
+                "contentBlockStart",
+                r#"{"contentBlockIndex":1,"start":{"toolUse":{"toolUseId":"tooluse_0","name":"get_weather"}}}"#,
+            ),
+            (
+                "contentBlockDelta",
+                r#"{"contentBlockIndex":1,"delta":{"toolUse":{"input":"{\"city\":"}}}"#,
+            ),
+            (
+                "contentBlockDelta",
+                r#"{"contentBlockIndex":1,"delta":{"toolUse":{"input":"\"Paris\"}"}}}"#,
+            ),
+            ("contentBlockStop", r#"{"contentBlockIndex":1}"#),
+            ("messageStop", r#"{"stopReason":"tool_use"}"#),
+            (
+                "metadata",
+                r#"{"usage":{"inputTokens":5,"outputTokens":3},"metrics":{"latencyMs":10}}"#,
+            ),
+        ] {
+            body.extend(encode("event", event, payload));
+        }
+        let (base_url, mut request) = serve(vec![TestResponse::Body(
+            "application/vnd.amazon.eventstream".to_string(),
+            body,
+        )])
+        .await;
+        let mut model = model(base_url);
+
+        let path = std::env::temp_dir().join(format!("bedrock_{}.json", uuid::Uuid::new_v4()));
+        let recorder = Cassette::record(&path);
+        let stream = recorder
+            .run(model.stream_text(LanguageModelOptions::default()))
+            .await
+            .unwrap();
+        let chunks = collect(stream).await;
+        assert!(request.recv().await.unwrap().starts_with(
+            "POST /model/anthropic.claude-3-5-haiku-20241022-v1%3A0/converse-stream "
+        ));
+        assert_eq!(
+            chunks[..4],
+            [
+                "text:Hel",
+                "text:lo",
+                "tool:get_weather:{\"city\":",
+                "tool:get_weather:\"Paris\"}"
+            ]
+        );
+        assert_eq!(chunks[4], "done:Text(\"Hello\"):Some(3)");
+        assert!(chunks[5].starts_with("done:ToolCall("));
+        assert_eq!(chunks.len(), 6);
+
+        // The server is gone, so the stream can only come from the cassette
+        let player = Cassette::from_json(&recorder.to_json().unwrap()).unwrap();
+        let stream = player
+            .run(model.stream_text(LanguageModelOptions::default()))
+            .await
+            .unwrap();
+        assert_eq!(collect(stream).await, chunks);
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -1,27 +1,374 @@
-//! This module provides the Amazon Bedrock provider, wrapping OpenAI Chat Completions for Bedrock requests.
+//! This module provides the Amazon Bedrock provider, which implements the `LanguageModel`
+//! and `Provider` traits for the Bedrock `Converse` and `ConverseStream` APIs.
 //!
-
-// NOTE: Amazon Bedrock might not be fully compatible with the OpenAI API. Please
-// refer to the Amazon Bedrock documentation for more information.
+//! Requests are authenticated with a Bedrock API key when one is set, and signed
+//! with AWS Signature Version 4 using the access keys of the environment otherwise.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "amazon-bedrock")]
+//!# {
+//! use aisdk::core::LanguageModelRequest;
+//! use aisdk::providers::AmazonBedrock;
+//!
+//! # async fn run() -> aisdk::Result<()> {
+//! // Reads AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY, AWS_SESSION_TOKEN and AWS_REGION
+//! let bedrock = AmazonBedrock::anthropic_claude_sonnet_4_20250514_v1_0();
+//!
+//! let response = LanguageModelRequest::builder()
+//!     .model(bedrock)
+//!     .prompt("Why is the sky blue?")
+//!     .build()
+//!     .generate_text()
+//!     .await?;
+//! # Ok(())
+//! # }
+//!# }
+//! ```
 
 pub mod capabilities;
+/// Client implementation for the Bedrock Converse API.
+pub mod client;
+/// Conversion utilities for Bedrock types.
+pub mod conversions;
+pub mod extensions;
+pub mod language_model;
+pub mod settings;
+
+use crate::core::DynamicModel;
+use crate::core::capabilities::ModelName;
+use crate::core::client::HttpSettings;
+use crate::core::credentials::{CredentialProvider, Credentials};
+use crate::core::utils::{env_var, validate_base_url};
+use crate::error::{Error, Result};
+use crate::providers::amazon_bedrock::client::BedrockOptions;
+use crate::providers::amazon_bedrock::settings::{
+    AmazonBedrockProviderSettings, regional_base_url,
+};
+use serde::Serialize;
+
+/// The Amazon Bedrock provider.
+#[derive(Debug, Serialize, Clone)]
+pub struct AmazonBedrock<M: ModelName> {
+    /// Configuration settings for the Amazon Bedrock provider.
+    pub settings: AmazonBedrockProviderSettings,
+    options: BedrockOptions,
+    _phantom: std::marker::PhantomData<M>,
+}
+
+impl<M: ModelName> AmazonBedrock<M> {
+    /// Amazon Bedrock provider setting builder.
+    pub fn builder() -> AmazonBedrockBuilder<M> {
+        AmazonBedrockBuilder::default()
+    }
+
+    /// Creates an Amazon Bedrock provider builder configured from the environment.
+    ///
+    /// Reads the region from `AWS_REGION`, the credentials from `AWS_BEARER_TOKEN_BEDROCK`
+    /// or `AWS_ACCESS_KEY_ID` and `AWS_SECRET_ACCESS_KEY`, and the base URL from
+    /// `AWS_ENDPOINT_URL_BEDROCK_RUNTIME`.
+    /// Unset variables keep the defaults.
+    pub fn from_env() -> AmazonBedrockBuilder<M> {
+        let mut builder = Self::builder();
+        if let Some(base_url) = env_var("AWS_ENDPOINT_URL_BEDROCK_RUNTIME") {
+            builder = builder.base_url(base_url);
+        }
+        builder
+    }
+}
+
+impl AmazonBedrock<DynamicModel> {
+    /// Creates an Amazon Bedrock provider with a dynamic model name using default settings.
+    ///
+    /// This allows you to specify the model ID or inference profile ID as a string
+    /// rather than using typed constructor methods.
+    ///
+    /// **WARNING**: when using `DynamicModel`, model capabilities are not validated.
+    /// This means there is no compile-time guarantee that the model supports requested features.
+    ///
+    /// For custom configuration (region, credentials, etc.), use the builder pattern:
+    /// `AmazonBedrock::<DynamicModel>::builder().model_name(...).region(...).build()`
+    ///
+    /// # Parameters
+    ///
+    /// * `model_name` - The model ID (e.g., "anthropic.claude-3-5-sonnet-20241022-v2:0")
+    ///
+    /// # Returns
+    ///
+    /// A configured `AmazonBedrock<DynamicModel>` provider instance with default settings.
+    pub fn model_name(name: impl Into<String>) -> Self {
+        let settings = AmazonBedrockProviderSettings::default();
+        let options = BedrockOptions::builder()
+            .model(name.into())
+            .build()
+            .unwrap();
+
+        AmazonBedrock {
+            settings,
+            options,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<M: ModelName> Default for AmazonBedrock<M> {
+    /// Creates a new Amazon Bedrock provider with default settings.
+    fn default() -> Self {
+        let settings = AmazonBedrockProviderSettings::default();
+        let options = BedrockOptions::builder()
+            .model(M::MODEL_NAME.to_string())
+            .build()
+            .unwrap();
+
+        Self {
+            settings,
+            options,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+/// Amazon Bedrock Provider Builder
+pub struct AmazonBedrockBuilder<M: ModelName> {
+    settings: AmazonBedrockProviderSettings,
+    options: BedrockOptions,
+    _phantom: std::marker::PhantomData<M>,
+}
+
+impl<M: ModelName> Default for AmazonBedrockBuilder<M> {
+    /// Creates a new Amazon Bedrock provider builder with default settings.
+    fn default() -> Self {
+        let settings = AmazonBedrockProviderSettings::default();
+        let options = BedrockOptions::builder()
+            .model(M::MODEL_NAME.to_string())
+            .build()
+            .unwrap();
+
+        Self {
+            settings,
+            options,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl AmazonBedrockBuilder<DynamicModel> {
+    /// Sets the model name from a string. e.g., "anthropic.claude-3-5-sonnet-20241022-v2:0"
+    ///
+    /// Inference profile IDs such as "us.anthropic.claude-sonnet-4-20250514-v1:0"
+    /// are accepted as well.
+    ///
+    /// **WARNING**: when using `DynamicModel`, model capabilities are not validated.
+    /// This means there is no compile-time guarantee that the model supports requested features.
+    ///
+    /// For compile-time model validation, use the constructor methods like
+    /// `AmazonBedrock::anthropic_claude_3_5_haiku_20241022_v1_0()`.
+    ///
+    /// # Parameters
+    ///
+    /// * `model_name` - The Bedrock model ID.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the model name set.
+    pub fn model_name(mut self, model_name: impl Into<String>) -> Self {
+        self.options.model = model_name.into();
+        self
+    }
+}
+
+impl<M: ModelName> AmazonBedrockBuilder<M> {
+    /// Sets the base URL for the Amazon Bedrock provider, such as a VPC endpoint.
+    /// Defaults to the runtime endpoint of the region.
+    ///
+    /// # Parameters
+    ///
+    /// * `base_url` - The base URL string for API requests.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the base URL set.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.settings.base_url = base_url.into();
+        self
+    }
+
+    /// Sets the Bedrock API key, which is sent as a bearer token instead of
+    /// signing requests. Defaults to `AWS_BEARER_TOKEN_BEDROCK` or `BEDROCK_API_KEY`.
+    ///
+    /// # Parameters
+    ///
+    /// * `api_key` - The API key string for authentication.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the API key set.
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.settings.api_key = api_key.into();
+        self
+    }
+
+    /// Sets the credentials resolving the API key before each request, to fetch
+    /// it from a secret manager or rotate it. Takes precedence over `api_key`.
+    ///
+    /// # Parameters
+    ///
+    /// * `credentials` - The source of the API key.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the credentials set.
+    pub fn credential_provider(mut self, credentials: impl CredentialProvider + 'static) -> Self {
+        self.settings.credential_provider = Some(Credentials::new(credentials));
+        self
+    }
+
+    /// Sets the HTTP client sending the requests, to share its pool of connections
+    /// or configure it. Requests share one pooled client by default.
+    ///
+    /// # Parameters
+    ///
+    /// * `client` - The `reqwest` client sending the requests.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the HTTP client set.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.settings.http_client = Some(client);
+        self
+    }
+
+    /// Sets the network settings of the HTTP client, such as a proxy or
+    /// certificate authorities. Ignored if an HTTP client is set.
+    ///
+    /// # Parameters
+    ///
+    /// * `http` - The network settings.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the network settings set.
+    pub fn http_settings(mut self, http: HttpSettings) -> Self {
+        self.settings.http = http;
+        self
+    }
+
+    /// Sets the name of the provider. Defaults to "AmazonBedrock".
+    ///
+    /// # Parameters
+    ///
+    /// * `provider_name` - The provider name string.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the provider name set.
+    pub fn provider_name(mut self, provider_name: impl Into<String>) -> Self {
+        self.settings.provider_name = provider_name.into();
+        self
+    }
+
+    /// Sets a custom API path for the Amazon Bedrock provider, overriding the
+    /// default "/model/{model_id}/converse". "-stream" is appended for streaming.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The API path.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the path set.
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.settings.path = Some(path.into());
+        self
+    }
+
+    /// Sets the AWS region, and the base URL to the runtime endpoint of the region.
+    /// Defaults to `AWS_REGION`, `AWS_DEFAULT_REGION` or "us-east-1".
+    ///
+    /// Call `base_url` afterwards to use a different endpoint of the region.
+    ///
+    /// # Parameters
+    ///
+    /// * `region` - The AWS region (e.g., "eu-west-1").
+    ///
+    /// # Returns
+    ///
+    /// The builder with the region set.
+    pub fn region(mut self, region: impl Into<String>) -> Self {
+        self.settings.region = region.into();
+        self.settings.base_url = regional_base_url(&self.settings.region);
+        self
+    }
+
+    /// Sets the AWS access keys used to sign requests. Defaults to
+    /// `AWS_ACCESS_KEY_ID` and `AWS_SECRET_ACCESS_KEY`.
+    ///
+    /// # Parameters
+    ///
+    /// * `access_key_id` - The access key ID.
+    /// * `secret_access_key` - The secret access key.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the access keys set.
+    pub fn credentials(
+        mut self,
+        access_key_id: impl Into<String>,
+        secret_access_key: impl Into<String>,
+    ) -> Self {
+        self.settings.access_key_id = access_key_id.into();
+        self.settings.secret_access_key = secret_access_key.into();
+        self
+    }
+
+    /// Sets the session token of temporary AWS credentials. Defaults to `AWS_SESSION_TOKEN`.
+    ///
+    /// # Parameters
+    ///
+    /// * `session_token` - The session token.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the session token set.
+    pub fn session_token(mut self, session_token: impl Into<String>) -> Self {
+        self.settings.session_token = Some(session_token.into());
+        self
+    }
+
+    /// Builds the Amazon Bedrock provider.
+    ///
+    /// Validates the configuration and creates the provider instance. Either an
+    /// API key or AWS access keys are required.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the configured `AmazonBedrock` provider or an `Error`.
+    pub fn build(self) -> Result<AmazonBedrock<M>> {
+        let base_url = validate_base_url(&self.settings.base_url)?;
+
+        if self.settings.api_key.is_empty()
+            && self.settings.credential_provider.is_none()
+            && (self.settings.access_key_id.is_empty()
+                || self.settings.secret_access_key.is_empty())
+        {
+            return Err(Error::MissingField("api_key".to_string()));
+        }
+
+        Ok(AmazonBedrock {
+            settings: AmazonBedrockProviderSettings {
+                base_url,
+                http_client: self
+                    .settings
+                    .http
+                    .resolve_client(self.settings.http_client.clone())?,
+                ..self.settings
+            },
+            options: self.options,
+            _phantom: std::marker::PhantomData,
+        })
+    }
+}
 
-// Generate the settings module
-crate::openai_compatible_settings!(
-    AmazonBedrockProviderSettings,
-    AmazonBedrockProviderSettingsBuilder,
-    "AmazonBedrock",
-    "https://bedrock-runtime.us-east-1.amazonaws.com/openai/v1/",
-    "BEDROCK_API_KEY"
-);
-
-// Generate the provider struct and builder
-crate::openai_compatible_provider!(
-    AmazonBedrock,
-    AmazonBedrockBuilder,
-    AmazonBedrockProviderSettings,
-    "anthropic.claude-3-5-sonnet-20241022-v2:0"
-);
-
-// Generate the language model implementation
-crate::openai_compatible_language_model!(AmazonBedrock);
+// Re-exports for convenience
+pub use capabilities::*;
@@ -0,0 +1,92 @@
+//! Defines the settings for the Amazon Bedrock provider.
+
+use crate::core::client::HttpSettings;
+use crate::core::credentials::Credentials;
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// Settings for the Amazon Bedrock provider.
+///
+/// Requests are authenticated with a Bedrock API key when one is set, and signed
+/// with AWS Signature Version 4 using the access keys otherwise.
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+#[builder(setter(into), default)]
+pub struct AmazonBedrockProviderSettings {
+    /// The name of the provider.
+    pub provider_name: String,
+
+    /// The base URL of the Bedrock runtime endpoint.
+    pub base_url: String,
+
+    /// The Bedrock API key, sent as a bearer token.
+    pub api_key: String,
+
+    /// Credentials resolving the API key before each request, in place of
+    /// `api_key`.
+    #[serde(skip)]
+    pub credential_provider: Option<Credentials>,
+
+    /// The HTTP client sending the requests. Requests share one pooled client
+    /// if it is not set.
+    #[serde(skip)]
+    pub http_client: Option<reqwest::Client>,
+
+    /// Network settings of the HTTP client built for the provider when no
+    /// client is set, such as a proxy or certificate authorities.
+    #[serde(default)]
+    pub http: HttpSettings,
+
+    /// Custom API path override. When set, this path is used instead of the
+    /// default "/model/{model_id}/converse", with "-stream" appended for streaming.
+    pub path: Option<String>,
+
+    /// The AWS region used to sign requests (e.g., "us-east-1").
+    pub region: String,
+
+    /// The AWS access key ID used to sign requests.
+    pub access_key_id: String,
+
+    /// The AWS secret access key used to sign requests.
+    pub secret_access_key: String,
+
+    /// The session token of temporary AWS credentials.
+    pub session_token: Option<String>,
+}
+
+/// Returns the Bedrock runtime endpoint of a region.
+pub(crate) fn regional_base_url(region: &str) -> String {
+    format!("https://bedrock-runtime.{region}.amazonaws.com/")
+}
+
+impl Default for AmazonBedrockProviderSettings {
+    /// Returns the default settings for the Amazon Bedrock provider, reading the
+    /// credentials and region from the standard AWS environment variables.
+    fn default() -> Self {
+        let region = std::env::var("AWS_REGION")
+            .or_else(|_| std::env::var("AWS_DEFAULT_REGION"))
+            .unwrap_or_else(|_| "us-east-1".to_string());
+
+        Self {
+            provider_name: "AmazonBedrock".to_string(),
+            base_url: regional_base_url(&region),
+            api_key: std::env::var("AWS_BEARER_TOKEN_BEDROCK")
+                .or_else(|_| std::env::var("BEDROCK_API_KEY"))
+                .unwrap_or_default(),
+            path: None,
+            credential_provider: None,
+            http_client: None,
+            http: HttpSettings::default(),
+            region,
+            access_key_id: std::env::var("AWS_ACCESS_KEY_ID").unwrap_or_default(),
+            secret_access_key: std::env::var("AWS_SECRET_ACCESS_KEY").unwrap_or_default(),
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+        }
+    }
+}
+
+impl AmazonBedrockProviderSettings {
+    /// Creates a new builder for `AmazonBedrockProviderSettings`.
+    pub fn builder() -> AmazonBedrockProviderSettingsBuilder {
+        AmazonBedrockProviderSettingsBuilder::default()
+    }
+}
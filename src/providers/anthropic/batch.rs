@@ -0,0 +1,317 @@
+//! Message Batches API of the Anthropic provider.
+//!
+//! The requests of a batch are sent inline with the batch, and their results are
+//! downloaded as a JSON Lines file once the batch has ended.
+
+use crate::core::batch::{
+    BatchInfo, BatchLanguageModel, BatchRequest, BatchRequestCounts, BatchResult,
+    BatchResultStream, BatchStatus,
+};
+use crate::core::capabilities::ModelName;
+use crate::core::client::{LanguageModelClient, response_lines, send_json, send_request};
+use crate::core::utils::join_url;
+use crate::error::{Error, Result};
+use crate::providers::anthropic::Anthropic;
+use crate::providers::anthropic::client::{AnthropicMessageResponse, AnthropicOptions};
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+/// A request of a message batch.
+#[derive(Debug, Serialize)]
+struct AnthropicBatchRequest {
+    custom_id: String,
+    params: AnthropicOptions,
+}
+
+/// The request creating a message batch.
+#[derive(Debug, Serialize)]
+struct AnthropicCreateBatchRequest {
+    requests: Vec<AnthropicBatchRequest>,
+}
+
+/// The processing status of a message batch.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum AnthropicProcessingStatus {
+    InProgress,
+    Canceling,
+    Ended,
+}
+
+/// The number of requests of a message batch, by state.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct AnthropicBatchRequestCounts {
+    processing: usize,
+    succeeded: usize,
+    errored: usize,
+    canceled: usize,
+    expired: usize,
+}
+
+/// A message batch.
+#[derive(Debug, Deserialize)]
+struct AnthropicMessageBatch {
+    id: String,
+    processing_status: AnthropicProcessingStatus,
+    #[serde(default)]
+    request_counts: AnthropicBatchRequestCounts,
+    #[serde(default)]
+    cancel_initiated_at: Option<String>,
+    #[serde(default)]
+    results_url: Option<String>,
+}
+
+impl From<AnthropicMessageBatch> for BatchInfo {
+    fn from(batch: AnthropicMessageBatch) -> Self {
+        let counts = batch.request_counts;
+        let status = match batch.processing_status {
+            AnthropicProcessingStatus::InProgress => BatchStatus::InProgress,
+            AnthropicProcessingStatus::Canceling => BatchStatus::Cancelling,
+            AnthropicProcessingStatus::Ended if batch.cancel_initiated_at.is_some() => {
+                BatchStatus::Cancelled
+            }
+            AnthropicProcessingStatus::Ended => BatchStatus::Completed,
+        };
+
+        Self {
+            id: batch.id,
+            status,
+            request_counts: BatchRequestCounts {
+                total: counts.processing
+                    + counts.succeeded
+                    + counts.errored
+                    + counts.canceled
+                    + counts.expired,
+                succeeded: counts.succeeded,
+                failed: counts.errored + counts.canceled + counts.expired,
+            },
+        }
+    }
+}
+
+/// The result of a request of a message batch.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum AnthropicBatchResultType {
+    Succeeded {
+        message: Box<AnthropicMessageResponse>,
+    },
+    Errored {
+        error: serde_json::Value,
+    },
+    Canceled,
+    Expired,
+}
+
+/// A line of the results file of a message batch.
+#[derive(Debug, Deserialize)]
+struct AnthropicBatchResultLine {
+    custom_id: String,
+    result: AnthropicBatchResultType,
+}
+
+impl From<AnthropicBatchResultLine> for BatchResult {
+    fn from(line: AnthropicBatchResultLine) -> Self {
+        let error = |details: String| {
+            Err(Error::ApiError {
+                status_code: None,
+                details,
+            })
+        };
+        let response = match line.result {
+            AnthropicBatchResultType::Succeeded { message } => Ok((*message).into()),
+            AnthropicBatchResultType::Errored { error: body } => {
+                // The error is wrapped in an error response
+                let body = body.get("error").unwrap_or(&body);
+                error(format!(
+                    "{}: {}",
+                    body["type"].as_str().unwrap_or("error"),
+                    body["message"].as_str().unwrap_or_default()
+                ))
+            }
+            AnthropicBatchResultType::Canceled => error("Request was cancelled".to_string()),
+            AnthropicBatchResultType::Expired => error("Request expired".to_string()),
+        };
+
+        Self {
+            custom_id: line.custom_id,
+            response,
+        }
+    }
+}
+
+impl<M: ModelName> Anthropic<M> {
+    /// Builds a request to an endpoint of the Message Batches API.
+    fn batches_request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+    ) -> Result<reqwest::RequestBuilder> {
+        let path = format!("{}/batches{path}", LanguageModelClient::path(self));
+        let url = join_url(self.settings.base_url.as_str(), &path)?;
+        Ok(LanguageModelClient::http_client(self)
+            .request(method, url)
+            .headers(LanguageModelClient::headers(self)))
+    }
+}
+
+#[async_trait]
+impl<M: ModelName> BatchLanguageModel for Anthropic<M> {
+    async fn create_batch(&mut self, requests: Vec<BatchRequest>) -> Result<BatchInfo> {
+        let requests = requests
+            .into_iter()
+            .map(|request| {
+                let mut params: AnthropicOptions = request.options.into();
+                params.model = self.options.model.clone();
+                AnthropicBatchRequest {
+                    custom_id: request.custom_id,
+                    params,
+                }
+            })
+            .collect();
+
+        let request = self
+            .batches_request(reqwest::Method::POST, "")?
+            .json(&AnthropicCreateBatchRequest { requests });
+        let batch: AnthropicMessageBatch = send_json(request).await?;
+        Ok(batch.into())
+    }
+
+    async fn retrieve_batch(&self, id: &str) -> Result<BatchInfo> {
+        let request = self.batches_request(reqwest::Method::GET, &format!("/{id}"))?;
+        let batch: AnthropicMessageBatch = send_json(request).await?;
+        Ok(batch.into())
+    }
+
+    async fn cancel_batch(&self, id: &str) -> Result<BatchInfo> {
+        let request = self.batches_request(reqwest::Method::POST, &format!("/{id}/cancel"))?;
+        let batch: AnthropicMessageBatch = send_json(request).await?;
+        Ok(batch.into())
+    }
+
+    async fn batch_results(&self, id: &str) -> Result<BatchResultStream> {
+        let request = self.batches_request(reqwest::Method::GET, &format!("/{id}"))?;
+        let batch: AnthropicMessageBatch = send_json(request).await?;
+        let Some(results_url) = batch.results_url else {
+            return Err(Error::InvalidInput(format!(
+                "Batch {id} is still being processed"
+            )));
+        };
+
+        let request = LanguageModelClient::http_client(self)
+            .get(results_url)
+            .headers(LanguageModelClient::headers(self));
+        let lines = response_lines(send_request(request).await?);
+
+        Ok(Box::pin(lines.map(|line| {
+            let line: AnthropicBatchResultLine =
+                serde_json::from_str(&line?).map_err(|e| Error::ApiError {
+                    status_code: None,
+                    details: format!("Failed to parse batch result: {e}"),
+                })?;
+            Ok(line.into())
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DynamicModel;
+    use crate::core::batch::wait_for_batch;
+    use crate::core::client::test_server::{TestResponse, serve};
+    use crate::core::language_model::{LanguageModelOptions, LanguageModelResponseContentType};
+
+    #[tokio::test]
+    async fn test_message_batch_round_trip() {
+        let (base_url, mut requests) = serve(vec![
+            TestResponse::json(r#"{"id":"msgbatch_1","type":"message_batch","processing_status":"in_progress","request_counts":{"processing":2,"succeeded":0,"errored":0,"canceled":0,"expired":0},"results_url":null}"#),
+            TestResponse::json(r#"{"id":"msgbatch_1","type":"message_batch","processing_status":"ended","request_counts":{"processing":0,"succeeded":1,"errored":1,"canceled":0,"expired":0},"results_url":"{address}/v1/messages/batches/msgbatch_1/results"}"#),
+            TestResponse::json(r#"{"id":"msgbatch_1","type":"message_batch","processing_status":"ended","results_url":"{address}/v1/messages/batches/msgbatch_1/results"}"#),
+            TestResponse::json(concat!(
+                r#"{"custom_id":"sky","result":{"type":"succeeded","message":{"id":"msg_1","type":"message","role":"assistant","model":"claude-sonnet-4-5","content":[{"type":"text","text":"Rayleigh scattering"}],"stop_reason":"end_turn","stop_sequence":null,"usage":{"input_tokens":10,"output_tokens":3,"cache_creation_input_tokens":0,"cache_read_input_tokens":0,"cache_creation":{"ephemeral_5m_input_tokens":0,"ephemeral_1h_input_tokens":0},"service_tier":"batch"}}}}"#,
+                "\n",
+                r#"{"custom_id":"sea","result":{"type":"errored","error":{"type":"error","error":{"type":"invalid_request_error","message":"Invalid prompt"}}}}"#,
+                "\n"
+            )),
+        ])
+        .await;
+
+        let mut model = Anthropic::<DynamicModel>::builder()
+            .model_name("claude-sonnet-4-5")
+            .base_url(format!("{base_url}/v1/"))
+            .api_key("test")
+            .build()
+            .unwrap();
+
+        let request = |custom_id: &str| BatchRequest {
+            custom_id: custom_id.to_string(),
+            options: LanguageModelOptions {
+                system: Some("Be brief".to_string()),
+                ..Default::default()
+            },
+        };
+        let info = model
+            .create_batch(vec![request("sky"), request("sea")])
+            .await
+            .unwrap();
+        assert_eq!(info.status, BatchStatus::InProgress);
+        assert_eq!(info.request_counts.total, 2);
+
+        let create = requests.recv().await.unwrap();
+        assert!(create.starts_with("POST /v1/messages/batches "));
+        assert!(
+            create.contains(
+                r#"{"requests":[{"custom_id":"sky","params":{"model":"claude-sonnet-4-5""#
+            )
+        );
+        assert!(create.contains("x-api-key: test"));
+
+        let info = wait_for_batch(&model, "msgbatch_1").await.unwrap();
+        assert_eq!(info.status, BatchStatus::Completed);
+        assert_eq!(info.request_counts.failed, 1);
+
+        let results: Vec<BatchResult> = model
+            .batch_results("msgbatch_1")
+            .await
+            .unwrap()
+            .map(|result| result.unwrap())
+            .collect()
+            .await;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].custom_id, "sky");
+        assert!(matches!(
+            &results[0].response.as_ref().unwrap().contents[..],
+            [LanguageModelResponseContentType::Text(text)] if text == "Rayleigh scattering"
+        ));
+        assert_eq!(results[1].custom_id, "sea");
+        assert!(matches!(
+            &results[1].response,
+            Err(Error::ApiError { details, .. }) if details == "invalid_request_error: Invalid prompt"
+        ));
+
+        assert!(
+            requests
+                .recv()
+                .await
+                .unwrap()
+                .starts_with("GET /v1/messages/batches/msgbatch_1 ")
+        );
+        assert!(
+            requests
+                .recv()
+                .await
+                .unwrap()
+                .starts_with("GET /v1/messages/batches/msgbatch_1 ")
+        );
+        assert!(
+            requests
+                .recv()
+                .await
+                .unwrap()
+                .starts_with("GET /v1/messages/batches/msgbatch_1/results ")
+        );
+    }
+}
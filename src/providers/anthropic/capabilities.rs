@@ -14,13 +14,19 @@ model_capabilities! {
             model_name: "claude-3-5-haiku-20241022",
             constructor_name: claude_3_5_haiku_20241022,
             display_name: "Claude Haiku 3.5",
-            capabilities: [ImageInputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 0.8, output: 4.0, cached: 0.08 },
+            context_window: 200000,
+            max_output_tokens: 8192
         },
         Claude35HaikuLatest {
             model_name: "claude-3-5-haiku-latest",
             constructor_name: claude_3_5_haiku_latest,
             display_name: "Claude Haiku 3.5 (latest)",
-            capabilities: [ImageInputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 0.8, output: 4.0, cached: 0.08 },
+            context_window: 200000,
+            max_output_tokens: 8192
         },
         Claude35Sonnet20240620 {
             model_name: "claude-3-5-sonnet-20240620",
@@ -68,13 +74,19 @@ model_capabilities! {
             model_name: "claude-haiku-4-5",
             constructor_name: claude_haiku_4_5,
             display_name: "Claude Haiku 4.5 (latest)",
-            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 1.0, output: 5.0, cached: 0.1 },
+            context_window: 200000,
+            max_output_tokens: 64000
         },
         ClaudeHaiku4520251001 {
             model_name: "claude-haiku-4-5-20251001",
             constructor_name: claude_haiku_4_5_20251001,
             display_name: "Claude Haiku 4.5",
-            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 1.0, output: 5.0, cached: 0.1 },
+            context_window: 200000,
+            max_output_tokens: 64000
         },
         ClaudeOpus40 {
             model_name: "claude-opus-4-0",
@@ -86,13 +98,19 @@ model_capabilities! {
             model_name: "claude-opus-4-1",
             constructor_name: claude_opus_4_1,
             display_name: "Claude Opus 4.1 (latest)",
-            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 15.0, output: 75.0, cached: 1.5 },
+            context_window: 200000,
+            max_output_tokens: 32000
         },
         ClaudeOpus4120250805 {
             model_name: "claude-opus-4-1-20250805",
             constructor_name: claude_opus_4_1_20250805,
             display_name: "Claude Opus 4.1",
-            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 15.0, output: 75.0, cached: 1.5 },
+            context_window: 200000,
+            max_output_tokens: 32000
         },
         ClaudeOpus420250514 {
             model_name: "claude-opus-4-20250514",
@@ -104,37 +122,55 @@ model_capabilities! {
             model_name: "claude-opus-4-5",
             constructor_name: claude_opus_4_5,
             display_name: "Claude Opus 4.5 (latest)",
-            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 5.0, output: 25.0, cached: 0.5 },
+            context_window: 200000,
+            max_output_tokens: 64000
         },
         ClaudeOpus4520251101 {
             model_name: "claude-opus-4-5-20251101",
             constructor_name: claude_opus_4_5_20251101,
             display_name: "Claude Opus 4.5",
-            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 5.0, output: 25.0, cached: 0.5 },
+            context_window: 200000,
+            max_output_tokens: 64000
         },
         ClaudeSonnet40 {
             model_name: "claude-sonnet-4-0",
             constructor_name: claude_sonnet_4_0,
             display_name: "Claude Sonnet 4 (latest)",
-            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 3.0, output: 15.0, cached: 0.3 },
+            context_window: 200000,
+            max_output_tokens: 64000
         },
         ClaudeSonnet420250514 {
             model_name: "claude-sonnet-4-20250514",
             constructor_name: claude_sonnet_4_20250514,
             display_name: "Claude Sonnet 4",
-            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 3.0, output: 15.0, cached: 0.3 },
+            context_window: 200000,
+            max_output_tokens: 64000
         },
         ClaudeSonnet45 {
             model_name: "claude-sonnet-4-5",
             constructor_name: claude_sonnet_4_5,
             display_name: "Claude Sonnet 4.5 (latest)",
-            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 3.0, output: 15.0, cached: 0.3 },
+            context_window: 200000,
+            max_output_tokens: 64000
         },
         ClaudeSonnet4520250929 {
             model_name: "claude-sonnet-4-5-20250929",
             constructor_name: claude_sonnet_4_5_20250929,
             display_name: "Claude Sonnet 4.5",
-            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, ReasoningSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 3.0, output: 15.0, cached: 0.3 },
+            context_window: 200000,
+            max_output_tokens: 64000
         },
     }
 }
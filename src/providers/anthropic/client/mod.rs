@@ -10,8 +10,8 @@ use reqwest_eventsource::Event;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    core::client::LanguageModelClient,
-    providers::anthropic::{ANTHROPIC_API_VERSION, Anthropic},
+    core::client::{LanguageModelClient, TokenCountClient, shared_client, sse_error},
+    providers::anthropic::{ANTHROPIC_API_VERSION, ANTHROPIC_FILES_BETA, Anthropic},
 };
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, Builder)]
@@ -42,16 +42,34 @@ pub(crate) struct AnthropicOptions {
     pub tools: Option<Vec<AnthropicTool>>,
     #[builder(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<AnthropicToolChoice>,
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub top_k: Option<u32>,
     #[builder(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub top_p: Option<f32>,
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<AnthropicMetadata>,
 }
 
 impl AnthropicOptions {
     pub(crate) fn builder() -> AnthropicOptionsBuilder {
         AnthropicOptionsBuilder::default()
     }
+
+    /// Returns whether a message references a file of the Files API.
+    pub(crate) fn uses_files(&self) -> bool {
+        self.messages.iter().any(|message| {
+            matches!(
+                message,
+                AnthropicMessageParam::User {
+                    content: AnthropicUserMessageContent::Blocks(blocks),
+                } if blocks.iter().any(|block| block.is_file())
+            )
+        })
+    }
 }
 
 impl<M: ModelName> LanguageModelClient for Anthropic<M> {
@@ -69,12 +87,33 @@ impl<M: ModelName> LanguageModelClient for Anthropic<M> {
         reqwest::Method::POST
     }
 
+    fn http_client(&self) -> reqwest::Client {
+        self.settings
+            .http_client
+            .clone()
+            .unwrap_or_else(shared_client)
+    }
+
     fn headers(&self) -> reqwest::header::HeaderMap {
         // Default headers
         let mut default_headers = reqwest::header::HeaderMap::new();
         default_headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
         default_headers.insert("x-api-key", self.settings.api_key.parse().unwrap());
         default_headers.insert("anthropic-version", ANTHROPIC_API_VERSION.parse().unwrap());
+        let mut betas: Vec<&str> = self
+            .settings
+            .beta_features
+            .iter()
+            .map(String::as_str)
+            .collect();
+        if self.options.uses_files() && !betas.contains(&ANTHROPIC_FILES_BETA) {
+            betas.push(ANTHROPIC_FILES_BETA);
+        }
+        if !betas.is_empty()
+            && let Ok(value) = betas.join(",").parse()
+        {
+            default_headers.insert("anthropic-beta", value);
+        }
 
         default_headers
     }
@@ -109,17 +148,7 @@ impl<M: ModelName> LanguageModelClient for Anthropic<M> {
                         .unwrap_or(AnthropicStreamEvent::NotSupported(msg.data)))
                 }
             },
-            Err(e) => {
-                // Extract status code if it's an InvalidStatusCode error
-                let status_code = match &e {
-                    reqwest_eventsource::Error::InvalidStatusCode(status, _) => Some(*status),
-                    _ => None,
-                };
-                Err(Error::ApiError {
-                    status_code,
-                    details: format!("SSE error: {e}"),
-                })
-            }
+            Err(e) => Err(sse_error(e)),
         }
     }
 
@@ -127,3 +156,79 @@ impl<M: ModelName> LanguageModelClient for Anthropic<M> {
         matches!(event, AnthropicStreamEvent::MessageStop)
     }
 }
+
+impl<M: ModelName> TokenCountClient for Anthropic<M> {
+    type Response = AnthropicCountTokensResponse;
+
+    fn path(&self) -> String {
+        format!("{}/count_tokens", LanguageModelClient::path(self))
+    }
+
+    fn method(&self) -> reqwest::Method {
+        reqwest::Method::POST
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        self.settings
+            .http_client
+            .clone()
+            .unwrap_or_else(shared_client)
+    }
+
+    fn headers(&self) -> reqwest::header::HeaderMap {
+        LanguageModelClient::headers(self)
+    }
+
+    fn query_params(&self) -> Vec<(&str, &str)> {
+        Vec::new()
+    }
+
+    fn body(&self) -> reqwest::Body {
+        let request = AnthropicCountTokensRequest {
+            model: self.options.model.clone(),
+            messages: self.options.messages.clone(),
+            system: self.options.system.clone(),
+            thinking: self.options.thinking.clone(),
+            tools: self.options.tools.clone(),
+        };
+        let body = serde_json::to_string(&request).unwrap();
+        reqwest::Body::from(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DynamicModel;
+    use crate::providers::anthropic::{
+        ANTHROPIC_FINE_GRAINED_TOOL_STREAMING_BETA, ANTHROPIC_INTERLEAVED_THINKING_BETA,
+    };
+
+    #[test]
+    fn test_headers_send_beta_features() {
+        let model = Anthropic::<DynamicModel>::builder()
+            .model_name("claude-sonnet-4-0")
+            .api_key("key")
+            .build()
+            .unwrap();
+        assert!(
+            LanguageModelClient::headers(&model)
+                .get("anthropic-beta")
+                .is_none()
+        );
+
+        let model = Anthropic::<DynamicModel>::builder()
+            .model_name("claude-sonnet-4-0")
+            .api_key("key")
+            .beta_features(&[
+                ANTHROPIC_FINE_GRAINED_TOOL_STREAMING_BETA,
+                ANTHROPIC_INTERLEAVED_THINKING_BETA,
+            ])
+            .build()
+            .unwrap();
+        assert_eq!(
+            LanguageModelClient::headers(&model)["anthropic-beta"],
+            "fine-grained-tool-streaming-2025-05-14,interleaved-thinking-2025-05-14"
+        );
+    }
+}
@@ -96,48 +96,89 @@ pub(crate) enum AnthropicContentBlock {
         input: serde_json::Value,
         name: String,
     },
+    #[serde(rename = "server_tool_use")]
+    ServerToolUse {
+        id: String,
+        input: serde_json::Value,
+        name: String,
+    },
+    #[serde(rename = "web_search_tool_result")]
+    WebSearchToolResult {
+        tool_use_id: String,
+        content: AnthropicWebSearchToolResultContent,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum AnthropicWebSearchToolResultContent {
+    Results(Vec<AnthropicWebSearchResult>),
+    Error { error_code: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct AnthropicWebSearchResult {
+    pub url: String,
+    pub title: String,
+    #[serde(default)]
+    pub page_age: Option<String>,
+    pub encrypted_content: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 #[allow(clippy::enum_variant_names)]
 pub(crate) enum AnthropicCitation {
+    #[serde(rename = "char_location")]
     CitationCharLocation {
         cited_text: String,
         document_index: usize,
-        document_title: String,
+        #[serde(default)]
+        document_title: Option<String>,
         end_char_index: usize,
-        file_id: String,
+        #[serde(default)]
+        file_id: Option<String>,
         start_char_index: usize,
     },
+    #[serde(rename = "page_location")]
     CitationPageLocation {
         cited_text: String,
         document_index: usize,
-        document_title: String,
+        #[serde(default)]
+        document_title: Option<String>,
         end_page_number: usize,
-        file_id: String,
+        #[serde(default)]
+        file_id: Option<String>,
         start_page_number: usize,
     },
+    #[serde(rename = "content_block_location")]
     CitationContentBlockLocation {
         cited_text: String,
         document_index: usize,
-        document_title: String,
+        #[serde(default)]
+        document_title: Option<String>,
         end_block_index: usize,
-        file_id: String,
+        #[serde(default)]
+        file_id: Option<String>,
         start_block_index: usize,
     },
+    #[serde(rename = "web_search_result_location")]
     CitationsWebSearchResultLocation {
         cited_text: String,
         encrypted_index: String,
-        title: String,
+        #[serde(default)]
+        title: Option<String>,
+        url: String,
     },
+    #[serde(rename = "search_result_location")]
     CitationsSearchResultLocation {
         cited_text: String,
         end_block_index: usize,
         search_result_index: usize,
         source: String,
         start_block_index: usize,
-        title: String,
+        #[serde(default)]
+        title: Option<String>,
     },
 }
 
@@ -182,6 +223,44 @@ pub enum AnthropicUserMessageContentBlock {
         /// The content of the tool result
         content: String,
     },
+    #[serde(rename = "document")]
+    /// A document, such as a PDF
+    Document {
+        /// The source of the document
+        source: AnthropicContentSource,
+    },
+    #[serde(rename = "image")]
+    /// An image
+    Image {
+        /// The source of the image
+        source: AnthropicContentSource,
+    },
+}
+
+impl AnthropicUserMessageContentBlock {
+    /// Returns whether the block references a file of the Files API.
+    pub(crate) fn is_file(&self) -> bool {
+        matches!(
+            self,
+            Self::Document {
+                source: AnthropicContentSource::File { .. }
+            } | Self::Image {
+                source: AnthropicContentSource::File { .. }
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+/// The source of a document or an image
+pub enum AnthropicContentSource {
+    #[serde(rename = "file")]
+    /// A file uploaded to the Files API
+    File {
+        /// The ID of the file
+        file_id: String,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -191,6 +270,8 @@ pub(crate) enum AnthropicAssistantMessageParamContent {
     Text { text: String },
     #[serde(rename = "thinking")]
     Thinking { thinking: String, signature: String },
+    #[serde(rename = "redacted_thinking")]
+    RedactedThinking { data: String },
     #[serde(rename = "tool_use")]
     ToolUse {
         id: String,
@@ -206,14 +287,46 @@ pub(crate) struct AnthropicTool {
     pub input_schema: serde_json::Value,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum AnthropicToolChoice {
+    Auto,
+    Any,
+    None,
+    Tool { name: String },
+}
+
+/// Metadata of a request, identifying the end user it is made for.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct AnthropicMetadata {
+    pub user_id: Option<String>,
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub(crate) enum AnthropicThinking {
     #[default]
-    #[serde(rename = "disable")]
-    Disable,
-    #[serde(rename = "enable")]
-    Enable { budget_tokens: usize },
+    #[serde(rename = "disabled")]
+    Disabled,
+    #[serde(rename = "enabled")]
+    Enabled { budget_tokens: usize },
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct AnthropicCountTokensRequest {
+    pub model: String,
+    pub messages: Vec<AnthropicMessageParam>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<AnthropicThinking>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<AnthropicTool>>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct AnthropicCountTokensResponse {
+    pub input_tokens: usize,
 }
 
 // ---------------------------------- Streaming types ----------------------------------
@@ -1,10 +1,18 @@
 use crate::core::Message;
+use crate::core::language_model::sources::{Citation, Source};
 use crate::core::language_model::{
-    LanguageModelOptions, LanguageModelResponseContentType, ReasoningEffort, Usage,
+    LanguageModelOptions, LanguageModelResponse, LanguageModelResponseContentType, ReasoningEffort,
+    ResponseMetadata, Usage,
 };
+use crate::core::tools::{ToolCallInfo, ToolChoice, ToolDetails};
+use crate::extensions::Extensions;
 use crate::providers::anthropic::client::{
-    AnthropicAssistantMessageParamContent, AnthropicMessageDeltaUsage, AnthropicMessageParam,
-    AnthropicOptions, AnthropicThinking, AnthropicTool, AnthropicUsage,
+    AnthropicAssistantMessageParamContent, AnthropicCitation, AnthropicContentBlock,
+    AnthropicContentSource, AnthropicMessageDeltaUsage, AnthropicMessageParam,
+    AnthropicMessageResponse, AnthropicMetadata, AnthropicOptions, AnthropicThinking,
+    AnthropicTool, AnthropicToolChoice, AnthropicUsage, AnthropicUserMessageContent,
+    AnthropicUserMessageContentBlock, AnthropicWebSearchResult,
+    AnthropicWebSearchToolResultContent,
 };
 use crate::providers::anthropic::extensions;
 
@@ -18,85 +26,144 @@ impl From<LanguageModelOptions> for AnthropicOptions {
         // time checks if not set in core
         let max_tokens = options.max_output_tokens.unwrap_or(10_000);
 
-        if let Some(system) = options.system
-            && !system.is_empty()
-        {
-            request.system(Some(system));
-        } else {
-            request.system(None);
+        // convert reasoning to antropic thinking
+        let budget_tokens = options
+            .thinking_budget
+            .or(options.reasoning_effort.map(|effort| match effort {
+                // Low is 25% of the max_tokens
+                ReasoningEffort::Low => max_tokens / 4,
+                // Medium is 50% of the max_tokens
+                ReasoningEffort::Medium => max_tokens / 2,
+                // High is 75% of the max_tokens
+                ReasoningEffort::High => max_tokens - (max_tokens / 4),
+            }));
+        request.thinking(
+            budget_tokens.map(|budget_tokens| AnthropicThinking::Enabled {
+                budget_tokens: budget_tokens as usize,
+            }),
+        );
+
+        // The thinking budget counts towards max_tokens, which must exceed it
+        if options.max_output_tokens.is_some() {
+            request.max_tokens(max_tokens);
+        } else if let Some(budget_tokens) = budget_tokens {
+            request.max_tokens(budget_tokens + 4096);
         }
 
+        let mut system = options.system.filter(|system| !system.is_empty());
+
+        request.stop_sequences(options.stop_sequences);
+        request.temperature(options.temperature);
+        request.top_p(options.top_p);
+        request.top_k(options.top_k);
+        request.metadata(options.user.map(|user_id| AnthropicMetadata {
+            user_id: Some(user_id),
+        }));
+
         // convert messages to anthropic messages
         for msg in options.messages {
             match msg.message {
                 Message::System(s) => {
                     if !s.content.is_empty() {
-                        request.system(Some(s.content));
+                        system = Some(s.content);
                     }
                 }
+                Message::User(u) if u.files.is_empty() => {
+                    messages.push(AnthropicMessageParam::User {
+                        content: AnthropicUserMessageContent::Text(u.content),
+                    });
+                }
                 Message::User(u) => {
+                    let files = u.files.into_iter().map(|file| {
+                        let image = file.is_image();
+                        let source = AnthropicContentSource::File { file_id: file.id };
+                        if image {
+                            AnthropicUserMessageContentBlock::Image { source }
+                        } else {
+                            AnthropicUserMessageContentBlock::Document { source }
+                        }
+                    });
+                    let text = AnthropicUserMessageContentBlock::Text { text: u.content };
                     messages.push(AnthropicMessageParam::User {
-                        content:
-                            crate::providers::anthropic::client::AnthropicUserMessageContent::Text(
-                                u.content,
-                            ),
+                        content: AnthropicUserMessageContent::Blocks(
+                            std::iter::once(text).chain(files).collect(),
+                        ),
                     });
                 }
-                Message::Assistant(a) => match a.content {
-                    LanguageModelResponseContentType::Text(text) => {
-                        messages.push(AnthropicMessageParam::Assistant {
-                            content: vec![AnthropicAssistantMessageParamContent::Text { text }],
-                        });
-                    }
-                    LanguageModelResponseContentType::ToolCall(tool) => {
-                        messages.push(AnthropicMessageParam::Assistant {
-                            content: vec![AnthropicAssistantMessageParamContent::ToolUse {
+                Message::Assistant(a) => {
+                    let block = match a.content {
+                        LanguageModelResponseContentType::Text(text) => {
+                            AnthropicAssistantMessageParamContent::Text { text }
+                        }
+                        LanguageModelResponseContentType::ToolCall(tool) => {
+                            AnthropicAssistantMessageParamContent::ToolUse {
                                 id: tool.tool.id,
                                 input: tool.input,
                                 name: tool.tool.name,
-                            }],
-                        });
-                    }
-                    LanguageModelResponseContentType::Reasoning {
-                        content,
-                        extensions,
-                    } => {
-                        // Retrieve Anthropic-specific signature from extensions
-                        let signature = extensions
-                            .get::<extensions::AnthropicThinkingMetadata>()
-                            .signature
-                            .clone()
-                            .unwrap_or_else(|| content.clone());
-
-                        messages.push(AnthropicMessageParam::Assistant {
-                            content: vec![AnthropicAssistantMessageParamContent::Thinking {
-                                thinking: content.clone(),
-                                signature,
-                            }],
-                        });
+                            }
+                        }
+                        LanguageModelResponseContentType::Reasoning {
+                            content,
+                            extensions,
+                        } => {
+                            // Retrieve Anthropic-specific signature from extensions
+                            let metadata =
+                                extensions.get::<extensions::AnthropicThinkingMetadata>();
+                            if metadata.redacted {
+                                AnthropicAssistantMessageParamContent::RedactedThinking {
+                                    data: content,
+                                }
+                            } else if let Some(signature) = metadata.signature.clone() {
+                                AnthropicAssistantMessageParamContent::Thinking {
+                                    thinking: content,
+                                    signature,
+                                }
+                            } else {
+                                // Thinking without a signature, e.g. from another provider,
+                                // is rejected by the API
+                                continue;
+                            }
+                        }
+                        LanguageModelResponseContentType::Source(_)
+                        | LanguageModelResponseContentType::Citation(_)
+                        | LanguageModelResponseContentType::NotSupported(_) => continue,
+                    };
+
+                    // Blocks of a turn share a message, so a thinking block stays
+                    // in the same message as the tool calls that follow it
+                    match messages.last_mut() {
+                        Some(AnthropicMessageParam::Assistant { content }) => content.push(block),
+                        _ => messages.push(AnthropicMessageParam::Assistant {
+                            content: vec![block],
+                        }),
                     }
-                    LanguageModelResponseContentType::NotSupported(_) => {}
-                },
+                }
                 Message::Tool(tool) => {
-                    messages.push(AnthropicMessageParam::User {
-                        content: crate::providers::anthropic::client::AnthropicUserMessageContent::Blocks(vec![
-                            crate::providers::anthropic::client::AnthropicUserMessageContentBlock::ToolResult {
-                                tool_use_id: tool.tool.id,
-                                content: tool.output.unwrap_or_default().to_string(),
-                            },
-                        ]),
-                    });
+                    let block = AnthropicUserMessageContentBlock::ToolResult {
+                        tool_use_id: tool.tool.id,
+                        content: tool.output.unwrap_or_default().to_string(),
+                    };
+
+                    // Results of parallel tool calls are sent back in a single message
+                    match messages.last_mut() {
+                        Some(AnthropicMessageParam::User {
+                            content: AnthropicUserMessageContent::Blocks(blocks),
+                        }) => blocks.push(block),
+                        _ => messages.push(AnthropicMessageParam::User {
+                            content: AnthropicUserMessageContent::Blocks(vec![block]),
+                        }),
+                    }
                 }
+                // Anthropic has no developer role, the instructions extend the system prompt
                 Message::Developer(dev) => {
-                    messages.push(AnthropicMessageParam::User {
-                        content:
-                            crate::providers::anthropic::client::AnthropicUserMessageContent::Text(
-                                format!("<developer>\n{dev}\n</developer>"),
-                            ),
+                    system = Some(match system {
+                        Some(system) => format!("{system}\n\n{dev}"),
+                        None => dev,
                     });
                 }
             }
         }
+        request.system(system);
         // update messages
         request.messages(messages);
 
@@ -122,34 +189,201 @@ impl From<LanguageModelOptions> for AnthropicOptions {
                     })
                     .collect(),
             ));
+            request.tool_choice(options.tool_choice.map(|choice| match choice {
+                ToolChoice::Auto => AnthropicToolChoice::Auto,
+                ToolChoice::Required => AnthropicToolChoice::Any,
+                ToolChoice::None => AnthropicToolChoice::None,
+                ToolChoice::Tool(name) => AnthropicToolChoice::Tool { name },
+            }));
         }
 
-        // convert reasoning to antropic thinking
-        request.thinking(options.reasoning_effort.map(|effort| match effort {
-            // Low is 25% of the max_tokens
-            ReasoningEffort::Low => AnthropicThinking::Enable {
-                budget_tokens: (max_tokens / 4) as usize,
+        request.build().expect("Failed to build AntropicRequest")
+    }
+}
+
+impl From<AnthropicMessageResponse> for LanguageModelResponse {
+    fn from(response: AnthropicMessageResponse) -> Self {
+        let mut collected: Vec<LanguageModelResponseContentType> = Vec::new();
+        let mut references: Vec<LanguageModelResponseContentType> = Vec::new();
+        let mut previous_was_text = false;
+
+        for out in response.content {
+            let is_text = matches!(out, AnthropicContentBlock::Text { .. });
+            match out {
+                AnthropicContentBlock::Text { text, citations } => {
+                    // Cited passages are split into their own text blocks
+                    match collected.last_mut() {
+                        Some(LanguageModelResponseContentType::Text(previous))
+                            if previous_was_text =>
+                        {
+                            previous.push_str(&text)
+                        }
+                        _ => collected.push(LanguageModelResponseContentType::new(text)),
+                    }
+                    references.extend(
+                        citations
+                            .into_iter()
+                            .map(|c| LanguageModelResponseContentType::Citation(c.into())),
+                    );
+                }
+                AnthropicContentBlock::Thinking {
+                    signature,
+                    thinking,
+                } => {
+                    let extensions = Extensions::default();
+                    extensions
+                        .get_mut::<extensions::AnthropicThinkingMetadata>()
+                        .signature = Some(signature);
+                    collected.push(LanguageModelResponseContentType::Reasoning {
+                        content: thinking,
+                        extensions,
+                    });
+                }
+                AnthropicContentBlock::RedactedThinking { data } => {
+                    let extensions = Extensions::default();
+                    extensions
+                        .get_mut::<extensions::AnthropicThinkingMetadata>()
+                        .redacted = true;
+                    collected.push(LanguageModelResponseContentType::Reasoning {
+                        content: data,
+                        extensions,
+                    });
+                }
+                AnthropicContentBlock::ToolUse { id, input, name } => {
+                    collected.push(LanguageModelResponseContentType::ToolCall(ToolCallInfo {
+                        input,
+                        tool: ToolDetails {
+                            id: id.to_string(),
+                            name: name.to_string(),
+                        },
+                        extensions: Extensions::default(),
+                    }));
+                }
+                // Server tools are executed by Anthropic
+                AnthropicContentBlock::ServerToolUse { .. } => {}
+                AnthropicContentBlock::WebSearchToolResult { content, .. } => {
+                    references.extend(web_search_sources(content));
+                }
+            }
+            previous_was_text = is_text;
+        }
+        collected.extend(references);
+
+        LanguageModelResponse {
+            contents: collected,
+            usage: Some(response.usage.into()),
+            id: None,
+            logprobs: None,
+            candidates: Vec::new(),
+            provider_metadata: None,
+            metadata: Some(ResponseMetadata {
+                request_id: None,
+                response_id: Some(response.id),
+                model: Some(response.model),
+            }),
+        }
+    }
+}
+
+impl From<AnthropicCitation> for Citation {
+    fn from(citation: AnthropicCitation) -> Self {
+        let document = |index: usize, title: Option<String>, media_type: &str| Source::Document {
+            id: format!("document-{index}"),
+            title: title.unwrap_or_else(|| format!("Document {}", index + 1)),
+            media_type: media_type.to_string(),
+            filename: None,
+        };
+        match citation {
+            AnthropicCitation::CitationCharLocation {
+                cited_text,
+                document_index,
+                document_title,
+                ..
+            }
+            | AnthropicCitation::CitationContentBlockLocation {
+                cited_text,
+                document_index,
+                document_title,
+                ..
+            } => Citation {
+                cited_text,
+                source: document(document_index, document_title, "text/plain"),
             },
-            // Medium is 50% of the max_tokens
-            ReasoningEffort::Medium => AnthropicThinking::Enable {
-                budget_tokens: (max_tokens / 2) as usize,
+            AnthropicCitation::CitationPageLocation {
+                cited_text,
+                document_index,
+                document_title,
+                ..
+            } => Citation {
+                cited_text,
+                source: document(document_index, document_title, "application/pdf"),
             },
-            // High is 75% of the max_tokens
-            ReasoningEffort::High => AnthropicThinking::Enable {
-                budget_tokens: (max_tokens - (max_tokens / 4)) as usize,
+            AnthropicCitation::CitationsWebSearchResultLocation {
+                cited_text,
+                title,
+                url,
+                ..
+            } => Citation {
+                cited_text,
+                source: Source::Url {
+                    id: url.clone(),
+                    url,
+                    title,
+                },
             },
-        }));
+            AnthropicCitation::CitationsSearchResultLocation {
+                cited_text,
+                source,
+                title,
+                ..
+            } => Citation {
+                cited_text,
+                source: Source::Url {
+                    id: source.clone(),
+                    url: source,
+                    title,
+                },
+            },
+        }
+    }
+}
 
-        request.build().expect("Failed to build AntropicRequest")
+impl From<AnthropicWebSearchResult> for Source {
+    fn from(result: AnthropicWebSearchResult) -> Self {
+        Source::Url {
+            id: result.url.clone(),
+            url: result.url,
+            title: Some(result.title),
+        }
+    }
+}
+
+/// Returns the sources of a web search tool result. Failed searches have none.
+pub(crate) fn web_search_sources(
+    content: AnthropicWebSearchToolResultContent,
+) -> Vec<LanguageModelResponseContentType> {
+    match content {
+        AnthropicWebSearchToolResultContent::Results(results) => results
+            .into_iter()
+            .map(|r| LanguageModelResponseContentType::Source(r.into()))
+            .collect(),
+        AnthropicWebSearchToolResultContent::Error { .. } => Vec::new(),
     }
 }
 
+/// Anthropic reports cache reads and writes separately from the input tokens,
+/// which are added back so the input tokens cover the whole prompt.
 impl From<AnthropicUsage> for Usage {
     fn from(usage: AnthropicUsage) -> Self {
         Self {
-            input_tokens: Some(usage.input_tokens),
+            input_tokens: Some(
+                usage.input_tokens
+                    + usage.cache_read_input_tokens
+                    + usage.cache_creation_input_tokens,
+            ),
             output_tokens: Some(usage.output_tokens),
-            cached_tokens: Some(usage.cache_creation_input_tokens + usage.cache_read_input_tokens),
+            cached_tokens: Some(usage.cache_read_input_tokens),
+            cache_write_tokens: Some(usage.cache_creation_input_tokens),
             reasoning_tokens: None,
         }
     }
@@ -157,14 +391,234 @@ impl From<AnthropicUsage> for Usage {
 
 impl From<AnthropicMessageDeltaUsage> for Usage {
     fn from(usage: AnthropicMessageDeltaUsage) -> Self {
+        let cached = usage.cache_read_input_tokens.unwrap_or(0);
+        let cache_write = usage.cache_creation_input_tokens.unwrap_or(0);
         Self {
-            input_tokens: Some(usage.input_tokens.unwrap_or(0)),
+            input_tokens: Some(usage.input_tokens.unwrap_or(0) + cached + cache_write),
             output_tokens: Some(usage.output_tokens),
-            cached_tokens: Some(
-                usage.cache_creation_input_tokens.unwrap_or(0)
-                    + usage.cache_read_input_tokens.unwrap_or(0),
-            ),
+            cached_tokens: Some(cached),
+            cache_write_tokens: Some(cache_write),
             reasoning_tokens: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::messages::TaggedMessage;
+    use crate::core::pricing::ModelPricing;
+    use crate::core::tools::{ToolCallInfo, ToolDetails, ToolResultInfo};
+    use crate::core::{AssistantMessage, UserMessage};
+    use crate::extensions::Extensions;
+
+    fn reasoning(content: &str, signature: Option<&str>, redacted: bool) -> TaggedMessage {
+        let extensions = Extensions::default();
+        {
+            let mut metadata = extensions.get_mut::<extensions::AnthropicThinkingMetadata>();
+            metadata.signature = signature.map(str::to_string);
+            metadata.redacted = redacted;
+        }
+        TaggedMessage::from(Message::Assistant(AssistantMessage {
+            content: LanguageModelResponseContentType::Reasoning {
+                content: content.to_string(),
+                extensions,
+            },
+            usage: None,
+        }))
+    }
+
+    #[test]
+    fn test_thinking_round_trips_in_tool_loop() {
+        let mut call = ToolCallInfo::new("get_weather");
+        call.id("toolu_0");
+        call.input(serde_json::json!({ "city": "Paris" }));
+
+        let options = LanguageModelOptions {
+            thinking_budget: Some(2048),
+            reasoning_effort: Some(ReasoningEffort::High),
+            messages: vec![
+                TaggedMessage::from(Message::User(UserMessage::new("Weather in Paris?"))),
+                reasoning("I should call the tool.", Some("sig_0"), false),
+                reasoning("ENCRYPTED", None, true),
+                TaggedMessage::from(Message::Assistant(AssistantMessage {
+                    content: LanguageModelResponseContentType::ToolCall(call),
+                    usage: None,
+                })),
+                TaggedMessage::from(Message::Tool(ToolResultInfo {
+                    tool: ToolDetails {
+                        id: "toolu_0".to_string(),
+                        name: "get_weather".to_string(),
+                    },
+                    output: Ok(serde_json::Value::String("Sunny".to_string())),
+                })),
+                reasoning("Thinking of another provider", None, false),
+            ],
+            ..Default::default()
+        };
+
+        let request = serde_json::to_value(AnthropicOptions::from(options)).unwrap();
+        assert!(request.get("stop_sequences").is_none());
+        assert_eq!(request["max_tokens"], 2048 + 4096);
+        assert_eq!(
+            request["thinking"],
+            serde_json::json!({ "type": "enabled", "budget_tokens": 2048 })
+        );
+        assert_eq!(
+            request["messages"],
+            serde_json::json!([
+                { "role": "user", "content": "Weather in Paris?" },
+                {
+                    "role": "assistant",
+                    "content": [
+                        { "type": "thinking", "thinking": "I should call the tool.", "signature": "sig_0" },
+                        { "type": "redacted_thinking", "data": "ENCRYPTED" },
+                        { "type": "tool_use", "id": "toolu_0", "input": { "city": "Paris" }, "name": "get_weather" }
+                    ]
+                },
+                {
+                    "role": "user",
+                    "content": [{ "type": "tool_result", "tool_use_id": "toolu_0", "content": "\"Sunny\"" }]
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_developer_messages_extend_the_system_prompt() {
+        let options = LanguageModelOptions {
+            system: Some("Be brief.".to_string()),
+            messages: vec![
+                TaggedMessage::from(Message::Developer("Answer in French.".to_string())),
+                TaggedMessage::from(Message::User(UserMessage::new("Hi"))),
+            ],
+            ..Default::default()
+        };
+
+        let request = serde_json::to_value(AnthropicOptions::from(options)).unwrap();
+        assert_eq!(request["system"], "Be brief.\n\nAnswer in French.");
+        assert_eq!(request["messages"].as_array().unwrap().len(), 1);
+        assert_eq!(request["messages"][0]["role"], "user");
+    }
+
+    #[test]
+    fn test_sampling_settings_are_sent() {
+        let options = LanguageModelOptions {
+            temperature: Some(0.25),
+            top_p: Some(0.5),
+            top_k: Some(40),
+            messages: vec![TaggedMessage::from(Message::User(UserMessage::new("Hi")))],
+            ..Default::default()
+        };
+
+        let request = serde_json::to_value(AnthropicOptions::from(options)).unwrap();
+        assert_eq!(request["temperature"], 0.25);
+        assert_eq!(request["top_p"], 0.5);
+        assert_eq!(request["top_k"], 40);
+    }
+
+    #[test]
+    fn test_stop_sequences_are_sent() {
+        let options = LanguageModelOptions {
+            stop_sequences: Some(vec!["</answer>".to_string(), "END".to_string()]),
+            messages: vec![TaggedMessage::from(Message::User(UserMessage::new("Hi")))],
+            ..Default::default()
+        };
+
+        let request = serde_json::to_value(AnthropicOptions::from(options)).unwrap();
+        assert_eq!(
+            request["stop_sequences"],
+            serde_json::json!(["</answer>", "END"])
+        );
+    }
+
+    #[test]
+    fn test_user_is_sent_as_metadata() {
+        let options = LanguageModelOptions {
+            user: Some("user_42".to_string()),
+            messages: vec![TaggedMessage::from(Message::User(UserMessage::new("Hi")))],
+            ..Default::default()
+        };
+
+        let request = serde_json::to_value(AnthropicOptions::from(options)).unwrap();
+        assert_eq!(
+            request["metadata"],
+            serde_json::json!({ "user_id": "user_42" })
+        );
+    }
+
+    #[test]
+    fn test_citations_and_web_search_results_are_converted() {
+        let mut response = AnthropicMessageResponse::default();
+        response.content = serde_json::from_value(serde_json::json!([
+                { "type": "server_tool_use", "id": "srvtoolu_0", "name": "web_search", "input": { "query": "rust" } },
+                { "type": "web_search_tool_result", "tool_use_id": "srvtoolu_0", "content": [
+                    { "type": "web_search_result", "url": "https://rust-lang.org", "title": "Rust", "encrypted_content": "e0" }
+                ] },
+                { "type": "text", "text": "Rust is " },
+                { "type": "text", "text": "a systems language", "citations": [
+                    { "type": "web_search_result_location", "url": "https://rust-lang.org", "title": "Rust", "encrypted_index": "i0", "cited_text": "Rust is a language" }
+                ] },
+                { "type": "text", "text": ".", "citations": [
+                    { "type": "page_location", "cited_text": "Page", "document_index": 0, "document_title": null, "start_page_number": 1, "end_page_number": 2 }
+                ] }
+            ]))
+            .unwrap();
+
+        let contents = LanguageModelResponse::from(response).contents;
+        assert_eq!(contents.len(), 4);
+        assert!(
+            matches!(&contents[0], LanguageModelResponseContentType::Text(text) if text == "Rust is a systems language.")
+        );
+        let url = Source::Url {
+            id: "https://rust-lang.org".to_string(),
+            url: "https://rust-lang.org".to_string(),
+            title: Some("Rust".to_string()),
+        };
+        assert!(matches!(&contents[1], LanguageModelResponseContentType::Source(s) if *s == url));
+        assert!(
+            matches!(&contents[2], LanguageModelResponseContentType::Citation(c) if c.source == url && c.cited_text == "Rust is a language")
+        );
+        match &contents[3] {
+            LanguageModelResponseContentType::Citation(Citation {
+                source:
+                    Source::Document {
+                        id,
+                        title,
+                        media_type,
+                        ..
+                    },
+                ..
+            }) => {
+                assert_eq!(id, "document-0");
+                assert_eq!(title, "Document 1");
+                assert_eq!(media_type, "application/pdf");
+            }
+            other => panic!("Expected a document citation, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_usage_counts_cache_reads_and_writes_as_input() {
+        let usage: AnthropicUsage = serde_json::from_value(serde_json::json!({
+            "input_tokens": 100,
+            "output_tokens": 50,
+            "cache_creation_input_tokens": 2_000,
+            "cache_read_input_tokens": 10_000,
+            "cache_creation": {"ephemeral_5m_input_tokens": 2_000, "ephemeral_1h_input_tokens": 0},
+            "service_tier": "standard",
+        }))
+        .unwrap();
+        let usage = Usage::from(usage);
+
+        assert_eq!(usage.input_tokens, Some(12_100));
+        assert_eq!(usage.cached_tokens, Some(10_000));
+        assert_eq!(usage.cache_write_tokens, Some(2_000));
+
+        // 100 input * 3.0 + 10k read * 0.3 + 2k written * 3.75 + 50 output * 15.0
+        let pricing = ModelPricing::new(3.0, 15.0)
+            .with_cached(0.3)
+            .with_cache_write(3.75);
+        assert!((usage.cost(&pricing) - 0.01155).abs() < 1e-12);
+    }
+}
@@ -1,11 +1,23 @@
 //! Anthropic-specific metadata for extensions.
 
+use crate::extensions::PersistedExtension;
+use serde::{Deserialize, Serialize};
+
 /// Metadata specific to Anthropic provider thinking functionality.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub(crate) struct AnthropicThinkingMetadata {
     /// The signature returned by Anthropic models for thinking blocks.
     ///
     /// This must be preserved and sent back in subsequent turns
     /// for extended thinking to work correctly.
     pub signature: Option<String>,
+
+    /// Whether the thinking block was redacted, in which case the reasoning
+    /// content holds the encrypted data of the block.
+    #[serde(default)]
+    pub redacted: bool,
+}
+
+impl PersistedExtension for AnthropicThinkingMetadata {
+    const KEY: &'static str = "anthropic.thinking";
 }
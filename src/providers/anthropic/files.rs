@@ -0,0 +1,179 @@
+//! Files API of the Anthropic provider.
+//!
+//! The Files API is in beta, so its requests, and the messages referencing a
+//! stored file, carry the [`ANTHROPIC_FILES_BETA`] header.
+
+use crate::core::capabilities::ModelName;
+use crate::core::client::{LanguageModelClient, multipart_body, send_json};
+use crate::core::files::{FileHandle, FileProvider, FilePurpose, mime_type};
+use crate::core::utils::join_url;
+use crate::error::{Error, Result};
+use crate::providers::anthropic::{ANTHROPIC_FILES_BETA, Anthropic};
+use async_trait::async_trait;
+use reqwest::header::CONTENT_TYPE;
+use serde::Deserialize;
+
+/// A file of the Files API.
+#[derive(Debug, Deserialize)]
+struct AnthropicFile {
+    id: String,
+    #[serde(default)]
+    filename: Option<String>,
+    #[serde(default)]
+    mime_type: Option<String>,
+    #[serde(default)]
+    size_bytes: Option<u64>,
+}
+
+impl From<AnthropicFile> for FileHandle {
+    fn from(file: AnthropicFile) -> Self {
+        Self {
+            id: file.id,
+            filename: file.filename,
+            mime_type: file.mime_type,
+            size: file.size_bytes,
+            uri: None,
+        }
+    }
+}
+
+/// A page of the list of files.
+#[derive(Debug, Deserialize)]
+struct AnthropicFileList {
+    data: Vec<AnthropicFile>,
+    #[serde(default)]
+    has_more: bool,
+}
+
+impl<M: ModelName> Anthropic<M> {
+    /// Builds a request to an endpoint of the Files API.
+    fn files_request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+    ) -> Result<reqwest::RequestBuilder> {
+        let url = join_url(self.settings.base_url.as_str(), &format!("/files{path}"))?;
+        let mut headers = LanguageModelClient::headers(self);
+        headers.insert(
+            "anthropic-beta",
+            ANTHROPIC_FILES_BETA.parse().expect("valid header value"),
+        );
+        Ok(LanguageModelClient::http_client(self)
+            .request(method, url)
+            .headers(headers))
+    }
+}
+
+#[async_trait]
+impl<M: ModelName> FileProvider for Anthropic<M> {
+    /// Uploads a file. The purpose is ignored, as files are not tied to one.
+    async fn upload_file(
+        &self,
+        bytes: Vec<u8>,
+        filename: &str,
+        _purpose: FilePurpose,
+    ) -> Result<FileHandle> {
+        let (content_type, body) =
+            multipart_body(&[], "file", filename, mime_type(filename), &bytes);
+        // Replaces the JSON content type of the provider headers
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            CONTENT_TYPE,
+            content_type
+                .parse()
+                .map_err(|_| Error::InvalidInput("Invalid file name".to_string()))?,
+        );
+
+        let request = self
+            .files_request(reqwest::Method::POST, "")?
+            .headers(headers)
+            .body(body);
+        let file: AnthropicFile = send_json(request).await?;
+        Ok(file.into())
+    }
+
+    async fn list_files(&self) -> Result<Vec<FileHandle>> {
+        let mut files: Vec<FileHandle> = Vec::new();
+        loop {
+            let mut request = self.files_request(reqwest::Method::GET, "")?;
+            if let Some(last) = files.last() {
+                request = request.query(&[("after_id", last.id.as_str())]);
+            }
+            let page: AnthropicFileList = send_json(request).await?;
+            let empty = page.data.is_empty();
+            files.extend(page.data.into_iter().map(FileHandle::from));
+            if !page.has_more || empty {
+                return Ok(files);
+            }
+        }
+    }
+
+    async fn delete_file(&self, id: &str) -> Result<()> {
+        let request = self.files_request(reqwest::Method::DELETE, &format!("/{id}"))?;
+        let _: serde_json::Value = send_json(request).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DynamicModel;
+    use crate::core::client::test_server::{TestResponse, serve};
+
+    /// Serves JSON responses on a local port, one per connection, and returns its
+    #[tokio::test]
+    async fn test_upload_list_and_delete_files() {
+        let (base_url, mut requests) = serve(vec![
+            TestResponse::json(r#"{"id":"file_1","type":"file","filename":"report.pdf","mime_type":"application/pdf","size_bytes":5,"created_at":"2025-04-14T00:00:00Z"}"#),
+            TestResponse::json(r#"{"data":[{"id":"file_1","type":"file","filename":"report.pdf","mime_type":"application/pdf","size_bytes":5}],"has_more":true,"first_id":"file_1","last_id":"file_1"}"#),
+            TestResponse::json(r#"{"data":[{"id":"file_2","type":"file","filename":"photo.png","mime_type":"image/png","size_bytes":9}],"has_more":false,"first_id":"file_2","last_id":"file_2"}"#),
+            TestResponse::json(r#"{"id":"file_1","type":"file_deleted"}"#),
+        ])
+        .await;
+
+        let anthropic = Anthropic::<DynamicModel>::builder()
+            .model_name("claude-sonnet-4-5")
+            .base_url(format!("{base_url}/v1/"))
+            .api_key("test")
+            .build()
+            .unwrap();
+
+        let file = anthropic
+            .upload_file(b"%PDF-".to_vec(), "report.pdf", FilePurpose::UserData)
+            .await
+            .unwrap();
+        assert_eq!(file.id, "file_1");
+        assert_eq!(file.size, Some(5));
+
+        let upload = requests.recv().await.unwrap();
+        assert!(upload.starts_with("POST /v1/files "));
+        assert!(upload.contains(&format!("anthropic-beta: {ANTHROPIC_FILES_BETA}")));
+        assert!(upload.contains("content-type: multipart/form-data; boundary="));
+        assert!(!upload.contains("name=\"purpose\""));
+
+        let files = anthropic.list_files().await.unwrap();
+        assert_eq!(
+            files.iter().map(|f| f.id.as_str()).collect::<Vec<_>>(),
+            ["file_1", "file_2"]
+        );
+        assert!(files[1].is_image());
+        assert!(requests.recv().await.unwrap().starts_with("GET /v1/files "));
+        assert!(
+            requests
+                .recv()
+                .await
+                .unwrap()
+                .starts_with("GET /v1/files?after_id=file_1 ")
+        );
+
+        anthropic.delete_file("file_1").await.unwrap();
+        assert!(
+            requests
+                .recv()
+                .await
+                .unwrap()
+                .starts_with("DELETE /v1/files/file_1 ")
+        );
+    }
+}
@@ -1,25 +1,29 @@
 //! Language model implementation for the Anthropic provider.
 
+use crate::core::capabilities::CapabilitySet;
 use crate::core::capabilities::ModelName;
-use crate::core::client::LanguageModelClient;
+use crate::core::client::{self, LanguageModelClient};
+use crate::core::credentials::resolve_api_key;
 use crate::core::language_model::{
     LanguageModelOptions, LanguageModelResponse, LanguageModelResponseContentType,
-    LanguageModelStreamChunk, ProviderStream,
+    LanguageModelStreamChunk, ProviderStream, ResponseMetadata, ToolCallDelta,
 };
-use crate::core::messages::AssistantMessage;
+use crate::core::messages::{AssistantMessage, TaggedMessage};
+use crate::core::pricing::ModelPricing;
 use crate::core::tools::ToolDetails;
-use crate::core::{LanguageModelStreamChunkType, ToolCallInfo};
+use crate::core::{LanguageModelStreamChunkType, Message, ToolCallInfo};
 use crate::extensions::Extensions;
 use crate::providers::anthropic::Anthropic;
 use crate::providers::anthropic::client::{
     AnthropicContentBlock, AnthropicDelta, AnthropicMessageDeltaUsage, AnthropicOptions,
     AnthropicStreamEvent,
 };
+use crate::providers::anthropic::conversions::web_search_sources;
 use crate::providers::anthropic::extensions;
 use crate::{core::language_model::LanguageModel, error::Result};
 use async_trait::async_trait;
 use futures::StreamExt;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 #[async_trait]
 impl<M: ModelName> LanguageModel for Anthropic<M> {
@@ -28,92 +32,106 @@ impl<M: ModelName> LanguageModel for Anthropic<M> {
         self.options.model.clone()
     }
 
+    fn pricing(&self) -> Option<ModelPricing> {
+        M::PRICING
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        M::CONTEXT_WINDOW
+    }
+
+    fn max_output_tokens(&self) -> Option<usize> {
+        M::MAX_OUTPUT_TOKENS
+    }
+
+    fn capabilities(&self) -> CapabilitySet {
+        M::CAPABILITIES
+    }
+
+    /// Counts tokens using Anthropic's token counting endpoint.
+    async fn count_tokens(&mut self, messages: &[Message]) -> Result<usize> {
+        let options = LanguageModelOptions {
+            messages: messages.iter().cloned().map(TaggedMessage::from).collect(),
+            ..Default::default()
+        };
+        let mut options: AnthropicOptions = options.into();
+        options.model = self.options.model.clone();
+        self.options = options;
+
+        let response = client::TokenCountClient::send(self, self.settings.base_url.clone()).await?;
+
+        Ok(response.input_tokens)
+    }
+
     /// Generates text using the Anthropic provider.
     async fn generate_text(
         &mut self,
-        options: LanguageModelOptions,
+        mut options: LanguageModelOptions,
     ) -> Result<LanguageModelResponse> {
+        resolve_api_key(
+            &self.settings.credential_provider,
+            &mut self.settings.api_key,
+        )
+        .await?;
+        options.resolve_constrained_output("Anthropic", |_| false)?;
+        options.validate_sampling(0.0..=1.0)?;
         let additional_headers = options.headers.clone();
+        let additional_query = options.query.clone();
+        let max_retries = options.max_retries;
         let mut options: AnthropicOptions = options.into();
         options.model = self.options.model.clone();
         self.options = options;
 
-        let response = self
-            .send(self.settings.base_url.clone(), additional_headers)
+        let (response, metadata) = self
+            .send_with_metadata(
+                self.settings.base_url.clone(),
+                additional_headers,
+                additional_query,
+                max_retries,
+            )
             .await?;
 
-        let mut collected: Vec<LanguageModelResponseContentType> = Vec::new();
-
-        for out in response.content {
-            match out {
-                AnthropicContentBlock::Text { text, .. } => {
-                    collected.push(LanguageModelResponseContentType::new(text));
-                }
-                AnthropicContentBlock::Thinking {
-                    signature,
-                    thinking,
-                } => {
-                    let extensions = Extensions::default();
-                    extensions
-                        .get_mut::<extensions::AnthropicThinkingMetadata>()
-                        .signature = Some(signature);
-                    collected.push(LanguageModelResponseContentType::Reasoning {
-                        content: thinking,
-                        extensions,
-                    });
-                }
-                AnthropicContentBlock::RedactedThinking { data } => {
-                    collected.push(LanguageModelResponseContentType::Reasoning {
-                        content: data,
-                        extensions: Extensions::default(),
-                    });
-                }
-                AnthropicContentBlock::ToolUse { id, input, name } => {
-                    collected.push(LanguageModelResponseContentType::ToolCall(ToolCallInfo {
-                        input,
-                        tool: ToolDetails {
-                            id: id.to_string(),
-                            name: name.to_string(),
-                        },
-                        extensions: Extensions::default(),
-                    }));
-                }
-            }
-        }
-
-        Ok(LanguageModelResponse {
-            contents: collected,
-            usage: Some(response.usage.into()),
-        })
+        let mut response: LanguageModelResponse = response.into();
+        response.metadata.get_or_insert_default().merge(metadata);
+        Ok(response)
     }
 
     /// Streams text using the Anthropic provider.
-    async fn stream_text(&mut self, options: LanguageModelOptions) -> Result<ProviderStream> {
+    async fn stream_text(&mut self, mut options: LanguageModelOptions) -> Result<ProviderStream> {
+        resolve_api_key(
+            &self.settings.credential_provider,
+            &mut self.settings.api_key,
+        )
+        .await?;
+        options.resolve_constrained_output("Anthropic", |_| false)?;
+        options.validate_sampling(0.0..=1.0)?;
         let additional_headers = options.headers.clone();
+        let additional_query = options.query.clone();
+        let max_retries = options.max_retries.unwrap_or(5);
         let mut options: AnthropicOptions = options.into();
         options.stream = Some(true);
         options.model = self.options.model.clone();
         self.options = options;
 
         // Retry logic for rate limiting
-        let max_retries = 5;
         let mut retry_count = 0;
         let mut wait_time = std::time::Duration::from_secs(1);
 
         let response = loop {
             match self
-                .send_and_stream(self.settings.base_url.clone(), additional_headers.clone())
+                .send_and_stream(
+                    self.settings.base_url.clone(),
+                    additional_headers.clone(),
+                    additional_query.clone(),
+                )
                 .await
             {
                 Ok(stream) => break stream,
-                Err(crate::error::Error::ApiError {
-                    status_code: Some(status),
-                    ..
-                }) if status == reqwest::StatusCode::TOO_MANY_REQUESTS
-                    && retry_count < max_retries =>
+                Err(crate::error::Error::RateLimited { retry_after, .. })
+                    if retry_count < max_retries =>
                 {
                     retry_count += 1;
-                    tokio::time::sleep(wait_time).await;
+                    crate::core::runtime::sleep(retry_after.unwrap_or(wait_time)).await;
                     wait_time *= 2; // Exponential backoff
                     continue;
                 }
@@ -123,7 +141,8 @@ impl<M: ModelName> LanguageModel for Anthropic<M> {
 
         #[derive(Default)]
         struct StreamState {
-            content_blocks: HashMap<usize, AccumulatedBlock>,
+            // Ordered by index, so thinking blocks stay ahead of the tool calls they precede
+            content_blocks: BTreeMap<usize, AccumulatedBlock>,
             usage: Option<AnthropicMessageDeltaUsage>,
         }
 
@@ -140,6 +159,8 @@ impl<M: ModelName> LanguageModel for Anthropic<M> {
                 name: String,
                 accumulated_json: String,
             },
+            // Server tool blocks, executed by Anthropic
+            Server,
         }
 
         let stream = response.scan::<_, Result<Vec<LanguageModelStreamChunk>>, _, _>(
@@ -153,11 +174,16 @@ impl<M: ModelName> LanguageModel for Anthropic<M> {
                 futures::future::ready({
                     match evt_res {
                     Ok(event) => match event {
-                        AnthropicStreamEvent::MessageStart { .. } => {
-                            Some(Ok(vec![LanguageModelStreamChunk::Delta(
-                                LanguageModelStreamChunkType::Start,
-                            )]))
-                        }
+                        AnthropicStreamEvent::MessageStart { message } => Some(Ok(vec![
+                            LanguageModelStreamChunk::Delta(LanguageModelStreamChunkType::Start),
+                            LanguageModelStreamChunk::Delta(
+                                LanguageModelStreamChunkType::ResponseMetadata(ResponseMetadata {
+                                    request_id: None,
+                                    response_id: Some(message.id),
+                                    model: Some(message.model),
+                                }),
+                            ),
+                        ])),
                         AnthropicStreamEvent::ContentBlockStart {
                             index,
                             content_block,
@@ -195,6 +221,24 @@ impl<M: ModelName> LanguageModel for Anthropic<M> {
                                 );
                                 Some(Ok(unsupported("ContentBlockStart::ToolUse")))
                             }
+                            AnthropicContentBlock::ServerToolUse { .. } => {
+                                state.content_blocks.insert(index, AccumulatedBlock::Server);
+                                Some(Ok(unsupported("ContentBlockStart::ServerToolUse")))
+                            }
+                            AnthropicContentBlock::WebSearchToolResult { content, .. } => {
+                                state.content_blocks.insert(index, AccumulatedBlock::Server);
+                                Some(Ok(web_search_sources(content)
+                                    .into_iter()
+                                    .filter_map(|source| match source {
+                                        LanguageModelResponseContentType::Source(source) => {
+                                            Some(LanguageModelStreamChunk::Delta(
+                                                LanguageModelStreamChunkType::Source(source),
+                                            ))
+                                        }
+                                        _ => None,
+                                    })
+                                    .collect()))
+                            }
                         },
                         AnthropicStreamEvent::ContentBlockDelta { index, delta } => {
                             if let Some(block) = state.content_blocks.get_mut(&index) {
@@ -208,6 +252,12 @@ impl<M: ModelName> LanguageModel for Anthropic<M> {
                                             LanguageModelStreamChunkType::Text(delta_text),
                                         )]))
                                     }
+                                    (
+                                        AccumulatedBlock::Text(_),
+                                        AnthropicDelta::CitationDelta { citation },
+                                    ) => Some(Ok(vec![LanguageModelStreamChunk::Delta(
+                                        LanguageModelStreamChunkType::Citation(citation.into()),
+                                    )])),
                                     (
                                         AccumulatedBlock::Thinking { thinking, .. },
                                         AnthropicDelta::ThinkingDelta { thinking: delta_thinking },
@@ -226,13 +276,19 @@ impl<M: ModelName> LanguageModel for Anthropic<M> {
                                     }
                                     (
                                         AccumulatedBlock::ToolUse {
-                                            accumulated_json, ..
+                                            id,
+                                            name,
+                                            accumulated_json,
                                         },
                                         AnthropicDelta::ToolUseDelta { partial_json },
                                     ) => {
                                         accumulated_json.push_str(&partial_json);
                                         Some(Ok(vec![LanguageModelStreamChunk::Delta(
-                                            LanguageModelStreamChunkType::ToolCall(partial_json),
+                                            LanguageModelStreamChunkType::ToolCall(ToolCallDelta {
+                                                id: id.clone(),
+                                                name: name.clone(),
+                                                delta: partial_json,
+                                            }),
                                         )]))
                                     }
                                     _ => Some(Ok(unsupported("ContentBlockDelta"))),
@@ -250,10 +306,20 @@ impl<M: ModelName> LanguageModel for Anthropic<M> {
                         }
                         AnthropicStreamEvent::MessageStop => {
                             let mut collected = vec![];
+                            let mut previous_was_text = false;
                             for block in state.content_blocks.values() {
+                                let is_text = matches!(block, AccumulatedBlock::Text(_));
                                 match block {
-                                    AccumulatedBlock::Text(text) => collected
-                                        .push(LanguageModelResponseContentType::new(text.clone())),
+                                    // Cited passages are split into their own text blocks
+                                    AccumulatedBlock::Text(text) => match collected.last_mut() {
+                                        Some(LanguageModelResponseContentType::Text(previous))
+                                            if previous_was_text =>
+                                        {
+                                            previous.push_str(text)
+                                        }
+                                        _ => collected
+                                            .push(LanguageModelResponseContentType::new(text.clone())),
+                                    },
                                     AccumulatedBlock::Thinking { thinking, signature } => {
                                         let extensions = Extensions::default();
                                         if let Some(sig) = signature {
@@ -266,12 +332,16 @@ impl<M: ModelName> LanguageModel for Anthropic<M> {
                                             extensions,
                                         })
                                     }
-                                    AccumulatedBlock::RedactedThinking(data) => collected.push(
-                                        LanguageModelResponseContentType::Reasoning {
+                                    AccumulatedBlock::RedactedThinking(data) => {
+                                        let extensions = Extensions::default();
+                                        extensions
+                                            .get_mut::<extensions::AnthropicThinkingMetadata>()
+                                            .redacted = true;
+                                        collected.push(LanguageModelResponseContentType::Reasoning {
                                             content: data.clone(),
-                                            extensions: Extensions::default(),
-                                        },
-                                    ),
+                                            extensions,
+                                        })
+                                    }
                                     AccumulatedBlock::ToolUse {
                                         id,
                                         name,
@@ -305,7 +375,9 @@ impl<M: ModelName> LanguageModel for Anthropic<M> {
                                             );
                                         }
                                     }
+                                    AccumulatedBlock::Server => {}
                                 }
+                                previous_was_text = is_text;
                             }
                             Some(Ok(collected
                                 .into_iter()
@@ -1,18 +1,22 @@
 //! This module provides the Anthropic provider, which implements the `LanguageModel`
 //! and `Provider` traits for interacting with the Anthropic API.
 
+pub mod batch;
 pub mod capabilities;
 /// Client implementation for Anthropic API.
 pub mod client;
 /// Conversion utilities for Anthropic types.
 pub mod conversions;
 pub mod extensions;
+pub mod files;
 pub mod language_model;
 pub mod settings;
 
 use crate::core::DynamicModel;
 use crate::core::capabilities::ModelName;
-use crate::core::utils::validate_base_url;
+use crate::core::client::HttpSettings;
+use crate::core::credentials::{CredentialProvider, Credentials};
+use crate::core::utils::{env_var, validate_base_url};
 use crate::error::Error;
 use crate::providers::anthropic::client::AnthropicOptions;
 use crate::providers::anthropic::settings::AnthropicProviderSettings;
@@ -21,6 +25,29 @@ use serde::Serialize;
 /// The API version used for Anthropic requests.
 pub const ANTHROPIC_API_VERSION: &str = "2023-06-01";
 
+/// The beta of the Files API, needed to upload files and to reference them in messages.
+pub const ANTHROPIC_FILES_BETA: &str = "files-api-2025-04-14";
+
+/// The beta streaming the input of tool calls without buffering and validating
+/// it, which lowers the latency of large inputs.
+///
+/// The streamed input may not be valid JSON, in which case the tool call is
+/// reported as not supported.
+pub const ANTHROPIC_FINE_GRAINED_TOOL_STREAMING_BETA: &str =
+    "fine-grained-tool-streaming-2025-05-14";
+
+/// The beta letting Claude 4 models think between tool calls.
+pub const ANTHROPIC_INTERLEAVED_THINKING_BETA: &str = "interleaved-thinking-2025-05-14";
+
+/// The beta extending the context window of Claude Sonnet 4 to one million tokens.
+pub const ANTHROPIC_CONTEXT_1M_BETA: &str = "context-1m-2025-08-07";
+
+/// The beta of the token efficient tool use of Claude Sonnet 3.7.
+pub const ANTHROPIC_TOKEN_EFFICIENT_TOOLS_BETA: &str = "token-efficient-tools-2025-02-19";
+
+/// The beta raising the output limit of Claude Sonnet 3.7 to 128k tokens.
+pub const ANTHROPIC_OUTPUT_128K_BETA: &str = "output-128k-2025-02-19";
+
 /// The Anthropic provider.
 #[derive(Debug, Serialize, Clone)]
 pub struct Anthropic<M: ModelName> {
@@ -35,6 +62,19 @@ impl<M: ModelName> Anthropic<M> {
     pub fn builder() -> AnthropicBuilder<M> {
         AnthropicBuilder::default()
     }
+
+    /// Creates an Anthropic provider builder configured from the environment.
+    ///
+    /// Reads the API key from `ANTHROPIC_API_KEY` and the base URL from
+    /// `ANTHROPIC_BASE_URL`.
+    /// Unset variables keep the defaults.
+    pub fn from_env() -> AnthropicBuilder<M> {
+        let mut builder = Self::builder();
+        if let Some(base_url) = env_var("ANTHROPIC_BASE_URL") {
+            builder = builder.base_url(base_url);
+        }
+        builder
+    }
 }
 
 impl Anthropic<DynamicModel> {
@@ -162,6 +202,51 @@ impl<M: ModelName> AnthropicBuilder<M> {
         self
     }
 
+    /// Sets the credentials resolving the API key before each request, to fetch
+    /// it from a secret manager or rotate it. Takes precedence over `api_key`.
+    ///
+    /// # Parameters
+    ///
+    /// * `credentials` - The source of the API key.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the credentials set.
+    pub fn credential_provider(mut self, credentials: impl CredentialProvider + 'static) -> Self {
+        self.settings.credential_provider = Some(Credentials::new(credentials));
+        self
+    }
+
+    /// Sets the HTTP client sending the requests, to share its pool of connections
+    /// or configure it. Requests share one pooled client by default.
+    ///
+    /// # Parameters
+    ///
+    /// * `client` - The `reqwest` client sending the requests.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the HTTP client set.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.settings.http_client = Some(client);
+        self
+    }
+
+    /// Sets the network settings of the HTTP client, such as a proxy or
+    /// certificate authorities. Ignored if an HTTP client is set.
+    ///
+    /// # Parameters
+    ///
+    /// * `http` - The network settings.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the network settings set.
+    pub fn http_settings(mut self, http: HttpSettings) -> Self {
+        self.settings.http = http;
+        self
+    }
+
     /// Sets the name of the provider. Defaults to "anthropic".
     ///
     /// # Parameters
@@ -182,6 +267,22 @@ impl<M: ModelName> AnthropicBuilder<M> {
         self
     }
 
+    /// Opts into Anthropic beta features, sent in the `anthropic-beta` header of
+    /// every request. Known betas have constants, such as
+    /// [`ANTHROPIC_FINE_GRAINED_TOOL_STREAMING_BETA`].
+    ///
+    /// # Parameters
+    ///
+    /// * `betas` - The names of the beta features.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the beta features set.
+    pub fn beta_features(mut self, betas: &[&str]) -> Self {
+        self.settings.beta_features = betas.iter().map(|beta| beta.to_string()).collect();
+        self
+    }
+
     /// Builds the Anthropic provider.
     ///
     /// Validates the configuration and creates the provider instance.
@@ -194,13 +295,17 @@ impl<M: ModelName> AnthropicBuilder<M> {
         let base_url = validate_base_url(&self.settings.base_url)?;
 
         // check api key exists
-        if self.settings.api_key.is_empty() {
+        if self.settings.api_key.is_empty() && self.settings.credential_provider.is_none() {
             return Err(Error::MissingField("api_key".to_string()));
         }
 
         Ok(Anthropic {
             settings: AnthropicProviderSettings {
                 base_url,
+                http_client: self
+                    .settings
+                    .http
+                    .resolve_client(self.settings.http_client.clone())?,
                 ..self.settings
             },
             options: self.options,
@@ -1,5 +1,7 @@
 //! Defines the settings for the Anthropic provider.
 
+use crate::core::client::HttpSettings;
+use crate::core::credentials::Credentials;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 
@@ -16,9 +18,28 @@ pub struct AnthropicProviderSettings {
     /// The API key for the Anthropic API.
     pub api_key: String,
 
+    /// Credentials resolving the API key before each request, in place of
+    /// `api_key`.
+    #[serde(skip)]
+    pub credential_provider: Option<Credentials>,
+
+    /// The HTTP client sending the requests. Requests share one pooled client
+    /// if it is not set.
+    #[serde(skip)]
+    pub http_client: Option<reqwest::Client>,
+
+    /// Network settings of the HTTP client built for the provider when no
+    /// client is set, such as a proxy or certificate authorities.
+    #[serde(default)]
+    pub http: HttpSettings,
+
     /// Custom API path override. When set, this path is used instead of the
     /// default "/messages".
     pub path: Option<String>,
+
+    /// Beta features sent in the `anthropic-beta` header of every request.
+    #[serde(default)]
+    pub beta_features: Vec<String>,
 }
 
 impl Default for AnthropicProviderSettings {
@@ -28,7 +49,11 @@ impl Default for AnthropicProviderSettings {
             provider_name: "anthropic".to_string(),
             base_url: "https://api.anthropic.com/v1/".to_string(),
             api_key: std::env::var("ANTHROPIC_API_KEY").unwrap_or_default(),
+            credential_provider: None,
+            http_client: None,
+            http: HttpSettings::default(),
             path: None,
+            beta_features: Vec::new(),
         }
     }
 }
@@ -1,23 +1,395 @@
 //! This module provides the CloudflareAiGateway provider, wrapping OpenAI Chat Completions for CloudflareAiGateway requests.
+//!
+//! Requests are sent to the gateway of an account, either to its OpenAI-compatible
+//! endpoint, with model names prefixed by their provider, or to the endpoint of an
+//! upstream provider.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use aisdk::providers::CloudflareAiGateway;
+//! use aisdk::core::DynamicModel;
+//!
+//! let provider = CloudflareAiGateway::<DynamicModel>::builder()
+//!     .account_id("your-account-id")
+//!     .gateway_id("your-gateway")
+//!     .gateway_token("your-gateway-token")
+//!     .model_name("openai/gpt-4o")
+//!     .build()
+//!     .unwrap();
+//! ```
 
 pub mod capabilities;
+pub mod settings;
 
-// Generate the settings module
-crate::openai_compatible_settings!(
-    CloudflareAiGatewayProviderSettings,
-    CloudflareAiGatewayProviderSettingsBuilder,
-    "CloudflareAiGateway",
-    "https://gateway.ai.cloudflare.com/v1/${CLOUDFLARE_ACCOUNT_ID}/${CLOUDFLARE_GATEWAY_ID}/compat/",
-    "CLOUDFLARE_API_TOKEN"
-);
-
-// Generate the provider struct and builder
-crate::openai_compatible_provider!(
-    CloudflareAiGateway,
-    CloudflareAiGatewayBuilder,
-    CloudflareAiGatewayProviderSettings,
-    "cloudflare-ai-gateway"
-);
+use crate::Error;
+use crate::core::DynamicModel;
+use crate::core::capabilities::ModelName;
+use crate::core::client::HttpSettings;
+use crate::core::credentials::{CredentialProvider, Credentials};
+use crate::core::utils::{env_var, validate_base_url};
+use crate::error::Result;
+use crate::providers::openai_chat_completions::OpenAIChatCompletions;
+use settings::CloudflareAiGatewayProviderSettings;
+
+/// The CloudflareAiGateway provider, wrapping OpenAI Chat Completions API.
+#[derive(Debug, Clone)]
+pub struct CloudflareAiGateway<M: ModelName> {
+    /// Configuration settings for the CloudflareAiGateway provider.
+    pub settings: CloudflareAiGatewayProviderSettings,
+    pub(crate) inner: OpenAIChatCompletions<M>,
+}
+
+impl<M: ModelName> CloudflareAiGateway<M> {
+    /// CloudflareAiGateway provider setting builder.
+    pub fn builder() -> CloudflareAiGatewayBuilder<M> {
+        CloudflareAiGatewayBuilder::default()
+    }
+
+    /// Creates a CloudflareAiGateway provider builder configured from the environment.
+    ///
+    /// Reads the account from `CLOUDFLARE_ACCOUNT_ID`, the gateway from
+    /// `CLOUDFLARE_GATEWAY_ID`, the API key from `CLOUDFLARE_API_TOKEN` and the base URL
+    /// from `CLOUDFLARE_AI_GATEWAY_BASE_URL`.
+    /// Unset variables keep the defaults.
+    pub fn from_env() -> CloudflareAiGatewayBuilder<M> {
+        let mut builder = Self::builder();
+        if let Some(base_url) = env_var("CLOUDFLARE_AI_GATEWAY_BASE_URL") {
+            builder = builder.base_url(base_url);
+        }
+        builder
+    }
+}
+
+impl CloudflareAiGateway<DynamicModel> {
+    /// Creates a CloudflareAiGateway provider with a dynamic model name using default settings.
+    ///
+    /// This allows you to specify the model name as a string rather than
+    /// using typed constructor methods.
+    ///
+    /// **WARNING**: when using `DynamicModel`, model capabilities are not validated.
+    /// This means there is no compile-time guarantee that the model supports requested features.
+    ///
+    /// For custom configuration (account, gateway, API key, etc.), use the builder pattern:
+    /// `CloudflareAiGateway::<DynamicModel>::builder().model_name(...).account_id(...).build()`
+    ///
+    /// # Parameters
+    ///
+    /// * `model_name` - The model identifier (e.g., "openai/gpt-4o")
+    ///
+    /// # Returns
+    ///
+    /// A configured `CloudflareAiGateway<DynamicModel>` provider instance with default settings.
+    pub fn model_name(name: impl Into<String>) -> Self {
+        let settings = CloudflareAiGatewayProviderSettings::default();
+        let inner = OpenAIChatCompletions::<DynamicModel>::model_name(name);
+
+        CloudflareAiGateway { settings, inner }
+    }
+}
+
+impl<M: ModelName> Default for CloudflareAiGateway<M> {
+    /// Creates a new CloudflareAiGateway provider with default settings.
+    fn default() -> CloudflareAiGateway<M> {
+        CloudflareAiGatewayBuilder::default().build().unwrap()
+    }
+}
+
+/// CloudflareAiGateway provider builder
+pub struct CloudflareAiGatewayBuilder<M: ModelName> {
+    settings: CloudflareAiGatewayProviderSettings,
+    inner: OpenAIChatCompletions<M>,
+}
+
+impl<M: ModelName> Default for CloudflareAiGatewayBuilder<M> {
+    /// Creates a new CloudflareAiGateway provider builder with default settings.
+    fn default() -> Self {
+        let settings = CloudflareAiGatewayProviderSettings::default();
+        let mut inner = OpenAIChatCompletions::default();
+        inner.settings.provider_name = settings.provider_name.clone();
+        inner.settings.api_key = settings.api_key.clone();
+        inner.settings.path = settings.path.clone();
+
+        Self { settings, inner }
+    }
+}
+
+impl<M: ModelName> CloudflareAiGatewayBuilder<M> {
+    /// Sets the provider name for the CloudflareAiGateway provider.
+    ///
+    /// # Parameters
+    ///
+    /// * `provider_name` - The provider name string.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the provider name set.
+    pub fn provider_name(mut self, provider_name: impl Into<String>) -> Self {
+        let name = provider_name.into();
+        self.settings.provider_name = name.clone();
+        self.inner.settings.provider_name = name;
+        self
+    }
+
+    /// Sets the ID of the Cloudflare account owning the gateway.
+    pub fn account_id(mut self, account_id: impl Into<String>) -> Self {
+        self.settings.account_id = account_id.into();
+        self
+    }
+
+    /// Sets the name of the gateway.
+    pub fn gateway_id(mut self, gateway_id: impl Into<String>) -> Self {
+        self.settings.gateway_id = gateway_id.into();
+        self
+    }
+
+    /// Sets the provider the gateway forwards the requests to, such as "openai"
+    /// or "groq", in place of its OpenAI-compatible endpoint.
+    pub fn upstream_provider(mut self, upstream_provider: impl Into<String>) -> Self {
+        self.settings.upstream_provider = Some(upstream_provider.into());
+        self
+    }
+
+    /// Sets the URL of the gateway, overriding the one of the account and
+    /// gateway IDs.
+    ///
+    /// # Parameters
+    ///
+    /// * `base_url` - The base URL string for API requests.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the base URL set.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.settings.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Sets the API key of the upstream provider.
+    ///
+    /// # Parameters
+    ///
+    /// * `api_key` - The API key string for authentication.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the API key set.
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        let key = api_key.into();
+        self.settings.api_key = key.clone();
+        self.inner.settings.api_key = key;
+        self
+    }
+
+    /// Sets the credentials resolving the API key before each request, to fetch
+    /// it from a secret manager or rotate it. Takes precedence over `api_key`.
+    ///
+    /// # Parameters
+    ///
+    /// * `credentials` - The source of the API key.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the credentials set.
+    pub fn credential_provider(mut self, credentials: impl CredentialProvider + 'static) -> Self {
+        let credentials = Credentials::new(credentials);
+        self.settings.credential_provider = Some(credentials.clone());
+        self.inner.settings.credential_provider = Some(credentials);
+        self
+    }
+
+    /// Sets the token of an authenticated gateway. No API key is needed when the
+    /// gateway holds the keys of the upstream providers.
+    pub fn gateway_token(mut self, gateway_token: impl Into<String>) -> Self {
+        self.settings.gateway_token = Some(gateway_token.into());
+        self
+    }
+
+    /// Sets the alias of the upstream provider key stored in the gateway.
+    pub fn byok_alias(mut self, alias: impl Into<String>) -> Self {
+        self.settings.byok_alias = Some(alias.into());
+        self
+    }
+
+    /// Sets how many seconds the gateway caches responses.
+    pub fn cache_ttl(mut self, seconds: u64) -> Self {
+        self.settings.cache_ttl = Some(seconds);
+        self
+    }
+
+    /// Sets whether the gateway bypasses its cache.
+    pub fn skip_cache(mut self, skip_cache: bool) -> Self {
+        self.settings.skip_cache = skip_cache;
+        self
+    }
+
+    /// Sets the HTTP client sending the requests, to share its pool of connections
+    /// or configure it. Requests share one pooled client by default.
+    ///
+    /// # Parameters
+    ///
+    /// * `client` - The `reqwest` client sending the requests.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the HTTP client set.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.settings.http_client = Some(client.clone());
+        self.inner.settings.http_client = Some(client);
+        self
+    }
+
+    /// Sets the network settings of the HTTP client, such as a proxy or
+    /// certificate authorities. Ignored if an HTTP client is set.
+    ///
+    /// # Parameters
+    ///
+    /// * `http` - The network settings.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the network settings set.
+    pub fn http_settings(mut self, http: HttpSettings) -> Self {
+        self.settings.http = http;
+        self
+    }
+
+    /// Sets a custom API path, overriding the default "chat/completions".
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        let p = Some(path.into());
+        self.settings.path = p.clone();
+        self.inner.settings.path = p;
+        self
+    }
+
+    /// Builds the CloudflareAiGateway provider.
+    ///
+    /// Validates the configuration and creates the provider instance.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the configured `CloudflareAiGateway<M>` or an `Error`.
+    pub fn build(mut self) -> Result<CloudflareAiGateway<M>> {
+        // validate the url of the gateway
+        let base_url = validate_base_url(&self.settings.gateway_url()?)?;
+
+        // check api key exists, unless the gateway holds the keys
+        if self.settings.api_key.is_empty()
+            && self.settings.credential_provider.is_none()
+            && self.settings.gateway_token.is_none()
+        {
+            return Err(Error::MissingField("api_key".to_string()));
+        }
+
+        self.inner.settings.headers = self.settings.gateway_headers()?;
+
+        // Build the client of the network settings
+        let http_client = self
+            .settings
+            .http
+            .resolve_client(self.settings.http_client.take())?;
+        self.settings.http_client = http_client.clone();
+        self.inner.settings.http_client = http_client;
+
+        self.inner.settings.base_url = base_url;
+
+        Ok(CloudflareAiGateway {
+            settings: self.settings,
+            inner: self.inner,
+        })
+    }
+}
+
+impl CloudflareAiGatewayBuilder<DynamicModel> {
+    /// Sets the model name from a string. e.g., "openai/gpt-4o"
+    ///
+    /// **WARNING**: when using `DynamicModel`, model capabilities are not validated.
+    /// This means there is no compile-time guarantee that the model supports requested features.
+    ///
+    /// For compile-time model validation, use typed constructor methods.
+    ///
+    /// # Parameters
+    ///
+    /// * `model_name` - The model identifier (e.g., "openai/gpt-4o")
+    ///
+    /// # Returns
+    ///
+    /// The builder with the model name set.
+    pub fn model_name(mut self, model_name: impl Into<String>) -> Self {
+        self.inner.options.model = model_name.into();
+        self
+    }
+}
+
+// Re-exports Models for convenience
+pub use capabilities::*;
 
 // Generate the language model implementation
 crate::openai_compatible_language_model!(CloudflareAiGateway);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::client::LanguageModelClient;
+
+    #[test]
+    fn test_build_gateway_url_and_headers() {
+        let model = CloudflareAiGateway::<DynamicModel>::builder()
+            .account_id("account")
+            .gateway_id("gateway")
+            .api_key("")
+            .gateway_token("cf-token")
+            .byok_alias("production")
+            .cache_ttl(60)
+            .model_name("openai/gpt-4o")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            model.inner.settings.base_url,
+            "https://gateway.ai.cloudflare.com/v1/account/gateway/compat/"
+        );
+        let headers = model.inner.headers();
+        assert_eq!(headers["cf-aig-authorization"], "Bearer cf-token");
+        assert_eq!(headers["cf-aig-byok-alias"], "production");
+        assert_eq!(headers["cf-aig-cache-ttl"], "60");
+        assert!(!headers.contains_key("cf-aig-skip-cache"));
+        assert!(!headers.contains_key("Authorization"));
+
+        let model = CloudflareAiGateway::<DynamicModel>::builder()
+            .account_id("account")
+            .gateway_id("gateway")
+            .upstream_provider("groq")
+            .api_key("gsk-key")
+            .build()
+            .unwrap();
+        assert_eq!(
+            model.inner.settings.base_url,
+            "https://gateway.ai.cloudflare.com/v1/account/gateway/groq/"
+        );
+        assert_eq!(model.inner.headers()["Authorization"], "Bearer gsk-key");
+    }
+
+    #[test]
+    fn test_build_requires_account_and_gateway() {
+        let result = CloudflareAiGateway::<DynamicModel>::builder()
+            .account_id("")
+            .gateway_id("gateway")
+            .api_key("key")
+            .build();
+        assert_eq!(
+            result.unwrap_err(),
+            Error::MissingField("account_id".to_string())
+        );
+
+        let result = CloudflareAiGateway::<DynamicModel>::builder()
+            .account_id("account")
+            .gateway_id("")
+            .api_key("key")
+            .build();
+        assert_eq!(
+            result.unwrap_err(),
+            Error::MissingField("gateway_id".to_string())
+        );
+    }
+}
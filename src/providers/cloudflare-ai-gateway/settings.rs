@@ -0,0 +1,150 @@
+//! Defines the settings for the Cloudflare AI Gateway provider.
+
+use crate::core::client::HttpSettings;
+use crate::core::credentials::Credentials;
+use crate::error::{Error, Result};
+use derive_builder::Builder;
+
+/// Settings for the Cloudflare AI Gateway provider (delegates to OpenAI Chat Completions).
+#[derive(Debug, Clone, Builder)]
+#[builder(setter(into), default)]
+pub struct CloudflareAiGatewayProviderSettings {
+    /// The name of the provider. Defaults to "CloudflareAiGateway".
+    pub provider_name: String,
+
+    /// The ID of the Cloudflare account owning the gateway. Defaults to the
+    /// `CLOUDFLARE_ACCOUNT_ID` environment variable.
+    pub account_id: String,
+
+    /// The name of the gateway. Defaults to the `CLOUDFLARE_GATEWAY_ID`
+    /// environment variable.
+    pub gateway_id: String,
+
+    /// The provider the gateway forwards the requests to, such as "openai" or
+    /// "groq", whose model names are used as is. Requests go to the
+    /// OpenAI-compatible endpoint of the gateway if it is not set, with model
+    /// names prefixed by their provider, such as "openai/gpt-4o".
+    pub upstream_provider: Option<String>,
+
+    /// The URL of the gateway, overriding the one of the account and gateway IDs.
+    pub base_url: Option<String>,
+
+    /// The API key of the upstream provider. Not needed when the gateway holds
+    /// the keys of the upstream providers.
+    pub api_key: String,
+
+    /// Credentials resolving the API key before each request, in place of
+    /// `api_key`.
+    pub credential_provider: Option<Credentials>,
+
+    /// The token of an authenticated gateway, sent in the `cf-aig-authorization`
+    /// header.
+    pub gateway_token: Option<String>,
+
+    /// The alias of the upstream provider key stored in the gateway, sent in the
+    /// `cf-aig-byok-alias` header.
+    pub byok_alias: Option<String>,
+
+    /// How many seconds the gateway caches responses, sent in the
+    /// `cf-aig-cache-ttl` header.
+    pub cache_ttl: Option<u64>,
+
+    /// Whether the gateway bypasses its cache, sent in the `cf-aig-skip-cache`
+    /// header.
+    pub skip_cache: bool,
+
+    /// The HTTP client sending the requests. Requests share one pooled client
+    /// if it is not set.
+    pub http_client: Option<reqwest::Client>,
+
+    /// Network settings of the HTTP client built for the provider when no
+    /// client is set, such as a proxy or certificate authorities.
+    pub http: HttpSettings,
+
+    /// Custom API path override.
+    pub path: Option<String>,
+}
+
+impl Default for CloudflareAiGatewayProviderSettings {
+    /// Returns the default settings for the Cloudflare AI Gateway provider.
+    fn default() -> Self {
+        Self {
+            provider_name: "CloudflareAiGateway".to_string(),
+            account_id: std::env::var("CLOUDFLARE_ACCOUNT_ID").unwrap_or_default(),
+            gateway_id: std::env::var("CLOUDFLARE_GATEWAY_ID").unwrap_or_default(),
+            upstream_provider: None,
+            base_url: None,
+            api_key: std::env::var("CLOUDFLARE_API_TOKEN").unwrap_or_default(),
+            credential_provider: None,
+            gateway_token: None,
+            byok_alias: None,
+            cache_ttl: None,
+            skip_cache: false,
+            http_client: None,
+            http: HttpSettings::default(),
+            path: None,
+        }
+    }
+}
+
+impl CloudflareAiGatewayProviderSettings {
+    /// Creates a new builder for `CloudflareAiGatewayProviderSettings`.
+    pub fn builder() -> CloudflareAiGatewayProviderSettingsBuilder {
+        CloudflareAiGatewayProviderSettingsBuilder::default()
+    }
+
+    /// Returns the URL the requests are sent to.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::MissingField` if no base URL is set and the account or
+    /// gateway ID is missing.
+    pub fn gateway_url(&self) -> Result<String> {
+        if let Some(base_url) = &self.base_url {
+            return Ok(base_url.clone());
+        }
+        if self.account_id.is_empty() {
+            return Err(Error::MissingField("account_id".to_string()));
+        }
+        if self.gateway_id.is_empty() {
+            return Err(Error::MissingField("gateway_id".to_string()));
+        }
+
+        Ok(format!(
+            "https://gateway.ai.cloudflare.com/v1/{}/{}/{}/",
+            self.account_id,
+            self.gateway_id,
+            self.upstream_provider.as_deref().unwrap_or("compat")
+        ))
+    }
+
+    /// Returns the `cf-aig-*` headers of the gateway options.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if an option is not a valid header value.
+    pub fn gateway_headers(&self) -> Result<reqwest::header::HeaderMap> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        let mut insert = |name: &'static str, value: String| {
+            let value = value
+                .parse()
+                .map_err(|_| Error::InvalidInput(format!("Invalid value of the {name} header")))?;
+            headers.insert(name, value);
+            Ok::<_, Error>(())
+        };
+
+        if let Some(token) = &self.gateway_token {
+            insert("cf-aig-authorization", format!("Bearer {token}"))?;
+        }
+        if let Some(alias) = &self.byok_alias {
+            insert("cf-aig-byok-alias", alias.clone())?;
+        }
+        if let Some(ttl) = self.cache_ttl {
+            insert("cf-aig-cache-ttl", ttl.to_string())?;
+        }
+        if self.skip_cache {
+            insert("cf-aig-skip-cache", "true".to_string())?;
+        }
+        Ok(headers)
+    }
+}
@@ -0,0 +1,350 @@
+//! Construction of providers from configuration files.
+//!
+//! A [`ProvidersConfig`] lists the providers of a deployment with their API keys,
+//! base URLs and organizations, and builds a [`ProviderRegistry`] resolving
+//! `"{provider}:{model}"` IDs with them. Settings missing from the configuration
+//! are read from the environment, as by the `from_env` constructors of the
+//! providers.
+//!
+//! Each provider is configured under an ID. Its type is the name of its feature,
+//! such as `"openai"` or `"amazon-bedrock"`, and defaults to the ID.
+//!
+//! # Examples
+//!
+//! ```toml
+//! [providers.openai]
+//! api_key_env = "OPENAI_API_KEY"
+//! organization = "org-123"
+//!
+//! [providers.local]
+//! type = "ollama"
+//! base_url = "http://localhost:11434"
+//! ```
+//!
+//! ```rust,no_run
+//!# #[cfg(all(feature = "toml", feature = "ollama"))]
+//!# {
+//! use aisdk::providers::config::ProvidersConfig;
+//!
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = ProvidersConfig::from_toml(&std::fs::read_to_string("providers.toml")?)?;
+//! let registry = config.registry()?;
+//! let model = registry.language_model("local:llama3.2")?;
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+use crate::core::registry::ProviderRegistry;
+use crate::core::utils::env_var;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The configuration of a provider.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProviderConfig {
+    /// The type of the provider, the name of its feature. Defaults to the ID of
+    /// the provider.
+    #[serde(rename = "type")]
+    pub provider_type: Option<String>,
+    /// The API key.
+    pub api_key: Option<String>,
+    /// The environment variable holding the API key, to keep it out of the
+    /// configuration file.
+    pub api_key_env: Option<String>,
+    /// The base URL of the API.
+    pub base_url: Option<String>,
+    /// The organization the requests are billed to. Only supported by OpenAI.
+    pub organization: Option<String>,
+    /// The project the requests are billed to. Only supported by OpenAI.
+    pub project: Option<String>,
+}
+
+impl ProviderConfig {
+    /// Returns the configured API key, reading it from its environment variable
+    /// if one is set.
+    fn api_key(&self) -> Result<Option<String>> {
+        match &self.api_key_env {
+            Some(variable) => env_var(variable)
+                .map(Some)
+                .ok_or_else(|| Error::MissingField(variable.clone())),
+            None => Ok(self.api_key.clone()),
+        }
+    }
+}
+
+/// The configuration of the providers of a deployment, by ID.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProvidersConfig {
+    /// The providers, by the ID their models are resolved with.
+    pub providers: HashMap<String, ProviderConfig>,
+}
+
+impl ProvidersConfig {
+    /// Parses a configuration from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if the JSON is not a providers configuration.
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| Error::InvalidInput(format!("Invalid providers configuration: {e}")))
+    }
+
+    /// Parses a configuration from TOML.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if the TOML is not a providers configuration.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        toml::from_str(toml)
+            .map_err(|e| Error::InvalidInput(format!("Invalid providers configuration: {e}")))
+    }
+
+    /// Builds a registry of the configured providers.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if the type of a provider is unknown or its
+    /// feature is not enabled, or if it has options its type does not support, and
+    /// `Error::MissingField` if the environment variable of an API key is not set.
+    pub fn registry(&self) -> Result<ProviderRegistry> {
+        let mut registry = ProviderRegistry::new();
+        for (id, config) in &self.providers {
+            let provider_type = config.provider_type.as_deref().unwrap_or(id);
+            if provider_type != "openai"
+                && (config.organization.is_some() || config.project.is_some())
+            {
+                return Err(Error::InvalidInput(format!(
+                    "provider `{id}` of type `{provider_type}` does not support organizations or projects"
+                )));
+            }
+            registry = register(registry, id, provider_type, config)?;
+        }
+        Ok(registry)
+    }
+}
+
+/// Generates [`register`] for the providers, by feature.
+macro_rules! register_providers {
+    ($($feature:literal => $provider:ident),* $(,)?) => {
+        /// Registers a provider of the given type under an ID.
+        // Without provider features, only the error of unknown types remains.
+        #[allow(unused_variables, unused_mut)]
+        fn register(
+            registry: ProviderRegistry,
+            id: &str,
+            provider_type: &str,
+            config: &ProviderConfig,
+        ) -> Result<ProviderRegistry> {
+            let api_key = config.api_key()?;
+            let base_url = config.base_url.clone();
+            match provider_type {
+                #[cfg(feature = "openai")]
+                "openai" => {
+                    let config = config.clone();
+                    Ok(registry.with_provider(id, move |model| {
+                        let mut builder =
+                            crate::providers::OpenAI::<crate::core::DynamicModel>::from_env()
+                                .model_name(model);
+                        if let Some(api_key) = &api_key {
+                            builder = builder.api_key(api_key);
+                        }
+                        if let Some(base_url) = &base_url {
+                            builder = builder.base_url(base_url);
+                        }
+                        if let Some(organization) = &config.organization {
+                            builder = builder.organization(organization);
+                        }
+                        if let Some(project) = &config.project {
+                            builder = builder.project(project);
+                        }
+                        builder.build()
+                    }))
+                }
+                $(
+                    #[cfg(feature = $feature)]
+                    $feature => Ok(registry.with_provider(id, move |model| {
+                        let mut builder =
+                            crate::providers::$provider::<crate::core::DynamicModel>::from_env()
+                                .model_name(model);
+                        if let Some(api_key) = &api_key {
+                            builder = builder.api_key(api_key);
+                        }
+                        if let Some(base_url) = &base_url {
+                            builder = builder.base_url(base_url);
+                        }
+                        builder.build()
+                    })),
+                )*
+                _ => Err(Error::InvalidInput(format!(
+                    "unknown provider type `{provider_type}` of provider `{id}`, or its feature is not enabled"
+                ))),
+            }
+        }
+    };
+}
+
+register_providers! {
+    "openaicompatible" => OpenAICompatible,
+    "anthropic" => Anthropic,
+    "google" => Google,
+    "vertex-ai" => VertexAI,
+    "amazon-bedrock" => AmazonBedrock,
+    "ollama" => Ollama,
+    "groq" => Groq,
+    "vercel" => Vercel,
+    "openrouter" => Openrouter,
+    "mistral" => Mistral,
+    "togetherai" => TogetherAI,
+    "xai" => XAI,
+    "302ai" => Ai302,
+    "abacus" => Abacus,
+    "aihubmix" => Aihubmix,
+    "alibaba" => Alibaba,
+    "alibaba-cn" => AlibabaCn,
+    "bailing" => Bailing,
+    "baseten" => Baseten,
+    "berget" => Berget,
+    "chutes" => Chutes,
+    "cloudflare-ai-gateway" => CloudflareAiGateway,
+    "cloudflare-workers-ai" => CloudflareWorkersAi,
+    "cortecs" => Cortecs,
+    "deepseek" => Deepseek,
+    "fastrouter" => Fastrouter,
+    "fireworks-ai" => FireworksAi,
+    "firmware" => Firmware,
+    "friendli" => Friendli,
+    "github-copilot" => GithubCopilot,
+    "github-models" => GithubModels,
+    "helicone" => Helicone,
+    "huggingface" => Huggingface,
+    "iflowcn" => Iflowcn,
+    "inception" => Inception,
+    "inference" => Inference,
+    "io-net" => IoNet,
+    "jiekou" => Jiekou,
+    "kuae-cloud-coding-plan" => KuaeCloudCodingPlan,
+    "llama" => Llama,
+    "lmstudio" => Lmstudio,
+    "lucidquery" => Lucidquery,
+    "moark" => Moark,
+    "modelscope" => Modelscope,
+    "moonshotai" => Moonshotai,
+    "moonshotai-cn" => MoonshotaiCn,
+    "morph" => Morph,
+    "nano-gpt" => NanoGpt,
+    "nebius" => Nebius,
+    "nova" => Nova,
+    "novita-ai" => NovitaAi,
+    "nvidia" => Nvidia,
+    "ollama-cloud" => OllamaCloud,
+    "opencode" => Opencode,
+    "ovhcloud" => Ovhcloud,
+    "poe" => Poe,
+    "requesty" => Requesty,
+    "scaleway" => Scaleway,
+    "siliconflow" => Siliconflow,
+    "siliconflow-cn" => SiliconflowCn,
+    "stackit" => Stackit,
+    "stepfun" => Stepfun,
+    "submodel" => Submodel,
+    "synthetic" => Synthetic,
+    "upstage" => Upstage,
+    "vultr" => Vultr,
+    "wandb" => Wandb,
+    "xiaomi" => Xiaomi,
+    "zai" => Zai,
+    "zai-coding-plan" => ZaiCodingPlan,
+    "zenmux" => Zenmux,
+    "zhipuai" => Zhipuai,
+    "zhipuai-coding-plan" => ZhipuaiCodingPlan,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_providers_config_from_json() {
+        let config = ProvidersConfig::from_json(
+            r#"{"providers": {"local": {"type": "ollama", "base_url": "http://localhost:11434"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.providers["local"],
+            ProviderConfig {
+                provider_type: Some("ollama".to_string()),
+                base_url: Some("http://localhost:11434".to_string()),
+                ..Default::default()
+            }
+        );
+
+        assert!(matches!(
+            ProvidersConfig::from_json(r#"{"providers": {"local": {"url": "x"}}}"#),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_registry_rejects_invalid_providers() {
+        let config = ProvidersConfig::from_json(r#"{"providers": {"unknown": {}}}"#).unwrap();
+        assert!(matches!(config.registry(), Err(Error::InvalidInput(_))));
+
+        let config = ProvidersConfig::from_json(
+            r#"{"providers": {"local": {"type": "ollama", "organization": "org"}}}"#,
+        )
+        .unwrap();
+        assert!(matches!(config.registry(), Err(Error::InvalidInput(_))));
+
+        let config = ProvidersConfig::from_json(
+            r#"{"providers": {"openai": {"api_key_env": "AISDK_TEST_MISSING_KEY"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.registry().unwrap_err(),
+            Error::MissingField("AISDK_TEST_MISSING_KEY".to_string())
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_providers_config_from_toml() {
+        let config = ProvidersConfig::from_toml(
+            "[providers.openai]\napi_key = \"sk-test\"\norganization = \"org-123\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.providers["openai"].organization.as_deref(),
+            Some("org-123")
+        );
+    }
+
+    #[cfg(all(feature = "ollama", feature = "openai"))]
+    #[test]
+    fn test_registry_builds_configured_providers() {
+        use crate::core::language_model::LanguageModel;
+
+        let config = ProvidersConfig::from_json(
+            r#"{"providers": {
+                "local": {"type": "ollama", "base_url": "http://localhost:11434"},
+                "openai": {"api_key": "sk-test", "organization": "org-123"}
+            }}"#,
+        )
+        .unwrap();
+        let registry = config.registry().unwrap();
+
+        assert_eq!(
+            registry.language_model("local:llama3.2").unwrap().name(),
+            "llama3.2"
+        );
+        assert_eq!(
+            registry.language_model("openai:gpt-4o").unwrap().name(),
+            "gpt-4o"
+        );
+    }
+}
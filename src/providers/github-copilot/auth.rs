@@ -0,0 +1,408 @@
+//! GitHub OAuth tokens and their exchange for Copilot tokens.
+//!
+//! The Copilot API does not accept GitHub tokens directly. A GitHub OAuth token,
+//! obtained with the device flow of the Copilot OAuth app, is exchanged for a
+//! short-lived Copilot token at `copilot_internal/v2/token`, which is sent as the
+//! bearer token of the completion requests and refreshed before it expires. The
+//! requests go to the API endpoint returned with the token, which differs for
+//! business and enterprise accounts.
+
+use crate::core::client::{send_json, shared_client};
+use crate::core::credentials::CredentialProvider;
+use crate::core::runtime::{SystemTime, UNIX_EPOCH};
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use reqwest::header::{ACCEPT, AUTHORIZATION, HeaderMap, HeaderValue, USER_AGENT};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The client ID of the OAuth app of the Copilot editor plugins.
+pub const COPILOT_CLIENT_ID: &str = "Iv1.b507a08c87ecfe98";
+
+/// How long before its expiry a cached Copilot token is refreshed.
+const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// How much longer to wait between polls when GitHub asks to slow down.
+const SLOW_DOWN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The headers identifying the editor, required by the Copilot API.
+pub(crate) fn editor_headers() -> HeaderMap {
+    let version = concat!("aisdk/", env!("CARGO_PKG_VERSION"));
+    let mut headers = HeaderMap::new();
+    headers.insert("Editor-Version", HeaderValue::from_static(version));
+    headers.insert("Editor-Plugin-Version", HeaderValue::from_static(version));
+    headers.insert(
+        "Copilot-Integration-Id",
+        HeaderValue::from_static("vscode-chat"),
+    );
+    headers.insert(USER_AGENT, HeaderValue::from_static(version));
+    headers
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// A pending device authorization, to be approved by the user at
+/// `verification_uri` with `user_code`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DeviceCode {
+    /// The code identifying the device while polling for the token.
+    pub device_code: String,
+    /// The code the user enters at the verification page.
+    pub user_code: String,
+    /// The page where the user approves the device.
+    pub verification_uri: String,
+    /// The number of seconds before the codes expire.
+    pub expires_in: u64,
+    /// The minimum number of seconds between two polls.
+    pub interval: u64,
+}
+
+/// A response of the access token endpoint, either the token or the reason
+/// there is none yet.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum AccessTokenResponse {
+    Token {
+        access_token: String,
+    },
+    Error {
+        error: String,
+        #[serde(default)]
+        error_description: Option<String>,
+        #[serde(default)]
+        interval: Option<u64>,
+    },
+}
+
+/// The OAuth device flow of GitHub, authorizing the Copilot OAuth app for a user.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # #[cfg(feature = "github-copilot")]
+/// # {
+/// use aisdk::providers::github_copilot::auth::DeviceFlow;
+///
+/// # async fn run() -> aisdk::Result<()> {
+/// let flow = DeviceFlow::new();
+/// let code = flow.start().await?;
+/// println!("Enter {} at {}", code.user_code, code.verification_uri);
+/// let oauth_token = flow.poll(&code).await?;
+/// # Ok(())
+/// # }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DeviceFlow {
+    client_id: String,
+    github_url: String,
+    client: reqwest::Client,
+}
+
+impl Default for DeviceFlow {
+    fn default() -> Self {
+        Self {
+            client_id: COPILOT_CLIENT_ID.to_string(),
+            github_url: "https://github.com".to_string(),
+            client: shared_client(),
+        }
+    }
+}
+
+impl DeviceFlow {
+    /// Creates a device flow for the Copilot OAuth app on github.com.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the client ID of the OAuth app.
+    pub fn client_id(mut self, client_id: impl Into<String>) -> Self {
+        self.client_id = client_id.into();
+        self
+    }
+
+    /// Sets the URL of the GitHub instance, such as a GitHub Enterprise server.
+    pub fn github_url(mut self, github_url: impl Into<String>) -> Self {
+        self.github_url = github_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets the HTTP client sending the requests.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Requests the codes the user approves the device with.
+    pub async fn start(&self) -> Result<DeviceCode> {
+        let request = self
+            .client
+            .post(format!("{}/login/device/code", self.github_url))
+            .header(ACCEPT, "application/json")
+            .json(&serde_json::json!({
+                "client_id": self.client_id,
+                "scope": "read:user",
+            }));
+        send_json(request).await
+    }
+
+    /// Polls GitHub until the user approves the device, and returns the OAuth
+    /// token of the user.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::AuthenticationError` if the user denies the device or the
+    /// codes expire.
+    pub async fn poll(&self, code: &DeviceCode) -> Result<String> {
+        let mut interval = Duration::from_secs(code.interval);
+        loop {
+            crate::core::runtime::sleep(interval).await;
+
+            let request = self
+                .client
+                .post(format!("{}/login/oauth/access_token", self.github_url))
+                .header(ACCEPT, "application/json")
+                .json(&serde_json::json!({
+                    "client_id": self.client_id,
+                    "device_code": code.device_code,
+                    "grant_type": "urn:ietf:params:oauth:grant-type:device_code",
+                }));
+
+            match send_json(request).await? {
+                AccessTokenResponse::Token { access_token } => return Ok(access_token),
+                AccessTokenResponse::Error { error, .. } if error == "authorization_pending" => {}
+                AccessTokenResponse::Error {
+                    error, interval: i, ..
+                } if error == "slow_down" => {
+                    interval = i.map_or(interval + SLOW_DOWN_INTERVAL, Duration::from_secs);
+                }
+                AccessTokenResponse::Error {
+                    error,
+                    error_description,
+                    ..
+                } => {
+                    return Err(Error::AuthenticationError {
+                        details: error_description.unwrap_or(error),
+                        status_code: None,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// A response of the Copilot token endpoint.
+#[derive(Debug, Deserialize)]
+struct CopilotTokenResponse {
+    token: String,
+    expires_at: u64,
+    #[serde(default)]
+    endpoints: Option<CopilotEndpoints>,
+}
+
+/// The endpoints of the account a Copilot token is issued for.
+#[derive(Debug, Deserialize)]
+struct CopilotEndpoints {
+    api: Option<String>,
+}
+
+/// Credentials exchanging a GitHub OAuth token for Copilot tokens, refreshed
+/// before they expire.
+///
+/// Clones share the cached token, and concurrent requests wait for a single
+/// exchange.
+#[derive(Clone)]
+pub struct CopilotTokenExchange {
+    oauth_token: String,
+    token_url: String,
+    client: reqwest::Client,
+    token: Arc<tokio::sync::Mutex<Option<(String, u64)>>>,
+    api_url: Arc<Mutex<Option<String>>>,
+}
+
+impl std::fmt::Debug for CopilotTokenExchange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CopilotTokenExchange")
+            .field("oauth_token", &"<redacted>")
+            .field("token_url", &self.token_url)
+            .finish()
+    }
+}
+
+impl CopilotTokenExchange {
+    /// Creates credentials exchanging the given GitHub OAuth token.
+    pub fn new(oauth_token: impl Into<String>) -> Self {
+        Self {
+            oauth_token: oauth_token.into(),
+            token_url: "https://api.github.com/copilot_internal/v2/token".to_string(),
+            client: shared_client(),
+            token: Arc::default(),
+            api_url: Arc::default(),
+        }
+    }
+
+    /// Sets the URL of the token endpoint, such as the one of a GitHub
+    /// Enterprise server.
+    pub fn token_url(mut self, token_url: impl Into<String>) -> Self {
+        self.token_url = token_url.into();
+        self
+    }
+
+    /// Sets the HTTP client sending the token requests.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for CopilotTokenExchange {
+    /// Returns the cached Copilot token, exchanging the OAuth token for a new
+    /// one when it is missing or about to expire.
+    async fn api_key(&self) -> Result<String> {
+        // Held during the exchange, so concurrent requests reuse its token
+        let mut cached = self.token.lock().await;
+        if let Some((token, expires_at)) = cached.as_ref()
+            && unix_now() + REFRESH_MARGIN.as_secs() < *expires_at
+        {
+            return Ok(token.clone());
+        }
+
+        let request = self
+            .client
+            .get(&self.token_url)
+            .headers(editor_headers())
+            .header(ACCEPT, "application/json")
+            .header(AUTHORIZATION, format!("token {}", self.oauth_token));
+        let response: CopilotTokenResponse = send_json(request).await?;
+
+        *self.api_url.lock() = response.endpoints.and_then(|endpoints| endpoints.api);
+        *cached = Some((response.token.clone(), response.expires_at));
+        Ok(response.token)
+    }
+
+    /// Returns the API endpoint of the account the cached token is issued for.
+    fn base_url(&self) -> Option<String> {
+        self.api_url.lock().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::client::test_server::{TestResponse, serve};
+    use tokio::sync::mpsc::UnboundedReceiver;
+
+    /// Returns the requests received so far, lowercased.
+    fn received(requests: &mut UnboundedReceiver<String>) -> Vec<String> {
+        std::iter::from_fn(|| requests.try_recv().ok())
+            .map(|request| request.to_ascii_lowercase())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_device_flow_polls_until_authorized() {
+        let (base_url, mut requests) = serve(vec![
+            TestResponse::json(r#"{"device_code":"dc","user_code":"ABCD-1234","verification_uri":"https://github.com/login/device","expires_in":900,"interval":0}"#),
+            TestResponse::json(r#"{"error":"authorization_pending"}"#),
+            TestResponse::json(r#"{"access_token":"gho_user","token_type":"bearer","scope":"read:user"}"#),
+        ])
+        .await;
+
+        let flow = DeviceFlow::new().github_url(format!("{base_url}/"));
+        let code = flow.start().await.unwrap();
+        assert_eq!(code.user_code, "ABCD-1234");
+        assert_eq!(flow.poll(&code).await.unwrap(), "gho_user");
+
+        let requests = received(&mut requests);
+        assert!(requests[0].starts_with("post /login/device/code"));
+        assert!(requests[0].contains(COPILOT_CLIENT_ID.to_ascii_lowercase().as_str()));
+        assert!(requests[2].starts_with("post /login/oauth/access_token"));
+        assert!(requests[2].contains(r#""device_code":"dc""#));
+    }
+
+    #[tokio::test]
+    async fn test_device_flow_fails_when_access_is_denied() {
+        let (base_url, _requests) = serve(vec![TestResponse::json(
+            r#"{"error":"access_denied","error_description":"The user denied the request"}"#,
+        )])
+        .await;
+
+        let code = DeviceCode {
+            device_code: "dc".to_string(),
+            user_code: "ABCD-1234".to_string(),
+            verification_uri: "https://github.com/login/device".to_string(),
+            expires_in: 900,
+            interval: 0,
+        };
+        let error = DeviceFlow::new()
+            .github_url(base_url)
+            .poll(&code)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error,
+            Error::AuthenticationError {
+                details: "The user denied the request".to_string(),
+                status_code: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_exchange_caches_tokens_until_they_expire() {
+        let expired = unix_now() + 30;
+        let valid = unix_now() + 1800;
+        let (base_url, mut requests) = serve(vec![
+            TestResponse::json(format!(
+                r#"{{"token":"tid=1","expires_at":{expired},"refresh_in":0}}"#
+            )),
+            TestResponse::json(format!(
+                r#"{{"token":"tid=2","expires_at":{valid},"refresh_in":1500}}"#
+            )),
+        ])
+        .await;
+
+        let exchange = CopilotTokenExchange::new("gho_user")
+            .token_url(format!("{base_url}/copilot_internal/v2/token"));
+        assert_eq!(exchange.api_key().await.unwrap(), "tid=1");
+        // The first token expires within the refresh margin, so it is exchanged again.
+        assert_eq!(exchange.api_key().await.unwrap(), "tid=2");
+        assert_eq!(exchange.clone().api_key().await.unwrap(), "tid=2");
+        assert!(!format!("{exchange:?}").contains("gho_user"));
+
+        let requests = received(&mut requests);
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].starts_with("get /copilot_internal/v2/token"));
+        assert!(requests[0].contains("authorization: token gho_user"));
+        assert!(requests[0].contains("editor-version: aisdk/"));
+    }
+
+    #[tokio::test]
+    async fn test_token_exchange_is_shared_by_concurrent_requests() {
+        let valid = unix_now() + 1800;
+        let (base_url, mut requests) = serve(vec![TestResponse::json(format!(
+            r#"{{"token":"tid=1","expires_at":{valid},"endpoints":{{"api":"https://api.business.githubcopilot.com"}}}}"#
+        ))])
+        .await;
+
+        let exchange = CopilotTokenExchange::new("gho_user").token_url(base_url);
+        assert_eq!(exchange.base_url(), None);
+        let clone = exchange.clone();
+        let (first, second) = tokio::join!(exchange.api_key(), clone.api_key());
+        assert_eq!(first.unwrap(), "tid=1");
+        assert_eq!(second.unwrap(), "tid=1");
+        assert_eq!(received(&mut requests).len(), 1);
+        assert_eq!(
+            exchange.base_url().as_deref(),
+            Some("https://api.business.githubcopilot.com")
+        );
+    }
+}
@@ -1,5 +1,11 @@
 //! This module provides the GithubCopilot provider, wrapping OpenAI Chat Completions for GithubCopilot requests.
+//!
+//! The Copilot API authenticates requests with short-lived Copilot tokens. Set a
+//! GitHub OAuth token with [`GithubCopilotBuilder::oauth_token`] to exchange it
+//! for Copilot tokens before each request, see [`auth`] for obtaining one with
+//! the device flow. An API key set with `api_key` is sent as is.
 
+pub mod auth;
 pub mod capabilities;
 
 // Generate the settings module
@@ -16,8 +22,40 @@ crate::openai_compatible_provider!(
     GithubCopilot,
     GithubCopilotBuilder,
     GithubCopilotProviderSettings,
-    "github-copilot"
+    "github-copilot",
+    headers = auth::editor_headers()
 );
 
 // Generate the language model implementation
 crate::openai_compatible_language_model!(GithubCopilot);
+
+impl<M: ModelName> GithubCopilotBuilder<M> {
+    /// Sets the GitHub OAuth token exchanged for the Copilot tokens of the
+    /// requests.
+    pub fn oauth_token(self, oauth_token: impl Into<String>) -> Self {
+        self.credential_provider(auth::CopilotTokenExchange::new(oauth_token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::client::LanguageModelClient;
+
+    #[test]
+    fn test_requests_are_sent_with_editor_headers() {
+        let model = GithubCopilot::<DynamicModel>::builder()
+            .model_name("gpt-4o")
+            .oauth_token("gho_user")
+            .build()
+            .unwrap();
+
+        assert!(model.settings.credential_provider.is_some());
+        let headers = model.inner.headers();
+        assert_eq!(headers["Copilot-Integration-Id"], "vscode-chat");
+        assert!(headers.contains_key("Editor-Version"));
+
+        let model = GithubCopilot::model_name("gpt-4o");
+        assert!(model.inner.headers().contains_key("Copilot-Integration-Id"));
+    }
+}
@@ -1,5 +1,5 @@
 //! Client implementation for the Google provider.
-use crate::core::client::{EmbeddingClient, LanguageModelClient};
+use crate::core::client::{EmbeddingClient, LanguageModelClient, shared_client, sse_error};
 use crate::error::{Error, Result};
 use crate::providers::google::{Google, ModelName};
 use derive_builder::Builder;
@@ -19,6 +19,11 @@ pub(crate) struct GoogleOptions {
     #[serde(skip)]
     #[builder(default)]
     pub(crate) streaming: bool,
+    /// The path of the models collection, "/v1beta/models" by default. Vertex AI
+    /// uses the publisher model path of a project.
+    #[serde(skip)]
+    #[builder(default)]
+    pub(crate) models_path: Option<String>,
 }
 
 impl GoogleOptions {
@@ -42,23 +47,38 @@ impl<M: ModelName> LanguageModelClient for Google<M> {
         if let Some(ref path) = self.settings.path {
             return path.clone();
         }
+        let models_path = self
+            .lm_options
+            .models_path
+            .as_deref()
+            .unwrap_or("/v1beta/models");
         if self.lm_options.streaming {
             return format!(
-                "/v1beta/models/{}:streamGenerateContent",
+                "{models_path}/{}:streamGenerateContent",
                 self.lm_options.model
             );
         };
-        format!("/v1beta/models/{}:generateContent", self.lm_options.model)
+        format!("{models_path}/{}:generateContent", self.lm_options.model)
     }
 
     fn method(&self) -> reqwest::Method {
         reqwest::Method::POST
     }
 
+    fn http_client(&self) -> reqwest::Client {
+        self.settings
+            .http_client
+            .clone()
+            .unwrap_or_else(shared_client)
+    }
+
     fn headers(&self) -> reqwest::header::HeaderMap {
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
-        headers.insert("x-goog-api-key", self.settings.api_key.parse().unwrap());
+        // Vertex AI authenticates with a bearer token instead of an API key
+        if !self.settings.api_key.is_empty() {
+            headers.insert("x-goog-api-key", self.settings.api_key.parse().unwrap());
+        }
         headers
     }
 
@@ -97,17 +117,7 @@ impl<M: ModelName> LanguageModelClient for Google<M> {
                     )
                 }
             },
-            Err(e) => {
-                // Extract status code if it's an InvalidStatusCode error
-                let status_code = match &e {
-                    reqwest_eventsource::Error::InvalidStatusCode(status, _) => Some(*status),
-                    _ => None,
-                };
-                Err(Error::ApiError {
-                    status_code,
-                    details: e.to_string(),
-                })
-            }
+            Err(e) => Err(sse_error(e)),
         }
     }
 
@@ -135,6 +145,13 @@ impl<M: ModelName> EmbeddingClient for Google<M> {
         reqwest::Method::POST
     }
 
+    fn http_client(&self) -> reqwest::Client {
+        self.settings
+            .http_client
+            .clone()
+            .unwrap_or_else(shared_client)
+    }
+
     fn headers(&self) -> reqwest::header::HeaderMap {
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
@@ -62,6 +62,7 @@ pub(crate) struct Blob {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct FileData {
     pub(crate) mime_type: String,
     pub(crate) file_uri: String,
@@ -129,8 +130,10 @@ pub(crate) struct ToolConfig {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct FunctionCallingConfig {
     pub(crate) mode: FunctionCallingMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) allowed_function_names: Option<Vec<String>>,
 }
 
@@ -156,6 +159,7 @@ pub(crate) struct GenerationConfig {
     pub(crate) top_k: Option<i32>,
     pub(crate) presence_penalty: Option<f32>,
     pub(crate) frequency_penalty: Option<f32>,
+    pub(crate) seed: Option<u32>,
     pub(crate) response_logprobs: Option<bool>,
     pub(crate) logprobs: Option<i32>,
 }
@@ -195,6 +199,7 @@ pub(crate) struct GenerateContentResponse {
     pub(crate) prompt_feedback: Option<PromptFeedback>,
     pub(crate) usage_metadata: Option<UsageMetadata>,
     pub(crate) model_version: Option<String>,
+    pub(crate) response_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -208,6 +213,31 @@ pub(crate) struct Candidate {
     pub(crate) grounding_metadata: Option<GroundingMetadata>,
     pub(crate) index: Option<i32>,
     pub(crate) finish_message: Option<String>,
+    pub(crate) logprobs_result: Option<LogprobsResult>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LogprobsResult {
+    #[serde(default)]
+    pub(crate) top_candidates: Vec<TopCandidates>,
+    #[serde(default)]
+    pub(crate) chosen_candidates: Vec<LogprobsCandidate>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct TopCandidates {
+    #[serde(default)]
+    pub(crate) candidates: Vec<LogprobsCandidate>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LogprobsCandidate {
+    #[serde(default)]
+    pub(crate) token: String,
+    #[serde(default)]
+    pub(crate) log_probability: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
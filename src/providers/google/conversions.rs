@@ -1,8 +1,12 @@
 //! Conversions between types used by the Google provider and the types used by the core library.
 use crate::core::embedding_model::EmbeddingModelOptions;
-use crate::core::language_model::{LanguageModelOptions, LanguageModelResponseContentType, Usage};
+use crate::core::language_model::logprobs::{TokenLogprob, TopLogprob};
+use crate::core::language_model::{
+    ConstrainedOutput, LanguageModelOptions, LanguageModelResponseContentType, Usage,
+};
 use crate::core::messages::{Message, TaggedMessage};
-use crate::core::tools::Tool;
+use crate::core::schema::{SchemaDialect, sanitize};
+use crate::core::tools::{Tool, ToolChoice};
 use crate::providers::google::client::GoogleEmbeddingOptions;
 use crate::providers::google::client::types::{
     self, Content, FunctionDeclaration, GenerateContentRequest, Part, Role,
@@ -13,9 +17,7 @@ use serde_json::Value;
 impl From<Tool> for FunctionDeclaration {
     fn from(value: Tool) -> Self {
         let mut params = value.input_schema.to_value();
-        if let Some(obj) = params.as_object_mut() {
-            obj.remove("$schema");
-        }
+        sanitize(&mut params, SchemaDialect::Gemini);
 
         Self {
             name: value.name,
@@ -27,9 +29,24 @@ impl From<Tool> for FunctionDeclaration {
 
 impl From<LanguageModelOptions> for GenerateContentRequest {
     fn from(options: LanguageModelOptions) -> Self {
-        let contents = options.messages.into_iter().map(|m| m.into()).collect();
+        // Gemini has no system or developer messages, they make the system instruction
+        let mut system = options.system.filter(|system| !system.is_empty());
+        let mut contents = Vec::new();
+        for message in options.messages {
+            match message.message {
+                Message::System(s) if !s.content.is_empty() => system = Some(s.content),
+                Message::System(_) => (),
+                Message::Developer(dev) => {
+                    system = Some(match system {
+                        Some(system) => format!("{system}\n\n{dev}"),
+                        None => dev,
+                    });
+                }
+                message => contents.push(message.into()),
+            }
+        }
 
-        let system_instruction = options.system.map(|s| Content {
+        let system_instruction = system.map(|s| Content {
             role: Role::User, // System instructions are often text-only content
             parts: vec![Part {
                 text: Some(s),
@@ -48,34 +65,50 @@ impl From<LanguageModelOptions> for GenerateContentRequest {
             }]
         });
 
+        let tool_config = tools.as_ref().and(options.tool_choice).map(|choice| {
+            let (mode, allowed_function_names) = match choice {
+                ToolChoice::Auto => (types::FunctionCallingMode::Auto, None),
+                ToolChoice::None => (types::FunctionCallingMode::None, None),
+                ToolChoice::Required => (types::FunctionCallingMode::Any, None),
+                ToolChoice::Tool(name) => (types::FunctionCallingMode::Any, Some(vec![name])),
+            };
+            types::ToolConfig {
+                function_calling_config: Some(types::FunctionCallingConfig {
+                    mode,
+                    allowed_function_names,
+                }),
+            }
+        });
+
         let generation_config = Some(types::GenerationConfig {
-            stop_sequences: options.stop_sequences,
-            response_mime_type: options
-                .schema
-                .as_ref()
-                .map(|_| "application/json".to_string()),
+            // Gemini accepts up to 5 stop sequences
+            stop_sequences: options
+                .stop_sequences
+                .map(|seqs| seqs.into_iter().take(5).collect()),
+            response_mime_type: (options.schema.is_some()
+                || options.constrained_output == Some(ConstrainedOutput::JsonObject))
+            .then(|| "application/json".to_string()),
             response_schema: options.schema.map(|s| {
                 let mut v = serde_json::to_value(s).unwrap();
-                if let Some(obj) = v.as_object_mut() {
-                    obj.remove("$schema");
-                }
+                sanitize(&mut v, SchemaDialect::Gemini);
                 v
             }),
-            candidate_count: None,
+            candidate_count: options.candidate_count.map(|n| n as i32),
             max_output_tokens: options.max_output_tokens.map(|t| t as i32),
-            temperature: options.temperature.map(|t| t as f32 / 100.0),
-            top_p: options.top_p.map(|t| t as f32 / 100.0),
+            temperature: options.temperature,
+            top_p: options.top_p,
             top_k: options.top_k.map(|t| t as i32),
             presence_penalty: options.presence_penalty,
             frequency_penalty: options.frequency_penalty,
-            response_logprobs: None,
-            logprobs: None,
+            seed: options.seed,
+            response_logprobs: options.logprobs.map(|_| true),
+            logprobs: options.logprobs.map(|n| n as i32),
         });
 
         Self {
             contents,
             tools,
-            tool_config: None, // Default to auto
+            tool_config,
             safety_settings: None,
             system_instruction,
             generation_config,
@@ -93,13 +126,28 @@ impl From<TaggedMessage> for Content {
 impl From<Message> for Content {
     fn from(message: Message) -> Self {
         match message {
-            Message::User(u) => Content {
-                role: Role::User,
-                parts: vec![Part {
+            Message::User(u) => {
+                let mut parts = vec![Part {
                     text: Some(u.content),
                     ..Default::default()
-                }],
-            },
+                }];
+                // Stored files are referenced by their URI
+                parts.extend(u.files.into_iter().map(|file| {
+                    Part {
+                        file_data: Some(types::FileData {
+                            mime_type: file
+                                .mime_type
+                                .unwrap_or_else(|| "application/octet-stream".to_string()),
+                            file_uri: file.uri.unwrap_or(file.id),
+                        }),
+                        ..Default::default()
+                    }
+                }));
+                Content {
+                    role: Role::User,
+                    parts,
+                }
+            }
             Message::Assistant(a) => {
                 let part = match a.content {
                     LanguageModelResponseContentType::Text(t) => Part {
@@ -173,10 +221,37 @@ impl From<types::UsageMetadata> for Usage {
             output_tokens: Some(value.candidates_token_count as usize),
             reasoning_tokens: None, // Gemini doesn't separate reasoning tokens in UsageMetadata v1beta
             cached_tokens: None,
+            cache_write_tokens: None,
         }
     }
 }
 
+impl From<types::LogprobsResult> for Vec<TokenLogprob> {
+    fn from(value: types::LogprobsResult) -> Self {
+        let mut top_candidates = value.top_candidates.into_iter();
+        value
+            .chosen_candidates
+            .into_iter()
+            .map(|chosen| TokenLogprob {
+                token: chosen.token,
+                logprob: chosen.log_probability,
+                top_logprobs: top_candidates
+                    .next()
+                    .map(|top| {
+                        top.candidates
+                            .into_iter()
+                            .map(|c| TopLogprob {
+                                token: c.token,
+                                logprob: c.log_probability,
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
 impl From<EmbeddingModelOptions> for GoogleEmbeddingOptions {
     fn from(value: EmbeddingModelOptions) -> Self {
         let requests = value
@@ -203,3 +278,31 @@ impl From<EmbeddingModelOptions> for GoogleEmbeddingOptions {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::UserMessage;
+
+    #[test]
+    fn test_system_and_developer_messages_make_the_system_instruction() {
+        let options = LanguageModelOptions {
+            system: Some("Be brief.".to_string()),
+            messages: vec![
+                TaggedMessage::from(Message::System("Be brief.".to_string().into())),
+                TaggedMessage::from(Message::Developer("Answer in French.".to_string())),
+                TaggedMessage::from(Message::User(UserMessage::new("Hi"))),
+            ],
+            ..Default::default()
+        };
+
+        let request = GenerateContentRequest::from(options);
+        let system = request.system_instruction.unwrap();
+        assert_eq!(
+            system.parts[0].text.as_deref(),
+            Some("Be brief.\n\nAnswer in French.")
+        );
+        assert_eq!(request.contents.len(), 1);
+        assert_eq!(request.contents[0].parts[0].text.as_deref(), Some("Hi"));
+    }
+}
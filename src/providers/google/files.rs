@@ -0,0 +1,201 @@
+//! Files API of the Google provider.
+//!
+//! Stored files are referenced in messages by their URI, and deleted by their
+//! name (e.g., "files/abc123"), which is used as the ID of their handle.
+
+use crate::core::capabilities::ModelName;
+use crate::core::client::{LanguageModelClient, send_json};
+use crate::core::files::{FileHandle, FileProvider, FilePurpose, mime_type};
+use crate::core::utils::join_url;
+use crate::error::{Error, Result};
+use crate::providers::google::Google;
+use async_trait::async_trait;
+use reqwest::header::CONTENT_TYPE;
+use serde::Deserialize;
+
+/// A file of the Files API.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleFile {
+    name: String,
+    #[serde(default)]
+    display_name: Option<String>,
+    #[serde(default)]
+    mime_type: Option<String>,
+    /// The size of the file, as a string of an int64.
+    #[serde(default)]
+    size_bytes: Option<String>,
+    #[serde(default)]
+    uri: Option<String>,
+}
+
+impl From<GoogleFile> for FileHandle {
+    fn from(file: GoogleFile) -> Self {
+        Self {
+            id: file.name,
+            filename: file.display_name,
+            mime_type: file.mime_type,
+            size: file.size_bytes.and_then(|size| size.parse().ok()),
+            uri: file.uri,
+        }
+    }
+}
+
+/// The response to an upload.
+#[derive(Debug, Deserialize)]
+struct GoogleUploadResponse {
+    file: GoogleFile,
+}
+
+/// A page of the list of files.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleFileList {
+    #[serde(default)]
+    files: Vec<GoogleFile>,
+    #[serde(default)]
+    next_page_token: Option<String>,
+}
+
+impl<M: ModelName> Google<M> {
+    /// Builds a request to an endpoint of the Files API.
+    fn files_request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+    ) -> Result<reqwest::RequestBuilder> {
+        let url = join_url(self.settings.base_url.as_str(), path)?;
+        Ok(LanguageModelClient::http_client(self)
+            .request(method, url)
+            .headers(LanguageModelClient::headers(self)))
+    }
+}
+
+#[async_trait]
+impl<M: ModelName> FileProvider for Google<M> {
+    /// Uploads a file. The purpose is ignored, as files are not tied to one.
+    async fn upload_file(
+        &self,
+        bytes: Vec<u8>,
+        filename: &str,
+        _purpose: FilePurpose,
+    ) -> Result<FileHandle> {
+        // The content is sent as is, with its media type
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            CONTENT_TYPE,
+            mime_type(filename)
+                .parse()
+                .map_err(|_| Error::InvalidInput("Invalid file name".to_string()))?,
+        );
+        headers.insert("x-goog-upload-protocol", "raw".parse().unwrap());
+
+        let request = self
+            .files_request(reqwest::Method::POST, "/upload/v1beta/files")?
+            .headers(headers)
+            .body(bytes);
+        let response: GoogleUploadResponse = send_json(request).await?;
+
+        let mut file = FileHandle::from(response.file);
+        // The display name is only set by resumable uploads
+        file.filename.get_or_insert_with(|| filename.to_string());
+        Ok(file)
+    }
+
+    async fn list_files(&self) -> Result<Vec<FileHandle>> {
+        let mut files = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut request = self.files_request(reqwest::Method::GET, "/v1beta/files")?;
+            if let Some(token) = &page_token {
+                request = request.query(&[("pageToken", token.as_str())]);
+            }
+            let page: GoogleFileList = send_json(request).await?;
+            files.extend(page.files.into_iter().map(FileHandle::from));
+            match page.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => return Ok(files),
+            }
+        }
+    }
+
+    async fn delete_file(&self, id: &str) -> Result<()> {
+        let request = self.files_request(reqwest::Method::DELETE, &format!("/v1beta/{id}"))?;
+        let _: serde_json::Value = send_json(request).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DynamicModel;
+    use crate::core::client::test_server::{TestResponse, serve};
+
+    /// Serves JSON responses on a local port, one per connection, and returns its
+    #[tokio::test]
+    async fn test_upload_list_and_delete_files() {
+        let (base_url, mut requests) = serve(vec![
+            TestResponse::json(r#"{"file":{"name":"files/abc","mimeType":"application/pdf","sizeBytes":"5","uri":"https://generativelanguage.googleapis.com/v1beta/files/abc","state":"ACTIVE"}}"#),
+            TestResponse::json(r#"{"files":[{"name":"files/abc","mimeType":"application/pdf","sizeBytes":"5","uri":"https://generativelanguage.googleapis.com/v1beta/files/abc"}],"nextPageToken":"page-2"}"#),
+            TestResponse::json(r#"{"files":[{"name":"files/def","displayName":"photo.png","mimeType":"image/png","sizeBytes":"9","uri":"https://generativelanguage.googleapis.com/v1beta/files/def"}]}"#),
+            TestResponse::json(r#"{}"#),
+        ])
+        .await;
+
+        let google = Google::<DynamicModel>::builder()
+            .model_name("gemini-2.5-flash")
+            .base_url(base_url)
+            .api_key("test")
+            .build()
+            .unwrap();
+
+        let file = google
+            .upload_file(b"%PDF-".to_vec(), "report.pdf", FilePurpose::UserData)
+            .await
+            .unwrap();
+        assert_eq!(file.id, "files/abc");
+        assert_eq!(file.filename.as_deref(), Some("report.pdf"));
+        assert_eq!(file.size, Some(5));
+        assert_eq!(
+            file.uri.as_deref(),
+            Some("https://generativelanguage.googleapis.com/v1beta/files/abc")
+        );
+
+        let upload = requests.recv().await.unwrap();
+        assert!(upload.starts_with("POST /upload/v1beta/files "));
+        assert!(upload.contains("content-type: application/pdf"));
+        assert!(upload.contains("x-goog-upload-protocol: raw"));
+        assert!(upload.ends_with("\r\n\r\n%PDF-"));
+
+        let files = google.list_files().await.unwrap();
+        assert_eq!(
+            files.iter().map(|f| f.id.as_str()).collect::<Vec<_>>(),
+            ["files/abc", "files/def"]
+        );
+        assert!(files[1].is_image());
+        assert!(
+            requests
+                .recv()
+                .await
+                .unwrap()
+                .starts_with("GET /v1beta/files ")
+        );
+        assert!(
+            requests
+                .recv()
+                .await
+                .unwrap()
+                .starts_with("GET /v1beta/files?pageToken=page-2 ")
+        );
+
+        google.delete_file("files/abc").await.unwrap();
+        assert!(
+            requests
+                .recv()
+                .await
+                .unwrap()
+                .starts_with("DELETE /v1beta/files/abc ")
+        );
+    }
+}
@@ -1,11 +1,16 @@
 //! Language model implementation for the Google provider.
+use crate::core::capabilities::CapabilitySet;
 use crate::core::capabilities::ModelName;
 use crate::core::client::LanguageModelClient;
+use crate::core::credentials::resolve_api_key;
+use crate::core::language_model::logprobs::TokenLogprob;
 use crate::core::language_model::{
-    LanguageModelOptions, LanguageModelResponse, LanguageModelResponseContentType,
-    LanguageModelStreamChunk, LanguageModelStreamChunkType, ProviderStream, Usage,
+    Candidate, ConstrainedOutput, LanguageModelOptions, LanguageModelResponse,
+    LanguageModelResponseContentType, LanguageModelStreamChunk, LanguageModelStreamChunkType,
+    ProviderStream, ResponseMetadata, ToolCallDelta, Usage,
 };
 use crate::core::messages::AssistantMessage;
+use crate::core::pricing::ModelPricing;
 use crate::providers::google::{Google, client::types, extensions};
 use crate::{
     core::{language_model::LanguageModel, tools::ToolCallInfo},
@@ -20,26 +25,65 @@ impl<M: ModelName> LanguageModel for Google<M> {
         self.lm_options.model.clone()
     }
 
+    fn pricing(&self) -> Option<ModelPricing> {
+        M::PRICING
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        M::CONTEXT_WINDOW
+    }
+
+    fn max_output_tokens(&self) -> Option<usize> {
+        M::MAX_OUTPUT_TOKENS
+    }
+
+    fn capabilities(&self) -> CapabilitySet {
+        M::CAPABILITIES
+    }
+
     async fn generate_text(
         &mut self,
-        options: LanguageModelOptions,
+        mut options: LanguageModelOptions,
     ) -> Result<LanguageModelResponse> {
+        resolve_api_key(
+            &self.settings.credential_provider,
+            &mut self.settings.api_key,
+        )
+        .await?;
+        options.resolve_constrained_output("Google", |output| {
+            *output == ConstrainedOutput::JsonObject
+        })?;
+        options.validate_penalties(-2.0..=2.0)?;
+        options.validate_sampling(0.0..=2.0)?;
         let additional_headers = options.headers.clone();
+        let additional_query = options.query.clone();
+        let max_retries = options.max_retries;
         let request: types::GenerateContentRequest = options.into();
         self.lm_options.request = Some(request);
         self.lm_options.streaming = false;
 
-        let response: types::GenerateContentResponse = self
-            .send(&self.settings.base_url, additional_headers)
+        let (response, mut metadata): (types::GenerateContentResponse, _) = self
+            .send_with_metadata(
+                &self.settings.base_url,
+                additional_headers,
+                additional_query,
+                max_retries,
+            )
             .await?;
+        metadata.response_id = response.response_id;
+        metadata.model = response.model_version;
 
         let mut collected = Vec::new();
+        let mut logprobs: Option<Vec<TokenLogprob>> = None;
+        let mut candidates = Vec::new();
         let usage = response.usage_metadata.map(|u| u.into());
 
-        for candidate in response.candidates {
+        // The first candidate is the response, the others additional candidates
+        for (index, candidate) in response.candidates.into_iter().enumerate() {
+            let mut contents = Vec::new();
             for part in candidate.content.parts {
                 if let Some(t) = part.text {
-                    collected.push(LanguageModelResponseContentType::Text(t));
+                    contents.push(LanguageModelResponseContentType::Text(t));
                 }
                 if let Some(fc) = part.function_call {
                     let mut tool_info = ToolCallInfo::new(fc.name);
@@ -50,42 +94,69 @@ impl<M: ModelName> LanguageModel for Google<M> {
                             .get_mut::<extensions::GoogleToolMetadata>()
                             .thought_signature = Some(sig);
                     }
-                    collected.push(LanguageModelResponseContentType::ToolCall(tool_info));
+                    contents.push(LanguageModelResponseContentType::ToolCall(tool_info));
                 }
             }
+
+            if index == 0 {
+                collected = contents;
+                logprobs = candidate.logprobs_result.map(Vec::<TokenLogprob>::from);
+            } else {
+                candidates.push(Candidate {
+                    contents,
+                    usage: None,
+                });
+            }
         }
 
         Ok(LanguageModelResponse {
             contents: collected,
             usage,
+            id: None,
+            logprobs,
+            candidates,
+            provider_metadata: None,
+            metadata: Some(metadata),
         })
     }
 
-    async fn stream_text(&mut self, options: LanguageModelOptions) -> Result<ProviderStream> {
+    async fn stream_text(&mut self, mut options: LanguageModelOptions) -> Result<ProviderStream> {
+        resolve_api_key(
+            &self.settings.credential_provider,
+            &mut self.settings.api_key,
+        )
+        .await?;
+        options.resolve_constrained_output("Google", |output| {
+            *output == ConstrainedOutput::JsonObject
+        })?;
+        options.validate_penalties(-2.0..=2.0)?;
+        options.validate_sampling(0.0..=2.0)?;
         let additional_headers = options.headers.clone();
+        let additional_query = options.query.clone();
+        let max_retries = options.max_retries.unwrap_or(5);
         let request: types::GenerateContentRequest = options.into();
         self.lm_options.request = Some(request);
         self.lm_options.streaming = true;
 
         // Retry logic for rate limiting
-        let max_retries = 5;
         let mut retry_count = 0;
         let mut wait_time = std::time::Duration::from_secs(1);
 
         let google_stream = loop {
             match self
-                .send_and_stream(&self.settings.base_url, additional_headers.clone())
+                .send_and_stream(
+                    &self.settings.base_url,
+                    additional_headers.clone(),
+                    additional_query.clone(),
+                )
                 .await
             {
                 Ok(stream) => break stream,
-                Err(crate::error::Error::ApiError {
-                    status_code: Some(status),
-                    ..
-                }) if status == reqwest::StatusCode::TOO_MANY_REQUESTS
-                    && retry_count < max_retries =>
+                Err(crate::error::Error::RateLimited { retry_after, .. })
+                    if retry_count < max_retries =>
                 {
                     retry_count += 1;
-                    tokio::time::sleep(wait_time).await;
+                    crate::core::runtime::sleep(retry_after.unwrap_or(wait_time)).await;
                     wait_time *= 2; // Exponential backoff
                     continue;
                 }
@@ -98,6 +169,7 @@ impl<M: ModelName> LanguageModel for Google<M> {
             accumulated_text: String,
             accumulated_tool_call: Option<ToolCallInfo>,
             usage: Option<Usage>,
+            metadata_sent: bool,
         }
 
         let stream = google_stream.scan(StreamState::default(), |state, evt_res| {
@@ -105,6 +177,17 @@ impl<M: ModelName> LanguageModel for Google<M> {
                 Ok(types::GoogleStreamEvent::Response(response)) => {
                     let mut chunks = Vec::new();
 
+                    if !state.metadata_sent {
+                        state.metadata_sent = true;
+                        chunks.push(LanguageModelStreamChunk::Delta(
+                            LanguageModelStreamChunkType::ResponseMetadata(ResponseMetadata {
+                                request_id: None,
+                                response_id: response.response_id.clone(),
+                                model: response.model_version.clone(),
+                            }),
+                        ));
+                    }
+
                     if let Some(usage) = response.usage_metadata.clone().map(Usage::from) {
                         state.usage = Some(usage);
                     }
@@ -129,13 +212,21 @@ impl<M: ModelName> LanguageModel for Google<M> {
                                 state.accumulated_tool_call = Some(tool_info);
 
                                 chunks.push(LanguageModelStreamChunk::Delta(
-                                    LanguageModelStreamChunkType::ToolCall(
-                                        serde_json::to_string(&fc).unwrap_or_default(),
-                                    ),
+                                    LanguageModelStreamChunkType::ToolCall(ToolCallDelta {
+                                        id: String::new(),
+                                        name: fc.name.clone(),
+                                        delta: fc.args.to_string(),
+                                    }),
                                 ));
                             }
                         }
 
+                        if let Some(result) = candidate.logprobs_result.clone() {
+                            chunks.push(LanguageModelStreamChunk::Delta(
+                                LanguageModelStreamChunkType::Logprobs(result.into()),
+                            ));
+                        }
+
                         if candidate.finish_reason.is_some() {
                             let content = if let Some(tc) = state.accumulated_tool_call.take() {
                                 LanguageModelResponseContentType::ToolCall(tc)
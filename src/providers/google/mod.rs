@@ -6,12 +6,15 @@ pub mod client;
 pub mod conversions;
 pub mod embedding_model;
 pub mod extensions;
+pub mod files;
 pub mod language_model;
 pub mod settings;
 
 use crate::core::DynamicModel;
 use crate::core::capabilities::ModelName;
-use crate::core::utils::validate_base_url;
+use crate::core::client::HttpSettings;
+use crate::core::credentials::{CredentialProvider, Credentials};
+use crate::core::utils::{env_var, validate_base_url};
 use crate::error::Error;
 use crate::providers::google::client::{GoogleEmbeddingOptions, GoogleOptions};
 use crate::providers::google::settings::GoogleProviderSettings;
@@ -32,6 +35,19 @@ impl<M: ModelName> Google<M> {
     pub fn builder() -> GoogleBuilder<M> {
         GoogleBuilder::default()
     }
+
+    /// Creates a Google provider builder configured from the environment.
+    ///
+    /// Reads the API key from `GOOGLE_API_KEY` and the base URL from
+    /// `GOOGLE_BASE_URL`.
+    /// Unset variables keep the defaults.
+    pub fn from_env() -> GoogleBuilder<M> {
+        let mut builder = Self::builder();
+        if let Some(base_url) = env_var("GOOGLE_BASE_URL") {
+            builder = builder.base_url(base_url);
+        }
+        builder
+    }
 }
 
 impl Google<DynamicModel> {
@@ -154,6 +170,27 @@ impl<M: ModelName> GoogleBuilder<M> {
         self
     }
 
+    /// Sets the credentials resolving the API key before each request, to fetch
+    /// it from a secret manager or rotate it. Takes precedence over `api_key`.
+    pub fn credential_provider(mut self, credentials: impl CredentialProvider + 'static) -> Self {
+        self.settings.credential_provider = Some(Credentials::new(credentials));
+        self
+    }
+
+    /// Sets the HTTP client sending the requests, to share its pool of connections
+    /// or configure it. Requests share one pooled client by default.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.settings.http_client = Some(client);
+        self
+    }
+
+    /// Sets the network settings of the HTTP client, such as a proxy or
+    /// certificate authorities. Ignored if an HTTP client is set.
+    pub fn http_settings(mut self, http: HttpSettings) -> Self {
+        self.settings.http = http;
+        self
+    }
+
     /// Sets the name of the provider. Defaults to "google".
     pub fn provider_name(mut self, provider_name: impl Into<String>) -> Self {
         self.settings.provider_name = provider_name.into();
@@ -172,7 +209,7 @@ impl<M: ModelName> GoogleBuilder<M> {
         let base_url = validate_base_url(&self.settings.base_url)?;
 
         // check api key exists
-        if self.settings.api_key.is_empty() {
+        if self.settings.api_key.is_empty() && self.settings.credential_provider.is_none() {
             return Err(Error::MissingField("api_key".to_string()));
         }
 
@@ -186,6 +223,10 @@ impl<M: ModelName> GoogleBuilder<M> {
         Ok(Google {
             settings: GoogleProviderSettings {
                 base_url,
+                http_client: self
+                    .settings
+                    .http
+                    .resolve_client(self.settings.http_client.clone())?,
                 ..self.settings
             },
             lm_options: options,
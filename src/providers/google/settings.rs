@@ -1,5 +1,7 @@
 //! Defines the settings for the Google provider.
 
+use crate::core::client::HttpSettings;
+use crate::core::credentials::Credentials;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 
@@ -16,6 +18,21 @@ pub struct GoogleProviderSettings {
     /// The API key for the Google API.
     pub api_key: String,
 
+    /// Credentials resolving the API key before each request, in place of
+    /// `api_key`.
+    #[serde(skip)]
+    pub credential_provider: Option<Credentials>,
+
+    /// The HTTP client sending the requests. Requests share one pooled client
+    /// if it is not set.
+    #[serde(skip)]
+    pub http_client: Option<reqwest::Client>,
+
+    /// Network settings of the HTTP client built for the provider when no
+    /// client is set, such as a proxy or certificate authorities.
+    #[serde(default)]
+    pub http: HttpSettings,
+
     /// Custom API path override. When set, this path is used instead of the
     /// default dynamic path (e.g., "/v1beta/models/{model}:generateContent").
     pub path: Option<String>,
@@ -28,6 +45,9 @@ impl Default for GoogleProviderSettings {
             provider_name: "google".to_string(),
             base_url: "https://generativelanguage.googleapis.com".to_string(),
             api_key: std::env::var("GOOGLE_API_KEY").unwrap_or_default(),
+            credential_provider: None,
+            http_client: None,
+            http: HttpSettings::default(),
             path: None,
         }
     }
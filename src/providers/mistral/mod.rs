@@ -21,3 +21,63 @@ crate::openai_compatible_provider!(
 
 // Generate the language model implementation
 crate::openai_compatible_language_model!(Mistral);
+
+/// Options of the Mistral API beyond the Chat Completions API, sent with every
+/// request of a [`Mistral`] model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MistralRequestOptions {
+    /// Whether to inject Mistral's safety prompt before the conversation.
+    pub safe_prompt: Option<bool>,
+    /// The seed used by requests without a seed of their own, for
+    /// deterministic sampling.
+    pub random_seed: Option<u32>,
+}
+
+impl<M: ModelName> MistralBuilder<M> {
+    /// Sets the Mistral specific options sent with every request.
+    pub fn request_options(mut self, options: MistralRequestOptions) -> Self {
+        self.inner.safe_prompt = options.safe_prompt;
+        self.inner.random_seed = options.random_seed;
+        self
+    }
+
+    /// Sets whether to inject Mistral's safety prompt before the conversation.
+    pub fn safe_prompt(mut self, safe_prompt: bool) -> Self {
+        self.inner.safe_prompt = Some(safe_prompt);
+        self
+    }
+
+    /// Sets the seed used by requests without a seed of their own.
+    pub fn random_seed(mut self, seed: u32) -> Self {
+        self.inner.random_seed = Some(seed);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::language_model::LanguageModelOptions;
+
+    #[test]
+    fn test_request_options_are_sent_with_requests() {
+        let model = Mistral::<DynamicModel>::builder()
+            .model_name("mistral-small-latest")
+            .api_key("key")
+            .request_options(MistralRequestOptions {
+                safe_prompt: Some(true),
+                random_seed: Some(42),
+            })
+            .build()
+            .unwrap();
+
+        let request = model
+            .inner
+            .request(LanguageModelOptions::default())
+            .unwrap();
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["safe_prompt"], true);
+        assert_eq!(body["random_seed"], 42);
+        assert!(body.get("seed").is_none());
+    }
+}
@@ -0,0 +1,406 @@
+//! A scripted language model for deterministic tests.
+//!
+//! [`MockLanguageModel`] answers requests with responses queued up front instead of
+//! calling a provider, so code built on the SDK, such as agent loops with tools, can
+//! be tested without API keys or network access. Every request is recorded and can
+//! be inspected with [`MockLanguageModel::calls`].
+//!
+//! Responses are consumed in order by both `generate_text` and `stream_text`. A
+//! response queued with [`with_stream`](MockLanguageModel::with_stream) is emitted
+//! chunk by chunk when streaming, and all other responses are split into text
+//! deltas followed by the final messages.
+//!
+//! This module is only available with the `test-utils` feature, and to the
+//! unit tests of the crate itself.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aisdk::core::LanguageModelRequest;
+//! use aisdk::core::tools::{Tool, ToolExecute};
+//! use aisdk::macros::tool;
+//! use aisdk::providers::MockLanguageModel;
+//!
+//! #[tool]
+//! /// Returns the weather in a city.
+//! fn get_weather(city: String) -> Tool {
+//!     Ok(format!("Sunny in {city}"))
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> aisdk::Result<()> {
+//! let model = MockLanguageModel::new()
+//!     .with_tool_call("get_weather", serde_json::json!({ "city": "Paris" }))
+//!     .with_text("It is sunny in Paris.");
+//!
+//! let response = LanguageModelRequest::builder()
+//!     .model(model.clone())
+//!     .prompt("What is the weather in Paris?")
+//!     .with_tool(get_weather())
+//!     .build()
+//!     .generate_text()
+//!     .await?;
+//!
+//! assert_eq!(response.text(), Some("It is sunny in Paris.".to_string()));
+//! // The second request contains the tool result
+//! assert_eq!(model.calls().len(), 2);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::core::AssistantMessage;
+use crate::core::capabilities::*;
+use crate::core::language_model::middleware::cache::replay;
+use crate::core::language_model::{
+    LanguageModel, LanguageModelOptions, LanguageModelResponse, LanguageModelResponseContentType,
+    LanguageModelStreamChunk, LanguageModelStreamChunkType, ProviderStream,
+};
+use crate::core::pricing::ModelPricing;
+use crate::core::tools::ToolCallInfo;
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A scripted response of a [`MockLanguageModel`].
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)] // Scripted in tests, where the size does not matter
+pub enum MockResponse {
+    /// A complete response.
+    Response(LanguageModelResponse),
+    /// A sequence of stream chunks, each emitted as a separate stream item.
+    Stream(Vec<LanguageModelStreamChunk>),
+    /// A sequence of stream chunks interrupted by an error, such as a dropped
+    /// connection. Answered with the error when not streaming.
+    InterruptedStream(Vec<LanguageModelStreamChunk>, Error),
+    /// An error returned instead of a response.
+    Error(Error),
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    responses: VecDeque<MockResponse>,
+    calls: Vec<LanguageModelOptions>,
+    next_tool_call_id: usize,
+}
+
+/// A language model that answers with scripted responses.
+///
+/// Clones share the same script and call history, so a clone can be handed to a
+/// request while the original is used for assertions.
+#[derive(Debug, Clone)]
+pub struct MockLanguageModel {
+    name: String,
+    capabilities: CapabilitySet,
+    pricing: Option<ModelPricing>,
+    context_window: Option<usize>,
+    delay: Duration,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl Default for MockLanguageModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockLanguageModel {
+    /// Creates a new mock model without any scripted responses.
+    pub fn new() -> Self {
+        Self {
+            name: "mock".to_string(),
+            capabilities: CapabilitySet::all(),
+            pricing: None,
+            context_window: None,
+            delay: Duration::ZERO,
+            state: Arc::new(Mutex::new(MockState::default())),
+        }
+    }
+
+    /// Sets the name reported by the model.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Sets the capabilities reported by the model, all of them by default.
+    pub fn with_capabilities(mut self, capabilities: CapabilitySet) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Sets the pricing reported by the model.
+    pub fn with_pricing(mut self, pricing: ModelPricing) -> Self {
+        self.pricing = Some(pricing);
+        self
+    }
+
+    /// Sets the context window reported by the model.
+    pub fn with_context_window(mut self, tokens: usize) -> Self {
+        self.context_window = Some(tokens);
+        self
+    }
+
+    /// Sets a delay before every response, to simulate the latency of a provider.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Queues a scripted response.
+    pub fn push(&self, response: MockResponse) {
+        self.state.lock().responses.push_back(response);
+    }
+
+    /// Queues a complete response.
+    pub fn with_response(self, response: LanguageModelResponse) -> Self {
+        self.push(MockResponse::Response(response));
+        self
+    }
+
+    /// Queues a text response.
+    pub fn with_text(self, text: impl Into<String>) -> Self {
+        self.with_response(LanguageModelResponse::new(text))
+    }
+
+    /// Queues a response that calls the tool `name` with `input`.
+    ///
+    /// Tool call ids are generated in order as `call_0`, `call_1`, ...
+    pub fn with_tool_call(self, name: impl Into<String>, input: serde_json::Value) -> Self {
+        let id = {
+            let mut state = self.state.lock();
+            let id = format!("call_{}", state.next_tool_call_id);
+            state.next_tool_call_id += 1;
+            id
+        };
+
+        let mut info = ToolCallInfo::new(name);
+        info.id(id);
+        info.input(input);
+
+        self.with_response(LanguageModelResponse {
+            contents: vec![LanguageModelResponseContentType::ToolCall(info)],
+            usage: None,
+            id: None,
+            logprobs: None,
+            candidates: Vec::new(),
+            provider_metadata: None,
+            metadata: None,
+        })
+    }
+
+    /// Queues a sequence of stream chunks.
+    ///
+    /// When answered with `generate_text`, the final messages of the sequence
+    /// become the response.
+    pub fn with_stream(self, chunks: Vec<LanguageModelStreamChunk>) -> Self {
+        self.push(MockResponse::Stream(chunks));
+        self
+    }
+
+    /// Queues a sequence of stream chunks followed by an error interrupting the stream.
+    pub fn with_interrupted_stream(
+        self,
+        chunks: Vec<LanguageModelStreamChunk>,
+        error: Error,
+    ) -> Self {
+        self.push(MockResponse::InterruptedStream(chunks, error));
+        self
+    }
+
+    /// Queues an error.
+    pub fn with_error(self, error: Error) -> Self {
+        self.push(MockResponse::Error(error));
+        self
+    }
+
+    /// Returns the options of every request made so far, in order.
+    pub fn calls(&self) -> Vec<LanguageModelOptions> {
+        self.state.lock().calls.clone()
+    }
+
+    /// Returns the number of scripted responses that have not been used yet.
+    pub fn remaining(&self) -> usize {
+        self.state.lock().responses.len()
+    }
+
+    /// Records the request and takes the next scripted response, after the delay.
+    async fn next(&self, options: LanguageModelOptions) -> Result<MockResponse> {
+        let response = {
+            let mut state = self.state.lock();
+            state.calls.push(options);
+            state.responses.pop_front().ok_or_else(|| {
+                Error::Other("MockLanguageModel has no scripted responses left".into())
+            })
+        };
+        if !self.delay.is_zero() {
+            crate::core::runtime::sleep(self.delay).await;
+        }
+        response
+    }
+}
+
+#[async_trait]
+impl LanguageModel for MockLanguageModel {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn pricing(&self) -> Option<ModelPricing> {
+        self.pricing
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        self.context_window
+    }
+
+    fn capabilities(&self) -> CapabilitySet {
+        self.capabilities
+    }
+
+    async fn generate_text(
+        &mut self,
+        options: LanguageModelOptions,
+    ) -> Result<LanguageModelResponse> {
+        match self.next(options).await? {
+            MockResponse::Response(response) => Ok(response),
+            MockResponse::Stream(chunks) => {
+                let mut response = LanguageModelResponse {
+                    contents: Vec::new(),
+                    usage: None,
+                    id: None,
+                    logprobs: None,
+                    candidates: Vec::new(),
+                    provider_metadata: None,
+                    metadata: None,
+                };
+                for chunk in chunks {
+                    match chunk {
+                        LanguageModelStreamChunk::Done(AssistantMessage { content, usage }) => {
+                            response.contents.push(content);
+                            response.usage = usage.or(response.usage);
+                        }
+                        LanguageModelStreamChunk::Delta(
+                            LanguageModelStreamChunkType::Logprobs(logprobs),
+                        ) => response.logprobs.get_or_insert_default().extend(logprobs),
+                        LanguageModelStreamChunk::Delta(_) => {}
+                    }
+                }
+                Ok(response)
+            }
+            MockResponse::InterruptedStream(_, error) | MockResponse::Error(error) => Err(error),
+        }
+    }
+
+    async fn stream_text(&mut self, options: LanguageModelOptions) -> Result<ProviderStream> {
+        match self.next(options).await? {
+            MockResponse::Response(response) => Ok(replay(response)),
+            MockResponse::Stream(chunks) => Ok(Box::pin(futures::stream::iter(
+                chunks.into_iter().map(|chunk| Ok(vec![chunk])),
+            ))),
+            MockResponse::InterruptedStream(chunks, error) => Ok(Box::pin(futures::stream::iter(
+                chunks
+                    .into_iter()
+                    .map(|chunk| Ok(vec![chunk]))
+                    .chain([Err(error)]),
+            ))),
+            MockResponse::Error(error) => Err(error),
+        }
+    }
+}
+
+// The mock accepts every kind of request, like a dynamic model
+
+impl ToolCallSupport for MockLanguageModel {}
+impl StructuredOutputSupport for MockLanguageModel {}
+impl ReasoningSupport for MockLanguageModel {}
+impl TextInputSupport for MockLanguageModel {}
+impl TextOutputSupport for MockLanguageModel {}
+impl ImageInputSupport for MockLanguageModel {}
+impl VideoInputSupport for MockLanguageModel {}
+impl AudioInputSupport for MockLanguageModel {}
+impl ImageOutputSupport for MockLanguageModel {}
+impl VideoOutputSupport for MockLanguageModel {}
+impl AudioOutputSupport for MockLanguageModel {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::language_model::{LanguageModelStreamChunkType, Usage};
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_mock_language_model_answers_in_order() {
+        let mut model = MockLanguageModel::new()
+            .with_text("first")
+            .with_error(Error::Other("boom".to_string()))
+            .with_text("second");
+
+        let first = model.generate_text(Default::default()).await.unwrap();
+        assert!(matches!(
+            &first.contents[..],
+            [LanguageModelResponseContentType::Text(text)] if text == "first"
+        ));
+        assert_eq!(
+            model.generate_text(Default::default()).await.unwrap_err(),
+            Error::Other("boom".to_string())
+        );
+        assert!(model.generate_text(Default::default()).await.is_ok());
+        assert!(model.generate_text(Default::default()).await.is_err());
+        assert_eq!(model.calls().len(), 4);
+        assert_eq!(model.remaining(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_mock_language_model_tool_call_ids() {
+        let mut model = MockLanguageModel::new()
+            .with_tool_call("a", serde_json::json!({}))
+            .with_tool_call("b", serde_json::json!({ "x": 1 }));
+
+        for (name, id) in [("a", "call_0"), ("b", "call_1")] {
+            let response = model.generate_text(Default::default()).await.unwrap();
+            match &response.contents[0] {
+                LanguageModelResponseContentType::ToolCall(info) => {
+                    assert_eq!(info.tool.name, name);
+                    assert_eq!(info.tool.id, id);
+                }
+                other => panic!("Expected tool call, got {other:?}"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_language_model_streams_scripted_chunks() {
+        let chunks = vec![
+            LanguageModelStreamChunk::Delta(LanguageModelStreamChunkType::Text("Hi".to_string())),
+            LanguageModelStreamChunk::Done(AssistantMessage {
+                content: LanguageModelResponseContentType::Text("Hi".to_string()),
+                usage: Some(Usage {
+                    output_tokens: Some(1),
+                    ..Default::default()
+                }),
+            }),
+        ];
+        let mut model = MockLanguageModel::new()
+            .with_stream(chunks.clone())
+            .with_stream(chunks);
+
+        let items: Vec<_> = model
+            .stream_text(Default::default())
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(items.len(), 2);
+
+        // The same script answers generate_text with its final messages
+        let response = model.generate_text(Default::default()).await.unwrap();
+        assert!(matches!(
+            &response.contents[..],
+            [LanguageModelResponseContentType::Text(text)] if text == "Hi"
+        ));
+        assert_eq!(response.usage.unwrap().output_tokens, Some(1));
+    }
+}
@@ -1,6 +1,9 @@
 //! This module provides the `Provider` trait, which defines the interface for
 //! interacting with different AI providers.
 
+pub mod config;
+pub use config::{ProviderConfig, ProvidersConfig};
+
 #[cfg(feature = "openai")]
 pub mod openai;
 #[cfg(feature = "openai")]
@@ -27,6 +30,11 @@ pub mod google;
 #[cfg(feature = "google")]
 pub use google::Google;
 
+#[cfg(feature = "vertex-ai")]
+pub mod vertex_ai;
+#[cfg(feature = "vertex-ai")]
+pub use vertex_ai::VertexAI;
+
 #[cfg(feature = "vercel")]
 pub mod vercel;
 #[cfg(feature = "vercel")]
@@ -57,6 +65,16 @@ pub mod xai;
 #[cfg(feature = "xai")]
 pub use xai::XAI;
 
+#[cfg(feature = "ollama")]
+pub mod ollama;
+#[cfg(feature = "ollama")]
+pub use ollama::Ollama;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod mock;
+#[cfg(any(test, feature = "test-utils"))]
+pub use mock::MockLanguageModel;
+
 // Internal module for OpenAI Chat Completions API compatible providers
 #[cfg(feature = "openaichatcompletions")]
 pub(crate) mod openai_chat_completions;
@@ -0,0 +1,38 @@
+//! Capabilities for ollama models.
+//!
+//! This module defines model types and their capabilities for the ollama provider.
+//! Users can implement additional traits on custom models.
+
+use crate::core::capabilities::*;
+use crate::model_capabilities;
+use crate::providers::ollama::Ollama;
+
+model_capabilities! {
+    provider: Ollama,
+    models: {
+        GptOss20b {
+            model_name: "gpt-oss:20b",
+            constructor_name: gpt_oss_20b,
+            display_name: "GPT OSS 20B",
+            capabilities: [ReasoningSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+        },
+        Llama32 {
+            model_name: "llama3.2",
+            constructor_name: llama3_2,
+            display_name: "Llama 3.2",
+            capabilities: [StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+        },
+        Qwen3 {
+            model_name: "qwen3",
+            constructor_name: qwen3,
+            display_name: "Qwen3",
+            capabilities: [ReasoningSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+        },
+        Gemma3 {
+            model_name: "gemma3",
+            constructor_name: gemma3,
+            display_name: "Gemma 3",
+            capabilities: [ImageInputSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport]
+        },
+    }
+}
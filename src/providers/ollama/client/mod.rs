@@ -0,0 +1,283 @@
+//! Client implementation for the Ollama provider.
+
+/// Type definitions for the native Ollama API.
+pub mod types;
+
+pub(crate) use types::*;
+
+use crate::core::capabilities::ModelName;
+use crate::core::client::{
+    LanguageModelClient, request_error, send_json, shared_client, sse_error, status_error, vcr,
+    with_additional_query,
+};
+use crate::core::runtime::sendable;
+use crate::core::utils::join_url;
+use crate::error::{Error, Result};
+use crate::providers::ollama::Ollama;
+use derive_builder::Builder;
+use futures::{Stream, StreamExt};
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+use reqwest_eventsource::Event;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::pin::Pin;
+
+/// A request to the `/api/chat` endpoint.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Builder)]
+#[builder(setter(into), build_fn(error = "Error"))]
+pub(crate) struct OllamaOptions {
+    pub(crate) model: String,
+    #[builder(default)]
+    pub(crate) messages: Vec<OllamaMessage>,
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) tools: Option<Vec<OllamaTool>>,
+    /// The JSON schema of a structured output.
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) format: Option<serde_json::Value>,
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) options: Option<OllamaModelOptions>,
+    #[builder(default)]
+    pub(crate) stream: bool,
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) keep_alive: Option<String>,
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) think: Option<bool>,
+}
+
+impl OllamaOptions {
+    pub(crate) fn builder() -> OllamaOptionsBuilder {
+        OllamaOptionsBuilder::default()
+    }
+}
+
+/// Parses a line of a streamed response.
+fn parse_line(line: &str) -> Result<OllamaStreamEvent> {
+    serde_json::from_str(line).map_err(|e| Error::ApiError {
+        status_code: None,
+        details: format!("Invalid JSON in stream: {e}"),
+    })
+}
+
+impl<M: ModelName> LanguageModelClient for Ollama<M> {
+    type Response = OllamaChatResponse;
+    type StreamEvent = OllamaStreamEvent;
+
+    fn path(&self) -> String {
+        "/api/chat".to_string()
+    }
+
+    fn method(&self) -> reqwest::Method {
+        reqwest::Method::POST
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        self.settings
+            .http_client
+            .clone()
+            .unwrap_or_else(shared_client)
+    }
+
+    fn headers(&self) -> reqwest::header::HeaderMap {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        if !self.settings.api_key.is_empty() {
+            headers.insert(
+                AUTHORIZATION,
+                format!("Bearer {}", self.settings.api_key).parse().unwrap(),
+            );
+        }
+        headers
+    }
+
+    fn query_params(&self) -> Vec<(&str, &str)> {
+        Vec::new()
+    }
+
+    fn body(&self) -> reqwest::Body {
+        let body = serde_json::to_string(&self.options).unwrap();
+        reqwest::Body::from(body)
+    }
+
+    // Ollama streams newline-delimited JSON instead of server-sent events, see
+    // `send_and_stream_ndjson`. Each line has the same shape as an SSE message.
+    fn parse_stream_sse(
+        event: std::result::Result<Event, reqwest_eventsource::Error>,
+    ) -> Result<Self::StreamEvent> {
+        match event {
+            Ok(Event::Open) => Err(Error::Other("Unexpected stream open event".to_string())),
+            Ok(Event::Message(msg)) => parse_line(&msg.data),
+            Err(e) => Err(sse_error(e)),
+        }
+    }
+
+    fn end_stream(event: &Self::StreamEvent) -> bool {
+        match event {
+            OllamaStreamEvent::Chunk(chunk) => chunk.done,
+            OllamaStreamEvent::Error { .. } => true,
+        }
+    }
+}
+
+impl<M: ModelName> Ollama<M> {
+    /// Sends the chat request and streams the newline-delimited JSON response.
+    ///
+    /// Like `send_and_stream`, streams are recorded to and replayed from the
+    /// current cassette, with one event per line.
+    pub(crate) async fn send_and_stream_ndjson(
+        &self,
+        additional_headers: Option<HashMap<String, String>>,
+        additional_query: Option<HashMap<String, String>>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<OllamaStreamEvent>> + Send>>> {
+        let url = join_url(self.settings.base_url.as_str(), &self.path())?;
+        let mut headers = LanguageModelClient::headers(self);
+        if let Some(ref extra) = additional_headers
+            && let Ok(extra_map) = reqwest::header::HeaderMap::try_from(extra)
+        {
+            headers.extend(extra_map);
+        }
+
+        let method = self.method();
+        let body = self.body();
+        let cassette = vcr::current();
+        let request = cassette
+            .as_ref()
+            .map(|_| vcr::RecordedRequest::new(&method, &url, &body));
+
+        let lines: Pin<Box<dyn Stream<Item = Result<String>> + Send>> = match (cassette, request) {
+            (Some(cassette), Some(request)) if cassette.is_replaying() => {
+                let events = cassette.next_events(&request)?;
+                Box::pin(futures::stream::iter(events).filter_map(|event| {
+                    futures::future::ready(match event {
+                        vcr::RecordedEvent::Open => None,
+                        vcr::RecordedEvent::Message { data, .. } => Some(Ok(data)),
+                        vcr::RecordedEvent::Error {
+                            status,
+                            headers,
+                            details,
+                        } => Some(Err(
+                            match status.and_then(|s| reqwest::StatusCode::from_u16(s).ok()) {
+                                Some(status) => {
+                                    status_error(status, &vcr::replay_headers(&headers), details)
+                                }
+                                None => Error::ApiError {
+                                    status_code: None,
+                                    details,
+                                },
+                            },
+                        )),
+                    })
+                }))
+            }
+            (cassette, request) => {
+                let recorder = cassette.zip(request).map(|(c, r)| c.record_stream(r));
+
+                let request = self
+                    .http_client()
+                    .request(method, url)
+                    .headers(headers)
+                    .query(&with_additional_query(
+                        self.query_params(),
+                        &additional_query,
+                    ))
+                    .body(body);
+
+                // The response is not `Send` on wasm32, so it is read in a single future
+                let response = sendable(async {
+                    let response = request.send().await.map_err(request_error)?;
+
+                    let status = response.status();
+                    if !status.is_success() {
+                        let response_headers = response.headers().clone();
+                        let details = response.text().await.unwrap_or_default();
+                        if let Some(recorder) = &recorder {
+                            recorder.push(vcr::RecordedEvent::Error {
+                                status: Some(status.as_u16()),
+                                headers: vcr::record_headers(&response_headers),
+                                details: details.clone(),
+                            });
+                        }
+                        return Err(status_error(status, &response_headers, details));
+                    }
+                    Ok(response)
+                })
+                .await?;
+
+                Box::pin(ndjson_lines(response).inspect(move |line| {
+                    if let (Some(recorder), Ok(line)) = (&recorder, line) {
+                        recorder.push(vcr::RecordedEvent::Message {
+                            event: String::new(),
+                            data: line.clone(),
+                        });
+                    }
+                }))
+            }
+        };
+
+        // Stop after the final chunk or the first error
+        let stream = lines
+            .map(|line| line.and_then(|line| parse_line(&line)))
+            .scan(false, |ended, event| {
+                if *ended {
+                    return futures::future::ready(None);
+                }
+                *ended = event.as_ref().map_or(true, Self::end_stream);
+                futures::future::ready(Some(event))
+            });
+
+        Ok(Box::pin(stream))
+    }
+
+    /// Sends a request to one of the model management endpoints.
+    pub(crate) async fn request<T: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> Result<T> {
+        let url = join_url(self.settings.base_url.as_str(), path)?;
+        let mut request = self
+            .http_client()
+            .request(method, url)
+            .headers(LanguageModelClient::headers(self));
+        if let Some(body) = body {
+            request = request.body(body.to_string());
+        }
+
+        send_json(request).await
+    }
+}
+
+/// Splits the body of a response into its non-empty lines.
+fn ndjson_lines(response: reqwest::Response) -> impl Stream<Item = Result<String>> + Send {
+    futures::stream::unfold(
+        (Box::pin(sendable(response.bytes_stream())), Vec::new()),
+        |(mut bytes, mut buffer)| async move {
+            loop {
+                if let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+                    let line: Vec<u8> = buffer.drain(..=pos).collect();
+                    let line = String::from_utf8_lossy(&line).trim().to_string();
+                    return Some((Ok(line), (bytes, buffer)));
+                }
+                match bytes.next().await {
+                    Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                    Some(Err(e)) => {
+                        return Some((Err(request_error(e)), (bytes, buffer)));
+                    }
+                    None if buffer.is_empty() => return None,
+                    None => {
+                        let line = String::from_utf8_lossy(&buffer).trim().to_string();
+                        buffer.clear();
+                        return Some((Ok(line), (bytes, buffer)));
+                    }
+                }
+            }
+        },
+    )
+    .filter(|line| futures::future::ready(!matches!(line, Ok(line) if line.is_empty())))
+}
@@ -0,0 +1,181 @@
+//! Type definitions for the native Ollama API.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The role of a chat message.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum OllamaRole {
+    System,
+    #[default]
+    User,
+    Assistant,
+    Tool,
+}
+
+/// A message of the `/api/chat` endpoint.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct OllamaMessage {
+    pub role: OllamaRole,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<OllamaToolCall>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+}
+
+/// A tool call made by the model.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct OllamaToolCall {
+    pub function: OllamaFunctionCall,
+}
+
+/// The function of a tool call. Unlike OpenAI, the arguments are a JSON object.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct OllamaFunctionCall {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+/// A tool available to the model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct OllamaTool {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub function: OllamaFunction,
+}
+
+/// The definition of a function tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct OllamaFunction {
+    pub name: String,
+    pub description: String,
+    pub parameters: Value,
+}
+
+/// Model parameters of a request.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct OllamaModelOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_predict: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+}
+
+/// A response of the `/api/chat` endpoint, or a chunk of a streamed response.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct OllamaChatResponse {
+    #[serde(default)]
+    pub model: String,
+    #[serde(default)]
+    pub message: OllamaMessage,
+    #[serde(default)]
+    pub done: bool,
+    #[serde(default)]
+    pub done_reason: Option<String>,
+    #[serde(default)]
+    pub prompt_eval_count: Option<usize>,
+    #[serde(default)]
+    pub eval_count: Option<usize>,
+}
+
+/// A line of a streamed `/api/chat` response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum OllamaStreamEvent {
+    /// The server reported an error in the middle of the stream.
+    Error { error: String },
+    /// A chunk of the response.
+    Chunk(OllamaChatResponse),
+}
+
+/// A request to the `/api/generate` endpoint.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct OllamaGenerateRequest {
+    pub model: String,
+    pub prompt: String,
+    pub stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<String>,
+}
+
+/// A response of the `/api/generate` endpoint.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct OllamaGenerateResponse {
+    #[serde(default)]
+    pub response: String,
+}
+
+/// A model available on the Ollama server, as returned by `/api/tags`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OllamaModelInfo {
+    /// The name of the model (e.g., "llama3.2:latest").
+    pub name: String,
+    /// The size of the model in bytes.
+    #[serde(default)]
+    pub size: u64,
+    /// The digest of the model.
+    #[serde(default)]
+    pub digest: String,
+    /// When the model was last modified.
+    #[serde(default)]
+    pub modified_at: String,
+    /// Details about the model, such as its family and quantization.
+    #[serde(default)]
+    pub details: Option<OllamaModelDetails>,
+}
+
+/// Details about a local model.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OllamaModelDetails {
+    /// The format of the model file (e.g., "gguf").
+    #[serde(default)]
+    pub format: String,
+    /// The model family (e.g., "llama").
+    #[serde(default)]
+    pub family: String,
+    /// The parameter size (e.g., "3.2B").
+    #[serde(default)]
+    pub parameter_size: String,
+    /// The quantization level (e.g., "Q4_K_M").
+    #[serde(default)]
+    pub quantization_level: String,
+}
+
+/// A response of the `/api/tags` endpoint.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct OllamaListModelsResponse {
+    #[serde(default)]
+    pub models: Vec<OllamaModelInfo>,
+}
+
+/// A request to the `/api/pull` endpoint.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct OllamaPullRequest {
+    pub model: String,
+    pub stream: bool,
+}
+
+/// A response of the `/api/pull` endpoint.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct OllamaPullResponse {
+    #[serde(default)]
+    pub status: String,
+}
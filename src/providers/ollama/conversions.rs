@@ -0,0 +1,213 @@
+//! Conversions between types used by the Ollama provider and the types used by the core library.
+
+use crate::core::Message;
+use crate::core::language_model::{
+    ConstrainedOutput, LanguageModelOptions, LanguageModelResponseContentType, Usage,
+};
+use crate::core::tools::ToolChoice;
+use crate::providers::ollama::client::{
+    OllamaChatResponse, OllamaFunction, OllamaFunctionCall, OllamaMessage, OllamaModelOptions,
+    OllamaOptions, OllamaRole, OllamaTool, OllamaToolCall,
+};
+
+impl From<LanguageModelOptions> for OllamaOptions {
+    fn from(options: LanguageModelOptions) -> Self {
+        let mut messages = Vec::new();
+
+        if let Some(system) = options.system
+            && !system.is_empty()
+        {
+            messages.push(OllamaMessage {
+                role: OllamaRole::System,
+                content: system,
+                ..Default::default()
+            });
+        }
+
+        for msg in options.messages {
+            let message = match msg.message {
+                Message::System(s) => OllamaMessage {
+                    role: OllamaRole::System,
+                    content: s.content,
+                    ..Default::default()
+                },
+                Message::Developer(content) => OllamaMessage {
+                    role: OllamaRole::System,
+                    content,
+                    ..Default::default()
+                },
+                Message::User(u) => OllamaMessage {
+                    role: OllamaRole::User,
+                    content: u.content,
+                    ..Default::default()
+                },
+                Message::Assistant(a) => match a.content {
+                    LanguageModelResponseContentType::Text(content) => OllamaMessage {
+                        role: OllamaRole::Assistant,
+                        content,
+                        ..Default::default()
+                    },
+                    LanguageModelResponseContentType::ToolCall(call) => OllamaMessage {
+                        role: OllamaRole::Assistant,
+                        tool_calls: Some(vec![OllamaToolCall {
+                            function: OllamaFunctionCall {
+                                name: call.tool.name,
+                                arguments: call.input,
+                            },
+                        }]),
+                        ..Default::default()
+                    },
+                    LanguageModelResponseContentType::Reasoning { content, .. } => OllamaMessage {
+                        role: OllamaRole::Assistant,
+                        thinking: Some(content),
+                        ..Default::default()
+                    },
+                    LanguageModelResponseContentType::Source(_)
+                    | LanguageModelResponseContentType::Citation(_)
+                    | LanguageModelResponseContentType::NotSupported(_) => continue,
+                },
+                Message::Tool(result) => OllamaMessage {
+                    role: OllamaRole::Tool,
+                    content: match result.output {
+                        Ok(serde_json::Value::String(output)) => output,
+                        Ok(output) => output.to_string(),
+                        Err(e) => e.to_string(),
+                    },
+                    tool_name: Some(result.tool.name),
+                    ..Default::default()
+                },
+            };
+            messages.push(message);
+        }
+
+        // Ollama has no tool choice, so it is approximated with the tools sent
+        let tools = match options.tool_choice {
+            Some(ToolChoice::None) => None,
+            Some(ToolChoice::Tool(name)) => options.tools.map(|tools| tools.only(&[name])),
+            _ => options.tools,
+        };
+        let tools = tools.map(|tools| {
+            tools
+                .tools
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .iter()
+                .map(|tool| {
+                    let mut parameters = tool.input_schema.clone().to_value();
+                    if let Some(obj) = parameters.as_object_mut() {
+                        obj.remove("$schema");
+                    }
+                    OllamaTool {
+                        type_: "function".to_string(),
+                        function: OllamaFunction {
+                            name: tool.name.clone(),
+                            description: tool.description.clone(),
+                            parameters,
+                        },
+                    }
+                })
+                .collect()
+        });
+
+        let format = match options.constrained_output {
+            Some(ConstrainedOutput::JsonObject) => Some(serde_json::Value::from("json")),
+            _ => options.schema.map(|schema| {
+                let mut value = serde_json::to_value(schema).unwrap_or_default();
+                if let Some(obj) = value.as_object_mut() {
+                    obj.remove("$schema");
+                }
+                value
+            }),
+        };
+
+        let model_options = OllamaModelOptions {
+            temperature: options.temperature,
+            top_p: options.top_p,
+            top_k: options.top_k,
+            num_predict: options.max_output_tokens,
+            seed: options.seed,
+            stop: options.stop_sequences,
+            presence_penalty: options.presence_penalty,
+            frequency_penalty: options.frequency_penalty,
+        };
+
+        OllamaOptions {
+            model: String::new(),
+            messages,
+            tools,
+            format,
+            options: Some(model_options),
+            stream: false,
+            keep_alive: None,
+            think: options.reasoning_effort.map(|_| true),
+        }
+    }
+}
+
+impl From<&OllamaChatResponse> for Usage {
+    fn from(response: &OllamaChatResponse) -> Self {
+        Self {
+            input_tokens: response.prompt_eval_count,
+            output_tokens: response.eval_count,
+            reasoning_tokens: None,
+            cached_tokens: None,
+            cache_write_tokens: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::messages::TaggedMessage;
+    use crate::core::tools::{ToolCallInfo, ToolDetails, ToolResultInfo};
+    use crate::core::{AssistantMessage, UserMessage};
+
+    #[test]
+    fn test_options_to_ollama_chat_request() {
+        let mut call = ToolCallInfo::new("get_weather");
+        call.id("call_0");
+        call.input(serde_json::json!({ "city": "Paris" }));
+
+        let options = LanguageModelOptions {
+            system: Some("Be brief".to_string()),
+            temperature: Some(0.5),
+            max_output_tokens: Some(100),
+            messages: vec![
+                TaggedMessage::from(Message::User(UserMessage::new("Weather in Paris?"))),
+                TaggedMessage::from(Message::Assistant(AssistantMessage {
+                    content: LanguageModelResponseContentType::ToolCall(call),
+                    usage: None,
+                })),
+                TaggedMessage::from(Message::Tool(ToolResultInfo {
+                    tool: ToolDetails {
+                        id: "call_0".to_string(),
+                        name: "get_weather".to_string(),
+                    },
+                    output: Ok(serde_json::Value::String("Sunny".to_string())),
+                })),
+            ],
+            ..Default::default()
+        };
+
+        let request = serde_json::to_value(OllamaOptions::from(options)).unwrap();
+        assert_eq!(
+            request,
+            serde_json::json!({
+                "model": "",
+                "messages": [
+                    { "role": "system", "content": "Be brief" },
+                    { "role": "user", "content": "Weather in Paris?" },
+                    {
+                        "role": "assistant",
+                        "content": "",
+                        "tool_calls": [{ "function": { "name": "get_weather", "arguments": { "city": "Paris" } } }]
+                    },
+                    { "role": "tool", "content": "Sunny", "tool_name": "get_weather" }
+                ],
+                "options": { "temperature": 0.5, "num_predict": 100 },
+                "stream": false
+            })
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Language model implementation for the Ollama provider.
+
+use crate::core::capabilities::CapabilitySet;
+use crate::core::capabilities::ModelName;
+use crate::core::client::LanguageModelClient;
+use crate::core::credentials::resolve_api_key;
+use crate::core::language_model::{
+    ConstrainedOutput, LanguageModel, LanguageModelOptions, LanguageModelResponse,
+    LanguageModelResponseContentType, LanguageModelStreamChunk, LanguageModelStreamChunkType,
+    ProviderStream, ResponseMetadata, ToolCallDelta, Usage,
+};
+use crate::core::messages::AssistantMessage;
+use crate::core::pricing::ModelPricing;
+use crate::core::tools::ToolCallInfo;
+use crate::error::Result;
+use crate::extensions::Extensions;
+use crate::providers::ollama::Ollama;
+use crate::providers::ollama::client::{
+    OllamaMessage, OllamaOptions, OllamaStreamEvent, OllamaToolCall,
+};
+use async_trait::async_trait;
+use futures::StreamExt;
+
+/// Converts a tool call of the model. Ollama does not return call IDs, so one is generated.
+fn tool_call_info(call: &OllamaToolCall) -> ToolCallInfo {
+    let mut info = ToolCallInfo::new(call.function.name.clone());
+    info.id(crate::core::ids::generate_id("call"));
+    info.input(call.function.arguments.clone());
+    info
+}
+
+/// Converts a complete assistant message into response contents.
+fn contents(message: &OllamaMessage) -> Vec<LanguageModelResponseContentType> {
+    let mut contents = Vec::new();
+    if let Some(thinking) = message.thinking.as_ref().filter(|t| !t.is_empty()) {
+        contents.push(LanguageModelResponseContentType::Reasoning {
+            content: thinking.clone(),
+            extensions: Extensions::default(),
+        });
+    }
+    if !message.content.is_empty() {
+        contents.push(LanguageModelResponseContentType::Text(
+            message.content.clone(),
+        ));
+    }
+    for call in message.tool_calls.iter().flatten() {
+        contents.push(LanguageModelResponseContentType::ToolCall(tool_call_info(
+            call,
+        )));
+    }
+    contents
+}
+
+#[async_trait]
+impl<M: ModelName> LanguageModel for Ollama<M> {
+    /// Returns the name of the model.
+    fn name(&self) -> String {
+        self.options.model.clone()
+    }
+
+    fn pricing(&self) -> Option<ModelPricing> {
+        M::PRICING
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        M::CONTEXT_WINDOW
+    }
+
+    fn max_output_tokens(&self) -> Option<usize> {
+        M::MAX_OUTPUT_TOKENS
+    }
+
+    fn capabilities(&self) -> CapabilitySet {
+        M::CAPABILITIES
+    }
+
+    /// Generates text using the native `/api/chat` endpoint.
+    async fn generate_text(
+        &mut self,
+        mut options: LanguageModelOptions,
+    ) -> Result<LanguageModelResponse> {
+        resolve_api_key(
+            &self.settings.credential_provider,
+            &mut self.settings.api_key,
+        )
+        .await?;
+        options.resolve_constrained_output("Ollama", |output| {
+            *output == ConstrainedOutput::JsonObject
+        })?;
+        options.validate_sampling(0.0..=2.0)?;
+        let additional_headers = options.headers.clone();
+        let additional_query = options.query.clone();
+        let max_retries = options.max_retries;
+        let mut options: OllamaOptions = options.into();
+        options.model = self.options.model.clone();
+        options.keep_alive = self.settings.keep_alive.clone();
+        self.options = options;
+
+        let response = self
+            .send(
+                self.settings.base_url.clone(),
+                additional_headers,
+                additional_query,
+                max_retries,
+            )
+            .await?;
+
+        Ok(LanguageModelResponse {
+            contents: contents(&response.message),
+            usage: Some(Usage::from(&response)),
+            id: None,
+            logprobs: None,
+            candidates: Vec::new(),
+            provider_metadata: None,
+            metadata: Some(ResponseMetadata {
+                model: Some(response.model.clone()),
+                ..Default::default()
+            }),
+        })
+    }
+
+    /// Streams text using the native `/api/chat` endpoint.
+    async fn stream_text(&mut self, mut options: LanguageModelOptions) -> Result<ProviderStream> {
+        resolve_api_key(
+            &self.settings.credential_provider,
+            &mut self.settings.api_key,
+        )
+        .await?;
+        options.resolve_constrained_output("Ollama", |output| {
+            *output == ConstrainedOutput::JsonObject
+        })?;
+        options.validate_sampling(0.0..=2.0)?;
+        let additional_headers = options.headers.clone();
+        let additional_query = options.query.clone();
+        let mut options: OllamaOptions = options.into();
+        options.model = self.options.model.clone();
+        options.keep_alive = self.settings.keep_alive.clone();
+        options.stream = true;
+        self.options = options;
+
+        let response = self
+            .send_and_stream_ndjson(additional_headers, additional_query)
+            .await?;
+
+        // The accumulated assistant message
+        let stream = response.scan(OllamaMessage::default(), |message, event| {
+            futures::future::ready(Some(match event {
+                Ok(OllamaStreamEvent::Chunk(chunk)) => {
+                    let mut chunks = Vec::new();
+                    let usage = Usage::from(&chunk);
+
+                    if let Some(thinking) = chunk.message.thinking.filter(|t| !t.is_empty()) {
+                        message
+                            .thinking
+                            .get_or_insert_with(String::new)
+                            .push_str(&thinking);
+                        chunks.push(LanguageModelStreamChunk::Delta(
+                            LanguageModelStreamChunkType::Reasoning(thinking),
+                        ));
+                    }
+
+                    if !chunk.message.content.is_empty() {
+                        message.content.push_str(&chunk.message.content);
+                        chunks.push(LanguageModelStreamChunk::Delta(
+                            LanguageModelStreamChunkType::Text(chunk.message.content),
+                        ));
+                    }
+
+                    for call in chunk.message.tool_calls.into_iter().flatten() {
+                        message.tool_calls.get_or_insert_with(Vec::new).push(call);
+                    }
+
+                    if chunk.done {
+                        for content in contents(message) {
+                            if let LanguageModelResponseContentType::ToolCall(call) = &content {
+                                chunks.push(LanguageModelStreamChunk::Delta(
+                                    LanguageModelStreamChunkType::ToolCall(ToolCallDelta {
+                                        id: call.tool.id.clone(),
+                                        name: call.tool.name.clone(),
+                                        delta: call.input.to_string(),
+                                    }),
+                                ));
+                            }
+                            chunks.push(LanguageModelStreamChunk::Done(AssistantMessage {
+                                content,
+                                usage: Some(usage.clone()),
+                            }));
+                        }
+                    }
+
+                    Ok(chunks)
+                }
+                Ok(OllamaStreamEvent::Error { error }) => {
+                    Ok(vec![LanguageModelStreamChunk::Delta(
+                        LanguageModelStreamChunkType::Failed(error),
+                    )])
+                }
+                Err(e) => Err(e),
+            }))
+        });
+
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DynamicModel;
+    use crate::core::client::test_server::{TestResponse, serve};
+    use crate::core::client::vcr::Cassette;
+
+    /// Serves a chat response answering "Hi" on a local port, and returns its
+    /// address along with the received request.
+    async fn serve_chat() -> (String, tokio::sync::mpsc::UnboundedReceiver<String>) {
+        serve(vec![TestResponse::json(
+            r#"{"message":{"role":"assistant","content":"Hi"},"done":true}"#,
+        )])
+        .await
+    }
+
+    async fn collect(stream: ProviderStream) -> Vec<String> {
+        stream
+            .map(|chunks| chunks.unwrap())
+            .concat()
+            .await
+            .into_iter()
+            .map(|chunk| match chunk {
+                LanguageModelStreamChunk::Delta(LanguageModelStreamChunkType::Reasoning(r)) => {
+                    format!("reasoning:{r}")
+                }
+                LanguageModelStreamChunk::Delta(LanguageModelStreamChunkType::Text(t)) => {
+                    format!("text:{t}")
+                }
+                LanguageModelStreamChunk::Done(message) => format!(
+                    "done:{:?}:{:?}",
+                    message.content,
+                    message.usage.unwrap().output_tokens
+                ),
+                other => format!("{other:?}"),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_stream_text_reads_ndjson_and_replays_it() {
+        let (base_url, _requests) = serve(vec![TestResponse::Body(
+            "application/x-ndjson".to_string(),
+            concat!(
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"\",\"thinking\":\"Hmm\"},\"done\":false}\n",
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"Hel\"},\"done\":false}\n",
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"lo\"},\"done\":false}\n",
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true,\"prompt_eval_count\":5,\"eval_count\":3}\n",
+            )
+            .as_bytes()
+            .to_vec(),
+        )])
+        .await;
+        let mut model = Ollama::<DynamicModel>::builder()
+            .model_name("llama3.2")
+            .base_url(base_url)
+            .build()
+            .unwrap();
+
+        let path = std::env::temp_dir().join(format!("ollama_{}.json", uuid::Uuid::new_v4()));
+        let recorder = Cassette::record(&path);
+        let stream = recorder
+            .run(model.stream_text(LanguageModelOptions::default()))
+            .await
+            .unwrap();
+        let chunks = collect(stream).await;
+        assert_eq!(chunks.len(), 5);
+        assert_eq!(chunks[..3], ["reasoning:Hmm", "text:Hel", "text:lo"]);
+        assert!(chunks[3].starts_with("done:Reasoning { content: \"Hmm\""));
+        assert_eq!(chunks[4], "done:Text(\"Hello\"):Some(3)");
+
+        // The server is gone, so the stream can only come from the cassette
+        let player = Cassette::from_json(&recorder.to_json().unwrap()).unwrap();
+        let stream = player
+            .run(model.stream_text(LanguageModelOptions::default()))
+            .await
+            .unwrap();
+        assert_eq!(collect(stream).await, chunks);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_sends_extra_headers_and_query() {
+        let (base_url, mut requests) = serve_chat().await;
+        let model = Ollama::<DynamicModel>::builder()
+            .model_name("llama3.2")
+            .base_url(base_url)
+            .build()
+            .unwrap();
+
+        let response = crate::core::LanguageModelRequest::builder()
+            .model(model)
+            .prompt("Hello")
+            .extra_headers([("x-tenant-id", "acme")])
+            .extra_query([("tenant", "acme")])
+            .build()
+            .generate_text()
+            .await
+            .unwrap();
+
+        assert_eq!(response.text().as_deref(), Some("Hi"));
+        let request = requests.recv().await.unwrap();
+        assert!(request.starts_with("POST /api/chat?tenant=acme "));
+        assert!(request.contains("x-tenant-id: acme"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_resolves_api_key_of_credential_provider() {
+        let (base_url, mut requests) = serve_chat().await;
+        let mut model = Ollama::<DynamicModel>::builder()
+            .model_name("llama3.2")
+            .base_url(base_url)
+            .credential_provider(crate::core::credentials::from_fn(|| async {
+                Ok("sk-rotated".to_string())
+            }))
+            .build()
+            .unwrap();
+
+        model
+            .generate_text(LanguageModelOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(model.settings.api_key, "sk-rotated");
+        assert!(
+            requests
+                .recv()
+                .await
+                .unwrap()
+                .contains("authorization: Bearer sk-rotated")
+        );
+    }
+}
@@ -0,0 +1,378 @@
+//! This module provides the native Ollama provider, which implements the `LanguageModel`
+//! and `Provider` traits for interacting with a local or remote Ollama server.
+//!
+//! Unlike the OpenAI-compatible endpoint, the native API supports `keep_alive`,
+//! and the provider exposes the model management endpoints of the server.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "ollama")]
+//!# {
+//! use aisdk::core::{DynamicModel, LanguageModelRequest};
+//! use aisdk::providers::Ollama;
+//!
+//! # async fn run() -> aisdk::Result<()> {
+//! let ollama = Ollama::<DynamicModel>::builder()
+//!     .model_name("llama3.2")
+//!     .keep_alive("10m")
+//!     .build()?;
+//!
+//! // Download the model if it is not available locally
+//! if !ollama.list_models().await?.iter().any(|m| m.name.starts_with("llama3.2")) {
+//!     ollama.pull_model("llama3.2").await?;
+//! }
+//!
+//! let response = LanguageModelRequest::builder()
+//!     .model(ollama)
+//!     .prompt("Why is the sky blue?")
+//!     .build()
+//!     .generate_text()
+//!     .await?;
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+pub mod capabilities;
+/// Client implementation for the native Ollama API.
+pub mod client;
+/// Conversion utilities for Ollama types.
+pub mod conversions;
+pub mod language_model;
+pub mod settings;
+
+use crate::core::DynamicModel;
+use crate::core::capabilities::ModelName;
+use crate::core::client::HttpSettings;
+use crate::core::credentials::{CredentialProvider, Credentials};
+use crate::core::utils::validate_base_url;
+use crate::error::{Error, Result};
+use crate::providers::ollama::client::{
+    OllamaGenerateRequest, OllamaGenerateResponse, OllamaListModelsResponse, OllamaModelInfo,
+    OllamaOptions, OllamaPullRequest, OllamaPullResponse,
+};
+use crate::providers::ollama::settings::OllamaProviderSettings;
+use serde::Serialize;
+
+/// The Ollama provider.
+#[derive(Debug, Serialize, Clone)]
+pub struct Ollama<M: ModelName> {
+    /// Configuration settings for the Ollama provider.
+    pub settings: OllamaProviderSettings,
+    options: OllamaOptions,
+    _phantom: std::marker::PhantomData<M>,
+}
+
+impl<M: ModelName> Ollama<M> {
+    /// Ollama provider setting builder.
+    pub fn builder() -> OllamaBuilder<M> {
+        OllamaBuilder::default()
+    }
+
+    /// Creates an Ollama provider builder configured from the environment.
+    ///
+    /// Reads the base URL from `OLLAMA_HOST` and the API key of Ollama Cloud from
+    /// `OLLAMA_API_KEY`.
+    /// Unset variables keep the defaults.
+    pub fn from_env() -> OllamaBuilder<M> {
+        Self::builder()
+    }
+
+    /// Lists the models available on the server, using the `/api/tags` endpoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if the server can not be reached or the request fails.
+    pub async fn list_models(&self) -> Result<Vec<OllamaModelInfo>> {
+        let response: OllamaListModelsResponse = self
+            .request(reqwest::Method::GET, "/api/tags", None)
+            .await?;
+        Ok(response.models)
+    }
+
+    /// Downloads a model to the server, using the `/api/pull` endpoint.
+    ///
+    /// Waits until the download has finished, which can take a while for large models.
+    ///
+    /// # Parameters
+    ///
+    /// * `model` - The name of the model to download (e.g., "llama3.2").
+    ///
+    /// # Returns
+    ///
+    /// The final status reported by the server, usually "success".
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if the model does not exist or the download fails.
+    pub async fn pull_model(&self, model: impl Into<String>) -> Result<String> {
+        let request = OllamaPullRequest {
+            model: model.into(),
+            stream: false,
+        };
+        let response: OllamaPullResponse = self
+            .request(
+                reqwest::Method::POST,
+                "/api/pull",
+                serde_json::to_value(request).ok(),
+            )
+            .await?;
+        Ok(response.status)
+    }
+
+    /// Generates a raw completion of a prompt, using the `/api/generate` endpoint.
+    ///
+    /// The prompt is passed to the model without a chat template conversation, which
+    /// is useful for base models and code completion.
+    ///
+    /// # Parameters
+    ///
+    /// * `prompt` - The prompt to complete.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if the request fails.
+    pub async fn complete(&self, prompt: impl Into<String>) -> Result<String> {
+        let request = OllamaGenerateRequest {
+            model: self.options.model.clone(),
+            prompt: prompt.into(),
+            stream: false,
+            keep_alive: self.settings.keep_alive.clone(),
+        };
+        let response: OllamaGenerateResponse = self
+            .request(
+                reqwest::Method::POST,
+                "/api/generate",
+                serde_json::to_value(request).ok(),
+            )
+            .await?;
+        Ok(response.response)
+    }
+}
+
+impl Ollama<DynamicModel> {
+    /// Creates an Ollama provider with a dynamic model name using default settings.
+    ///
+    /// This allows you to use any model available on the server, since local models
+    /// are not known ahead of time.
+    ///
+    /// **WARNING**: when using `DynamicModel`, model capabilities are not validated.
+    /// This means there is no compile-time guarantee that the model supports requested features.
+    ///
+    /// For custom configuration (base URL, keep alive, etc.), use the builder pattern:
+    /// `Ollama::<DynamicModel>::builder().model_name(...).base_url(...).build()`
+    ///
+    /// # Parameters
+    ///
+    /// * `model_name` - The Ollama model name (e.g., "llama3.2", "qwen3:8b")
+    ///
+    /// # Returns
+    ///
+    /// A configured `Ollama<DynamicModel>` provider instance with default settings.
+    pub fn model_name(name: impl Into<String>) -> Self {
+        let settings = OllamaProviderSettings::default();
+        let options = OllamaOptions::builder().model(name.into()).build().unwrap();
+
+        Ollama {
+            settings,
+            options,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<M: ModelName> Default for Ollama<M> {
+    /// Creates a new Ollama provider with default settings.
+    fn default() -> Self {
+        let settings = OllamaProviderSettings::default();
+        let options = OllamaOptions::builder()
+            .model(M::MODEL_NAME.to_string())
+            .build()
+            .unwrap();
+
+        Self {
+            settings,
+            options,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+/// Ollama Provider Builder
+pub struct OllamaBuilder<M: ModelName> {
+    settings: OllamaProviderSettings,
+    options: OllamaOptions,
+    _phantom: std::marker::PhantomData<M>,
+}
+
+impl<M: ModelName> Default for OllamaBuilder<M> {
+    /// Creates a new Ollama provider builder with default settings.
+    fn default() -> Self {
+        let settings = OllamaProviderSettings::default();
+        let options = OllamaOptions::builder()
+            .model(M::MODEL_NAME.to_string())
+            .build()
+            .unwrap();
+
+        Self {
+            settings,
+            options,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl OllamaBuilder<DynamicModel> {
+    /// Sets the model name from a string. e.g., "llama3.2", "qwen3:8b"
+    ///
+    /// **WARNING**: when using `DynamicModel`, model capabilities are not validated.
+    /// This means there is no compile-time guarantee that the model supports requested features.
+    ///
+    /// For compile-time model validation, use the constructor methods like `Ollama::llama3_2()`.
+    ///
+    /// # Parameters
+    ///
+    /// * `model_name` - The Ollama model name.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the model name set.
+    pub fn model_name(mut self, model_name: impl Into<String>) -> Self {
+        self.options.model = model_name.into();
+        self
+    }
+}
+
+impl<M: ModelName> OllamaBuilder<M> {
+    /// Sets the base URL of the Ollama server. Defaults to `OLLAMA_HOST` or
+    /// "http://localhost:11434".
+    ///
+    /// # Parameters
+    ///
+    /// * `base_url` - The base URL string for API requests.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the base URL set.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.settings.base_url = base_url.into();
+        self
+    }
+
+    /// Sets the API key sent as a bearer token, for servers behind an authenticating proxy.
+    ///
+    /// # Parameters
+    ///
+    /// * `api_key` - The API key string for authentication.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the API key set.
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.settings.api_key = api_key.into();
+        self
+    }
+
+    /// Sets the credentials resolving the API key before each request, to fetch
+    /// it from a secret manager or rotate it. Takes precedence over `api_key`.
+    ///
+    /// # Parameters
+    ///
+    /// * `credentials` - The source of the API key.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the credentials set.
+    pub fn credential_provider(mut self, credentials: impl CredentialProvider + 'static) -> Self {
+        self.settings.credential_provider = Some(Credentials::new(credentials));
+        self
+    }
+
+    /// Sets the HTTP client sending the requests, to share its pool of connections
+    /// or configure it. Requests share one pooled client by default.
+    ///
+    /// # Parameters
+    ///
+    /// * `client` - The `reqwest` client sending the requests.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the HTTP client set.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.settings.http_client = Some(client);
+        self
+    }
+
+    /// Sets the network settings of the HTTP client, such as a proxy or
+    /// certificate authorities. Ignored if an HTTP client is set.
+    ///
+    /// # Parameters
+    ///
+    /// * `http` - The network settings.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the network settings set.
+    pub fn http_settings(mut self, http: HttpSettings) -> Self {
+        self.settings.http = http;
+        self
+    }
+
+    /// Sets the name of the provider. Defaults to "ollama".
+    ///
+    /// # Parameters
+    ///
+    /// * `provider_name` - The provider name string.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the provider name set.
+    pub fn provider_name(mut self, provider_name: impl Into<String>) -> Self {
+        self.settings.provider_name = provider_name.into();
+        self
+    }
+
+    /// Sets how long the model stays loaded in memory after a request.
+    ///
+    /// # Parameters
+    ///
+    /// * `keep_alive` - A duration such as "5m" or "1h", "-1" to keep the model
+    ///   loaded indefinitely, or "0" to unload it right after the request.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the keep alive duration set.
+    pub fn keep_alive(mut self, keep_alive: impl Into<String>) -> Self {
+        self.settings.keep_alive = Some(keep_alive.into());
+        self
+    }
+
+    /// Builds the Ollama provider.
+    ///
+    /// Validates the configuration and creates the provider instance. An API key is
+    /// not required.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the configured `Ollama` provider or an `Error`.
+    pub fn build(self) -> std::result::Result<Ollama<M>, Error> {
+        let base_url = validate_base_url(&self.settings.base_url)?;
+
+        Ok(Ollama {
+            settings: OllamaProviderSettings {
+                base_url,
+                http_client: self
+                    .settings
+                    .http
+                    .resolve_client(self.settings.http_client.clone())?,
+                ..self.settings
+            },
+            options: self.options,
+            _phantom: std::marker::PhantomData,
+        })
+    }
+}
+
+// Re-exports for convenience
+pub use capabilities::*;
@@ -0,0 +1,62 @@
+//! Defines the settings for the Ollama provider.
+
+use crate::core::client::HttpSettings;
+use crate::core::credentials::Credentials;
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// Settings for the Ollama provider.
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+#[builder(setter(into), default)]
+pub struct OllamaProviderSettings {
+    /// The name of the provider.
+    pub provider_name: String,
+
+    /// The base URL of the Ollama server.
+    pub base_url: String,
+
+    /// The API key sent as a bearer token. Not needed for a local server.
+    pub api_key: String,
+
+    /// Credentials resolving the API key before each request, in place of
+    /// `api_key`.
+    #[serde(skip)]
+    pub credential_provider: Option<Credentials>,
+
+    /// The HTTP client sending the requests. Requests share one pooled client
+    /// if it is not set.
+    #[serde(skip)]
+    pub http_client: Option<reqwest::Client>,
+
+    /// Network settings of the HTTP client built for the provider when no
+    /// client is set, such as a proxy or certificate authorities.
+    #[serde(default)]
+    pub http: HttpSettings,
+
+    /// How long the model stays loaded after a request (e.g., "5m", "1h", or "0"
+    /// to unload it immediately). Uses the server default when not set.
+    pub keep_alive: Option<String>,
+}
+
+impl Default for OllamaProviderSettings {
+    /// Returns the default settings for the Ollama provider.
+    fn default() -> Self {
+        Self {
+            provider_name: "ollama".to_string(),
+            base_url: std::env::var("OLLAMA_HOST")
+                .unwrap_or_else(|_| "http://localhost:11434".to_string()),
+            api_key: std::env::var("OLLAMA_API_KEY").unwrap_or_default(),
+            credential_provider: None,
+            http_client: None,
+            http: HttpSettings::default(),
+            keep_alive: None,
+        }
+    }
+}
+
+impl OllamaProviderSettings {
+    /// Creates a new builder for `OllamaProviderSettings`.
+    pub fn builder() -> OllamaProviderSettingsBuilder {
+        OllamaProviderSettingsBuilder::default()
+    }
+}
@@ -0,0 +1,252 @@
+//! Background mode of the Responses API.
+//!
+//! Background responses are queued and run without an open connection. They
+//! are identified by their ID, which can be used to retrieve them later, even
+//! from another process.
+
+use crate::core::client::{LanguageModelClient, send_json};
+use crate::core::language_model::{LanguageModelOptions, LanguageModelResponse};
+use crate::core::utils::join_url;
+use crate::error::{Error, Result};
+use crate::providers::openai::client::{OpenAILanguageModelOptions, OpenAIResponse};
+use crate::providers::openai::{ModelName, OpenAI};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The first delay between two polls of a background response.
+const INITIAL_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The longest delay between two polls of a background response.
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The status of a response of the Responses API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpenAIResponseStatus {
+    /// The response is waiting to be processed.
+    Queued,
+    /// The model is generating the response.
+    InProgress,
+    /// The response finished successfully.
+    Completed,
+    /// The response finished before the model was done, e.g. at the output token limit.
+    Incomplete,
+    /// The response failed.
+    Failed,
+    /// The response was cancelled.
+    Cancelled,
+}
+
+impl OpenAIResponseStatus {
+    /// Returns whether the response is still waiting or running.
+    pub fn is_pending(&self) -> bool {
+        matches!(self, Self::Queued | Self::InProgress)
+    }
+}
+
+/// A snapshot of a background response.
+#[derive(Debug, Clone)]
+pub struct OpenAIBackgroundResponse {
+    /// The ID of the response.
+    pub id: String,
+    /// The status of the response.
+    pub status: OpenAIResponseStatus,
+    /// The output of the response, once it has completed.
+    pub response: Option<LanguageModelResponse>,
+}
+
+impl TryFrom<OpenAIResponse> for OpenAIBackgroundResponse {
+    type Error = Error;
+
+    fn try_from(response: OpenAIResponse) -> Result<Self> {
+        let id = response
+            .id
+            .clone()
+            .ok_or_else(|| Error::MissingField("id".to_string()))?;
+        let status = response
+            .status
+            .as_deref()
+            .map(|s| serde_json::from_value(serde_json::Value::String(s.to_string())))
+            .transpose()
+            .map_err(|e| Error::Other(format!("Unknown response status: {e}")))?
+            .unwrap_or(OpenAIResponseStatus::Completed);
+
+        let response = match status {
+            OpenAIResponseStatus::Completed | OpenAIResponseStatus::Incomplete => {
+                Some(response.try_into()?)
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            id,
+            status,
+            response,
+        })
+    }
+}
+
+/// Returns whether a response has been created in the background and not finished yet.
+pub(crate) fn is_pending(response: &OpenAIResponse) -> bool {
+    matches!(response.status.as_deref(), Some("queued" | "in_progress"))
+}
+
+impl<M: ModelName> OpenAI<M> {
+    /// Creates a response in background mode, without waiting for it to finish.
+    ///
+    /// # Parameters
+    ///
+    /// * `options` - The options of the request.
+    ///
+    /// # Returns
+    ///
+    /// The queued response, whose ID can be passed to
+    /// [`retrieve_response`](Self::retrieve_response) or
+    /// [`wait_for_response`](Self::wait_for_response).
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if the request fails.
+    pub async fn create_background_response(
+        &mut self,
+        options: LanguageModelOptions,
+    ) -> Result<OpenAIBackgroundResponse> {
+        let additional_headers = options.headers.clone();
+        let additional_query = options.query.clone();
+        let max_retries = options.max_retries;
+        let mut options: OpenAILanguageModelOptions = options.into();
+
+        options.model = self.lm_options.model.clone();
+        options.background = Some(true);
+
+        let background = self.lm_options.background;
+        self.lm_options = options;
+        let response: Result<OpenAIResponse> = self
+            .send(
+                &self.settings.base_url,
+                additional_headers,
+                additional_query,
+                max_retries,
+            )
+            .await;
+        self.lm_options.background = background;
+
+        response?.try_into()
+    }
+
+    /// Retrieves the current state of a response, using its ID.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - The ID of the response (e.g., "resp_123").
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if the response does not exist or the request fails.
+    pub async fn retrieve_response(&self, id: impl AsRef<str>) -> Result<OpenAIBackgroundResponse> {
+        self.get_response(id.as_ref()).await?.try_into()
+    }
+
+    /// Waits for a background response to finish, polling it with an increasing interval.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - The ID of the response (e.g., "resp_123").
+    ///
+    /// # Returns
+    ///
+    /// The output of the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if the response failed or was cancelled, or if a request fails.
+    pub async fn wait_for_response(&self, id: impl AsRef<str>) -> Result<LanguageModelResponse> {
+        let mut interval = INITIAL_POLL_INTERVAL;
+        loop {
+            let response = self.get_response(id.as_ref()).await?;
+            if !is_pending(&response) {
+                return response.try_into();
+            }
+
+            crate::core::runtime::sleep(interval).await;
+            interval = (interval * 2).min(MAX_POLL_INTERVAL);
+        }
+    }
+
+    /// Sends a request to retrieve a response.
+    async fn get_response(&self, id: &str) -> Result<OpenAIResponse> {
+        let path = format!("{}/{id}", LanguageModelClient::path(self));
+        let url = join_url(self.settings.base_url.as_str(), &path)?;
+
+        send_json(
+            LanguageModelClient::http_client(self)
+                .get(url)
+                .headers(LanguageModelClient::headers(self)),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DynamicModel;
+    use crate::core::client::test_server::{TestResponse, serve};
+    use crate::core::language_model::{LanguageModel, LanguageModelResponseContentType};
+
+    fn model(base_url: String) -> OpenAI<DynamicModel> {
+        OpenAI::<DynamicModel>::builder()
+            .model_name("o3-pro")
+            .base_url(base_url)
+            .api_key("sk-test")
+            .background(true)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_polls_background_response() {
+        let (base_url, mut requests) = serve(vec![
+            TestResponse::json(r#"{"id":"resp_1","status":"queued","output":[]}"#),
+            TestResponse::json(r#"{"id":"resp_1","status":"in_progress","output":[]}"#),
+            TestResponse::json(r#"{"id":"resp_1","status":"completed","output":[{"type":"message","id":"msg_1","status":"completed","role":"assistant","content":[{"type":"output_text","text":"Done","annotations":[],"logprobs":[]}]}]}"#),
+        ])
+        .await;
+
+        let response = model(base_url)
+            .generate_text(LanguageModelOptions::default())
+            .await
+            .unwrap();
+        assert!(matches!(
+            &response.contents[..],
+            [LanguageModelResponseContentType::Text(text)] if text == "Done"
+        ));
+
+        let create = requests.recv().await.unwrap();
+        assert!(create.starts_with("POST /v1/responses "));
+        assert!(create.contains(r#""background":true"#));
+        for _ in 0..2 {
+            let poll = requests.recv().await.unwrap();
+            assert!(poll.starts_with("GET /v1/responses/resp_1 "));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_failed_background_response() {
+        let failed =
+            r#"{"id":"resp_2","status":"failed","error":{"code":"server_error","message":"Boom"}}"#;
+        let (base_url, _requests) =
+            serve(vec![TestResponse::json(failed), TestResponse::json(failed)]).await;
+        let model = model(base_url);
+
+        let response = model.retrieve_response("resp_2").await.unwrap();
+        assert_eq!(response.id, "resp_2");
+        assert_eq!(response.status, OpenAIResponseStatus::Failed);
+        assert!(response.response.is_none());
+
+        let error = model.wait_for_response("resp_2").await.unwrap_err();
+        assert!(
+            matches!(error, Error::ApiError { details, .. } if details == "server_error: Boom")
+        );
+    }
+}
@@ -0,0 +1,334 @@
+//! Batch API of the OpenAI provider.
+//!
+//! The requests of a batch are uploaded as a JSON Lines file of Responses API
+//! requests, and their results are downloaded from the output and error files of
+//! the batch.
+
+use crate::core::batch::{
+    BatchInfo, BatchLanguageModel, BatchRequest, BatchRequestCounts, BatchResult,
+    BatchResultStream, BatchStatus,
+};
+use crate::core::client::{response_lines, send_json, send_request, status_error};
+use crate::core::files::{FileProvider, FilePurpose};
+use crate::core::language_model::LanguageModelResponse;
+use crate::error::{Error, Result};
+use crate::providers::openai::client::{OpenAILanguageModelOptions, OpenAIResponse};
+use crate::providers::openai::{ModelName, OpenAI};
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+/// The endpoint of the requests of a batch.
+const BATCH_ENDPOINT: &str = "/v1/responses";
+
+/// A line of the input file of a batch.
+#[derive(Debug, Serialize)]
+struct OpenAIBatchInputLine<'a> {
+    custom_id: &'a str,
+    method: &'static str,
+    url: &'static str,
+    body: OpenAILanguageModelOptions,
+}
+
+/// The request creating a batch.
+#[derive(Debug, Serialize)]
+struct OpenAICreateBatchRequest<'a> {
+    input_file_id: &'a str,
+    endpoint: &'static str,
+    completion_window: &'static str,
+}
+
+/// The number of requests of a batch, by state.
+#[derive(Debug, Default, Deserialize)]
+struct OpenAIBatchRequestCounts {
+    #[serde(default)]
+    total: usize,
+    #[serde(default)]
+    completed: usize,
+    #[serde(default)]
+    failed: usize,
+}
+
+/// A batch of the Batch API.
+#[derive(Debug, Deserialize)]
+struct OpenAIBatch {
+    id: String,
+    status: BatchStatus,
+    #[serde(default)]
+    output_file_id: Option<String>,
+    #[serde(default)]
+    error_file_id: Option<String>,
+    #[serde(default)]
+    request_counts: Option<OpenAIBatchRequestCounts>,
+}
+
+impl From<OpenAIBatch> for BatchInfo {
+    fn from(batch: OpenAIBatch) -> Self {
+        let counts = batch.request_counts.unwrap_or_default();
+        Self {
+            id: batch.id,
+            status: batch.status,
+            request_counts: BatchRequestCounts {
+                total: counts.total,
+                succeeded: counts.completed,
+                failed: counts.failed,
+            },
+        }
+    }
+}
+
+/// The response of a request of a batch.
+#[derive(Debug, Deserialize)]
+struct OpenAIBatchOutputResponse {
+    status_code: u16,
+    body: serde_json::Value,
+}
+
+/// The error of a request of a batch that was not sent.
+#[derive(Debug, Deserialize)]
+struct OpenAIBatchOutputError {
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+/// A line of the output or error file of a batch.
+#[derive(Debug, Deserialize)]
+struct OpenAIBatchOutputLine {
+    custom_id: String,
+    #[serde(default)]
+    response: Option<OpenAIBatchOutputResponse>,
+    #[serde(default)]
+    error: Option<OpenAIBatchOutputError>,
+}
+
+impl From<OpenAIBatchOutputLine> for BatchResult {
+    fn from(line: OpenAIBatchOutputLine) -> Self {
+        let response = match (line.response, line.error) {
+            (Some(response), _) => {
+                let status = reqwest::StatusCode::from_u16(response.status_code)
+                    .unwrap_or(reqwest::StatusCode::INTERNAL_SERVER_ERROR);
+                if status.is_success() {
+                    serde_json::from_value::<OpenAIResponse>(response.body)
+                        .map_err(|e| Error::ApiError {
+                            status_code: Some(status),
+                            details: format!("Failed to parse response: {e}"),
+                        })
+                        .and_then(LanguageModelResponse::try_from)
+                } else {
+                    Err(status_error(
+                        status,
+                        &reqwest::header::HeaderMap::new(),
+                        response.body.to_string(),
+                    ))
+                }
+            }
+            (None, Some(error)) => Err(Error::ApiError {
+                status_code: None,
+                details: format!(
+                    "{}: {}",
+                    error.code.unwrap_or_default(),
+                    error.message.unwrap_or_default()
+                ),
+            }),
+            (None, None) => Err(Error::MissingField("response".to_string())),
+        };
+
+        Self {
+            custom_id: line.custom_id,
+            response,
+        }
+    }
+}
+
+impl<M: ModelName> OpenAI<M> {
+    /// Streams the lines of a file of a batch, parsed as results.
+    async fn file_results(&self, file_id: &str) -> Result<BatchResultStream> {
+        let request =
+            self.api_request(reqwest::Method::GET, &format!("/files/{file_id}/content"))?;
+        let lines = response_lines(send_request(request).await?);
+
+        Ok(Box::pin(lines.map(|line| {
+            let line: OpenAIBatchOutputLine =
+                serde_json::from_str(&line?).map_err(|e| Error::ApiError {
+                    status_code: None,
+                    details: format!("Failed to parse batch result: {e}"),
+                })?;
+            Ok(line.into())
+        })))
+    }
+}
+
+#[async_trait]
+impl<M: ModelName> BatchLanguageModel for OpenAI<M> {
+    async fn create_batch(&mut self, requests: Vec<BatchRequest>) -> Result<BatchInfo> {
+        let mut input = Vec::new();
+        for request in requests {
+            let mut body: OpenAILanguageModelOptions = request.options.into();
+            body.model = self.lm_options.model.clone();
+
+            let line = OpenAIBatchInputLine {
+                custom_id: &request.custom_id,
+                method: "POST",
+                url: BATCH_ENDPOINT,
+                body,
+            };
+            serde_json::to_writer(&mut input, &line)
+                .map_err(|e| Error::Other(format!("Failed to serialize request: {e}")))?;
+            input.push(b'\n');
+        }
+
+        let file = self
+            .upload_file(input, "batch.jsonl", FilePurpose::Batch)
+            .await?;
+
+        let request =
+            self.api_request(reqwest::Method::POST, "/batches")?
+                .json(&OpenAICreateBatchRequest {
+                    input_file_id: &file.id,
+                    endpoint: BATCH_ENDPOINT,
+                    completion_window: "24h",
+                });
+        let batch: OpenAIBatch = send_json(request).await?;
+        Ok(batch.into())
+    }
+
+    async fn retrieve_batch(&self, id: &str) -> Result<BatchInfo> {
+        let request = self.api_request(reqwest::Method::GET, &format!("/batches/{id}"))?;
+        let batch: OpenAIBatch = send_json(request).await?;
+        Ok(batch.into())
+    }
+
+    async fn cancel_batch(&self, id: &str) -> Result<BatchInfo> {
+        let request = self.api_request(reqwest::Method::POST, &format!("/batches/{id}/cancel"))?;
+        let batch: OpenAIBatch = send_json(request).await?;
+        Ok(batch.into())
+    }
+
+    async fn batch_results(&self, id: &str) -> Result<BatchResultStream> {
+        let request = self.api_request(reqwest::Method::GET, &format!("/batches/{id}"))?;
+        let batch: OpenAIBatch = send_json(request).await?;
+        if batch.status.is_pending() {
+            return Err(Error::InvalidInput(format!(
+                "Batch {id} is still being processed"
+            )));
+        }
+
+        // Successful requests are in the output file, failed ones in the error file
+        let mut streams = Vec::new();
+        for file_id in [batch.output_file_id, batch.error_file_id]
+            .into_iter()
+            .flatten()
+        {
+            streams.push(self.file_results(&file_id).await?);
+        }
+        Ok(Box::pin(futures::stream::iter(streams).flatten()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::batch::Batch;
+    use crate::core::client::test_server::{TestResponse, serve};
+    use crate::core::{DynamicModel, LanguageModelRequest};
+
+    #[tokio::test]
+    async fn test_batch_uploads_requests_and_streams_results() {
+        let (base_url, mut requests) = serve(vec![
+            TestResponse::json(r#"{"id":"file-in","object":"file","purpose":"batch"}"#),
+            TestResponse::json(r#"{"id":"batch_1","status":"validating","request_counts":{"total":0,"completed":0,"failed":0}}"#),
+            TestResponse::json(r#"{"id":"batch_1","status":"completed","output_file_id":"file-out","error_file_id":"file-err","request_counts":{"total":2,"completed":1,"failed":1}}"#),
+            TestResponse::json(r#"{"id":"batch_1","status":"completed","output_file_id":"file-out","error_file_id":"file-err"}"#),
+            TestResponse::json(concat!(
+                r#"{"id":"req_1","custom_id":"sky","response":{"status_code":200,"body":{"id":"resp_1","status":"completed","output":[{"type":"message","id":"msg_1","status":"completed","role":"assistant","content":[{"type":"output_text","text":"Rayleigh scattering","annotations":[],"logprobs":[]}]}]}},"error":null}"#,
+                "\n"
+            )),
+            TestResponse::json(r#"{"id":"req_2","custom_id":"sea","response":{"status_code":400,"body":{"error":{"message":"Invalid prompt","type":"invalid_request_error"}}},"error":null}"#),
+        ])
+        .await;
+
+        let model = || {
+            OpenAI::<DynamicModel>::builder()
+                .model_name("gpt-5")
+                .base_url(base_url.clone())
+                .api_key("test")
+                .build()
+                .unwrap()
+        };
+        let request = |prompt: &str| {
+            LanguageModelRequest::builder()
+                .model(model())
+                .prompt(prompt)
+                .build()
+        };
+
+        let mut batch = Batch::new(model())
+            .add("sky", request("Why is the sky blue?"))
+            .add("sea", request("Why is the sea salty?"))
+            .submit()
+            .await
+            .unwrap();
+        assert_eq!(batch.info().id, "batch_1");
+
+        let upload = requests.recv().await.unwrap();
+        assert!(upload.starts_with("POST /v1/files "));
+        assert!(upload.contains("name=\"purpose\"\r\n\r\nbatch\r\n"));
+        let line = upload
+            .lines()
+            .find(|l| l.contains(r#""custom_id":"sky""#))
+            .unwrap();
+        let line: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert_eq!(line["url"], "/v1/responses");
+        assert_eq!(line["body"]["model"], "gpt-5");
+
+        let create = requests.recv().await.unwrap();
+        assert!(create.starts_with("POST /v1/batches "));
+        assert!(create.contains(r#""input_file_id":"file-in","endpoint":"/v1/responses""#));
+
+        let info = batch.refresh().await.unwrap();
+        assert_eq!(info.status, BatchStatus::Completed);
+        assert_eq!(
+            info.request_counts,
+            BatchRequestCounts {
+                total: 2,
+                succeeded: 1,
+                failed: 1
+            }
+        );
+
+        let results: Vec<_> = batch.results().await.unwrap().collect().await;
+        assert_eq!(results.len(), 2);
+
+        let (custom_id, response) = &results[0];
+        assert_eq!(custom_id, "sky");
+        let response = response.as_ref().unwrap();
+        assert_eq!(response.text().as_deref(), Some("Rayleigh scattering"));
+        assert!(matches!(
+            response.options.messages.first().map(|m| &m.message),
+            Some(crate::core::Message::User(_))
+        ));
+
+        let (custom_id, response) = &results[1];
+        assert_eq!(custom_id, "sea");
+        assert!(response.is_err());
+
+        for path in ["/v1/batches/batch_1", "/v1/batches/batch_1"] {
+            assert!(
+                requests
+                    .recv()
+                    .await
+                    .unwrap()
+                    .starts_with(&format!("GET {path} "))
+            );
+        }
+        assert!(
+            requests
+                .recv()
+                .await
+                .unwrap()
+                .starts_with("GET /v1/files/file-out/content ")
+        );
+    }
+}
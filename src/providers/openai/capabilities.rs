@@ -32,19 +32,28 @@ model_capabilities! {
             model_name: "gpt-4.1",
             constructor_name: gpt_4_1,
             display_name: "GPT-4.1",
-            capabilities: [ImageInputSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 2.0, output: 8.0, cached: 0.5 },
+            context_window: 1047576,
+            max_output_tokens: 32768
         },
         Gpt41Mini {
             model_name: "gpt-4.1-mini",
             constructor_name: gpt_4_1_mini,
             display_name: "GPT-4.1 mini",
-            capabilities: [ImageInputSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 0.4, output: 1.6, cached: 0.1 },
+            context_window: 1047576,
+            max_output_tokens: 32768
         },
         Gpt41Nano {
             model_name: "gpt-4.1-nano",
             constructor_name: gpt_4_1_nano,
             display_name: "GPT-4.1 nano",
-            capabilities: [ImageInputSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 0.1, output: 0.4, cached: 0.025 },
+            context_window: 1047576,
+            max_output_tokens: 32768
         },
         Gpt4Turbo {
             model_name: "gpt-4-turbo",
@@ -56,7 +65,10 @@ model_capabilities! {
             model_name: "gpt-4o",
             constructor_name: gpt_4o,
             display_name: "GPT-4o",
-            capabilities: [ImageInputSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 2.5, output: 10.0, cached: 1.25 },
+            context_window: 128000,
+            max_output_tokens: 16384
         },
         Gpt4o20240513 {
             model_name: "gpt-4o-2024-05-13",
@@ -80,13 +92,19 @@ model_capabilities! {
             model_name: "gpt-4o-mini",
             constructor_name: gpt_4o_mini,
             display_name: "GPT-4o mini",
-            capabilities: [ImageInputSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 0.15, output: 0.6, cached: 0.075 },
+            context_window: 128000,
+            max_output_tokens: 16384
         },
         Gpt5 {
             model_name: "gpt-5",
             constructor_name: gpt_5,
             display_name: "GPT-5",
-            capabilities: [ImageInputSupport, ReasoningSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, ReasoningSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 1.25, output: 10.0, cached: 0.125 },
+            context_window: 400000,
+            max_output_tokens: 128000
         },
         Gpt51 {
             model_name: "gpt-5.1",
@@ -152,13 +170,19 @@ model_capabilities! {
             model_name: "gpt-5-mini",
             constructor_name: gpt_5_mini,
             display_name: "GPT-5 Mini",
-            capabilities: [ImageInputSupport, ReasoningSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, ReasoningSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 0.25, output: 2.0, cached: 0.025 },
+            context_window: 400000,
+            max_output_tokens: 128000
         },
         Gpt5Nano {
             model_name: "gpt-5-nano",
             constructor_name: gpt_5_nano,
             display_name: "GPT-5 Nano",
-            capabilities: [ImageInputSupport, ReasoningSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, ReasoningSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 0.05, output: 0.4, cached: 0.005 },
+            context_window: 400000,
+            max_output_tokens: 128000
         },
         Gpt5Pro {
             model_name: "gpt-5-pro",
@@ -194,7 +218,10 @@ model_capabilities! {
             model_name: "o3",
             constructor_name: o3,
             display_name: "o3",
-            capabilities: [ImageInputSupport, ReasoningSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, ReasoningSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 2.0, output: 8.0, cached: 0.5 },
+            context_window: 200000,
+            max_output_tokens: 100000
         },
         O3DeepResearch {
             model_name: "o3-deep-research",
@@ -218,7 +245,10 @@ model_capabilities! {
             model_name: "o4-mini",
             constructor_name: o4_mini,
             display_name: "o4-mini",
-            capabilities: [ImageInputSupport, ReasoningSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport]
+            capabilities: [ImageInputSupport, ReasoningSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport],
+            pricing: { input: 1.1, output: 4.4, cached: 0.275 },
+            context_window: 200000,
+            max_output_tokens: 100000
         },
         O4MiniDeepResearch {
             model_name: "o4-mini-deep-research",
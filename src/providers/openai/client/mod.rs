@@ -6,12 +6,26 @@ pub(crate) mod types;
 
 pub(crate) use types::*;
 
-use crate::core::client::{EmbeddingClient, LanguageModelClient};
+use crate::core::client::{EmbeddingClient, LanguageModelClient, shared_client, sse_error};
 use crate::error::Error;
+use crate::providers::openai::settings::OpenAIProviderSettings;
 use crate::providers::openai::{ModelName, OpenAI};
-use reqwest::header::CONTENT_TYPE;
+use reqwest::header::{CONTENT_TYPE, HeaderMap};
 use reqwest_eventsource::Event;
 
+/// Inserts the organization and project headers of the settings.
+fn insert_account_headers(settings: &OpenAIProviderSettings, headers: &mut HeaderMap) {
+    let account_headers = [
+        ("OpenAI-Organization", &settings.organization),
+        ("OpenAI-Project", &settings.project),
+    ];
+    for (name, value) in account_headers {
+        if let Some(value) = value.as_deref().and_then(|value| value.parse().ok()) {
+            headers.insert(name, value);
+        }
+    }
+}
+
 impl<M: ModelName> LanguageModelClient for OpenAI<M> {
     type Response = types::OpenAIResponse;
     type StreamEvent = types::OpenAiStreamEvent;
@@ -27,6 +41,13 @@ impl<M: ModelName> LanguageModelClient for OpenAI<M> {
         reqwest::Method::POST
     }
 
+    fn http_client(&self) -> reqwest::Client {
+        self.settings
+            .http_client
+            .clone()
+            .unwrap_or_else(shared_client)
+    }
+
     fn headers(&self) -> reqwest::header::HeaderMap {
         // Default headers
         let mut default_headers = reqwest::header::HeaderMap::new();
@@ -38,6 +59,7 @@ impl<M: ModelName> LanguageModelClient for OpenAI<M> {
                 .parse()
                 .unwrap(),
         );
+        insert_account_headers(&self.settings, &mut default_headers);
 
         default_headers
     }
@@ -72,17 +94,7 @@ impl<M: ModelName> LanguageModelClient for OpenAI<M> {
                         .unwrap_or(types::OpenAiStreamEvent::NotSupported(msg.data)))
                 }
             },
-            Err(e) => {
-                // Extract status code if it's an InvalidStatusCode error
-                let status_code = match &e {
-                    reqwest_eventsource::Error::InvalidStatusCode(status, _) => Some(*status),
-                    _ => None,
-                };
-                Err(Error::ApiError {
-                    status_code,
-                    details: e.to_string(),
-                })
-            }
+            Err(e) => Err(sse_error(e)),
         }
     }
 
@@ -104,6 +116,13 @@ impl<M: ModelName> EmbeddingClient for OpenAI<M> {
         reqwest::Method::POST
     }
 
+    fn http_client(&self) -> reqwest::Client {
+        self.settings
+            .http_client
+            .clone()
+            .unwrap_or_else(shared_client)
+    }
+
     fn headers(&self) -> reqwest::header::HeaderMap {
         // Default headers
         let mut default_headers = reqwest::header::HeaderMap::new();
@@ -115,6 +134,7 @@ impl<M: ModelName> EmbeddingClient for OpenAI<M> {
                 .parse()
                 .unwrap(),
         );
+        insert_account_headers(&self.settings, &mut default_headers);
 
         default_headers
     }
@@ -1,6 +1,7 @@
 use crate::error::Error;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Configuration options for OpenAI API requests.
 #[derive(Debug, Default, Clone, Serialize, Deserialize, Builder)]
@@ -30,6 +31,30 @@ pub(crate) struct OpenAILanguageModelOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default)]
     pub(crate) tools: Option<Vec<ToolParams>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    pub(crate) tool_choice: Option<ToolChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    pub(crate) background: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    pub(crate) previous_response_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    pub(crate) include: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    pub(crate) top_logprobs: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    pub(crate) store: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    pub(crate) metadata: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    pub(crate) user: Option<String>,
 }
 
 /// Response structure from the OpenAI API.
@@ -58,6 +83,8 @@ pub(crate) struct OpenAIResponse {
     pub parallel_tool_calls: Option<bool>,
     /// Previous response ID.
     pub previous_response_id: Option<String>,
+    /// Status of the response, such as "queued" for background responses.
+    pub status: Option<String>,
     /// Reasoning configuration.
     pub reasoning: Option<ReasoningConfig>,
     /// Text configuration.
@@ -66,6 +93,20 @@ pub(crate) struct OpenAIResponse {
     pub usage: Option<ResponseUsage>,
 }
 
+/// Whether and which tools the model must call.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub(crate) enum ToolChoice {
+    /// "auto", "none" or "required".
+    Mode(String),
+    /// A specific function.
+    Function {
+        #[serde(rename = "type")]
+        type_: String,
+        name: String,
+    },
+}
+
 impl OpenAILanguageModelOptions {
     pub(crate) fn builder() -> OpenAILanguageModelOptionsBuilder {
         OpenAILanguageModelOptionsBuilder::default()
@@ -209,6 +250,7 @@ pub(crate) struct TextConfig {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub(crate) enum TextResponseFormat {
     Text,
+    JsonObject,
     JsonSchema {
         name: String,
         schema: serde_json::Value,
@@ -306,12 +348,16 @@ pub(crate) enum MessageItem {
         status: Option<String>,
     },
     Reasoning {
+        #[serde(skip_serializing_if = "Option::is_none")]
         id: Option<String>,
         summary: Vec<ReasoningSummary>,
         #[serde(rename = "type")]
         type_: String, // always "reasoning"
+        #[serde(skip_serializing_if = "Option::is_none")]
         content: Option<Vec<ReasoningTextContent>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         encrypted_content: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         status: Option<String>,
     },
 }
@@ -345,18 +391,25 @@ pub(crate) enum ContentType {
     },
     InputImage {
         detail: ImageDetail,
+        #[serde(skip_serializing_if = "Option::is_none")]
         file_id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         image_url: Option<String>,
     },
     InputFile {
+        #[serde(skip_serializing_if = "Option::is_none")]
         file_data: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         filename: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         file_url: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         file_id: Option<String>,
     },
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
 pub(crate) enum ImageDetail {
     #[default]
     Auto,
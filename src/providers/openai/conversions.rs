@@ -1,21 +1,25 @@
 //! Helper functions and conversions for the OpenAI provider.
 
 use crate::core::embedding_model::EmbeddingModelOptions;
+use crate::core::language_model::logprobs::{TokenLogprob, TopLogprob};
 use crate::core::language_model::{
-    LanguageModelOptions, LanguageModelResponseContentType, ReasoningEffort, Usage,
+    ConstrainedOutput, LanguageModelOptions, LanguageModelResponse,
+    LanguageModelResponseContentType, ReasoningEffort, ResponseMetadata, Usage,
 };
 use crate::core::messages::Message;
-use crate::core::tools::Tool;
+use crate::core::schema::{SchemaDialect, make_strict, sanitize};
+use crate::core::tools::{Tool, ToolCallInfo, ToolChoice};
+use crate::error::{Error, Result};
+use crate::extensions::Extensions;
 use crate::providers::openai::client::{self, types};
+use crate::providers::openai::extensions::OpenAIItemMetadata;
 use schemars::Schema;
 use serde_json::Value;
 
 impl From<Tool> for types::ToolParams {
     fn from(value: Tool) -> Self {
         let mut params = value.input_schema.to_value();
-
-        // open ai requires 'additionalProperties' to be false
-        params["additionalProperties"] = Value::Bool(false);
+        let strict = value.strict.unwrap_or(true);
 
         // open ai requires 'properties' to be an object
         let properties = params.get("properties");
@@ -24,10 +28,15 @@ impl From<Tool> for types::ToolParams {
             params["properties"] = Value::Object(serde_json::Map::new());
         }
 
+        if strict {
+            sanitize(&mut params, SchemaDialect::OpenAIStrict);
+            make_strict(&mut params);
+        }
+
         types::ToolParams::Function {
             name: value.name,
             description: Some(value.description),
-            strict: true,
+            strict,
             parameters: params,
         }
     }
@@ -50,6 +59,19 @@ impl From<LanguageModelOptions> for client::OpenAILanguageModelOptions {
                 .collect()
         });
 
+        let tool_choice = tools
+            .as_ref()
+            .and(options.tool_choice)
+            .map(|choice| match choice {
+                ToolChoice::Tool(name) => types::ToolChoice::Function {
+                    type_: "function".to_string(),
+                    name,
+                },
+                ToolChoice::None => types::ToolChoice::Mode("none".to_string()),
+                ToolChoice::Required => types::ToolChoice::Mode("required".to_string()),
+                ToolChoice::Auto => types::ToolChoice::Mode("auto".to_string()),
+            });
+
         let reasoning = options
             .reasoning_effort
             .map(|reasoning| types::ReasoningConfig {
@@ -62,19 +84,30 @@ impl From<LanguageModelOptions> for client::OpenAILanguageModelOptions {
             input: Some(types::Input::InputItemList(items)),
             text: Some(types::TextConfig {
                 verbosity: None,
-                format: Some(
-                    options
+                format: Some(match options.constrained_output {
+                    Some(ConstrainedOutput::JsonObject) => types::TextResponseFormat::JsonObject,
+                    _ => options
                         .schema
                         .map(from_schema_to_response_format)
                         .unwrap_or(types::TextResponseFormat::Text),
-                ),
+                }),
             }),
             reasoning,
-            temperature: options.temperature.map(|t| t as f32 / 100.0),
+            temperature: options.temperature,
             max_output_tokens: options.max_output_tokens.map(|t| t as usize),
             stream: Some(false),
-            top_p: options.top_p.map(|t| t as f32 / 100.0),
+            top_p: options.top_p,
             tools,
+            tool_choice,
+            background: None,
+            previous_response_id: options.previous_response_id,
+            include: options
+                .logprobs
+                .map(|_| vec!["message.output_text.logprobs".to_string()]),
+            top_logprobs: options.logprobs,
+            store: options.store,
+            metadata: options.request_metadata,
+            user: options.user,
         }
     }
 }
@@ -113,7 +146,11 @@ impl From<Message> for Option<types::InputItem> {
                 }
                 LanguageModelResponseContentType::ToolCall(ref tool_info) => {
                     Some(types::InputItem::Item(types::MessageItem::FunctionCall {
-                        id: None,
+                        id: tool_info
+                            .extensions
+                            .get::<OpenAIItemMetadata>()
+                            .item_id
+                            .clone(),
                         status: None,
                         arguments: tool_info.input.to_string(),
                         call_id: tool_info.tool.id.clone(),
@@ -121,26 +158,59 @@ impl From<Message> for Option<types::InputItem> {
                         type_: "function_call".to_string(),
                     }))
                 }
-                LanguageModelResponseContentType::Reasoning { ref content, .. } => {
-                    Some(types::InputItem::Item(types::MessageItem::Reasoning {
-                        id: None,
-                        summary: vec![types::ReasoningSummary {
+                LanguageModelResponseContentType::Reasoning {
+                    ref content,
+                    ref extensions,
+                } => {
+                    // Reasoning is replayed by item ID, so reasoning without one,
+                    // e.g. from another provider, can not be sent back
+                    let metadata = extensions.get::<OpenAIItemMetadata>();
+                    let id = metadata.item_id.clone()?;
+                    let summary = (!content.is_empty())
+                        .then(|| types::ReasoningSummary {
                             type_: "summary_text".to_string(),
                             text: content.clone(),
-                        }],
+                        })
+                        .into_iter()
+                        .collect();
+
+                    Some(types::InputItem::Item(types::MessageItem::Reasoning {
+                        id: Some(id),
+                        summary,
                         type_: "reasoning".to_string(),
                         content: None,
-                        encrypted_content: None,
+                        encrypted_content: metadata.encrypted_content.clone(),
                         status: None,
                     }))
                 }
                 _ => None,
             },
-            Message::User(u) => Some(types::InputItem::Item(types::MessageItem::InputMessage {
-                content: vec![types::ContentType::InputText { text: u.content }],
-                role: types::Role::User,
-                type_: "message".to_string(),
-            })),
+            Message::User(u) => {
+                let files = u.files.into_iter().map(|file| {
+                    if file.is_image() {
+                        types::ContentType::InputImage {
+                            detail: types::ImageDetail::Auto,
+                            file_id: Some(file.id),
+                            image_url: None,
+                        }
+                    } else {
+                        types::ContentType::InputFile {
+                            file_data: None,
+                            filename: None,
+                            file_url: None,
+                            file_id: Some(file.id),
+                        }
+                    }
+                });
+                let content = std::iter::once(types::ContentType::InputText { text: u.content })
+                    .chain(files)
+                    .collect();
+                Some(types::InputItem::Item(types::MessageItem::InputMessage {
+                    content,
+                    role: types::Role::User,
+                    type_: "message".to_string(),
+                }))
+            }
             Message::System(s) => Some(types::InputItem::Item(types::MessageItem::InputMessage {
                 content: vec![types::ContentType::InputText { text: s.content }],
                 role: types::Role::System,
@@ -157,6 +227,136 @@ impl From<Message> for Option<types::InputItem> {
     }
 }
 
+/// Converts a reasoning item, keeping its ID and encrypted content for the next request.
+pub(crate) fn reasoning(
+    id: Option<String>,
+    summary: &[types::ReasoningSummary],
+    encrypted_content: Option<String>,
+) -> LanguageModelResponseContentType {
+    let extensions = Extensions::default();
+    {
+        let mut metadata = extensions.get_mut::<OpenAIItemMetadata>();
+        metadata.item_id = id;
+        metadata.encrypted_content = encrypted_content;
+    }
+
+    LanguageModelResponseContentType::Reasoning {
+        content: summary
+            .iter()
+            .map(|s| s.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        extensions,
+    }
+}
+
+/// Converts a function call item, keeping its item ID for the next request.
+pub(crate) fn function_call(
+    id: Option<String>,
+    call_id: String,
+    name: String,
+    arguments: &str,
+) -> ToolCallInfo {
+    let mut tool_info = ToolCallInfo::new(name);
+    tool_info.id(call_id);
+    tool_info.input(serde_json::from_str(arguments).unwrap_or_default());
+    tool_info.extensions.get_mut::<OpenAIItemMetadata>().item_id = id;
+    tool_info
+}
+
+impl From<types::LogProbs> for TokenLogprob {
+    fn from(logprob: types::LogProbs) -> Self {
+        Self {
+            token: logprob.token,
+            logprob: logprob.logprob,
+            top_logprobs: logprob
+                .top_logprobs
+                .into_iter()
+                .map(|top| TopLogprob {
+                    token: top.token,
+                    logprob: top.logprob,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<client::OpenAIResponse> for LanguageModelResponse {
+    type Error = Error;
+
+    fn try_from(response: client::OpenAIResponse) -> Result<Self> {
+        match response.status.as_deref() {
+            Some("failed") => {
+                let details = response
+                    .error
+                    .map(|e| format!("{}: {}", e.code, e.message))
+                    .unwrap_or_else(|| "Response failed".to_string());
+                return Err(Error::ApiError {
+                    status_code: None,
+                    details,
+                });
+            }
+            Some("cancelled") => {
+                return Err(Error::ApiError {
+                    status_code: None,
+                    details: "Response was cancelled".to_string(),
+                });
+            }
+            _ => {}
+        }
+
+        let mut contents = Vec::new();
+        let mut token_logprobs: Option<Vec<TokenLogprob>> = None;
+        for out in response.output.unwrap_or_default() {
+            match out {
+                types::MessageItem::OutputMessage { content, .. } => {
+                    for c in content {
+                        if let types::OutputContent::OutputText { text, logprobs, .. } = c {
+                            if !logprobs.is_empty() {
+                                token_logprobs
+                                    .get_or_insert_default()
+                                    .extend(logprobs.into_iter().map(TokenLogprob::from));
+                            }
+                            contents.push(LanguageModelResponseContentType::new(text))
+                        }
+                    }
+                }
+                types::MessageItem::FunctionCall {
+                    arguments,
+                    name,
+                    call_id,
+                    id,
+                    ..
+                } => {
+                    contents.push(LanguageModelResponseContentType::ToolCall(function_call(
+                        id, call_id, name, &arguments,
+                    )));
+                }
+                types::MessageItem::Reasoning {
+                    id,
+                    summary,
+                    encrypted_content,
+                    ..
+                } => contents.push(reasoning(id, &summary, encrypted_content)),
+                _ => (),
+            }
+        }
+
+        Ok(LanguageModelResponse {
+            contents,
+            usage: response.usage.map(|usage| usage.into()),
+            id: response.id,
+            logprobs: token_logprobs,
+            candidates: Vec::new(),
+            provider_metadata: None,
+            metadata: Some(ResponseMetadata {
+                model: response.model,
+                ..Default::default()
+            }),
+        })
+    }
+}
+
 impl From<types::ResponseUsage> for Usage {
     fn from(value: types::ResponseUsage) -> Self {
         Self {
@@ -164,6 +364,7 @@ impl From<types::ResponseUsage> for Usage {
             output_tokens: Some(value.output_tokens as usize),
             cached_tokens: Some(value.input_tokens_details.cached_tokens as usize),
             reasoning_tokens: Some(value.output_tokens_details.reasoning_tokens as usize),
+            cache_write_tokens: None,
         }
     }
 }
@@ -210,6 +411,7 @@ fn from_schema_to_response_format(schema: Schema) -> types::TextResponseFormat {
 #[cfg(test)]
 mod tests {
     use super::client::*;
+    use crate::core::Message;
     use crate::core::language_model::{
         LanguageModelOptions, ReasoningEffort as LMReasoningEffort, Usage,
     };
@@ -285,6 +487,78 @@ mod tests {
         assert!(lm_options.reasoning.is_none());
     }
 
+    #[test]
+    fn test_language_model_options_to_create_response_with_retention_controls() {
+        let request = serde_json::to_value(OpenAILanguageModelOptions::from(
+            LanguageModelOptions::default(),
+        ))
+        .unwrap();
+        for field in ["store", "metadata", "user"] {
+            assert!(request.get(field).is_none());
+        }
+
+        let options = LanguageModelOptions {
+            store: Some(false),
+            request_metadata: Some([("team".to_string(), "search".to_string())].into()),
+            user: Some("user_42".to_string()),
+            ..Default::default()
+        };
+        let request = serde_json::to_value(OpenAILanguageModelOptions::from(options)).unwrap();
+        assert_eq!(request["store"], false);
+        assert_eq!(request["metadata"], serde_json::json!({ "team": "search" }));
+        assert_eq!(request["user"], "user_42");
+    }
+
+    #[test]
+    fn test_tool_schema_strictness() {
+        let tool = crate::core::tools::Tool {
+            name: "weather".to_string(),
+            input_schema: schemars::json_schema!({
+                "type": "object",
+                "properties": {
+                    "city": { "type": "string" },
+                    "unit": { "type": "string" },
+                },
+                "required": ["city"],
+            }),
+            ..Default::default()
+        };
+
+        let params = serde_json::to_value(types::ToolParams::from(tool.clone())).unwrap();
+        assert_eq!(params["strict"], true);
+        assert_eq!(
+            params["parameters"]["required"],
+            serde_json::json!(["city", "unit"])
+        );
+        assert_eq!(
+            params["parameters"]["properties"]["unit"]["type"],
+            serde_json::json!(["string", "null"])
+        );
+        assert_eq!(params["parameters"]["additionalProperties"], false);
+
+        let tool = crate::core::tools::Tool {
+            strict: Some(false),
+            ..tool
+        };
+        let params = serde_json::to_value(types::ToolParams::from(tool)).unwrap();
+        assert_eq!(params["strict"], false);
+        assert_eq!(
+            params["parameters"]["required"],
+            serde_json::json!(["city"])
+        );
+        assert_eq!(params["parameters"]["properties"]["unit"]["type"], "string");
+        assert!(params["parameters"].get("additionalProperties").is_none());
+    }
+
+    #[test]
+    fn test_developer_message_keeps_developer_role() {
+        let item: Option<types::InputItem> =
+            Message::Developer("Answer in French.".to_string()).into();
+        let item = serde_json::to_value(item.unwrap()).unwrap();
+        assert_eq!(item["role"], "developer");
+        assert_eq!(item["content"][0]["text"], "Answer in French.");
+    }
+
     #[test]
     fn test_openai_usage_to_usage_conversion() {
         let openai_usage = types::ResponseUsage {
@@ -302,4 +576,64 @@ mod tests {
         assert_eq!(usage.cached_tokens, Some(0));
         assert_eq!(usage.reasoning_tokens, Some(0));
     }
+
+    #[test]
+    fn test_reasoning_and_function_call_items_round_trip() {
+        use crate::core::language_model::LanguageModelResponseContentType;
+        use crate::core::messages::{AssistantMessage, Message};
+        use crate::core::tools::ToolCallInfo;
+        use crate::extensions::Extensions;
+        use crate::providers::openai::extensions::OpenAIItemMetadata;
+
+        let assistant = |content| {
+            Option::<InputItem>::from(Message::Assistant(AssistantMessage {
+                content,
+                usage: None,
+            }))
+            .map(|item| serde_json::to_value(item).unwrap())
+        };
+
+        let extensions = Extensions::default();
+        {
+            let mut metadata = extensions.get_mut::<OpenAIItemMetadata>();
+            metadata.item_id = Some("rs_0".to_string());
+            metadata.encrypted_content = Some("gAAAA".to_string());
+        }
+        assert_eq!(
+            assistant(LanguageModelResponseContentType::Reasoning {
+                content: String::new(),
+                extensions,
+            }),
+            Some(serde_json::json!({
+                "id": "rs_0",
+                "summary": [],
+                "type": "reasoning",
+                "encrypted_content": "gAAAA"
+            }))
+        );
+
+        let mut call = ToolCallInfo::new("get_weather");
+        call.id("call_0");
+        call.input(serde_json::json!({}));
+        call.extensions.get_mut::<OpenAIItemMetadata>().item_id = Some("fc_0".to_string());
+        assert_eq!(
+            assistant(LanguageModelResponseContentType::ToolCall(call)),
+            Some(serde_json::json!({
+                "arguments": "{}",
+                "call_id": "call_0",
+                "name": "get_weather",
+                "type": "function_call",
+                "id": "fc_0"
+            }))
+        );
+
+        // Reasoning of other providers has no item to refer to
+        assert_eq!(
+            assistant(LanguageModelResponseContentType::Reasoning {
+                content: "Thinking".to_string(),
+                extensions: Extensions::default(),
+            }),
+            None
+        );
+    }
 }
@@ -4,6 +4,7 @@ use crate::{
     core::{
         capabilities::ModelName,
         client::EmbeddingClient,
+        credentials::resolve_api_key,
         embedding_model::{EmbeddingModel, EmbeddingModelOptions, EmbeddingModelResponse},
     },
     error::Result,
@@ -26,6 +27,11 @@ impl<M: ModelName> EmbeddingModel for OpenAI<M> {
     async fn embed(&self, input: EmbeddingModelOptions) -> Result<EmbeddingModelResponse> {
         // Clone self to allow mutation
         let mut model = self.clone();
+        resolve_api_key(
+            &model.settings.credential_provider,
+            &mut model.settings.api_key,
+        )
+        .await?;
 
         // Convert input to OpenAI embedding options
         let mut options: crate::providers::openai::client::OpenAIEmbeddingOptions = input.into();
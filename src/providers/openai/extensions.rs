@@ -0,0 +1,22 @@
+//! OpenAI-specific metadata for extensions.
+
+use crate::extensions::PersistedExtension;
+use serde::{Deserialize, Serialize};
+
+/// Metadata of the output items of the Responses API.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct OpenAIItemMetadata {
+    /// The ID of the reasoning or function call item.
+    ///
+    /// Reasoning items must be sent back in subsequent turns together with
+    /// the function calls that follow them, each with its original ID.
+    pub item_id: Option<String>,
+
+    /// The encrypted content of a reasoning item, returned when requested
+    /// through `include`.
+    pub encrypted_content: Option<String>,
+}
+
+impl PersistedExtension for OpenAIItemMetadata {
+    const KEY: &'static str = "openai.item";
+}
@@ -0,0 +1,185 @@
+//! Files API of the OpenAI provider.
+
+use crate::core::client::{LanguageModelClient, multipart_body, send_json};
+use crate::core::files::{FileHandle, FileProvider, FilePurpose, mime_type};
+use crate::core::utils::join_url;
+use crate::error::{Error, Result};
+use crate::providers::openai::{ModelName, OpenAI};
+use async_trait::async_trait;
+use reqwest::header::CONTENT_TYPE;
+use serde::Deserialize;
+
+/// A file of the Files API.
+#[derive(Debug, Deserialize)]
+struct OpenAIFile {
+    id: String,
+    #[serde(default)]
+    filename: Option<String>,
+    #[serde(default)]
+    bytes: Option<u64>,
+}
+
+impl From<OpenAIFile> for FileHandle {
+    fn from(file: OpenAIFile) -> Self {
+        Self {
+            mime_type: file
+                .filename
+                .as_deref()
+                .map(|name| mime_type(name).to_string()),
+            id: file.id,
+            filename: file.filename,
+            size: file.bytes,
+            uri: None,
+        }
+    }
+}
+
+/// A page of the list of files.
+#[derive(Debug, Deserialize)]
+struct OpenAIFileList {
+    data: Vec<OpenAIFile>,
+    #[serde(default)]
+    has_more: bool,
+}
+
+impl<M: ModelName> OpenAI<M> {
+    /// Returns the path of an endpoint of the API, next to the Responses endpoint.
+    pub(crate) fn api_path(&self, path: &str) -> String {
+        let responses = LanguageModelClient::path(self);
+        let root = responses.strip_suffix("/responses").unwrap_or("/v1");
+        format!("{root}{path}")
+    }
+
+    /// Builds a request to an endpoint of the API, with the headers of the provider.
+    pub(crate) fn api_request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+    ) -> Result<reqwest::RequestBuilder> {
+        let url = join_url(self.settings.base_url.as_str(), &self.api_path(path))?;
+        Ok(LanguageModelClient::http_client(self)
+            .request(method, url)
+            .headers(LanguageModelClient::headers(self)))
+    }
+}
+
+#[async_trait]
+impl<M: ModelName> FileProvider for OpenAI<M> {
+    async fn upload_file(
+        &self,
+        bytes: Vec<u8>,
+        filename: &str,
+        purpose: FilePurpose,
+    ) -> Result<FileHandle> {
+        let (content_type, body) = multipart_body(
+            &[("purpose", purpose.as_str())],
+            "file",
+            filename,
+            mime_type(filename),
+            &bytes,
+        );
+        let mut headers = LanguageModelClient::headers(self);
+        headers.insert(
+            CONTENT_TYPE,
+            content_type
+                .parse()
+                .map_err(|_| Error::InvalidInput("Invalid file name".to_string()))?,
+        );
+
+        let request = self
+            .api_request(reqwest::Method::POST, "/files")?
+            .headers(headers)
+            .body(body);
+        let file: OpenAIFile = send_json(request).await?;
+        Ok(file.into())
+    }
+
+    async fn list_files(&self) -> Result<Vec<FileHandle>> {
+        let mut files: Vec<FileHandle> = Vec::new();
+        loop {
+            let mut request = self.api_request(reqwest::Method::GET, "/files")?;
+            if let Some(last) = files.last() {
+                request = request.query(&[("after", last.id.as_str())]);
+            }
+            let page: OpenAIFileList = send_json(request).await?;
+            let empty = page.data.is_empty();
+            files.extend(page.data.into_iter().map(FileHandle::from));
+            if !page.has_more || empty {
+                return Ok(files);
+            }
+        }
+    }
+
+    async fn delete_file(&self, id: &str) -> Result<()> {
+        let request = self.api_request(reqwest::Method::DELETE, &format!("/files/{id}"))?;
+        let _: serde_json::Value = send_json(request).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DynamicModel;
+    use crate::core::client::test_server::{TestResponse, serve};
+
+    /// Serves JSON responses on a local port, one per connection, and returns its
+    #[tokio::test]
+    async fn test_upload_list_and_delete_files() {
+        let (base_url, mut requests) = serve(vec![
+            TestResponse::json(r#"{"id":"file-1","object":"file","bytes":5,"filename":"report.pdf","purpose":"user_data"}"#),
+            TestResponse::json(r#"{"object":"list","data":[{"id":"file-1","object":"file","bytes":5,"filename":"report.pdf"}],"has_more":true}"#),
+            TestResponse::json(r#"{"object":"list","data":[{"id":"file-2","object":"file","bytes":9,"filename":"photo.png"}],"has_more":false}"#),
+            TestResponse::json(r#"{"id":"file-1","object":"file","deleted":true}"#),
+        ])
+        .await;
+
+        let openai = OpenAI::<DynamicModel>::builder()
+            .model_name("gpt-5")
+            .base_url(base_url)
+            .api_key("test")
+            .build()
+            .unwrap();
+
+        let file = openai
+            .upload_file(b"%PDF-".to_vec(), "report.pdf", FilePurpose::UserData)
+            .await
+            .unwrap();
+        assert_eq!(file.id, "file-1");
+        assert_eq!(file.mime_type.as_deref(), Some("application/pdf"));
+
+        let upload = requests.recv().await.unwrap();
+        assert!(upload.starts_with("POST /v1/files "));
+        assert!(upload.contains("content-type: multipart/form-data; boundary="));
+        assert!(upload.contains("name=\"purpose\"\r\n\r\nuser_data\r\n"));
+        assert!(
+            upload.contains(
+                "filename=\"report.pdf\"\r\nContent-Type: application/pdf\r\n\r\n%PDF-\r\n"
+            )
+        );
+
+        let files = openai.list_files().await.unwrap();
+        assert_eq!(
+            files.iter().map(|f| f.id.as_str()).collect::<Vec<_>>(),
+            ["file-1", "file-2"]
+        );
+        assert!(files[1].is_image());
+        assert!(requests.recv().await.unwrap().starts_with("GET /v1/files "));
+        assert!(
+            requests
+                .recv()
+                .await
+                .unwrap()
+                .starts_with("GET /v1/files?after=file-1 ")
+        );
+
+        openai.delete_file("file-1").await.unwrap();
+        assert!(
+            requests
+                .recv()
+                .await
+                .unwrap()
+                .starts_with("DELETE /v1/files/file-1 ")
+        );
+    }
+}
@@ -1,18 +1,22 @@
 //! Language model implementation for the OpenAI provider.
 
+use crate::core::capabilities::CapabilitySet;
 use crate::core::capabilities::ModelName;
 use crate::core::client::LanguageModelClient;
+use crate::core::credentials::resolve_api_key;
+use crate::core::language_model::logprobs::TokenLogprob;
 use crate::core::language_model::{
-    LanguageModelOptions, LanguageModelResponse, LanguageModelResponseContentType,
-    LanguageModelStreamChunk, LanguageModelStreamChunkType, ProviderStream, Usage,
+    ConstrainedOutput, LanguageModelOptions, LanguageModelResponse,
+    LanguageModelResponseContentType, LanguageModelStreamChunk, LanguageModelStreamChunkType,
+    ProviderStream, ResponseMetadata, Usage,
 };
 use crate::core::messages::AssistantMessage;
+use crate::core::pricing::ModelPricing;
+use crate::providers::openai::background::is_pending;
 use crate::providers::openai::client::{OpenAILanguageModelOptions, types};
+use crate::providers::openai::conversions::{function_call, reasoning};
 use crate::providers::openai::{OpenAI, client};
-use crate::{
-    core::{language_model::LanguageModel, tools::ToolCallInfo},
-    error::Result,
-};
+use crate::{core::language_model::LanguageModel, error::Result};
 use async_trait::async_trait;
 use futures::StreamExt;
 
@@ -23,83 +27,106 @@ impl<M: ModelName> LanguageModel for OpenAI<M> {
         self.lm_options.model.clone()
     }
 
+    fn pricing(&self) -> Option<ModelPricing> {
+        M::PRICING
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        M::CONTEXT_WINDOW
+    }
+
+    fn max_output_tokens(&self) -> Option<usize> {
+        M::MAX_OUTPUT_TOKENS
+    }
+
+    fn capabilities(&self) -> CapabilitySet {
+        M::CAPABILITIES
+    }
+
     /// Generates text using the OpenAI provider.
     async fn generate_text(
         &mut self,
-        options: LanguageModelOptions,
+        mut options: LanguageModelOptions,
     ) -> Result<LanguageModelResponse> {
+        resolve_api_key(
+            &self.settings.credential_provider,
+            &mut self.settings.api_key,
+        )
+        .await?;
+        options.resolve_constrained_output("OpenAI", |output| {
+            *output == ConstrainedOutput::JsonObject
+        })?;
+        options.validate_sampling(0.0..=2.0)?;
         let additional_headers = options.headers.clone();
+        let additional_query = options.query.clone();
+        let max_retries = options.max_retries;
         let mut options: OpenAILanguageModelOptions = options.into();
 
         options.model = self.lm_options.model.clone();
+        options.background = self.lm_options.background;
 
         self.lm_options = options;
 
-        let response: client::OpenAIResponse = self
-            .send(&self.settings.base_url, additional_headers)
+        let (response, metadata): (client::OpenAIResponse, _) = self
+            .send_with_metadata(
+                &self.settings.base_url,
+                additional_headers,
+                additional_query,
+                max_retries,
+            )
             .await?;
 
-        let mut collected: Vec<LanguageModelResponseContentType> = Vec::new();
-
-        for out in response.output.unwrap_or_default() {
-            match out {
-                types::MessageItem::OutputMessage { content, .. } => {
-                    for c in content {
-                        if let types::OutputContent::OutputText { text, .. } = c {
-                            collected.push(LanguageModelResponseContentType::new(text))
-                        }
-                    }
-                }
-                types::MessageItem::FunctionCall {
-                    arguments,
-                    name,
-                    call_id,
-                    ..
-                } => {
-                    let mut tool_info = ToolCallInfo::new(name);
-                    tool_info.id(call_id);
-                    tool_info.input(serde_json::from_str(&arguments).unwrap_or_default());
-                    collected.push(LanguageModelResponseContentType::ToolCall(tool_info));
-                }
-                _ => (),
-            }
+        // Background responses are created queued, and polled until they finish
+        if let Some(id) = response.id.clone().filter(|_| is_pending(&response)) {
+            return self.wait_for_response(id).await;
         }
 
-        Ok(LanguageModelResponse {
-            contents: collected,
-            usage: response.usage.map(|usage| usage.into()),
-        })
+        let mut response: LanguageModelResponse = response.try_into()?;
+        response.metadata.get_or_insert_default().merge(metadata);
+        Ok(response)
     }
 
     /// Streams text using the OpenAI provider.
-    async fn stream_text(&mut self, options: LanguageModelOptions) -> Result<ProviderStream> {
+    async fn stream_text(&mut self, mut options: LanguageModelOptions) -> Result<ProviderStream> {
+        resolve_api_key(
+            &self.settings.credential_provider,
+            &mut self.settings.api_key,
+        )
+        .await?;
+        options.resolve_constrained_output("OpenAI", |output| {
+            *output == ConstrainedOutput::JsonObject
+        })?;
+        options.validate_sampling(0.0..=2.0)?;
         let additional_headers = options.headers.clone();
+        let additional_query = options.query.clone();
+        let max_retries = options.max_retries.unwrap_or(5);
         let mut options: OpenAILanguageModelOptions = options.into();
 
         options.model = self.lm_options.model.to_string();
         options.stream = Some(true);
+        options.background = self.lm_options.background;
 
         self.lm_options = options;
 
         // Retry logic for rate limiting
-        let max_retries = 5;
         let mut retry_count = 0;
         let mut wait_time = std::time::Duration::from_secs(1);
 
         let openai_stream = loop {
             match self
-                .send_and_stream(&self.settings.base_url, additional_headers.clone())
+                .send_and_stream(
+                    &self.settings.base_url,
+                    additional_headers.clone(),
+                    additional_query.clone(),
+                )
                 .await
             {
                 Ok(stream) => break stream,
-                Err(crate::error::Error::ApiError {
-                    status_code: Some(status),
-                    ..
-                }) if status == reqwest::StatusCode::TOO_MANY_REQUESTS
-                    && retry_count < max_retries =>
+                Err(crate::error::Error::RateLimited { retry_after, .. })
+                    if retry_count < max_retries =>
                 {
                     retry_count += 1;
-                    tokio::time::sleep(wait_time).await;
+                    crate::core::runtime::sleep(retry_after.unwrap_or(wait_time)).await;
                     wait_time *= 2; // Exponential backoff
                     continue;
                 }
@@ -108,10 +135,20 @@ impl<M: ModelName> LanguageModel for OpenAI<M> {
         };
 
         let stream = openai_stream.map(|evt_res| match evt_res {
-            Ok(client::OpenAiStreamEvent::ResponseOutputTextDelta { delta, .. }) => {
-                Ok(vec![LanguageModelStreamChunk::Delta(
+            Ok(client::OpenAiStreamEvent::ResponseOutputTextDelta {
+                delta, logprobs, ..
+            }) => {
+                let mut chunks = vec![LanguageModelStreamChunk::Delta(
                     LanguageModelStreamChunkType::Text(delta),
-                )])
+                )];
+                if let Some(logprobs) = logprobs.filter(|l| !l.is_empty()) {
+                    chunks.push(LanguageModelStreamChunk::Delta(
+                        LanguageModelStreamChunkType::Logprobs(
+                            logprobs.into_iter().map(TokenLogprob::from).collect(),
+                        ),
+                    ));
+                }
+                Ok(chunks)
             }
             Ok(client::OpenAiStreamEvent::ResponseReasoningSummaryTextDelta { delta, .. }) => {
                 Ok(vec![LanguageModelStreamChunk::Delta(
@@ -119,7 +156,14 @@ impl<M: ModelName> LanguageModel for OpenAI<M> {
                 )])
             }
             Ok(client::OpenAiStreamEvent::ResponseCompleted { response, .. }) => {
-                let mut result: Vec<LanguageModelStreamChunk> = Vec::new();
+                let mut result: Vec<LanguageModelStreamChunk> =
+                    vec![LanguageModelStreamChunk::Delta(
+                        LanguageModelStreamChunkType::ResponseMetadata(ResponseMetadata {
+                            request_id: None,
+                            response_id: response.id.clone(),
+                            model: response.model.clone(),
+                        }),
+                    )];
 
                 let usage: Usage = response.usage.unwrap_or_default().into();
                 let output = response.output.unwrap_or_default();
@@ -139,16 +183,18 @@ impl<M: ModelName> LanguageModel for OpenAI<M> {
                         }
 
                         // ---- Reasoning ----
-                        types::MessageItem::Reasoning { summary, .. } => {
-                            if let Some(types::ReasoningSummary { text, .. }) = summary.first() {
-                                result.push(LanguageModelStreamChunk::Done(AssistantMessage {
-                                    content: LanguageModelResponseContentType::Reasoning {
-                                        content: text.to_owned(),
-                                        extensions: crate::extensions::Extensions::default(),
-                                    },
-                                    usage: Some(usage.clone()),
-                                }));
-                            }
+                        // Kept even without a summary, since it must precede the
+                        // function calls that follow it in the next request
+                        types::MessageItem::Reasoning {
+                            id,
+                            summary,
+                            encrypted_content,
+                            ..
+                        } => {
+                            result.push(LanguageModelStreamChunk::Done(AssistantMessage {
+                                content: reasoning(id.clone(), summary, encrypted_content.clone()),
+                                usage: Some(usage.clone()),
+                            }));
                         }
 
                         // ---- FunctionCall ----
@@ -156,11 +202,11 @@ impl<M: ModelName> LanguageModel for OpenAI<M> {
                             call_id,
                             name,
                             arguments,
+                            id,
                             ..
                         } => {
-                            let mut tool_info = ToolCallInfo::new(name.clone());
-                            tool_info.id(call_id.clone());
-                            tool_info.input(serde_json::from_str(arguments).unwrap_or_default());
+                            let tool_info =
+                                function_call(id.clone(), call_id.clone(), name.clone(), arguments);
 
                             result.push(LanguageModelStreamChunk::Done(AssistantMessage {
                                 content: LanguageModelResponseContentType::ToolCall(tool_info),
@@ -1,15 +1,22 @@
 //! OpenAI provider implementation.
 
+pub mod background;
+pub mod batch;
 pub mod capabilities;
 pub mod client;
 pub mod conversions;
 pub mod embedding_model;
+pub mod extensions;
+pub mod files;
 pub mod language_model;
+pub mod moderation;
 pub mod settings;
 
 use crate::core::DynamicModel;
 use crate::core::capabilities::ModelName;
-use crate::core::utils::validate_base_url;
+use crate::core::client::HttpSettings;
+use crate::core::credentials::{CredentialProvider, Credentials};
+use crate::core::utils::{env_var, validate_base_url};
 use crate::error::Error;
 use crate::providers::openai::client::{OpenAIEmbeddingOptions, OpenAILanguageModelOptions};
 use crate::providers::openai::settings::OpenAIProviderSettings;
@@ -31,6 +38,25 @@ impl<M: ModelName> OpenAI<M> {
     pub fn builder() -> OpenAIBuilder<M> {
         OpenAIBuilder::default()
     }
+
+    /// Creates an OpenAI provider builder configured from the environment.
+    ///
+    /// Reads the API key from `OPENAI_API_KEY`, the base URL from `OPENAI_BASE_URL`,
+    /// the organization from `OPENAI_ORG_ID` and the project from `OPENAI_PROJECT_ID`.
+    /// Unset variables keep the defaults.
+    pub fn from_env() -> OpenAIBuilder<M> {
+        let mut builder = Self::builder();
+        if let Some(base_url) = env_var("OPENAI_BASE_URL") {
+            builder = builder.base_url(base_url);
+        }
+        if let Some(organization) = env_var("OPENAI_ORG_ID") {
+            builder = builder.organization(organization);
+        }
+        if let Some(project) = env_var("OPENAI_PROJECT_ID") {
+            builder = builder.project(project);
+        }
+        builder
+    }
 }
 
 impl<M: ModelName> Default for OpenAI<M> {
@@ -180,6 +206,63 @@ impl<M: ModelName> OpenAIBuilder<M> {
         self
     }
 
+    /// Sets the ID of the organization the requests are billed to.
+    pub fn organization(mut self, organization: impl Into<String>) -> Self {
+        self.settings.organization = Some(organization.into());
+        self
+    }
+
+    /// Sets the ID of the project the requests are billed to.
+    pub fn project(mut self, project: impl Into<String>) -> Self {
+        self.settings.project = Some(project.into());
+        self
+    }
+
+    /// Sets the credentials resolving the API key before each request, to fetch
+    /// it from a secret manager or rotate it. Takes precedence over `api_key`.
+    ///
+    /// # Parameters
+    ///
+    /// * `credentials` - The source of the API key.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the credentials set.
+    pub fn credential_provider(mut self, credentials: impl CredentialProvider + 'static) -> Self {
+        self.settings.credential_provider = Some(Credentials::new(credentials));
+        self
+    }
+
+    /// Sets the HTTP client sending the requests, to share its pool of connections
+    /// or configure it. Requests share one pooled client by default.
+    ///
+    /// # Parameters
+    ///
+    /// * `client` - The `reqwest` client sending the requests.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the HTTP client set.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.settings.http_client = Some(client);
+        self
+    }
+
+    /// Sets the network settings of the HTTP client, such as a proxy or
+    /// certificate authorities. Ignored if an HTTP client is set.
+    ///
+    /// # Parameters
+    ///
+    /// * `http` - The network settings.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the network settings set.
+    pub fn http_settings(mut self, http: HttpSettings) -> Self {
+        self.settings.http = http;
+        self
+    }
+
     /// Sets the name of the provider. Defaults to "openai".
     ///
     /// # Parameters
@@ -200,6 +283,24 @@ impl<M: ModelName> OpenAIBuilder<M> {
         self
     }
 
+    /// Runs responses in background mode.
+    ///
+    /// Background responses are created without waiting for the model, and
+    /// `generate_text` polls them until they finish instead of holding the
+    /// connection open, which suits long-running reasoning models.
+    ///
+    /// # Parameters
+    ///
+    /// * `background` - Whether to run responses in the background.
+    ///
+    /// # Returns
+    ///
+    /// The builder with background mode set.
+    pub fn background(mut self, background: bool) -> Self {
+        self.options.background = Some(background);
+        self
+    }
+
     /// Builds the OpenAI provider.
     ///
     /// Validates the configuration and creates the provider instance.
@@ -212,7 +313,7 @@ impl<M: ModelName> OpenAIBuilder<M> {
         let base_url = validate_base_url(&self.settings.base_url)?;
 
         // check api key exists
-        if self.settings.api_key.is_empty() {
+        if self.settings.api_key.is_empty() && self.settings.credential_provider.is_none() {
             return Err(Error::MissingField("api_key".to_string()));
         }
 
@@ -230,6 +331,10 @@ impl<M: ModelName> OpenAIBuilder<M> {
         Ok(OpenAI {
             settings: OpenAIProviderSettings {
                 base_url,
+                http_client: self
+                    .settings
+                    .http
+                    .resolve_client(self.settings.http_client.clone())?,
                 ..self.settings
             },
             lm_options,
@@ -0,0 +1,175 @@
+//! Moderation API of the OpenAI provider.
+//!
+//! The model of the provider is used for moderation, so it should be a
+//! moderation model such as `omni-moderation-latest`.
+
+use crate::core::client::send_json;
+use crate::core::moderation::{
+    ModerationCategory, ModerationCategoryScore, ModerationInput, ModerationModel, ModerationResult,
+};
+use crate::error::Result;
+use crate::providers::openai::{ModelName, OpenAI};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A part of the input of a moderation request.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum OpenAIModerationInput {
+    Text { text: String },
+    ImageUrl { image_url: OpenAIImageUrl },
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAIImageUrl {
+    url: String,
+}
+
+impl From<ModerationInput> for OpenAIModerationInput {
+    fn from(input: ModerationInput) -> Self {
+        match input {
+            ModerationInput::Text(text) => Self::Text { text },
+            ModerationInput::ImageUrl(url) => Self::ImageUrl {
+                image_url: OpenAIImageUrl { url },
+            },
+        }
+    }
+}
+
+/// A moderation request.
+#[derive(Debug, Serialize)]
+struct OpenAIModerationRequest {
+    model: String,
+    input: Vec<OpenAIModerationInput>,
+}
+
+/// The classification of the input of a moderation request.
+#[derive(Debug, Deserialize)]
+struct OpenAIModerationResult {
+    flagged: bool,
+    #[serde(default)]
+    categories: HashMap<String, bool>,
+    #[serde(default)]
+    category_scores: HashMap<String, f32>,
+}
+
+impl From<OpenAIModerationResult> for ModerationResult {
+    fn from(result: OpenAIModerationResult) -> Self {
+        let mut categories: Vec<ModerationCategoryScore> = result
+            .category_scores
+            .into_iter()
+            .map(|(name, score)| ModerationCategoryScore {
+                flagged: result.categories.get(&name).copied().unwrap_or(false),
+                category: ModerationCategory::from_openai_name(&name),
+                score,
+            })
+            .collect();
+        categories.sort_by(|a, b| a.category.name().cmp(b.category.name()));
+
+        Self {
+            flagged: result.flagged,
+            categories,
+        }
+    }
+}
+
+/// The response to a moderation request.
+#[derive(Debug, Deserialize)]
+struct OpenAIModerationResponse {
+    results: Vec<OpenAIModerationResult>,
+}
+
+#[async_trait]
+impl<M: ModelName> ModerationModel for OpenAI<M> {
+    async fn moderate(&self, input: Vec<ModerationInput>) -> Result<ModerationResult> {
+        let body = OpenAIModerationRequest {
+            model: self.lm_options.model.clone(),
+            input: input.into_iter().map(Into::into).collect(),
+        };
+        let request = self
+            .api_request(reqwest::Method::POST, "/moderations")?
+            .json(&body);
+        let response: OpenAIModerationResponse = send_json(request).await?;
+
+        // Multimodal inputs are classified as a whole, with a single result
+        Ok(response
+            .results
+            .into_iter()
+            .next()
+            .map(ModerationResult::from)
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DynamicModel;
+    use crate::core::client::test_server::{TestResponse, serve};
+
+    /// Serves a JSON response on a local port, and returns its address along
+    #[tokio::test]
+    async fn test_moderate_text_and_image() {
+        let (base_url, mut request) = serve(vec![TestResponse::json(
+            r#"{"id":"modr-1","model":"omni-moderation-latest","results":[{"flagged":true,"categories":{"violence":true,"hate":false,"self-harm/intent":false},"category_scores":{"violence":0.91,"hate":0.02,"self-harm/intent":0.001}}]}"#,
+        )])
+        .await;
+
+        let openai = OpenAI::<DynamicModel>::builder()
+            .model_name("omni-moderation-latest")
+            .base_url(base_url)
+            .api_key("test")
+            .build()
+            .unwrap();
+
+        let result = openai
+            .moderate(vec![
+                ModerationInput::text("A fight"),
+                ModerationInput::image_url("https://example.com/fight.png"),
+            ])
+            .await
+            .unwrap();
+        assert!(result.flagged);
+        assert_eq!(result.flagged_categories(), [&ModerationCategory::Violence]);
+        assert_eq!(result.score(&ModerationCategory::Violence), 0.91);
+        assert_eq!(result.score(&ModerationCategory::SelfHarmIntent), 0.001);
+
+        let request = request.recv().await.unwrap();
+        assert!(request.starts_with("POST /v1/moderations "));
+        assert!(request.contains(
+            r#"{"model":"omni-moderation-latest","input":[{"type":"text","text":"A fight"},{"type":"image_url","image_url":{"url":"https://example.com/fight.png"}}]}"#
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_sent_with_injected_http_client() {
+        let (base_url, mut request) = serve(vec![TestResponse::json(
+            r#"{"id":"modr-1","model":"omni-moderation-latest","results":[{"flagged":false,"categories":{},"category_scores":{}}]}"#,
+        )])
+        .await;
+
+        let headers = reqwest::header::HeaderMap::from_iter([(
+            reqwest::header::HeaderName::from_static("x-client"),
+            reqwest::header::HeaderValue::from_static("injected"),
+        )]);
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .unwrap();
+        let openai = OpenAI::<DynamicModel>::builder()
+            .model_name("omni-moderation-latest")
+            .base_url(base_url)
+            .api_key("test")
+            .http_client(client)
+            .build()
+            .unwrap();
+
+        let result = openai
+            .moderate(vec![ModerationInput::text("Hello")])
+            .await
+            .unwrap();
+        assert!(!result.flagged);
+        assert!(request.recv().await.unwrap().contains("x-client: injected"));
+    }
+}
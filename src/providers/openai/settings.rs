@@ -1,5 +1,7 @@
 //! Defines the settings for the OpenAI provider.
 
+use crate::core::client::HttpSettings;
+use crate::core::credentials::Credentials;
 use derive_builder::Builder;
 
 #[derive(Debug, Clone, Builder)]
@@ -15,6 +17,26 @@ pub struct OpenAIProviderSettings {
     /// The API key for the OpenAI API.
     pub api_key: String,
 
+    /// Credentials resolving the API key before each request, in place of
+    /// `api_key`.
+    pub credential_provider: Option<Credentials>,
+
+    /// The ID of the organization the requests are billed to, sent in the
+    /// `OpenAI-Organization` header.
+    pub organization: Option<String>,
+
+    /// The ID of the project the requests are billed to, sent in the
+    /// `OpenAI-Project` header.
+    pub project: Option<String>,
+
+    /// The HTTP client sending the requests. Requests share one pooled client
+    /// if it is not set.
+    pub http_client: Option<reqwest::Client>,
+
+    /// Network settings of the HTTP client built for the provider when no
+    /// client is set, such as a proxy or certificate authorities.
+    pub http: HttpSettings,
+
     /// Custom API path override. When set, this path is used instead of the
     /// provider's default path (e.g., "/v1/responses").
     /// This is useful for connecting to endpoints that use a different path,
@@ -29,6 +51,11 @@ impl Default for OpenAIProviderSettings {
             provider_name: "openai".to_string(),
             base_url: "https://api.openai.com".to_string(),
             api_key: std::env::var("OPENAI_API_KEY").unwrap_or_default(),
+            credential_provider: None,
+            organization: None,
+            project: None,
+            http_client: None,
+            http: HttpSettings::default(),
             path: None,
         }
     }
@@ -5,7 +5,7 @@ pub(crate) mod types;
 pub(crate) use types::ChatCompletionsOptions;
 
 use crate::core::capabilities::ModelName;
-use crate::core::client::LanguageModelClient;
+use crate::core::client::{LanguageModelClient, shared_client, sse_error};
 use crate::error::Error;
 use crate::providers::openai_chat_completions::OpenAIChatCompletions;
 use reqwest::header::CONTENT_TYPE;
@@ -27,13 +27,24 @@ impl<M: ModelName> LanguageModelClient for OpenAIChatCompletions<M> {
         reqwest::Method::POST
     }
 
+    fn http_client(&self) -> reqwest::Client {
+        self.settings
+            .http_client
+            .clone()
+            .unwrap_or_else(shared_client)
+    }
+
     fn headers(&self) -> reqwest::header::HeaderMap {
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
-        headers.insert(
-            "Authorization",
-            format!("Bearer {}", self.settings.api_key).parse().unwrap(),
-        );
+        // Gateways holding the keys of the upstream providers take no API key.
+        if !self.settings.api_key.is_empty() {
+            headers.insert(
+                "Authorization",
+                format!("Bearer {}", self.settings.api_key).parse().unwrap(),
+            );
+        }
+        headers.extend(self.settings.headers.clone());
         headers
     }
 
@@ -66,16 +77,7 @@ impl<M: ModelName> LanguageModelClient for OpenAIChatCompletions<M> {
                     Ok(ChatCompletionsStreamEvent::Chunk(chunk))
                 }
             },
-            Err(e) => {
-                let status_code = match &e {
-                    reqwest_eventsource::Error::InvalidStatusCode(status, _) => Some(*status),
-                    _ => None,
-                };
-                Err(Error::ApiError {
-                    status_code,
-                    details: e.to_string(),
-                })
-            }
+            Err(e) => Err(sse_error(e)),
         }
     }
 
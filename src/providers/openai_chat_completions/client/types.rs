@@ -39,6 +39,14 @@ pub(crate) struct ChatCompletionsOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub seed: Option<u32>,
 
+    /// The seed, for providers naming it `random_seed` (Mistral).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub random_seed: Option<u32>,
+
+    /// Whether to inject a safety prompt before the conversation (Mistral).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safe_prompt: Option<bool>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stop: Option<StopSequences>,
 
@@ -68,6 +76,19 @@ pub(crate) struct ChatCompletionsOptions {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub verbosity: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+
+    /// Provider-specific options, for gateways that accept them (Vercel AI Gateway).
+    #[serde(rename = "providerOptions", skip_serializing_if = "Option::is_none")]
+    pub provider_options: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -159,7 +180,17 @@ pub(crate) enum StopSequences {
 pub(crate) enum ResponseFormat {
     Text,
     JsonObject,
-    JsonSchema { json_schema: JsonSchemaDefinition },
+    JsonSchema {
+        json_schema: JsonSchemaDefinition,
+    },
+    /// Output matching a regular expression (Together AI).
+    RegexObject {
+        pattern: String,
+    },
+    /// Output matching a GBNF grammar (Fireworks AI).
+    Grammar {
+        grammar: String,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -198,6 +229,9 @@ pub(crate) struct ChatCompletionsResponse {
     pub system_fingerprint: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub service_tier: Option<String>,
+    /// Provider-specific metadata, returned by gateways (Vercel AI Gateway).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_metadata: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -275,6 +309,9 @@ pub(crate) struct ChatCompletionsStreamChunk {
     pub system_fingerprint: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub usage: Option<Usage>,
+    /// Provider-specific metadata, returned by gateways (Vercel AI Gateway).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_metadata: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -1,10 +1,13 @@
 //! Helper functions and conversions for the OpenAI Chat Completions provider.
 
+use crate::core::language_model::logprobs::{TokenLogprob, TopLogprob};
 use crate::core::language_model::{
-    LanguageModelOptions, LanguageModelResponseContentType, ReasoningEffort, Usage,
+    ConstrainedOutput, LanguageModelOptions, LanguageModelResponseContentType, ReasoningEffort,
+    Usage,
 };
 use crate::core::messages::Message;
-use crate::core::tools::Tool as SdkTool;
+use crate::core::schema::{SchemaDialect, make_strict, sanitize};
+use crate::core::tools::{Tool as SdkTool, ToolChoice};
 use crate::providers::openai_chat_completions::client::{self, types};
 
 // ============================================================================
@@ -42,33 +45,39 @@ impl From<LanguageModelOptions> for client::ChatCompletionsOptions {
                 .collect()
         });
 
-        let response_format = options.schema.map(|schema| {
-            let mut json_value = serde_json::to_value(schema).unwrap();
-
-            // Ensure required fields for OpenAI Structured Outputs
-            if let serde_json::Value::Object(ref mut obj) = json_value {
-                obj.insert(
-                    "additionalProperties".to_string(),
-                    serde_json::Value::Bool(false),
-                );
+        let response_format = match options.constrained_output {
+            Some(ConstrainedOutput::JsonObject) => Some(types::ResponseFormat::JsonObject),
+            Some(ConstrainedOutput::Regex(pattern)) => {
+                Some(types::ResponseFormat::RegexObject { pattern })
             }
-
-            types::ResponseFormat::JsonSchema {
-                json_schema: types::JsonSchemaDefinition {
-                    name: json_value
-                        .get("title")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("Response")
-                        .to_string(),
-                    schema: json_value.clone(),
-                    description: json_value
-                        .get("description")
-                        .and_then(|v| v.as_str())
-                        .map(str::to_string),
-                    strict: Some(true),
-                },
+            Some(ConstrainedOutput::Grammar(grammar)) => {
+                Some(types::ResponseFormat::Grammar { grammar })
             }
-        });
+            // JSON schema constraints are moved to the schema by the provider
+            Some(ConstrainedOutput::JsonSchema(_)) | None => options.schema.map(|schema| {
+                let mut json_value = serde_json::to_value(schema).unwrap();
+
+                // Ensure required fields for OpenAI Structured Outputs
+                sanitize(&mut json_value, SchemaDialect::OpenAIStrict);
+                make_strict(&mut json_value);
+
+                types::ResponseFormat::JsonSchema {
+                    json_schema: types::JsonSchemaDefinition {
+                        name: json_value
+                            .get("title")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("Response")
+                            .to_string(),
+                        schema: json_value.clone(),
+                        description: json_value
+                            .get("description")
+                            .and_then(|v| v.as_str())
+                            .map(str::to_string),
+                        strict: Some(true),
+                    },
+                }
+            }),
+        };
 
         let reasoning_effort = options.reasoning_effort.map(|effort| {
             match effort {
@@ -79,11 +88,17 @@ impl From<LanguageModelOptions> for client::ChatCompletionsOptions {
             .to_string()
         });
 
-        let tool_choice = if tools.is_some() {
-            Some(types::ToolChoice::String("auto".to_string()))
-        } else {
-            None
-        };
+        let tool_choice = tools.as_ref().map(|_| match options.tool_choice {
+            Some(ToolChoice::Tool(name)) => {
+                types::ToolChoice::Specific(types::ToolChoiceSpecific {
+                    type_: "function".to_string(),
+                    function: types::FunctionChoice { name },
+                })
+            }
+            Some(ToolChoice::None) => types::ToolChoice::String("none".to_string()),
+            Some(ToolChoice::Required) => types::ToolChoice::String("required".to_string()),
+            Some(ToolChoice::Auto) | None => types::ToolChoice::String("auto".to_string()),
+        });
 
         let parallel_tool_calls = if tools.is_some() { Some(true) } else { None };
 
@@ -92,13 +107,15 @@ impl From<LanguageModelOptions> for client::ChatCompletionsOptions {
             messages,
             frequency_penalty: options.frequency_penalty,
             logit_bias: None,
-            logprobs: None,
-            top_logprobs: None,
+            logprobs: options.logprobs.map(|_| true),
+            top_logprobs: options.logprobs,
             max_completion_tokens: options.max_output_tokens,
-            n: None,
+            n: options.candidate_count,
             presence_penalty: options.presence_penalty,
             response_format,
             seed: options.seed,
+            random_seed: None,
+            safe_prompt: None,
             stop: options.stop_sequences.map(|seqs| {
                 if seqs.len() == 1 {
                     types::StopSequences::Single(seqs[0].clone())
@@ -108,13 +125,17 @@ impl From<LanguageModelOptions> for client::ChatCompletionsOptions {
             }),
             stream: None,
             stream_options: None,
-            temperature: options.temperature.map(|t| t as f32 / 100.0),
-            top_p: options.top_p.map(|t| t as f32 / 100.0),
+            temperature: options.temperature,
+            top_p: options.top_p,
             tools,
             tool_choice,
             parallel_tool_calls,
             reasoning_effort,
             verbosity: None,
+            store: options.store,
+            metadata: options.request_metadata,
+            user: options.user,
+            provider_options: None,
         }
     }
 }
@@ -220,7 +241,6 @@ impl From<SdkTool> for types::Tool {
 
         // Ensure required fields for OpenAI Chat Completions
         params["type"] = serde_json::Value::String("object".to_string());
-        params["additionalProperties"] = serde_json::Value::Bool(false);
 
         if !params
             .get("properties")
@@ -230,13 +250,19 @@ impl From<SdkTool> for types::Tool {
             params["properties"] = serde_json::Value::Object(serde_json::Map::new());
         }
 
+        let strict = tool.strict.unwrap_or(true);
+        if strict {
+            sanitize(&mut params, SchemaDialect::OpenAIStrict);
+            make_strict(&mut params);
+        }
+
         types::Tool {
             type_: "function".to_string(),
             function: types::FunctionDefinition {
                 name: tool.name,
                 description: Some(tool.description),
                 parameters: params,
-                strict: Some(true),
+                strict: Some(strict),
             },
         }
     }
@@ -257,10 +283,37 @@ impl From<types::Usage> for Usage {
             cached_tokens: usage
                 .prompt_tokens_details
                 .map(|d| d.cached_tokens as usize),
+            cache_write_tokens: None,
         }
     }
 }
 
+// ============================================================================
+// ChatCompletions LogProbs -> SDK TokenLogprob
+// ============================================================================
+
+impl From<types::ContentLogProb> for TokenLogprob {
+    fn from(logprob: types::ContentLogProb) -> Self {
+        Self {
+            token: logprob.token,
+            logprob: logprob.logprob,
+            top_logprobs: logprob
+                .top_logprobs
+                .into_iter()
+                .map(|top| TopLogprob {
+                    token: top.token,
+                    logprob: top.logprob,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Converts the log probabilities of a choice, if the provider returned them.
+pub(crate) fn token_logprobs(logprobs: Option<types::LogProbs>) -> Option<Vec<TokenLogprob>> {
+    logprobs.map(|l| l.content.into_iter().map(TokenLogprob::from).collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -284,6 +337,85 @@ mod tests {
         assert_eq!(chat_msg.content, Some("Hello".to_string()));
     }
 
+    #[test]
+    fn test_logprobs_request_and_response() {
+        let options = LanguageModelOptions {
+            logprobs: Some(3),
+            ..Default::default()
+        };
+        let completions_opts: client::ChatCompletionsOptions = options.into();
+        assert_eq!(completions_opts.logprobs, Some(true));
+        assert_eq!(completions_opts.top_logprobs, Some(3));
+
+        let logprobs: types::LogProbs = serde_json::from_value(serde_json::json!({
+            "content": [{
+                "token": "Yes",
+                "logprob": -0.1,
+                "bytes": null,
+                "top_logprobs": [
+                    { "token": "Yes", "logprob": -0.1, "bytes": null },
+                    { "token": "No", "logprob": -2.4, "bytes": null }
+                ]
+            }]
+        }))
+        .unwrap();
+        let tokens = token_logprobs(Some(logprobs)).unwrap();
+        assert_eq!(tokens[0].token, "Yes");
+        assert_eq!(tokens[0].top_logprobs[1].token, "No");
+        assert_eq!(tokens[0].top_logprobs[1].logprob, -2.4);
+    }
+
+    #[test]
+    fn test_candidate_count_maps_to_n() {
+        let options = LanguageModelOptions {
+            candidate_count: Some(3),
+            ..Default::default()
+        };
+        let completions_opts: client::ChatCompletionsOptions = options.into();
+        assert_eq!(completions_opts.n, Some(3));
+    }
+
+    #[test]
+    fn test_tool_choice_of_specific_tool() {
+        let options = LanguageModelOptions {
+            tools: Some(crate::core::tools::ToolList::new(vec![
+                crate::core::tools::Tool {
+                    name: "search".to_string(),
+                    ..Default::default()
+                },
+            ])),
+            tool_choice: Some(ToolChoice::Tool("search".to_string())),
+            ..Default::default()
+        };
+        let completions_opts: client::ChatCompletionsOptions = options.into();
+        assert_eq!(
+            serde_json::to_value(completions_opts.tool_choice).unwrap(),
+            serde_json::json!({ "type": "function", "function": { "name": "search" } })
+        );
+    }
+
+    #[test]
+    fn test_non_strict_tool_keeps_its_schema() {
+        let tool = SdkTool {
+            name: "search".to_string(),
+            input_schema: schemars::json_schema!({
+                "type": "object",
+                "properties": { "query": { "type": "string" } },
+            }),
+            strict: Some(false),
+            ..Default::default()
+        };
+        let tool = types::Tool::from(tool);
+        assert_eq!(tool.function.strict, Some(false));
+        assert_eq!(
+            tool.function.parameters,
+            serde_json::json!({
+                "type": "object",
+                "properties": { "query": { "type": "string" } },
+            })
+        );
+    }
+
     #[test]
     fn test_stop_sequences_single() {
         let options = LanguageModelOptions {
@@ -3,7 +3,8 @@
 use crate::{
     core::{
         capabilities::ModelName,
-        client::EmbeddingClient,
+        client::{EmbeddingClient, shared_client},
+        credentials::resolve_api_key,
         embedding_model::{EmbeddingModel, EmbeddingModelOptions, EmbeddingModelResponse},
     },
     error::Result,
@@ -25,6 +26,13 @@ impl<M: ModelName> EmbeddingClient for OpenAIChatCompletions<M> {
         reqwest::Method::POST
     }
 
+    fn http_client(&self) -> reqwest::Client {
+        self.settings
+            .http_client
+            .clone()
+            .unwrap_or_else(shared_client)
+    }
+
     fn headers(&self) -> reqwest::header::HeaderMap {
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert(
@@ -65,8 +73,10 @@ impl<M: ModelName> OpenAIChatCompletions<M> {
         let embedding_options = self.create_embedding_body(input)?;
 
         // Create a temporary client instance for this request
+        let mut settings = self.settings.clone();
+        resolve_api_key(&settings.credential_provider, &mut settings.api_key).await?;
         let embedding_client = EmbeddingClientWrapper {
-            settings: self.settings.clone(),
+            settings,
             options: embedding_options,
         };
 
@@ -94,6 +104,13 @@ impl EmbeddingClient for EmbeddingClientWrapper {
         reqwest::Method::POST
     }
 
+    fn http_client(&self) -> reqwest::Client {
+        self.settings
+            .http_client
+            .clone()
+            .unwrap_or_else(shared_client)
+    }
+
     fn headers(&self) -> reqwest::header::HeaderMap {
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert(
@@ -1,16 +1,21 @@
 //! Language model implementation for the OpenAI Chat Completions provider.
 
+use crate::core::capabilities::CapabilitySet;
 use crate::core::capabilities::ModelName;
 use crate::core::client::LanguageModelClient;
+use crate::core::credentials::resolve_api_key;
 use crate::core::language_model::{
-    LanguageModel, LanguageModelOptions, LanguageModelResponse, LanguageModelResponseContentType,
-    LanguageModelStreamChunk, LanguageModelStreamChunkType, ProviderStream,
+    Candidate, ConstrainedOutput, LanguageModel, LanguageModelOptions, LanguageModelResponse,
+    LanguageModelResponseContentType, LanguageModelStreamChunk, LanguageModelStreamChunkType,
+    ProviderStream, ResponseMetadata, ToolCallDelta,
 };
 use crate::core::messages::AssistantMessage;
+use crate::core::pricing::ModelPricing;
 use crate::core::tools::ToolCallInfo;
 use crate::error::Result;
 use crate::providers::openai_chat_completions::OpenAIChatCompletions;
 use crate::providers::openai_chat_completions::client::{self, types};
+use crate::providers::openai_chat_completions::conversions::token_logprobs;
 use async_trait::async_trait;
 use futures::StreamExt;
 
@@ -20,28 +25,69 @@ impl<M: ModelName> LanguageModel for OpenAIChatCompletions<M> {
         self.options.model.clone()
     }
 
+    fn pricing(&self) -> Option<ModelPricing> {
+        M::PRICING
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        M::CONTEXT_WINDOW
+    }
+
+    fn max_output_tokens(&self) -> Option<usize> {
+        M::MAX_OUTPUT_TOKENS
+    }
+
+    fn capabilities(&self) -> CapabilitySet {
+        M::CAPABILITIES
+    }
+
     async fn generate_text(
         &mut self,
         options: LanguageModelOptions,
     ) -> Result<LanguageModelResponse> {
+        resolve_api_key(
+            &self.settings.credential_provider,
+            &mut self.settings.api_key,
+        )
+        .await?;
+        if let Some(base_url) = self
+            .settings
+            .credential_provider
+            .as_ref()
+            .and_then(|credentials| credentials.base_url())
+        {
+            self.settings.base_url = base_url;
+        }
         let additional_headers = options.headers.clone();
-        let mut options: client::ChatCompletionsOptions = options.into();
-        options.model = self.options.model.clone();
-        self.options = options;
+        let additional_query = options.query.clone();
+        let max_retries = options.max_retries;
+        self.options = self.request(options)?;
 
-        let response: types::ChatCompletionsResponse = self
-            .send(&self.settings.base_url, additional_headers)
+        let (response, mut metadata): (types::ChatCompletionsResponse, _) = self
+            .send_with_metadata(
+                &self.settings.base_url,
+                additional_headers,
+                additional_query,
+                max_retries,
+            )
             .await?;
+        metadata.response_id = Some(response.id);
+        metadata.model = Some(response.model);
 
-        // Convert choices to LanguageModelResponse
+        // Convert choices to LanguageModelResponse, the first choice being the
+        // response and the others additional candidates
         let mut contents = Vec::new();
+        let mut logprobs = None;
+        let mut candidates = Vec::new();
+
+        for (index, choice) in response.choices.into_iter().enumerate() {
+            let mut choice_contents = Vec::new();
 
-        for choice in response.choices {
             // Handle text content
             if let Some(text) = choice.message.content
                 && !text.is_empty()
             {
-                contents.push(LanguageModelResponseContentType::Text(text));
+                choice_contents.push(LanguageModelResponseContentType::Text(text));
             }
 
             // Handle tool calls
@@ -53,21 +99,49 @@ impl<M: ModelName> LanguageModel for OpenAIChatCompletions<M> {
                         serde_json::from_str(&tool_call.function.arguments)
                             .unwrap_or_else(|_| serde_json::Value::Object(serde_json::Map::new())),
                     );
-                    contents.push(LanguageModelResponseContentType::ToolCall(tool_info));
+                    choice_contents.push(LanguageModelResponseContentType::ToolCall(tool_info));
                 }
             }
+
+            if index == 0 {
+                contents = choice_contents;
+                logprobs = token_logprobs(choice.logprobs);
+            } else {
+                candidates.push(Candidate {
+                    contents: choice_contents,
+                    usage: None,
+                });
+            }
         }
 
         Ok(LanguageModelResponse {
             contents,
             usage: response.usage.map(|u| u.into()),
+            id: None,
+            logprobs,
+            candidates,
+            provider_metadata: response.provider_metadata,
+            metadata: Some(metadata),
         })
     }
 
     async fn stream_text(&mut self, options: LanguageModelOptions) -> Result<ProviderStream> {
+        resolve_api_key(
+            &self.settings.credential_provider,
+            &mut self.settings.api_key,
+        )
+        .await?;
+        if let Some(base_url) = self
+            .settings
+            .credential_provider
+            .as_ref()
+            .and_then(|credentials| credentials.base_url())
+        {
+            self.settings.base_url = base_url;
+        }
         let additional_headers = options.headers.clone();
-        let mut options: client::ChatCompletionsOptions = options.into();
-        options.model = self.options.model.clone();
+        let additional_query = options.query.clone();
+        let mut options = self.request(options)?;
         options.stream = Some(true);
         // Note: stream_options is not sent to maintain compatibility with
         // OpenAI-compatible providers that don't support this field (e.g., Z.ai)
@@ -76,18 +150,40 @@ impl<M: ModelName> LanguageModel for OpenAIChatCompletions<M> {
         self.options = options;
 
         let stream = self
-            .send_and_stream(&self.settings.base_url, additional_headers)
+            .send_and_stream(
+                &self.settings.base_url,
+                additional_headers,
+                additional_query,
+            )
             .await?;
 
         // State for accumulating tool calls across chunks
         use std::collections::HashMap;
         let mut accumulated_tool_calls: HashMap<u32, (String, String, String)> = HashMap::new();
+        let mut metadata_sent = false;
 
         // Map stream events to SDK stream chunks
         let stream = stream.map(move |evt_res| match evt_res {
             Ok(types::ChatCompletionsStreamEvent::Chunk(chunk)) => {
                 let mut results = Vec::new();
 
+                if !metadata_sent {
+                    metadata_sent = true;
+                    results.push(LanguageModelStreamChunk::Delta(
+                        LanguageModelStreamChunkType::ResponseMetadata(ResponseMetadata {
+                            request_id: None,
+                            response_id: Some(chunk.id.clone()),
+                            model: Some(chunk.model.clone()),
+                        }),
+                    ));
+                }
+
+                if let Some(metadata) = chunk.provider_metadata.clone() {
+                    results.push(LanguageModelStreamChunk::Delta(
+                        LanguageModelStreamChunkType::ProviderMetadata(metadata),
+                    ));
+                }
+
                 for choice in chunk.choices {
                     // Reasoning delta (for reasoning models like o1, DeepSeek R1)
                     if let Some(reasoning) = choice.delta.reasoning_content
@@ -107,6 +203,15 @@ impl<M: ModelName> LanguageModel for OpenAIChatCompletions<M> {
                         ));
                     }
 
+                    // Log probabilities of the text delta
+                    if let Some(logprobs) = token_logprobs(choice.logprobs)
+                        && !logprobs.is_empty()
+                    {
+                        results.push(LanguageModelStreamChunk::Delta(
+                            LanguageModelStreamChunkType::Logprobs(logprobs),
+                        ));
+                    }
+
                     // Accumulate tool call deltas
                     if let Some(tool_calls) = choice.delta.tool_calls {
                         for tool_call in tool_calls {
@@ -129,7 +234,11 @@ impl<M: ModelName> LanguageModel for OpenAIChatCompletions<M> {
                                 if let Some(args) = function.arguments {
                                     entry.2.push_str(&args);
                                     results.push(LanguageModelStreamChunk::Delta(
-                                        LanguageModelStreamChunkType::ToolCall(args),
+                                        LanguageModelStreamChunkType::ToolCall(ToolCallDelta {
+                                            id: entry.0.clone(),
+                                            name: entry.1.clone(),
+                                            delta: args,
+                                        }),
                                     ));
                                 }
                             }
@@ -201,3 +310,236 @@ impl<M: ModelName> LanguageModel for OpenAIChatCompletions<M> {
         Ok(Box::pin(stream))
     }
 }
+
+impl<M: ModelName> OpenAIChatCompletions<M> {
+    /// Converts the options to a request for the model of this provider.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidInput`](crate::error::Error::InvalidInput) if a
+    /// penalty is outside of the -2.0 to 2.0 range of the API, or if the
+    /// provider does not support the constrained output.
+    pub(crate) fn request(
+        &self,
+        mut options: LanguageModelOptions,
+    ) -> Result<client::ChatCompletionsOptions> {
+        options.validate_penalties(-2.0..=2.0)?;
+        options.validate_sampling(0.0..=2.0)?;
+        // Regex and grammar constraints are extensions of Together AI and Fireworks AI
+        let provider = self.settings.provider_name.to_ascii_lowercase();
+        options.resolve_constrained_output(
+            &self.settings.provider_name,
+            |output| match output {
+                ConstrainedOutput::Regex(_) => provider.starts_with("together"),
+                ConstrainedOutput::Grammar(_) => provider.starts_with("fireworks"),
+                ConstrainedOutput::JsonObject | ConstrainedOutput::JsonSchema(_) => true,
+            },
+        )?;
+        let mut request: client::ChatCompletionsOptions = options.into();
+        request.model = self.options.model.clone();
+        request.provider_options = self.provider_options.clone();
+        // Only OpenAI knows the developer role, other providers get system messages
+        if !matches!(provider.as_str(), "openai" | "openai-chat") {
+            for message in &mut request.messages {
+                if message.role == types::Role::Developer {
+                    message.role = types::Role::System;
+                }
+            }
+        }
+        if self.settings.provider_name.eq_ignore_ascii_case("mistral") {
+            request.random_seed = request.seed.take().or(self.random_seed);
+            request.safe_prompt = self.safe_prompt;
+            for message in &mut request.messages {
+                for tool_call in message.tool_calls.iter_mut().flatten() {
+                    tool_call.id = mistral_tool_call_id(&tool_call.id);
+                }
+                if let Some(id) = &mut message.tool_call_id {
+                    *id = mistral_tool_call_id(id);
+                }
+            }
+        }
+        Ok(request)
+    }
+}
+
+/// Maps a tool call id to the 9 alphanumeric characters Mistral accepts.
+///
+/// Ids of other providers or generated by the SDK are rejected by Mistral when
+/// sent back in the conversation, so they are replaced by a hash of the id,
+/// keeping the calls and their results paired.
+fn mistral_tool_call_id(id: &str) -> String {
+    const LENGTH: usize = 9;
+    const ALPHABET: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+    if id.len() == LENGTH && id.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        return id.to_string();
+    }
+
+    // FNV-1a, stable across runs unlike the std hasher
+    let mut hash = id.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    (0..LENGTH)
+        .map(|_| {
+            let c = ALPHABET[(hash % ALPHABET.len() as u64) as usize];
+            hash /= ALPHABET.len() as u64;
+            c as char
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DynamicModel;
+    use crate::core::Message;
+    use crate::core::tools::ToolResultInfo;
+    use crate::error::Error;
+
+    #[test]
+    fn test_request_validates_penalties_and_maps_seed() {
+        let mut model = OpenAIChatCompletions::<DynamicModel>::model_name("mistral-large");
+        let options = LanguageModelOptions {
+            seed: Some(7),
+            presence_penalty: Some(0.5),
+            frequency_penalty: Some(-1.0),
+            ..Default::default()
+        };
+
+        let request = model.request(options.clone()).unwrap();
+        assert_eq!(request.seed, Some(7));
+        assert_eq!(request.random_seed, None);
+        assert_eq!(request.presence_penalty, Some(0.5));
+        assert_eq!(request.frequency_penalty, Some(-1.0));
+
+        model.settings.provider_name = "Mistral".to_string();
+        let request = model.request(options.clone()).unwrap();
+        assert_eq!(request.seed, None);
+        assert_eq!(request.random_seed, Some(7));
+
+        let result = model.request(LanguageModelOptions {
+            presence_penalty: Some(2.5),
+            ..options
+        });
+        assert!(matches!(result, Err(Error::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_request_maps_mistral_options_and_tool_call_ids() {
+        let mut model = OpenAIChatCompletions::<DynamicModel>::model_name("mistral-large");
+        model.settings.provider_name = "mistral".to_string();
+        model.safe_prompt = Some(true);
+        model.random_seed = Some(3);
+
+        let messages = ["call_5f2a9d1c8b7e4a3f", "D681PevKs"]
+            .into_iter()
+            .flat_map(|id| {
+                let mut call = ToolCallInfo::new("weather");
+                call.id(id);
+                let mut result = ToolResultInfo::new("weather");
+                result.id(id);
+                result.output(serde_json::json!("sunny"));
+                [
+                    Message::Assistant(AssistantMessage::new(
+                        LanguageModelResponseContentType::ToolCall(call),
+                        None,
+                    )),
+                    Message::Tool(result),
+                ]
+            })
+            .map(Into::into)
+            .collect();
+
+        let request = model
+            .request(LanguageModelOptions {
+                messages,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(request.safe_prompt, Some(true));
+        assert_eq!(request.random_seed, Some(3));
+
+        let ids: Vec<&str> = request
+            .messages
+            .iter()
+            .filter_map(|message| match &message.tool_calls {
+                Some(calls) => Some(calls[0].id.as_str()),
+                None => message.tool_call_id.as_deref(),
+            })
+            .collect();
+        assert_eq!(ids.len(), 4);
+        assert_eq!(ids[0], ids[1]);
+        assert_eq!(ids[0].len(), 9);
+        assert!(ids[0].bytes().all(|b| b.is_ascii_alphanumeric()));
+        assert_eq!(ids[2..], ["D681PevKs", "D681PevKs"]);
+
+        let request = model
+            .request(LanguageModelOptions {
+                seed: Some(7),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(request.random_seed, Some(7));
+    }
+
+    #[test]
+    fn test_request_maps_constrained_output() {
+        let mut model = OpenAIChatCompletions::<DynamicModel>::model_name("model");
+        let options = |output: ConstrainedOutput| LanguageModelOptions {
+            constrained_output: Some(output),
+            ..Default::default()
+        };
+        let response_format = |request: client::ChatCompletionsOptions| {
+            serde_json::to_value(request).unwrap()["response_format"].clone()
+        };
+
+        let request = model
+            .request(options(ConstrainedOutput::JsonObject))
+            .unwrap();
+        assert_eq!(
+            response_format(request),
+            serde_json::json!({ "type": "json_object" })
+        );
+
+        let regex = ConstrainedOutput::Regex("(yes|no)".to_string());
+        let grammar = ConstrainedOutput::Grammar("root ::= \"yes\" | \"no\"".to_string());
+        assert!(matches!(
+            model.request(options(regex.clone())),
+            Err(Error::InvalidInput(_))
+        ));
+
+        model.settings.provider_name = "Together AI".to_string();
+        let request = model.request(options(regex)).unwrap();
+        assert_eq!(
+            response_format(request),
+            serde_json::json!({ "type": "regex_object", "pattern": "(yes|no)" })
+        );
+        assert!(matches!(
+            model.request(options(grammar.clone())),
+            Err(Error::InvalidInput(_))
+        ));
+
+        model.settings.provider_name = "FireworksAi".to_string();
+        let request = model.request(options(grammar)).unwrap();
+        assert_eq!(
+            response_format(request),
+            serde_json::json!({ "type": "grammar", "grammar": "root ::= \"yes\" | \"no\"" })
+        );
+    }
+
+    #[test]
+    fn test_developer_role_is_only_sent_to_openai() {
+        let mut model = OpenAIChatCompletions::<DynamicModel>::model_name("model");
+        let options = LanguageModelOptions {
+            messages: vec![Message::Developer("Answer in French.".to_string()).into()],
+            ..Default::default()
+        };
+
+        let request = model.request(options.clone()).unwrap();
+        assert_eq!(request.messages[0].role, types::Role::Developer);
+
+        model.settings.provider_name = "Groq".to_string();
+        let request = model.request(options).unwrap();
+        assert_eq!(request.messages[0].role, types::Role::System);
+    }
+}
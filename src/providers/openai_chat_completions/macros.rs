@@ -38,6 +38,18 @@ macro_rules! openai_compatible_settings {
                 /// The API key for authentication.
                 pub api_key: String,
 
+                /// Credentials resolving the API key before each request, in place of
+                /// `api_key`.
+                pub credential_provider: Option<$crate::core::credentials::Credentials>,
+
+                /// The HTTP client sending the requests. Requests share one pooled client
+                /// if it is not set.
+                pub http_client: Option<reqwest::Client>,
+
+                /// Network settings of the HTTP client built for the provider when no
+                /// client is set, such as a proxy or certificate authorities.
+                pub http: $crate::core::client::HttpSettings,
+
                 /// Custom API path override.
                 pub path: Option<String>,
             }
@@ -48,6 +60,9 @@ macro_rules! openai_compatible_settings {
                         provider_name: $provider_display_name.to_string(),
                         base_url: $default_base_url.to_string(),
                         api_key: std::env::var($api_key_env).unwrap_or_default(),
+                        credential_provider: None,
+                        http_client: None,
+                        http: $crate::core::client::HttpSettings::default(),
                         path: None,
                     }
                 }
@@ -93,6 +108,26 @@ macro_rules! openai_compatible_language_model {
                     self.inner.name()
                 }
 
+                /// Returns the pricing of the model.
+                fn pricing(&self) -> Option<$crate::core::pricing::ModelPricing> {
+                    self.inner.pricing()
+                }
+
+                /// Returns the context window of the model.
+                fn context_window(&self) -> Option<usize> {
+                    self.inner.context_window()
+                }
+
+                /// Returns the maximum output tokens of the model.
+                fn max_output_tokens(&self) -> Option<usize> {
+                    self.inner.max_output_tokens()
+                }
+
+                /// Returns the capabilities of the model.
+                fn capabilities(&self) -> $crate::core::capabilities::CapabilitySet {
+                    self.inner.capabilities()
+                }
+
                 #[doc = concat!("Generates text using the ", stringify!($provider_struct), " provider.")]
                 async fn generate_text(
                     &mut self,
@@ -159,6 +194,7 @@ macro_rules! openai_compatible_embedding_model {
 /// * `$builder_struct` - The name of the builder struct (e.g., `DeepSeekBuilder`)
 /// * `$settings_struct` - The name of the settings struct (e.g., `DeepSeekProviderSettings`)
 /// * `$example_model` - Example model identifier for docs (e.g., `"deepseek-chat"`)
+/// * `$headers` - Optional headers sent with every request (e.g., `headers = editor_headers()`)
 #[macro_export]
 macro_rules! openai_compatible_provider {
     (
@@ -166,6 +202,7 @@ macro_rules! openai_compatible_provider {
         $builder_struct:ident,
         $settings_struct:ident,
         $example_model:literal
+        $(, headers = $headers:expr)?
     ) => {
         use $crate::Error;
         use $crate::core::DynamicModel;
@@ -188,6 +225,20 @@ macro_rules! openai_compatible_provider {
             pub fn builder() -> $builder_struct<M> {
                 $builder_struct::default()
             }
+
+            #[doc = concat!(
+                "Creates a ", stringify!($provider_struct), " provider builder configured from the environment.\n\n",
+                "Reads the API key from the variable of the provider and the base URL from ",
+                "`<PROVIDER>_BASE_URL`, such as `MISTRAL_BASE_URL`. Unset variables keep the defaults."
+            )]
+            pub fn from_env() -> $builder_struct<M> {
+                let mut builder = Self::builder();
+                let prefix = $crate::core::utils::env_prefix(stringify!($provider_struct));
+                if let Some(base_url) = $crate::core::utils::env_var(&format!("{prefix}_BASE_URL")) {
+                    builder = builder.base_url(base_url);
+                }
+                builder
+            }
         }
 
         impl $provider_struct<DynamicModel> {
@@ -207,6 +258,10 @@ macro_rules! openai_compatible_provider {
             pub fn model_name(name: impl Into<String>) -> Self {
                 let settings = $settings_struct::default();
                 let inner = OpenAIChatCompletions::<DynamicModel>::model_name(name);
+                $(
+                    let mut inner = inner;
+                    inner.settings.headers = $headers;
+                )?
 
                 $provider_struct { settings, inner }
             }
@@ -234,6 +289,7 @@ macro_rules! openai_compatible_provider {
                 inner.settings.base_url = settings.base_url.clone();
                 inner.settings.api_key = settings.api_key.clone();
                 inner.settings.path = settings.path.clone();
+                $(inner.settings.headers = $headers;)?
 
                 Self { settings, inner }
             }
@@ -282,6 +338,39 @@ macro_rules! openai_compatible_provider {
                 self
             }
 
+            #[doc = concat!(
+                "Sets the credentials resolving the API key of the ", stringify!($provider_struct), " provider ",
+                "before each request, to fetch it from a secret manager or rotate it. Takes precedence over `api_key`."
+            )]
+            pub fn credential_provider(
+                mut self,
+                credentials: impl $crate::core::credentials::CredentialProvider + 'static,
+            ) -> Self {
+                let credentials = $crate::core::credentials::Credentials::new(credentials);
+                self.settings.credential_provider = Some(credentials.clone());
+                self.inner.settings.credential_provider = Some(credentials);
+                self
+            }
+
+            #[doc = concat!(
+                "Sets the HTTP client sending the requests of the ", stringify!($provider_struct), " provider, ",
+                "to share its pool of connections or configure it. Requests share one pooled client by default."
+            )]
+            pub fn http_client(mut self, client: reqwest::Client) -> Self {
+                self.settings.http_client = Some(client.clone());
+                self.inner.settings.http_client = Some(client);
+                self
+            }
+
+            #[doc = concat!(
+                "Sets the network settings of the HTTP client of the ", stringify!($provider_struct), " provider, ",
+                "such as a proxy or certificate authorities. Ignored if an HTTP client is set."
+            )]
+            pub fn http_settings(mut self, http: $crate::core::client::HttpSettings) -> Self {
+                self.settings.http = http;
+                self
+            }
+
             #[doc = concat!(
                 "Sets a custom API path for the ", stringify!($provider_struct), " provider, ",
                 "overriding the default \"chat/completions\"."
@@ -304,10 +393,18 @@ macro_rules! openai_compatible_provider {
                 let base_url = validate_base_url(&self.settings.base_url)?;
 
                 // check api key exists
-                if self.settings.api_key.is_empty() {
+                if self.settings.api_key.is_empty() && self.settings.credential_provider.is_none() {
                     return Err(Error::MissingField("api_key".to_string()));
                 }
 
+                // Build the client of the network settings
+                let http_client = self
+                    .settings
+                    .http
+                    .resolve_client(self.settings.http_client.take())?;
+                self.settings.http_client = http_client.clone();
+                self.inner.settings.http_client = http_client;
+
                 // Update the inner provider with the validated base_url
                 self.inner.settings.base_url = base_url.to_string();
                 self.settings.base_url = base_url.to_string();
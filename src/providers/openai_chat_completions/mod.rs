@@ -28,6 +28,12 @@ pub struct OpenAIChatCompletions<M: ModelName> {
     pub settings: OpenAIChatCompletionsSettings,
     /// Request options for the API call
     pub(crate) options: ChatCompletionsOptions,
+    /// Provider-specific options sent with every request.
+    pub(crate) provider_options: Option<serde_json::Value>,
+    /// Whether Mistral injects its safety prompt into every request.
+    pub(crate) safe_prompt: Option<bool>,
+    /// The Mistral seed used by requests without a seed of their own.
+    pub(crate) random_seed: Option<u32>,
     _phantom: std::marker::PhantomData<M>,
 }
 
@@ -43,6 +49,9 @@ impl<M: ModelName> Default for OpenAIChatCompletions<M> {
         Self {
             settings,
             options,
+            provider_options: None,
+            safe_prompt: None,
+            random_seed: None,
             _phantom: std::marker::PhantomData,
         }
     }
@@ -71,6 +80,9 @@ impl OpenAIChatCompletions<DynamicModel> {
         Self {
             settings,
             options,
+            provider_options: None,
+            safe_prompt: None,
+            random_seed: None,
             _phantom: std::marker::PhantomData,
         }
     }
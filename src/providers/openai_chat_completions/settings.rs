@@ -1,5 +1,7 @@
 //! Settings for the OpenAI Chat Completions API compatible providers.
 
+use crate::core::client::HttpSettings;
+use crate::core::credentials::Credentials;
 use derive_builder::Builder;
 
 #[derive(Debug, Clone, Builder)]
@@ -18,9 +20,24 @@ pub struct OpenAIChatCompletionsSettings {
     /// The API key for authentication
     pub api_key: String,
 
+    /// Credentials resolving the API key before each request, in place of
+    /// `api_key`.
+    pub credential_provider: Option<Credentials>,
+
+    /// The HTTP client sending the requests. Requests share one pooled client
+    /// if it is not set.
+    pub http_client: Option<reqwest::Client>,
+
+    /// Network settings of the HTTP client built for the provider when no
+    /// client is set, such as a proxy or certificate authorities.
+    pub http: HttpSettings,
+
     /// Custom API path override. When set, this path is used instead of the
     /// default "chat/completions".
     pub path: Option<String>,
+
+    /// Headers sent with every request, such as the headers of a gateway.
+    pub headers: reqwest::header::HeaderMap,
 }
 
 impl Default for OpenAIChatCompletionsSettings {
@@ -29,7 +46,11 @@ impl Default for OpenAIChatCompletionsSettings {
             provider_name: "openai-chat".to_string(),
             base_url: "https://api.openai.com/v1".to_string(),
             api_key: std::env::var("OPENAI_API_KEY").unwrap_or_default(),
+            credential_provider: None,
+            http_client: None,
+            http: HttpSettings::default(),
             path: None,
+            headers: reqwest::header::HeaderMap::new(),
         }
     }
 }
@@ -20,6 +20,11 @@ impl<M: ModelName> EmbeddingModel for OpenAICompatible<M> {
             settings: crate::providers::openai::settings::OpenAIProviderSettings {
                 base_url: self.inner.settings.base_url.clone(),
                 api_key: self.inner.settings.api_key.clone(),
+                credential_provider: self.inner.settings.credential_provider.clone(),
+                organization: None,
+                project: None,
+                http_client: self.inner.settings.http_client.clone(),
+                http: self.inner.settings.http.clone(),
                 provider_name: self.inner.settings.provider_name.clone(),
                 path: self.inner.settings.path.clone(),
             },
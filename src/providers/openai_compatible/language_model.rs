@@ -6,8 +6,9 @@ use crate::{
     Result,
     core::{
         LanguageModel,
-        capabilities::ModelName,
+        capabilities::{CapabilitySet, ModelName},
         language_model::{LanguageModelOptions, LanguageModelResponse, ProviderStream},
+        pricing::ModelPricing,
     },
     providers::openai_compatible::OpenAICompatible,
 };
@@ -19,6 +20,26 @@ impl<M: ModelName> LanguageModel for OpenAICompatible<M> {
         self.inner.name()
     }
 
+    /// Returns the pricing of the model.
+    fn pricing(&self) -> Option<ModelPricing> {
+        self.inner.pricing()
+    }
+
+    /// Returns the context window of the model.
+    fn context_window(&self) -> Option<usize> {
+        self.inner.context_window()
+    }
+
+    /// Returns the maximum output tokens of the model.
+    fn max_output_tokens(&self) -> Option<usize> {
+        self.inner.max_output_tokens()
+    }
+
+    /// Returns the capabilities of the model.
+    fn capabilities(&self) -> CapabilitySet {
+        self.inner.capabilities()
+    }
+
     /// Generates text using the OpenAI-compatible provider.
     async fn generate_text(
         &mut self,
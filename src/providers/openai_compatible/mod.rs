@@ -26,7 +26,9 @@ pub mod settings;
 use crate::Error;
 use crate::core::DynamicModel;
 use crate::core::capabilities::ModelName;
-use crate::core::utils::validate_base_url;
+use crate::core::client::HttpSettings;
+use crate::core::credentials::{CredentialProvider, Credentials};
+use crate::core::utils::{env_var, validate_base_url};
 use crate::error::Result;
 use crate::providers::openai_chat_completions::OpenAIChatCompletions;
 use crate::providers::openai_compatible::settings::OpenAICompatibleSettings;
@@ -62,6 +64,19 @@ impl<M: ModelName> OpenAICompatible<M> {
     pub fn builder() -> OpenAICompatibleBuilder<M> {
         OpenAICompatibleBuilder::default()
     }
+
+    /// Creates an OpenAICompatible provider builder configured from the environment.
+    ///
+    /// Reads the API key from `OPENAI_API_KEY` and the base URL from
+    /// `OPENAI_COMPATIBLE_BASE_URL`.
+    /// Unset variables keep the defaults.
+    pub fn from_env() -> OpenAICompatibleBuilder<M> {
+        let mut builder = Self::builder();
+        if let Some(base_url) = env_var("OPENAI_COMPATIBLE_BASE_URL") {
+            builder = builder.base_url(base_url);
+        }
+        builder
+    }
 }
 
 impl OpenAICompatible<DynamicModel> {
@@ -167,6 +182,54 @@ impl<M: ModelName> OpenAICompatibleBuilder<M> {
         self
     }
 
+    /// Sets the credentials resolving the API key before each request, to fetch
+    /// it from a secret manager or rotate it. Takes precedence over `api_key`.
+    ///
+    /// # Parameters
+    ///
+    /// * `credentials` - The source of the API key.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the credentials set.
+    pub fn credential_provider(mut self, credentials: impl CredentialProvider + 'static) -> Self {
+        let credentials = Credentials::new(credentials);
+        self.settings.credential_provider = Some(credentials.clone());
+        self.inner.settings.credential_provider = Some(credentials);
+        self
+    }
+
+    /// Sets the HTTP client sending the requests, to share its pool of connections
+    /// or configure it. Requests share one pooled client by default.
+    ///
+    /// # Parameters
+    ///
+    /// * `client` - The `reqwest` client sending the requests.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the HTTP client set.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.settings.http_client = Some(client.clone());
+        self.inner.settings.http_client = Some(client);
+        self
+    }
+
+    /// Sets the network settings of the HTTP client, such as a proxy or
+    /// certificate authorities. Ignored if an HTTP client is set.
+    ///
+    /// # Parameters
+    ///
+    /// * `http` - The network settings.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the network settings set.
+    pub fn http_settings(mut self, http: HttpSettings) -> Self {
+        self.settings.http = http;
+        self
+    }
+
     /// Sets a custom API path, overriding the default "chat/completions".
     pub fn path(mut self, path: impl Into<String>) -> Self {
         let p = Some(path.into());
@@ -187,10 +250,18 @@ impl<M: ModelName> OpenAICompatibleBuilder<M> {
         let base_url = validate_base_url(&self.settings.base_url)?;
 
         // check api key exists
-        if self.settings.api_key.is_empty() {
+        if self.settings.api_key.is_empty() && self.settings.credential_provider.is_none() {
             return Err(Error::MissingField("api_key".to_string()));
         }
 
+        // Build the client of the network settings
+        let http_client = self
+            .settings
+            .http
+            .resolve_client(self.settings.http_client.take())?;
+        self.settings.http_client = http_client.clone();
+        self.inner.settings.http_client = http_client;
+
         // Update the inner provider with the validated base_url
         self.inner.settings.base_url = base_url.to_string();
         self.settings.base_url = base_url.to_string();
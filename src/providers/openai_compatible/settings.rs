@@ -1,5 +1,7 @@
 //! Defines the settings for the OpenAI-compatible provider.
 
+use crate::core::client::HttpSettings;
+use crate::core::credentials::Credentials;
 use derive_builder::Builder;
 
 /// Settings for the OpenAI-compatible provider (delegates to OpenAI).
@@ -15,6 +17,18 @@ pub struct OpenAICompatibleSettings {
     /// The API key for the OpenAI-compatible API.
     pub api_key: String,
 
+    /// Credentials resolving the API key before each request, in place of
+    /// `api_key`.
+    pub credential_provider: Option<Credentials>,
+
+    /// The HTTP client sending the requests. Requests share one pooled client
+    /// if it is not set.
+    pub http_client: Option<reqwest::Client>,
+
+    /// Network settings of the HTTP client built for the provider when no
+    /// client is set, such as a proxy or certificate authorities.
+    pub http: HttpSettings,
+
     /// Custom API path override.
     pub path: Option<String>,
 }
@@ -26,6 +40,9 @@ impl Default for OpenAICompatibleSettings {
             provider_name: "OpenAICompatible".to_string(),
             base_url: "https://api.openai.com/v1".to_string(),
             api_key: std::env::var("OPENAI_API_KEY").unwrap_or_default(),
+            credential_provider: None,
+            http_client: None,
+            http: HttpSettings::default(),
             path: None,
         }
     }
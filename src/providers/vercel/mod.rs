@@ -28,3 +28,117 @@ crate::openai_compatible_language_model!(Vercel);
 
 // Generate the embedding model implementation
 crate::openai_compatible_embedding_model!(Vercel);
+
+impl<M: ModelName> VercelBuilder<M> {
+    /// Sets the order in which the gateway tries the providers of the model,
+    /// e.g. `["bedrock", "anthropic"]`.
+    pub fn gateway_order<I, S>(self, providers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.gateway_option("order", providers)
+    }
+
+    /// Restricts the gateway to the given providers of the model.
+    pub fn gateway_only<I, S>(self, providers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.gateway_option("only", providers)
+    }
+
+    /// Sets an option of the `gateway` provider options sent with every request.
+    fn gateway_option<I, S>(mut self, key: &str, providers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let providers: Vec<String> = providers.into_iter().map(Into::into).collect();
+        let options = self
+            .inner
+            .provider_options
+            .get_or_insert_with(|| serde_json::json!({ "gateway": {} }));
+        options["gateway"][key] = providers.into();
+        self
+    }
+}
+
+/// Metadata the Vercel AI Gateway returns with a response, read from its
+/// [`provider_metadata`](crate::core::language_model::LanguageModelOptions::provider_metadata).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GatewayMetadata {
+    /// The cost of the request in US dollars, as billed by the gateway.
+    pub cost: Option<f64>,
+    /// How the gateway routed the request, such as the provider that served it
+    /// and the attempts made.
+    pub routing: Option<serde_json::Value>,
+}
+
+impl GatewayMetadata {
+    /// Reads the gateway metadata from the provider metadata of a response.
+    ///
+    /// Returns `None` if the response has no gateway metadata.
+    pub fn from_provider_metadata(metadata: &serde_json::Value) -> Option<Self> {
+        let gateway = metadata.get("gateway")?;
+        // The cost is sent as a decimal string
+        let cost = gateway.get("cost").and_then(|cost| match cost {
+            serde_json::Value::String(cost) => cost.parse().ok(),
+            cost => cost.as_f64(),
+        });
+        Some(Self {
+            cost,
+            routing: gateway.get("routing").cloned(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LanguageModel;
+    use crate::core::language_model::LanguageModelOptions;
+
+    #[test]
+    fn test_gateway_options_are_sent_as_provider_options() {
+        let model = Vercel::<DynamicModel>::builder()
+            .model_name("anthropic/claude-sonnet-4")
+            .api_key("key")
+            .gateway_order(["bedrock", "anthropic"])
+            .gateway_only(["bedrock", "anthropic"])
+            .build()
+            .unwrap();
+
+        let request = model
+            .inner
+            .request(LanguageModelOptions::default())
+            .unwrap();
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            body["providerOptions"],
+            serde_json::json!({
+                "gateway": {
+                    "order": ["bedrock", "anthropic"],
+                    "only": ["bedrock", "anthropic"],
+                }
+            })
+        );
+        assert_eq!(model.name(), "anthropic/claude-sonnet-4");
+    }
+
+    #[test]
+    fn test_gateway_metadata_is_read_from_provider_metadata() {
+        let metadata = serde_json::json!({
+            "gateway": {
+                "routing": { "resolvedProvider": "bedrock" },
+                "cost": "0.0045",
+            }
+        });
+        let gateway = GatewayMetadata::from_provider_metadata(&metadata).unwrap();
+        assert_eq!(gateway.cost, Some(0.0045));
+        assert_eq!(gateway.routing.unwrap()["resolvedProvider"], "bedrock");
+
+        assert!(GatewayMetadata::from_provider_metadata(&serde_json::json!({})).is_none());
+    }
+}
@@ -0,0 +1,38 @@
+//! Capabilities for Vertex AI models.
+//!
+//! This module defines model types and their capabilities for the Vertex AI provider.
+//! Users can implement additional traits on custom models.
+
+use crate::core::capabilities::*;
+use crate::model_capabilities;
+use crate::providers::vertex_ai::VertexAI;
+
+model_capabilities! {
+    provider: VertexAI,
+    models: {
+        Gemini20Flash {
+            model_name: "gemini-2.0-flash",
+            constructor_name: gemini_2_0_flash,
+            display_name: "Gemini 2.0 Flash",
+            capabilities: [AudioInputSupport, ImageInputSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport, VideoInputSupport]
+        },
+        Gemini25Flash {
+            model_name: "gemini-2.5-flash",
+            constructor_name: gemini_2_5_flash,
+            display_name: "Gemini 2.5 Flash",
+            capabilities: [AudioInputSupport, ImageInputSupport, ReasoningSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport, VideoInputSupport]
+        },
+        Gemini25FlashLite {
+            model_name: "gemini-2.5-flash-lite",
+            constructor_name: gemini_2_5_flash_lite,
+            display_name: "Gemini 2.5 Flash Lite",
+            capabilities: [AudioInputSupport, ImageInputSupport, ReasoningSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport, VideoInputSupport]
+        },
+        Gemini25Pro {
+            model_name: "gemini-2.5-pro",
+            constructor_name: gemini_2_5_pro,
+            display_name: "Gemini 2.5 Pro",
+            capabilities: [AudioInputSupport, ImageInputSupport, ReasoningSupport, StructuredOutputSupport, TextInputSupport, TextOutputSupport, ToolCallSupport, VideoInputSupport]
+        },
+    }
+}
@@ -0,0 +1,174 @@
+//! Language model implementation for the Vertex AI provider.
+//!
+//! Requests and responses are handled by the Gemini client of the Google provider,
+//! with the access token of the request added as a bearer token.
+
+use crate::core::capabilities::CapabilitySet;
+use crate::core::capabilities::ModelName;
+use crate::core::client::shared_client;
+use crate::core::language_model::{
+    LanguageModel, LanguageModelOptions, LanguageModelResponse, ProviderStream,
+};
+use crate::core::pricing::ModelPricing;
+use crate::error::Result;
+use crate::providers::vertex_ai::VertexAI;
+use crate::providers::vertex_ai::auth::ServiceAccountKey;
+use async_trait::async_trait;
+
+impl<M: ModelName> VertexAI<M> {
+    /// Returns the access token of the next request, if the provider does not use
+    /// an API key.
+    async fn access_token(&self) -> Result<Option<String>> {
+        if let Some(token) = &self.settings.access_token {
+            return Ok(Some(token.clone()));
+        }
+
+        let key = match (
+            &self.settings.service_account,
+            &self.settings.credentials_file,
+        ) {
+            (Some(key), _) => key.clone(),
+            (None, Some(path)) if self.settings.api_key.is_empty() => {
+                ServiceAccountKey::from_file(path)?
+            }
+            _ => return Ok(None),
+        };
+        let client = self
+            .settings
+            .http_client
+            .clone()
+            .unwrap_or_else(shared_client);
+        self.tokens.token(&client, &key).await.map(Some)
+    }
+
+    /// Adds the authorization header to the options of a request.
+    async fn authorize(&self, options: &mut LanguageModelOptions) -> Result<()> {
+        if let Some(token) = self.access_token().await? {
+            options
+                .headers
+                .get_or_insert_with(Default::default)
+                .insert("Authorization".to_string(), format!("Bearer {token}"));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<M: ModelName> LanguageModel for VertexAI<M> {
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn pricing(&self) -> Option<ModelPricing> {
+        M::PRICING
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        M::CONTEXT_WINDOW
+    }
+
+    fn max_output_tokens(&self) -> Option<usize> {
+        M::MAX_OUTPUT_TOKENS
+    }
+
+    fn capabilities(&self) -> CapabilitySet {
+        M::CAPABILITIES
+    }
+
+    async fn generate_text(
+        &mut self,
+        mut options: LanguageModelOptions,
+    ) -> Result<LanguageModelResponse> {
+        self.authorize(&mut options).await?;
+        self.inner.generate_text(options).await
+    }
+
+    async fn stream_text(&mut self, mut options: LanguageModelOptions) -> Result<ProviderStream> {
+        self.authorize(&mut options).await?;
+        self.inner.stream_text(options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DynamicModel;
+    use crate::core::client::test_server::{TestResponse, serve};
+    use crate::core::language_model::LanguageModelResponseContentType;
+    use crate::providers::vertex_ai::auth::tests::test_key;
+
+    /// Serves a single JSON response on a local port and returns its address,
+    #[tokio::test]
+    async fn test_generate_text_uses_service_account_token() {
+        let (token_url, mut token_request) = serve(vec![TestResponse::json(
+            r#"{"access_token":"ya29.test","expires_in":3600,"token_type":"Bearer"}"#,
+        )])
+        .await;
+        let (base_url, mut request) = serve(vec![TestResponse::json(
+            r#"{"candidates":[{"content":{"role":"model","parts":[{"text":"Hello"}]}}]}"#,
+        )])
+        .await;
+
+        let mut model = VertexAI::<DynamicModel>::builder()
+            .model_name("gemini-2.5-flash")
+            .location("europe-west4")
+            .base_url(base_url)
+            .api_key("")
+            .service_account_key(test_key(&format!("{token_url}/token")))
+            .build()
+            .unwrap();
+        assert_eq!(model.settings.project, "test-project");
+
+        let response = model
+            .generate_text(LanguageModelOptions::default())
+            .await
+            .unwrap();
+        assert!(matches!(
+            &response.contents[..],
+            [LanguageModelResponseContentType::Text(text)] if text == "Hello"
+        ));
+
+        let token_request = token_request.recv().await.unwrap();
+        assert!(token_request.starts_with("POST /token "));
+        assert!(token_request.contains(
+            "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Ajwt-bearer&assertion=ey"
+        ));
+
+        let request = request.recv().await.unwrap().to_lowercase();
+        assert!(request.starts_with(
+            "post /v1/projects/test-project/locations/europe-west4/publishers/google/models/gemini-2.5-flash:generatecontent "
+        ));
+        assert!(request.contains("authorization: bearer ya29.test"));
+        assert!(!request.contains("x-goog-api-key"));
+    }
+
+    #[test]
+    fn test_build_requires_credentials_and_project() {
+        let builder = || {
+            VertexAI::<DynamicModel>::builder()
+                .model_name("gemini-2.5-flash")
+                .api_key("")
+                .project("")
+        };
+        let missing = |field: &str| Err(crate::Error::MissingField(field.to_string()));
+
+        let mut without_credentials = builder();
+        without_credentials.settings.access_token = None;
+        without_credentials.settings.service_account = None;
+        without_credentials.settings.credentials_file = None;
+        assert_eq!(
+            without_credentials.build().map(|_| ()),
+            missing("credentials")
+        );
+
+        let with_token = builder().access_token("ya29.test");
+        assert_eq!(with_token.build().map(|_| ()), missing("project"));
+
+        // Express mode needs no project
+        let express = builder().api_key("key").build().unwrap();
+        assert_eq!(
+            express.inner.lm_options.models_path.as_deref(),
+            Some("/v1/publishers/google/models")
+        );
+    }
+}
@@ -0,0 +1,309 @@
+//! This module provides the Vertex AI provider, which implements the `LanguageModel`
+//! trait for Gemini models served by Google Cloud Vertex AI.
+//!
+//! Unlike the [`Google`](crate::providers::Google) provider, which uses the Gemini
+//! API and an API key, requests go to the regional endpoint of a Google Cloud
+//! project and are authenticated with OAuth2 access tokens, generated from a
+//! service account key when one is configured.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//!# #[cfg(feature = "vertex-ai")]
+//!# {
+//! use aisdk::core::LanguageModelRequest;
+//! use aisdk::providers::VertexAI;
+//! use aisdk::providers::vertex_ai::Gemini25Flash;
+//!
+//! # async fn run() -> aisdk::Result<()> {
+//! let vertex = VertexAI::<Gemini25Flash>::builder()
+//!     .project("my-project")
+//!     .location("europe-west4")
+//!     .credentials_file("service-account.json")
+//!     .build()?;
+//!
+//! let response = LanguageModelRequest::builder()
+//!     .model(vertex)
+//!     .prompt("Why is the sky blue?")
+//!     .build()
+//!     .generate_text()
+//!     .await?;
+//! # Ok(())
+//! # }
+//!# }
+//! ```
+
+pub mod auth;
+pub mod capabilities;
+pub mod language_model;
+pub mod settings;
+
+use crate::core::DynamicModel;
+use crate::core::capabilities::ModelName;
+use crate::core::client::HttpSettings;
+use crate::core::utils::{env_var, validate_base_url};
+use crate::error::{Error, Result};
+use crate::providers::google::Google;
+use crate::providers::vertex_ai::auth::{ServiceAccountKey, TokenCache};
+use crate::providers::vertex_ai::settings::{VertexAIProviderSettings, regional_base_url};
+use std::sync::Arc;
+
+/// The Vertex AI provider.
+#[derive(Debug, Clone)]
+pub struct VertexAI<M: ModelName> {
+    /// Configuration settings for the Vertex AI provider.
+    pub settings: VertexAIProviderSettings,
+    pub(crate) inner: Google<M>,
+    pub(crate) tokens: Arc<TokenCache>,
+}
+
+impl<M: ModelName> VertexAI<M> {
+    /// Vertex AI provider setting builder.
+    pub fn builder() -> VertexAIBuilder<M> {
+        VertexAIBuilder::default()
+    }
+
+    /// Creates a Vertex AI provider builder configured from the environment.
+    ///
+    /// Reads the project from `GOOGLE_VERTEX_PROJECT`, the location from
+    /// `GOOGLE_VERTEX_LOCATION`, the credentials from `GOOGLE_APPLICATION_CREDENTIALS`
+    /// or `GOOGLE_VERTEX_API_KEY`, and the base URL from `GOOGLE_VERTEX_BASE_URL`.
+    /// Unset variables keep the defaults.
+    pub fn from_env() -> VertexAIBuilder<M> {
+        let mut builder = Self::builder();
+        if let Some(base_url) = env_var("GOOGLE_VERTEX_BASE_URL") {
+            builder = builder.base_url(base_url);
+        }
+        builder
+    }
+
+    /// Creates the provider, configuring the Gemini client for the Vertex AI endpoint.
+    fn from_settings(settings: VertexAIProviderSettings, model: String) -> Self {
+        let mut inner = Google::<M>::default();
+        inner.settings.provider_name = settings.provider_name.clone();
+        inner.settings.base_url = settings.base_url.clone();
+        inner.settings.api_key = settings.api_key.clone();
+        inner.settings.http_client = settings.http_client.clone();
+        inner.lm_options.model = model;
+        inner.lm_options.models_path = Some(settings.models_path());
+
+        Self {
+            settings,
+            inner,
+            tokens: Arc::default(),
+        }
+    }
+}
+
+impl VertexAI<DynamicModel> {
+    /// Creates a Vertex AI provider with a dynamic model name using default settings.
+    ///
+    /// This allows you to specify the model name as a string rather than
+    /// using methods like `VertexAI::gemini_2_5_pro()`, etc.
+    ///
+    /// **WARNING**: when using `DynamicModel`, model capabilities are not validated.
+    /// This means there is no compile-time guarantee that the model supports requested features.
+    ///
+    /// For custom configuration (project, location, credentials, etc.), use the builder pattern:
+    /// `VertexAI::<DynamicModel>::builder().model_name(...).project(...).build()`
+    ///
+    /// # Parameters
+    ///
+    /// * `model_name` - The model identifier (e.g., "gemini-2.5-pro", "gemini-2.5-flash")
+    ///
+    /// # Returns
+    ///
+    /// A configured `VertexAI<DynamicModel>` provider instance with default settings.
+    pub fn model_name(name: impl Into<String>) -> Self {
+        Self::from_settings(VertexAIProviderSettings::default(), name.into())
+    }
+}
+
+impl<M: ModelName> Default for VertexAI<M> {
+    /// Creates a new Vertex AI provider with default settings.
+    fn default() -> Self {
+        Self::from_settings(
+            VertexAIProviderSettings::default(),
+            M::MODEL_NAME.to_string(),
+        )
+    }
+}
+
+/// Vertex AI Provider Builder
+pub struct VertexAIBuilder<M: ModelName> {
+    settings: VertexAIProviderSettings,
+    model: String,
+    _phantom: std::marker::PhantomData<M>,
+}
+
+impl<M: ModelName> Default for VertexAIBuilder<M> {
+    /// Creates a new Vertex AI provider builder with default settings.
+    fn default() -> Self {
+        Self {
+            settings: VertexAIProviderSettings::default(),
+            model: M::MODEL_NAME.to_string(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl VertexAIBuilder<DynamicModel> {
+    /// Sets the model name from a string. e.g., "gemini-2.5-pro", "gemini-2.5-flash"
+    ///
+    /// **WARNING**: when using `DynamicModel`, model capabilities are not validated.
+    /// This means there is no compile-time guarantee that the model supports requested features.
+    ///
+    /// For compile-time model validation, use the constructor methods like `VertexAI::gemini_2_5_pro()`.
+    ///
+    /// # Parameters
+    ///
+    /// * `model_name` - The model identifier.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the model name set.
+    pub fn model_name(mut self, model_name: impl Into<String>) -> Self {
+        self.model = model_name.into();
+        self
+    }
+}
+
+impl<M: ModelName> VertexAIBuilder<M> {
+    /// Sets the base URL of the Vertex AI endpoint, such as a Private Service
+    /// Connect endpoint. Defaults to the endpoint of the location.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.settings.base_url = base_url.into();
+        self
+    }
+
+    /// Sets the name of the provider. Defaults to "vertex-ai".
+    pub fn provider_name(mut self, provider_name: impl Into<String>) -> Self {
+        self.settings.provider_name = provider_name.into();
+        self
+    }
+
+    /// Sets the Google Cloud project ID. Defaults to `GOOGLE_VERTEX_PROJECT`,
+    /// `GOOGLE_CLOUD_PROJECT` or the project of the service account.
+    pub fn project(mut self, project: impl Into<String>) -> Self {
+        self.settings.project = project.into();
+        self
+    }
+
+    /// Sets the location, and the base URL to the endpoint of the location.
+    /// Defaults to `GOOGLE_VERTEX_LOCATION`, `GOOGLE_CLOUD_LOCATION` or "us-central1".
+    ///
+    /// Call `base_url` afterwards to use a different endpoint.
+    ///
+    /// # Parameters
+    ///
+    /// * `location` - The location (e.g., "europe-west4"), or "global".
+    ///
+    /// # Returns
+    ///
+    /// The builder with the location set.
+    pub fn location(mut self, location: impl Into<String>) -> Self {
+        self.settings.location = location.into();
+        self.settings.base_url = regional_base_url(&self.settings.location);
+        self
+    }
+
+    /// Sets the publisher of the model. Defaults to "google".
+    pub fn publisher(mut self, publisher: impl Into<String>) -> Self {
+        self.settings.publisher = publisher.into();
+        self
+    }
+
+    /// Sets the API key of express mode, which needs no project. Defaults to
+    /// `GOOGLE_VERTEX_API_KEY`.
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.settings.api_key = api_key.into();
+        self
+    }
+
+    /// Sets an OAuth2 access token, which takes precedence over the other credentials.
+    pub fn access_token(mut self, access_token: impl Into<String>) -> Self {
+        self.settings.access_token = Some(access_token.into());
+        self
+    }
+
+    /// Sets the service account key used to generate access tokens.
+    pub fn service_account_key(mut self, key: ServiceAccountKey) -> Self {
+        self.settings.service_account = Some(key);
+        self
+    }
+
+    /// Sets the path of a service account key file. Defaults to
+    /// `GOOGLE_APPLICATION_CREDENTIALS`.
+    pub fn credentials_file(mut self, path: impl Into<String>) -> Self {
+        self.settings.credentials_file = Some(path.into());
+        self
+    }
+
+    /// Sets the HTTP client sending the requests, to share its pool of connections
+    /// or configure it. Requests share one pooled client by default.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.settings.http_client = Some(client);
+        self
+    }
+
+    /// Sets the network settings of the HTTP client, such as a proxy or
+    /// certificate authorities. Ignored if an HTTP client is set.
+    pub fn http_settings(mut self, http: HttpSettings) -> Self {
+        self.settings.http = http;
+        self
+    }
+
+    /// Builds the Vertex AI provider.
+    ///
+    /// Loads the service account key file, if any, and validates the configuration.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the configured `VertexAI` provider or an `Error`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the base URL is invalid, the key file can not be read,
+    /// no credentials are configured, or the project is missing outside of express mode.
+    pub fn build(mut self) -> Result<VertexAI<M>> {
+        self.settings.base_url = validate_base_url(&self.settings.base_url)?;
+
+        if self.settings.access_token.is_none()
+            && self.settings.service_account.is_none()
+            && self.settings.api_key.is_empty()
+            && let Some(path) = &self.settings.credentials_file
+        {
+            self.settings.service_account = Some(ServiceAccountKey::from_file(path)?);
+        }
+
+        if self.settings.project.is_empty()
+            && let Some(project) = self
+                .settings
+                .service_account
+                .as_ref()
+                .and_then(|key| key.project_id.clone())
+        {
+            self.settings.project = project;
+        }
+
+        if self.settings.access_token.is_none()
+            && self.settings.service_account.is_none()
+            && self.settings.api_key.is_empty()
+        {
+            return Err(Error::MissingField("credentials".to_string()));
+        }
+        if self.settings.project.is_empty() && self.settings.api_key.is_empty() {
+            return Err(Error::MissingField("project".to_string()));
+        }
+
+        self.settings.http_client = self
+            .settings
+            .http
+            .resolve_client(self.settings.http_client.take())?;
+
+        Ok(VertexAI::from_settings(self.settings, self.model))
+    }
+}
+
+// Re-exports for convenience
+pub use capabilities::*;
@@ -0,0 +1,105 @@
+//! Defines the settings for the Vertex AI provider.
+
+use crate::core::client::HttpSettings;
+use crate::providers::vertex_ai::auth::ServiceAccountKey;
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// Settings for the Vertex AI provider.
+///
+/// Requests are authenticated with, in order of precedence, an access token, a
+/// token generated from a service account key, or an API key in express mode.
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+#[builder(setter(into), default)]
+pub struct VertexAIProviderSettings {
+    /// The name of the provider.
+    pub provider_name: String,
+
+    /// The base URL of the Vertex AI endpoint.
+    pub base_url: String,
+
+    /// The Google Cloud project ID. Not needed in express mode.
+    pub project: String,
+
+    /// The location of the endpoint (e.g., "us-central1" or "global").
+    pub location: String,
+
+    /// The publisher of the models, "google" for Gemini models.
+    pub publisher: String,
+
+    /// The API key of express mode.
+    pub api_key: String,
+
+    /// The HTTP client sending the requests. Requests share one pooled client
+    /// if it is not set.
+    #[serde(skip)]
+    pub http_client: Option<reqwest::Client>,
+
+    /// Network settings of the HTTP client built for the provider when no
+    /// client is set, such as a proxy or certificate authorities.
+    #[serde(default)]
+    pub http: HttpSettings,
+
+    /// An OAuth2 access token, such as the output of `gcloud auth print-access-token`.
+    pub access_token: Option<String>,
+
+    /// The service account key used to generate access tokens.
+    pub service_account: Option<ServiceAccountKey>,
+
+    /// The path of a service account key file, loaded on the first request when
+    /// `service_account` is not set.
+    pub credentials_file: Option<String>,
+}
+
+/// Returns the Vertex AI endpoint of a location.
+pub(crate) fn regional_base_url(location: &str) -> String {
+    match location {
+        "global" => "https://aiplatform.googleapis.com".to_string(),
+        location => format!("https://{location}-aiplatform.googleapis.com"),
+    }
+}
+
+impl Default for VertexAIProviderSettings {
+    /// Returns the default settings for the Vertex AI provider, reading the project,
+    /// location and credentials from the standard Google Cloud environment variables.
+    fn default() -> Self {
+        let location = std::env::var("GOOGLE_VERTEX_LOCATION")
+            .or_else(|_| std::env::var("GOOGLE_CLOUD_LOCATION"))
+            .unwrap_or_else(|_| "us-central1".to_string());
+
+        Self {
+            provider_name: "vertex-ai".to_string(),
+            base_url: regional_base_url(&location),
+            project: std::env::var("GOOGLE_VERTEX_PROJECT")
+                .or_else(|_| std::env::var("GOOGLE_CLOUD_PROJECT"))
+                .unwrap_or_default(),
+            location,
+            publisher: "google".to_string(),
+            api_key: std::env::var("GOOGLE_VERTEX_API_KEY").unwrap_or_default(),
+            access_token: None,
+            http_client: None,
+            http: HttpSettings::default(),
+            service_account: None,
+            credentials_file: std::env::var("GOOGLE_APPLICATION_CREDENTIALS").ok(),
+        }
+    }
+}
+
+impl VertexAIProviderSettings {
+    /// Creates a new builder for `VertexAIProviderSettings`.
+    pub fn builder() -> VertexAIProviderSettingsBuilder {
+        VertexAIProviderSettingsBuilder::default()
+    }
+
+    /// Returns the path of the models collection of the publisher.
+    pub(crate) fn models_path(&self) -> String {
+        if self.project.is_empty() {
+            // Express mode
+            return format!("/v1/publishers/{}/models", self.publisher);
+        }
+        format!(
+            "/v1/projects/{}/locations/{}/publishers/{}/models",
+            self.project, self.location, self.publisher
+        )
+    }
+}
@@ -21,3 +21,16 @@ generate_language_model_tests!(
     skip_streaming: false,
     skip_embedding: true
 );
+
+#[tokio::test]
+async fn test_count_tokens() {
+    skip_if_no_api_key!();
+
+    let mut model = Anthropic::claude_haiku_4_5();
+    let count = model
+        .count_tokens(&[Message::User("Hello, Claude".into())])
+        .await;
+
+    assert!(count.is_ok());
+    assert!(count.unwrap() > 0);
+}
@@ -770,6 +770,75 @@ macro_rules! generate_language_model_hook_tests {
             }
         }
 
+        #[tokio::test]
+        async fn test_on_finish_runs_once_after_all_steps() {
+            skip_if_no_api_key!();
+
+            let log = Arc::new(Mutex::new(Vec::new()));
+            let log_step = Arc::clone(&log);
+            let log_finish = Arc::clone(&log);
+
+            #[tool]
+            fn get_neighbourhood() -> Tool {
+                Ok("ankocha".to_string())
+            }
+
+            let _ = LanguageModelRequest::builder()
+                .model($tool_model)
+                .system("Call the tool. Return the neighborhood. Nothing more and nothing less")
+                .prompt("What is the neighborhood?")
+                .with_tool(get_neighbourhood())
+                .on_step_finish(move |_| {
+                    log_step.lock().unwrap().push("step");
+                })
+                .on_finish(move |_| {
+                    log_finish.lock().unwrap().push("finish");
+                })
+                .build()
+                .generate_text()
+                .await
+                .unwrap();
+
+            let log = log.lock().unwrap();
+            assert_eq!(log.iter().filter(|e| **e == "finish").count(), 1);
+            assert_eq!(log.last(), Some(&"finish"));
+        }
+
+        #[tokio::test]
+        async fn test_on_chunk_and_on_finish_with_stream() {
+            skip_if_no_api_key!();
+
+            let chunks = Arc::new(Mutex::new(Vec::new()));
+            let chunks_clone = Arc::clone(&chunks);
+            let finished = Arc::new(Mutex::new(false));
+            let finished_clone = Arc::clone(&finished);
+
+            let mut stream = LanguageModelRequest::builder()
+                .model($tool_model)
+                .prompt("Respond with exactly the word 'hello' in all lowercase.")
+                .on_chunk(move |chunk| {
+                    chunks_clone.lock().unwrap().push(chunk.clone());
+                })
+                .on_finish(move |_| {
+                    *finished_clone.lock().unwrap() = true;
+                })
+                .build()
+                .stream_text()
+                .await
+                .unwrap()
+                .stream;
+
+            let mut received = 0;
+            while stream.next().await.is_some() {
+                received += 1;
+            }
+
+            let chunks = chunks.lock().unwrap();
+            assert_eq!(chunks.len(), received);
+            assert!(matches!(chunks.first(), Some(LanguageModelStreamChunkType::Start)));
+            assert!(*finished.lock().unwrap());
+        }
+
         #[tokio::test]
         async fn test_stop_when_halts_during_tool_call() {
             skip_if_no_api_key!();
@@ -1126,6 +1195,34 @@ macro_rules! generate_language_model_step_id_tests {
             assert!(result.text().unwrap().contains("test_value"));
         }
 
+        #[tokio::test]
+        async fn test_step_id_continue_from() {
+            skip_if_no_api_key!();
+
+            let first = LanguageModelRequest::builder()
+                .model($default_model)
+                .system("Do the exact instructions you are told")
+                .prompt("Respond with exactly 'first' in lowercase.")
+                .build()
+                .generate_text()
+                .await
+                .unwrap();
+
+            let result = LanguageModelRequest::builder()
+                .model($default_model)
+                .continue_from(&first)
+                .prompt("Respond with exactly 'second' in lowercase.")
+                .build()
+                .generate_text()
+                .await
+                .unwrap();
+
+            // system (0), user (0), assistant (1), user (2), assistant (3)
+            let step_ids = result.step_ids();
+            assert_eq!(step_ids, vec![0, 0, 1, 2, 3]);
+            assert!(result.text().unwrap().contains("second"));
+        }
+
         #[tokio::test]
         async fn test_step_id_streaming() {
             skip_if_no_api_key!();